@@ -0,0 +1,188 @@
+use criterion::*;
+use pprof::criterion::{Output, PProfProfiler};
+
+use ark_bn254::{Fq, Fr, G1Projective};
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+use ark_ff::{BigInteger, PrimeField};
+use ark_grumpkin::Projective as G2Projective;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::{marker::PhantomData, UniformRand};
+
+use folding_schemes::{
+    arith::{r1cs::R1CS, Arith, ArithRelation},
+    commitment::{pedersen::Pedersen, CommitmentScheme},
+    constants::NOVA_N_BITS_RO,
+    folding::{
+        circuits::cyclefold::{
+            fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldConfig,
+            CycleFoldWitness, GenericCycleFoldConfig,
+        },
+        nova::get_r1cs_from_cs,
+    },
+    transcript::poseidon::poseidon_canonical_config,
+    Curve,
+};
+
+/// CycleFold config as in Nova (2 points), with a custom scalar multiplication window size
+struct WindowedCycleFoldConfig<C: Curve, const W: usize> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve, const W: usize> CycleFoldConfig for WindowedCycleFoldConfig<C, W> {
+    const RANDOMNESS_BIT_LENGTH: usize = NOVA_N_BITS_RO;
+    const N_INPUT_POINTS: usize = 2;
+    const SCALAR_MUL_WINDOW_SIZE: usize = W;
+    type C = C;
+}
+
+fn bench_window<const W: usize>(group: &mut BenchmarkGroup<measurement::WallTime>) {
+    let (n_constraints, n_witnesses) =
+        CycleFoldCircuit::<WindowedCycleFoldConfig<G1Projective, W>>::size(2).unwrap();
+    println!(
+        "CycleFold circuit, window size {}: {} constraints, {} witnesses",
+        W, n_constraints, n_witnesses
+    );
+
+    let mut rng = ark_std::test_rng();
+    let r_bits = Fr::rand(&mut rng).into_bigint().to_bits_le()[..NOVA_N_BITS_RO].to_vec();
+    let points = vec![G1Projective::rand(&mut rng), G1Projective::rand(&mut rng)];
+    group.bench_function(format!("window size {}", W), |b| {
+        b.iter(|| {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            CycleFoldCircuit::<WindowedCycleFoldConfig<G1Projective, W>> {
+                r_bits: Some(r_bits.clone()),
+                points: Some(points.clone()),
+            }
+            .generate_constraints(cs)
+        })
+    });
+}
+
+/// reports the size of Nova's CycleFold circuit over BN254 for different scalar multiplication
+/// window sizes, and benchmarks the generation of its witness
+fn bench_cyclefold_circuit(c: &mut Criterion) {
+    // before: double-and-add (window size 1), after: the default window size
+    let (before, _) =
+        CycleFoldCircuit::<WindowedCycleFoldConfig<G1Projective, 1>>::size(2).unwrap();
+    let (after, _) = CycleFoldCircuit::<GenericCycleFoldConfig<G1Projective, 2>>::size(2).unwrap();
+    println!(
+        "CycleFold circuit, default window size {}: {} constraints (double-and-add: {})",
+        <GenericCycleFoldConfig<G1Projective, 2> as CycleFoldConfig>::SCALAR_MUL_WINDOW_SIZE,
+        after,
+        before
+    );
+
+    let mut group = c.benchmark_group("CycleFold circuit - BN254 points");
+    group.significance_level(0.1).sample_size(10);
+    bench_window::<1>(&mut group);
+    bench_window::<2>(&mut group);
+    bench_window::<3>(&mut group);
+    bench_window::<4>(&mut group);
+    group.finish();
+}
+
+/// prepares the CycleFold R1CS, the Pedersen params and a dummy running instance for the given
+/// config
+#[allow(clippy::type_complexity)]
+fn cyclefold_setup<CFG: CycleFoldConfig<C = G1Projective>>() -> (
+    R1CS<Fq>,
+    <Pedersen<G2Projective> as CommitmentScheme<G2Projective>>::ProverParams,
+    CycleFoldWitness<G2Projective>,
+    CycleFoldCommittedInstance<G2Projective>,
+) {
+    let mut rng = ark_std::test_rng();
+    let cf_r1cs = get_r1cs_from_cs(CycleFoldCircuit::<CFG>::empty()).unwrap();
+    let (cf_cs_pp, _) = Pedersen::<G2Projective>::setup(
+        &mut rng,
+        cf_r1cs.n_constraints().max(cf_r1cs.n_witnesses()),
+    )
+    .unwrap();
+    let (cf_W, cf_U) = cf_r1cs.dummy_witness_instance();
+    (cf_r1cs, cf_cs_pp, cf_W, cf_U)
+}
+
+/// compares the time spent per Nova step on the CycleFold part (ie. the generation of the
+/// CycleFold witnesses, their commitments over the secondary curve, and their folding), when the
+/// folds of `cmW` and `cmE` are checked by two separate CycleFold instances (as before) and when
+/// they are checked by a single one (as Nova currently does). The CycleFold part does not depend
+/// on the step circuit, so these timings are the same for any `FCircuit`, e.g. the ChaCha20 one
+/// of the `chacha20_folding` example.
+fn bench_cyclefold_batching(c: &mut Criterion) {
+    type SingleCFG = GenericCycleFoldConfig<G1Projective, 2>;
+    type BatchedCFG = GenericCycleFoldConfig<G1Projective, 2, 2>;
+
+    let mut rng = ark_std::test_rng();
+    let poseidon_config = poseidon_canonical_config::<Fq>();
+    let pp_hash = Fq::rand(&mut rng);
+    let r_bits = Fr::rand(&mut rng).into_bigint().to_bits_le()[..NOVA_N_BITS_RO].to_vec();
+    let points: Vec<G1Projective> = std::iter::repeat_with(|| G1Projective::rand(&mut rng))
+        .take(4)
+        .collect();
+
+    let mut group = c.benchmark_group("CycleFold part of a Nova step - BN254-Grumpkin curves");
+    group.significance_level(0.1).sample_size(10);
+
+    let (cf_r1cs, cf_cs_pp, cf_W, cf_U) = cyclefold_setup::<SingleCFG>();
+    group.bench_function("2 CycleFold instances (cmW, cmE)", |b| {
+        b.iter(|| {
+            let mut transcript = PoseidonSponge::<Fq>::new(&poseidon_config);
+            let (mut W, mut U) = (cf_W.clone(), cf_U.clone());
+            for points in points.chunks(2) {
+                let circuit = CycleFoldCircuit::<SingleCFG> {
+                    r_bits: Some(r_bits.clone()),
+                    points: Some(points.to_vec()),
+                };
+                let (_, W1, U1, _) = fold_cyclefold_circuit::<
+                    SingleCFG,
+                    G2Projective,
+                    Pedersen<G2Projective>,
+                    false,
+                >(
+                    &mut transcript,
+                    &cf_r1cs,
+                    &cf_cs_pp,
+                    pp_hash,
+                    &W,
+                    &U,
+                    circuit,
+                    &mut rng.clone(),
+                )
+                .unwrap();
+                (W, U) = (W1, U1);
+            }
+            black_box(U)
+        })
+    });
+
+    let (cf_r1cs, cf_cs_pp, cf_W, cf_U) = cyclefold_setup::<BatchedCFG>();
+    group.bench_function("1 batched CycleFold instance (cmW & cmE)", |b| {
+        b.iter(|| {
+            let mut transcript = PoseidonSponge::<Fq>::new(&poseidon_config);
+            let circuit = CycleFoldCircuit::<BatchedCFG> {
+                r_bits: Some(r_bits.clone()),
+                points: Some(points.clone()),
+            };
+            let (_, _, U1, _) =
+                fold_cyclefold_circuit::<BatchedCFG, G2Projective, Pedersen<G2Projective>, false>(
+                    &mut transcript,
+                    &cf_r1cs,
+                    &cf_cs_pp,
+                    pp_hash,
+                    &cf_W,
+                    &cf_U,
+                    circuit,
+                    &mut rng.clone(),
+                )
+                .unwrap();
+            black_box(U1)
+        })
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_cyclefold_circuit, bench_cyclefold_batching
+}
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+use criterion::*;
+use pprof::criterion::{Output, PProfProfiler};
+
+use ark_bn254::Bn254;
+
+use folding_schemes::commitment::{
+    kzg::{KZG, SRS_CHECK_SECURITY_BITS},
+    CommitmentScheme,
+};
+
+fn bench_kzg_verify_srs(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    let params = KZG::<Bn254>::setup(&mut rng, 1 << 20).unwrap();
+
+    let mut group = c.benchmark_group("KZG verify_srs, 2^20 powers");
+    group.sample_size(10);
+    for security_bits in [SRS_CHECK_SECURITY_BITS, 64] {
+        group.bench_function(format!("{} bits coefficients", security_bits), |b| {
+            b.iter(|| KZG::<Bn254>::verify_srs(&params, security_bits, &mut rng).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_kzg_verify_srs
+}
+criterion_main!(benches);
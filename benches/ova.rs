@@ -0,0 +1,147 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, uint32::UInt32};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use criterion::*;
+use pprof::criterion::{Output, PProfProfiler};
+use std::marker::PhantomData;
+
+use ark_bn254::{Fr as bn_Fr, G1Projective as bn_G};
+use ark_grumpkin::Projective as grumpkin_G;
+
+use experimental_frontends::gadgets::chacha20::{enforce_fits_u32, ChaCha20Variant};
+use experimental_frontends::gadgets::u32ops::u32_to_fp;
+use folding_schemes::{
+    commitment::pedersen::Pedersen,
+    folding::nova::{ova::Ova, Nova, PreprocessorParam},
+    frontend::FCircuit,
+    transcript::poseidon::poseidon_canonical_config,
+    Error, FoldingScheme,
+};
+
+/// Computes `blocks_per_step` consecutive ChaCha20 (IETF) keystream blocks per step.
+/// State: [key (8 words), nonce (3 words), counter, last keystream block (16 words)]
+#[derive(Clone, Copy, Debug)]
+struct ChaCha20FCircuit<F: PrimeField> {
+    blocks_per_step: usize,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> FCircuit<F> for ChaCha20FCircuit<F> {
+    type Params = usize;
+    type ExternalInputs = ();
+    type ExternalInputsVar = ();
+    type WitnessHint = ();
+
+    fn new(blocks_per_step: Self::Params) -> Result<Self, Error> {
+        Ok(Self {
+            blocks_per_step,
+            _f: PhantomData,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        28
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        _external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let variant = ChaCha20Variant::Ietf;
+        let words = z_i[..12]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let key: [UInt32<F>; 8] = core::array::from_fn(|j| words[j].clone());
+        let nonce = &words[8..11];
+        let mut counter = vec![words[11].clone()];
+        let mut block = vec![];
+        for _ in 0..self.blocks_per_step {
+            let keystream = variant.block_gadget(cs.clone(), &key, nonce, &counter)?;
+            block = keystream
+                .iter()
+                .map(u32_to_fp)
+                .collect::<Result<Vec<_>, _>>()?;
+            counter = variant.next_counter_gadget(&counter)?;
+        }
+        Ok([z_i[..11].to_vec(), vec![u32_to_fp(&counter[0])?], block].concat())
+    }
+}
+
+fn bench_prove_step<FS: FoldingScheme<bn_G, grumpkin_G, ChaCha20FCircuit<bn_Fr>>>(
+    c: &mut Criterion,
+    name: &str,
+    blocks_per_step: usize,
+    prep_param: FS::PreprocessorParam,
+) -> Result<(), Error> {
+    let f_circuit = ChaCha20FCircuit::<bn_Fr>::new(blocks_per_step)?;
+    let mut rng = rand::rngs::OsRng;
+    let fs_params = FS::preprocess(&mut rng, &prep_param)?;
+    let mut fs = FS::init(&fs_params, f_circuit, vec![bn_Fr::from(0_u32); 28])?;
+
+    // warmup steps, the first step does not fold the CycleFold instances
+    for _ in 0..3 {
+        fs.prove_step(rng, (), None)?;
+    }
+
+    let mut group = c.benchmark_group(format!(
+        "{} - ChaCha20 FCircuit: {} blocks per step",
+        name, blocks_per_step
+    ));
+    group.significance_level(0.1).sample_size(10);
+    group.bench_function("prove_step", |b| {
+        b.iter(|| -> Result<_, _> { black_box(fs.clone()).prove_step(rng, (), None) })
+    });
+    let ivc_proof = fs.ivc_proof();
+    group.bench_function("verify", |b| {
+        b.iter(|| -> Result<_, _> {
+            FS::verify(black_box(fs_params.1.clone()), black_box(ivc_proof.clone()))
+        })
+    });
+    group.finish();
+    Ok(())
+}
+
+// compares the prover & verifier time of Nova and of its Ova variant on the ChaCha20 circuit
+fn bench_ova_vs_nova_ivc(c: &mut Criterion) {
+    let poseidon_config = poseidon_canonical_config::<bn_Fr>();
+
+    for blocks_per_step in [1_usize, 4, 16] {
+        let fcircuit = ChaCha20FCircuit::<bn_Fr>::new(blocks_per_step).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), fcircuit);
+
+        bench_prove_step::<
+            Nova<
+                bn_G,
+                grumpkin_G,
+                ChaCha20FCircuit<bn_Fr>,
+                Pedersen<bn_G>,
+                Pedersen<grumpkin_G>,
+                false,
+            >,
+        >(c, "Nova", blocks_per_step, prep_param.clone())
+        .unwrap();
+
+        bench_prove_step::<
+            Ova<
+                bn_G,
+                grumpkin_G,
+                ChaCha20FCircuit<bn_Fr>,
+                Pedersen<bn_G>,
+                Pedersen<grumpkin_G>,
+                false,
+            >,
+        >(c, "Ova", blocks_per_step, prep_param)
+        .unwrap();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_ova_vs_nova_ivc
+}
+criterion_main!(benches);
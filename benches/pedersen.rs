@@ -0,0 +1,79 @@
+use criterion::*;
+use pprof::criterion::{Output, PProfProfiler};
+
+use ark_bn254::{Fr, G1Projective};
+use ark_std::{rand::RngCore, UniformRand, Zero};
+
+use folding_schemes::commitment::{pedersen::Pedersen, CommitmentScheme};
+use folding_schemes::utils::vec::dense_vec_to_sparse;
+
+fn bench_pedersen_commit(c: &mut Criterion) {
+    let n: usize = 1 << 16;
+    let mut rng = ark_std::test_rng();
+    let (params, _) = Pedersen::<G1Projective>::setup(&mut rng, n).unwrap();
+    let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+        .take(n)
+        .collect();
+
+    let mut group = c.benchmark_group("Pedersen commit, 2^16 elements");
+    group.sample_size(10);
+    group.bench_function("generic MSM", |b| {
+        b.iter(|| black_box(Pedersen::<G1Projective>::commit(&params, &v, &Fr::zero()).unwrap()))
+    });
+    for window_bits in [4, 8, 12] {
+        let mut params_tables = params.clone();
+        params_tables.precompute(window_bits).unwrap();
+        // build the tables outside of the measured loop
+        Pedersen::<G1Projective>::commit(&params_tables, &v, &Fr::zero()).unwrap();
+        group.bench_function(
+            format!("fixed-base tables, window size {}", window_bits),
+            |b| {
+                b.iter(|| {
+                    black_box(
+                        Pedersen::<G1Projective>::commit(&params_tables, &v, &Fr::zero()).unwrap(),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_pedersen_commit_sparse(c: &mut Criterion) {
+    let n: usize = 1 << 16;
+    let mut rng = ark_std::test_rng();
+    let (params, _) = Pedersen::<G1Projective>::setup(&mut rng, n).unwrap();
+    // 90% of the elements are zero
+    let v: Vec<Fr> = (0..n)
+        .map(|_| {
+            if rng.next_u32() % 10 == 0 {
+                Fr::rand(&mut rng)
+            } else {
+                Fr::zero()
+            }
+        })
+        .collect();
+    let v_sparse = dense_vec_to_sparse(&v);
+
+    let mut group = c.benchmark_group("Pedersen commit, 2^16 elements, 90% sparse");
+    group.sample_size(10);
+    group.bench_function("dense", |b| {
+        b.iter(|| black_box(Pedersen::<G1Projective>::commit(&params, &v, &Fr::zero()).unwrap()))
+    });
+    group.bench_function("sparse", |b| {
+        b.iter(|| {
+            black_box(
+                Pedersen::<G1Projective>::commit_sparse(&params, &v_sparse, n, &Fr::zero())
+                    .unwrap(),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_pedersen_commit, bench_pedersen_commit_sparse
+}
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+use criterion::*;
+use pprof::criterion::{Output, PProfProfiler};
+use rayon::prelude::*;
+
+use ark_bn254::Fr;
+use ark_std::{rand::Rng, UniformRand};
+
+use folding_schemes::utils::vec::{mat_vec_mul, R1CSMatrix, SparseMatrix};
+
+/// Returns a square matrix with 3 non-zero coefficients per row, which is about the density of the
+/// matrices of the circuits folded in practice.
+fn rand_rows<R: Rng>(rng: &mut R, n: usize) -> R1CSMatrix<Fr> {
+    (0..n)
+        .map(|_| {
+            (0..3)
+                .map(|_| (Fr::rand(rng), rng.gen_range(0..n)))
+                .collect()
+        })
+        .collect()
+}
+
+fn bench_mat_vec_mul(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    for log_n in [18, 20] {
+        let n = 1 << log_n;
+        let rows = rand_rows(&mut rng, n);
+        let z: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let m = SparseMatrix::from_rows(n, rows.clone());
+
+        let mut group = c.benchmark_group(format!("mat_vec_mul, 2^{} constraints", log_n));
+        group.sample_size(10);
+        // the previous representation, where each row is a vector of its own
+        group.bench_function("rows, sequential", |b| {
+            b.iter(|| {
+                black_box(
+                    rows.iter()
+                        .map(|row| row.iter().map(|(v, i)| *v * z[*i]).sum())
+                        .collect::<Vec<Fr>>(),
+                )
+            })
+        });
+        group.bench_function("rows, parallel", |b| {
+            b.iter(|| {
+                black_box(
+                    rows.par_iter()
+                        .map(|row| row.iter().map(|(v, i)| *v * z[*i]).sum())
+                        .collect::<Vec<Fr>>(),
+                )
+            })
+        });
+        group.bench_function("CSR, parallel", |b| {
+            b.iter(|| black_box(mat_vec_mul(&m, &z).unwrap()))
+        });
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_mat_vec_mul
+}
+criterion_main!(benches);
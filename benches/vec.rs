@@ -0,0 +1,50 @@
+use criterion::*;
+use pprof::criterion::{Output, PProfProfiler};
+
+use ark_bn254::Fr;
+use ark_std::UniformRand;
+
+use folding_schemes::utils::vec::{add_scaled, axpy_in_place, hadamard};
+
+fn bench_batch_ops(c: &mut Criterion) {
+    let n: usize = 1 << 20;
+    let mut rng = ark_std::test_rng();
+    let a: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+    let b: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+    let r = Fr::rand(&mut rng);
+
+    let mut group = c.benchmark_group("Vector operations, 2^20 elements");
+    group.sample_size(10);
+    // the loops previously used by the folding of the witnesses
+    group.bench_function("a + r * b, naive", |bench| {
+        bench.iter(|| {
+            black_box(
+                a.iter()
+                    .zip(&b)
+                    .map(|(a, b)| *a + (r * b))
+                    .collect::<Vec<Fr>>(),
+            )
+        })
+    });
+    group.bench_function("a + r * b, add_scaled", |bench| {
+        bench.iter(|| black_box(add_scaled(&a, &r, &b).unwrap()))
+    });
+    group.bench_function("a += r * b, axpy_in_place", |bench| {
+        let mut y = a.clone();
+        bench.iter(|| axpy_in_place(black_box(&mut y), &r, &b).unwrap())
+    });
+    group.bench_function("a ∘ b, naive", |bench| {
+        bench.iter(|| black_box(a.iter().zip(&b).map(|(a, b)| *a * b).collect::<Vec<Fr>>()))
+    });
+    group.bench_function("a ∘ b, hadamard", |bench| {
+        bench.iter(|| black_box(hadamard(&a, &b).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_batch_ops
+}
+criterion_main!(benches);
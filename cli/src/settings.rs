@@ -1,7 +1,8 @@
 use ark_serialize::SerializationError;
 use clap::{Parser, ValueEnum};
 use solidity_verifiers::{
-    Groth16VerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    Groth16VerifierKey, HyperNovaVerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey,
+    ProtocolVerifierKey,
 };
 use std::{env, fmt::Display, path::PathBuf};
 
@@ -16,6 +17,7 @@ pub(crate) enum Protocol {
     Groth16,
     Kzg,
     NovaCycleFold,
+    HyperNova,
 }
 
 impl Display for Protocol {
@@ -42,6 +44,10 @@ impl Protocol {
                 data,
             )?
             .render_as_template(pragma)),
+            Self::HyperNova => Ok(
+                HyperNovaVerifierKey::deserialize_protocol_verifier_key(data)?
+                    .render_as_template(pragma),
+            ),
         }
     }
 }
@@ -0,0 +1,452 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds the AES-128-CTR encryption of a message, encrypting one or more 16-byte
+/// blocks per folding step as the ChaCha20FCircuit of the `chacha20_folding` example does with
+/// 64-byte blocks, and reports the constraints per block of both ciphers.
+use ark_crypto_primitives::{
+    crh::{
+        poseidon::{
+            constraints::{CRHGadget, CRHParametersVar},
+            CRH,
+        },
+        CRHScheme, CRHSchemeGadget,
+    },
+    sponge::Absorb,
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar, convert::ToBitsGadget, fields::fp::FpVar, uint32::UInt32, uint8::UInt8,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use core::marker::PhantomData;
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use experimental_frontends::gadgets::aes::{
+    aes128_ctr_native, aes128_encrypt_gadget, aes128_key_expansion_gadget,
+};
+use experimental_frontends::gadgets::chacha20::{chacha20_block_gadget, enforce_fits_u32};
+use experimental_frontends::gadgets::u32ops::{u32_to_fp, xor};
+use experimental_frontends::utils::VecFpVarDyn;
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+use folding_schemes::{Error, FoldingScheme};
+
+/// What the state of the AesCtrFCircuit carries besides the key and the counter block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AesCtrOutput {
+    /// The ciphertext of the blocks of the last step
+    #[default]
+    Ciphertext,
+    /// A Poseidon digest of the whole ciphertext stream, updated at each step as
+    /// `digest = Poseidon(digest, ciphertext words of the step)`
+    Digest,
+}
+
+/// Parameters of the AesCtrFCircuit
+#[derive(Clone, Copy, Debug)]
+pub struct AesCtrParams {
+    /// Number of consecutive 16-byte blocks encrypted by each folding step
+    pub blocks_per_step: usize,
+    pub output: AesCtrOutput,
+}
+
+impl Default for AesCtrParams {
+    fn default() -> Self {
+        Self {
+            blocks_per_step: 1,
+            output: AesCtrOutput::Ciphertext,
+        }
+    }
+}
+
+/// AES-128-CTR Folding Circuit
+/// State: [key (4 words), counter block (4 words), block_outputs (4 * blocks_per_step words)], or
+/// [key (4 words), counter block (4 words), digest] with AesCtrOutput::Digest
+/// External inputs: the 4 * blocks_per_step words of plaintext of the step
+///
+/// The words are the big-endian 32-bit words of the bytes of the key, of the counter block and of
+/// the blocks, as AES test vectors are written. The key is expanded in-circuit once per step, and
+/// the counter is the last word of the counter block, incremented modulo 2^32 after each block as
+/// the `inc32` function of GCM.
+#[derive(Clone, Copy, Debug)]
+pub struct AesCtrFCircuit<F: PrimeField> {
+    blocks_per_step: usize,
+    output: AesCtrOutput,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> FCircuit<F> for AesCtrFCircuit<F> {
+    type Params = AesCtrParams;
+    type ExternalInputs = Vec<F>; // plaintext blocks (4 * blocks_per_step words)
+    type ExternalInputsVar = VecFpVarDyn<F>;
+    type WitnessHint = ();
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if params.blocks_per_step == 0 {
+            return Err(Error::NotExpectedLength(0, 1));
+        }
+        Ok(Self {
+            blocks_per_step: params.blocks_per_step,
+            output: params.output,
+            _f: PhantomData,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        // key(4) + counter block(4) + block_outputs(4 * blocks_per_step) or digest(1)
+        let output_len = match self.output {
+            AesCtrOutput::Ciphertext => 4 * self.blocks_per_step,
+            AesCtrOutput::Digest => 1,
+        };
+        8 + output_len
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let plaintext = external_inputs.allocate(4 * self.blocks_per_step)?;
+        let words = z_i[0..8]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let key = words_to_bytes(&words[0..4])?;
+        let round_keys = aes128_key_expansion_gadget(&key)?;
+        let mut counter_block = words[4..8].to_vec();
+        let mut ciphertext = Vec::with_capacity(plaintext.len());
+
+        for block in plaintext.chunks(4) {
+            let keystream = aes128_encrypt_gadget(&round_keys, &words_to_bytes(&counter_block)?)?;
+            for (word, keystream_word) in block.iter().zip(bytes_to_words(&keystream)?) {
+                let ciphertext_word = xor(&enforce_fits_u32(word)?, &keystream_word)?;
+                ciphertext.push(u32_to_fp(&ciphertext_word)?);
+            }
+            counter_block[3] = counter_block[3].wrapping_add(&UInt32::constant(1));
+        }
+
+        let mut next_state = z_i[0..4].to_vec();
+        for word in &counter_block {
+            next_state.push(u32_to_fp(word)?);
+        }
+        match self.output {
+            AesCtrOutput::Ciphertext => next_state.extend(ciphertext),
+            AesCtrOutput::Digest => {
+                let crh_params = CRHParametersVar::<F>::new_constant(
+                    cs.clone(),
+                    poseidon_canonical_config::<F>(),
+                )?;
+                let hash_input = [vec![z_i[8].clone()], ciphertext].concat();
+                next_state.push(CRHGadget::<F>::evaluate(&crh_params, &hash_input)?);
+            }
+        }
+        Ok(next_state)
+    }
+}
+
+/// Bytes of the big-endian words, i.e. the most significant byte of each word first
+fn words_to_bytes<F: PrimeField>(words: &[UInt32<F>]) -> Result<[UInt8<F>; 16], SynthesisError> {
+    let mut bytes = Vec::with_capacity(16);
+    for word in words {
+        let bits = word.to_bits_le()?;
+        for byte in (0..4).rev() {
+            bytes.push(UInt8::from_bits_le(&bits[8 * byte..8 * (byte + 1)]));
+        }
+    }
+    Ok(core::array::from_fn(|j| bytes[j].clone()))
+}
+
+/// Big-endian words of the bytes, the inverse of `words_to_bytes`
+fn bytes_to_words<F: PrimeField>(bytes: &[UInt8<F>; 16]) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    bytes
+        .chunks(4)
+        .map(|word| {
+            let bits = word
+                .iter()
+                .rev()
+                .map(|byte| byte.to_bits_le())
+                .collect::<Result<Vec<_>, _>>()?
+                .concat();
+            Ok(UInt32::from_bits_le(&bits))
+        })
+        .collect()
+}
+
+/// Big-endian words of `bytes`, whose length must be a multiple of 4
+pub fn be_words(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks(4)
+        .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// Computes natively the digest of the final state of an AesCtrFCircuit with
+/// AesCtrOutput::Digest, which encrypts the plaintext starting from a zero digest
+pub fn aes_ctr_digest_native<F: PrimeField + Absorb>(
+    params: &AesCtrParams,
+    key: [u8; 16],
+    counter_block: [u8; 16],
+    plaintext: &[u8],
+) -> Result<F, Error> {
+    let bytes_per_step = 16 * params.blocks_per_step;
+    if plaintext.len() % bytes_per_step != 0 {
+        return Err(Error::NotExpectedLength(plaintext.len(), bytes_per_step));
+    }
+    let poseidon_config = poseidon_canonical_config::<F>();
+    let ciphertext = aes128_ctr_native(key, counter_block, plaintext);
+    let mut digest = F::zero();
+    for step_ciphertext in ciphertext.chunks(bytes_per_step) {
+        let hash_input = [
+            vec![digest],
+            be_words(step_ciphertext).into_iter().map(F::from).collect(),
+        ]
+        .concat();
+        digest =
+            CRH::<F>::evaluate(&poseidon_config, hash_input).map_err(|_| Error::EvaluationFail)?;
+    }
+    Ok(digest)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use folding_schemes::frontend::utils::simulate_steps;
+
+    // CTR-AES128.Encrypt test vector of SP 800-38A F.5.1
+    const KEY: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ];
+    const COUNTER_BLOCK: [u8; 16] = [
+        0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe,
+        0xff,
+    ];
+    const PLAINTEXT: [u32; 16] = [
+        0x6bc1bee2, 0x2e409f96, 0xe93d7e11, 0x7393172a, 0xae2d8a57, 0x1e03ac9c, 0x9eb76fac,
+        0x45af8e51, 0x30c81c46, 0xa35ce411, 0xe5fbc119, 0x1a0a52ef, 0xf69f2445, 0xdf4f9b17,
+        0xad2b417b, 0xe66c3710,
+    ];
+    const CIPHERTEXT: [u32; 16] = [
+        0x874d6191, 0xb620e326, 0x1bef6864, 0x990db6ce, 0x9806f66b, 0x7970fdff, 0x8617187b,
+        0xb9fffdff, 0x5ae4df3e, 0xdbd5d35e, 0x5b4f0902, 0x0db03eab, 0x1e031dda, 0x2fbe03d1,
+        0x792170a0, 0xf3009cee,
+    ];
+
+    fn z_0(output_len: usize) -> Vec<Fr> {
+        [
+            be_words(&KEY),
+            be_words(&COUNTER_BLOCK),
+            vec![0; output_len],
+        ]
+        .concat()
+        .into_iter()
+        .map(Fr::from)
+        .collect()
+    }
+
+    /// The NIST vector encrypted with 1, 2 or 4 blocks per step
+    #[test]
+    fn test_aes_ctr_f_circuit() -> Result<(), Error> {
+        for blocks_per_step in [1, 2, 4] {
+            let circuit = AesCtrFCircuit::<Fr>::new(AesCtrParams {
+                blocks_per_step,
+                ..Default::default()
+            })?;
+            let words_per_step = 4 * blocks_per_step;
+            let z_0 = z_0(words_per_step);
+            assert_eq!(circuit.state_len(), z_0.len());
+            let inputs: Vec<Vec<Fr>> = PLAINTEXT
+                .chunks(words_per_step)
+                .map(|words| words.iter().map(|&w| Fr::from(w)).collect())
+                .collect();
+
+            let states = simulate_steps(&circuit, z_0, inputs)?.into_states()?;
+            for (step, state) in states[1..].iter().enumerate() {
+                let counter = 0xfcfdfeffu32.wrapping_add(((step + 1) * blocks_per_step) as u32);
+                assert_eq!(state[7], Fr::from(counter));
+                let expected: Vec<Fr> = CIPHERTEXT
+                    [step * words_per_step..(step + 1) * words_per_step]
+                    .iter()
+                    .map(|&w| Fr::from(w))
+                    .collect();
+                assert_eq!(state[8..], expected);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fold the NIST vector with Nova, with the ciphertext or its digest in the state
+    #[test]
+    fn test_aes_ctr_folding() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            AesCtrFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let plaintext: Vec<u8> = PLAINTEXT.iter().flat_map(|w| w.to_be_bytes()).collect();
+        for output in [AesCtrOutput::Ciphertext, AesCtrOutput::Digest] {
+            let params = AesCtrParams {
+                blocks_per_step: 2,
+                output,
+            };
+            let circuit = AesCtrFCircuit::<Fr>::new(params)?;
+            let z_0 = z_0(circuit.state_len() - 8);
+
+            let mut rng = ark_std::test_rng();
+            let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+            let nova_params = N::preprocess(&mut rng, &prep_param)?;
+            let mut nova = N::init(&nova_params, circuit, z_0.clone())?;
+            for words in PLAINTEXT.chunks(8) {
+                nova.prove_step(&mut rng, words.iter().map(|&w| Fr::from(w)).collect(), None)?;
+            }
+            N::verify(nova_params.1, nova.ivc_proof())?;
+
+            let output: Vec<Fr> = match output {
+                AesCtrOutput::Ciphertext => CIPHERTEXT[8..].iter().map(|&w| Fr::from(w)).collect(),
+                AesCtrOutput::Digest => vec![aes_ctr_digest_native(
+                    &params,
+                    KEY,
+                    COUNTER_BLOCK,
+                    &plaintext,
+                )?],
+            };
+            let expected = [z_0[..7].to_vec(), vec![Fr::from(0xfcfdfeffu32 + 4)], output].concat();
+            assert_eq!(nova.state(), expected);
+        }
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    // Constraints per block of AES-128 and of ChaCha20, which encrypt 16 and 64 bytes
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let key = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(vec![0u8; 16]))?;
+    let block = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(vec![0u8; 16]))?;
+    let num_constraints = cs.num_constraints();
+    let round_keys = aes128_key_expansion_gadget(&core::array::from_fn(|j| key[j].clone()))?;
+    let key_expansion_constraints = cs.num_constraints() - num_constraints;
+    let num_constraints = cs.num_constraints();
+    aes128_encrypt_gadget(&round_keys, &core::array::from_fn(|j| block[j].clone()))?;
+    let aes_constraints = cs.num_constraints() - num_constraints;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let words = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(vec![0u32; 12]))?;
+    let num_constraints = cs.num_constraints();
+    chacha20_block_gadget(
+        cs.clone(),
+        &core::array::from_fn(|j| words[j].clone()),
+        &core::array::from_fn(|j| words[8 + j].clone()),
+        &words[11],
+    )?;
+    let chacha20_constraints = cs.num_constraints() - num_constraints;
+    println!(
+        "AES-128: {} constraints per 16-byte block ({:.1} per byte), plus {} for the key expansion",
+        aes_constraints,
+        aes_constraints as f64 / 16.0,
+        key_expansion_constraints
+    );
+    println!(
+        "ChaCha20: {} constraints per 64-byte block ({:.1} per byte)",
+        chacha20_constraints,
+        chacha20_constraints as f64 / 64.0
+    );
+
+    type N = Nova<
+        Projective,
+        Projective2,
+        AesCtrFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    // CTR-AES128.Encrypt test vector of SP 800-38A F.5.1, repeated to 16 blocks
+    let key = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ];
+    let counter_block = [
+        0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe,
+        0xff,
+    ];
+    let plaintext: Vec<u8> = [
+        0x6bc1bee2u32,
+        0x2e409f96,
+        0xe93d7e11,
+        0x7393172a,
+        0xae2d8a57,
+        0x1e03ac9c,
+        0x9eb76fac,
+        0x45af8e51,
+        0x30c81c46,
+        0xa35ce411,
+        0xe5fbc119,
+        0x1a0a52ef,
+        0xf69f2445,
+        0xdf4f9b17,
+        0xad2b417b,
+        0xe66c3710,
+    ]
+    .iter()
+    .flat_map(|w| w.to_be_bytes())
+    .collect::<Vec<_>>()
+    .repeat(4);
+
+    for blocks_per_step in [1, 4] {
+        let params = AesCtrParams {
+            blocks_per_step,
+            ..Default::default()
+        };
+        let F_circuit = AesCtrFCircuit::<Fr>::new(params)?;
+        let z_0: Vec<Fr> = [
+            be_words(&key),
+            be_words(&counter_block),
+            vec![0; 4 * blocks_per_step],
+        ]
+        .concat()
+        .into_iter()
+        .map(Fr::from)
+        .collect();
+
+        let mut rng = rand::rngs::OsRng;
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut folding_scheme = N::init(&nova_params, F_circuit, z_0)?;
+
+        let start = Instant::now();
+        for words in be_words(&plaintext).chunks(4 * blocks_per_step) {
+            folding_scheme.prove_step(rng, words.iter().map(|&w| Fr::from(w)).collect(), None)?;
+        }
+        let prove_time = start.elapsed();
+        N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+
+        let ciphertext = aes128_ctr_native(key, counter_block, &plaintext);
+        let last_words: Vec<Fr> = be_words(&ciphertext[ciphertext.len() - 16 * blocks_per_step..])
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+        assert_eq!(folding_scheme.state()[8..], last_words);
+        println!(
+            "{} blocks per step: {} bytes encrypted and folded in {:?} ({:?} per block)",
+            blocks_per_step,
+            plaintext.len(),
+            prove_time,
+            prove_time / (plaintext.len() / 16) as u32
+        );
+    }
+    Ok(())
+}
@@ -3,54 +3,153 @@
 #![allow(non_camel_case_types)]
 #![allow(clippy::upper_case_acronyms)]
 
+use ark_crypto_primitives::sponge::Absorb;
 use ark_ff::PrimeField;
-use ark_r1cs_std::{
-    alloc::AllocVar,
-    fields::fp::FpVar,
-    uint32::UInt32,
-    boolean::Boolean,
-    R1CSVar,
-    convert::ToBitsGadget,
-};
-use std::ops::BitXor;
-use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, uint32::UInt32};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
 use core::marker::PhantomData;
 use std::time::Instant;
 
 use ark_bn254::{Bn254, Fr, G1Projective as Projective};
 use ark_grumpkin::Projective as Projective2;
 
+use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use experimental_frontends::gadgets::chacha20::{
+    bytes_to_words_le, chacha20_block_gadget, enforce_fits_u32, quarter_round,
+    state_to_ciphertext_words, words_to_bytes_le, ChaCha20Variant,
+};
+use experimental_frontends::gadgets::u32ops::{u32_to_fp, xor};
+use experimental_frontends::utils::VecFpVarDyn;
+use folding_schemes::arith::Arith;
 use folding_schemes::commitment::{kzg::KZG, pedersen::Pedersen};
-use folding_schemes::folding::nova::{Nova, PreprocessorParam};
-use folding_schemes::frontend::FCircuit;
-use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+use folding_schemes::folding::nova::{get_r1cs, Nova, PreprocessorParam};
+use folding_schemes::frontend::{
+    utils::{simulate_steps, SimulatedSteps},
+    FCircuit,
+};
+use folding_schemes::transcript::poseidon::{
+    poseidon_canonical_config, poseidon_hash, poseidon_hash_gadget,
+};
 use folding_schemes::{Error, FoldingScheme};
 
+/// What the state of the ChaCha20FCircuit carries besides the key, nonce and counter
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChaCha20Output {
+    /// The ciphertext of the blocks of the last step
+    #[default]
+    Ciphertext,
+    /// A Poseidon digest of the whole ciphertext stream, updated at each step as
+    /// `digest = Poseidon(digest, ciphertext words of the step)`, which keeps the state small when
+    /// only a commitment to the ciphertext is needed
+    Digest,
+}
+
+/// What the ChaCha20FCircuit proves about each block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChaCha20Mode {
+    /// The encryption of the plaintext given as external inputs
+    #[default]
+    Encrypt,
+    /// The generation of the keystream only, whose XOR with the plaintext happens outside of the
+    /// circuit. The steps take no external inputs, and the state carries the keystream words (or
+    /// their digest) in place of the ciphertext
+    KeystreamOnly,
+}
+
+/// Parameters of the ChaCha20FCircuit
+#[derive(Clone, Copy, Debug)]
+pub struct ChaCha20Params {
+    pub variant: ChaCha20Variant,
+    /// Number of consecutive blocks encrypted by each folding step
+    pub blocks_per_step: usize,
+    pub output: ChaCha20Output,
+    pub mode: ChaCha20Mode,
+    /// Whether the state also carries a Poseidon commitment to the whole plaintext stream, see
+    /// [`plaintext_commitment`]
+    pub commit_plaintext: bool,
+}
+
+impl Default for ChaCha20Params {
+    fn default() -> Self {
+        Self {
+            variant: ChaCha20Variant::Ietf,
+            blocks_per_step: 1,
+            output: ChaCha20Output::Ciphertext,
+            mode: ChaCha20Mode::Encrypt,
+            commit_plaintext: false,
+        }
+    }
+}
+
 /// ChaCha20 Folding Circuit for stream cipher operations
-/// This circuit implements one ChaCha20 block operation per folding step
-/// State: [key (8 words), nonce (3 words), counter (1 word), block_output (16 words)]
-/// Total state size: 28 field elements
+/// This circuit implements `blocks_per_step` ChaCha20 block operations per folding step, which
+/// amortizes the overhead of the augmented circuit of the folding scheme over more blocks
+/// State: [key (8 words), nonce, counter, block_outputs (16 * blocks_per_step words)], or
+/// [key (8 words), nonce, counter, digest] with ChaCha20Output::Digest
+/// The lengths of the nonce and of the counter depend on the ChaCha20Variant: 3 and 1 words for
+/// Ietf, 2 and 2 words for Original, 6 and 1 words for XChaCha20, so with one block per step the
+/// total state size is 28, 28 or 31 field elements, or 13, 13 or 16 with the digest
+/// With commit_plaintext, the state ends with one more element, the running commitment to the
+/// plaintext blocks, so that the final state binds the plaintext that was encrypted
+/// External inputs: the 16 * blocks_per_step words of plaintext of the step, or none with
+/// ChaCha20Mode::KeystreamOnly, where the block outputs are the keystream words
+///
+/// Each word of the state is a field element holding a u32. The key, nonce and counter words are
+/// decomposed to UInt32 once per step, the 20 rounds of the block function operate on UInt32 only,
+/// and only the 16 ciphertext words are recomposed to field elements, which costs no constraints.
 #[derive(Clone, Copy, Debug)]
 pub struct ChaCha20FCircuit<F: PrimeField> {
+    variant: ChaCha20Variant,
+    blocks_per_step: usize,
+    output: ChaCha20Output,
+    mode: ChaCha20Mode,
+    commit_plaintext: bool,
     _f: PhantomData<F>,
 }
 
-impl<F: PrimeField> FCircuit<F> for ChaCha20FCircuit<F> {
-    type Params = ();
-    type ExternalInputs = [F; 16]; // plaintext block (16 words)
-    type ExternalInputsVar = [FpVar<F>; 16];
+impl<F: PrimeField + Absorb> FCircuit<F> for ChaCha20FCircuit<F> {
+    type Params = ChaCha20Params;
+    type ExternalInputs = Vec<F>; // plaintext blocks (16 * blocks_per_step words), or empty
+    type ExternalInputsVar = VecFpVarDyn<F>;
+    type WitnessHint = ();
 
-    fn new(_params: Self::Params) -> Result<Self, Error> {
-        Ok(Self { _f: PhantomData })
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if params.blocks_per_step == 0 {
+            return Err(Error::NotExpectedLength(0, 1));
+        }
+        if params.mode == ChaCha20Mode::KeystreamOnly && params.commit_plaintext {
+            return Err(Error::NotSupported(
+                "plaintext commitment without plaintext in ChaCha20Mode::KeystreamOnly".to_string(),
+            ));
+        }
+        Ok(Self {
+            variant: params.variant,
+            blocks_per_step: params.blocks_per_step,
+            output: params.output,
+            mode: params.mode,
+            commit_plaintext: params.commit_plaintext,
+            _f: PhantomData,
+        })
     }
 
     fn state_len(&self) -> usize {
-        28 // key(8) + nonce(3) + counter(1) + block_output(16)
+        // key(8) + nonce + counter + block_outputs(16 * blocks_per_step) or digest(1)
+        // + plaintext_commitment(1)
+        let output_len = match self.output {
+            ChaCha20Output::Ciphertext => 16 * self.blocks_per_step,
+            ChaCha20Output::Digest => 1,
+        };
+        8 + self.variant.nonce_len()
+            + self.variant.counter_len()
+            + output_len
+            + self.commit_plaintext as usize
     }
 
-    /// Generates constraints for one ChaCha20 block operation
-    /// Input state: [key, nonce, counter, previous_block_output]
-    /// Output state: [key, nonce, counter+1, current_block_output]
+    /// Generates constraints for blocks_per_step ChaCha20 block operations
+    /// Input state: [key, nonce, counter, previous_block_outputs]
+    /// Output state: [key, nonce, counter+blocks_per_step (wrapping), current_block_outputs], or
+    /// the digest of the previous state updated with the current block outputs, followed by the
+    /// plaintext commitment updated with the current plaintext blocks with commit_plaintext
     fn generate_step_constraints(
         &self,
         cs: ConstraintSystemRef<F>,
@@ -58,526 +157,207 @@ impl<F: PrimeField> FCircuit<F> for ChaCha20FCircuit<F> {
         z_i: Vec<FpVar<F>>,
         external_inputs: Self::ExternalInputsVar,
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
-        let mut next_state = z_i.clone();
-        
-        // Extract counter from state and increment it
-        let counter_val = z_i[11].value().unwrap_or(F::zero());
-        let next_counter_val = counter_val + F::one();
-        next_state[11] = FpVar::new_witness(cs.clone(), || Ok(next_counter_val))?;
-        
-        // Implement ChaCha20 block operation constraints
-        let keystream = self.chacha20_block_gadget(cs.clone(), &z_i[0..12], &counter_val)?;
-        
-        // XOR plaintext with keystream (proper XOR operation)
-         for i in 0..16 {
-             let plaintext_u32 = self.fpvar_to_uint32(cs.clone(), &external_inputs[i])?;
-             let keystream_u32 = self.fpvar_to_uint32(cs.clone(), &keystream[i])?;
-             let ciphertext_u32 = self.xor_uint32(cs.clone(), &plaintext_u32, &keystream_u32)?;
-             next_state[12 + i] = self.uint32_to_fpvar(cs.clone(), &ciphertext_u32)?;
-         }
-        
-        Ok(next_state)
-    }
-}
-
-impl<F: PrimeField> ChaCha20FCircuit<F> {
-    /// ChaCha20 block operation as R1CS constraints
-    fn chacha20_block_gadget(
-        &self,
-        cs: ConstraintSystemRef<F>,
-        state_prefix: &[FpVar<F>], // key + nonce + counter (12 elements)
-        _counter: &F,
-    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
-        // Initialize ChaCha20 state with constants, key, nonce, counter
-        let mut state = Vec::new();
-        
-        // ChaCha20 constants: "expand 32-byte k"
-        state.push(FpVar::new_constant(cs.clone(), F::from(0x61707865u32))?);
-        state.push(FpVar::new_constant(cs.clone(), F::from(0x3320646eu32))?);
-        state.push(FpVar::new_constant(cs.clone(), F::from(0x79622d32u32))?);
-        state.push(FpVar::new_constant(cs.clone(), F::from(0x6b206574u32))?);
-        
-        // Add key (8 words): state_prefix[0..8]
-        for i in 0..8 {
-            state.push(state_prefix[i].clone());
-        }
-        
-        // Add counter (1 word): state_prefix[11]
-        state.push(state_prefix[11].clone());
-        
-        // Add nonce (3 words): state_prefix[8..11]
-        for i in 8..11 {
-            state.push(state_prefix[i].clone());
+        let (nonce_len, counter_len) = (self.variant.nonce_len(), self.variant.counter_len());
+        let output_offset = 8 + nonce_len + counter_len;
+        let plaintext = match self.mode {
+            ChaCha20Mode::Encrypt => external_inputs.allocate(16 * self.blocks_per_step)?,
+            ChaCha20Mode::KeystreamOnly => external_inputs.allocate(0)?,
+        };
+        let mut next_state = z_i[0..output_offset].to_vec();
+        
+        let words = z_i[0..output_offset]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let key: [UInt32<F>; 8] = core::array::from_fn(|j| words[j].clone());
+        let (nonce, counter) = words[8..].split_at(nonce_len);
+        let mut counter = counter.to_vec();
+        let mut block_outputs = Vec::with_capacity(16 * self.blocks_per_step);
+        
+        for block in 0..self.blocks_per_step {
+            // Implement ChaCha20 block operation constraints
+            let keystream = self.variant.block_gadget(cs.clone(), &key, nonce, &counter)?;
+            
+            match self.mode {
+                ChaCha20Mode::Encrypt => {
+                    // XOR plaintext with keystream (proper XOR operation)
+                    let words = &plaintext[16 * block..16 * (block + 1)];
+                    for (word, keystream_u32) in words.iter().zip(&keystream) {
+                        let plaintext_u32 = enforce_fits_u32(word)?;
+                        let ciphertext_u32 = xor(&plaintext_u32, keystream_u32)?;
+                        block_outputs.push(u32_to_fp(&ciphertext_u32)?);
+                    }
+                }
+                ChaCha20Mode::KeystreamOnly => {
+                    for keystream_u32 in &keystream {
+                        block_outputs.push(u32_to_fp(keystream_u32)?);
+                    }
+                }
+            }
+            
+            // Increment the counter, which wraps around as the block counter of ChaCha20
+            counter = self.variant.next_counter_gadget(&counter)?;
         }
-        
-        // Perform 10 rounds of ChaCha20
-        let mut working_state = state.clone();
-        for _round in 0..10 {
-            working_state = self.chacha20_round(cs.clone(), working_state)?;
+        for (j, word) in counter.iter().enumerate() {
+            next_state[8 + nonce_len + j] = u32_to_fp(word)?;
         }
         
-        // Add original state to working state (ChaCha20 final step)
-        let mut keystream = Vec::new();
-        for i in 0..16 {
-            keystream.push(&state[i] + &working_state[i]);
+        match self.output {
+            ChaCha20Output::Ciphertext => next_state.extend(block_outputs),
+            ChaCha20Output::Digest => {
+                let hash_input = [vec![z_i[output_offset].clone()], block_outputs].concat();
+                next_state.push(poseidon_hash_gadget(
+                    cs.clone(),
+                    &poseidon_canonical_config::<F>(),
+                    &hash_input,
+                )?);
+            }
         }
         
-        Ok(keystream)
-    }
-    
-    /// Single ChaCha20 round (column + diagonal quarter rounds)
-    fn chacha20_round(
-        &self,
-        cs: ConstraintSystemRef<F>,
-        mut state: Vec<FpVar<F>>,
-    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
-        // Column rounds
-        let (a0, a4, a8, a12) = self.quarter_round(cs.clone(), &state[0], &state[4], &state[8], &state[12])?;
-        let (a1, a5, a9, a13) = self.quarter_round(cs.clone(), &state[1], &state[5], &state[9], &state[13])?;
-        let (a2, a6, a10, a14) = self.quarter_round(cs.clone(), &state[2], &state[6], &state[10], &state[14])?;
-        let (a3, a7, a11, a15) = self.quarter_round(cs.clone(), &state[3], &state[7], &state[11], &state[15])?;
-        
-        // Update state after column rounds
-        state[0] = a0; state[4] = a4; state[8] = a8; state[12] = a12;
-        state[1] = a1; state[5] = a5; state[9] = a9; state[13] = a13;
-        state[2] = a2; state[6] = a6; state[10] = a10; state[14] = a14;
-        state[3] = a3; state[7] = a7; state[11] = a11; state[15] = a15;
-        
-        // Diagonal rounds
-        let (b0, b5, b10, b15) = self.quarter_round(cs.clone(), &state[0], &state[5], &state[10], &state[15])?;
-        let (b1, b6, b11, b12) = self.quarter_round(cs.clone(), &state[1], &state[6], &state[11], &state[12])?;
-        let (b2, b7, b8, b13) = self.quarter_round(cs.clone(), &state[2], &state[7], &state[8], &state[13])?;
-        let (b3, b4, b9, b14) = self.quarter_round(cs.clone(), &state[3], &state[4], &state[9], &state[14])?;
-        
-        // Update state after diagonal rounds
-        state[0] = b0; state[5] = b5; state[10] = b10; state[15] = b15;
-        state[1] = b1; state[6] = b6; state[11] = b11; state[12] = b12;
-        state[2] = b2; state[7] = b7; state[8] = b8; state[13] = b13;
-        state[3] = b3; state[4] = b4; state[9] = b9; state[14] = b14;
-        
-        Ok(state)
-    }
-    
-    /// ChaCha20 quarter round as R1CS constraints (equivalent to noir implementation)
-    fn quarter_round(
-        &self,
-        cs: ConstraintSystemRef<F>,
-        a: &FpVar<F>,
-        b: &FpVar<F>,
-        c: &FpVar<F>,
-        d: &FpVar<F>,
-    ) -> Result<(FpVar<F>, FpVar<F>, FpVar<F>, FpVar<F>), SynthesisError> {
-        // Convert FpVar to UInt32 for proper 32-bit operations
-        let a_u32 = self.fpvar_to_uint32(cs.clone(), a)?;
-        let b_u32 = self.fpvar_to_uint32(cs.clone(), b)?;
-        let c_u32 = self.fpvar_to_uint32(cs.clone(), c)?;
-        let d_u32 = self.fpvar_to_uint32(cs.clone(), d)?;
-        
-        // 1. a += b; d ^= a; d <<<= 16;
-         let a1 = self.add_uint32(cs.clone(), &a_u32, &b_u32)?;
-         let d1 = self.xor_uint32(cs.clone(), &d_u32, &a1)?;
-         let d2 = self.rotate_left_32(cs.clone(), &d1, 16)?;
-         
-         // 2. c += d; b ^= c; b <<<= 12;
-         let c1 = self.add_uint32(cs.clone(), &c_u32, &d2)?;
-         let b1 = self.xor_uint32(cs.clone(), &b_u32, &c1)?;
-         let b2 = self.rotate_left_32(cs.clone(), &b1, 12)?;
-         
-         // 3. a += b; d ^= a; d <<<= 8;
-         let a2 = self.add_uint32(cs.clone(), &a1, &b2)?;
-         let d3 = self.xor_uint32(cs.clone(), &d2, &a2)?;
-         let d4 = self.rotate_left_32(cs.clone(), &d3, 8)?;
-         
-         // 4. c += d; b ^= c; b <<<= 7;
-         let c2 = self.add_uint32(cs.clone(), &c1, &d4)?;
-         let b3 = self.xor_uint32(cs.clone(), &b2, &c2)?;
-         let b4 = self.rotate_left_32(cs.clone(), &b3, 7)?;
-        
-        // Convert back to FpVar
-        let a_result = self.uint32_to_fpvar(cs.clone(), &a2)?;
-        let b_result = self.uint32_to_fpvar(cs.clone(), &b4)?;
-        let c_result = self.uint32_to_fpvar(cs.clone(), &c2)?;
-        let d_result = self.uint32_to_fpvar(cs.clone(), &d4)?;
-        
-        Ok((a_result, b_result, c_result, d_result))
-    }
-    
-    /// Convert FpVar to UInt32
-    fn fpvar_to_uint32(
-        &self,
-        _cs: ConstraintSystemRef<F>,
-        fp: &FpVar<F>,
-    ) -> Result<UInt32<F>, SynthesisError> {
-        let bits = fp.to_bits_le()?;
-        // Take only the first 32 bits
-        let mut u32_bits = Vec::new();
-        for i in 0..32 {
-            if i < bits.len() {
-                u32_bits.push(bits[i].clone());
-            } else {
-                u32_bits.push(Boolean::constant(false));
+        if self.commit_plaintext {
+            // absorb the plaintext one block at a time, so that the commitment does not depend on
+            // blocks_per_step. The plaintext words were range checked by the XOR above
+            let poseidon_config = poseidon_canonical_config::<F>();
+            let mut commitment = z_i[self.state_len() - 1].clone();
+            for block in plaintext.chunks(16) {
+                let hash_input = [vec![commitment], block.to_vec()].concat();
+                commitment = poseidon_hash_gadget(cs.clone(), &poseidon_config, &hash_input)?;
             }
+            next_state.push(commitment);
         }
-        Ok(UInt32::from_bits_le(&u32_bits))
-    }
-    
-    /// Convert UInt32 to FpVar
-    fn uint32_to_fpvar(
-        &self,
-        _cs: ConstraintSystemRef<F>,
-        u32_val: &UInt32<F>,
-    ) -> Result<FpVar<F>, SynthesisError> {
-        let bits = u32_val.to_bits_le()?;
-        let mut result = FpVar::new_constant(_cs.clone(), F::zero())?;
-         let mut power = F::one();
-         for bit in bits {
-             let bit_val = FpVar::new_witness(_cs.clone(), || {
-                 if bit.value()? { Ok(power) } else { Ok(F::zero()) }
-             })?;
-             result = &result + &bit_val;
-             power = power + power; // power *= 2
-         }
-         Ok(result)
-    }
-    
-    /// Add two UInt32 values
-    fn add_uint32(
-        &self,
-        _cs: ConstraintSystemRef<F>,
-        a: &UInt32<F>,
-        b: &UInt32<F>,
-    ) -> Result<UInt32<F>, SynthesisError> {
-        let a_bits = a.to_bits_le()?;
-        let b_bits = b.to_bits_le()?;
-        let mut result_bits = Vec::new();
-        let mut carry = Boolean::constant(false);
-        
-        for i in 0..32 {
-               // 真正的XOR操作：a XOR b
-                 let sum = a_bits[i].clone().bitxor(&b_bits[i]);
-                 // 32位模运算加法的进位计算：(a AND b) OR ((a XOR b) AND carry)
-                 let ab_and = Boolean::kary_and(&[a_bits[i].clone(), b_bits[i].clone()])?;
-                 let sum_carry_and = Boolean::kary_and(&[sum.clone(), carry.clone()])?;
-                 let new_carry = Boolean::kary_or(&[ab_and, sum_carry_and])?;
-                 // 最终结果：(a XOR b) XOR carry
-                 result_bits.push(sum.bitxor(&carry));
-                 carry = new_carry;
-           }
         
-        Ok(UInt32::from_bits_le(&result_bits))
+        Ok(next_state)
     }
-    
-    /// XOR two UInt32 values
-    fn xor_uint32(
-        &self,
-        _cs: ConstraintSystemRef<F>,
-        a: &UInt32<F>,
-        b: &UInt32<F>,
-    ) -> Result<UInt32<F>, SynthesisError> {
-        let a_bits = a.to_bits_le()?;
-        let b_bits = b.to_bits_le()?;
-        let mut result_bits = Vec::new();
-        
-        for i in 0..32 {
-               // 真正的XOR操作：a XOR b
-                 result_bits.push(a_bits[i].clone().bitxor(&b_bits[i]));
-           }
-        
-        Ok(UInt32::from_bits_le(&result_bits))
+}
+
+/// Computes natively the digest of the final state of a ChaCha20FCircuit with
+/// ChaCha20Output::Digest, which encrypts the plaintext from the given key, nonce and initial
+/// counter, starting from a zero digest. With ChaCha20Mode::KeystreamOnly, the digest is the one of
+/// an all-zero plaintext, whose ciphertext is the keystream
+pub fn chacha20_digest_native<F: PrimeField + Absorb>(
+    params: &ChaCha20Params,
+    key: [u32; 8],
+    nonce: &[u32],
+    counter: &[u32],
+    plaintext: &[u32],
+) -> Result<F, Error> {
+    let words_per_step = 16 * params.blocks_per_step;
+    if plaintext.len() % words_per_step != 0 {
+        return Err(Error::NotExpectedLength(plaintext.len(), words_per_step));
     }
+    let poseidon_config = poseidon_canonical_config::<F>();
+    // the counter as a little-endian number of counter_len words
+    let counter_bits = 32 * params.variant.counter_len() as u32;
+    let mut counter = counter.iter().rev().fold(0u64, |acc, &w| (acc << 32) | w as u64);
     
-    /// 32-bit left rotation
-    fn rotate_left_32(
-        &self,
-        _cs: ConstraintSystemRef<F>,
-        x: &UInt32<F>,
-        n: u8,
-    ) -> Result<UInt32<F>, SynthesisError> {
-        let bits = x.to_bits_le()?;
-        let mut rotated_bits = Vec::new();
-        
-        // Rotate left by n positions
-        for i in 0..32 {
-            let src_idx = (i + 32 - (n as usize)) % 32;
-            rotated_bits.push(bits[src_idx].clone());
+    let mut digest = F::zero();
+    for step_plaintext in plaintext.chunks(words_per_step) {
+        let mut hash_input = vec![digest];
+        for block in step_plaintext.chunks(16) {
+            let counter_words: Vec<u32> = (0..params.variant.counter_len())
+                .map(|j| (counter >> (32 * j)) as u32)
+                .collect();
+            let keystream = params.variant.block_native(key, nonce, &counter_words);
+            hash_input.extend((0..16).map(|j| F::from(block[j] ^ keystream[j])));
+            counter = counter.wrapping_add(1) & (u64::MAX >> (64 - counter_bits));
         }
-        
-        Ok(UInt32::from_bits_le(&rotated_bits))
+        digest = poseidon_hash(&poseidon_config, &hash_input)?;
     }
+    Ok(digest)
 }
 
-// Note: This is a simplified ChaCha20 implementation for demonstration
-// A production version would implement proper 32-bit arithmetic and rotations
-
-/// Native ChaCha20 step function for testing (simplified)
-fn chacha20_step_native<F: PrimeField>(z_i: Vec<F>, external_inputs: [F; 16]) -> Vec<F> {
-    // Extract key, nonce, and counter from state
-    let mut key = [0u32; 8];
-    let mut nonce = [0u32; 3];
-    
-    for i in 0..8 {
-        let bigint = z_i[i].into_bigint();
-        key[i] = bigint.as_ref()[0] as u32;
-    }
-    
-    for i in 0..3 {
-        let bigint = z_i[8 + i].into_bigint();
-        nonce[i] = bigint.as_ref()[0] as u32;
-    }
-    
-    let counter_bigint = z_i[11].into_bigint();
-    let counter = counter_bigint.as_ref()[0] as u32;
-    
-    // Convert external inputs to u32
-    let mut plaintext = [0u32; 16];
-    for i in 0..16 {
-        let bigint = external_inputs[i].into_bigint();
-        plaintext[i] = bigint.as_ref()[0] as u32;
-    }
-    
-    // Generate ChaCha20 keystream block
-    let keystream = chacha20_block_native(key, nonce, counter);
-    
-    // XOR plaintext with keystream to get ciphertext
-    let mut ciphertext = [0u32; 16];
-    for i in 0..16 {
-        ciphertext[i] = plaintext[i] ^ keystream[i];
-    }
-    
-    // Update state
-    let mut next_state = z_i.clone();
-    next_state[11] = F::from(counter + 1); // Increment counter
-    
-    // Store ciphertext in state
-    for i in 0..16 {
-        next_state[12 + i] = F::from(ciphertext[i]);
-    }
-    
-    next_state
+/// Computes natively the plaintext commitment of the final state of a ChaCha20FCircuit with
+/// commit_plaintext, which absorbs the plaintext blocks one at a time starting from zero, as
+/// `commitment = Poseidon(commitment, words of the block)`
+pub fn plaintext_commitment<F: PrimeField + Absorb>(blocks: &[[u32; 16]]) -> Result<F, Error> {
+    let poseidon_config = poseidon_canonical_config::<F>();
+    blocks.iter().try_fold(F::zero(), |commitment, block| {
+        let hash_input = [vec![commitment], block.iter().map(|&w| F::from(w)).collect()].concat();
+        poseidon_hash(&poseidon_config, &hash_input)
+    })
 }
 
-/// Native ChaCha20 block function
-fn chacha20_block_native(key: [u32; 8], nonce: [u32; 3], counter: u32) -> [u32; 16] {
-    // ChaCha20 constants
-    let constants = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
-    
-    // Initialize state
-    let mut state = [0u32; 16];
-    state[0..4].copy_from_slice(&constants);
-    state[4..12].copy_from_slice(&key);
-    state[12] = counter;
-    state[13..16].copy_from_slice(&nonce);
-    
-    let original_state = state;
-    
-    // Perform 10 rounds (20 quarter-rounds)
-    for _ in 0..10 {
-        // Column rounds
-        quarter_round_native(&mut state, 0, 4, 8, 12);
-        quarter_round_native(&mut state, 1, 5, 9, 13);
-        quarter_round_native(&mut state, 2, 6, 10, 14);
-        quarter_round_native(&mut state, 3, 7, 11, 15);
-        
-        // Diagonal rounds
-        quarter_round_native(&mut state, 0, 5, 10, 15);
-        quarter_round_native(&mut state, 1, 6, 11, 12);
-        quarter_round_native(&mut state, 2, 7, 8, 13);
-        quarter_round_native(&mut state, 3, 4, 9, 14);
-    }
-    
-    // Add original state
-    for i in 0..16 {
-        state[i] = state[i].wrapping_add(original_state[i]);
+/// Numbers of constraints of one quarter round, of one block and of one step of the
+/// ChaCha20FCircuit for each variant, synthesized on fresh ConstraintSystems with witness words,
+/// followed by the full cost of a Nova step: the augmented circuit wrapping each of these steps,
+/// and the CycleFold circuit folded along with it
+pub fn constraint_counts() -> Result<Vec<(String, usize)>, Error> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let words = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(vec![0u32; 16]))?;
+    let mut state: [UInt32<Fr>; 16] = core::array::from_fn(|j| words[j].clone());
+    let n_constraints = cs.num_constraints();
+    quarter_round(&mut state, 0, 4, 8, 12)?;
+    let mut counts = vec![("quarter round".to_string(), cs.num_constraints() - n_constraints)];
+
+    let key: [UInt32<Fr>; 8] = core::array::from_fn(|j| words[j].clone());
+    let nonce: [UInt32<Fr>; 3] = core::array::from_fn(|j| words[8 + j].clone());
+    let n_constraints = cs.num_constraints();
+    chacha20_block_gadget(cs.clone(), &key, &nonce, &words[11])?;
+    counts.push(("block".to_string(), cs.num_constraints() - n_constraints));
+
+    let (mut augmented_counts, mut cyclefold_constraints) = (vec![], 0);
+    for (variant, blocks_per_step) in [
+        (ChaCha20Variant::Ietf, 1),
+        (ChaCha20Variant::Original, 1),
+        (ChaCha20Variant::XChaCha20, 1),
+        (ChaCha20Variant::Ietf, 4),
+    ] {
+        let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+            variant,
+            blocks_per_step,
+            ..Default::default()
+        })?;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || {
+            Ok(vec![Fr::from(0u32); circuit.state_len()])
+        })?;
+        let plaintext = VecFpVarDyn::new_witness(cs.clone(), || {
+            Ok(vec![Fr::from(0u32); 16 * blocks_per_step])
+        })?;
+        circuit.generate_step_constraints(cs.clone(), 0, z_i, plaintext)?;
+        counts.push((
+            format!("step ({:?}, {} blocks)", variant, blocks_per_step),
+            cs.num_constraints(),
+        ));
+
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<Projective, Projective2, _>(&poseidon_canonical_config(), circuit)?;
+        augmented_counts.push((
+            format!("Nova step ({:?}, {} blocks)", variant, blocks_per_step),
+            r1cs.n_constraints(),
+        ));
+        cyclefold_constraints = cf_r1cs.n_constraints();
     }
-    
-    state
+    counts.extend(augmented_counts);
+    counts.push(("CycleFold circuit".to_string(), cyclefold_constraints));
+    Ok(counts)
 }
 
-/// Native quarter round function
-fn quarter_round_native(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
-    state[a] = state[a].wrapping_add(state[b]);
-    state[d] ^= state[a];
-    state[d] = state[d].rotate_left(16);
-    
-    state[c] = state[c].wrapping_add(state[d]);
-    state[b] ^= state[c];
-    state[b] = state[b].rotate_left(12);
-    
-    state[a] = state[a].wrapping_add(state[b]);
-    state[d] ^= state[a];
-    state[d] = state[d].rotate_left(8);
-    
-    state[c] = state[c].wrapping_add(state[d]);
-    state[b] ^= state[c];
-    state[b] = state[b].rotate_left(7);
+/// Decrypts the ciphertext with the ChaCha20 of the RustCrypto `chacha20` crate, starting from the
+/// block `counter`, as an independent check of the ciphertext produced by the folding
+pub fn rustcrypto_decrypt(
+    key: [u32; 8],
+    nonce: [u32; 3],
+    counter: u32,
+    ciphertext: &[u8],
+) -> Vec<u8> {
+    let key: [u8; 32] = words_to_bytes_le(&key).try_into().unwrap();
+    let nonce: [u8; 12] = words_to_bytes_le(&nonce).try_into().unwrap();
+    let mut cipher = chacha20::ChaCha20::new(&key.into(), &nonce.into());
+    cipher.seek(64 * counter as u64);
+    let mut plaintext = ciphertext.to_vec();
+    cipher.apply_keystream(&mut plaintext);
+    plaintext
 }
 
-// Note: Full native ChaCha20 implementation would be here
-// This demo focuses on the folding scheme integration
-
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use ark_r1cs_std::R1CSVar;
-    use ark_relations::r1cs::ConstraintSystem;
-
-    /// Test Quarter Round operation from RFC 7539 Section 2.1.1
-    #[test]
-    fn test_quarter_round_rfc7539() -> Result<(), Error> {
-        let cs = ConstraintSystem::<Fr>::new_ref();
-        let circuit = ChaCha20FCircuit::<Fr>::new(())?;
-        
-        // RFC 7539 Section 2.1.1 test vector
-        let a = 0x11111111u32;
-        let b = 0x01020304u32;
-        let c = 0x9b8d6f43u32;
-        let d = 0x01234567u32;
-        
-        // Expected results from RFC 7539
-        let expected_a = 0xea2a92f4u32;
-        let expected_b = 0xcb1cf8ceu32;
-        let expected_c = 0x4581472eu32;
-        let expected_d = 0x5881c4bbu32;
-        
-        // Test circuit quarter round
-        let a_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(a)))?;
-        let b_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(b)))?;
-        let c_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(c)))?;
-        let d_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(d)))?;
-        
-        let (result_a, result_b, result_c, result_d) = circuit.quarter_round(
-            cs.clone(),
-            &a_var,
-            &b_var,
-            &c_var,
-            &d_var,
-        )?;
-        
-        assert_eq!(result_a.value()?, Fr::from(expected_a));
-        assert_eq!(result_b.value()?, Fr::from(expected_b));
-        assert_eq!(result_c.value()?, Fr::from(expected_c));
-        assert_eq!(result_d.value()?, Fr::from(expected_d));
-        
-        println!("✅ Quarter Round RFC 7539 test passed!");
-        Ok(())
-    }
-    
-    /// Test Quarter Round on ChaCha State from RFC 7539 Section 2.2.1
-    #[test]
-    fn test_quarter_round_on_state_rfc7539() -> Result<(), Error> {
-        let cs = ConstraintSystem::<Fr>::new_ref();
-        let circuit = ChaCha20FCircuit::<Fr>::new(())?;
-        
-        // RFC 7539 Section 2.2.1 test vector
-        // Testing QUARTERROUND(2,7,8,13) operation
-        let a = 0x516461b1u32;  // position 2
-        let b = 0x2a5f714cu32;  // position 7
-        let c = 0x53372767u32;  // position 8
-        let d = 0x3d631689u32;  // position 13
-        
-        // Expected results from RFC 7539
-        let expected_a = 0xbdb886dcu32;
-        let expected_b = 0xcfacafd2u32;
-        let expected_c = 0xe46bea80u32;
-        let expected_d = 0xccc07c79u32;
-        
-        // Test circuit quarter round
-        let a_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(a)))?;
-        let b_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(b)))?;
-        let c_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(c)))?;
-        let d_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(d)))?;
-        
-        let (result_a, result_b, result_c, result_d) = circuit.quarter_round(
-            cs.clone(),
-            &a_var,
-            &b_var,
-            &c_var,
-            &d_var,
-        )?;
-        
-        assert_eq!(result_a.value()?, Fr::from(expected_a));
-        assert_eq!(result_b.value()?, Fr::from(expected_b));
-        assert_eq!(result_c.value()?, Fr::from(expected_c));
-        assert_eq!(result_d.value()?, Fr::from(expected_d));
-        
-        println!("✅ Quarter Round on State RFC 7539 test passed!");
-        Ok(())
-    }
-    
-    /// Test Quarter Round with overflow cases
-    #[test]
-    fn test_quarter_round_overflow() -> Result<(), Error> {
-        let cs = ConstraintSystem::<Fr>::new_ref();
-        let circuit = ChaCha20FCircuit::<Fr>::new(())?;
-        
-        // Test case where a + b would overflow u32
-        let a = 0xFFFFFFFFu32; // Maximum u32 value
-        let b = 0x00000001u32; // Adding 1 would overflow
-        let c = 0x00000000u32;
-        let d = 0x00000000u32;
-        
-        // Expected results (calculated manually)
-        let expected_a = 0x00001000u32;
-        let expected_b = 0x08080000u32;
-        let expected_c = 0x00100000u32;
-        let expected_d = 0x00100000u32;
-        
-        // Test circuit quarter round
-        let a_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(a)))?;
-        let b_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(b)))?;
-        let c_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(c)))?;
-        let d_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(d)))?;
-        
-        let (result_a, result_b, result_c, result_d) = circuit.quarter_round(
-            cs.clone(),
-            &a_var,
-            &b_var,
-            &c_var,
-            &d_var,
-        )?;
-        
-        assert_eq!(result_a.value()?, Fr::from(expected_a));
-        assert_eq!(result_b.value()?, Fr::from(expected_b));
-        assert_eq!(result_c.value()?, Fr::from(expected_c));
-        assert_eq!(result_d.value()?, Fr::from(expected_d));
-        
-        println!("✅ Quarter Round overflow test passed!");
-        Ok(())
-    }
-    
-    /// Test ChaCha20 Block Function from RFC 7539 Section 2.3.2
-    #[test]
-    fn test_chacha20_block_function_rfc7539() -> Result<(), Error> {
-        // Test the native implementation with RFC 7539 test vector
-        let key = [
-            0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
-            0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
-        ];
-        let nonce = [0x09000000, 0x4a000000, 0x00000000];
-        let counter = 0x00000001u32;
-        
-        // Expected result from RFC 7539 Section 2.3.2
-        let expected_result = [
-            0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3,
-            0xc7f4d1c7, 0x0368c033, 0x9aaa2204, 0x4e6cd4c3,
-            0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9,
-            0xd19c12b5, 0xb94e16de, 0xe883d0cb, 0x4e3c50a2,
-        ];
-        
-        let result = chacha20_block_native(key, nonce, counter);
-        
-        for i in 0..16 {
-            assert_eq!(result[i], expected_result[i], "Mismatch at position {}", i);
-        }
-        
-        println!("✅ ChaCha20 Block Function RFC 7539 test passed!");
-        Ok(())
-    }
 
     #[test]
     fn test_chacha20_f_circuit() -> Result<(), Error> {
-        let cs = ConstraintSystem::<Fr>::new_ref();
-        let circuit = ChaCha20FCircuit::<Fr>::new(())?;
+        let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
         
         // Test with RFC 7539 test vector
         let key = [
@@ -607,61 +387,857 @@ pub mod tests {
             0x666f2073, 0x39392720, 0x6649203a, 0x63204920,
             0x646c756f, 0x66666f20, 0x79207265, 0x6f20756f,
         ];
-        let external_inputs: [Fr; 16] = plaintext.iter().map(|&x| Fr::from(x)).collect::<Vec<_>>().try_into().unwrap();
-        
-        // Test native implementation
-        let z_i1_native = chacha20_step_native(z_i.clone(), external_inputs);
-        
-        // Test circuit implementation
-        let z_iVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i))?;
-        let external_inputsVar: [FpVar<Fr>; 16] = <[FpVar<Fr>; 16] as AllocVar<[Fr; 16], Fr>>::new_witness(cs.clone(), || Ok(external_inputs))?;
-        let computed_z_i1Var = circuit.generate_step_constraints(
-            cs.clone(),
-            0,
-            z_iVar,
-            external_inputsVar,
-        )?;
-        
-        assert_eq!(computed_z_i1Var.value()?, z_i1_native);
+        let external_inputs: Vec<Fr> = plaintext.iter().map(|&x| Fr::from(x)).collect();
+        
+        // Ciphertext of the test vector of RFC 7539 Section 2.4.2
+        let ciphertext: [u32; 16] = [
+            0x9a352e6e, 0x80f96825, 0x2807ba41, 0x81690ddd,
+            0xec7a7ee9, 0xc260431d, 0xccaf270a, 0x0bae9ffd,
+            0xc5651bf9, 0xab334752, 0xab3d598f, 0x57b362cd,
+            0x24d63916, 0xab5251e6, 0x350c538f, 0xd861089f,
+        ];
+        let expected_z_i1: Vec<Fr> = [
+            z_i[..11].to_vec(),
+            vec![Fr::from(counter + 1)],
+            ciphertext.iter().map(|&x| Fr::from(x)).collect(),
+        ]
+        .concat();
+        
+        // Run the step circuit, checking that its constraints are satisfied
+        let states = simulate_steps(&circuit, z_i, [external_inputs])?.into_states()?;
+        assert_eq!(states[1], expected_z_i1);
         println!("✅ ChaCha20 circuit test passed!");
         Ok(())
     }
+
+    /// The next counter must be the increment of the counter of the state, and not a free witness
+    #[test]
+    fn test_chacha20_counter_is_constrained() -> Result<(), Error> {
+        use ark_r1cs_std::eq::EqGadget;
+
+        let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+        let z_i: Vec<Fr> = [
+            vec![Fr::from(0x03020100u32); 8],
+            vec![Fr::from(0u32), Fr::from(0x4a000000u32), Fr::from(0u32)],
+            vec![Fr::from(1u32)],
+            vec![Fr::from(0u32); 16],
+        ]
+        .concat();
+
+        for (next_counter, satisfied) in [(2u32, true), (3u32, false)] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i.clone()))?;
+            let external_inputs =
+                VecFpVarDyn::new_witness(cs.clone(), || Ok(vec![Fr::from(0u32); 16]))?;
+            let z_i1 = circuit.generate_step_constraints(cs.clone(), 0, z_i, external_inputs)?;
+            // as done by the folding schemes, the claimed next state is a witness enforced to be
+            // the output of the step
+            let claimed_counter = FpVar::new_witness(cs.clone(), || Ok(Fr::from(next_counter)))?;
+            z_i1[11].enforce_equal(&claimed_counter)?;
+            assert_eq!(cs.is_satisfied()?, satisfied);
+        }
+        Ok(())
+    }
+
+    /// The conversions between FpVar and UInt32 must not leave the converted values as free
+    /// witnesses
+    #[test]
+    fn test_word_conversions_are_constrained() -> Result<(), Error> {
+        use ark_ff::One;
+        use ark_r1cs_std::eq::EqGadget;
+
+        // UInt32 -> FpVar adds no witnesses, so the result is determined by the bits of the word
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = UInt32::new_witness(cs.clone(), || Ok(0xdeadbeefu32))?;
+        let n_witnesses = cs.num_witness_variables();
+        let y = u32_to_fp(&x)?;
+        assert_eq!(cs.num_witness_variables(), n_witnesses);
+        assert_eq!(y.value()?, Fr::from(0xdeadbeefu32));
+        // claiming another value for the result is rejected
+        let claimed = FpVar::new_witness(cs.clone(), || Ok(Fr::from(0xdeadbeefu32)))?;
+        y.enforce_equal(&claimed)?;
+        assert!(cs.is_satisfied()?);
+        cs.borrow_mut().unwrap().witness_assignment[n_witnesses] += Fr::one();
+        assert!(!cs.is_satisfied()?);
+
+        // FpVar -> UInt32 only accepts values in [0, 2^32)
+        for (value, satisfied) in [
+            (Fr::from(u32::MAX), true),
+            (Fr::from(1u64 << 32), false),
+            (-Fr::one(), false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let fp = FpVar::new_witness(cs.clone(), || Ok(value))?;
+            enforce_fits_u32(&fp)?;
+            assert_eq!(cs.is_satisfied()?, satisfied);
+        }
+
+        // and its bits can not be set independently of the input
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let fp = FpVar::new_witness(cs.clone(), || Ok(Fr::from(5u32)))?;
+        let x = enforce_fits_u32(&fp)?;
+        assert_eq!(x.value()?, 5);
+        assert!(cs.is_satisfied()?);
+        // flip the bit of weight 2, turning the word into 7
+        cs.borrow_mut().unwrap().witness_assignment[2] = Fr::one();
+        assert!(!cs.is_satisfied()?);
+        Ok(())
+    }
+
+    /// The words are decomposed to bits once per step, so that besides the block function a step
+    /// only pays for the range checks of the 28 input words, the XOR with the plaintext and the
+    /// increment of the counter
+    #[test]
+    fn test_chacha20_step_constraints() -> Result<(), Error> {
+        use experimental_frontends::gadgets::chacha20::chacha20_block_gadget;
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(0u32); 28]))?;
+        let plaintext = VecFpVarDyn::new_witness(cs.clone(), || Ok(vec![Fr::from(0u32); 16]))?;
+        let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+        circuit.generate_step_constraints(cs.clone(), 0, z_i, plaintext)?;
+        assert!(cs.is_satisfied()?);
+
+        let block_cs = ConstraintSystem::<Fr>::new_ref();
+        let words = (0..12)
+            .map(|_| UInt32::new_witness(block_cs.clone(), || Ok(0u32)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let n_constraints = block_cs.num_constraints();
+        let key: [UInt32<Fr>; 8] = core::array::from_fn(|j| words[j].clone());
+        let nonce: [UInt32<Fr>; 3] = core::array::from_fn(|j| words[8 + j].clone());
+        chacha20_block_gadget(block_cs.clone(), &key, &nonce, &words[11])?;
+        let block_constraints = block_cs.num_constraints() - n_constraints;
+
+        let range_check_cs = ConstraintSystem::<Fr>::new_ref();
+        enforce_fits_u32(&FpVar::new_witness(range_check_cs.clone(), || Ok(Fr::from(0u32)))?)?;
+
+        // and for the increment of the counter
+        let n_constraints = block_cs.num_constraints();
+        let _ = words[11].wrapping_add(&UInt32::constant(1));
+        let increment_constraints = block_cs.num_constraints() - n_constraints;
+
+        assert_eq!(
+            cs.num_constraints(),
+            block_constraints
+                + 28 * range_check_cs.num_constraints()
+                + 16 * 32
+                + increment_constraints
+        );
+        Ok(())
+    }
+
+    /// Snapshot of the numbers of constraints of the steps, which size the SRS of the folding
+    /// schemes. An optimization of the circuit must update them. The gadgets are snapshotted in
+    /// `experimental_frontends::gadgets::chacha20`, and the decomposition of a step is checked by
+    /// `test_chacha20_step_constraints`.
+    #[test]
+    fn test_chacha20_constraint_counts() -> Result<(), Error> {
+        let counts = constraint_counts()?;
+        for (name, count) in &counts {
+            println!("{:<32} {:>8}", name, count);
+        }
+        let count = |name: &str| counts.iter().find(|(n, _)| n == name).map(|&(_, c)| c);
+
+        for (config, expected) in [
+            ("Ietf, 1 blocks", 23134),
+            ("Original, 1 blocks", 23166),
+            ("XChaCha20, 1 blocks", 44353),
+            ("Ietf, 4 blocks", 91348),
+        ] {
+            let step = count(&format!("step ({})", config));
+            assert_eq!(step, Some(expected));
+            // the augmented circuit folded by Nova contains the step of the FCircuit
+            assert!(count(&format!("Nova step ({})", config)) > step);
+        }
+        assert!(count("CycleFold circuit") > Some(0));
+        Ok(())
+    }
+
+    /// The block counter wraps around from 0xffffffff to 0, as in ChaCha20
+    #[test]
+    fn test_chacha20_counter_wraps() -> Result<(), Error> {
+        use experimental_frontends::gadgets::chacha20::chacha20_block_native;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let key = [0x03020100u32; 8];
+        let nonce = [0x00000000u32, 0x4a000000, 0x00000000];
+        let plaintext = [0x6964614cu32; 16];
+        let z_0: Vec<Fr> = [
+            key.iter().map(|&w| Fr::from(w)).collect(),
+            nonce.iter().map(|&w| Fr::from(w)).collect(),
+            vec![Fr::from(u32::MAX)],
+            vec![Fr::from(0u32); 16],
+        ]
+        .concat();
+        let external_inputs = plaintext.map(Fr::from).to_vec();
+
+        let mut rng = ark_std::test_rng();
+        let F_circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone())?;
+        let states =
+            simulate_steps(&F_circuit, z_0, vec![external_inputs.clone(); 2])?.into_states()?;
+        // the blocks of the two steps use the counters 0xffffffff and 0
+        for (i, (counter, next_counter)) in [(u32::MAX, 0u32), (0, 1)].into_iter().enumerate() {
+            nova.prove_step(&mut rng, external_inputs.clone(), None)?;
+            let keystream = chacha20_block_native(key, nonce, counter);
+            let ciphertext = (0..16).map(|j| Fr::from(plaintext[j] ^ keystream[j]));
+            let expected: Vec<Fr> = [
+                states[0][..11].to_vec(),
+                vec![Fr::from(next_counter)],
+                ciphertext.collect(),
+            ]
+            .concat();
+            assert_eq!(states[i + 1], expected);
+            assert_eq!(nova.state(), expected);
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
+    /// Fold two steps of the Original and XChaCha20 variants, the former with its 64-bit counter
+    /// crossing into its high word
+    #[test]
+    fn test_chacha20_variants() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let key = [
+            0x83828180u32, 0x87868584, 0x8b8a8988, 0x8f8e8d8c,
+            0x93929190, 0x97969594, 0x9b9a9998, 0x9f9e9d9c,
+        ];
+        let plaintext = [0x6964614cu32; 16];
+        let external_inputs = plaintext.map(Fr::from).to_vec();
+        for (variant, nonce, counters) in [
+            (
+                ChaCha20Variant::Original,
+                vec![0x00000000u32, 0x01000000],
+                [vec![u32::MAX, 0u32], vec![0, 1], vec![1, 1]],
+            ),
+            (
+                // nonce of draft-irtf-cfrg-xchacha-03 Appendix A.3.2
+                ChaCha20Variant::XChaCha20,
+                vec![0x43424140, 0x47464544, 0x4b4a4948, 0x4f4e4d4c, 0x53525150, 0x58565554],
+                [vec![1], vec![2], vec![3]],
+            ),
+        ] {
+            let z_0: Vec<Fr> = [
+                key.to_vec(),
+                nonce.clone(),
+                counters[0].clone(),
+                vec![0u32; 16],
+            ]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+
+            let mut rng = ark_std::test_rng();
+            let F_circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+                variant,
+                ..Default::default()
+            })?;
+            assert_eq!(F_circuit.state_len(), z_0.len());
+            let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+            let nova_params = N::preprocess(&mut rng, &prep_param)?;
+            let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+            for i in 0..2 {
+                nova.prove_step(&mut rng, external_inputs.clone(), None)?;
+                let keystream = variant.block_native(key, &nonce, &counters[i]);
+                let expected: Vec<Fr> = [
+                    key.to_vec(),
+                    nonce.clone(),
+                    counters[i + 1].clone(),
+                    (0..16).map(|j| plaintext[j] ^ keystream[j]).collect(),
+                ]
+                .concat()
+                .into_iter()
+                .map(Fr::from)
+                .collect();
+                assert_eq!(nova.state(), expected);
+            }
+            N::verify(nova_params.1, nova.ivc_proof())?;
+        }
+        Ok(())
+    }
+
+    /// Encrypting several blocks per step gives the ciphertexts of the same blocks encrypted one
+    /// after the other
+    #[test]
+    fn test_chacha20_blocks_per_step() -> Result<(), Error> {
+        use experimental_frontends::gadgets::chacha20::chacha20_block_native;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let key = [
+            0x03020100u32, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+            0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+        ];
+        let nonce = [0x00000000u32, 0x4a000000, 0x00000000];
+        // 4 blocks of plaintext
+        let plaintext: Vec<u32> = (0..64u32).map(|j| j.wrapping_mul(0x01010101)).collect();
+        let ciphertext: Vec<u32> = plaintext
+            .chunks(16)
+            .enumerate()
+            .flat_map(|(block, words)| {
+                let keystream = chacha20_block_native(key, nonce, 1 + block as u32);
+                (0..16).map(move |j| words[j] ^ keystream[j])
+            })
+            .collect();
+
+        for blocks_per_step in [1, 2, 4] {
+            let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+                blocks_per_step,
+                ..Default::default()
+            })?;
+            let words_per_step = 16 * blocks_per_step;
+            let z_0: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![1], vec![0; words_per_step]]
+                .concat()
+                .into_iter()
+                .map(Fr::from)
+                .collect();
+            assert_eq!(circuit.state_len(), z_0.len());
+            let inputs: Vec<Vec<Fr>> = plaintext
+                .chunks(words_per_step)
+                .map(|words| words.iter().map(|&w| Fr::from(w)).collect())
+                .collect();
+
+            let states = simulate_steps(&circuit, z_0.clone(), inputs.clone())?.into_states()?;
+            for (step, state) in states[1..].iter().enumerate() {
+                let next_counter = 1 + ((step + 1) * blocks_per_step) as u32;
+                let expected: Vec<Fr> = [
+                    key.to_vec(),
+                    nonce.to_vec(),
+                    vec![next_counter],
+                    ciphertext[step * words_per_step..(step + 1) * words_per_step].to_vec(),
+                ]
+                .concat()
+                .into_iter()
+                .map(Fr::from)
+                .collect();
+                assert_eq!(state, &expected);
+            }
+
+            // the folding scheme builds the shape of the step from empty external inputs, which
+            // must match the steps with their 16 * blocks_per_step words of plaintext
+            if blocks_per_step == 2 {
+                let mut rng = ark_std::test_rng();
+                let prep_param =
+                    PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+                let nova_params = N::preprocess(&mut rng, &prep_param)?;
+                let mut nova = N::init(&nova_params, circuit, z_0)?;
+                for step_inputs in inputs {
+                    nova.prove_step(&mut rng, step_inputs, None)?;
+                }
+                N::verify(nova_params.1, nova.ivc_proof())?;
+                assert_eq!(&nova.state(), states.last().unwrap());
+            }
+        }
+
+        assert!(matches!(
+            ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+                blocks_per_step: 0,
+                ..Default::default()
+            }),
+            Err(Error::NotExpectedLength(0, 1))
+        ));
+        Ok(())
+    }
+
+    /// Fold the ChaCha20 circuit carrying a Poseidon digest of the ciphertext, and check the final
+    /// state against the digest recomputed natively from the plaintext and the key
+    #[test]
+    fn test_chacha20_digest() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let key = [
+            0x03020100u32, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+            0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+        ];
+        let nonce = [0x00000000u32, 0x4a000000, 0x00000000];
+        let params = ChaCha20Params {
+            blocks_per_step: 2,
+            output: ChaCha20Output::Digest,
+            ..Default::default()
+        };
+        let circuit = ChaCha20FCircuit::<Fr>::new(params)?;
+        assert_eq!(circuit.state_len(), 8 + 3 + 1 + 1);
+        let z_0: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![1, 0]]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+
+        // 2 steps of 2 blocks of plaintext
+        let plaintext: Vec<u32> = (0..64u32).map(|j| j.wrapping_mul(0x01010101)).collect();
+        let inputs: Vec<Vec<Fr>> = plaintext
+            .chunks(32)
+            .map(|words| words.iter().map(|&w| Fr::from(w)).collect())
+            .collect();
+
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, circuit, z_0)?;
+        for step_inputs in inputs {
+            nova.prove_step(&mut rng, step_inputs, None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+
+        let digest = chacha20_digest_native::<Fr>(&params, key, &nonce, &[1], &plaintext)?;
+        let expected: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![5]]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .chain([digest])
+            .collect();
+        assert_eq!(nova.state(), expected);
+
+        // the digest commits to the plaintext
+        let mut other_plaintext = plaintext.clone();
+        other_plaintext[40] ^= 1;
+        assert_ne!(
+            chacha20_digest_native::<Fr>(&params, key, &nonce, &[1], &other_plaintext)?,
+            digest
+        );
+        assert!(matches!(
+            chacha20_digest_native::<Fr>(&params, key, &nonce, &[1], &plaintext[..16]),
+            Err(Error::NotExpectedLength(16, 32))
+        ));
+        Ok(())
+    }
+
+    /// Fold the ChaCha20 circuit carrying a commitment to the plaintext, and check that the final
+    /// state binds the plaintext: a verifier holding a different claimed plaintext rejects it
+    #[test]
+    fn test_chacha20_plaintext_commitment() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let key = [
+            0x03020100u32, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+            0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+        ];
+        let nonce = [0x00000000u32, 0x4a000000, 0x00000000];
+        let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+            blocks_per_step: 2,
+            output: ChaCha20Output::Digest,
+            commit_plaintext: true,
+            ..Default::default()
+        })?;
+        assert_eq!(circuit.state_len(), 8 + 3 + 1 + 1 + 1);
+        let z_0: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![1, 0, 0]]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+
+        // 2 steps of 2 blocks of plaintext
+        let blocks: Vec<[u32; 16]> = (0..4u32)
+            .map(|b| core::array::from_fn(|j| (16 * b + j as u32).wrapping_mul(0x01010101)))
+            .collect();
+        let inputs: Vec<Vec<Fr>> = blocks
+            .chunks(2)
+            .map(|step| step.concat().into_iter().map(Fr::from).collect())
+            .collect();
+
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, circuit, z_0)?;
+        for step_inputs in inputs {
+            nova.prove_step(&mut rng, step_inputs, None)?;
+        }
+        let ivc_proof = nova.ivc_proof();
+        N::verify(nova_params.1.clone(), ivc_proof.clone())?;
+
+        // the commitment does not depend on the number of blocks per step
+        let commitment = plaintext_commitment::<Fr>(&blocks)?;
+        assert_eq!(*ivc_proof.z_i.last().unwrap(), commitment);
+
+        // a final state claiming another plaintext does not verify
+        let mut claimed_blocks = blocks.clone();
+        claimed_blocks[3][5] ^= 1;
+        let claimed_commitment = plaintext_commitment::<Fr>(&claimed_blocks)?;
+        assert_ne!(claimed_commitment, commitment);
+        let mut forged_proof = ivc_proof;
+        *forged_proof.z_i.last_mut().unwrap() = claimed_commitment;
+        assert!(N::verify(nova_params.1, forged_proof).is_err());
+        Ok(())
+    }
+
+    /// Fold the encryption of the plaintext of RFC 7539 Section 2.4.2 one block per step, read the
+    /// ciphertext bytes out of the state after each step, and decrypt them with the RustCrypto
+    /// implementation of ChaCha20
+    #[test]
+    fn test_chacha20_rustcrypto_decrypt() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let key = [
+            0x03020100u32, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+            0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+        ];
+        let nonce = [0x00000000u32, 0x4a000000, 0x00000000];
+        let message = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let mut plaintext = message.to_vec();
+        plaintext.resize(128, 0);
+
+        let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+        let z_0: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![1], vec![0; 16]]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, circuit, z_0)?;
+        let mut ciphertext = Vec::new();
+        for block in bytes_to_words_le(&plaintext).chunks(16) {
+            nova.prove_step(&mut rng, block.iter().map(|&w| Fr::from(w)).collect(), None)?;
+            ciphertext.extend(words_to_bytes_le(&state_to_ciphertext_words(&nova.state())));
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+
+        // the first bytes of the ciphertext of RFC 7539 Section 2.4.2
+        assert_eq!(
+            ciphertext[..16],
+            [
+                0x6e, 0x2e, 0x35, 0x9a, 0x25, 0x68, 0xf9, 0x80, 0x41, 0xba, 0x07, 0x28, 0xdd, 0x0d,
+                0x69, 0x81,
+            ]
+        );
+        assert_eq!(rustcrypto_decrypt(key, nonce, 1, &ciphertext), plaintext);
+        assert_eq!(
+            rustcrypto_decrypt(key, nonce, 1, &ciphertext[..message.len()]),
+            message
+        );
+        Ok(())
+    }
+
+    /// In the keystream-only mode the steps take no plaintext, and the state carries the keystream
+    /// blocks of RFC 7539 Section 2.3.2, or their digest
+    #[test]
+    fn test_chacha20_keystream_only() -> Result<(), Error> {
+        use experimental_frontends::gadgets::chacha20::chacha20_block_native;
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let key = [
+            0x03020100u32, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+            0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+        ];
+        let nonce = [0x09000000u32, 0x4a000000, 0x00000000];
+        let params = ChaCha20Params {
+            mode: ChaCha20Mode::KeystreamOnly,
+            ..Default::default()
+        };
+        let circuit = ChaCha20FCircuit::<Fr>::new(params)?;
+        let z_0: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![1], vec![0; 16]]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+        assert_eq!(circuit.state_len(), z_0.len());
+
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, circuit, z_0)?;
+        for counter in [1, 2] {
+            nova.prove_step(&mut rng, vec![], None)?;
+            let keystream = chacha20_block_native(key, nonce, counter);
+            assert_eq!(
+                nova.state(),
+                [key.to_vec(), nonce.to_vec(), vec![counter + 1], keystream.to_vec()]
+                    .concat()
+                    .into_iter()
+                    .map(Fr::from)
+                    .collect::<Vec<_>>()
+            );
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+        // the first keystream block of RFC 7539 Section 2.3.2
+        assert_eq!(chacha20_block_native(key, nonce, 1)[0], 0xe4e7f110);
+
+        // the digest of the keystream is the one of the encryption of zeros
+        let params = ChaCha20Params {
+            blocks_per_step: 2,
+            output: ChaCha20Output::Digest,
+            mode: ChaCha20Mode::KeystreamOnly,
+            ..Default::default()
+        };
+        let circuit = ChaCha20FCircuit::<Fr>::new(params)?;
+        let z_0: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![1, 0]]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+        let states = simulate_steps(&circuit, z_0, vec![vec![]; 2])?.into_states()?;
+        assert_eq!(
+            states[2][12],
+            chacha20_digest_native::<Fr>(&params, key, &nonce, &[1], &[0; 64])?
+        );
+
+        // the plaintext given to a keystream-only step is rejected, as well as a commitment to it
+        let plaintext = vec![vec![Fr::from(0u32); 32]];
+        assert!(simulate_steps(&circuit, states[0].clone(), plaintext)
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        assert!(matches!(
+            ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+                commit_plaintext: true,
+                ..params
+            }),
+            Err(Error::NotSupported(_))
+        ));
+        Ok(())
+    }
+
+    /// Plaintext words of more than 32 bits, whose low 32 bits would still be encrypted, are
+    /// rejected
+    #[test]
+    fn test_chacha20_plaintext_range_check() -> Result<(), Error> {
+        let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+        let z_0 = vec![Fr::from(0u32); 28];
+        let mut plaintext = vec![Fr::from(0x6964614cu32); 16];
+        assert!(simulate_steps(&circuit, z_0.clone(), [plaintext.clone()])?.all_satisfied());
+
+        plaintext[3] += Fr::from(1u64 << 32);
+        assert!(simulate_steps(&circuit, z_0, [plaintext])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        Ok(())
+    }
+
+    /// Fold ChaCha20 blocks with HyperNova and wrap the result with the onchain decider
+    #[test]
+    fn test_chacha20_hypernova_decider() -> Result<(), Error> {
+        use ark_groth16::Groth16;
+        use folding_schemes::folding::hypernova::{decider_eth::Decider as DeciderEth, HyperNova};
+        use folding_schemes::folding::traits::CommittedInstanceOps;
+        use folding_schemes::Decider;
+
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            1,
+            1,
+            false,
+        >;
+        type D = DeciderEth<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Groth16<Bn254>,
+            HN,
+            1,
+            1,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let F_circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+
+        // z_0 = [key, nonce, counter, zeros]
+        let z_0: Vec<Fr> = [
+            vec![Fr::from(0x03020100u32); 8],
+            vec![Fr::from(0u32), Fr::from(0x4a000000u32), Fr::from(0u32)],
+            vec![Fr::from(1u32)],
+            vec![Fr::from(0u32); 16],
+        ]
+        .concat();
+        let plaintext = vec![Fr::from(0x6964614cu32); 16];
+
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+        let hypernova_params = HN::preprocess(&mut rng, &prep_param)?;
+        let mut hypernova = HN::init(&hypernova_params, F_circuit, z_0.clone())?;
+        for _ in 0..2 {
+            hypernova.prove_step(&mut rng, plaintext.clone(), None)?;
+        }
+        HN::verify(hypernova_params.1.clone(), hypernova.ivc_proof())?;
+
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (hypernova_params, F_circuit.state_len()))?;
+        let proof = D::prove(rng, decider_pp, hypernova.clone())?;
+        let verified = D::verify(
+            decider_vp,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &hypernova.U_i.get_commitments(),
+            &hypernova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
+
+    /// Fold ChaCha20 blocks with Nova using IPA commitments (transparent setup) and wrap the result
+    /// with the offchain decider
+    #[test]
+    fn test_chacha20_nova_ipa_offchain_decider() -> Result<(), Error> {
+        use ark_groth16::Groth16;
+        // Note: do not use the MNTx_298 curves in practice, these are just for tests. Use the
+        // MNTx_753 curves instead.
+        use ark_mnt4_298::{Fr, G1Projective as Projective, MNT4_298 as MNT4};
+        use ark_mnt6_298::{G1Projective as Projective2, MNT6_298 as MNT6};
+        use folding_schemes::commitment::ipa::IPA;
+        use folding_schemes::folding::nova::decider::Decider;
+        use folding_schemes::folding::traits::CommittedInstanceOps;
+        use folding_schemes::Decider as DeciderTrait;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            IPA<Projective>,
+            IPA<Projective2>,
+            false,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            ChaCha20FCircuit<Fr>,
+            IPA<Projective>,
+            IPA<Projective2>,
+            Groth16<MNT4>,
+            Groth16<MNT6>,
+            N,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let F_circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+
+        // z_0 = [key, nonce, counter, zeros]
+        let z_0: Vec<Fr> = [
+            vec![Fr::from(0x03020100u32); 8],
+            vec![Fr::from(0u32), Fr::from(0x4a000000u32), Fr::from(0u32)],
+            vec![Fr::from(1u32)],
+            vec![Fr::from(0u32); 16],
+        ]
+        .concat();
+        let plaintext = vec![Fr::from(0x6964614cu32); 16];
+
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone())?;
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, plaintext.clone(), None)?;
+        }
+        N::verify(nova_params.1.clone(), nova.ivc_proof())?;
+
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
 }
 
 /// Large-scale ChaCha20 folding demonstration
 fn main() -> Result<(), Error> {
     println!("🚀 ChaCha20 Folding Scheme Demo");
     
-    // Test different data sizes to demonstrate folding benefits
-    let test_sizes = vec![1, 10, 100, 1000]; // Number of 64-byte blocks
+    // Size of the step circuit, and the part of it spent on the 32-bit range checks of the 12
+    // key, nonce and counter words of the state and of the 16 plaintext words
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(0u32); 28]))?;
+    let plaintext = VecFpVarDyn::new_witness(cs.clone(), || Ok(vec![Fr::from(0u32); 16]))?;
+    let circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params::default())?;
+    circuit.generate_step_constraints(cs.clone(), 0, z_i, plaintext)?;
+    let range_check_cs = ConstraintSystem::<Fr>::new_ref();
+    enforce_fits_u32(&FpVar::new_witness(range_check_cs.clone(), || Ok(Fr::from(0u32)))?)?;
+    println!(
+        "📐 Step circuit: {} constraints, {} of them for the 32-bit range checks of the 28 input words",
+        cs.num_constraints(),
+        28 * range_check_cs.num_constraints()
+    );
+    println!("📐 Constraints:");
+    for (name, count) in constraint_counts()? {
+        println!("   {:<32} {:>8}", name, count);
+    }
     
-    for &num_blocks in &test_sizes {
-        println!("\n📊 Testing {} blocks ({} bytes)", num_blocks, num_blocks * 64);
-        
-        let num_steps = num_blocks;
-        
-        // RFC 7539 test vector
+    // Keystream-only mode: the steps take no plaintext and prove the keystream blocks, which the
+    // prover XORs with the plaintext outside of the circuit
+    {
+        println!("\n🔑 Keystream-only mode");
         let key = [
-            0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+            0x03020100u32, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
             0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
         ];
-        let nonce = [0x00000000, 0x4a000000, 0x00000000];
-        let counter = 1u32;
-        
-        // Initial state: [key, nonce, counter, zeros]
-        let mut initial_state = Vec::new();
-        for k in key {
-            initial_state.push(Fr::from(k));
-        }
-        for n in nonce {
-            initial_state.push(Fr::from(n));
-        }
-        initial_state.push(Fr::from(counter));
-        for _ in 0..16 {
-            initial_state.push(Fr::from(0u32));
-        }
-        
-        let F_circuit = ChaCha20FCircuit::<Fr>::new(())?;
+        let nonce = [0x00000000u32, 0x4a000000, 0x00000000];
+        let F_circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+            mode: ChaCha20Mode::KeystreamOnly,
+            ..Default::default()
+        })?;
+        let z_0: Vec<Fr> = [key.to_vec(), nonce.to_vec(), vec![1], vec![0; 16]]
+            .concat()
+            .into_iter()
+            .map(Fr::from)
+            .collect();
         
         type N = Nova<
             Projective,
@@ -671,67 +1247,154 @@ fn main() -> Result<(), Error> {
             Pedersen<Projective2>,
             false,
         >;
-        
-        let poseidon_config = poseidon_canonical_config::<Fr>();
         let mut rng = rand::rngs::OsRng;
-        
-        println!("⚙️  Preparing Nova ProverParams & VerifierParams");
-        let setup_start = Instant::now();
-        let nova_preprocess_params = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_preprocess_params =
+            PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
         let nova_params = N::preprocess(&mut rng, &nova_preprocess_params)?;
-        println!("   Setup time: {:?}", setup_start.elapsed());
-        
-        println!("🔄 Initializing FoldingScheme");
-        let init_start = Instant::now();
-        let mut folding_scheme = N::init(&nova_params, F_circuit, initial_state.clone())?;
-        println!("   Init time: {:?}", init_start.elapsed());
-        
-        // Generate sample plaintext blocks
-        let sample_plaintext = [
-            0x6964614c, 0x61207365, 0x4720646e, 0x6c746e65,
-            0x6e656d65, 0x20666f20, 0x20656874, 0x73616c63,
-            0x666f2073, 0x39392720, 0x6649203a, 0x63204920,
-            0x646c756f, 0x66666f20, 0x79207265, 0x6f20756f,
-        ];
-        
-        let mut total_prove_time = std::time::Duration::new(0, 0);
-        
-        // Perform folding steps
-        for i in 0..num_steps {
-            let external_inputs: [Fr; 16] = sample_plaintext.iter().map(|&x| Fr::from(x)).collect::<Vec<_>>().try_into().unwrap();
+        let mut folding_scheme = N::init(&nova_params, F_circuit, z_0)?;
+        let prove_start = Instant::now();
+        for counter in 1..=4 {
+            folding_scheme.prove_step(rng, vec![], None)?;
+            let keystream = state_to_ciphertext_words(&folding_scheme.state());
+            assert_eq!(keystream, ChaCha20Variant::Ietf.block_native(key, &nonce, &[counter]));
+        }
+        println!("   Proving time of 4 keystream blocks: {:?}", prove_start.elapsed());
+        N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+        println!("✅ Keystream blocks verified, the XOR with the plaintext happens off-circuit");
+    }
+    
+    // Test different data sizes to demonstrate folding benefits, encrypting 1, 4 or 16 blocks
+    // per folding step
+    println!("\n🔒 Encrypt mode");
+    let test_sizes = vec![16, 64, 256]; // Number of 64-byte blocks
+    let blocks_per_step_options = [1, 4, 16];
+    let mut throughputs = Vec::new();
+    
+    for &blocks_per_step in &blocks_per_step_options {
+        for &num_blocks in &test_sizes {
+            println!(
+                "\n📊 Testing {} blocks ({} bytes), {} blocks per step",
+                num_blocks, num_blocks * 64, blocks_per_step
+            );
             
-            let step_start = Instant::now();
-            folding_scheme.prove_step(rng, external_inputs, None)?;
-            let step_time = step_start.elapsed();
-            total_prove_time += step_time;
+            let num_steps = num_blocks / blocks_per_step;
             
-            if i < 5 || i % (num_steps / 5).max(1) == 0 {
-                println!("   Step {}: {:?}", i + 1, step_time);
+            // RFC 7539 test vector
+            let key = [
+                0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+                0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+            ];
+            let nonce = [0x00000000, 0x4a000000, 0x00000000];
+            let counter = 1u32;
+            
+            // Initial state: [key, nonce, counter, zeros]
+            let mut initial_state = Vec::new();
+            for k in key {
+                initial_state.push(Fr::from(k));
+            }
+            for n in nonce {
+                initial_state.push(Fr::from(n));
+            }
+            initial_state.push(Fr::from(counter));
+            for _ in 0..16 * blocks_per_step {
+                initial_state.push(Fr::from(0u32));
+            }
+            
+            let F_circuit = ChaCha20FCircuit::<Fr>::new(ChaCha20Params {
+                variant: ChaCha20Variant::Ietf,
+                blocks_per_step,
+                ..Default::default()
+            })?;
+            
+            type N = Nova<
+                Projective,
+                Projective2,
+                ChaCha20FCircuit<Fr>,
+                KZG<'static, Bn254>,
+                Pedersen<Projective2>,
+                false,
+            >;
+            
+            let poseidon_config = poseidon_canonical_config::<Fr>();
+            let mut rng = rand::rngs::OsRng;
+            
+            println!("⚙️  Preparing Nova ProverParams & VerifierParams");
+            let setup_start = Instant::now();
+            let nova_preprocess_params = PreprocessorParam::new(poseidon_config, F_circuit);
+            let nova_params = N::preprocess(&mut rng, &nova_preprocess_params)?;
+            println!("   Setup time: {:?}", setup_start.elapsed());
+            
+            println!("🔄 Initializing FoldingScheme");
+            let init_start = Instant::now();
+            let mut folding_scheme = N::init(&nova_params, F_circuit, initial_state.clone())?;
+            println!("   Init time: {:?}", init_start.elapsed());
+            
+            // Generate sample plaintext blocks
+            let sample_plaintext = [
+                0x6964614c, 0x61207365, 0x4720646e, 0x6c746e65,
+                0x6e656d65, 0x20666f20, 0x20656874, 0x73616c63,
+                0x666f2073, 0x39392720, 0x6649203a, 0x63204920,
+                0x646c756f, 0x66666f20, 0x79207265, 0x6f20756f,
+            ];
+            
+            let mut total_prove_time = std::time::Duration::new(0, 0);
+            
+            // Perform folding steps
+            for i in 0..num_steps {
+                let external_inputs: Vec<Fr> = sample_plaintext.iter().map(|&x| Fr::from(x)).collect::<Vec<_>>().repeat(blocks_per_step);
+                
+                let step_start = Instant::now();
+                folding_scheme.prove_step(rng, external_inputs, None)?;
+                let step_time = step_start.elapsed();
+                total_prove_time += step_time;
+                
+                if i < 5 || i % (num_steps / 5).max(1) == 0 {
+                    println!("   Step {}: {:?}", i + 1, step_time);
+                }
+            }
+            
+            println!("✅ Total proving time: {:?}", total_prove_time);
+            println!("📈 Average time per block: {:?}", total_prove_time / num_blocks as u32);
+            
+            println!("🔍 Verifying IVC proof");
+            let verify_start = Instant::now();
+            let ivc_proof = folding_scheme.ivc_proof();
+            N::verify(nova_params.1, ivc_proof)?;
+            println!("   Verification time: {:?}", verify_start.elapsed());
+            
+            println!("✅ Verification successful for {} blocks!", num_blocks);
+            
+            // Decrypt the ciphertext of the last block, read from the final state, with the
+            // RustCrypto implementation of ChaCha20
+            let ciphertext = words_to_bytes_le(&state_to_ciphertext_words(&folding_scheme.state()));
+            let last_counter = counter + num_blocks as u32 - 1;
+            assert_eq!(
+                rustcrypto_decrypt(key, nonce, last_counter, &ciphertext),
+                words_to_bytes_le(&sample_plaintext)
+            );
+            println!("🔓 Last ciphertext block decrypts to the plaintext with RustCrypto ChaCha20");
+            
+            // Performance analysis
+            let bytes_processed = num_blocks * 64;
+            let throughput = bytes_processed as f64 / total_prove_time.as_secs_f64();
+            println!("📊 Throughput: {:.2} bytes/second", throughput);
+            throughputs.push((blocks_per_step, num_blocks, throughput));
+            
+            if num_blocks >= 100 {
+                println!("🎯 Large-scale folding demonstrates significant efficiency gains!");
+                println!("   - Proof size: O(1) regardless of data size");
+                println!("   - Memory usage: Constant");
+                println!("   - Verification time: Independent of computation steps");
             }
         }
-        
-        println!("✅ Total proving time: {:?}", total_prove_time);
-        println!("📈 Average time per block: {:?}", total_prove_time / num_steps as u32);
-        
-        println!("🔍 Verifying IVC proof");
-        let verify_start = Instant::now();
-        let ivc_proof = folding_scheme.ivc_proof();
-        N::verify(nova_params.1, ivc_proof)?;
-        println!("   Verification time: {:?}", verify_start.elapsed());
-        
-        println!("✅ Verification successful for {} blocks!", num_blocks);
-        
-        // Performance analysis
-        let bytes_processed = num_blocks * 64;
-        let throughput = bytes_processed as f64 / total_prove_time.as_secs_f64();
-        println!("📊 Throughput: {:.2} bytes/second", throughput);
-        
-        if num_blocks >= 100 {
-            println!("🎯 Large-scale folding demonstrates significant efficiency gains!");
-            println!("   - Proof size: O(1) regardless of data size");
-            println!("   - Memory usage: Constant");
-            println!("   - Verification time: Independent of computation steps");
-        }
+    }
+    
+    println!("\n📊 Throughput by blocks per step:");
+    for (blocks_per_step, num_blocks, throughput) in throughputs {
+        println!(
+            "   {:>2} blocks/step, {:>4} blocks: {:.2} bytes/second",
+            blocks_per_step, num_blocks, throughput
+        );
     }
     
     println!("\n🎉 ChaCha20 Folding Integration Complete!");
@@ -0,0 +1,142 @@
+//! ChaCha20 Noir Frontend Integration with HyperNova
+//!
+//! This example mirrors `chacha20_noir_folding.rs`, but folds the ChaCha20 Noir circuit with
+//! HyperNova instead of Nova, showing that the frontend and the folding scheme can be swapped
+//! independently.
+
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+use ark_bn254::{Bn254, Fr, G1Projective as G1};
+use ark_grumpkin::Projective as G2;
+use experimental_frontends::{noir::NoirFCircuit, utils::VecF};
+use folding_schemes::{
+    commitment::{kzg::KZG, pedersen::Pedersen},
+    folding::{hypernova::HyperNova, nova::PreprocessorParam},
+    frontend::FCircuit,
+    transcript::poseidon::poseidon_canonical_config,
+    Error, FoldingScheme,
+};
+use std::{path::Path, time::Instant};
+
+fn main() -> Result<(), Error> {
+    println!("🚀 ChaCha20 Noir Frontend Integration with HyperNova");
+    println!("{}", "=".repeat(60));
+
+    // Step 1: Load the compiled Noir circuit
+    println!("\n📋 Loading Noir ChaCha20 Circuit:");
+    let circuit_path = Path::new("./noir-chacha20-folding/target/chacha20_folding.json");
+
+    if !circuit_path.exists() {
+        eprintln!("❌ Error: Noir circuit not found at {:?}", circuit_path);
+        eprintln!("Please run: cd noir-chacha20-folding && nargo compile");
+        return Ok(());
+    }
+
+    println!("✓ Found compiled Noir circuit: {:?}", circuit_path);
+
+    // Step 2: Initialize NoirFCircuit
+    println!("\n🔧 Initializing Noir Frontend:");
+    let start = Instant::now();
+
+    // External inputs: plaintext_word + step_counter = 2 elements
+    const STATE_LEN: usize = 1;
+    const EXT_INP_LEN: usize = 2;
+    // an error here (instead of a panic) means that the circuit contains ACIR opcodes that can not
+    // be lowered to R1CS/CCS constraints yet
+    let f_circuit = NoirFCircuit::<Fr, STATE_LEN, EXT_INP_LEN>::new(circuit_path.into())?;
+
+    let init_time = start.elapsed();
+    println!("✓ NoirFCircuit initialized in {:?}", init_time);
+
+    // HyperNova folding one running (MU) and one incoming (NU) instance per step
+    const MU: usize = 1;
+    const NU: usize = 1;
+    type HN = HyperNova<
+        G1,
+        G2,
+        NoirFCircuit<Fr, STATE_LEN, EXT_INP_LEN>,
+        KZG<'static, Bn254>,
+        Pedersen<G2>,
+        MU,
+        NU,
+        false,
+    >;
+
+    // Step 3: Setup HyperNova
+    println!("\n⚙️ Setting up HyperNova:");
+    let start = Instant::now();
+
+    let poseidon_config = poseidon_canonical_config::<Fr>();
+    let mut rng = ark_std::test_rng();
+
+    let z_0 = vec![Fr::from(0)];
+
+    let preprocess_params = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+    let params = HN::preprocess(&mut rng, &preprocess_params)?;
+
+    let setup_time = start.elapsed();
+    println!("✓ HyperNova preprocessing completed in {:?}", setup_time);
+
+    // Step 4: Initialize the folding scheme
+    println!("\n🚀 Initializing Folding Scheme:");
+    let start = Instant::now();
+
+    let mut hypernova = HN::init(&params, f_circuit.clone(), z_0.clone())?;
+
+    let hypernova_init_time = start.elapsed();
+    println!("✓ HyperNova initialized in {:?}", hypernova_init_time);
+
+    // Step 5: Perform folding steps
+    println!("\n🔄 Performing Folding Steps:");
+    let num_steps = 10;
+    let start = Instant::now();
+
+    for i in 1..=num_steps {
+        let external_inputs = vec![
+            Fr::from(0x6964614c + (i as u32) * 0x1000), // plaintext_word (varies with step)
+            Fr::from(i as u32),                         // step_counter
+        ];
+
+        hypernova.prove_step(&mut rng, VecF(external_inputs), None)?;
+
+        if i % 5 == 0 || i == num_steps {
+            println!("  ✓ Folding step {}/{} completed", i, num_steps);
+        }
+    }
+
+    let folding_time = start.elapsed();
+    println!(
+        "✓ All {} folding steps completed in {:?}",
+        num_steps, folding_time
+    );
+
+    // Step 6: Verify the computation
+    println!("\n🔍 Verifying Computation:");
+    let start = Instant::now();
+
+    let ivc_proof = hypernova.ivc_proof();
+    HN::verify(params.1, ivc_proof)?;
+
+    let verify_time = start.elapsed();
+    println!("✓ Verification completed in {:?}", verify_time);
+
+    // Step 7: Performance summary
+    println!("\n📊 Performance Summary:");
+    let total_time = init_time + setup_time + hypernova_init_time + folding_time + verify_time;
+    println!("  • Circuit initialization: {:?}", init_time);
+    println!("  • HyperNova preprocessing: {:?}", setup_time);
+    println!("  • HyperNova initialization: {:?}", hypernova_init_time);
+    println!("  • Folding ({} steps): {:?}", num_steps, folding_time);
+    println!("  • Verification: {:?}", verify_time);
+    println!("  • Total time: {:?}", total_time);
+
+    let avg_step_time = folding_time.as_millis() as f64 / num_steps as f64;
+    println!("  • Average time per step: {:.2}ms", avg_step_time);
+
+    println!("\n✅ Noir ChaCha20 + HyperNova Integration Successful!");
+
+    Ok(())
+}
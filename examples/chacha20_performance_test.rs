@@ -7,12 +7,16 @@
 #![allow(non_camel_case_types)]
 #![allow(clippy::upper_case_acronyms)]
 
-use std::{path::Path, time::Instant};
+use std::{path::Path, time::{Duration, Instant}};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use ark_bn254::{Bn254, Fr, G1Projective as G1};
 use ark_grumpkin::Projective as G2;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::ConstraintSystem;
 use experimental_frontends::{
     noir::NoirFCircuit,
-    utils::VecF,
+    utils::{VecF, VecFpVar},
 };
 use folding_schemes::{
     commitment::{kzg::KZG, pedersen::Pedersen},
@@ -25,18 +29,48 @@ use folding_schemes::{
 use ark_groth16::Groth16;
 // Solidity verifiers imports (now enabled with solc available)
 use solidity_verifiers::calldata::{
-    prepare_calldata_for_nova_cyclefold_verifier, NovaVerificationMode,
+    check_calldata_for_nova_cyclefold_verifier, prepare_calldata_for_nova_cyclefold_verifier,
+    NovaVerificationMode,
 };
 use solidity_verifiers::{
-    evm::{compile_solidity, Evm},
+    evm::gas_report,
+    export_foundry_project,
     verifiers::nova_cyclefold::get_decider_template_for_cyclefold_decider,
-    NovaCycleFoldVerifierKey,
+    NovaCycleFoldVerifierKey, SolidityTemplateOptions,
 };
 
 // Circuit configuration constants
 const STATE_LEN: usize = 1;  // ChaCha20 circuit state length
 const EXT_INP_LEN: usize = 2; // External inputs: plaintext_word + step_counter
 
+/// Allocator counting the bytes allocated by the example, to report the memory used by the
+/// clones of the Noir circuit and by the folding steps
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the number of bytes allocated while running `f`
+fn allocated_by<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let res = f();
+    let allocated = ALLOCATED.load(Ordering::Relaxed) - before;
+    drop(res);
+    allocated
+}
+
 type N = Nova<G1, G2, NoirFCircuit<Fr, STATE_LEN, EXT_INP_LEN>, KZG<'static, Bn254>, Pedersen<G2>, false>;
 type D = DeciderEth<
     G1,
@@ -120,6 +154,7 @@ fn main() -> Result<(), Error> {
     // Proving phase - measure individual steps
     println!("🔐 Proving Phase ({} steps)", num_proofs);
     let mut step_times = Vec::new();
+    let allocated_before_proving = ALLOCATED.load(Ordering::Relaxed);
     let total_prove_start = Instant::now();
     
     for i in 0..num_proofs {
@@ -139,8 +174,35 @@ fn main() -> Result<(), Error> {
     }
     
     let total_prove_time = total_prove_start.elapsed();
+    let allocated_by_proving = ALLOCATED.load(Ordering::Relaxed) - allocated_before_proving;
     println!("   Total proving time: {:?}", total_prove_time);
-    println!("   Average time per proof: {:?}\n", total_prove_time / num_proofs);
+    println!("   Average time per proof: {:?}", total_prove_time / num_proofs);
+    println!("   Average memory allocated per proof: {} bytes", allocated_by_proving / num_proofs as usize);
+
+    // The folding scheme clones the NoirFCircuit at each step. Before the circuit was shared
+    // between the clones, each of them copied the ACIR and the Brillig bytecode, while now a
+    // clone only increments reference counts
+    let copied_clone_bytes = allocated_by(|| {
+        ((*f_circuit.circuit).clone(), (*f_circuit.unconstrained_functions).clone())
+    });
+    let shared_clone_bytes = allocated_by(|| f_circuit.clone());
+    println!("   Memory allocated by a clone of the Noir circuit: {} bytes before (copied), {} bytes after (shared)", copied_clone_bytes, shared_clone_bytes);
+
+    // Time the part of each step done by the Noir frontend alone: the ACVM witness solving and
+    // the instantiation of the constraints, while the decoding and lowering of the circuit are
+    // done once when loading it
+    let mut noir_step_time = Duration::ZERO;
+    for i in 0..num_proofs {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_0.clone()))?;
+        let external_inputs = VecFpVar::new_witness(cs.clone(), || {
+            Ok(VecF(vec![Fr::from(0x6964614c + i * 0x1000), Fr::from(i + 1)]))
+        })?;
+        let step_start = Instant::now();
+        f_circuit.generate_step_constraints(cs, i as usize, z_i, external_inputs)?;
+        noir_step_time += step_start.elapsed();
+    }
+    println!("   Average Noir step synthesis time: {:?}\n", noir_step_time / num_proofs);
     
     // Verification phase
     println!("✅ Verification Phase");
@@ -174,8 +236,10 @@ fn main() -> Result<(), Error> {
     
     // Solidity verifier integration (requires solc compiler)
      println!("\n🔗 Solidity Verifier Integration");
-     println!("   Note: This step requires 'solc' (Solidity compiler) to be installed.");
-     println!("   Install with: npm install -g solc");
+     match solidity_verifiers::solc::resolve_solc() {
+         Ok(solc) => println!("   Using solc at {}", solc.display()),
+         Err(e) => println!("   {e}"),
+     }
      
      // Generate calldata for Solidity verifier
      let calldata: Vec<u8> = prepare_calldata_for_nova_cyclefold_verifier(
@@ -190,23 +254,40 @@ fn main() -> Result<(), Error> {
      
      // Prepare the setup params for the solidity verifier
      let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp.clone(), f_circuit.state_len()));
+
+     // Check that the calldata is laid out as the contract expects before deploying it
+     check_calldata_for_nova_cyclefold_verifier(
+         &nova_cyclefold_vk,
+         &SolidityTemplateOptions::default(),
+         NovaVerificationMode::Explicit,
+         &calldata,
+     )?;
      
      // Generate the solidity code
-     let decider_solidity_code = get_decider_template_for_cyclefold_decider(nova_cyclefold_vk);
+     let decider_solidity_code = get_decider_template_for_cyclefold_decider(nova_cyclefold_vk.clone());
      
-     // Verify the proof against the solidity code in the EVM
-     let nova_cyclefold_verifier_bytecode = compile_solidity(&decider_solidity_code, "NovaDecider");
-     let mut evm = Evm::default();
-     let verifier_address = evm.create(nova_cyclefold_verifier_bytecode);
-     let (_, output) = evm.call(verifier_address, calldata.clone());
+     // Verify the proof against the solidity code in the EVM, measuring its gas costs
+     let report = gas_report(&decider_solidity_code, calldata.clone())
+         .map_err(|e| Error::Other(e.to_string()))?;
      
      println!("   ✅ Solidity verifier contract generated");
-     println!("   ✅ EVM verification result: {}", *output.last().unwrap() == 1);
+     println!("   ⛽ EVM gas report:");
+     println!("      deployment gas: {}", report.deployment_gas);
+     println!("      verification gas: {}", report.verification_gas);
+     println!("      calldata size: {} bytes", report.calldata_size);
+     println!("      verified: {}", report.verified);
+     if let Some(reason) = &report.revert_reason {
+         println!("      revert reason: {}", reason);
+     }
      
      // Save smart contract and calldata
      std::fs::write("./NovaDecider.sol", &decider_solidity_code)?;
      std::fs::write("./calldata.txt", hex::encode(&calldata))?;
      println!("   ✅ Saved NovaDecider.sol and calldata.txt");
+
+     // Export a Foundry project with the contract and a test verifying the proof
+     export_foundry_project("./foundry", nova_cyclefold_vk, &calldata)?;
+     println!("   ✅ Exported Foundry project to ./foundry (run `forge test` in it)");
      
      println!("\n📝 Solidity Verifier Integration Status:");
      println!("   1. ✅ Generate Decider proof from Nova folding scheme");
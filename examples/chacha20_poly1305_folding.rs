@@ -0,0 +1,337 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds the ChaCha20-Poly1305 AEAD encryption (RFC 7539 §2.8) of a message of whole
+/// 64-byte blocks: each step encrypts a block and absorbs its ciphertext into the running Poly1305
+/// accumulator, and the step of the last block finalizes the tag into the state.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    uint32::UInt32,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use experimental_frontends::gadgets::chacha20::{chacha20_block_gadget, enforce_fits_u32};
+use experimental_frontends::gadgets::poly1305::{
+    chacha20_poly1305_key_gadget, chacha20_poly1305_native, poly1305_acc_from_fp,
+    poly1305_acc_to_fp, poly1305_block_gadget, poly1305_clamp_gadget, poly1305_tag_gadget,
+    poly1305_words_gadget,
+};
+use experimental_frontends::gadgets::u32ops::{u32_to_fp, xor};
+use experimental_frontends::utils::VecFpVarDyn;
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+use folding_schemes::{Error, FoldingScheme};
+
+/// Parameters of the ChaCha20Poly1305FCircuit
+#[derive(Clone, Debug, Default)]
+pub struct ChaCha20Poly1305Params {
+    /// Number of 64-byte blocks of the message, i.e. of folding steps
+    pub num_blocks: usize,
+    /// Additional authenticated data, which is a constant of the circuit
+    pub aad: Vec<u8>,
+}
+
+/// ChaCha20-Poly1305 Folding Circuit, encrypting and authenticating a block of the message per
+/// folding step
+/// State: [key (8 words), nonce (3 words), counter, acc, tag (4 words), ciphertext (16 words)],
+/// i.e. 33 field elements, where `acc` is the Poly1305 accumulator as a single field element
+/// External inputs: the 16 words of plaintext of the step
+///
+/// The one-time Poly1305 key is the ChaCha20 block of counter 0, which is derived again at every
+/// step, so that the step circuit is the same for all the blocks. The step of counter 1 starts
+/// from the accumulator of the additional data instead of the one of the state, and the step of
+/// counter `num_blocks` writes the tag of the message into the state, which stays zero before.
+/// The initial state must thus have the counter 1.
+#[derive(Clone, Debug)]
+pub struct ChaCha20Poly1305FCircuit<F: PrimeField> {
+    num_blocks: usize,
+    aad: Vec<u8>,
+    _f: core::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> FCircuit<F> for ChaCha20Poly1305FCircuit<F> {
+    type Params = ChaCha20Poly1305Params;
+    type ExternalInputs = Vec<F>; // plaintext block (16 words)
+    type ExternalInputsVar = VecFpVarDyn<F>;
+    type WitnessHint = ();
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if params.num_blocks == 0 {
+            return Err(Error::NotExpectedLength(0, 1));
+        }
+        Ok(Self {
+            num_blocks: params.num_blocks,
+            aad: params.aad,
+            _f: core::marker::PhantomData,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        // key(8) + nonce(3) + counter(1) + acc(1) + tag(4) + ciphertext(16)
+        33
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let plaintext = external_inputs.allocate(16)?;
+        let words = z_i[0..12]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let key: [UInt32<F>; 8] = core::array::from_fn(|j| words[j].clone());
+        let nonce: [UInt32<F>; 3] = core::array::from_fn(|j| words[8 + j].clone());
+        let counter = &words[11];
+
+        let one_time_key = chacha20_poly1305_key_gadget(cs.clone(), &key, &nonce)?;
+        let r = poly1305_clamp_gadget(&core::array::from_fn(|j| one_time_key[j].clone()))?;
+        let s = core::array::from_fn(|j| one_time_key[4 + j].clone());
+
+        // the first block starts from the accumulator of the zero-padded additional data
+        let mut aad_acc = poly1305_acc_from_fp(&FpVar::zero())?;
+        for chunk in self.aad.chunks(16) {
+            let mut block = chunk.to_vec();
+            block.resize(16, 0);
+            let bits: Vec<Boolean<F>> = block
+                .iter()
+                .flat_map(|byte| (0..8).map(move |i| Boolean::constant((byte >> i) & 1 == 1)))
+                .collect();
+            aad_acc = poly1305_block_gadget(&aad_acc, &r, &bits)?;
+        }
+        let is_first = z_i[11].is_eq(&FpVar::one())?;
+        let acc = is_first.select(&poly1305_acc_to_fp(&aad_acc), &z_i[12])?;
+        let acc = poly1305_acc_from_fp(&acc)?;
+
+        let keystream = chacha20_block_gadget(cs.clone(), &key, &nonce, counter)?;
+        let ciphertext = plaintext
+            .iter()
+            .zip(&keystream)
+            .map(|(word, keystream_word)| xor(&enforce_fits_u32(word)?, keystream_word))
+            .collect::<Result<Vec<_>, _>>()?;
+        let acc = poly1305_words_gadget(&acc, &r, &ciphertext)?;
+
+        // the last block also absorbs the lengths of the additional data and of the ciphertext,
+        // and computes the tag
+        let ciphertext_len = 64 * self.num_blocks as u64;
+        let lengths = [
+            self.aad.len() as u32,
+            (self.aad.len() as u64 >> 32) as u32,
+            ciphertext_len as u32,
+            (ciphertext_len >> 32) as u32,
+        ]
+        .map(UInt32::constant);
+        let tag = poly1305_tag_gadget(&poly1305_words_gadget(&acc, &r, &lengths)?, &s)?;
+        let is_last = z_i[11].is_eq(&FpVar::constant(F::from(self.num_blocks as u64)))?;
+
+        let mut next_state = z_i[0..11].to_vec();
+        next_state.push(u32_to_fp(&counter.wrapping_add(&UInt32::constant(1)))?);
+        next_state.push(poly1305_acc_to_fp(&acc));
+        for (word, previous) in tag.iter().zip(&z_i[13..17]) {
+            next_state.push(is_last.select(&u32_to_fp(word)?, previous)?);
+        }
+        for word in &ciphertext {
+            next_state.push(u32_to_fp(word)?);
+        }
+        Ok(next_state)
+    }
+}
+
+/// Little-endian words of `bytes`, whose length must be a multiple of 4
+pub fn bytes_to_words(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// Initial state of the ChaCha20Poly1305FCircuit for the given key and nonce
+pub fn initial_state<F: PrimeField>(key: [u32; 8], nonce: [u32; 3]) -> Vec<F> {
+    [key.to_vec(), nonce.to_vec(), vec![1]]
+        .concat()
+        .into_iter()
+        .map(F::from)
+        .chain(vec![F::zero(); 1 + 4 + 16])
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use folding_schemes::frontend::utils::simulate_steps;
+
+    // key, nonce and additional data of the test vector of RFC 7539 2.8.2
+    const KEY: [u32; 8] = [
+        0x83828180, 0x87868584, 0x8b8a8988, 0x8f8e8d8c, 0x93929190, 0x97969594, 0x9b9a9998,
+        0x9f9e9d9c,
+    ];
+    const NONCE: [u32; 3] = [0x00000007, 0x43424140, 0x47464544];
+    const AAD: [u8; 12] = [
+        0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7,
+    ];
+
+    fn plaintext(num_blocks: usize) -> Vec<u8> {
+        (0..64 * num_blocks).map(|i| (i * 7 + 3) as u8).collect()
+    }
+
+    /// The final state holds the tag of ChaCha20-Poly1305, and the ciphertext of each block is
+    /// output by its step
+    #[test]
+    fn test_chacha20_poly1305_f_circuit() -> Result<(), Error> {
+        for (num_blocks, aad) in [(1, AAD.to_vec()), (3, AAD.to_vec()), (2, vec![])] {
+            let circuit = ChaCha20Poly1305FCircuit::<Fr>::new(ChaCha20Poly1305Params {
+                num_blocks,
+                aad: aad.clone(),
+            })?;
+            let plaintext = plaintext(num_blocks);
+            let (ciphertext, tag) = chacha20_poly1305_native(KEY, NONCE, &aad, &plaintext);
+            let ciphertext = bytes_to_words(&ciphertext);
+
+            let inputs = bytes_to_words(&plaintext)
+                .chunks(16)
+                .map(|words| words.iter().map(|&w| Fr::from(w)).collect())
+                .collect::<Vec<_>>();
+            let states =
+                simulate_steps(&circuit, initial_state(KEY, NONCE), inputs)?.into_states()?;
+            for (step, state) in states[1..].iter().enumerate() {
+                assert_eq!(state[11], Fr::from(step as u32 + 2));
+                let expected_tag = if step + 1 == num_blocks {
+                    tag.map(Fr::from)
+                } else {
+                    [Fr::from(0u32); 4]
+                };
+                assert_eq!(state[13..17], expected_tag);
+                let expected_ciphertext: Vec<Fr> = ciphertext[16 * step..16 * (step + 1)]
+                    .iter()
+                    .map(|&w| Fr::from(w))
+                    .collect();
+                assert_eq!(state[17..], expected_ciphertext);
+            }
+        }
+
+        assert!(matches!(
+            ChaCha20Poly1305FCircuit::<Fr>::new(ChaCha20Poly1305Params::default()),
+            Err(Error::NotExpectedLength(0, 1))
+        ));
+        Ok(())
+    }
+
+    /// Fold the encryption of a multi-block message with Nova, and check the tag of the final
+    /// state
+    #[test]
+    fn test_chacha20_poly1305_folding() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            ChaCha20Poly1305FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let num_blocks = 3;
+        let circuit = ChaCha20Poly1305FCircuit::<Fr>::new(ChaCha20Poly1305Params {
+            num_blocks,
+            aad: AAD.to_vec(),
+        })?;
+        let plaintext = plaintext(num_blocks);
+        let (ciphertext, tag) = chacha20_poly1305_native(KEY, NONCE, &AAD, &plaintext);
+
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit.clone());
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, circuit, initial_state(KEY, NONCE))?;
+        for block in bytes_to_words(&plaintext).chunks(16) {
+            nova.prove_step(&mut rng, block.iter().map(|&w| Fr::from(w)).collect(), None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+
+        let state = nova.state();
+        assert_eq!(state[11], Fr::from(num_blocks as u32 + 1));
+        assert_eq!(state[13..17], tag.map(Fr::from));
+        let last_ciphertext: Vec<Fr> = bytes_to_words(&ciphertext[64 * (num_blocks - 1)..])
+            .into_iter()
+            .map(Fr::from)
+            .collect();
+        assert_eq!(state[17..], last_ciphertext);
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    type N = Nova<
+        Projective,
+        Projective2,
+        ChaCha20Poly1305FCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    // key, nonce and additional data of the test vector of RFC 7539 2.8.2
+    let key = [
+        0x83828180, 0x87868584, 0x8b8a8988, 0x8f8e8d8c, 0x93929190, 0x97969594, 0x9b9a9998,
+        0x9f9e9d9c,
+    ];
+    let nonce = [0x00000007, 0x43424140, 0x47464544];
+    let aad = vec![
+        0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7,
+    ];
+    let num_blocks = 4;
+    let plaintext: Vec<u8> = (0..64 * num_blocks).map(|i| i as u8).collect();
+
+    let F_circuit = ChaCha20Poly1305FCircuit::<Fr>::new(ChaCha20Poly1305Params {
+        num_blocks,
+        aad: aad.clone(),
+    })?;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(initial_state::<Fr>(key, nonce)))?;
+    let block = VecFpVarDyn::new_witness(cs.clone(), || Ok(vec![Fr::from(0u32); 16]))?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, block)?;
+    println!("Step circuit: {} constraints", cs.num_constraints());
+
+    let mut rng = rand::rngs::OsRng;
+    let start = Instant::now();
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit.clone());
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    println!("Nova preprocess, {:?}", start.elapsed());
+
+    let mut folding_scheme = N::init(&nova_params, F_circuit, initial_state(key, nonce))?;
+    for (i, block) in bytes_to_words(&plaintext).chunks(16).enumerate() {
+        let start = Instant::now();
+        folding_scheme.prove_step(rng, block.iter().map(|&w| Fr::from(w)).collect(), None)?;
+        println!("Nova::prove_step {}: {:?}", i, start.elapsed());
+    }
+
+    let start = Instant::now();
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+    println!("Nova::verify: {:?}", start.elapsed());
+
+    let (_, tag) = chacha20_poly1305_native(key, nonce, &aad, &plaintext);
+    assert_eq!(folding_scheme.state()[13..17], tag.map(Fr::from));
+    println!(
+        "Tag of the {} bytes of ciphertext: {}",
+        64 * num_blocks,
+        tag.iter()
+            .flat_map(|word| word.to_le_bytes())
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    );
+    Ok(())
+}
@@ -0,0 +1,284 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds the verification of a stream of secp256k1 ECDSA signatures, one signature
+/// per step: each step verifies the signature of a message hash under a public key with emulated
+/// secp256k1 arithmetic, and absorbs the message hash into a running digest, so that the final
+/// state commits to the sequence of the message hashes whose signatures were verified.
+///
+/// The public keys are not absorbed into the digest, so the signers of the messages are not bound
+/// by the final state.
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    fields::{emulated_fp::EmulatedFpVar, fp::FpVar},
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, Namespace, SynthesisError};
+use ark_secp256k1::{Affine, Fr as Secp256k1Fr};
+use ark_std::{borrow::Borrow, UniformRand};
+use core::marker::PhantomData;
+use sha3::{Digest, Keccak256};
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use experimental_frontends::gadgets::ecdsa::{
+    ecdsa_msg_hash, ecdsa_sign_native, ecdsa_verify_gadget, Secp256k1AffineVar,
+};
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::{
+    poseidon_canonical_config, poseidon_hash, poseidon_hash_gadget,
+};
+use folding_schemes::{Error, FoldingScheme};
+
+/// A signed message hash: the external inputs of a step of the [`EcdsaFCircuit`].
+#[derive(Clone, Debug)]
+pub struct EcdsaInputs {
+    pub pubkey: Affine,
+    pub r: Secp256k1Fr,
+    pub s: Secp256k1Fr,
+    pub msg_hash: Secp256k1Fr,
+}
+
+impl Default for EcdsaInputs {
+    fn default() -> Self {
+        Self {
+            pubkey: Affine::generator(),
+            r: Secp256k1Fr::zero(),
+            s: Secp256k1Fr::zero(),
+            msg_hash: Secp256k1Fr::zero(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EcdsaInputsVar<F: PrimeField> {
+    pub pubkey: Secp256k1AffineVar<F>,
+    pub r: EmulatedFpVar<Secp256k1Fr, F>,
+    pub s: EmulatedFpVar<Secp256k1Fr, F>,
+    pub msg_hash: EmulatedFpVar<Secp256k1Fr, F>,
+}
+
+impl<F: PrimeField> AllocVar<EcdsaInputs, F> for EcdsaInputsVar<F> {
+    fn new_variable<T: Borrow<EcdsaInputs>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let val = val.borrow();
+            Ok(Self {
+                pubkey: Secp256k1AffineVar::new_variable(cs.clone(), || Ok(val.pubkey), mode)?,
+                r: EmulatedFpVar::new_variable(cs.clone(), || Ok(val.r), mode)?,
+                s: EmulatedFpVar::new_variable(cs.clone(), || Ok(val.s), mode)?,
+                msg_hash: EmulatedFpVar::new_variable(cs.clone(), || Ok(val.msg_hash), mode)?,
+            })
+        })
+    }
+}
+
+/// EcdsaFCircuit verifies one secp256k1 ECDSA signature per step.
+/// State: [digest], the Poseidon hash chain of the message hashes of the verified signatures
+/// External inputs: the [`EcdsaInputs`] of the signature
+#[derive(Clone, Copy, Debug)]
+pub struct EcdsaFCircuit<F: PrimeField> {
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> FCircuit<F> for EcdsaFCircuit<F> {
+    type Params = ();
+    type ExternalInputs = EcdsaInputs;
+    type ExternalInputsVar = EcdsaInputsVar<F>;
+    type WitnessHint = ();
+
+    fn new(_params: Self::Params) -> Result<Self, Error> {
+        Ok(Self { _f: PhantomData })
+    }
+
+    fn state_len(&self) -> usize {
+        1
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let EcdsaInputsVar {
+            pubkey,
+            r,
+            s,
+            msg_hash,
+        } = external_inputs;
+        ecdsa_verify_gadget(&pubkey, &msg_hash, &r, &s)?;
+
+        // the 256 bits of the message hash are absorbed as two 128-bit halves
+        let bits = msg_hash.to_bits_le()?;
+        let hash_input = [
+            z_i[0].clone(),
+            Boolean::le_bits_to_fp(&bits[..128])?,
+            Boolean::le_bits_to_fp(&bits[128..])?,
+        ];
+        Ok(vec![poseidon_hash_gadget(
+            cs,
+            &poseidon_canonical_config::<F>(),
+            &hash_input,
+        )?])
+    }
+}
+
+/// Native counterpart of the digest of the EcdsaFCircuit, after the steps of the given message
+/// hashes.
+pub fn msg_hash_digest<F: PrimeField + Absorb>(msg_hashes: &[Secp256k1Fr]) -> Result<F, Error> {
+    let poseidon_config = poseidon_canonical_config::<F>();
+    msg_hashes.iter().try_fold(F::zero(), |digest, msg_hash| {
+        let bytes = msg_hash.into_bigint().to_bytes_le();
+        let hash_input = [
+            digest,
+            F::from_le_bytes_mod_order(&bytes[..16]),
+            F::from_le_bytes_mod_order(&bytes[16..]),
+        ];
+        poseidon_hash(&poseidon_config, &hash_input)
+    })
+}
+
+/// Signs the Keccak-256 hashes of the given messages, each one with a fresh random key.
+pub fn sign_messages<M: AsRef<[u8]>>(msgs: &[M]) -> Vec<EcdsaInputs> {
+    let mut rng = ark_std::test_rng();
+    msgs.iter()
+        .map(|msg| {
+            let sk = Secp256k1Fr::rand(&mut rng);
+            let msg_hash = ecdsa_msg_hash(&Keccak256::digest(msg.as_ref()));
+            // the nonce gives `r = 0` or `s = 0` with negligible probability
+            let (r, s) = ecdsa_sign_native(sk, msg_hash, Secp256k1Fr::rand(&mut rng)).unwrap();
+            EcdsaInputs {
+                pubkey: (Affine::generator() * sk).into_affine(),
+                r,
+                s,
+                msg_hash,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_ff::One;
+    use folding_schemes::frontend::utils::{simulate_steps, SimulatedSteps};
+
+    #[test]
+    fn test_ecdsa_f_circuit() -> Result<(), Error> {
+        let circuit = EcdsaFCircuit::<Fr>::new(())?;
+        let inputs = sign_messages(&["first message", "second message"]);
+        let msg_hashes: Vec<_> = inputs.iter().map(|input| input.msg_hash).collect();
+
+        let states = simulate_steps(&circuit, vec![Fr::zero()], inputs.clone())?.into_states()?;
+        assert_eq!(states[2], vec![msg_hash_digest::<Fr>(&msg_hashes)?]);
+
+        // a wrong signature does not satisfy the step circuit
+        let mut wrong_signature = inputs[0].clone();
+        wrong_signature.s += Secp256k1Fr::one();
+        assert!(
+            simulate_steps(&circuit, vec![Fr::zero()], [wrong_signature])
+                .and_then(SimulatedSteps::into_states)
+                .is_err()
+        );
+        // and neither does a valid signature of another message
+        let mut wrong_msg = inputs[0].clone();
+        wrong_msg.msg_hash = inputs[1].msg_hash;
+        assert!(simulate_steps(&circuit, vec![Fr::zero()], [wrong_msg])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        Ok(())
+    }
+
+    /// Fold the verification of two signatures with Nova
+    #[test]
+    #[ignore]
+    fn test_ecdsa_folding() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            EcdsaFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let circuit = EcdsaFCircuit::<Fr>::new(())?;
+        let inputs = sign_messages(&["first message", "second message"]);
+        let msg_hashes: Vec<_> = inputs.iter().map(|input| input.msg_hash).collect();
+
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, circuit, vec![Fr::zero()])?;
+        for input in inputs {
+            nova.prove_step(&mut rng, input, None)?;
+        }
+        N::verify(params.1, nova.ivc_proof())?;
+        assert_eq!(nova.state(), vec![msg_hash_digest::<Fr>(&msg_hashes)?]);
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    type N = Nova<
+        Projective,
+        Projective2,
+        EcdsaFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    let msgs: Vec<String> = (0..4).map(|i| format!("transfer {} tokens", i)).collect();
+    let inputs = sign_messages(&msgs);
+    let msg_hashes: Vec<_> = inputs.iter().map(|input| input.msg_hash).collect();
+
+    let F_circuit = EcdsaFCircuit::<Fr>::new(())?;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::zero()]))?;
+    let input = EcdsaInputsVar::new_witness(cs.clone(), || Ok(inputs[0].clone()))?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, input)?;
+    println!(
+        "Step circuit: {} constraints, of which the ECDSA verification is the bulk",
+        cs.num_constraints()
+    );
+
+    let mut rng = rand::rngs::OsRng;
+    let start = Instant::now();
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    println!("Nova preprocess, {:?}", start.elapsed());
+
+    let mut folding_scheme = N::init(&nova_params, F_circuit, vec![Fr::zero()])?;
+    for (i, input) in inputs.into_iter().enumerate() {
+        let start = Instant::now();
+        folding_scheme.prove_step(rng, input, None)?;
+        println!("Nova::prove_step {}: {:?}", i, start.elapsed());
+    }
+
+    let start = Instant::now();
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+    println!("Nova::verify: {:?}", start.elapsed());
+    assert_eq!(
+        folding_scheme.state(),
+        vec![msg_hash_digest::<Fr>(&msg_hashes)?]
+    );
+    Ok(())
+}
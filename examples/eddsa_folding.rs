@@ -0,0 +1,250 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds the verification of a stream of EdDSA signatures over Baby Jubjub, the
+/// curve embedded in BN254, one signature per step: each step verifies the signature of a message
+/// under a public key, and absorbs the message into a running digest, so that the final state
+/// commits to the sequence of the messages whose signatures were verified.
+///
+/// As the arithmetic of Baby Jubjub is native to the scalar field of BN254, a step costs a few
+/// thousand constraints, against millions for the ECDSA signatures of the `ecdsa_folding` example.
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ed_on_bn254::{constraints::EdwardsVar, EdwardsAffine, Fr as BabyJubjubFr};
+use ark_ff::{UniformRand, Zero};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    fields::fp::FpVar,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, rand::Rng};
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use experimental_frontends::gadgets::eddsa::{
+    eddsa_keygen, eddsa_sign_native, eddsa_verify_gadget, scalar_to_base,
+};
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::{
+    poseidon_canonical_config, poseidon_hash, poseidon_hash_gadget,
+};
+use folding_schemes::{Error, FoldingScheme};
+
+/// A signed message: the external inputs of a step of the [`EddsaFCircuit`].
+#[derive(Clone, Debug)]
+pub struct EddsaInputs {
+    pub pk: EdwardsAffine,
+    pub r: EdwardsAffine,
+    pub s: BabyJubjubFr,
+    pub msg: Fr,
+}
+
+impl Default for EddsaInputs {
+    fn default() -> Self {
+        Self {
+            pk: EdwardsAffine::generator(),
+            r: EdwardsAffine::generator(),
+            s: BabyJubjubFr::zero(),
+            msg: Fr::zero(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EddsaInputsVar {
+    pub pk: EdwardsVar,
+    pub r: EdwardsVar,
+    pub s: FpVar<Fr>,
+    pub msg: FpVar<Fr>,
+}
+
+impl AllocVar<EddsaInputs, Fr> for EddsaInputsVar {
+    fn new_variable<T: Borrow<EddsaInputs>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let val = val.borrow();
+            Ok(Self {
+                pk: EdwardsVar::new_variable(cs.clone(), || Ok(val.pk.into_group()), mode)?,
+                r: EdwardsVar::new_variable(cs.clone(), || Ok(val.r.into_group()), mode)?,
+                s: FpVar::new_variable(cs.clone(), || Ok(scalar_to_base(val.s)), mode)?,
+                msg: FpVar::new_variable(cs.clone(), || Ok(val.msg), mode)?,
+            })
+        })
+    }
+}
+
+/// EddsaFCircuit verifies one Baby Jubjub EdDSA signature per step.
+/// State: [digest], the Poseidon hash chain of the messages of the verified signatures
+/// External inputs: the [`EddsaInputs`] of the signature
+#[derive(Clone, Debug)]
+pub struct EddsaFCircuit {
+    poseidon_config: PoseidonConfig<Fr>,
+}
+
+impl FCircuit<Fr> for EddsaFCircuit {
+    type Params = ();
+    type ExternalInputs = EddsaInputs;
+    type ExternalInputsVar = EddsaInputsVar;
+    type WitnessHint = ();
+
+    fn new(_params: Self::Params) -> Result<Self, Error> {
+        Ok(Self {
+            poseidon_config: poseidon_canonical_config::<Fr>(),
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        1
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<Fr>,
+        _i: usize,
+        z_i: Vec<FpVar<Fr>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+        let EddsaInputsVar { pk, r, s, msg } = external_inputs;
+        eddsa_verify_gadget(cs.clone(), &self.poseidon_config, &pk, &msg, &r, &s)?;
+        Ok(vec![poseidon_hash_gadget(
+            cs,
+            &self.poseidon_config,
+            &[z_i[0].clone(), msg],
+        )?])
+    }
+}
+
+/// Native counterpart of the digest of the EddsaFCircuit, after the steps of the given messages.
+pub fn msg_digest(msgs: &[Fr]) -> Result<Fr, Error> {
+    let poseidon_config = poseidon_canonical_config::<Fr>();
+    msgs.iter().try_fold(Fr::zero(), |digest, &msg| {
+        poseidon_hash(&poseidon_config, &[digest, msg])
+    })
+}
+
+/// Signs each of the given messages with a fresh random key.
+pub fn sign_messages<R: Rng>(rng: &mut R, msgs: &[Fr]) -> Result<Vec<EddsaInputs>, Error> {
+    let poseidon_config = poseidon_canonical_config::<Fr>();
+    msgs.iter()
+        .map(|&msg| {
+            let (sk, pk) = eddsa_keygen(rng);
+            let (r, s) = eddsa_sign_native(&poseidon_config, sk, msg)?;
+            Ok(EddsaInputs { pk, r, s, msg })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_ff::One;
+    use folding_schemes::frontend::utils::{simulate_steps, SimulatedSteps};
+
+    #[test]
+    fn test_eddsa_f_circuit() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let circuit = EddsaFCircuit::new(())?;
+        let msgs: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        let inputs = sign_messages(&mut rng, &msgs)?;
+
+        let states = simulate_steps(&circuit, vec![Fr::zero()], inputs.clone())?.into_states()?;
+        assert_eq!(states[3], vec![msg_digest(&msgs)?]);
+
+        // a forged signature does not satisfy the step circuit
+        let mut forged = inputs[0].clone();
+        forged.s += BabyJubjubFr::one();
+        assert!(simulate_steps(&circuit, vec![Fr::zero()], [forged])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        // and neither does the signature of a message under the key of another one
+        let mut wrong_pk = inputs[0].clone();
+        wrong_pk.pk = inputs[1].pk;
+        assert!(simulate_steps(&circuit, vec![Fr::zero()], [wrong_pk])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_eddsa_folding() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            EddsaFCircuit,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let circuit = EddsaFCircuit::new(())?;
+        let msgs: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, circuit, vec![Fr::zero()])?;
+        for input in sign_messages(&mut rng, &msgs)? {
+            nova.prove_step(&mut rng, input, None)?;
+        }
+        N::verify(params.1, nova.ivc_proof())?;
+        assert_eq!(nova.state(), vec![msg_digest(&msgs)?]);
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    type N = Nova<
+        Projective,
+        Projective2,
+        EddsaFCircuit,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    let num_signatures = 100;
+    let mut rng = rand::rngs::OsRng;
+    let msgs: Vec<Fr> = (0..num_signatures).map(|_| Fr::rand(&mut rng)).collect();
+    let inputs = sign_messages(&mut rng, &msgs)?;
+
+    let F_circuit = EddsaFCircuit::new(())?;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::zero()]))?;
+    let input = EddsaInputsVar::new_witness(cs.clone(), || Ok(inputs[0].clone()))?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, input)?;
+    println!("Step circuit: {} constraints", cs.num_constraints());
+
+    let start = Instant::now();
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit.clone());
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    println!("Nova preprocess, {:?}", start.elapsed());
+
+    let mut folding_scheme = N::init(&nova_params, F_circuit, vec![Fr::zero()])?;
+    let start = Instant::now();
+    for input in inputs {
+        folding_scheme.prove_step(rng, input, None)?;
+    }
+    println!(
+        "Nova::prove_step x {}: {:?} ({:?} per step)",
+        num_signatures,
+        start.elapsed(),
+        start.elapsed() / num_signatures
+    );
+
+    let start = Instant::now();
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+    println!("Nova::verify: {:?}", start.elapsed());
+    assert_eq!(folding_scheme.state(), vec![msg_digest(&msgs)?]);
+    Ok(())
+}
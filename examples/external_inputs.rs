@@ -76,6 +76,7 @@ where
     type Params = PoseidonConfig<F>;
     type ExternalInputs = [F; 1];
     type ExternalInputsVar = [FpVar<F>; 1];
+    type WitnessHint = ();
 
     fn new(params: Self::Params) -> Result<Self, Error> {
         Ok(Self {
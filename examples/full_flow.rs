@@ -47,6 +47,7 @@ impl<F: PrimeField> FCircuit<F> for CubicFCircuit<F> {
     type Params = ();
     type ExternalInputs = ();
     type ExternalInputsVar = ();
+    type WitnessHint = ();
 
     fn new(_params: Self::Params) -> Result<Self, Error> {
         Ok(Self { _f: PhantomData })
@@ -134,7 +135,8 @@ fn main() -> Result<(), Error> {
     let decider_solidity_code = get_decider_template_for_cyclefold_decider(nova_cyclefold_vk);
 
     // verify the proof against the solidity code in the EVM
-    let nova_cyclefold_verifier_bytecode = compile_solidity(&decider_solidity_code, "NovaDecider");
+    let nova_cyclefold_verifier_bytecode =
+        compile_solidity(&decider_solidity_code, "NovaDecider").unwrap();
     let mut evm = Evm::default();
     let verifier_address = evm.create(nova_cyclefold_verifier_bytecode);
     let (_, output) = evm.call(verifier_address, calldata.clone());
@@ -0,0 +1,367 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example uses folding to aggregate Groth16 proofs: each step verifies one Groth16 proof
+/// in-circuit, so that the final IVC proof (and the decider proof that can be generated from it)
+/// attests to the validity of all the proofs at once.
+///
+/// Verifying a pairing in-circuit is only practical when the base field of the pairing-friendly
+/// curve of the proofs is the native field of the step circuit. We use the MNT4-298/MNT6-298
+/// cycle: the proofs are over MNT4-298, and the folding is done with Nova over MNT6-298, whose
+/// scalar field is the base field of MNT4-298, with MNT4-298 as the CycleFold curve.
+///
+/// Note: do not use the MNTx_298 curves in practice, these are just for the example. Use the
+/// MNTx_753 curves instead.
+use ark_crypto_primitives::snark::constraints::{BooleanInputVar, SNARKGadget};
+use ark_ff::{BigInt, PrimeField, Zero};
+use ark_groth16::{
+    constraints::{Groth16VerifierGadget, ProofVar, VerifyingKeyVar},
+    Groth16, Proof, ProvingKey, VerifyingKey,
+};
+use ark_mnt4_298::{
+    constraints::PairingVar, Fq, Fr as InnerFr, G1Projective as Projective2, MNT4_298,
+};
+use ark_mnt6_298::G1Projective as Projective;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, Namespace, SynthesisError,
+};
+use ark_snark::SNARK;
+use ark_std::{
+    borrow::Borrow,
+    fmt::Debug,
+    rand::{CryptoRng, RngCore},
+    UniformRand,
+};
+use std::time::Instant;
+
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::{
+    poseidon_canonical_config, poseidon_hash, poseidon_hash_gadget,
+};
+use folding_schemes::{Error, FoldingScheme};
+
+type InnerSNARK = Groth16<MNT4_298>;
+type VerifierGadget = Groth16VerifierGadget<MNT4_298, PairingVar>;
+
+/// Converts a public input of the proofs to an element of the field of the step circuit, or
+/// returns `Error::OutOfBounds` if it does not fit in it: the scalar field of MNT4-298 is larger
+/// than its base field, so that the proofs with a public input in `[q, r)` can not be aggregated.
+fn inner_to_outer(x: InnerFr) -> Result<Fq, Error> {
+    Fq::from_bigint(BigInt(x.into_bigint().0)).ok_or(Error::OutOfBounds)
+}
+
+/// A Groth16 proof and its `N` public inputs: the external inputs of a step of the
+/// [`Groth16FCircuit`], which rejects the public inputs that do not fit in its field (see
+/// [`inner_to_outer`]).
+#[derive(Clone, Debug)]
+pub struct Groth16Inputs<const N: usize> {
+    pub proof: Proof<MNT4_298>,
+    pub public_inputs: [InnerFr; N],
+}
+
+impl<const N: usize> Default for Groth16Inputs<N> {
+    fn default() -> Self {
+        Self {
+            proof: Proof::default(),
+            public_inputs: [InnerFr::zero(); N],
+        }
+    }
+}
+
+/// In-circuit counterpart of [`Groth16Inputs`], where the public inputs are allocated as elements
+/// of the field of the step circuit.
+#[derive(Clone)]
+pub struct Groth16InputsVar<const N: usize> {
+    pub proof: ProofVar<MNT4_298, PairingVar>,
+    pub public_inputs: [FpVar<Fq>; N],
+}
+
+impl<const N: usize> Debug for Groth16InputsVar<N> {
+    fn fmt(&self, f: &mut ark_std::fmt::Formatter<'_>) -> ark_std::fmt::Result {
+        f.debug_struct("Groth16InputsVar")
+            .field("public_inputs", &self.public_inputs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize> AllocVar<Groth16Inputs<N>, Fq> for Groth16InputsVar<N> {
+    fn new_variable<T: Borrow<Groth16Inputs<N>>>(
+        cs: impl Into<Namespace<Fq>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let val = val.borrow();
+            let proof = ProofVar::new_variable(cs.clone(), || Ok(val.proof.clone()), mode)?;
+            let public_inputs = Vec::<FpVar<Fq>>::new_variable(
+                cs.clone(),
+                || {
+                    val.public_inputs
+                        .iter()
+                        .map(|&x| inner_to_outer(x).map_err(|_| SynthesisError::Unsatisfiable))
+                        .collect::<Result<Vec<_>, _>>()
+                },
+                mode,
+            )?;
+            Ok(Self {
+                proof,
+                public_inputs: public_inputs
+                    .try_into()
+                    .map_err(|_| SynthesisError::Unsatisfiable)?,
+            })
+        })
+    }
+}
+
+/// Groth16FCircuit verifies one Groth16 proof over MNT4-298 with `N` public inputs per step,
+/// against the verifying key given as parameter.
+/// State: [count, digest], the number of verified proofs and the Poseidon hash chain of their
+/// public inputs
+/// External inputs: the [`Groth16Inputs`] of the proof
+#[derive(Clone, Debug)]
+pub struct Groth16FCircuit<const N: usize> {
+    vk: VerifyingKey<MNT4_298>,
+}
+
+impl<const N: usize> FCircuit<Fq> for Groth16FCircuit<N> {
+    /// the verifying key of the aggregated proofs
+    type Params = VerifyingKey<MNT4_298>;
+    type ExternalInputs = Groth16Inputs<N>;
+    type ExternalInputsVar = Groth16InputsVar<N>;
+    type WitnessHint = ();
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if params.gamma_abc_g1.len() != N + 1 {
+            return Err(Error::NotExpectedLength(params.gamma_abc_g1.len() - 1, N));
+        }
+        Ok(Self { vk: params })
+    }
+
+    fn state_len(&self) -> usize {
+        2
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<Fq>,
+        _i: usize,
+        z_i: Vec<FpVar<Fq>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<Fq>>, SynthesisError> {
+        let vk = VerifyingKeyVar::<MNT4_298, PairingVar>::new_constant(cs.clone(), &self.vk)?;
+
+        // the public inputs are elements of the field of the circuit, which is smaller than the
+        // scalar field of MNT4-298, so that their canonical bits are the ones of public inputs of
+        // the proofs
+        let input_bits = external_inputs
+            .public_inputs
+            .iter()
+            .map(|x| x.to_bits_le())
+            .collect::<Result<Vec<_>, _>>()?;
+        <VerifierGadget as SNARKGadget<InnerFr, Fq, InnerSNARK>>::verify(
+            &vk,
+            &BooleanInputVar::new(input_bits),
+            &external_inputs.proof,
+        )?
+        .enforce_equal(&Boolean::TRUE)?;
+
+        let mut hash_input = vec![z_i[1].clone()];
+        hash_input.extend(external_inputs.public_inputs);
+        let digest = poseidon_hash_gadget(cs, &poseidon_canonical_config::<Fq>(), &hash_input)?;
+        Ok(vec![&z_i[0] + FpVar::one(), digest])
+    }
+}
+
+/// Native counterpart of the state of the Groth16FCircuit, after the steps of the proofs of the
+/// given public inputs.
+pub fn aggregated_state<const N: usize>(public_inputs: &[[InnerFr; N]]) -> Result<Vec<Fq>, Error> {
+    let poseidon_config = poseidon_canonical_config::<Fq>();
+    let digest = public_inputs
+        .iter()
+        .try_fold(Fq::zero(), |digest, inputs| {
+            let mut hash_input = vec![digest];
+            for &x in inputs {
+                hash_input.push(inner_to_outer(x)?);
+            }
+            poseidon_hash(&poseidon_config, &hash_input)
+        })?;
+    Ok(vec![Fq::from(public_inputs.len() as u64), digest])
+}
+
+/// The circuit of the aggregated proofs: knowledge of `x` such that `x^3 + x + 5 = y`, for the
+/// public input `y`.
+#[derive(Clone, Copy, Debug)]
+pub struct CubicCircuit {
+    pub x: Option<InnerFr>,
+}
+
+impl CubicCircuit {
+    pub fn y(x: InnerFr) -> InnerFr {
+        x * x * x + x + InnerFr::from(5u32)
+    }
+}
+
+impl ConstraintSynthesizer<InnerFr> for CubicCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<InnerFr>) -> Result<(), SynthesisError> {
+        let x = FpVar::new_witness(cs.clone(), || {
+            self.x.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let y = FpVar::new_input(cs, || {
+            self.x.map(Self::y).ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        (&x * &x * &x + &x + FpVar::constant(InnerFr::from(5u32))).enforce_equal(&y)
+    }
+}
+
+/// Generates `n` proofs of the [`CubicCircuit`] for random `x`.
+pub fn cubic_proofs<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    pk: &ProvingKey<MNT4_298>,
+    n: usize,
+) -> Result<Vec<Groth16Inputs<1>>, Error> {
+    (0..n)
+        .map(|_| {
+            let x = InnerFr::rand(rng);
+            let proof = InnerSNARK::prove(pk, CubicCircuit { x: Some(x) }, rng)?;
+            Ok(Groth16Inputs {
+                proof,
+                public_inputs: [CubicCircuit::y(x)],
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::Field;
+    use folding_schemes::frontend::utils::{simulate_steps, SimulatedSteps};
+
+    #[test]
+    fn test_groth16_aggregation() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            Groth16FCircuit<1>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let (pk, vk) = InnerSNARK::circuit_specific_setup(CubicCircuit { x: None }, &mut rng)?;
+        let proofs = cubic_proofs(&mut rng, &pk, 3)?;
+        let public_inputs: Vec<_> = proofs.iter().map(|p| p.public_inputs).collect();
+        for p in &proofs {
+            assert!(InnerSNARK::verify(&vk, &p.public_inputs, &p.proof)?);
+        }
+
+        let circuit = Groth16FCircuit::<1>::new(vk.clone())?;
+        let z_0 = vec![Fq::zero(); 2];
+        let states = simulate_steps(&circuit, z_0.clone(), proofs[..2].to_vec())?.into_states()?;
+        assert_eq!(states[2], aggregated_state(&public_inputs[..2])?);
+
+        // a proof against other public inputs does not satisfy the step circuit
+        let mut wrong_inputs = proofs[0].clone();
+        wrong_inputs.public_inputs = proofs[1].public_inputs;
+        assert!(simulate_steps(&circuit, z_0.clone(), [wrong_inputs])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+
+        // a public input in [q, r) does not fit in the field of the circuit, and is rejected
+        let q = InnerFr::from_bigint(BigInt(Fq::MODULUS.0)).unwrap();
+        assert!(matches!(inner_to_outer(q), Err(Error::OutOfBounds)));
+        assert!(inner_to_outer(q - InnerFr::ONE).is_ok());
+        let mut too_large = proofs[0].clone();
+        too_large.public_inputs = [q];
+        assert!(aggregated_state(&[too_large.public_inputs]).is_err());
+        assert!(simulate_steps(&circuit, z_0.clone(), [too_large])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+
+        // fold two proofs, and check that a corrupted third one makes `prove_step` unsatisfiable
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fq>(), circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, circuit, z_0)?;
+        nova.prove_step(&mut rng, proofs[0].clone(), None)?;
+        nova.prove_step(&mut rng, proofs[1].clone(), None)?;
+        N::verify(params.1, nova.ivc_proof())?;
+        assert_eq!(nova.state(), aggregated_state(&public_inputs[..2])?);
+
+        let mut corrupted = proofs[2].clone();
+        corrupted.proof.a = (corrupted.proof.a + ark_mnt4_298::G1Affine::generator()).into_affine();
+        assert!(!InnerSNARK::verify(
+            &vk,
+            &corrupted.public_inputs,
+            &corrupted.proof
+        )?);
+        assert!(matches!(
+            nova.prove_step(&mut rng, corrupted, None),
+            Err(Error::UnsatisfiedConstraint { step: 2, .. })
+        ));
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    type N = Nova<
+        Projective,
+        Projective2,
+        Groth16FCircuit<1>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    let num_proofs = 8;
+    let mut rng = rand::rngs::OsRng;
+
+    let (pk, vk) = InnerSNARK::circuit_specific_setup(CubicCircuit { x: None }, &mut rng)?;
+    let start = Instant::now();
+    let proofs = cubic_proofs(&mut rng, &pk, num_proofs)?;
+    println!(
+        "Generate {} Groth16 proofs, {:?}",
+        num_proofs,
+        start.elapsed()
+    );
+    let public_inputs: Vec<_> = proofs.iter().map(|p| p.public_inputs).collect();
+
+    let F_circuit = Groth16FCircuit::<1>::new(vk)?;
+
+    let cs = ConstraintSystem::<Fq>::new_ref();
+    let z_i = Vec::<FpVar<Fq>>::new_witness(cs.clone(), || Ok(vec![Fq::zero(); 2]))?;
+    let input = Groth16InputsVar::new_witness(cs.clone(), || Ok(proofs[0].clone()))?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, input)?;
+    println!("Step circuit: {} constraints", cs.num_constraints());
+
+    let start = Instant::now();
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fq>(), F_circuit.clone());
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    println!("Nova preprocess, {:?}", start.elapsed());
+
+    let mut folding_scheme = N::init(&nova_params, F_circuit, vec![Fq::zero(); 2])?;
+    for (i, proof) in proofs.into_iter().enumerate() {
+        let start = Instant::now();
+        folding_scheme.prove_step(rng, proof, None)?;
+        println!("Nova::prove_step {}: {:?}", i, start.elapsed());
+    }
+
+    let start = Instant::now();
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+    println!("Nova::verify: {:?}", start.elapsed());
+    assert_eq!(folding_scheme.state(), aggregated_state(&public_inputs)?);
+    Ok(())
+}
@@ -0,0 +1,333 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds a two-phase computation as found in the key schedule of TLS 1.3: the first
+/// step derives a ChaCha20 key from a secret with HKDF-Expand (RFC 5869), and the following steps
+/// encrypt a 64-byte block each under the derived key, which is carried in the state so that the
+/// ciphertext of every step is bound to the secret.
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    select::CondSelectGadget,
+    uint32::UInt32,
+    uint8::UInt8,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use experimental_frontends::gadgets::chacha20::{
+    chacha20_block_gadget, chacha20_block_native, enforce_fits_u32,
+};
+use experimental_frontends::gadgets::hkdf::{hkdf_expand_gadget, hkdf_expand_native, HASH_LEN};
+use experimental_frontends::gadgets::u32ops::{u32_to_fp, xor};
+use experimental_frontends::utils::VecFpVarDyn;
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+use folding_schemes::{Error, FoldingScheme};
+
+/// Parameters of the HkdfChaCha20FCircuit
+#[derive(Clone, Debug, Default)]
+pub struct HkdfChaCha20Params {
+    /// The `info` of HKDF-Expand, which is a constant of the circuit, e.g. the `HkdfLabel` of
+    /// TLS 1.3 given by [`tls13_hkdf_label`]
+    pub info: Vec<u8>,
+}
+
+/// HKDF + ChaCha20 Folding Circuit, deriving a key in the first step and encrypting a block per
+/// folding step afterwards
+/// State: [secret (8 words), key (8 words), nonce (3 words), counter, derived, ciphertext (16
+/// words)]
+/// External inputs: the 16 words of plaintext of the step, which are ignored by the first step
+///
+/// The secret is the pseudorandom key of HKDF-Expand, as the big-endian words of its 32 bytes, and
+/// the key, nonce and plaintext words are the little-endian words of ChaCha20. The initial state
+/// has a zero key and `derived` = 0: the first step then writes `HKDF-Expand(secret, info, 32)`
+/// into the key and sets `derived`, and the following steps encrypt under the key of the state.
+/// Since the step circuit is the same for all the steps, each step pays for both the derivation
+/// and the encryption, and the phase only selects which of their outputs go into the state.
+#[derive(Clone, Debug)]
+pub struct HkdfChaCha20FCircuit<F: PrimeField> {
+    info: Vec<u8>,
+    _f: core::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> FCircuit<F> for HkdfChaCha20FCircuit<F> {
+    type Params = HkdfChaCha20Params;
+    type ExternalInputs = Vec<F>; // plaintext block (16 words)
+    type ExternalInputsVar = VecFpVarDyn<F>;
+    type WitnessHint = ();
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        Ok(Self {
+            info: params.info,
+            _f: core::marker::PhantomData,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        // secret(8) + key(8) + nonce(3) + counter(1) + derived(1) + ciphertext(16)
+        37
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let plaintext = external_inputs.allocate(16)?;
+        let words = z_i[0..20]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let nonce: [UInt32<F>; 3] = core::array::from_fn(|j| words[16 + j].clone());
+        let counter = &words[19];
+        let derived = Boolean::new_witness(cs.clone(), || Ok(z_i[20].value()?.is_one()))?;
+        FpVar::from(derived.clone()).enforce_equal(&z_i[20])?;
+
+        // big-endian bytes of the secret
+        let mut secret = Vec::with_capacity(HASH_LEN);
+        for word in &words[0..8] {
+            let bits = word.to_bits_le()?;
+            for byte in (0..4).rev() {
+                secret.push(UInt8::from_bits_le(&bits[8 * byte..8 * (byte + 1)]));
+            }
+        }
+        let info: Vec<UInt8<F>> = self.info.iter().map(|&b| UInt8::constant(b)).collect();
+        let okm = hkdf_expand_gadget(&secret, &info, 32)?;
+        let mut key = Vec::with_capacity(8);
+        for (word, okm_word) in words[8..16].iter().zip(okm.chunks(4)) {
+            let bits = okm_word
+                .iter()
+                .map(|byte| byte.to_bits_le())
+                .collect::<Result<Vec<_>, _>>()?
+                .concat();
+            let derived_word = UInt32::from_bits_le(&bits);
+            key.push(UInt32::conditionally_select(&derived, word, &derived_word)?);
+        }
+        let key: [UInt32<F>; 8] = core::array::from_fn(|j| key[j].clone());
+
+        let keystream = chacha20_block_gadget(cs.clone(), &key, &nonce, counter)?;
+        let ciphertext = plaintext
+            .iter()
+            .zip(&keystream)
+            .map(|(word, keystream_word)| xor(&enforce_fits_u32(word)?, keystream_word))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut next_state = z_i[0..8].to_vec();
+        for word in &key {
+            next_state.push(u32_to_fp(word)?);
+        }
+        next_state.extend_from_slice(&z_i[16..19]);
+        // the step which derives the key does not use up a block of keystream
+        next_state.push(derived.select(
+            &u32_to_fp(&counter.wrapping_add(&UInt32::constant(1)))?,
+            &z_i[19],
+        )?);
+        next_state.push(FpVar::one());
+        for word in &ciphertext {
+            next_state.push(derived.select(&u32_to_fp(word)?, &FpVar::zero())?);
+        }
+        Ok(next_state)
+    }
+}
+
+/// The `HkdfLabel` of HKDF-Expand-Label in TLS 1.3 (RFC 8446 §7.1), to be used as the `info` of
+/// HKDF-Expand
+pub fn tls13_hkdf_label(label: &str, context: &[u8], length: u16) -> Vec<u8> {
+    let label = [b"tls13 ".as_slice(), label.as_bytes()].concat();
+    [
+        length.to_be_bytes().to_vec(),
+        vec![label.len() as u8],
+        label,
+        vec![context.len() as u8],
+        context.to_vec(),
+    ]
+    .concat()
+}
+
+/// Key of ChaCha20, as its little-endian words, derived natively as the first step of the
+/// HkdfChaCha20FCircuit does
+pub fn derive_key_native(secret: &[u8; HASH_LEN], info: &[u8]) -> [u32; 8] {
+    let okm = hkdf_expand_native(secret, info, 32);
+    core::array::from_fn(|j| u32::from_le_bytes(okm[4 * j..4 * (j + 1)].try_into().unwrap()))
+}
+
+/// Initial state of the HkdfChaCha20FCircuit for the given secret, nonce and first counter
+pub fn initial_state<F: PrimeField>(
+    secret: &[u8; HASH_LEN],
+    nonce: [u32; 3],
+    counter: u32,
+) -> Vec<F> {
+    secret
+        .chunks(4)
+        .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+        .chain([0; 8])
+        .chain(nonce)
+        .chain([counter])
+        .map(F::from)
+        .chain(vec![F::zero(); 1 + 16])
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use folding_schemes::frontend::utils::simulate_steps;
+
+    // pseudorandom key of the test case 1 of RFC 5869
+    const SECRET: [u8; HASH_LEN] = [
+        0x07, 0x77, 0x09, 0x36, 0x2c, 0x2e, 0x32, 0xdf, 0x0d, 0xdc, 0x3f, 0x0d, 0xc4, 0x7b, 0xba,
+        0x63, 0x90, 0xb6, 0xc7, 0x3b, 0xb5, 0x0f, 0x9c, 0x31, 0x22, 0xec, 0x84, 0x4a, 0xd7, 0xc2,
+        0xb3, 0xe5,
+    ];
+    const NONCE: [u32; 3] = [0x00000000, 0x4a000000, 0x00000000];
+
+    fn plaintext(step: usize) -> Vec<u32> {
+        (0..16)
+            .map(|j| (step * 16 + j) as u32 * 0x01010101)
+            .collect()
+    }
+
+    /// The state after each step, computed natively
+    fn expected_state(info: &[u8], step: usize) -> Vec<Fr> {
+        let mut state: Vec<Fr> = initial_state(&SECRET, NONCE, 1);
+        if step == 0 {
+            return state;
+        }
+        let key = derive_key_native(&SECRET, info);
+        let counter = step as u32;
+        let ciphertext: Vec<u32> = if step == 1 {
+            vec![0; 16]
+        } else {
+            let keystream = chacha20_block_native(key, NONCE, counter - 1);
+            plaintext(step)
+                .iter()
+                .zip(keystream)
+                .map(|(p, k)| p ^ k)
+                .collect()
+        };
+        state[8..16].copy_from_slice(&key.map(Fr::from));
+        state[19] = Fr::from(counter);
+        state[20] = Fr::from(1);
+        state[21..].copy_from_slice(&ciphertext.into_iter().map(Fr::from).collect::<Vec<_>>());
+        state
+    }
+
+    fn inputs(num_steps: usize) -> Vec<Vec<Fr>> {
+        (0..num_steps)
+            .map(|step| match step {
+                0 => vec![],
+                _ => plaintext(step + 1).into_iter().map(Fr::from).collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hkdf_chacha20_f_circuit() -> Result<(), Error> {
+        let info = tls13_hkdf_label("key", &[], 32);
+        let circuit = HkdfChaCha20FCircuit::<Fr>::new(HkdfChaCha20Params { info: info.clone() })?;
+        let states =
+            simulate_steps(&circuit, initial_state(&SECRET, NONCE, 1), inputs(3))?.into_states()?;
+        for (step, state) in states.iter().enumerate() {
+            assert_eq!(state, &expected_state(&info, step));
+        }
+        // the plaintext of the first step does not reach the state
+        let mut inputs = inputs(1);
+        inputs[0] = plaintext(0).into_iter().map(Fr::from).collect();
+        let states =
+            simulate_steps(&circuit, initial_state(&SECRET, NONCE, 1), inputs)?.into_states()?;
+        assert_eq!(states[1], expected_state(&info, 1));
+        Ok(())
+    }
+
+    /// Fold the derivation and two blocks of encryption with Nova
+    #[test]
+    fn test_hkdf_chacha20_folding() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            HkdfChaCha20FCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let info = tls13_hkdf_label("key", &[], 32);
+        let circuit = HkdfChaCha20FCircuit::<Fr>::new(HkdfChaCha20Params { info: info.clone() })?;
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit.clone());
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, circuit, initial_state(&SECRET, NONCE, 1))?;
+        for block in inputs(3) {
+            nova.prove_step(&mut rng, block, None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+        assert_eq!(nova.state(), expected_state(&info, 3));
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    type N = Nova<
+        Projective,
+        Projective2,
+        HkdfChaCha20FCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    let secret: [u8; HASH_LEN] = core::array::from_fn(|i| i as u8);
+    let nonce = [0x00000000, 0x4a000000, 0x00000000];
+    let info = tls13_hkdf_label("key", &[], 32);
+    let num_blocks = 4;
+    let plaintext: Vec<u32> = (0..16 * num_blocks as u32).collect();
+
+    let F_circuit = HkdfChaCha20FCircuit::<Fr>::new(HkdfChaCha20Params { info: info.clone() })?;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(initial_state(&secret, nonce, 1)))?;
+    let block = VecFpVarDyn::new_witness(cs.clone(), || Ok(vec![Fr::from(0u32); 16]))?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, block)?;
+    println!("Step circuit: {} constraints", cs.num_constraints());
+
+    let mut rng = rand::rngs::OsRng;
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit.clone());
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    let mut folding_scheme = N::init(&nova_params, F_circuit, initial_state(&secret, nonce, 1))?;
+
+    let start = Instant::now();
+    folding_scheme.prove_step(rng, vec![], None)?;
+    println!("Key derived and folded in {:?}", start.elapsed());
+    for (i, block) in plaintext.chunks(16).enumerate() {
+        let start = Instant::now();
+        folding_scheme.prove_step(rng, block.iter().map(|&w| Fr::from(w)).collect(), None)?;
+        println!("Nova::prove_step {}: {:?}", i, start.elapsed());
+    }
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+
+    let key = derive_key_native(&secret, &info);
+    assert_eq!(folding_scheme.state()[8..16], key.map(Fr::from));
+    let keystream = chacha20_block_native(key, nonce, num_blocks as u32);
+    let last_ciphertext: Vec<Fr> = plaintext[16 * (num_blocks - 1)..]
+        .iter()
+        .zip(keystream)
+        .map(|(p, k)| Fr::from(p ^ k))
+        .collect();
+    assert_eq!(folding_scheme.state()[21..], last_ciphertext);
+    Ok(())
+}
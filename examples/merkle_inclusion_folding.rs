@@ -0,0 +1,92 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds the inclusion proofs of all the leaves of a Poseidon Merkle tree of depth
+/// 10: each step verifies the inclusion of one leaf against the root kept in the state, so that
+/// the final proof attests that the 1024 leaves are, in order, the leaves of the tree.
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::ConstraintSystem;
+use ark_std::UniformRand;
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::merkle::{
+    MerkleInclusionFCircuit, MerkleProof, MerkleProofVar, MerkleTree,
+};
+use folding_schemes::frontend::utils::{simulate_steps, SimulatedSteps};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+use folding_schemes::{Error, FoldingScheme};
+
+const D: usize = 10;
+
+fn main() -> Result<(), Error> {
+    type N = Nova<
+        Projective,
+        Projective2,
+        MerkleInclusionFCircuit<Fr, D>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    let mut rng = rand::rngs::OsRng;
+    let poseidon_config = poseidon_canonical_config::<Fr>();
+    let leaves: Vec<Fr> = (0..1 << D).map(|_| Fr::rand(&mut rng)).collect();
+    let start = Instant::now();
+    let tree = MerkleTree::new(&poseidon_config, D, leaves)?;
+    println!("Merkle tree of {} leaves, {:?}", 1 << D, start.elapsed());
+
+    let F_circuit = MerkleInclusionFCircuit::<Fr, D>::new(poseidon_config.clone())?;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i =
+        Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(F_circuit.initial_state(tree.root())))?;
+    let proof = MerkleProofVar::new_witness(cs.clone(), || Ok(MerkleProof::<Fr, D>::default()))?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, proof)?;
+    println!("Step circuit: {} constraints", cs.num_constraints());
+
+    // an inclusion proof with a wrong sibling does not satisfy the step circuit
+    let mut wrong_proof = tree.proof::<D>(0)?;
+    wrong_proof.siblings[D - 1] = Fr::rand(&mut rng);
+    assert!(simulate_steps(
+        &F_circuit,
+        F_circuit.initial_state(tree.root()),
+        [wrong_proof]
+    )
+    .and_then(SimulatedSteps::into_states)
+    .is_err());
+
+    let start = Instant::now();
+    let prep_param = PreprocessorParam::new(poseidon_config, F_circuit.clone());
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    println!("Nova preprocess, {:?}", start.elapsed());
+
+    let z_0 = F_circuit.initial_state(tree.root());
+    let mut folding_scheme = N::init(&nova_params, F_circuit, z_0)?;
+    let start = Instant::now();
+    for index in 0..1 << D {
+        folding_scheme.prove_step(rng, tree.proof::<D>(index)?, None)?;
+    }
+    println!(
+        "Nova::prove_step x {}: {:?} ({:?} per step)",
+        1 << D,
+        start.elapsed(),
+        start.elapsed() / (1 << D)
+    );
+
+    let start = Instant::now();
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+    println!("Nova::verify: {:?}", start.elapsed());
+    assert_eq!(
+        folding_scheme.state(),
+        vec![tree.root(), Fr::from(1u32 << D)]
+    );
+    Ok(())
+}
@@ -32,6 +32,7 @@ impl<F: PrimeField> FCircuit<F> for MultiInputsFCircuit<F> {
     type Params = ();
     type ExternalInputs = ();
     type ExternalInputsVar = ();
+    type WitnessHint = ();
 
     fn new(_params: Self::Params) -> Result<Self, Error> {
         Ok(Self { _f: PhantomData })
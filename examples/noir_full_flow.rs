@@ -25,7 +25,7 @@ use folding_schemes::{
     transcript::poseidon::poseidon_canonical_config,
     Decider, Error, FoldingScheme,
 };
-use std::{path::Path, time::Instant};
+use std::time::Instant;
 
 use solidity_verifiers::calldata::{
     prepare_calldata_for_nova_cyclefold_verifier, NovaVerificationMode,
@@ -43,10 +43,11 @@ fn main() -> Result<(), Error> {
     // initialize the noir fcircuit
     const EXT_INP_LEN: usize = 0;
     const STATE_LEN: usize = 1;
-    let f_circuit = NoirFCircuit::<Fr, STATE_LEN, EXT_INP_LEN>::new(
-        Path::new("./experimental-frontends/src/noir/test_folder/test_mimc/target/test_mimc.json")
-            .into(),
-    )?;
+    // the compiled circuit is embedded in the binary, so the example does not depend on the
+    // path it is run from
+    let f_circuit = NoirFCircuit::<Fr, STATE_LEN, EXT_INP_LEN>::from_bytes(include_bytes!(
+        "../experimental-frontends/src/noir/test_folder/test_mimc/target/test_mimc.json"
+    ))?;
 
     pub type N =
         Nova<G1, G2, NoirFCircuit<Fr, STATE_LEN, EXT_INP_LEN>, KZG<'static, Bn254>, Pedersen<G2>>;
@@ -121,7 +122,8 @@ fn main() -> Result<(), Error> {
     let decider_solidity_code = get_decider_template_for_cyclefold_decider(nova_cyclefold_vk);
 
     // verify the proof against the solidity code in the EVM
-    let nova_cyclefold_verifier_bytecode = compile_solidity(&decider_solidity_code, "NovaDecider");
+    let nova_cyclefold_verifier_bytecode =
+        compile_solidity(&decider_solidity_code, "NovaDecider").unwrap();
     let mut evm = Evm::default();
     let verifier_address = evm.create(nova_cyclefold_verifier_bytecode);
     let (_, output) = evm.call(verifier_address, calldata.clone());
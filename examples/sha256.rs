@@ -38,6 +38,7 @@ impl<F: PrimeField> FCircuit<F> for Sha256FCircuit<F> {
     type Params = ();
     type ExternalInputs = ();
     type ExternalInputsVar = ();
+    type WitnessHint = ();
 
     fn new(_params: Self::Params) -> Result<Self, Error> {
         Ok(Self { _f: PhantomData })
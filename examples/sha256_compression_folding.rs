@@ -0,0 +1,366 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds the SHA-256 hash of a message of arbitrary length, one 64-byte block of the
+/// padded message per folding step, so that long transcripts can be hashed with a step circuit of
+/// the size of a single compression. Unlike the `sha256` example, which hashes the state itself at
+/// each step, the message here is given as external inputs and the padding is checked in-circuit.
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    uint32::UInt32,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use core::marker::PhantomData;
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use experimental_frontends::gadgets::chacha20::enforce_fits_u32;
+use experimental_frontends::gadgets::sha256::{
+    sha256_compress_gadget, sha256_native, sha256_pad, SHA256_IV,
+};
+use experimental_frontends::gadgets::u32ops::u32_to_fp;
+use experimental_frontends::utils::VecFpVarDyn;
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+use folding_schemes::{Error, FoldingScheme};
+
+/// Number of bits of the length in bytes of the message, so that its length in bits fits in the
+/// 64 bits of the padding
+const LEN_BITS: usize = 61;
+
+/// SHA-256 Compression Folding Circuit
+/// State: [hash value (8 words), length in bytes of the message so far, padded, finished]
+/// External inputs: [block (16 words), n, is_last], where n is the number of bytes of the message
+/// in the block, and is_last whether the block is the last one of the padded message
+///
+/// The words are the big-endian 32-bit words of the padded message, as in FIPS 180-4. Each step
+/// compresses its block into the hash value, and checks that the bytes after the n bytes of the
+/// message are the padding: the byte 0x80, unless `padded` tells that the previous block already
+/// contained it, then zeros, and the length in bits of the message in the last 8 bytes of the last
+/// block. The initial state is [SHA256_IV, 0, 0, 0], and once the last block is folded `finished`
+/// is 1, no further step can be folded and the hash value is the SHA-256 hash of the message.
+#[derive(Clone, Copy, Debug)]
+pub struct Sha256CompressionFCircuit<F: PrimeField> {
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> FCircuit<F> for Sha256CompressionFCircuit<F> {
+    type Params = ();
+    type ExternalInputs = Vec<F>; // block (16 words), n, is_last
+    type ExternalInputsVar = VecFpVarDyn<F>;
+    type WitnessHint = ();
+
+    fn new(_params: Self::Params) -> Result<Self, Error> {
+        Ok(Self { _f: PhantomData })
+    }
+
+    fn state_len(&self) -> usize {
+        // hash value(8) + length(1) + padded(1) + finished(1)
+        11
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let external_inputs = external_inputs.allocate(18)?;
+        let (n, padded) = (&external_inputs[16], &z_i[9]);
+        // no block can follow the last one
+        z_i[10].enforce_equal(&FpVar::zero())?;
+
+        // one-hot encoding of n: selector[k] is set iff n == k
+        let selector = (0..=64)
+            .map(|k| Boolean::new_witness(cs.clone(), || Ok(n.value()? == F::from(k as u64))))
+            .collect::<Result<Vec<_>, _>>()?;
+        let selector: Vec<FpVar<F>> = selector.into_iter().map(FpVar::from).collect();
+        selector
+            .iter()
+            .sum::<FpVar<F>>()
+            .enforce_equal(&FpVar::one())?;
+        selector
+            .iter()
+            .enumerate()
+            .map(|(k, s)| s * F::from(k as u64))
+            .sum::<FpVar<F>>()
+            .enforce_equal(n)?;
+
+        let is_last =
+            Boolean::new_witness(cs.clone(), || Ok(external_inputs[17].value()?.is_one()))?;
+        FpVar::from(is_last.clone()).enforce_equal(&external_inputs[17])?;
+        let is_last = FpVar::from(is_last);
+        // the 0x80 byte can only be in the previous block if this block has no message bytes
+        padded.mul_equals(&(FpVar::one() - &selector[0]), &FpVar::zero())?;
+        // the block is the last one iff the message ends before its last 8 bytes, leaving room for
+        // the length
+        let ends_before_length: FpVar<F> = selector[..56].iter().sum();
+        let ends_in_length: FpVar<F> = selector[56..].iter().sum();
+        is_last.mul_equals(&ends_in_length, &FpVar::zero())?;
+        ends_before_length.mul_equals(&(FpVar::one() - &is_last), &FpVar::zero())?;
+
+        let len = &z_i[8] + n;
+        let len_bits = (0..LEN_BITS)
+            .map(|i| Boolean::new_witness(cs.clone(), || Ok(len.value()?.into_bigint().get_bit(i))))
+            .collect::<Result<Vec<_>, _>>()?;
+        Boolean::le_bits_to_fp(&len_bits)?.enforce_equal(&len)?;
+        // length in bits as a 64-bit integer
+        let bit_len = [vec![Boolean::FALSE; 3], len_bits].concat();
+
+        let block = external_inputs[..16]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let block_bits = block
+            .iter()
+            .map(|word| word.to_bits_le())
+            .collect::<Result<Vec<_>, _>>()?;
+        // padding[j] is set iff the byte j is after the n bytes of the message
+        let mut padding = FpVar::zero();
+        for j in 0..64 {
+            padding += &selector[j];
+            let byte_bits = &block_bits[j / 4][8 * (3 - j % 4)..8 * (4 - j % 4)];
+            let byte = Boolean::le_bits_to_fp(byte_bits)?;
+            let mut expected = if j == 0 {
+                (&selector[0] * (FpVar::one() - padded)) * F::from(0x80_u64)
+            } else {
+                &selector[j] * F::from(0x80_u64)
+            };
+            if j >= 56 {
+                let length_byte = Boolean::le_bits_to_fp(&bit_len[8 * (63 - j)..8 * (64 - j)])?;
+                expected += &is_last * length_byte;
+            }
+            padding.mul_equals(&(byte - expected), &FpVar::zero())?;
+        }
+
+        let state = z_i[..8]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_state = sha256_compress_gadget(
+            &core::array::from_fn(|i| state[i].clone()),
+            &core::array::from_fn(|i| block[i].clone()),
+        )?;
+        let mut z_i1 = next_state
+            .iter()
+            .map(u32_to_fp)
+            .collect::<Result<Vec<_>, _>>()?;
+        z_i1.push(len);
+        // the 0x80 byte is in this block unless the whole block is message
+        z_i1.push(FpVar::one() - &selector[64]);
+        z_i1.push(is_last);
+        Ok(z_i1)
+    }
+}
+
+/// Initial state of the Sha256CompressionFCircuit
+pub fn sha256_z_0<F: PrimeField>() -> Vec<F> {
+    [SHA256_IV.map(F::from).to_vec(), vec![F::zero(); 3]].concat()
+}
+
+/// External inputs of the steps of the Sha256CompressionFCircuit which hash `msg`, one per block
+/// of the padded message
+pub fn sha256_external_inputs<F: PrimeField>(msg: &[u8]) -> Vec<Vec<F>> {
+    let blocks = sha256_pad(msg);
+    let num_blocks = blocks.len();
+    blocks
+        .into_iter()
+        .enumerate()
+        .map(|(b, block)| {
+            let n = msg.len().saturating_sub(64 * b).min(64);
+            [
+                block.map(F::from).to_vec(),
+                vec![F::from(n as u64), F::from(b + 1 == num_blocks)],
+            ]
+            .concat()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use folding_schemes::frontend::utils::{simulate_steps, SimulatedSteps};
+
+    type N = Nova<
+        Projective,
+        Projective2,
+        Sha256CompressionFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    // FIPS 180-2 Appendix B.1 and B.2
+    const ONE_BLOCK: &[u8] = b"abc";
+    const ONE_BLOCK_HASH: [u32; 8] = [
+        0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+        0xf20015ad,
+    ];
+    const TWO_BLOCK: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    const TWO_BLOCK_HASH: [u32; 8] = [
+        0x248d6a61, 0xd20638b8, 0xe5c02693, 0x0c3e6039, 0xa33ce459, 0x64ff2167, 0xf6ecedd4,
+        0x19db06c1,
+    ];
+    // hash of 65536 bytes 'a'
+    const LONG_HASH: [u32; 8] = [
+        0xbf718b6f, 0x653bebc1, 0x84e1479f, 0x1935b8da, 0x974d701b, 0x893afcf4, 0x9e701f3e,
+        0x2f9f9c5a,
+    ];
+
+    fn final_state(msg: &[u8], hash: [u32; 8]) -> Vec<Fr> {
+        [
+            hash.map(Fr::from).to_vec(),
+            vec![Fr::from(msg.len() as u64), Fr::from(1), Fr::from(1)],
+        ]
+        .concat()
+    }
+
+    fn fold(msg: &[u8]) -> Result<Vec<Fr>, Error> {
+        let circuit = Sha256CompressionFCircuit::<Fr>::new(())?;
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, circuit, sha256_z_0())?;
+        for inputs in sha256_external_inputs(msg) {
+            nova.prove_step(&mut rng, inputs, None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+        Ok(nova.state())
+    }
+
+    /// Messages whose 0x80 byte and length fall at every position relative to the blocks
+    #[test]
+    fn test_sha256_compression_f_circuit() -> Result<(), Error> {
+        let circuit = Sha256CompressionFCircuit::<Fr>::new(())?;
+        for len in [0, 1, 3, 55, 56, 63, 64, 65, 119, 120, 128] {
+            let msg: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let states = simulate_steps(&circuit, sha256_z_0(), sha256_external_inputs(&msg))?
+                .into_states()?;
+            assert_eq!(
+                states.last().unwrap(),
+                &final_state(&msg, sha256_native(&msg))
+            );
+        }
+        Ok(())
+    }
+
+    /// Blocks whose padding is not the padding of their message are rejected
+    #[test]
+    fn test_sha256_compression_padding() -> Result<(), Error> {
+        let circuit = Sha256CompressionFCircuit::<Fr>::new(())?;
+        let steps = |inputs: Vec<Vec<Fr>>| {
+            simulate_steps(&circuit, sha256_z_0(), inputs).and_then(SimulatedSteps::into_states)
+        };
+
+        let inputs = sha256_external_inputs::<Fr>(ONE_BLOCK);
+        // the 0x80 byte, the zeros and the length are checked
+        for (word, value) in [(0, 0x61626300), (1, 1), (15, 8 * 4)] {
+            let mut cheat = inputs.clone();
+            cheat[0][word] = Fr::from(value as u64);
+            assert!(steps(cheat).is_err());
+        }
+        // the number of bytes of the message is bound to the padding
+        let mut cheat = inputs.clone();
+        cheat[0][16] = Fr::from(4);
+        assert!(steps(cheat).is_err());
+        // a block with the length is the last one
+        let mut cheat = inputs.clone();
+        cheat[0][17] = Fr::from(0);
+        assert!(steps(cheat).is_err());
+        // and no block follows it
+        assert!(steps([inputs.clone(), inputs].concat()).is_err());
+
+        // a full block can not end the message
+        let inputs = sha256_external_inputs::<Fr>(&[0; 64]);
+        let mut cheat = inputs[..1].to_vec();
+        cheat[0][17] = Fr::from(1);
+        assert!(steps(cheat).is_err());
+        // nor a block whose 0x80 byte leaves no room for the length
+        let inputs = sha256_external_inputs::<Fr>(&[0; 60]);
+        let mut cheat = inputs[..1].to_vec();
+        cheat[0][17] = Fr::from(1);
+        assert!(steps(cheat).is_err());
+        Ok(())
+    }
+
+    /// Fold the NIST one-block and two-block vectors with Nova
+    #[test]
+    fn test_sha256_compression_folding() -> Result<(), Error> {
+        assert_eq!(fold(ONE_BLOCK)?, final_state(ONE_BLOCK, ONE_BLOCK_HASH));
+        assert_eq!(fold(TWO_BLOCK)?, final_state(TWO_BLOCK, TWO_BLOCK_HASH));
+        Ok(())
+    }
+
+    /// Hash 64 KiB, i.e. 1025 blocks
+    #[test]
+    #[ignore]
+    fn test_sha256_compression_64kib() -> Result<(), Error> {
+        let msg = vec![b'a'; 1 << 16];
+        let circuit = Sha256CompressionFCircuit::<Fr>::new(())?;
+        let states =
+            simulate_steps(&circuit, sha256_z_0(), sha256_external_inputs(&msg))?.into_states()?;
+        assert_eq!(states.last().unwrap(), &final_state(&msg, LONG_HASH));
+        assert_eq!(fold(&msg)?, final_state(&msg, LONG_HASH));
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let words = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(vec![0u32; 24]))?;
+    let num_constraints = cs.num_constraints();
+    sha256_compress_gadget(
+        &core::array::from_fn(|i| words[i].clone()),
+        &core::array::from_fn(|i| words[8 + i].clone()),
+    )?;
+    println!(
+        "SHA-256: {} constraints per 64-byte block",
+        cs.num_constraints() - num_constraints
+    );
+
+    type N = Nova<
+        Projective,
+        Projective2,
+        Sha256CompressionFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    let msg: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+    let F_circuit = Sha256CompressionFCircuit::<Fr>::new(())?;
+    let mut rng = rand::rngs::OsRng;
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    let mut folding_scheme = N::init(&nova_params, F_circuit, sha256_z_0())?;
+
+    let start = Instant::now();
+    for inputs in sha256_external_inputs(&msg) {
+        folding_scheme.prove_step(rng, inputs, None)?;
+    }
+    println!(
+        "{} bytes hashed and folded in {:?}",
+        msg.len(),
+        start.elapsed()
+    );
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+    assert_eq!(
+        folding_scheme.state()[..8],
+        sha256_native(&msg).map(Fr::from)
+    );
+    Ok(())
+}
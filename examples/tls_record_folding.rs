@@ -0,0 +1,513 @@
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+/// This example folds the decryption of a stream of TLS 1.3 records protected with
+/// ChaCha20-Poly1305 (RFC 8446 §5.2): each step authenticates and decrypts one record under the
+/// per-record nonce derived from the static IV and the sequence number (RFC 8446 §5.3), and
+/// absorbs its plaintext into a running digest, so that the final state commits to the whole
+/// plaintext stream of the connection.
+use ark_crypto_primitives::{
+    crh::{
+        poseidon::{
+            constraints::{CRHGadget, CRHParametersVar},
+            CRH,
+        },
+        CRHScheme, CRHSchemeGadget,
+    },
+    sponge::Absorb,
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    uint32::UInt32,
+};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use std::time::Instant;
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_grumpkin::Projective as Projective2;
+
+use experimental_frontends::gadgets::chacha20::{
+    bytes_to_words_le, chacha20_block_gadget, enforce_fits_u32,
+};
+use experimental_frontends::gadgets::poly1305::{
+    chacha20_poly1305_key_gadget, chacha20_poly1305_native, poly1305_acc_from_fp,
+    poly1305_clamp_gadget, poly1305_tag_gadget, poly1305_words_gadget,
+};
+use experimental_frontends::gadgets::u32ops::{swap_bytes, u32_to_fp, unpack_fp_to_u32s, xor};
+use experimental_frontends::utils::VecFpVarDyn;
+use folding_schemes::commitment::pedersen::Pedersen;
+use folding_schemes::folding::nova::{Nova, PreprocessorParam};
+use folding_schemes::frontend::FCircuit;
+use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+use folding_schemes::{Error, FoldingScheme};
+
+/// Content type of the records of application data
+const APPLICATION_DATA: u8 = 0x17;
+/// Length of the Poly1305 tag appended to the encrypted record
+const TAG_LEN: usize = 16;
+/// Maximum length of the encrypted record, tag included, of RFC 8446 §5.2
+const MAX_CIPHERTEXT_LEN: usize = (1 << 14) + 256;
+
+/// Parameters of the TlsRecordFCircuit
+#[derive(Clone, Copy, Debug)]
+pub struct TlsRecordParams {
+    /// Length in bytes of the encrypted content of each record, i.e. of its TLSInnerPlaintext,
+    /// without the tag. It is a constant of the circuit, so that the record header is too
+    pub record_len: usize,
+}
+
+/// TLS 1.3 record decryption Folding Circuit, decrypting a record protected with
+/// ChaCha20-Poly1305 per folding step
+/// State: [key (8 words), static IV (3 words), sequence number, digest], i.e. 13 field elements
+/// External inputs: the encrypted record as little-endian words, zero-padded to whole 64-byte
+/// blocks, followed by its tag (4 words)
+///
+/// The step derives the nonce `iv XOR seq` of the record, where the sequence number is a 64-bit
+/// big-endian integer left-padded to 12 bytes, checks the tag of the record with its header
+/// `[application_data, 0x03, 0x03, length]` as additional data, and decrypts it. The ciphertext
+/// beyond the record is ignored. The digest absorbs the plaintext words of the record, zero-padded
+/// to whole blocks, as `digest = Poseidon(digest, plaintext words)`, and the sequence number is
+/// incremented.
+#[derive(Clone, Copy, Debug)]
+pub struct TlsRecordFCircuit<F: PrimeField> {
+    record_len: usize,
+    _f: core::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> TlsRecordFCircuit<F> {
+    /// Number of 64-byte blocks of the record
+    fn num_blocks(&self) -> usize {
+        self.record_len.div_ceil(64)
+    }
+}
+
+impl<F: PrimeField + Absorb> FCircuit<F> for TlsRecordFCircuit<F> {
+    type Params = TlsRecordParams;
+    type ExternalInputs = Vec<F>; // encrypted record (16 words per block) and tag (4 words)
+    type ExternalInputsVar = VecFpVarDyn<F>;
+    type WitnessHint = ();
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if params.record_len == 0 || params.record_len + TAG_LEN > MAX_CIPHERTEXT_LEN {
+            return Err(Error::NotExpectedLength(
+                params.record_len,
+                MAX_CIPHERTEXT_LEN - TAG_LEN,
+            ));
+        }
+        Ok(Self {
+            record_len: params.record_len,
+            _f: core::marker::PhantomData,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        // key(8) + iv(3) + seq(1) + digest(1)
+        13
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let num_words = 16 * self.num_blocks();
+        let inputs = external_inputs.allocate(num_words + 4)?;
+        let (ciphertext, tag) = inputs.split_at(num_words);
+
+        let words = z_i[0..11]
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let key: [UInt32<F>; 8] = core::array::from_fn(|j| words[j].clone());
+        // the low and high words of the 64-bit sequence number
+        let seq = unpack_fp_to_u32s(&z_i[11], 2)?;
+        // the sequence number is XORed as big-endian bytes into the last 8 bytes of the IV
+        let nonce = [
+            words[8].clone(),
+            xor(&words[9], &swap_bytes(&seq[1])?)?,
+            xor(&words[10], &swap_bytes(&seq[0])?)?,
+        ];
+
+        let one_time_key = chacha20_poly1305_key_gadget(cs.clone(), &key, &nonce)?;
+        let r = poly1305_clamp_gadget(&core::array::from_fn(|j| one_time_key[j].clone()))?;
+        let s = core::array::from_fn(|j| one_time_key[4 + j].clone());
+
+        let ciphertext = ciphertext
+            .iter()
+            .map(enforce_fits_u32)
+            .collect::<Result<Vec<_>, _>>()?;
+        let ciphertext = truncate_bytes(&ciphertext, self.record_len)?;
+
+        // check the tag of the header and of the ciphertext, each zero-padded to 16 bytes, and of
+        // their lengths
+        let header = tls13_record_header(self.record_len);
+        let header_words: Vec<UInt32<F>> = bytes_to_words_le(&[&header[..], &[0; 11]].concat())
+            .into_iter()
+            .map(UInt32::constant)
+            .collect();
+        let lengths = [header.len() as u32, 0, self.record_len as u32, 0].map(UInt32::constant);
+        let acc = poly1305_acc_from_fp(&FpVar::zero())?;
+        let acc = poly1305_words_gadget(&acc, &r, &header_words)?;
+        let acc = poly1305_words_gadget(&acc, &r, &ciphertext[..4 * self.record_len.div_ceil(16)])?;
+        let acc = poly1305_words_gadget(&acc, &r, &lengths)?;
+        for (word, tag_word) in poly1305_tag_gadget(&acc, &s)?.iter().zip(tag) {
+            u32_to_fp(word)?.enforce_equal(tag_word)?;
+        }
+
+        let mut plaintext = Vec::with_capacity(num_words);
+        for (block, words) in ciphertext.chunks(16).enumerate() {
+            let counter = UInt32::constant(1 + block as u32);
+            let keystream = chacha20_block_gadget(cs.clone(), &key, &nonce, &counter)?;
+            for (word, keystream_word) in words.iter().zip(&keystream) {
+                plaintext.push(xor(word, keystream_word)?);
+            }
+        }
+        let plaintext = truncate_bytes(&plaintext, self.record_len)?;
+
+        let crh_params =
+            CRHParametersVar::<F>::new_constant(cs.clone(), poseidon_canonical_config::<F>())?;
+        let mut hash_input = vec![z_i[12].clone()];
+        for word in &plaintext {
+            hash_input.push(u32_to_fp(word)?);
+        }
+
+        let mut next_state = z_i[0..11].to_vec();
+        next_state.push(&z_i[11] + FpVar::one());
+        next_state.push(CRHGadget::<F>::evaluate(&crh_params, &hash_input)?);
+        Ok(next_state)
+    }
+}
+
+/// Keeps the first `len` bytes of the little-endian words, and zeroes the others, which adds no
+/// constraints
+fn truncate_bytes<F: PrimeField>(
+    words: &[UInt32<F>],
+    len: usize,
+) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    words
+        .iter()
+        .enumerate()
+        .map(|(j, word)| {
+            let bits: Vec<Boolean<F>> = word
+                .to_bits_le()?
+                .into_iter()
+                .enumerate()
+                .map(|(i, bit)| {
+                    if 4 * j + i / 8 < len {
+                        bit
+                    } else {
+                        Boolean::FALSE
+                    }
+                })
+                .collect();
+            Ok(UInt32::from_bits_le(&bits))
+        })
+        .collect()
+}
+
+/// Per-record nonce of RFC 8446 §5.3 as little-endian words: the static IV XOR the sequence
+/// number, encoded as 8 big-endian bytes left-padded with zeros to the 12 bytes of the IV
+pub fn tls13_nonce(iv: [u32; 3], seq: u64) -> [u32; 3] {
+    [
+        iv[0],
+        iv[1] ^ ((seq >> 32) as u32).swap_bytes(),
+        iv[2] ^ (seq as u32).swap_bytes(),
+    ]
+}
+
+/// Header of the record of application data whose encrypted content has `record_len` bytes,
+/// which is the additional data of its AEAD encryption
+pub fn tls13_record_header(record_len: usize) -> [u8; 5] {
+    let length = (record_len + TAG_LEN) as u16;
+    [
+        APPLICATION_DATA,
+        0x03,
+        0x03,
+        (length >> 8) as u8,
+        length as u8,
+    ]
+}
+
+/// Encrypts natively the record of sequence number `seq`, returning its ciphertext and its tag
+pub fn tls13_seal_native(
+    key: [u32; 8],
+    iv: [u32; 3],
+    seq: u64,
+    record: &[u8],
+) -> (Vec<u8>, [u32; 4]) {
+    chacha20_poly1305_native(
+        key,
+        tls13_nonce(iv, seq),
+        &tls13_record_header(record.len()),
+        record,
+    )
+}
+
+/// External inputs of the step of an encrypted record: its words zero-padded to whole blocks, and
+/// its tag
+pub fn record_inputs<F: PrimeField>(ciphertext: &[u8], tag: [u32; 4]) -> Vec<F> {
+    let mut words = bytes_to_words_le(ciphertext);
+    words.resize(16 * ciphertext.len().div_ceil(64), 0);
+    words.into_iter().chain(tag).map(F::from).collect()
+}
+
+/// Computes natively the digest of the final state, from the plaintexts of the records
+pub fn plaintext_digest_native<F: PrimeField + Absorb>(records: &[Vec<u8>]) -> Result<F, Error> {
+    let poseidon_config = poseidon_canonical_config::<F>();
+    records.iter().try_fold(F::zero(), |digest, record| {
+        let mut words = bytes_to_words_le(record);
+        words.resize(16 * record.len().div_ceil(64), 0);
+        let hash_input = [vec![digest], words.into_iter().map(F::from).collect()].concat();
+        CRH::<F>::evaluate(&poseidon_config, hash_input).map_err(|_| Error::EvaluationFail)
+    })
+}
+
+/// Initial state of the TlsRecordFCircuit for the given traffic key and static IV, starting from
+/// the record of sequence number `seq`
+pub fn initial_state<F: PrimeField>(key: [u32; 8], iv: [u32; 3], seq: u64) -> Vec<F> {
+    [key.to_vec(), iv.to_vec()]
+        .concat()
+        .into_iter()
+        .map(F::from)
+        .chain([F::from(seq), F::zero()])
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use chacha20poly1305::{
+        aead::{AeadInPlace, KeyInit},
+        ChaCha20Poly1305, Nonce,
+    };
+    use experimental_frontends::gadgets::chacha20::words_to_bytes_le;
+    use folding_schemes::frontend::utils::{simulate_steps, SimulatedSteps};
+
+    const KEY: [u8; 32] = [
+        0x1f, 0x36, 0x9f, 0xf9, 0x28, 0x3c, 0xb2, 0x40, 0xbc, 0x0d, 0x71, 0xbd, 0x6d, 0x2a, 0x8c,
+        0xa8, 0x65, 0x4e, 0x73, 0x2d, 0xe6, 0xf3, 0x6b, 0x1f, 0xd0, 0x87, 0x62, 0x2c, 0x11, 0xb8,
+        0x5e, 0x0f,
+    ];
+    const IV: [u8; 12] = [
+        0x8d, 0xe4, 0x7a, 0x03, 0x96, 0x12, 0xf5, 0x5b, 0xc0, 0x3e, 0xa1, 0x77,
+    ];
+
+    fn key_words() -> [u32; 8] {
+        bytes_to_words_le(&KEY).try_into().unwrap()
+    }
+
+    fn iv_words() -> [u32; 3] {
+        bytes_to_words_le(&IV).try_into().unwrap()
+    }
+
+    /// Seals the record with the RustCrypto ChaCha20-Poly1305, deriving the nonce over bytes as
+    /// in RFC 8446 §5.3
+    fn rustcrypto_seal(seq: u64, record: &[u8]) -> (Vec<u8>, [u32; 4]) {
+        let mut nonce = IV;
+        for (byte, seq_byte) in nonce[4..].iter_mut().zip(seq.to_be_bytes()) {
+            *byte ^= seq_byte;
+        }
+        let mut ciphertext = record.to_vec();
+        let tag = ChaCha20Poly1305::new(&KEY.into())
+            .encrypt_in_place_detached(
+                Nonce::from_slice(&nonce),
+                &tls13_record_header(record.len()),
+                &mut ciphertext,
+            )
+            .unwrap();
+        (ciphertext, bytes_to_words_le(&tag).try_into().unwrap())
+    }
+
+    fn records(record_len: usize, n: usize) -> Vec<Vec<u8>> {
+        (0..n)
+            .map(|k| {
+                let mut record: Vec<u8> = (0..record_len - 1)
+                    .map(|i| (i * 13 + k * 7 + 1) as u8)
+                    .collect();
+                // the content type of the TLSInnerPlaintext
+                record.push(APPLICATION_DATA);
+                record
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tls13_nonce() {
+        // the nonce of the sequence number 0x0102030405060708, over bytes
+        let nonce = [
+            0x8d,
+            0xe4,
+            0x7a,
+            0x03,
+            0x96 ^ 0x01,
+            0x12 ^ 0x02,
+            0xf5 ^ 0x03,
+            0x5b ^ 0x04,
+            0xc0 ^ 0x05,
+            0x3e ^ 0x06,
+            0xa1 ^ 0x07,
+            0x77 ^ 0x08,
+        ];
+        assert_eq!(
+            words_to_bytes_le(&tls13_nonce(iv_words(), 0x0102030405060708)),
+            nonce
+        );
+        assert_eq!(tls13_nonce(iv_words(), 0), iv_words());
+        assert_eq!(tls13_record_header(1000), [0x17, 0x03, 0x03, 0x03, 0xf8]);
+    }
+
+    /// The records sealed by RustCrypto are decrypted by the steps, across a carry of the
+    /// sequence number into its high word, and the digest of the final state commits to their
+    /// plaintext
+    #[test]
+    fn test_tls_record_f_circuit() -> Result<(), Error> {
+        let first_seq = 0xfffffffe;
+        for record_len in [1, 100, 128] {
+            let circuit = TlsRecordFCircuit::<Fr>::new(TlsRecordParams { record_len })?;
+            let records = records(record_len, 3);
+            let mut inputs = vec![];
+            for (k, record) in records.iter().enumerate() {
+                let (ciphertext, tag) = rustcrypto_seal(first_seq + k as u64, record);
+                assert_eq!(
+                    tls13_seal_native(key_words(), iv_words(), first_seq + k as u64, record),
+                    (ciphertext.clone(), tag)
+                );
+                inputs.push(record_inputs::<Fr>(&ciphertext, tag));
+            }
+
+            let z_0 = initial_state(key_words(), iv_words(), first_seq);
+            let states = simulate_steps(&circuit, z_0.clone(), inputs.clone())?.into_states()?;
+            let expected = initial_state::<Fr>(key_words(), iv_words(), first_seq + 3);
+            assert_eq!(states[3][..12], expected[..12]);
+            assert_eq!(states[3][12], plaintext_digest_native::<Fr>(&records)?);
+
+            // a forged tag, a record replayed under another sequence number, and a record
+            // decrypted with another IV are rejected
+            let mut forged = inputs[0].clone();
+            *forged.last_mut().unwrap() += Fr::from(1u32);
+            assert!(simulate_steps(&circuit, z_0.clone(), vec![forged])
+                .and_then(SimulatedSteps::into_states)
+                .is_err());
+            assert!(
+                simulate_steps(&circuit, z_0.clone(), vec![inputs[1].clone()])
+                    .and_then(SimulatedSteps::into_states)
+                    .is_err()
+            );
+            let mut other_iv = z_0.clone();
+            other_iv[10] += Fr::from(1u32);
+            assert!(simulate_steps(&circuit, other_iv, vec![inputs[0].clone()])
+                .and_then(SimulatedSteps::into_states)
+                .is_err());
+        }
+
+        assert!(TlsRecordFCircuit::<Fr>::new(TlsRecordParams { record_len: 0 }).is_err());
+        assert!(TlsRecordFCircuit::<Fr>::new(TlsRecordParams {
+            record_len: MAX_CIPHERTEXT_LEN - TAG_LEN + 1
+        })
+        .is_err());
+        Ok(())
+    }
+
+    /// Fold the decryption of records with Nova
+    #[test]
+    fn test_tls_record_folding() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            TlsRecordFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let record_len = 100;
+        let circuit = TlsRecordFCircuit::<Fr>::new(TlsRecordParams { record_len })?;
+        let records = records(record_len, 2);
+
+        let mut rng = ark_std::test_rng();
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(
+            &nova_params,
+            circuit,
+            initial_state(key_words(), iv_words(), 0),
+        )?;
+        for (seq, record) in records.iter().enumerate() {
+            let (ciphertext, tag) = rustcrypto_seal(seq as u64, record);
+            nova.prove_step(&mut rng, record_inputs(&ciphertext, tag), None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+
+        let state = nova.state();
+        assert_eq!(state[11], Fr::from(2u32));
+        assert_eq!(state[12], plaintext_digest_native::<Fr>(&records)?);
+        Ok(())
+    }
+}
+
+fn main() -> Result<(), Error> {
+    type N = Nova<
+        Projective,
+        Projective2,
+        TlsRecordFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        false,
+    >;
+
+    let key = [
+        0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918,
+        0x1f1e1d1c,
+    ];
+    let iv = [0x00000000, 0x4a000000, 0x00000000];
+    let record_len = 256;
+    let num_records = 4;
+    let records: Vec<Vec<u8>> = (0..num_records)
+        .map(|k| (0..record_len).map(|i| (i + k) as u8).collect())
+        .collect();
+
+    let F_circuit = TlsRecordFCircuit::<Fr>::new(TlsRecordParams { record_len })?;
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(initial_state::<Fr>(key, iv, 0)))?;
+    let record = VecFpVarDyn::new_witness(cs.clone(), || Ok(vec![]))?;
+    F_circuit.generate_step_constraints(cs.clone(), 0, z_i, record)?;
+    println!(
+        "Step circuit of a {}-byte record: {} constraints",
+        record_len,
+        cs.num_constraints()
+    );
+
+    let mut rng = rand::rngs::OsRng;
+    let start = Instant::now();
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+    let nova_params = N::preprocess(&mut rng, &prep_param)?;
+    println!("Nova preprocess, {:?}", start.elapsed());
+
+    let mut folding_scheme = N::init(&nova_params, F_circuit, initial_state(key, iv, 0))?;
+    for (seq, record) in records.iter().enumerate() {
+        let (ciphertext, tag) = tls13_seal_native(key, iv, seq as u64, record);
+        let start = Instant::now();
+        folding_scheme.prove_step(rng, record_inputs(&ciphertext, tag), None)?;
+        println!("Nova::prove_step {}: {:?}", seq, start.elapsed());
+    }
+
+    let start = Instant::now();
+    N::verify(nova_params.1, folding_scheme.ivc_proof())?;
+    println!("Nova::verify, {:?}", start.elapsed());
+    assert_eq!(
+        folding_scheme.state()[12],
+        plaintext_digest_native::<Fr>(&records)?
+    );
+    println!("The digest of the final state commits to the decrypted records");
+    Ok(())
+}
@@ -0,0 +1,251 @@
+//! External inputs of Circom circuits given by the names of their signals.
+//!
+//! The witness of a Circom circuit holds its input signals after the constant 1 and its outputs,
+//! the public ones first, each group in the order of declaration of the signals. The external
+//! inputs of a [`super::CircomFCircuit`] are the input signals following the state `ivc_input`,
+//! whose names are read from the `.sym` file output by `circom --sym`, where each line
+//! `label_index,wire_index,component_index,name` names a wire of the circuit.
+
+use ark_ff::PrimeField;
+use num_bigint::BigInt;
+use std::collections::HashMap;
+use std::fs;
+
+use super::r1cs::R1csHeader;
+use super::CircomFCircuit;
+use crate::utils::VecF;
+use folding_schemes::{utils::PathOrBin, Error};
+
+/// Names and lengths of the external input signals of the main component of a Circom circuit, in
+/// the order of their wires.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CircomInputLayout {
+    signals: Vec<(String, usize)>,
+}
+
+impl CircomInputLayout {
+    /// Reads the external input signals of the circuit of `header` from its `.sym` file (or given
+    /// as bytes), the first `state_len` input signals being the state.
+    pub fn from_sym(sym: &PathOrBin, header: &R1csHeader, state_len: usize) -> Result<Self, Error> {
+        let contents = match sym {
+            PathOrBin::Path(path) => fs::read_to_string(path)?,
+            PathOrBin::Bin(bin) => String::from_utf8(bin.clone())
+                .map_err(|_| Error::Other("Invalid .sym file: not UTF-8".to_string()))?,
+        };
+        let first_wire = 1 + header.n_pub_out + state_len;
+        let last_wire = 1 + header.n_pub_out + header.n_pub_in + header.n_prv_in;
+
+        // a wire may be labelled by several signals, the ones of the main component are kept
+        let mut names = vec![None; last_wire.saturating_sub(first_wire)];
+        for line in contents.lines().filter(|line| !line.is_empty()) {
+            let fields: Vec<&str> = line.splitn(4, ',').collect();
+            let (Some(wire), Some(name)) = (fields.get(1), fields.get(3)) else {
+                return Err(Error::Other(format!("Invalid .sym file: line '{}'", line)));
+            };
+            let wire: i64 = wire
+                .parse()
+                .map_err(|_| Error::Other(format!("Invalid .sym file: line '{}'", line)))?;
+            let Some(signal) = name.strip_prefix("main.").filter(|s| !s.contains('.')) else {
+                continue;
+            };
+            if (first_wire as i64..last_wire as i64).contains(&wire) {
+                let base = signal.split('[').next().unwrap_or(signal);
+                names[wire as usize - first_wire] = Some(base.to_string());
+            }
+        }
+
+        let mut signals: Vec<(String, usize)> = vec![];
+        for (i, name) in names.into_iter().enumerate() {
+            let name = name.ok_or_else(|| {
+                Error::MissingValue(format!("name of the input wire {}", first_wire + i))
+            })?;
+            match signals.last_mut() {
+                Some((last, len)) if *last == name => *len += 1,
+                _ => {
+                    if signals.iter().any(|(s, _)| *s == name) {
+                        return Err(Error::Other(format!(
+                            "Invalid .sym file: the wires of the signal '{}' are not contiguous",
+                            name
+                        )));
+                    }
+                    signals.push((name, 1));
+                }
+            }
+        }
+        Ok(Self { signals })
+    }
+
+    /// Returns the names and lengths of the external input signals, in the order of their wires.
+    pub fn signals(&self) -> &[(String, usize)] {
+        &self.signals
+    }
+
+    /// Returns the total number of field elements of the external inputs.
+    pub fn len(&self) -> usize {
+        self.signals.iter().map(|(_, len)| len).sum()
+    }
+
+    /// Returns whether the circuit has no external inputs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Orders the values of the signals given by name, failing if one of them is missing, unknown
+    /// or of the wrong length.
+    pub fn order<F: PrimeField>(
+        &self,
+        mut values: HashMap<String, Vec<F>>,
+    ) -> Result<Vec<F>, Error> {
+        let mut ordered = Vec::with_capacity(self.len());
+        for (name, len) in &self.signals {
+            let value = values
+                .remove(name)
+                .ok_or_else(|| Error::MissingValue(format!("external input signal '{}'", name)))?;
+            if value.len() != *len {
+                return Err(Error::NotExpectedLength(value.len(), *len));
+            }
+            ordered.extend(value);
+        }
+        if let Some(name) = values.keys().next() {
+            return Err(Error::Other(format!(
+                "'{}' is not an external input signal of the circuit",
+                name
+            )));
+        }
+        Ok(ordered)
+    }
+
+    /// Splits the flat external inputs into the named inputs of the witness generator.
+    pub(crate) fn named_inputs(&self, values: Vec<BigInt>) -> Vec<(String, Vec<BigInt>)> {
+        let mut values = values.into_iter();
+        self.signals
+            .iter()
+            .map(|(name, len)| (name.clone(), values.by_ref().take(*len).collect()))
+            .collect()
+    }
+}
+
+/// Builder of the external inputs of a [`CircomFCircuit`] by the names of their signals, so that
+/// they do not depend on the order in which the signals are declared in the circuit.
+///
+/// ```ignore
+/// let external_inputs = CircomExternalInputs::new()
+///     .set("plaintext", plaintext)
+///     .set("counter", vec![counter])
+///     .build(&f_circuit)?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CircomExternalInputs<F: PrimeField> {
+    values: HashMap<String, Vec<F>>,
+}
+
+impl<F: PrimeField> CircomExternalInputs<F> {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+
+    /// Sets the values of the signal `name`, replacing the previous ones if it was already set.
+    pub fn set(mut self, name: impl Into<String>, values: Vec<F>) -> Self {
+        self.values.insert(name.into(), values);
+        self
+    }
+
+    /// Orders the values of the signals as the external inputs of `f_circuit`, which must have
+    /// been given the `.sym` file of its circuit.
+    pub fn build<const SL: usize, const EIL: usize>(
+        self,
+        f_circuit: &CircomFCircuit<F, SL, EIL>,
+    ) -> Result<VecF<F, EIL>, Error> {
+        let layout = f_circuit.input_layout().ok_or_else(|| {
+            Error::MissingValue("symbols (.sym file) of the Circom circuit".to_string())
+        })?;
+        Ok(VecF(layout.order(self.values)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use num_bigint::BigUint;
+
+    fn header(n_pub_out: usize, n_pub_in: usize, n_prv_in: usize) -> R1csHeader {
+        R1csHeader {
+            field_size: 32,
+            prime: BigUint::from(Fr::MODULUS),
+            n_wires: 16,
+            n_pub_out,
+            n_pub_in,
+            n_prv_in,
+            n_labels: 16,
+            n_constraints: 4,
+        }
+    }
+
+    #[test]
+    fn test_layout_from_sym() -> Result<(), Error> {
+        let sym = b"1,1,0,main.ivc_output[0]\n\
+            2,2,0,main.ivc_input[0]\n\
+            3,3,0,main.counter\n\
+            4,4,0,main.plaintext[0]\n\
+            5,5,0,main.plaintext[1]\n\
+            6,6,0,main.temp\n\
+            7,3,1,main.sub.in\n"
+            .to_vec();
+        let layout = CircomInputLayout::from_sym(&sym.clone().into(), &header(1, 1, 3), 1)?;
+        assert_eq!(
+            layout.signals(),
+            [("counter".to_string(), 1), ("plaintext".to_string(), 2)]
+        );
+        assert_eq!(
+            layout.named_inputs([3, 4, 5].map(BigInt::from).to_vec()),
+            vec![
+                ("counter".to_string(), vec![BigInt::from(3)]),
+                (
+                    "plaintext".to_string(),
+                    vec![BigInt::from(4), BigInt::from(5)]
+                ),
+            ]
+        );
+
+        let values = |names: &[(&str, Vec<u64>)]| -> HashMap<String, Vec<Fr>> {
+            names
+                .iter()
+                .map(|(name, v)| (name.to_string(), v.iter().copied().map(Fr::from).collect()))
+                .collect()
+        };
+        assert_eq!(
+            layout.order(values(&[("plaintext", vec![4, 5]), ("counter", vec![3])]))?,
+            [3, 4, 5].map(Fr::from).to_vec()
+        );
+        assert!(matches!(
+            layout.order(values(&[("plaintext", vec![4, 5])])),
+            Err(Error::MissingValue(_))
+        ));
+        assert!(matches!(
+            layout.order(values(&[("plaintext", vec![4]), ("counter", vec![3])])),
+            Err(Error::NotExpectedLength(1, 2))
+        ));
+        assert!(matches!(
+            layout.order(values(&[
+                ("plaintext", vec![4, 5]),
+                ("counter", vec![3]),
+                ("nonce", vec![1])
+            ])),
+            Err(Error::Other(_))
+        ));
+
+        // an input wire without a name in the main component
+        assert!(matches!(
+            CircomInputLayout::from_sym(&sym.into(), &header(1, 1, 4), 1),
+            Err(Error::MissingValue(_))
+        ));
+        assert!(matches!(
+            CircomInputLayout::from_sym(&b"1;main.counter\n".to_vec().into(), &header(1, 1, 1), 1),
+            Err(Error::Other(_))
+        ));
+        Ok(())
+    }
+}
@@ -1,4 +1,3 @@
-use ark_circom::circom::R1CS as CircomR1CS;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
     fields::fp::{AllocatedFp, FpVar},
@@ -11,17 +10,42 @@ use ark_relations::{
 use ark_std::fmt::Debug;
 use folding_schemes::{frontend::FCircuit, utils::PathOrBin, Error};
 use num_bigint::{BigInt, BigUint};
+use std::ops::Range;
+use std::sync::Arc;
 
+mod inputs;
+pub mod r1cs;
 pub mod utils;
 use crate::utils::{VecF, VecFpVar};
+pub use inputs::{CircomExternalInputs, CircomInputLayout};
+pub use r1cs::circuit_sizes;
+use r1cs::CircomR1cs;
 use utils::CircomWrapper;
+pub use utils::WitnessBackend;
+
+/// Callback receiving the step and the values of the outputs of a Circom circuit that are not part
+/// of the state, every time the step is synthesized with a witness.
+#[derive(Clone)]
+pub struct AuxOutputsCallback<F>(pub Arc<dyn Fn(usize, Vec<F>) + Send + Sync>);
+
+impl<F> Debug for AuxOutputsCallback<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuxOutputsCallback")
+    }
+}
 
 /// Define CircomFCircuit. The parameter `SL` indicates the length of the state vector.
 /// The parameter `EIL` indicates the length of the ExternalInputs vector of field elements.
 #[derive(Clone, Debug)]
 pub struct CircomFCircuit<F: PrimeField, const SL: usize, const EIL: usize> {
     circom_wrapper: CircomWrapper,
-    r1cs: CircomR1CS<F>,
+    // shared by the clones of the circuit, since the constraints do not change across steps
+    r1cs: Arc<CircomR1cs<F>>,
+    // names of the external input signals, read from the .sym file of the circuit
+    input_layout: Option<CircomInputLayout>,
+    // indexes, among the outputs of the circuit, of the outputs forming the next state
+    state_outputs: Range<usize>,
+    aux_outputs_callback: Option<AuxOutputsCallback<F>>,
 }
 
 impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for CircomFCircuit<F, SL, EIL> {
@@ -29,16 +53,11 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for CircomFCi
     type Params = (PathOrBin, PathOrBin);
     type ExternalInputs = VecF<F, EIL>;
     type ExternalInputsVar = VecFpVar<F, EIL>;
+    type WitnessHint = ();
 
     fn new(params: Self::Params) -> Result<Self, Error> {
         let (r1cs_path, wasm_path) = params;
-        let circom_wrapper = CircomWrapper::new(r1cs_path, wasm_path)?;
-
-        let r1cs = circom_wrapper.extract_r1cs()?;
-        Ok(Self {
-            circom_wrapper,
-            r1cs,
-        })
+        Self::new_with_witness_backend(r1cs_path, WitnessBackend::Wasm(wasm_path))
     }
 
     fn state_len(&self) -> usize {
@@ -48,7 +67,7 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for CircomFCi
     fn generate_step_constraints(
         &self,
         cs: ConstraintSystemRef<F>,
-        _i: usize,
+        i: usize,
         z_i: Vec<FpVar<F>>,
         external_inputs: Self::ExternalInputsVar,
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
@@ -62,19 +81,23 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for CircomFCi
 
         if EIL > 0 {
             let external_inputs_bi = Self::fpvars_to_bigints(&external_inputs.0);
-            inputs_map.push(("external_inputs".to_string(), external_inputs_bi));
+            match &self.input_layout {
+                Some(layout) => inputs_map.extend(layout.named_inputs(external_inputs_bi)),
+                None => inputs_map.push(("external_inputs".to_string(), external_inputs_bi)),
+            }
         }
 
         // The layout of `witness` is as follows:
         // [
         //   1,                  // The constant 1 is implicitly allocated by Arkworks
-        //   ...z_{i + 1},       // The next state marked as `signal output` in the circom circuit
+        //   ...outputs,         // The outputs marked as `signal output` in the circom circuit,
+        //                       // among which the next state z_{i + 1}
         //   ...z_i,             // The current state marked as `signal input` in the circom circuit
         //   ...external_inputs, // The optional external inputs marked as `external input` in the circom circuit
         //   ...aux,             // The intermediate witnesses
         // ]
         // Here, 1, z_i, and external_inputs have already been allocated in the
-        // constraint system, while z_{i + 1} and aux are yet to be allocated.
+        // constraint system, while the outputs and aux are yet to be allocated.
         let witness = self
             .circom_wrapper
             .extract_witness(inputs_map)
@@ -91,14 +114,30 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for CircomFCi
         // Constant 1 at idx 0 is already allocated by arkworks
         circom_index_to_cs_index.push(Variable::One);
 
-        // Allocate the next state (1..1 + SL) as witness, and at the same time,
-        // record the allocated variable's index in `circom_index_to_cs_index`.
+        // Allocate the outputs (1..1 + n_pub_out) as witness, and at the same time,
+        // record the allocated variable's index in `circom_index_to_cs_index`. The
+        // selected outputs form the next state.
         // Cf. https://github.com/arnaucube/circom-compat/blob/22c8f5/src/circom/circuit.rs#L56-L86
         let mut z_i1 = vec![];
-        for &w in witness.iter().skip(1).take(SL) {
+        let mut aux_outputs = vec![];
+        for (j, &w) in witness
+            .iter()
+            .skip(1)
+            .take(self.r1cs.header.n_pub_out)
+            .enumerate()
+        {
             let v = cs.new_witness_variable(|| Ok(w))?;
             circom_index_to_cs_index.push(v);
-            z_i1.push(FpVar::Var(AllocatedFp::new(Some(w), v, cs.clone())));
+            if self.state_outputs.contains(&j) {
+                z_i1.push(FpVar::Var(AllocatedFp::new(Some(w), v, cs.clone())));
+            } else {
+                aux_outputs.push(w);
+            }
+        }
+        if let Some(callback) = &self.aux_outputs_callback {
+            if !cs.is_in_setup_mode() {
+                (callback.0)(i, aux_outputs);
+            }
         }
 
         // `z_i` and `external_inputs` have already been allocated as witness,
@@ -120,14 +159,14 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for CircomFCi
             circom_index_to_cs_index.push(cs.new_witness_variable(|| Ok(w))?);
         }
 
-        let fold_lc = |lc, &(i, coeff)| lc + (coeff, circom_index_to_cs_index[i]);
+        let fold_lc = |lc, (i, coeff)| lc + (coeff, circom_index_to_cs_index[i]);
 
         // Generates the constraints for the circom_circuit.
-        for (a, b, c) in &self.r1cs.constraints {
+        for i in 0..self.r1cs.n_constraints() {
             cs.enforce_constraint(
-                a.iter().fold(lc!(), fold_lc),
-                b.iter().fold(lc!(), fold_lc),
-                c.iter().fold(lc!(), fold_lc),
+                self.r1cs.a.row(i).fold(lc!(), fold_lc),
+                self.r1cs.b.row(i).fold(lc!(), fold_lc),
+                self.r1cs.c.row(i).fold(lc!(), fold_lc),
             )?;
         }
 
@@ -141,6 +180,92 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for CircomFCi
 }
 
 impl<F: PrimeField, const SL: usize, const EIL: usize> CircomFCircuit<F, SL, EIL> {
+    /// Creates the circuit from its `.r1cs` file, computing its witnesses with the given backend.
+    /// The constraints only depend on the `.r1cs` file, so both backends produce the same
+    /// constraint system. All the outputs of the circuit form the next state.
+    pub fn new_with_witness_backend(
+        r1cs: PathOrBin,
+        witness_backend: WitnessBackend,
+    ) -> Result<Self, Error> {
+        Self::new_with_state_outputs(r1cs, witness_backend, None)
+    }
+
+    /// Creates the circuit from its `.r1cs` file, the next state being formed by the outputs of
+    /// indexes `state_outputs` (in the order of declaration of the output signals), or by all of
+    /// them if it is `None`. The other outputs are ignored, or given to the callback set with
+    /// [`Self::with_aux_outputs_callback`].
+    pub fn new_with_state_outputs(
+        r1cs: PathOrBin,
+        witness_backend: WitnessBackend,
+        state_outputs: Option<Range<usize>>,
+    ) -> Result<Self, Error> {
+        let circom_wrapper = CircomWrapper::new_with_witness_backend(r1cs, witness_backend)?;
+
+        let r1cs = Arc::new(circom_wrapper.extract_sparse_r1cs()?);
+        let header = &r1cs.header;
+        let state_outputs = state_outputs.unwrap_or(0..header.n_pub_out);
+        if state_outputs.end > header.n_pub_out {
+            return Err(Error::Other(format!(
+                "The state outputs {:?} are not outputs of the Circom circuit, which has {} outputs",
+                state_outputs, header.n_pub_out
+            )));
+        }
+        if state_outputs.len() != SL {
+            return Err(Error::NotSameLength(
+                "state outputs".to_string(),
+                state_outputs.len(),
+                "state_len".to_string(),
+                SL,
+            ));
+        }
+        if header.n_pub_in + header.n_prv_in != SL + EIL {
+            return Err(Error::NotSameLength(
+                "Circom circuit inputs".to_string(),
+                header.n_pub_in + header.n_prv_in,
+                "state_len + external_inputs_len".to_string(),
+                SL + EIL,
+            ));
+        }
+        Ok(Self {
+            circom_wrapper,
+            r1cs,
+            input_layout: None,
+            state_outputs,
+            aux_outputs_callback: None,
+        })
+    }
+
+    /// Sets the callback receiving the values of the outputs that are not part of the state, at
+    /// every synthesis of the step with a witness. The step is synthesized when proving it, but
+    /// also eg. when computing the parameters of the folding scheme, so the callback may be called
+    /// more than once for a step.
+    pub fn with_aux_outputs_callback(
+        mut self,
+        callback: impl Fn(usize, Vec<F>) + Send + Sync + 'static,
+    ) -> Self {
+        self.aux_outputs_callback = Some(AuxOutputsCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Reads the names of the external input signals of the circuit from its `.sym` file, so that
+    /// they can be given by name with [`CircomExternalInputs`], and passed to the witness
+    /// generator as the signals they are declared as. Without it, the external inputs are passed
+    /// as the single `external_inputs` signal.
+    pub fn with_sym(mut self, sym: PathOrBin) -> Result<Self, Error> {
+        let layout = CircomInputLayout::from_sym(&sym, &self.r1cs.header, SL)?;
+        if layout.len() != EIL {
+            return Err(Error::NotExpectedLength(layout.len(), EIL));
+        }
+        self.input_layout = Some(layout);
+        Ok(self)
+    }
+
+    /// Returns the names of the external input signals, if the `.sym` file of the circuit was
+    /// given.
+    pub fn input_layout(&self) -> Option<&CircomInputLayout> {
+        self.input_layout.as_ref()
+    }
+
     fn fpvars_to_bigints(fpvars: &[FpVar<F>]) -> Vec<BigInt> {
         fpvars
             .value()
@@ -155,11 +280,20 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> CircomFCircuit<F, SL, EIL
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use ark_bn254::Fr;
+    use ark_bn254::{Fq, Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
     use ark_r1cs_std::alloc::AllocVar;
     use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use folding_schemes::{
+        commitment::pedersen::Pedersen,
+        folding::nova::{Nova, PreprocessorParam},
+        transcript::poseidon::poseidon_canonical_config,
+        FoldingScheme,
+    };
     use std::path::PathBuf;
 
+    use crate::FrontendError;
+
     /// Native implementation of `src/circom/test_folder/cubic_circuit.r1cs`
     fn cubic_step_native<F: PrimeField>(z_i: Vec<F>) -> Vec<F> {
         let z = z_i[0];
@@ -321,6 +455,312 @@ pub mod tests {
         Ok(())
     }
 
+    // Tests that the native witness generator computes the same witness and constraint system as
+    // the Wasm one.
+    #[test]
+    fn test_circom_native_witness_backend() -> Result<(), Error> {
+        let r1cs_path = PathBuf::from("./src/circom/test_folder/with_external_inputs.r1cs");
+        let wasm_path = PathBuf::from(
+            "./src/circom/test_folder/with_external_inputs_js/with_external_inputs.wasm",
+        );
+        let binary_path =
+            PathBuf::from("./src/circom/test_folder/with_external_inputs_cpp/with_external_inputs");
+        let wasm_fcircuit = CircomFCircuit::<Fr, 1, 2>::new_with_witness_backend(
+            r1cs_path.clone().into(),
+            WitnessBackend::Wasm(wasm_path.into()),
+        )?;
+        let native_fcircuit = CircomFCircuit::<Fr, 1, 2>::new_with_witness_backend(
+            r1cs_path.into(),
+            WitnessBackend::NativeBinary(binary_path),
+        )?;
+
+        let inputs = vec![
+            ("ivc_input".to_string(), vec![BigInt::from(3)]),
+            (
+                "external_inputs".to_string(),
+                vec![BigInt::from(6), BigInt::from(7)],
+            ),
+        ];
+        let wasm_witness = wasm_fcircuit
+            .circom_wrapper
+            .extract_witness::<Fr>(inputs.clone())?;
+        assert_eq!(
+            native_fcircuit
+                .circom_wrapper
+                .extract_witness::<Fr>(inputs)?,
+            wasm_witness
+        );
+
+        let step = |fcircuit: &CircomFCircuit<Fr, 1, 2>| -> Result<_, Error> {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let z_i_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(3u32)]))?;
+            let external_inputs_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || {
+                Ok(vec![Fr::from(6u32), Fr::from(7u32)])
+            })?;
+            let z_i1 = fcircuit.generate_step_constraints(
+                cs.clone(),
+                1,
+                z_i_var,
+                VecFpVar(external_inputs_var),
+            )?;
+            cs.finalize();
+            let matrices = cs.to_matrices().ok_or(Error::NoInnerConstraintSystem)?;
+            let witness = cs
+                .borrow()
+                .ok_or(Error::NoInnerConstraintSystem)?
+                .witness_assignment
+                .clone();
+            Ok((z_i1.value()?, matrices.a, matrices.b, matrices.c, witness))
+        };
+        assert_eq!(step(&native_fcircuit)?, step(&wasm_fcircuit)?);
+        Ok(())
+    }
+
+    /// Native implementation of `src/circom/test_folder/with_named_inputs.r1cs`, whose external
+    /// inputs are `[counter, plaintext[0], plaintext[1]]`
+    fn named_inputs_step_native<F: PrimeField>(z_i: Vec<F>, external_inputs: Vec<F>) -> Vec<F> {
+        vec![z_i[0] * external_inputs[1] * external_inputs[0] + external_inputs[2]]
+    }
+
+    #[test]
+    fn test_circom_sym_layout() -> Result<(), Error> {
+        let test_folder = PathBuf::from("./src/circom/test_folder");
+        let circom_fcircuit = CircomFCircuit::<Fr, 1, 2>::new((
+            test_folder.join("with_external_inputs.r1cs").into(),
+            test_folder
+                .join("with_external_inputs_js/with_external_inputs.wasm")
+                .into(),
+        ))?
+        .with_sym(test_folder.join("with_external_inputs.sym").into())?;
+        assert_eq!(
+            circom_fcircuit.input_layout().unwrap().signals(),
+            [("external_inputs".to_string(), 2)]
+        );
+
+        let external_inputs = CircomExternalInputs::new()
+            .set("external_inputs", vec![Fr::from(6u32), Fr::from(7u32)])
+            .build(&circom_fcircuit)?;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(3u32)]))?;
+        let external_inputs_var =
+            Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(external_inputs.0.clone()))?;
+        let z_i1_var = circom_fcircuit.generate_step_constraints(
+            cs.clone(),
+            1,
+            z_i_var,
+            VecFpVar(external_inputs_var),
+        )?;
+        assert_eq!(
+            z_i1_var.value()?,
+            external_inputs_step_native(vec![Fr::from(3u32)], external_inputs.0)
+        );
+
+        // the inputs of the circuit must match the state and external inputs lengths
+        assert!(matches!(
+            CircomFCircuit::<Fr, 1, 3>::new((
+                test_folder.join("with_external_inputs.r1cs").into(),
+                test_folder
+                    .join("with_external_inputs_js/with_external_inputs.wasm")
+                    .into(),
+            )),
+            Err(Error::NotSameLength(..))
+        ));
+        Ok(())
+    }
+
+    // Folds `with_named_inputs` with external inputs set by name in different orders, and checks
+    // that the result matches the folding with the positional external inputs.
+    #[test]
+    fn test_circom_named_inputs_nova() -> Result<(), Error> {
+        type FC = CircomFCircuit<Fr, 1, 3>;
+        type N =
+            Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+        let test_folder = PathBuf::from("./src/circom/test_folder");
+        let f_circuit = FC::new((
+            test_folder.join("with_named_inputs.r1cs").into(),
+            test_folder
+                .join("with_named_inputs_js/with_named_inputs.wasm")
+                .into(),
+        ))?;
+        let named_f_circuit = f_circuit
+            .clone()
+            .with_sym(test_folder.join("with_named_inputs.sym").into())?;
+        assert_eq!(
+            named_f_circuit.input_layout().unwrap().signals(),
+            [("counter".to_string(), 1), ("plaintext".to_string(), 2)]
+        );
+
+        let steps: Vec<(Fr, [Fr; 2])> = (1..4u32)
+            .map(|i| (Fr::from(i), [Fr::from(i + 1), Fr::from(2 * i)]))
+            .collect();
+        let named_inputs = steps
+            .iter()
+            .enumerate()
+            .map(|(i, &(counter, plaintext))| {
+                let inputs = CircomExternalInputs::new();
+                // alternate the order in which the signals are set
+                let inputs = if i % 2 == 0 {
+                    inputs
+                        .set("plaintext", plaintext.to_vec())
+                        .set("counter", vec![counter])
+                } else {
+                    inputs
+                        .set("counter", vec![counter])
+                        .set("plaintext", plaintext.to_vec())
+                };
+                inputs.build(&named_f_circuit)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let positional_inputs: Vec<VecF<Fr, 3>> = steps
+            .iter()
+            .map(|&(counter, [p0, p1])| VecF(vec![counter, p0, p1]))
+            .collect();
+        assert_eq!(
+            named_inputs.iter().map(|v| v.0.clone()).collect::<Vec<_>>(),
+            positional_inputs
+                .iter()
+                .map(|v| v.0.clone())
+                .collect::<Vec<_>>()
+        );
+
+        let mut rng = ark_std::test_rng();
+        let z_0 = vec![Fr::from(3u32)];
+        let mut fold = |f_circuit: FC, inputs: Vec<VecF<Fr, 3>>| -> Result<Vec<Fr>, Error> {
+            let prep_param =
+                PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+            let params = N::preprocess(&mut rng, &prep_param)?;
+            let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+            for external_inputs in inputs {
+                nova.prove_step(&mut rng, external_inputs, None)?;
+            }
+            N::verify(params.1, nova.ivc_proof())?;
+            Ok(nova.state())
+        };
+        let positional_state = fold(f_circuit, positional_inputs.clone())?;
+        assert_eq!(
+            fold(named_f_circuit.clone(), named_inputs)?,
+            positional_state
+        );
+        let native_state = positional_inputs
+            .into_iter()
+            .fold(z_0.clone(), |z_i, inputs| {
+                named_inputs_step_native(z_i, inputs.0)
+            });
+        assert_eq!(positional_state, native_state);
+
+        // missing and unknown signals are rejected
+        assert!(matches!(
+            CircomExternalInputs::new()
+                .set("plaintext", vec![Fr::from(1u32); 2])
+                .build(&named_f_circuit),
+            Err(Error::MissingValue(_))
+        ));
+        assert!(matches!(
+            CircomExternalInputs::new()
+                .set("plaintext", vec![Fr::from(1u32); 2])
+                .set("counter", vec![Fr::from(1u32)])
+                .set("nonce", vec![Fr::from(1u32)])
+                .build(&named_f_circuit),
+            Err(Error::Other(_))
+        ));
+        Ok(())
+    }
+
+    // Tests that circuits compiled for another prime than the modulus of the field are rejected.
+    #[test]
+    fn test_circom_wrong_prime() -> Result<(), Error> {
+        let test_folder = PathBuf::from("./src/circom/test_folder");
+        let wasm_path = test_folder.join("cubic_circuit_js/cubic_circuit.wasm");
+
+        // compiled with `--prime pallas`, loaded over the BN254 scalar field
+        let r1cs_path = test_folder.join("pallas/cubic_circuit.r1cs");
+        let Some(Ok(FrontendError::FieldMismatch { expected, found })) =
+            CircomFCircuit::<Fr, 1, 0>::new((r1cs_path.into(), wasm_path.clone().into()))
+                .err()
+                .map(FrontendError::try_from)
+        else {
+            panic!("the prime of the circuit is not checked");
+        };
+        assert_eq!(expected, Fr::MODULUS.to_string());
+        assert_eq!(
+            found,
+            "28948022309329048855892746252171976963363056481941560715954676764349967630337"
+        );
+
+        // compiled with `--prime bn128`, loaded over the BN254 base field
+        let r1cs_path = test_folder.join("cubic_circuit.r1cs");
+        assert!(matches!(
+            CircomFCircuit::<Fq, 1, 0>::new((r1cs_path.into(), wasm_path.into()))
+                .err()
+                .map(FrontendError::try_from),
+            Some(Ok(FrontendError::FieldMismatch { .. }))
+        ));
+        Ok(())
+    }
+
+    /// Native implementation of `src/circom/test_folder/with_aux_outputs.r1cs`, returning the next
+    /// state and the auxiliary output
+    fn aux_outputs_step_native<F: PrimeField>(z_i: Vec<F>) -> (Vec<F>, F) {
+        let z_i1 = vec![z_i[0] + z_i[1], z_i[0] * z_i[1]];
+        let aux = z_i1[0] * z_i1[1];
+        (z_i1, aux)
+    }
+
+    // Folds `with_aux_outputs`, whose outputs are `[aux, ivc_output[0], ivc_output[1]]`, with the
+    // last two outputs as the state, collecting the auxiliary output of each step.
+    #[test]
+    fn test_circom_state_outputs_nova() -> Result<(), Error> {
+        type FC = CircomFCircuit<Fr, 2, 0>;
+        type N =
+            Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+        let test_folder = PathBuf::from("./src/circom/test_folder");
+        let r1cs_path = test_folder.join("with_aux_outputs.r1cs");
+        let wasm_path = test_folder.join("with_aux_outputs_js/with_aux_outputs.wasm");
+        let new = |state_outputs| {
+            FC::new_with_state_outputs(
+                r1cs_path.clone().into(),
+                WitnessBackend::Wasm(wasm_path.clone().into()),
+                state_outputs,
+            )
+        };
+
+        // all the outputs form the state by default, and the selection must be of length SL
+        assert!(matches!(new(None), Err(Error::NotSameLength(..))));
+        assert!(matches!(new(Some(0..3)), Err(Error::NotSameLength(..))));
+        assert!(matches!(new(Some(2..4)), Err(Error::Other(_))));
+
+        let aux_outputs = Arc::new(std::sync::Mutex::new(vec![]));
+        let f_circuit = new(Some(1..3))?.with_aux_outputs_callback({
+            let aux_outputs = aux_outputs.clone();
+            move |i, aux| aux_outputs.lock().unwrap().push((i, aux))
+        });
+        assert_eq!(f_circuit.state_len(), 2);
+
+        let mut rng = ark_std::test_rng();
+        let prep_param =
+            PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::from(1u32), Fr::from(2u32)];
+        let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+        aux_outputs.lock().unwrap().clear();
+
+        let mut z_i = z_0;
+        let mut expected_aux_outputs = vec![];
+        for i in 0..5 {
+            nova.prove_step(&mut rng, VecF(vec![]), None)?;
+            let (z_i1, aux) = aux_outputs_step_native(z_i);
+            z_i = z_i1;
+            expected_aux_outputs.push((i, vec![aux]));
+        }
+        assert_eq!(nova.state(), z_i);
+        assert_eq!(z_i, vec![Fr::from(371u32), Fr::from(13530u32)]);
+        assert_eq!(*aux_outputs.lock().unwrap(), expected_aux_outputs);
+        N::verify(params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
     #[test]
     fn test_custom_code() -> Result<(), Error> {
         let r1cs_path = PathBuf::from("./src/circom/test_folder/cubic_circuit.r1cs");
@@ -0,0 +1,411 @@
+//! Streaming reader of the `.r1cs` files output by circom, following the
+//! [r1cs binary format](https://github.com/iden3/r1csfile/blob/master/doc/r1cs_bin_format.md).
+//!
+//! The file starts with the magic `r1cs`, a version and a number of sections, followed by the
+//! sections, each made of its type, its size and its content, with all the integers in little
+//! endian. Only the header (type 1) and constraints (type 2) sections are read: the constraints
+//! are parsed term by term into sparse matrices in compressed sparse row form, without holding
+//! the file nor the constraints in any intermediate form.
+//!
+//! `tests/r1cs_memory.rs` measures the peak heap of reading a file of 2^16 constraints against
+//! reading it with ark-circom. Both grow linearly with the number of constraints, but the resident
+//! memory on files of 2^22 constraints has not been measured.
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+
+use folding_schemes::{utils::PathOrBin, Error};
+
+use crate::FrontendError;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+
+/// Header of a `.r1cs` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1csHeader {
+    /// size in bytes of the field elements
+    pub field_size: usize,
+    /// prime of the field over which the circuit was compiled
+    pub prime: BigUint,
+    pub n_wires: usize,
+    pub n_pub_out: usize,
+    pub n_pub_in: usize,
+    pub n_prv_in: usize,
+    pub n_labels: u64,
+    pub n_constraints: usize,
+}
+
+impl R1csHeader {
+    /// Returns the sizes of the circuit: its number of constraints, of wires, and of public
+    /// signals (outputs and public inputs).
+    pub fn sizes(&self) -> (usize, usize, usize) {
+        (
+            self.n_constraints,
+            self.n_wires,
+            self.n_pub_out + self.n_pub_in,
+        )
+    }
+}
+
+/// Sparse matrix in compressed sparse row form: the entries of its `i`-th row are the
+/// `(columns[k], values[k])` for `k` in `row_offsets[i]..row_offsets[i + 1]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsrMatrix<F: PrimeField> {
+    pub row_offsets: Vec<usize>,
+    pub columns: Vec<u32>,
+    pub values: Vec<F>,
+}
+
+impl<F: PrimeField> CsrMatrix<F> {
+    fn with_capacity(n_rows: usize) -> Self {
+        let mut row_offsets = Vec::with_capacity(n_rows + 1);
+        row_offsets.push(0);
+        Self {
+            row_offsets,
+            columns: vec![],
+            values: vec![],
+        }
+    }
+
+    /// Returns the number of rows of the matrix.
+    pub fn n_rows(&self) -> usize {
+        self.row_offsets.len() - 1
+    }
+
+    /// Returns the `(column, value)` entries of the `i`-th row.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, F)> + '_ {
+        let range = self.row_offsets[i]..self.row_offsets[i + 1];
+        self.columns[range.clone()]
+            .iter()
+            .zip(&self.values[range])
+            .map(|(&column, &value)| (column as usize, value))
+    }
+}
+
+/// Constraints of a circom circuit, as the matrices `A`, `B` and `C` of its R1CS, whose columns
+/// are the wires of the circuit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircomR1cs<F: PrimeField> {
+    pub header: R1csHeader,
+    pub a: CsrMatrix<F>,
+    pub b: CsrMatrix<F>,
+    pub c: CsrMatrix<F>,
+}
+
+impl<F: PrimeField> CircomR1cs<F> {
+    /// Reads the constraints of the `.r1cs` file at `source` (or given as bytes).
+    pub fn read(source: &PathOrBin) -> Result<Self, Error> {
+        match source {
+            PathOrBin::Path(path) => Self::from_reader(BufReader::new(File::open(path)?)),
+            PathOrBin::Bin(bin) => Self::from_reader(Cursor::new(bin)),
+        }
+    }
+
+    /// Reads the constraints of a `.r1cs` file from `reader`, streaming its constraints section.
+    /// Fails with [`FrontendError::FieldMismatch`] if the circuit was compiled for a prime other than the
+    /// modulus of `F`.
+    pub fn from_reader<R: Read + Seek>(mut reader: R) -> Result<Self, Error> {
+        let sections = read_sections(&mut reader)?;
+        let header = read_header_section(&mut reader, &sections)?;
+        // the coefficients would otherwise be silently reduced modulo the order of F
+        let modulus: BigUint = F::MODULUS.into();
+        if header.prime != modulus {
+            return Err(FrontendError::FieldMismatch {
+                expected: modulus.to_string(),
+                found: header.prime.to_string(),
+            }
+            .into());
+        }
+
+        let (offset, size) = find_section(&sections, CONSTRAINTS_SECTION)?;
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut reader = reader.take(size);
+
+        // each constraint takes at least the 12 bytes of the number of terms of its rows, which
+        // bounds the capacity reserved for a malformed header
+        let n_rows = header.n_constraints.min(size as usize / 12);
+        let mut matrices = [(); 3].map(|_| CsrMatrix::with_capacity(n_rows));
+        let mut coeff = vec![0; header.field_size];
+        for _ in 0..header.n_constraints {
+            for matrix in matrices.iter_mut() {
+                let n_terms = read_u32(&mut reader)? as usize;
+                for _ in 0..n_terms {
+                    let wire = read_u32(&mut reader)?;
+                    if wire as usize >= header.n_wires {
+                        return Err(invalid(&format!("wire {} does not exist", wire)));
+                    }
+                    reader.read_exact(&mut coeff).map_err(truncated)?;
+                    matrix.columns.push(wire);
+                    matrix.values.push(F::from_le_bytes_mod_order(&coeff));
+                }
+                matrix.row_offsets.push(matrix.columns.len());
+            }
+        }
+        let [a, b, c] = matrices;
+        Ok(Self { header, a, b, c })
+    }
+
+    /// Returns the number of constraints.
+    pub fn n_constraints(&self) -> usize {
+        self.a.n_rows()
+    }
+}
+
+/// Reads the header of the `.r1cs` file at `source` (or given as bytes), skipping its
+/// constraints.
+pub fn read_r1cs_header(source: &PathOrBin) -> Result<R1csHeader, Error> {
+    match source {
+        PathOrBin::Path(path) => read_r1cs_header_from_reader(BufReader::new(File::open(path)?)),
+        PathOrBin::Bin(bin) => read_r1cs_header_from_reader(Cursor::new(bin)),
+    }
+}
+
+/// Reads the header of a `.r1cs` file from `reader`, seeking over the other sections.
+pub fn read_r1cs_header_from_reader<R: Read + Seek>(mut reader: R) -> Result<R1csHeader, Error> {
+    let sections = read_sections(&mut reader)?;
+    read_header_section(&mut reader, &sections)
+}
+
+/// Returns the number of constraints, of wires, and of public signals of the circuit of the
+/// `.r1cs` file at `source`, reading only its header.
+pub fn circuit_sizes(source: &PathOrBin) -> Result<(usize, usize, usize), Error> {
+    Ok(read_r1cs_header(source)?.sizes())
+}
+
+/// Reads the table of sections of the file, returning the type, offset and size of each section.
+fn read_sections<R: Read + Seek>(reader: &mut R) -> Result<Vec<(u32, u64, u64)>, Error> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic).map_err(truncated)?;
+    if &magic != b"r1cs" {
+        return Err(invalid("wrong magic"));
+    }
+    let version = read_u32(reader)?;
+    if version != 1 {
+        return Err(invalid(&format!("unsupported version {}", version)));
+    }
+    let n_sections = read_u32(reader)?;
+
+    let mut sections = vec![];
+    for _ in 0..n_sections {
+        let section_type = read_u32(reader)?;
+        let size = read_u64(reader)?;
+        let offset = reader.stream_position()?;
+        sections.push((section_type, offset, size));
+        reader.seek(SeekFrom::Start(offset + size))?;
+    }
+    Ok(sections)
+}
+
+fn find_section(sections: &[(u32, u64, u64)], section_type: u32) -> Result<(u64, u64), Error> {
+    sections
+        .iter()
+        .find(|(t, ..)| *t == section_type)
+        .map(|&(_, offset, size)| (offset, size))
+        .ok_or_else(|| invalid(&format!("missing section {}", section_type)))
+}
+
+fn read_header_section<R: Read + Seek>(
+    reader: &mut R,
+    sections: &[(u32, u64, u64)],
+) -> Result<R1csHeader, Error> {
+    let (offset, size) = find_section(sections, HEADER_SECTION)?;
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut reader = reader.take(size);
+
+    let field_size = read_u32(&mut reader)? as usize;
+    if field_size == 0 || field_size % 8 != 0 {
+        return Err(invalid(&format!("invalid field size {}", field_size)));
+    }
+    let mut prime = vec![0; field_size];
+    reader.read_exact(&mut prime).map_err(truncated)?;
+    Ok(R1csHeader {
+        field_size,
+        prime: BigUint::from_bytes_le(&prime),
+        n_wires: read_u32(&mut reader)? as usize,
+        n_pub_out: read_u32(&mut reader)? as usize,
+        n_pub_in: read_u32(&mut reader)? as usize,
+        n_prv_in: read_u32(&mut reader)? as usize,
+        n_labels: read_u64(&mut reader)?,
+        n_constraints: read_u32(&mut reader)? as usize,
+    })
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes).map_err(truncated)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, Error> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes).map_err(truncated)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::Other(format!("Invalid .r1cs file: {}", reason))
+}
+
+fn truncated(_: std::io::Error) -> Error {
+    invalid("unexpected end of section")
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fq, Fr};
+    use ark_circom::circom::r1cs_reader;
+    use ark_ff::BigInteger;
+    use std::path::PathBuf;
+
+    /// Builds a `.r1cs` file over BN254 with the given number of wires and constraints, each
+    /// constraint being `constraint(i)`, with the constraints section placed before the header.
+    pub fn synthetic_r1cs(
+        n_wires: u32,
+        n_pub_out: u32,
+        n_constraints: u32,
+        constraint: impl Fn(u32) -> [Vec<(u32, u64)>; 3],
+    ) -> Vec<u8> {
+        let mut constraints = vec![];
+        for i in 0..n_constraints {
+            for terms in constraint(i) {
+                constraints.extend((terms.len() as u32).to_le_bytes());
+                for (wire, coeff) in terms {
+                    constraints.extend(wire.to_le_bytes());
+                    constraints.extend(Fr::from(coeff).into_bigint().to_bytes_le());
+                }
+            }
+        }
+        let header = [
+            32u32.to_le_bytes().to_vec(),
+            Fr::MODULUS.to_bytes_le(),
+            n_wires.to_le_bytes().to_vec(),
+            n_pub_out.to_le_bytes().to_vec(),
+            1u32.to_le_bytes().to_vec(), // public inputs
+            0u32.to_le_bytes().to_vec(), // private inputs
+            (n_wires as u64).to_le_bytes().to_vec(),
+            n_constraints.to_le_bytes().to_vec(),
+        ]
+        .concat();
+        [
+            b"r1cs".to_vec(),
+            1u32.to_le_bytes().to_vec(),
+            2u32.to_le_bytes().to_vec(),
+            CONSTRAINTS_SECTION.to_le_bytes().to_vec(),
+            (constraints.len() as u64).to_le_bytes().to_vec(),
+            constraints,
+            HEADER_SECTION.to_le_bytes().to_vec(),
+            (header.len() as u64).to_le_bytes().to_vec(),
+            header,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_r1cs_matches_ark_circom() -> Result<(), Error> {
+        for name in [
+            "cubic_circuit",
+            "with_external_inputs",
+            "no_external_inputs",
+        ] {
+            let path = PathBuf::from(format!("./src/circom/test_folder/{}.r1cs", name));
+            let r1cs = CircomR1cs::<Fr>::read(&path.clone().into())?;
+            let expected = r1cs_reader::R1CS::<Fr>::from(r1cs_reader::R1CSFile::new(
+                BufReader::new(File::open(&path)?),
+            )?);
+
+            assert_eq!(r1cs.n_constraints(), expected.constraints.len());
+            assert_eq!(r1cs.header.n_wires, expected.num_variables);
+            for (i, (a, b, c)) in expected.constraints.iter().enumerate() {
+                assert_eq!(r1cs.a.row(i).collect::<Vec<_>>(), *a);
+                assert_eq!(r1cs.b.row(i).collect::<Vec<_>>(), *b);
+                assert_eq!(r1cs.c.row(i).collect::<Vec<_>>(), *c);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_r1cs_header_only() -> Result<(), Error> {
+        // counts the bytes read, to check that the constraints are skipped
+        struct CountingReader<R> {
+            inner: R,
+            read: usize,
+        }
+        impl<R: Read> Read for CountingReader<R> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let n = self.inner.read(buf)?;
+                self.read += n;
+                Ok(n)
+            }
+        }
+        impl<R: Seek> Seek for CountingReader<R> {
+            fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+                self.inner.seek(pos)
+            }
+        }
+
+        let n_constraints = 1 << 16;
+        let bytes = synthetic_r1cs(n_constraints + 2, 1, n_constraints, |i| {
+            [
+                vec![(i + 1, 1), (0, 3)],
+                vec![(i + 2, i as u64)],
+                vec![(i + 1, 2)],
+            ]
+        });
+        let mut reader = CountingReader {
+            inner: Cursor::new(&bytes),
+            read: 0,
+        };
+        let header = read_r1cs_header_from_reader(&mut reader)?;
+        assert_eq!(
+            header.sizes(),
+            (n_constraints as usize, n_constraints as usize + 2, 2)
+        );
+        assert_eq!(header.prime, BigUint::from(Fr::MODULUS));
+        assert!(reader.read < 128);
+
+        let r1cs = CircomR1cs::<Fr>::from_reader(Cursor::new(&bytes))?;
+        assert_eq!(r1cs.n_constraints(), n_constraints as usize);
+        assert_eq!(r1cs.a.values.len(), 2 * n_constraints as usize);
+        assert_eq!(r1cs.b.row(7).collect::<Vec<_>>(), vec![(9, Fr::from(7u32))]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_r1cs_is_rejected() {
+        let bytes = synthetic_r1cs(3, 1, 1, |_| [vec![(1, 1)], vec![(2, 1)], vec![(3, 1)]]);
+        // wire 3 does not exist
+        assert!(matches!(
+            CircomR1cs::<Fr>::from_reader(Cursor::new(&bytes)),
+            Err(Error::Other(_))
+        ));
+        assert!(matches!(
+            read_r1cs_header_from_reader(Cursor::new(&bytes[..10])),
+            Err(Error::Other(_))
+        ));
+        assert!(matches!(
+            read_r1cs_header_from_reader(Cursor::new(b"wtns")),
+            Err(Error::Other(_))
+        ));
+    }
+
+    #[test]
+    fn test_wrong_prime_is_rejected() {
+        // the header declares the BN254 scalar field, while the constraints are read over its
+        // base field
+        let bytes = synthetic_r1cs(3, 1, 1, |_| [vec![(1, 1)], vec![(2, 1)], vec![(0, 1)]]);
+        let Some(Ok(FrontendError::FieldMismatch { expected, found })) =
+            CircomR1cs::<Fq>::from_reader(Cursor::new(&bytes))
+                .err()
+                .map(FrontendError::try_from)
+        else {
+            panic!("the prime of the header is not checked");
+        };
+        assert_eq!(expected, Fq::MODULUS.to_string());
+        assert_eq!(found, Fr::MODULUS.to_string());
+    }
+}
@@ -1,4 +1,9 @@
 #!/bin/bash
 circom ./experimental-frontends/src/circom/test_folder/cubic_circuit.circom --r1cs --sym --wasm --prime bn128 --output ./experimental-frontends/src/circom/test_folder/
-circom ./experimental-frontends/src/circom/test_folder/with_external_inputs.circom --r1cs --sym --wasm --prime bn128 --output ./experimental-frontends/src/circom/test_folder/
+circom ./experimental-frontends/src/circom/test_folder/with_external_inputs.circom --r1cs --sym --wasm --c --prime bn128 --output ./experimental-frontends/src/circom/test_folder/
 circom ./experimental-frontends/src/circom/test_folder/no_external_inputs.circom --r1cs --sym --wasm --prime bn128 --output ./experimental-frontends/src/circom/test_folder/
+circom ./experimental-frontends/src/circom/test_folder/with_named_inputs.circom --r1cs --sym --wasm --prime bn128 --output ./experimental-frontends/src/circom/test_folder/
+circom ./experimental-frontends/src/circom/test_folder/with_aux_outputs.circom --r1cs --sym --wasm --prime bn128 --output ./experimental-frontends/src/circom/test_folder/
+mkdir -p ./experimental-frontends/src/circom/test_folder/pallas/
+circom ./experimental-frontends/src/circom/test_folder/cubic_circuit.circom --r1cs --prime pallas --output ./experimental-frontends/src/circom/test_folder/pallas/
+make -C ./experimental-frontends/src/circom/test_folder/with_external_inputs_cpp/
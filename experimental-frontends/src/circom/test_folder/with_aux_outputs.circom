@@ -0,0 +1,13 @@
+pragma circom 2.0.3;
+
+template WithAuxOutputs () {
+    signal input ivc_input[2];
+    signal output aux;
+    signal output ivc_output[2];
+
+    ivc_output[0] <== ivc_input[0] + ivc_input[1];
+    ivc_output[1] <== ivc_input[0] * ivc_input[1];
+    aux <== ivc_output[0] * ivc_output[1];
+}
+
+component main {public [ivc_input]} = WithAuxOutputs();
@@ -0,0 +1,15 @@
+pragma circom 2.0.3;
+
+template WithNamedInputs () {
+    signal input ivc_input[1];
+    signal input counter;
+    signal input plaintext[2];
+    signal output ivc_output[1];
+
+    signal temp;
+
+    temp <== ivc_input[0] * plaintext[0];
+    ivc_output[0] <== temp * counter + plaintext[1];
+}
+
+component main {public [ivc_input]} = WithNamedInputs();
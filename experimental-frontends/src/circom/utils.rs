@@ -1,4 +1,11 @@
-use std::{fs::File, io::Cursor, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, Cursor},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use ark_circom::{
     circom::{r1cs_reader, R1CS},
@@ -6,50 +13,59 @@ use ark_circom::{
 };
 use ark_ff::PrimeField;
 use ark_serialize::Read;
-use num_bigint::BigInt;
+use num_bigint::{BigInt, BigUint};
 use tokio::runtime::Builder;
 use wasmer::{Module, Store};
 
+use super::r1cs::CircomR1cs;
 use folding_schemes::{utils::PathOrBin, Error};
 
+/// Witness generator of a Circom circuit.
+#[derive(Clone, Debug)]
+pub enum WitnessBackend {
+    /// WebAssembly witness generator, output by `circom --wasm` and executed with wasmer.
+    Wasm(PathOrBin),
+    /// Native witness generator, output by `circom --c` and compiled with the `Makefile` of its
+    /// `<circuit>_cpp` directory, which is much faster than the WebAssembly one for large
+    /// circuits. It is executed as `<binary> <input.json> <witness.wtns>`.
+    NativeBinary(PathBuf),
+}
+
 // A struct that wraps Circom functionalities, allowing for extraction of R1CS and witnesses
-// based on file paths to Circom's .r1cs and .wasm.
+// based on file paths to Circom's .r1cs and to its witness generator.
 #[derive(Clone, Debug)]
 pub struct CircomWrapper {
-    r1csfile_bytes: Vec<u8>,
-    wasmfile_bytes: Vec<u8>,
+    // the .r1cs file is only read when extracting the R1CS, so that it is not held in memory
+    r1cs: PathOrBin,
+    witness_backend: WitnessBackend,
 }
 
 impl CircomWrapper {
     // Creates a new instance of the CircomWrapper with the file paths.
     pub fn new(r1cs: PathOrBin, wasm: PathOrBin) -> Result<Self, Error> {
-        match (r1cs, wasm) {
-            (PathOrBin::Path(r1cs_path), PathOrBin::Path(wasm_path)) => {
-                Self::new_from_path(r1cs_path, wasm_path)
-            }
-            (PathOrBin::Bin(r1cs_bin), PathOrBin::Bin(wasm_bin)) => Ok(Self {
-                r1csfile_bytes: r1cs_bin,
-                wasmfile_bytes: wasm_bin,
-            }),
-            _ => unreachable!("You should pass the same enum branch for both inputs"),
-        }
+        Self::new_with_witness_backend(r1cs, WitnessBackend::Wasm(wasm))
     }
 
-    // Creates a new instance of the CircomWrapper with the file paths.
-    fn new_from_path(r1cs_file_path: PathBuf, wasm_file_path: PathBuf) -> Result<Self, Error> {
-        let mut file = File::open(r1cs_file_path)?;
-        let metadata = File::metadata(&file)?;
-        let mut r1csfile_bytes = vec![0; metadata.len() as usize];
-        file.read_exact(&mut r1csfile_bytes)?;
-
-        let mut file = File::open(wasm_file_path)?;
-        let metadata = File::metadata(&file)?;
-        let mut wasmfile_bytes = vec![0; metadata.len() as usize];
-        file.read_exact(&mut wasmfile_bytes)?;
-
+    // Creates a new instance of the CircomWrapper computing the witnesses with the given backend.
+    pub fn new_with_witness_backend(
+        r1cs: PathOrBin,
+        witness_backend: WitnessBackend,
+    ) -> Result<Self, Error> {
+        let witness_backend = match witness_backend {
+            WitnessBackend::Wasm(PathOrBin::Path(wasm_path)) => {
+                WitnessBackend::Wasm(PathOrBin::Bin(read_file(wasm_path)?))
+            }
+            WitnessBackend::NativeBinary(binary) if !binary.is_file() => {
+                return Err(Error::WitnessCalculationError(format!(
+                    "{} is not a Circom witness generator",
+                    binary.display()
+                )));
+            }
+            witness_backend => witness_backend,
+        };
         Ok(CircomWrapper {
-            r1csfile_bytes,
-            wasmfile_bytes,
+            r1cs,
+            witness_backend,
         })
     }
 
@@ -59,8 +75,7 @@ impl CircomWrapper {
         inputs: Vec<(String, Vec<BigInt>)>,
     ) -> Result<(R1CS<F>, Option<Vec<F>>), Error> {
         // Extracts the R1CS
-        let r1cs_file = r1cs_reader::R1CSFile::new(Cursor::new(&self.r1csfile_bytes))?;
-        let r1cs = r1cs_reader::R1CS::from(r1cs_file);
+        let r1cs = self.read_r1cs()?;
 
         // Extracts the witness vector
         let witness_vec = self.extract_witness(inputs)?;
@@ -69,12 +84,24 @@ impl CircomWrapper {
     }
 
     pub fn extract_r1cs<F: PrimeField>(&self) -> Result<R1CS<F>, Error> {
-        let r1cs_file = r1cs_reader::R1CSFile::new(Cursor::new(&self.r1csfile_bytes))?;
-        let mut r1cs = r1cs_reader::R1CS::from(r1cs_file);
+        let mut r1cs = self.read_r1cs()?;
         r1cs.wire_mapping = None;
         Ok(r1cs)
     }
 
+    // Extracts the constraints of the R1CS as sparse matrices, streaming the .r1cs file.
+    pub fn extract_sparse_r1cs<F: PrimeField>(&self) -> Result<CircomR1cs<F>, Error> {
+        CircomR1cs::read(&self.r1cs)
+    }
+
+    fn read_r1cs<F: PrimeField>(&self) -> Result<R1CS<F>, Error> {
+        let r1cs_file = match &self.r1cs {
+            PathOrBin::Path(path) => r1cs_reader::R1CSFile::new(BufReader::new(File::open(path)?))?,
+            PathOrBin::Bin(bin) => r1cs_reader::R1CSFile::new(Cursor::new(bin))?,
+        };
+        Ok(r1cs_reader::R1CS::from(r1cs_file))
+    }
+
     // Extracts the witness vector as a vector of PrimeField elements.
     pub fn extract_witness<F: PrimeField>(
         &self,
@@ -96,17 +123,33 @@ impl CircomWrapper {
             .collect()
     }
 
-    // Calculates the witness given the Wasm filepath and inputs.
+    // Calculates the witness given the inputs, with the witness generator of the wrapper.
     pub fn calculate_witness(
         &self,
         inputs: Vec<(String, Vec<BigInt>)>,
+    ) -> Result<Vec<BigInt>, Error> {
+        match &self.witness_backend {
+            WitnessBackend::Wasm(PathOrBin::Bin(wasmfile_bytes)) => {
+                Self::calculate_wasm_witness(wasmfile_bytes, inputs)
+            }
+            WitnessBackend::Wasm(PathOrBin::Path(wasm_path)) => {
+                Self::calculate_wasm_witness(&read_file(wasm_path.clone())?, inputs)
+            }
+            WitnessBackend::NativeBinary(binary) => Self::calculate_native_witness(binary, inputs),
+        }
+    }
+
+    // Calculates the witness given the bytes of the Wasm witness generator and the inputs.
+    fn calculate_wasm_witness(
+        wasmfile_bytes: &[u8],
+        inputs: Vec<(String, Vec<BigInt>)>,
     ) -> Result<Vec<BigInt>, Error> {
         Builder::new_current_thread()
             .build()
             .unwrap()
             .block_on(async {
                 let mut store = Store::default();
-                let module = Module::new(&store, &self.wasmfile_bytes).map_err(|e| {
+                let module = Module::new(&store, wasmfile_bytes).map_err(|e| {
                     Error::WitnessCalculationError(format!("Failed to create Wasm module: {}", e))
                 })?;
                 let mut calculator =
@@ -126,6 +169,129 @@ impl CircomWrapper {
                     })
             })
     }
+
+    // Calculates the witness by executing the native witness generator `binary`, which reads the
+    // inputs from a JSON file and writes the witness to a `.wtns` file.
+    fn calculate_native_witness(
+        binary: &Path,
+        inputs: Vec<(String, Vec<BigInt>)>,
+    ) -> Result<Vec<BigInt>, Error> {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "sonobe_circom_{}_{}",
+            std::process::id(),
+            CALLS.fetch_add(1, Ordering::Relaxed)
+        );
+        let input_path = std::env::temp_dir().join(format!("{}.json", name));
+        let witness_path = std::env::temp_dir().join(format!("{}.wtns", name));
+
+        // the values are given as decimal strings, since they do not fit in JSON numbers
+        let inputs: HashMap<String, Vec<String>> = inputs
+            .into_iter()
+            .map(|(name, values)| (name, values.iter().map(BigInt::to_string).collect()))
+            .collect();
+        fs::write(
+            &input_path,
+            serde_json::to_vec(&inputs).map_err(|e| Error::JSONSerdeError(e.to_string()))?,
+        )?;
+        let output = Command::new(binary)
+            .arg(&input_path)
+            .arg(&witness_path)
+            .output();
+        let _ = fs::remove_file(&input_path);
+        let output = output?;
+        if !output.status.success() {
+            let _ = fs::remove_file(&witness_path);
+            return Err(Error::WitnessCalculationError(format!(
+                "{} failed: {}",
+                binary.display(),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let wtns = fs::read(&witness_path);
+        let _ = fs::remove_file(&witness_path);
+        Ok(read_wtns(&wtns?)?.1)
+    }
+}
+
+fn read_file(path: PathBuf) -> Result<Vec<u8>, Error> {
+    let mut file = File::open(path)?;
+    let metadata = File::metadata(&file)?;
+    let mut bytes = vec![0; metadata.len() as usize];
+    file.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads a `.wtns` file, as written by the witness generators of Circom, returning the prime of
+/// the field of the circuit and the witness.
+///
+/// The file starts with the magic `wtns`, a version and a number of sections, followed by the
+/// sections, each made of its type, its size and its content, with all the integers in little
+/// endian. The header section (type 1) holds the size in bytes of the field elements, the prime
+/// and the number of elements of the witness, and the witness section (type 2) the elements of the
+/// witness.
+fn read_wtns(bytes: &[u8]) -> Result<(BigUint, Vec<BigInt>), Error> {
+    let invalid = |reason: &str| {
+        Error::WitnessCalculationError(format!("Invalid .wtns witness file: {}", reason))
+    };
+    let mut reader = Cursor::new(bytes);
+    let mut read = |len: u64| -> Result<Vec<u8>, Error> {
+        // the lengths are read from the file, so they are checked against what is left of it
+        // before allocating
+        if len > bytes.len() as u64 - reader.position() {
+            return Err(invalid("unexpected end of file"));
+        }
+        let mut buf = vec![0; len as usize];
+        reader
+            .read_exact(&mut buf)
+            .map_err(|_| invalid("unexpected end of file"))?;
+        Ok(buf)
+    };
+    let le_u32 = |bytes: &[u8]| u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+
+    if read(4)? != b"wtns" {
+        return Err(invalid("wrong magic"));
+    }
+    let _version = le_u32(&read(4)?);
+    let n_sections = le_u32(&read(4)?);
+
+    let mut header = None;
+    let mut witness = None;
+    for _ in 0..n_sections {
+        let section_type = le_u32(&read(4)?);
+        let section_size = u64::from_le_bytes(read(8)?.try_into().unwrap());
+        let section = read(section_size)?;
+        match section_type {
+            1 => {
+                let n8 = if section.len() >= 4 {
+                    le_u32(&section)
+                } else {
+                    0
+                };
+                if n8 == 0 || section.len() != 4 + n8 + 4 {
+                    return Err(invalid("malformed header"));
+                }
+                let prime = BigUint::from_bytes_le(&section[4..4 + n8]);
+                header = Some((n8, prime, le_u32(&section[4 + n8..])));
+            }
+            2 => witness = Some(section),
+            _ => {}
+        }
+    }
+
+    let (n8, prime, n_witness) = header.ok_or_else(|| invalid("missing header"))?;
+    let witness = witness.ok_or_else(|| invalid("missing witness"))?;
+    if witness.len() != n8 * n_witness {
+        return Err(invalid("wrong witness length"));
+    }
+    Ok((
+        prime,
+        witness
+            .chunks(n8)
+            .map(|element| BigInt::from(BigUint::from_bytes_le(element)))
+            .collect(),
+    ))
 }
 
 #[cfg(test)]
@@ -157,6 +323,47 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_read_wtns() -> Result<(), Error> {
+        let prime = BigUint::from(101u32);
+        let n8 = 4u32;
+        let wtns = [
+            b"wtns".to_vec(),
+            2u32.to_le_bytes().to_vec(), // version
+            2u32.to_le_bytes().to_vec(), // number of sections
+            1u32.to_le_bytes().to_vec(), // header
+            12u64.to_le_bytes().to_vec(),
+            n8.to_le_bytes().to_vec(),
+            101u32.to_le_bytes().to_vec(),
+            3u32.to_le_bytes().to_vec(), // number of elements
+            2u32.to_le_bytes().to_vec(), // witness
+            12u64.to_le_bytes().to_vec(),
+            [1u32, 35, 100].map(u32::to_le_bytes).concat(),
+        ]
+        .concat();
+        assert_eq!(
+            read_wtns(&wtns)?,
+            (prime, [1, 35, 100].map(BigInt::from).to_vec())
+        );
+
+        assert!(matches!(
+            read_wtns(&wtns[..wtns.len() - 1]),
+            Err(Error::WitnessCalculationError(_))
+        ));
+        assert!(matches!(
+            read_wtns(b"r1cs"),
+            Err(Error::WitnessCalculationError(_))
+        ));
+        // a section larger than the file is rejected before allocating it
+        let mut huge_section = wtns.clone();
+        huge_section[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            read_wtns(&huge_section),
+            Err(Error::WitnessCalculationError(_))
+        ));
+        Ok(())
+    }
+
     // Test the satisfication by using the CircomWrapper
     #[test]
     fn test_extract_r1cs_and_witness() -> Result<(), Error> {
@@ -0,0 +1,347 @@
+//! AES-128 block cipher ([FIPS-197](https://csrc.nist.gov/pubs/fips/197/final)) as R1CS
+//! constraints over bytes, with the S-box lowered to constraints as a lookup in its 256-entry
+//! table, together with its native counterpart and the CTR mode of
+//! [SP 800-38A](https://csrc.nist.gov/pubs/sp/800/38/a/final).
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::AllocVar, boolean::Boolean, convert::ToBitsGadget, eq::EqGadget, fields::fp::FpVar,
+    select::CondSelectGadget, uint8::UInt8, R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+use core::{array, ops::BitXor};
+
+/// The S-box of AES.
+pub const AES_SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// Round constants of the key expansion of AES-128.
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Substitutes `x` by its entry in the S-box, selected among the 256 constants of the table by
+/// the bits of `x`, which costs 127 constraints, followed by the decomposition of the entry in 8
+/// bits, which costs 9 constraints.
+pub fn aes_sbox_gadget<F: PrimeField>(x: &UInt8<F>) -> Result<UInt8<F>, SynthesisError> {
+    // the position of the selection is big-endian
+    let mut position = x.to_bits_le()?;
+    position.reverse();
+    let table: Vec<FpVar<F>> = AES_SBOX
+        .iter()
+        .map(|&entry| FpVar::constant(F::from(entry)))
+        .collect();
+    let y = FpVar::conditionally_select_power_of_two_vector(&position, &table)?;
+    if let FpVar::Constant(c) = y {
+        return Ok(UInt8::constant(c.into_bigint().as_ref()[0] as u8));
+    }
+    let cs = y.cs();
+    let bits = (0..8)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok(y.value()?.into_bigint().get_bit(i))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp(&bits)?.enforce_equal(&y)?;
+    Ok(UInt8::from_bits_le(&bits))
+}
+
+/// Expands the key into the 11 round keys of AES-128.
+pub fn aes128_key_expansion_gadget<F: PrimeField>(
+    key: &[UInt8<F>; 16],
+) -> Result<[[UInt8<F>; 16]; 11], SynthesisError> {
+    let mut w = key.to_vec();
+    for i in 4..44 {
+        let mut t = w[4 * (i - 1)..4 * i].to_vec();
+        if i % 4 == 0 {
+            // RotWord, SubWord and the round constant
+            t.rotate_left(1);
+            t = t.iter().map(aes_sbox_gadget).collect::<Result<_, _>>()?;
+            t[0] = xor(&t[0], &UInt8::constant(RCON[i / 4 - 1]))?;
+        }
+        for j in 0..4 {
+            let byte = xor(&w[4 * (i - 4) + j], &t[j])?;
+            w.push(byte);
+        }
+    }
+    Ok(array::from_fn(|round| {
+        array::from_fn(|j| w[16 * round + j].clone())
+    }))
+}
+
+/// Encrypts a block with the 11 round keys of AES-128. The block and the round keys are laid out
+/// as in FIPS-197, i.e. the state is filled column by column.
+pub fn aes128_encrypt_gadget<F: PrimeField>(
+    round_keys: &[[UInt8<F>; 16]; 11],
+    block: &[UInt8<F>; 16],
+) -> Result<[UInt8<F>; 16], SynthesisError> {
+    let mut state = add_round_key(block, &round_keys[0])?;
+    for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+        let substituted = state
+            .iter()
+            .map(aes_sbox_gadget)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut shifted: [UInt8<F>; 16] = shift_rows(&substituted);
+        if round != 10 {
+            shifted = mix_columns(&shifted)?;
+        }
+        state = add_round_key(&shifted, round_key)?;
+    }
+    Ok(state)
+}
+
+fn add_round_key<F: PrimeField>(
+    state: &[UInt8<F>; 16],
+    round_key: &[UInt8<F>; 16],
+) -> Result<[UInt8<F>; 16], SynthesisError> {
+    let bytes = state
+        .iter()
+        .zip(round_key)
+        .map(|(a, b)| xor(a, b))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(array::from_fn(|j| bytes[j].clone()))
+}
+
+/// Rotates the row `r` of the state by `r` bytes to the left, which only rewires the bytes.
+fn shift_rows<T: Clone>(state: &[T]) -> [T; 16] {
+    array::from_fn(|j| {
+        let (row, column) = (j % 4, j / 4);
+        state[row + 4 * ((column + row) % 4)].clone()
+    })
+}
+
+fn mix_columns<F: PrimeField>(state: &[UInt8<F>; 16]) -> Result<[UInt8<F>; 16], SynthesisError> {
+    let mut mixed = Vec::with_capacity(16);
+    for column in state.chunks(4) {
+        let doubled = column.iter().map(xtime).collect::<Result<Vec<_>, _>>()?;
+        for i in 0..4 {
+            // 2 a_i + 3 a_{i+1} + a_{i+2} + a_{i+3}
+            let mut byte = xor(&doubled[i], &doubled[(i + 1) % 4])?;
+            for a in [
+                &column[(i + 1) % 4],
+                &column[(i + 2) % 4],
+                &column[(i + 3) % 4],
+            ] {
+                byte = xor(&byte, a)?;
+            }
+            mixed.push(byte);
+        }
+    }
+    Ok(array::from_fn(|j| mixed[j].clone()))
+}
+
+/// Multiplication by `x` in GF(2^8), i.e. the left shift reduced by `x^8 + x^4 + x^3 + x + 1`.
+fn xtime<F: PrimeField>(a: &UInt8<F>) -> Result<UInt8<F>, SynthesisError> {
+    let bits = a.to_bits_le()?;
+    let carry = &bits[7];
+    let shifted = [
+        carry.clone(),
+        bits[0].clone().bitxor(carry),
+        bits[1].clone(),
+        bits[2].clone().bitxor(carry),
+        bits[3].clone().bitxor(carry),
+        bits[4].clone(),
+        bits[5].clone(),
+        bits[6].clone(),
+    ];
+    Ok(UInt8::from_bits_le(&shifted))
+}
+
+fn xor<F: PrimeField>(a: &UInt8<F>, b: &UInt8<F>) -> Result<UInt8<F>, SynthesisError> {
+    let a_bits = a.to_bits_le()?;
+    let b_bits = b.to_bits_le()?;
+    let result_bits: Vec<Boolean<F>> = a_bits
+        .iter()
+        .zip(&b_bits)
+        .map(|(a_bit, b_bit)| a_bit.clone().bitxor(b_bit))
+        .collect();
+    Ok(UInt8::from_bits_le(&result_bits))
+}
+
+/// Native counterpart of [`aes128_key_expansion_gadget`].
+pub fn aes128_key_expansion_native(key: [u8; 16]) -> [[u8; 16]; 11] {
+    let mut w = key.to_vec();
+    for i in 4..44 {
+        let mut t = [w[4 * i - 4], w[4 * i - 3], w[4 * i - 2], w[4 * i - 1]];
+        if i % 4 == 0 {
+            t.rotate_left(1);
+            t = t.map(|byte| AES_SBOX[byte as usize]);
+            t[0] ^= RCON[i / 4 - 1];
+        }
+        for j in 0..4 {
+            w.push(w[4 * (i - 4) + j] ^ t[j]);
+        }
+    }
+    array::from_fn(|round| array::from_fn(|j| w[16 * round + j]))
+}
+
+/// Native counterpart of [`aes128_encrypt_gadget`].
+pub fn aes128_encrypt_native(round_keys: &[[u8; 16]; 11], block: [u8; 16]) -> [u8; 16] {
+    let mut state: [u8; 16] = array::from_fn(|j| block[j] ^ round_keys[0][j]);
+    for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+        let mut shifted = shift_rows(&state.map(|byte| AES_SBOX[byte as usize]));
+        if round != 10 {
+            shifted = mix_columns_native(shifted);
+        }
+        state = array::from_fn(|j| shifted[j] ^ round_key[j]);
+    }
+    state
+}
+
+fn mix_columns_native(state: [u8; 16]) -> [u8; 16] {
+    let xtime = |a: u8| (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 };
+    array::from_fn(|j| {
+        let (i, column) = (j % 4, &state[j - j % 4..j - j % 4 + 4]);
+        xtime(column[i])
+            ^ xtime(column[(i + 1) % 4])
+            ^ column[(i + 1) % 4]
+            ^ column[(i + 2) % 4]
+            ^ column[(i + 3) % 4]
+    })
+}
+
+/// Encrypts (or decrypts) `data` with AES-128 in CTR mode from the initial counter block, where
+/// the counter is incremented modulo 2^32 in the last 4 bytes of the block (the `inc32` function
+/// of GCM).
+pub fn aes128_ctr_native(key: [u8; 16], counter_block: [u8; 16], data: &[u8]) -> Vec<u8> {
+    let round_keys = aes128_key_expansion_native(key);
+    let mut counter_block = counter_block;
+    let mut output = Vec::with_capacity(data.len());
+    for block in data.chunks(16) {
+        let keystream = aes128_encrypt_native(&round_keys, counter_block);
+        output.extend(block.iter().zip(keystream).map(|(m, k)| m ^ k));
+        let counter = u32::from_be_bytes(array::from_fn(|j| counter_block[12 + j]));
+        counter_block[12..].copy_from_slice(&counter.wrapping_add(1).to_be_bytes());
+    }
+    output
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+
+    fn hex_to_bytes<const N: usize>(hex: &str) -> [u8; N] {
+        array::from_fn(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+    }
+
+    fn bytes_var<const N: usize>(
+        cs: ConstraintSystemRef<Fr>,
+        bytes: [u8; N],
+    ) -> Result<[UInt8<Fr>; N], SynthesisError> {
+        let bytes = bytes
+            .iter()
+            .map(|&b| UInt8::new_witness(cs.clone(), || Ok(b)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(array::from_fn(|i| bytes[i].clone()))
+    }
+
+    #[test]
+    fn test_aes_sbox() -> Result<(), SynthesisError> {
+        // FIPS-197 5.1.1
+        assert_eq!(AES_SBOX[0x00], 0x63);
+        assert_eq!(AES_SBOX[0x53], 0xed);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        for x in 0..=255u8 {
+            let x_var = UInt8::new_witness(cs.clone(), || Ok(x))?;
+            let num_constraints = cs.num_constraints();
+            assert_eq!(aes_sbox_gadget(&x_var)?.value()?, AES_SBOX[x as usize]);
+            assert_eq!(cs.num_constraints() - num_constraints, 127 + 9);
+        }
+        assert!(cs.is_satisfied()?);
+        assert_eq!(
+            aes_sbox_gadget(&UInt8::<Fr>::constant(0x53))?.value()?,
+            0xed
+        );
+        Ok(())
+    }
+
+    /// Test vectors of FIPS-197 Appendices A.1, B and C.1
+    #[test]
+    fn test_aes128_native() {
+        let round_keys =
+            aes128_key_expansion_native(hex_to_bytes("2b7e151628aed2a6abf7158809cf4f3c"));
+        assert_eq!(
+            round_keys[10],
+            hex_to_bytes("d014f9a8c9ee2589e13f0cc8b6630ca6")
+        );
+        assert_eq!(
+            aes128_encrypt_native(
+                &round_keys,
+                hex_to_bytes("3243f6a8885a308d313198a2e0370734")
+            ),
+            hex_to_bytes::<16>("3925841d02dc09fbdc118597196a0b32")
+        );
+
+        let round_keys = aes128_key_expansion_native(array::from_fn(|i| i as u8));
+        assert_eq!(
+            aes128_encrypt_native(
+                &round_keys,
+                hex_to_bytes("00112233445566778899aabbccddeeff")
+            ),
+            hex_to_bytes::<16>("69c4e0d86a7b0430d8cdb78070b4c55a")
+        );
+    }
+
+    /// Test vectors of FIPS-197 Appendices B and C.1, with the key expanded in-circuit
+    #[test]
+    fn test_aes128() -> Result<(), SynthesisError> {
+        for (key, plaintext, ciphertext) in [
+            (
+                "2b7e151628aed2a6abf7158809cf4f3c",
+                "3243f6a8885a308d313198a2e0370734",
+                "3925841d02dc09fbdc118597196a0b32",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f",
+                "00112233445566778899aabbccddeeff",
+                "69c4e0d86a7b0430d8cdb78070b4c55a",
+            ),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let key = bytes_var(cs.clone(), hex_to_bytes::<16>(key))?;
+            let plaintext = bytes_var(cs.clone(), hex_to_bytes::<16>(plaintext))?;
+            let round_keys = aes128_key_expansion_gadget(&key)?;
+            let output = aes128_encrypt_gadget(&round_keys, &plaintext)?;
+            assert_eq!(output.value()?, hex_to_bytes::<16>(ciphertext));
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    /// Test vector of SP 800-38A F.5.1 (CTR-AES128.Encrypt)
+    #[test]
+    fn test_aes128_ctr_native() {
+        let plaintext: [u8; 64] = hex_to_bytes(
+            "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+        );
+        let ciphertext: [u8; 64] = hex_to_bytes(
+            "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+             5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee",
+        );
+        let key = hex_to_bytes("2b7e151628aed2a6abf7158809cf4f3c");
+        let counter_block = hex_to_bytes("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        assert_eq!(
+            aes128_ctr_native(key, counter_block, &plaintext),
+            ciphertext
+        );
+        assert_eq!(
+            aes128_ctr_native(key, counter_block, &ciphertext),
+            plaintext
+        );
+    }
+}
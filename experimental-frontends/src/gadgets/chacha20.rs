@@ -0,0 +1,789 @@
+//! ChaCha20 block function ([RFC 7539](https://www.rfc-editor.org/rfc/rfc7539)) as R1CS
+//! constraints over 32-bit words, together with its native counterpart, its variants with the
+//! original 64-bit nonce and the extended nonce of XChaCha20, and the HChaCha20 subkey derivation
+//! ([draft-irtf-cfrg-xchacha](https://datatracker.ietf.org/doc/draft-irtf-cfrg-xchacha/)).
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::AllocVar, convert::ToBitsGadget, fields::fp::FpVar, uint32::UInt32, uint64::UInt64,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use super::u32ops::{rotl, unpack_fp_to_u32s, xor};
+
+/// The constants `"expand 32-byte k"` of the first row of the ChaCha20 state.
+pub const CHACHA20_CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// Layout of the nonce and of the block counter in the ChaCha20 state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChaCha20Variant {
+    /// 96-bit nonce and 32-bit counter of RFC 7539.
+    #[default]
+    Ietf,
+    /// 64-bit nonce and 64-bit counter of the original ChaCha20, with the counter given as its
+    /// low and high words.
+    Original,
+    /// 192-bit nonce and 32-bit counter of XChaCha20, where the block is the one of the IETF
+    /// variant with the HChaCha20 subkey of the key and the first 4 words of the nonce, and the
+    /// nonce `[0, nonce[4], nonce[5]]`. Notice that the subkey is derived again for every block,
+    /// which doubles the constraints of the block function.
+    XChaCha20,
+}
+
+impl ChaCha20Variant {
+    /// Number of 32-bit words of the nonce.
+    pub fn nonce_len(&self) -> usize {
+        match self {
+            Self::Ietf => 3,
+            Self::Original => 2,
+            Self::XChaCha20 => 6,
+        }
+    }
+
+    /// Number of 32-bit words of the block counter, least significant first.
+    pub fn counter_len(&self) -> usize {
+        match self {
+            Self::Original => 2,
+            Self::Ietf | Self::XChaCha20 => 1,
+        }
+    }
+
+    /// Computes the keystream block for the given key, nonce and block counter, whose lengths
+    /// must be [`Self::nonce_len`] and [`Self::counter_len`].
+    pub fn block_gadget<F: PrimeField>(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        key: &[UInt32<F>; 8],
+        nonce: &[UInt32<F>],
+        counter: &[UInt32<F>],
+    ) -> Result<[UInt32<F>; 16], SynthesisError> {
+        if nonce.len() != self.nonce_len() || counter.len() != self.counter_len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        match self {
+            Self::Ietf => chacha20_block_gadget(
+                cs,
+                key,
+                &[nonce[0].clone(), nonce[1].clone(), nonce[2].clone()],
+                &counter[0],
+            ),
+            Self::Original => chacha20_block_gadget(
+                cs,
+                key,
+                &[counter[1].clone(), nonce[0].clone(), nonce[1].clone()],
+                &counter[0],
+            ),
+            Self::XChaCha20 => {
+                let subkey = hchacha20_gadget(
+                    cs.clone(),
+                    key,
+                    &[
+                        nonce[0].clone(),
+                        nonce[1].clone(),
+                        nonce[2].clone(),
+                        nonce[3].clone(),
+                    ],
+                )?;
+                chacha20_block_gadget(
+                    cs,
+                    &subkey,
+                    &[UInt32::constant(0), nonce[4].clone(), nonce[5].clone()],
+                    &counter[0],
+                )
+            }
+        }
+    }
+
+    /// Returns the block counter following `counter`, wrapping around `2^(32 * counter_len)`.
+    pub fn next_counter_gadget<F: PrimeField>(
+        &self,
+        counter: &[UInt32<F>],
+    ) -> Result<Vec<UInt32<F>>, SynthesisError> {
+        match counter {
+            [c] => Ok(vec![c.wrapping_add(&UInt32::constant(1))]),
+            [low, high] => {
+                let bits = [low.to_bits_le()?, high.to_bits_le()?].concat();
+                let next = UInt64::from_bits_le(&bits).wrapping_add(&UInt64::constant(1));
+                let next_bits = next.to_bits_le()?;
+                Ok(vec![
+                    UInt32::from_bits_le(&next_bits[..32]),
+                    UInt32::from_bits_le(&next_bits[32..]),
+                ])
+            }
+            _ => Err(SynthesisError::Unsatisfiable),
+        }
+    }
+
+    /// Native counterpart of [`Self::block_gadget`].
+    pub fn block_native(&self, key: [u32; 8], nonce: &[u32], counter: &[u32]) -> [u32; 16] {
+        assert_eq!(nonce.len(), self.nonce_len());
+        assert_eq!(counter.len(), self.counter_len());
+        match self {
+            Self::Ietf => chacha20_block_native(key, [nonce[0], nonce[1], nonce[2]], counter[0]),
+            Self::Original => {
+                chacha20_block_native(key, [counter[1], nonce[0], nonce[1]], counter[0])
+            }
+            Self::XChaCha20 => chacha20_block_native(
+                hchacha20_native(key, [nonce[0], nonce[1], nonce[2], nonce[3]]),
+                [0, nonce[4], nonce[5]],
+                counter[0],
+            ),
+        }
+    }
+}
+
+/// Computes the ChaCha20 block function of RFC 7539 Section 2.3, returning the 16 words of the
+/// keystream block for the given key, nonce and block counter.
+///
+/// The state is laid out as `[constants, key, counter, nonce]`, see [`ChaCha20Variant`] for the
+/// other layouts.
+pub fn chacha20_block_gadget<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    key: &[UInt32<F>; 8],
+    nonce: &[UInt32<F>; 3],
+    counter: &UInt32<F>,
+) -> Result<[UInt32<F>; 16], SynthesisError> {
+    let state = initial_state(cs, key, [counter, &nonce[0], &nonce[1], &nonce[2]])?;
+    let mut working_state = state.clone();
+    rounds(&mut working_state)?;
+    for (w, s) in working_state.iter_mut().zip(&state) {
+        w.wrapping_add_in_place(s);
+    }
+    Ok(working_state)
+}
+
+/// Computes the HChaCha20 function of draft-irtf-cfrg-xchacha Section 2.2, which derives the
+/// subkey of XChaCha20 from the key and the first 128 bits of its nonce.
+pub fn hchacha20_gadget<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    key: &[UInt32<F>; 8],
+    nonce: &[UInt32<F>; 4],
+) -> Result<[UInt32<F>; 8], SynthesisError> {
+    let mut state = initial_state(cs, key, [&nonce[0], &nonce[1], &nonce[2], &nonce[3]])?;
+    rounds(&mut state)?;
+    // unlike the block function, the initial state is not added, and the subkey is made of the
+    // first and last rows of the state
+    Ok(core::array::from_fn(|i| match i {
+        0..=3 => state[i].clone(),
+        _ => state[i + 8].clone(),
+    }))
+}
+
+/// Lays out the ChaCha20 state `[constants, key, last_row]`.
+fn initial_state<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    key: &[UInt32<F>; 8],
+    last_row: [&UInt32<F>; 4],
+) -> Result<[UInt32<F>; 16], SynthesisError> {
+    let constants = CHACHA20_CONSTANTS
+        .iter()
+        .map(|&c| UInt32::new_constant(cs.clone(), c))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(core::array::from_fn(|i| match i {
+        0..=3 => constants[i].clone(),
+        4..=11 => key[i - 4].clone(),
+        _ => last_row[i - 12].clone(),
+    }))
+}
+
+/// 20 rounds, as 10 iterations of a column round followed by a diagonal round.
+fn rounds<F: PrimeField>(state: &mut [UInt32<F>; 16]) -> Result<(), SynthesisError> {
+    for _ in 0..10 {
+        quarter_round(state, 0, 4, 8, 12)?;
+        quarter_round(state, 1, 5, 9, 13)?;
+        quarter_round(state, 2, 6, 10, 14)?;
+        quarter_round(state, 3, 7, 11, 15)?;
+
+        quarter_round(state, 0, 5, 10, 15)?;
+        quarter_round(state, 1, 6, 11, 12)?;
+        quarter_round(state, 2, 7, 8, 13)?;
+        quarter_round(state, 3, 4, 9, 14)?;
+    }
+    Ok(())
+}
+
+/// Applies the quarter round `QUARTERROUND(a, b, c, d)` of RFC 7539 Section 2.2 to the words at
+/// positions `a`, `b`, `c` and `d` of the ChaCha20 state.
+pub fn quarter_round<F: PrimeField>(
+    state: &mut [UInt32<F>; 16],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+) -> Result<(), SynthesisError> {
+    // a += b; d ^= a; d <<<= 16;
+    state[a] = state[a].wrapping_add(&state[b]);
+    state[d] = rotl(&xor(&state[d], &state[a])?, 16)?;
+    // c += d; b ^= c; b <<<= 12;
+    state[c] = state[c].wrapping_add(&state[d]);
+    state[b] = rotl(&xor(&state[b], &state[c])?, 12)?;
+    // a += b; d ^= a; d <<<= 8;
+    state[a] = state[a].wrapping_add(&state[b]);
+    state[d] = rotl(&xor(&state[d], &state[a])?, 8)?;
+    // c += d; b ^= c; b <<<= 7;
+    state[c] = state[c].wrapping_add(&state[d]);
+    state[b] = rotl(&xor(&state[b], &state[c])?, 7)?;
+    Ok(())
+}
+
+/// Enforces that the value of `fp` fits in 32 bits, returning it as the `UInt32` whose bits
+/// recompose `fp`. Unlike taking the low 32 bits of `fp.to_bits_le()`, this rejects the field
+/// elements of more than 32 bits, at the cost of 33 constraints: the booleanity of the 32 bits and
+/// their recomposition.
+pub fn enforce_fits_u32<F: PrimeField>(fp: &FpVar<F>) -> Result<UInt32<F>, SynthesisError> {
+    Ok(unpack_fp_to_u32s(fp, 1)?.remove(0))
+}
+
+/// Reads the ciphertext of the last block from the state `z_i` of a ChaCha20 folding circuit,
+/// which ends with the 16 ciphertext words of the block. Panics if the state is shorter than a
+/// block or one of its last 16 elements does not fit in 32 bits.
+pub fn state_to_ciphertext_words<F: PrimeField>(z_i: &[F]) -> [u32; 16] {
+    assert!(z_i.len() >= 16);
+    let block = &z_i[z_i.len() - 16..];
+    core::array::from_fn(|j| {
+        let word = block[j].into_bigint();
+        assert!(word.num_bits() <= 32);
+        word.as_ref()[0] as u32
+    })
+}
+
+/// Serializes the words in the byte order of RFC 7539, where each word is little-endian, e.g. to
+/// obtain the ciphertext bytes from [`state_to_ciphertext_words`].
+pub fn words_to_bytes_le(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+/// Inverse of [`words_to_bytes_le`], padding the last word with zero bytes, e.g. to obtain the
+/// plaintext words from the plaintext bytes.
+pub fn bytes_to_words_le(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        })
+        .collect()
+}
+
+/// Native counterpart of [`chacha20_block_gadget`].
+pub fn chacha20_block_native(key: [u32; 8], nonce: [u32; 3], counter: u32) -> [u32; 16] {
+    let state = initial_state_native(key, [counter, nonce[0], nonce[1], nonce[2]]);
+    let mut working_state = state;
+    rounds_native(&mut working_state);
+    for (w, s) in working_state.iter_mut().zip(state) {
+        *w = w.wrapping_add(s);
+    }
+    working_state
+}
+
+/// Native counterpart of [`hchacha20_gadget`].
+pub fn hchacha20_native(key: [u32; 8], nonce: [u32; 4]) -> [u32; 8] {
+    let mut state = initial_state_native(key, nonce);
+    rounds_native(&mut state);
+    core::array::from_fn(|i| match i {
+        0..=3 => state[i],
+        _ => state[i + 8],
+    })
+}
+
+fn initial_state_native(key: [u32; 8], last_row: [u32; 4]) -> [u32; 16] {
+    let mut state = [0u32; 16];
+    state[0..4].copy_from_slice(&CHACHA20_CONSTANTS);
+    state[4..12].copy_from_slice(&key);
+    state[12..16].copy_from_slice(&last_row);
+    state
+}
+
+fn rounds_native(state: &mut [u32; 16]) {
+    for _ in 0..10 {
+        quarter_round_native(state, 0, 4, 8, 12);
+        quarter_round_native(state, 1, 5, 9, 13);
+        quarter_round_native(state, 2, 6, 10, 14);
+        quarter_round_native(state, 3, 7, 11, 15);
+
+        quarter_round_native(state, 0, 5, 10, 15);
+        quarter_round_native(state, 1, 6, 11, 12);
+        quarter_round_native(state, 2, 7, 8, 13);
+        quarter_round_native(state, 3, 4, 9, 14);
+    }
+}
+
+/// Native counterpart of [`quarter_round`].
+pub fn quarter_round_native(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    /// Key of the test vectors of RFC 7539 Sections 2.3.2 and 2.4.2.
+    const KEY: [u32; 8] = [
+        0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918,
+        0x1f1e1d1c,
+    ];
+
+    fn alloc_words<const N: usize>(
+        cs: ConstraintSystemRef<Fr>,
+        words: [u32; N],
+    ) -> Result<[UInt32<Fr>; N], SynthesisError> {
+        let vars = words
+            .iter()
+            .map(|&w| UInt32::new_witness(cs.clone(), || Ok(w)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(core::array::from_fn(|i| vars[i].clone()))
+    }
+
+    fn words_value<const N: usize>(words: &[UInt32<Fr>; N]) -> Result<[u32; N], SynthesisError> {
+        let values = words
+            .iter()
+            .map(|w| w.value())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(core::array::from_fn(|i| values[i]))
+    }
+
+    /// Checks the quarter round on the words `[a, b, c, d]` against its expected output
+    fn check_quarter_round(input: [u32; 4], expected: [u32; 4]) -> Result<(), SynthesisError> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut state = alloc_words(cs.clone(), [0; 16])?;
+        let mut native_state = [0; 16];
+        // place the words in the positions of QUARTERROUND(2, 7, 8, 13), as in RFC 7539 Section
+        // 2.2.1
+        for (&pos, &w) in [2, 7, 8, 13].iter().zip(&input) {
+            state[pos] = UInt32::new_witness(cs.clone(), || Ok(w))?;
+            native_state[pos] = w;
+        }
+        quarter_round(&mut state, 2, 7, 8, 13)?;
+        quarter_round_native(&mut native_state, 2, 7, 8, 13);
+
+        let state = words_value(&state)?;
+        assert_eq!(state, native_state);
+        assert_eq!([state[2], state[7], state[8], state[13]], expected);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quarter_round() -> Result<(), SynthesisError> {
+        // RFC 7539 Section 2.1.1
+        check_quarter_round(
+            [0x11111111, 0x01020304, 0x9b8d6f43, 0x01234567],
+            [0xea2a92f4, 0xcb1cf8ce, 0x4581472e, 0x5881c4bb],
+        )?;
+        // RFC 7539 Section 2.2.1
+        check_quarter_round(
+            [0x516461b1, 0x2a5f714c, 0x53372767, 0x3d631689],
+            [0xbdb886dc, 0xcfacafd2, 0xe46bea80, 0xccc07c79],
+        )?;
+        // a + b overflows
+        check_quarter_round(
+            [0xffffffff, 0x00000001, 0x00000000, 0x00000000],
+            [0x00001000, 0x08080000, 0x00100000, 0x00100000],
+        )
+    }
+
+    #[test]
+    fn test_enforce_fits_u32() -> Result<(), SynthesisError> {
+        use ark_ff::One;
+
+        for (value, satisfied) in [
+            (Fr::from(0u32), true),
+            (Fr::from(u32::MAX), true),
+            (Fr::from(1u64 << 32), false),
+            (-Fr::one(), false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let fp = FpVar::new_witness(cs.clone(), || Ok(value))?;
+            let word = enforce_fits_u32(&fp)?;
+            assert_eq!(cs.num_constraints(), 33);
+            assert_eq!(cs.is_satisfied()?, satisfied);
+            if satisfied {
+                assert_eq!(Fr::from(word.value()?), value);
+            }
+        }
+
+        assert_eq!(
+            enforce_fits_u32(&FpVar::Constant(Fr::from(u32::MAX)))?.value()?,
+            u32::MAX
+        );
+        assert!(enforce_fits_u32(&FpVar::Constant(Fr::from(1u64 << 32))).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ciphertext_bytes() {
+        // the first words of the plaintext of RFC 7539 Section 2.4.2, "Ladies and Gentlemen"
+        let bytes = b"Ladies and Gentlemen";
+        let words = bytes_to_words_le(bytes);
+        assert_eq!(
+            words,
+            [0x6964614c, 0x61207365, 0x4720646e, 0x6c746e65, 0x6e656d65]
+        );
+        assert_eq!(words_to_bytes_le(&words), bytes);
+        assert_eq!(bytes_to_words_le(b"abcde"), [0x64636261, 0x65]);
+
+        // the ciphertext block is read from the end of the state
+        let z_i: Vec<Fr> = (0..28u32)
+            .map(|j| Fr::from(j.wrapping_mul(0x9e3779b9)))
+            .collect();
+        let words = state_to_ciphertext_words(&z_i);
+        assert_eq!(words[0], 12u32.wrapping_mul(0x9e3779b9));
+        assert_eq!(words[15], 27u32.wrapping_mul(0x9e3779b9));
+    }
+
+    #[test]
+    fn test_chacha20_block() -> Result<(), SynthesisError> {
+        // RFC 7539 Section 2.3.2
+        let nonce = [0x09000000, 0x4a000000, 0x00000000];
+        let expected = [
+            0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3, 0xc7f4d1c7, 0x0368c033, 0x9aaa2204,
+            0x4e6cd4c3, 0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9, 0xd19c12b5, 0xb94e16de,
+            0xe883d0cb, 0x4e3c50a2,
+        ];
+        assert_eq!(chacha20_block_native(KEY, nonce, 1), expected);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let key = alloc_words(cs.clone(), KEY)?;
+        let nonce = alloc_words(cs.clone(), nonce)?;
+        let counter = UInt32::new_witness(cs.clone(), || Ok(1))?;
+        let block = chacha20_block_gadget(cs.clone(), &key, &nonce, &counter)?;
+        assert_eq!(words_value(&block)?, expected);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    /// Regression test of the size of the block function, where each of the 336 additions
+    /// modulo 2^32 costs 34 constraints (the 33 bits of the sum and their recomposition) and each
+    /// of the 320 XORs of words costs 32 constraints. The former ripple-carry adder needed about
+    /// 5 constraints per bit of each addition instead.
+    /// Snapshot of the numbers of constraints of the gadgets on witness words, which size the step
+    /// circuits and thus the SRS of the folding schemes. An optimization of the gadgets must update
+    /// them.
+    #[test]
+    fn test_chacha20_constraint_counts() -> Result<(), SynthesisError> {
+        const ADDITION_CONSTRAINTS: usize = 34;
+        const XOR_CONSTRAINTS: usize = 32;
+        const QUARTER_ROUND_CONSTRAINTS: usize = 264;
+        const BLOCK_CONSTRAINTS: usize = 21664;
+        const HCHACHA20_CONSTRAINTS: usize = 21120;
+        const XCHACHA20_BLOCK_CONSTRAINTS: usize = 42784;
+        assert_eq!(
+            QUARTER_ROUND_CONSTRAINTS,
+            4 * ADDITION_CONSTRAINTS + 4 * XOR_CONSTRAINTS
+        );
+        assert_eq!(
+            BLOCK_CONSTRAINTS,
+            80 * QUARTER_ROUND_CONSTRAINTS + 16 * ADDITION_CONSTRAINTS
+        );
+        assert_eq!(HCHACHA20_CONSTRAINTS, 80 * QUARTER_ROUND_CONSTRAINTS);
+        assert_eq!(
+            XCHACHA20_BLOCK_CONSTRAINTS,
+            HCHACHA20_CONSTRAINTS + BLOCK_CONSTRAINTS
+        );
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let [a, b] = alloc_words(cs.clone(), [0xffffffff, 0x00000001])?;
+        let n_constraints = cs.num_constraints();
+        let _ = a.wrapping_add(&b);
+        assert_eq!(cs.num_constraints() - n_constraints, ADDITION_CONSTRAINTS);
+
+        let mut state = alloc_words(cs.clone(), [0; 16])?;
+        let n_constraints = cs.num_constraints();
+        quarter_round(&mut state, 0, 4, 8, 12)?;
+        let quarter_round_constraints = cs.num_constraints() - n_constraints;
+
+        let key = alloc_words(cs.clone(), KEY)?;
+        let nonce = alloc_words(cs.clone(), [0x09000000, 0x4a000000, 0x00000000, 0x00000000])?;
+        let counter = UInt32::new_witness(cs.clone(), || Ok(1))?;
+        let n_constraints = cs.num_constraints();
+        chacha20_block_gadget(
+            cs.clone(),
+            &key,
+            &[0, 1, 2].map(|i| nonce[i].clone()),
+            &counter,
+        )?;
+        let block_constraints = cs.num_constraints() - n_constraints;
+
+        let n_constraints = cs.num_constraints();
+        hchacha20_gadget(cs.clone(), &key, &nonce)?;
+        let hchacha20_constraints = cs.num_constraints() - n_constraints;
+
+        let xnonce = [nonce.to_vec(), nonce[..2].to_vec()].concat();
+        let n_constraints = cs.num_constraints();
+        ChaCha20Variant::XChaCha20.block_gadget(cs.clone(), &key, &xnonce, &[counter])?;
+        let xchacha20_block_constraints = cs.num_constraints() - n_constraints;
+        assert!(cs.is_satisfied()?);
+
+        println!("{:<20} {:>12}", "gadget", "constraints");
+        for (name, count) in [
+            ("quarter round", quarter_round_constraints),
+            ("ChaCha20 block", block_constraints),
+            ("HChaCha20", hchacha20_constraints),
+            ("XChaCha20 block", xchacha20_block_constraints),
+        ] {
+            println!("{:<20} {:>12}", name, count);
+        }
+        assert_eq!(quarter_round_constraints, QUARTER_ROUND_CONSTRAINTS);
+        assert_eq!(block_constraints, BLOCK_CONSTRAINTS);
+        assert_eq!(hchacha20_constraints, HCHACHA20_CONSTRAINTS);
+        assert_eq!(xchacha20_block_constraints, XCHACHA20_BLOCK_CONSTRAINTS);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chacha20_keystream() -> Result<(), SynthesisError> {
+        // keystream of the two blocks of the encryption of RFC 7539 Section 2.4.2, and of the
+        // first test vector of the block function in RFC 7539 Appendix A.1
+        let vectors: [([u32; 8], [u32; 3], u32, [u32; 16]); 3] = [
+            (
+                KEY,
+                [0x00000000, 0x4a000000, 0x00000000],
+                1,
+                [
+                    0xf3514f22, 0xe1d91b40, 0x6f27de2f, 0xed1d63b8, 0x821f138c, 0xe2062c3d,
+                    0xecca4f7e, 0x78cff39e, 0xa30a3b8a, 0x920a6072, 0xcd7479b5, 0x34932bed,
+                    0x40ba4c79, 0xcd343ec6, 0x4c2c21ea, 0xb7417df0,
+                ],
+            ),
+            (
+                KEY,
+                [0x00000000, 0x4a000000, 0x00000000],
+                2,
+                [
+                    0x9f74a669, 0x410f633f, 0x28feca22, 0x7ec44dec, 0x6d34d426, 0x738cb970,
+                    0x3ac5e9f3, 0x45590cc4, 0xda6e8b39, 0x892c831a, 0xcdea67c1, 0x2b7e1d90,
+                    0x037463f3, 0xa11a2073, 0xe8bcfb88, 0xedc49139,
+                ],
+            ),
+            (
+                [0; 8],
+                [0; 3],
+                0,
+                [
+                    0xade0b876, 0x903df1a0, 0xe56a5d40, 0x28bd8653, 0xb819d2bd, 0x1aed8da0,
+                    0xccef36a8, 0xc70d778b, 0x7c5941da, 0x8d485751, 0x3fe02477, 0x374ad8b8,
+                    0xf4b8436a, 0x1ca11815, 0x69b687c3, 0x8665eeb2,
+                ],
+            ),
+        ];
+
+        for (key, nonce, counter, expected) in vectors {
+            assert_eq!(chacha20_block_native(key, nonce, counter), expected);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let key = alloc_words(cs.clone(), key)?;
+            let nonce = alloc_words(cs.clone(), nonce)?;
+            let counter = UInt32::new_witness(cs.clone(), || Ok(counter))?;
+            let block = chacha20_block_gadget(cs.clone(), &key, &nonce, &counter)?;
+            assert_eq!(words_value(&block)?, expected);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_hchacha20() -> Result<(), SynthesisError> {
+        // draft-irtf-cfrg-xchacha-03 Section 2.2.1
+        let nonce = [0x09000000, 0x4a000000, 0x00000000, 0x27594131];
+        let expected = [
+            0x423b4182, 0xfe7bb227, 0x50420ed3, 0x737d878a, 0xd5e4f9a0, 0x53a8748a, 0x13c42ec1,
+            0xdcecd326,
+        ];
+        assert_eq!(hchacha20_native(KEY, nonce), expected);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let key = alloc_words(cs.clone(), KEY)?;
+        let nonce = alloc_words(cs.clone(), nonce)?;
+        let subkey = hchacha20_gadget(cs.clone(), &key, &nonce)?;
+        assert_eq!(words_value(&subkey)?, expected);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chacha20_variants() -> Result<(), SynthesisError> {
+        let vectors: [(ChaCha20Variant, [u32; 8], Vec<u32>, Vec<u32>, [u32; 16]); 4] = [
+            // RFC 7539 Section 2.3.2
+            (
+                ChaCha20Variant::Ietf,
+                KEY,
+                vec![0x09000000, 0x4a000000, 0x00000000],
+                vec![1],
+                [
+                    0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3, 0xc7f4d1c7, 0x0368c033,
+                    0x9aaa2204, 0x4e6cd4c3, 0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9,
+                    0xd19c12b5, 0xb94e16de, 0xe883d0cb, 0x4e3c50a2,
+                ],
+            ),
+            // test vectors of the original variant of draft-agl-tls-chacha20poly1305-04, with the
+            // nonce 0x0000000000000001
+            (
+                ChaCha20Variant::Original,
+                [0; 8],
+                vec![0x00000000, 0x01000000],
+                vec![0, 0],
+                [
+                    0x7bba9cde, 0xf59ed6f3, 0x63dc86e7, 0x3a653f97, 0x15e0490b, 0x13f7bfad,
+                    0xf17dcb4f, 0x31108237, 0x02055ae8, 0x4508a778, 0x734f2127, 0x5bfac7ef,
+                    0x2e067752, 0x3e43a0b7, 0xe3415f44, 0x57b7fa1a,
+                ],
+            ),
+            // and with the key 0x00..01
+            (
+                ChaCha20Variant::Original,
+                [0, 0, 0, 0, 0, 0, 0, 0x01000000],
+                vec![0, 0],
+                vec![0, 0],
+                [
+                    0x5af04045, 0x96b21f9f, 0x7b6e73d7, 0x963c8e20, 0x83e14feb, 0x60d28846,
+                    0x5209454f, 0x412d43ed, 0xb6a0e2bb, 0xd26675ea, 0xe2e7d1a5, 0x2caf420d,
+                    0xb192d753, 0x81ea3fc4, 0x75d29a7e, 0x636954ae,
+                ],
+            ),
+            // first block of the keystream of draft-irtf-cfrg-xchacha-03 Appendix A.3.2
+            (
+                ChaCha20Variant::XChaCha20,
+                [
+                    0x83828180, 0x87868584, 0x8b8a8988, 0x8f8e8d8c, 0x93929190, 0x97969594,
+                    0x9b9a9998, 0x9f9e9d9c,
+                ],
+                vec![
+                    0x43424140, 0x47464544, 0x4b4a4948, 0x4f4e4d4c, 0x53525150, 0x58565554,
+                ],
+                vec![1],
+                [
+                    0x4b4b6229, 0xce0a141b, 0x400e7453, 0x5468215b, 0x30d6d70f, 0xfe36f5c1,
+                    0xc32f72cd, 0xf4a7dbcd, 0xf98ca9cc, 0x645e7ee4, 0x0f4515d1, 0x545b129b,
+                    0x61f79f44, 0x0a62ca41, 0xabfc9c1f, 0x258a1a2a,
+                ],
+            ),
+        ];
+
+        for (variant, key, nonce, counter, expected) in vectors {
+            assert_eq!(variant.block_native(key, &nonce, &counter), expected);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let key = alloc_words(cs.clone(), key)?;
+            let nonce = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(nonce))?;
+            let counter = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(counter))?;
+            let block = variant.block_gadget(cs.clone(), &key, &nonce, &counter)?;
+            assert_eq!(words_value(&block)?, expected);
+            assert!(cs.is_satisfied()?);
+
+            // the nonce and counter must have the length of the variant
+            assert!(variant
+                .block_gadget(cs.clone(), &key, &nonce[1..], &counter)
+                .is_err());
+        }
+        Ok(())
+    }
+
+    /// Random keys, nonces, counters and plaintexts of the IETF, original and XChaCha20 variants,
+    /// including counters near 2^32, checked against the RustCrypto `chacha20` crate, an oracle
+    /// independent of the native implementation of this module.
+    #[test]
+    fn test_chacha20_against_rustcrypto() -> Result<(), SynthesisError> {
+        use ark_std::rand::Rng;
+        use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+
+        let mut rng = ark_std::test_rng();
+        for case in 0..256 {
+            let variant = [
+                ChaCha20Variant::Ietf,
+                ChaCha20Variant::Original,
+                ChaCha20Variant::XChaCha20,
+            ][case % 3];
+            let key: [u32; 8] = rng.gen();
+            let nonce: Vec<u32> = (0..variant.nonce_len()).map(|_| rng.gen()).collect();
+            // RustCrypto does not produce the block of the counter u32::MAX, after which the
+            // counter would overflow
+            let counter = if case % 4 < 2 {
+                u32::MAX - 1 - rng.gen_range(0..16)
+            } else {
+                rng.gen_range(0..u32::MAX)
+            };
+            // the original variant of RustCrypto keeps the high word of its 64-bit counter at 0
+            let counter = match variant {
+                ChaCha20Variant::Original => vec![counter, 0],
+                _ => vec![counter],
+            };
+            let plaintext: [u32; 16] = rng.gen();
+
+            let (key_bytes, nonce_bytes) = (words_to_bytes_le(&key), words_to_bytes_le(&nonce));
+            let (key_bytes, nonce_bytes) = (key_bytes.as_slice(), nonce_bytes.as_slice());
+            let position = 64 * counter[0] as u64;
+            let mut ciphertext = words_to_bytes_le(&plaintext);
+            match variant {
+                ChaCha20Variant::Ietf => {
+                    let mut cipher = chacha20::ChaCha20::new(key_bytes.into(), nonce_bytes.into());
+                    cipher.seek(position);
+                    cipher.apply_keystream(&mut ciphertext);
+                }
+                ChaCha20Variant::Original => {
+                    let mut cipher =
+                        chacha20::ChaCha20Legacy::new(key_bytes.into(), nonce_bytes.into());
+                    cipher.seek(position);
+                    cipher.apply_keystream(&mut ciphertext);
+                }
+                ChaCha20Variant::XChaCha20 => {
+                    let mut cipher = chacha20::XChaCha20::new(key_bytes.into(), nonce_bytes.into());
+                    cipher.seek(position);
+                    cipher.apply_keystream(&mut ciphertext);
+                }
+            }
+            let expected = bytes_to_words_le(&ciphertext);
+
+            let keystream = variant.block_native(key, &nonce, &counter);
+            let native: Vec<u32> = (0..16).map(|j| plaintext[j] ^ keystream[j]).collect();
+            assert_eq!(native, expected);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let key = alloc_words(cs.clone(), key)?;
+            let nonce = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(nonce))?;
+            let counter = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(counter))?;
+            let plaintext = alloc_words(cs.clone(), plaintext)?;
+            let keystream = variant.block_gadget(cs.clone(), &key, &nonce, &counter)?;
+            let ciphertext = plaintext
+                .iter()
+                .zip(&keystream)
+                .map(|(p, k)| xor(p, k)?.value())
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(ciphertext, expected);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_counter() -> Result<(), SynthesisError> {
+        for (variant, counter, expected) in [
+            (ChaCha20Variant::Ietf, vec![1], vec![2]),
+            (ChaCha20Variant::Ietf, vec![u32::MAX], vec![0]),
+            (ChaCha20Variant::Original, vec![u32::MAX, 0], vec![0, 1]),
+            (ChaCha20Variant::Original, vec![5, 7], vec![6, 7]),
+            (
+                ChaCha20Variant::Original,
+                vec![u32::MAX, u32::MAX],
+                vec![0, 0],
+            ),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let counter = Vec::<UInt32<Fr>>::new_witness(cs.clone(), || Ok(counter))?;
+            let next = variant.next_counter_gadget(&counter)?;
+            assert_eq!(next.value()?, expected);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+//! ECDSA signature verification over secp256k1 ([SEC 1](https://www.secg.org/sec1-v2.pdf) §4.1.4)
+//! as R1CS constraints, with the arithmetic of the base and scalar fields of the curve emulated by
+//! the [`EmulatedFpVar`] of arkworks, together with its native counterpart.
+//!
+//! The message hash is given as an element of the scalar field of secp256k1, i.e. the 256-bit
+//! hash reduced modulo the order of the curve (see [`ecdsa_msg_hash`]).
+//!
+//! As the final comparison of the abscissa with `r` uses the [`NonNativeUintVar`] of
+//! folding-schemes, the gadgets require a constraint field of more than 250 bits.
+
+use ark_ec::{short_weierstrass::SWCurveConfig, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    eq::EqGadget,
+    fields::{emulated_fp::EmulatedFpVar, FieldVar},
+    select::CondSelectGadget,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_secp256k1::{Affine, Config, Fq, Fr};
+use ark_std::borrow::Borrow;
+use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
+
+/// Affine point of secp256k1 whose coordinates are emulated in the constraint field `CF`. The
+/// point at infinity can not be represented, which is enough for public keys and for the
+/// intermediate points of the verification.
+#[derive(Clone, Debug)]
+pub struct Secp256k1AffineVar<CF: PrimeField> {
+    pub x: EmulatedFpVar<Fq, CF>,
+    pub y: EmulatedFpVar<Fq, CF>,
+}
+
+impl<CF: PrimeField> Secp256k1AffineVar<CF> {
+    /// Returns the given point as a constant, which must not be the point at infinity.
+    pub fn constant(p: Affine) -> Self {
+        Self {
+            x: EmulatedFpVar::constant(p.x),
+            y: EmulatedFpVar::constant(p.y),
+        }
+    }
+
+    /// Enforces that the point is on the curve, i.e. that `y^2 = x^3 + 7`.
+    pub fn enforce_on_curve(&self) -> Result<(), SynthesisError> {
+        (self.x.square()? * &self.x + Config::COEFF_B).enforce_equal(&self.y.square()?)
+    }
+
+    /// Computes `2 * self`. secp256k1 has no point of order 2, so the ordinate is never zero.
+    pub fn double(&self) -> Result<Self, SynthesisError> {
+        let x_square = self.x.square()?;
+        let lambda = (x_square.double()? + &x_square).mul_by_inverse(&self.y.double()?)?;
+        let x = lambda.square()? - self.x.double()?;
+        let y = lambda * (&self.x - &x) - &self.y;
+        Ok(Self { x, y })
+    }
+
+    /// Computes `self + other` with the incomplete addition formula, which leaves the constraint
+    /// system unsatisfiable if `self` and `other` have the same abscissa, i.e. if
+    /// `self = ±other`.
+    pub fn add_unequal(&self, other: &Self) -> Result<Self, SynthesisError> {
+        let lambda = (&other.y - &self.y).mul_by_inverse(&(&other.x - &self.x))?;
+        let x = lambda.square()? - &self.x - &other.x;
+        let y = lambda * (&self.x - &x) - &self.y;
+        Ok(Self { x, y })
+    }
+}
+
+impl<CF: PrimeField> R1CSVar<CF> for Secp256k1AffineVar<CF> {
+    type Value = Affine;
+
+    fn cs(&self) -> ConstraintSystemRef<CF> {
+        self.x.cs().or(self.y.cs())
+    }
+
+    fn value(&self) -> Result<Self::Value, SynthesisError> {
+        Ok(Affine::new_unchecked(self.x.value()?, self.y.value()?))
+    }
+}
+
+impl<CF: PrimeField> AllocVar<Affine, CF> for Secp256k1AffineVar<CF> {
+    /// Allocates the coordinates of the point and enforces that it is on the curve, so that the
+    /// point at infinity leaves the constraint system unsatisfiable.
+    fn new_variable<T: Borrow<Affine>>(
+        cs: impl Into<Namespace<CF>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let val = val.borrow();
+            let x = EmulatedFpVar::new_variable(cs.clone(), || Ok(val.x), mode)?;
+            let y = EmulatedFpVar::new_variable(cs.clone(), || Ok(val.y), mode)?;
+            let point = Self { x, y };
+            if mode != AllocationMode::Constant {
+                point.enforce_on_curve()?;
+            }
+            Ok(point)
+        })
+    }
+}
+
+impl<CF: PrimeField> CondSelectGadget<CF> for Secp256k1AffineVar<CF> {
+    fn conditionally_select(
+        cond: &Boolean<CF>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            x: cond.select(&true_value.x, &false_value.x)?,
+            y: cond.select(&true_value.y, &false_value.y)?,
+        })
+    }
+}
+
+/// Auxiliary point from which the accumulator of the double scalar multiplication starts, so that
+/// it never goes through the point at infinity: the point of smallest abscissa `1, 2, ...` of the
+/// curve, whose discrete logarithm is unknown.
+fn aux_point() -> Affine {
+    let mut x = Fq::one();
+    loop {
+        if let Some(p) = Affine::get_point_from_x_unchecked(x, false) {
+            return p;
+        }
+        x += Fq::one();
+    }
+}
+
+/// Computes `u1 * G + u2 * q`, where `G` is the generator of secp256k1 and the scalars are given
+/// by their little-endian bits, with Shamir's trick: a single sequence of doublings, each followed
+/// by the addition of `G`, `q` or `G + q` depending on the bits of the scalars.
+///
+/// The incomplete additions leave the constraint system unsatisfiable in the exceptional cases,
+/// which only happen with negligible probability for honestly generated keys and signatures
+/// (e.g. `q = ±G`), but never make it accept a wrong result.
+pub fn double_scalar_mul_gadget<CF: PrimeField>(
+    u1: &[Boolean<CF>],
+    q: &Secp256k1AffineVar<CF>,
+    u2: &[Boolean<CF>],
+) -> Result<Secp256k1AffineVar<CF>, SynthesisError> {
+    if u1.len() != u2.len() {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let g = Secp256k1AffineVar::constant(Affine::generator());
+    let g_plus_q = q.add_unequal(&g)?;
+
+    // the accumulator starts at the auxiliary point `A`, which ends up multiplied by `2^n` after
+    // the `n` doublings and is subtracted at the end
+    let aux = aux_point();
+    let aux_end = (0..u1.len())
+        .fold(aux.into_group(), |p, _| p + p)
+        .into_affine();
+    let mut acc = Secp256k1AffineVar::constant(aux);
+    for (b1, b2) in u1.iter().zip(u2).rev() {
+        acc = acc.double()?;
+        let summand = b1.select(&b2.select(&g_plus_q, &g)?, q)?;
+        acc = (b1 | b2).select(&acc.add_unequal(&summand)?, &acc)?;
+    }
+    acc.add_unequal(&Secp256k1AffineVar::constant(-aux_end))
+}
+
+/// Enforces that `(r, s)` is a valid ECDSA signature of the message hash `msg_hash` under the
+/// public key `pk`, i.e. that `r != 0`, `s != 0` and that the abscissa of
+/// `(msg_hash / s) * G + (r / s) * pk` is congruent to `r` modulo the order of the curve.
+pub fn ecdsa_verify_gadget<CF: PrimeField>(
+    pk: &Secp256k1AffineVar<CF>,
+    msg_hash: &EmulatedFpVar<Fr, CF>,
+    r: &EmulatedFpVar<Fr, CF>,
+    s: &EmulatedFpVar<Fr, CF>,
+) -> Result<(), SynthesisError> {
+    r.enforce_not_equal(&EmulatedFpVar::zero())?;
+    // `inverse` enforces that `s` is not zero
+    let s_inv = s.inverse()?;
+    let u1 = msg_hash * &s_inv;
+    let u2 = r * &s_inv;
+    let point = double_scalar_mul_gadget(&u1.to_bits_le()?, pk, &u2.to_bits_le()?)?;
+
+    // the abscissa lives in the base field, whose modulus is larger than the order of the curve,
+    // so it is compared to `r` as an integer modulo the order
+    let x = NonNativeUintVar::from(point.x.to_bits_le()?);
+    let r = NonNativeUintVar::from(r.to_bits_le()?);
+    x.enforce_congruent::<Fr>(&r)
+}
+
+/// Reduces a big-endian hash modulo the order of secp256k1, which for the hashes of 256 bits is
+/// the integer of the message hash of ECDSA.
+pub fn ecdsa_msg_hash(hash: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(hash)
+}
+
+/// Reduces the abscissa of a point modulo the order of secp256k1.
+fn x_mod_order(p: &Affine) -> Fr {
+    Fr::from_le_bytes_mod_order(&p.x.into_bigint().to_bytes_le())
+}
+
+/// Signs `msg_hash` with the secret key `sk` and the nonce `k`, returning the signature `(r, s)`,
+/// or `None` if the nonce gives `r = 0` or `s = 0`.
+pub fn ecdsa_sign_native(sk: Fr, msg_hash: Fr, k: Fr) -> Option<(Fr, Fr)> {
+    let r = x_mod_order(&(Affine::generator() * k).into_affine());
+    let s = k.inverse()? * (msg_hash + r * sk);
+    if r.is_zero() || s.is_zero() {
+        return None;
+    }
+    Some((r, s))
+}
+
+/// Native counterpart of [`ecdsa_verify_gadget`].
+pub fn ecdsa_verify_native(pk: &Affine, msg_hash: Fr, r: Fr, s: Fr) -> bool {
+    let Some(s_inv) = s.inverse() else {
+        return false;
+    };
+    let point = (Affine::generator() * (msg_hash * s_inv) + *pk * (r * s_inv)).into_affine();
+    !r.is_zero() && !point.infinity && x_mod_order(&point) == r
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr as Bn254Fr;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    /// Runs the verification gadget on the given inputs, returning whether the constraint system
+    /// is satisfied and its number of constraints.
+    fn ecdsa_verify_cs(
+        pk: Affine,
+        msg_hash: Fr,
+        r: Fr,
+        s: Fr,
+    ) -> Result<(bool, usize), SynthesisError> {
+        let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+        let pk = Secp256k1AffineVar::new_witness(cs.clone(), || Ok(pk))?;
+        let msg_hash = EmulatedFpVar::new_witness(cs.clone(), || Ok(msg_hash))?;
+        let r = EmulatedFpVar::new_witness(cs.clone(), || Ok(r))?;
+        let s = EmulatedFpVar::new_witness(cs.clone(), || Ok(s))?;
+        ecdsa_verify_gadget(&pk, &msg_hash, &r, &s)?;
+        Ok((cs.is_satisfied()?, cs.num_constraints()))
+    }
+
+    #[test]
+    fn test_double_scalar_mul() -> Result<(), SynthesisError> {
+        let mut rng = ark_std::test_rng();
+        let q = (Affine::generator() * Fr::rand(&mut rng)).into_affine();
+        // scalars of 16 bits, to keep the test fast
+        let (u1, u2) = (0x9a3fu64, 0x51c8u64);
+        let expected = (Affine::generator() * Fr::from(u1) + q * Fr::from(u2)).into_affine();
+
+        let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+        let bits = |u: u64| -> Result<Vec<_>, SynthesisError> {
+            (0..16)
+                .map(|i| Boolean::new_witness(cs.clone(), || Ok((u >> i) & 1 == 1)))
+                .collect()
+        };
+        let q_var = Secp256k1AffineVar::new_witness(cs.clone(), || Ok(q))?;
+        let point = double_scalar_mul_gadget(&bits(u1)?, &q_var, &bits(u2)?)?;
+        assert_eq!(point.value()?, expected);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ecdsa_verify() -> Result<(), SynthesisError> {
+        let mut rng = ark_std::test_rng();
+        let sk = Fr::rand(&mut rng);
+        let pk = (Affine::generator() * sk).into_affine();
+        let msg_hash = ecdsa_msg_hash(b"an arbitrary 32-byte msg digest!");
+        let (r, s) = ecdsa_sign_native(sk, msg_hash, Fr::rand(&mut rng)).unwrap();
+        assert!(ecdsa_verify_native(&pk, msg_hash, r, s));
+
+        let (satisfied, num_constraints) = ecdsa_verify_cs(pk, msg_hash, r, s)?;
+        assert!(satisfied);
+        println!("ECDSA verification: {} constraints", num_constraints);
+
+        // a wrong signature, a signature of another message, and a signature under another key
+        // are rejected
+        let other_pk = (Affine::generator() * Fr::rand(&mut rng)).into_affine();
+        for (pk, msg_hash, r, s) in [
+            (pk, msg_hash, r, s + Fr::one()),
+            (pk, msg_hash + Fr::one(), r, s),
+            (other_pk, msg_hash, r, s),
+        ] {
+            assert!(!ecdsa_verify_native(&pk, msg_hash, r, s));
+            assert!(!ecdsa_verify_cs(pk, msg_hash, r, s)?.0);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+//! EdDSA signature verification over Baby Jubjub, the twisted Edwards curve embedded in BN254 (in
+//! the form of `ark-ed-on-bn254`), as R1CS constraints over the scalar field of BN254, together
+//! with its native counterpart.
+//!
+//! The messages are elements of the constraint field, and the challenge is the Poseidon hash of
+//! `(R.x, R.y, A.x, A.y, msg)`, as in the EdDSA-Poseidon of circomlib (whose signatures are not
+//! interchangeable with these ones, as it uses another form of the curve). A signature `(R, s)` is
+//! valid for the public key `A` if `s < l` and `s * B = R + H(R, A, msg) * A`, where `B` is the
+//! generator of the subgroup of prime order `l`. As the points are allocated in that subgroup, no
+//! multiplication by the cofactor is needed.
+//!
+//! The verification is native arithmetic of the constraint field, so it costs a few thousand
+//! constraints, against millions for ECDSA over secp256k1 (see [`super::ecdsa`]).
+
+use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ed_on_bn254::{constraints::EdwardsVar, EdwardsAffine, Fq, Fr};
+use ark_ff::{BigInteger, Field, PrimeField};
+use ark_r1cs_std::{
+    boolean::Boolean, convert::ToBitsGadget, eq::EqGadget, fields::fp::FpVar, groups::CurveVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::{rand::Rng, UniformRand};
+use folding_schemes::transcript::poseidon::{poseidon_hash, poseidon_hash_gadget};
+use folding_schemes::Error;
+
+/// Converts a scalar of the curve, which is smaller than the modulus of the constraint field, to
+/// an element of the constraint field.
+pub fn scalar_to_base(s: Fr) -> Fq {
+    Fq::from_le_bytes_mod_order(&s.into_bigint().to_bytes_le())
+}
+
+/// Computes the challenge `H(R, A, msg)` of a signature natively.
+pub fn eddsa_challenge_native(
+    poseidon_config: &PoseidonConfig<Fq>,
+    r: &EdwardsAffine,
+    pk: &EdwardsAffine,
+    msg: Fq,
+) -> Result<Fq, Error> {
+    poseidon_hash(poseidon_config, &[r.x, r.y, pk.x, pk.y, msg])
+}
+
+/// Generates a random key pair `(a, A = a * B)`.
+pub fn eddsa_keygen<R: Rng>(rng: &mut R) -> (Fr, EdwardsAffine) {
+    let sk = Fr::rand(rng);
+    (sk, (EdwardsAffine::generator() * sk).into_affine())
+}
+
+/// Signs `msg` with the secret key `sk`, returning the signature `(R, s)`. The nonce is derived
+/// deterministically from the secret key and the message.
+pub fn eddsa_sign_native(
+    poseidon_config: &PoseidonConfig<Fq>,
+    sk: Fr,
+    msg: Fq,
+) -> Result<(EdwardsAffine, Fr), Error> {
+    let generator = EdwardsAffine::generator();
+    let pk = (generator * sk).into_affine();
+    let nonce = poseidon_hash(poseidon_config, &[scalar_to_base(sk), msg])?;
+    let nonce = Fr::from_le_bytes_mod_order(&nonce.into_bigint().to_bytes_le());
+    let r = (generator * nonce).into_affine();
+    let h = eddsa_challenge_native(poseidon_config, &r, &pk, msg)?;
+    let h = Fr::from_le_bytes_mod_order(&h.into_bigint().to_bytes_le());
+    Ok((r, nonce + h * sk))
+}
+
+/// Native counterpart of [`eddsa_verify_gadget`].
+pub fn eddsa_verify_native(
+    poseidon_config: &PoseidonConfig<Fq>,
+    pk: &EdwardsAffine,
+    msg: Fq,
+    r: &EdwardsAffine,
+    s: Fr,
+) -> Result<bool, Error> {
+    let h = eddsa_challenge_native(poseidon_config, r, pk, msg)?;
+    let h = Fr::from_le_bytes_mod_order(&h.into_bigint().to_bytes_le());
+    Ok(EdwardsAffine::generator() * s == *pk * h + r)
+}
+
+/// Enforces that `(r, s)` is a valid signature of `msg` under the public key `pk`, where `s` is
+/// given as an element of the constraint field and is enforced to be smaller than the order of
+/// the subgroup, so that the signatures are not malleable.
+///
+/// `pk` and `r` are expected to be allocated as witnesses, which enforces that they are in the
+/// subgroup of prime order.
+pub fn eddsa_verify_gadget(
+    cs: ConstraintSystemRef<Fq>,
+    poseidon_config: &PoseidonConfig<Fq>,
+    pk: &EdwardsVar,
+    msg: &FpVar<Fq>,
+    r: &EdwardsVar,
+    s: &FpVar<Fq>,
+) -> Result<(), SynthesisError> {
+    let h = poseidon_hash_gadget(
+        cs,
+        poseidon_config,
+        &[
+            r.x.clone(),
+            r.y.clone(),
+            pk.x.clone(),
+            pk.y.clone(),
+            msg.clone(),
+        ],
+    )?;
+
+    let s_bits = s.to_bits_le()?;
+    // `-1` is `l - 1`
+    Boolean::enforce_smaller_or_equal_than_le(&s_bits, (-Fr::ONE).into_bigint())?;
+
+    let lhs = EdwardsVar::constant(EdwardsAffine::generator().into_group())
+        .scalar_mul_le(s_bits.iter())?;
+    let rhs = pk.scalar_mul_le(h.to_bits_le()?.iter())? + r;
+    lhs.enforce_equal(&rhs)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use folding_schemes::transcript::poseidon::poseidon_canonical_config;
+
+    /// Runs the verification gadget on the given inputs, returning whether the constraint system
+    /// is satisfied and its number of constraints.
+    fn eddsa_verify_cs(
+        pk: EdwardsAffine,
+        msg: Fq,
+        r: EdwardsAffine,
+        s: Fq,
+    ) -> Result<(bool, usize), SynthesisError> {
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let pk = EdwardsVar::new_witness(cs.clone(), || Ok(pk.into_group()))?;
+        let msg = FpVar::new_witness(cs.clone(), || Ok(msg))?;
+        let r = EdwardsVar::new_witness(cs.clone(), || Ok(r.into_group()))?;
+        let s = FpVar::new_witness(cs.clone(), || Ok(s))?;
+        eddsa_verify_gadget(
+            cs.clone(),
+            &poseidon_canonical_config::<Fq>(),
+            &pk,
+            &msg,
+            &r,
+            &s,
+        )?;
+        Ok((cs.is_satisfied()?, cs.num_constraints()))
+    }
+
+    #[test]
+    fn test_eddsa_verify() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let config = poseidon_canonical_config::<Fq>();
+        let (sk, pk) = eddsa_keygen(&mut rng);
+        let msg = Fq::rand(&mut rng);
+        let (r, s) = eddsa_sign_native(&config, sk, msg)?;
+        assert!(eddsa_verify_native(&config, &pk, msg, &r, s)?);
+
+        let (satisfied, num_constraints) = eddsa_verify_cs(pk, msg, r, scalar_to_base(s))?;
+        assert!(satisfied);
+        println!("EdDSA verification: {} constraints", num_constraints);
+
+        // a forged signature, a signature of another message, and a signature under another key
+        // are rejected
+        let (_, other_pk) = eddsa_keygen(&mut rng);
+        for (pk, msg, r, s) in [
+            (pk, msg, r, s + Fr::ONE),
+            (pk, msg + Fq::ONE, r, s),
+            (other_pk, msg, r, s),
+        ] {
+            assert!(!eddsa_verify_native(&config, &pk, msg, &r, s)?);
+            assert!(!eddsa_verify_cs(pk, msg, r, scalar_to_base(s))?.0);
+        }
+
+        // and so is `s + l`, which satisfies the verification equation
+        let l = Fq::from_le_bytes_mod_order(&Fr::MODULUS.to_bytes_le());
+        assert!(!eddsa_verify_cs(pk, msg, r, scalar_to_base(s) + l)?.0);
+        Ok(())
+    }
+}
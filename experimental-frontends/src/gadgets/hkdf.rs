@@ -0,0 +1,229 @@
+//! HMAC-SHA256 ([RFC 2104](https://www.rfc-editor.org/rfc/rfc2104)) and the HKDF key derivation
+//! over it ([RFC 5869](https://www.rfc-editor.org/rfc/rfc5869)) as R1CS constraints over bytes,
+//! together with their native counterparts. The lengths of the keys, messages and derived keys are
+//! fixed at synthesis time.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{boolean::Boolean, convert::ToBitsGadget, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+use core::ops::BitXor;
+
+use super::sha256::{sha256_bytes_native, sha256_gadget};
+
+/// Block size of SHA-256 in bytes, to which the HMAC keys are padded.
+const BLOCK_LEN: usize = 64;
+/// Output size of SHA-256 in bytes.
+pub const HASH_LEN: usize = 32;
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// Computes HMAC-SHA256 of `message` under `key`. Keys longer than a block are hashed first, as in
+/// RFC 2104.
+pub fn hmac_sha256_gadget<F: PrimeField>(
+    key: &[UInt8<F>],
+    message: &[UInt8<F>],
+) -> Result<[UInt8<F>; HASH_LEN], SynthesisError> {
+    let mut key = if key.len() > BLOCK_LEN {
+        sha256_gadget(key)?.to_vec()
+    } else {
+        key.to_vec()
+    };
+    key.resize(BLOCK_LEN, UInt8::constant(0));
+
+    let inner_input = [xor_constant(&key, IPAD)?, message.to_vec()].concat();
+    let inner = sha256_gadget(&inner_input)?;
+    let outer_input = [xor_constant(&key, OPAD)?, inner.to_vec()].concat();
+    sha256_gadget(&outer_input)
+}
+
+/// HKDF-Extract: the pseudorandom key of the input keying material `ikm` under `salt`.
+pub fn hkdf_extract_gadget<F: PrimeField>(
+    salt: &[UInt8<F>],
+    ikm: &[UInt8<F>],
+) -> Result<[UInt8<F>; HASH_LEN], SynthesisError> {
+    hmac_sha256_gadget(salt, ikm)
+}
+
+/// HKDF-Expand: the first `length` bytes of output keying material of the pseudorandom key `prk`
+/// for the context `info`. As in RFC 5869, `length` can not exceed `255 * HASH_LEN`.
+pub fn hkdf_expand_gadget<F: PrimeField>(
+    prk: &[UInt8<F>],
+    info: &[UInt8<F>],
+    length: usize,
+) -> Result<Vec<UInt8<F>>, SynthesisError> {
+    if length > 255 * HASH_LEN {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let mut okm = Vec::with_capacity(length);
+    let mut t: Vec<UInt8<F>> = vec![];
+    for i in 1..=length.div_ceil(HASH_LEN) {
+        let message = [t, info.to_vec(), vec![UInt8::constant(i as u8)]].concat();
+        t = hmac_sha256_gadget(prk, &message)?.to_vec();
+        okm.extend_from_slice(&t);
+    }
+    okm.truncate(length);
+    Ok(okm)
+}
+
+/// XOR of the bytes with a constant, which adds no constraints.
+fn xor_constant<F: PrimeField>(bytes: &[UInt8<F>], c: u8) -> Result<Vec<UInt8<F>>, SynthesisError> {
+    bytes
+        .iter()
+        .map(|byte| {
+            let bits: Vec<Boolean<F>> = byte
+                .to_bits_le()?
+                .iter()
+                .enumerate()
+                .map(|(i, bit)| bit.clone().bitxor(&Boolean::constant((c >> i) & 1 == 1)))
+                .collect();
+            Ok(UInt8::from_bits_le(&bits))
+        })
+        .collect()
+}
+
+/// Native counterpart of [`hmac_sha256_gadget`].
+pub fn hmac_sha256_native(key: &[u8], message: &[u8]) -> [u8; HASH_LEN] {
+    let mut key = if key.len() > BLOCK_LEN {
+        sha256_bytes_native(key).to_vec()
+    } else {
+        key.to_vec()
+    };
+    key.resize(BLOCK_LEN, 0);
+
+    let inner_input = [
+        key.iter().map(|b| b ^ IPAD).collect::<Vec<_>>(),
+        message.to_vec(),
+    ]
+    .concat();
+    let inner = sha256_bytes_native(&inner_input);
+    let outer_input = [
+        key.iter().map(|b| b ^ OPAD).collect::<Vec<_>>(),
+        inner.to_vec(),
+    ]
+    .concat();
+    sha256_bytes_native(&outer_input)
+}
+
+/// Native counterpart of [`hkdf_extract_gadget`].
+pub fn hkdf_extract_native(salt: &[u8], ikm: &[u8]) -> [u8; HASH_LEN] {
+    hmac_sha256_native(salt, ikm)
+}
+
+/// Native counterpart of [`hkdf_expand_gadget`]. Panics if `length` exceeds `255 * HASH_LEN`.
+pub fn hkdf_expand_native(prk: &[u8], info: &[u8], length: usize) -> Vec<u8> {
+    assert!(length <= 255 * HASH_LEN);
+    let mut okm = Vec::with_capacity(length);
+    let mut t = vec![];
+    for i in 1..=length.div_ceil(HASH_LEN) {
+        let message = [t, info.to_vec(), vec![i as u8]].concat();
+        t = hmac_sha256_native(prk, &message).to_vec();
+        okm.extend_from_slice(&t);
+    }
+    okm.truncate(length);
+    okm
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    /// Test cases 1, 2 and 6 of RFC 4231, the last one with a key longer than a block
+    fn hmac_vectors() -> Vec<(Vec<u8>, Vec<u8>, [u8; HASH_LEN])> {
+        vec![
+            (
+                vec![0x0b; 20],
+                b"Hi There".to_vec(),
+                [
+                    0xb0, 0x34, 0x4c, 0x61, 0xd8, 0xdb, 0x38, 0x53, 0x5c, 0xa8, 0xaf, 0xce, 0xaf,
+                    0x0b, 0xf1, 0x2b, 0x88, 0x1d, 0xc2, 0x00, 0xc9, 0x83, 0x3d, 0xa7, 0x26, 0xe9,
+                    0x37, 0x6c, 0x2e, 0x32, 0xcf, 0xf7,
+                ],
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?".to_vec(),
+                [
+                    0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08,
+                    0x95, 0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec,
+                    0x58, 0xb9, 0x64, 0xec, 0x38, 0x43,
+                ],
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                [
+                    0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, 0x0d, 0x8a, 0x26, 0xaa, 0xcb,
+                    0xf5, 0xb7, 0x7f, 0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, 0x05, 0x46,
+                    0x04, 0x0f, 0x0e, 0xe3, 0x7f, 0x54,
+                ],
+            ),
+        ]
+    }
+
+    // Test case 1 of RFC 5869
+    const IKM: [u8; 22] = [0x0b; 22];
+    const SALT: [u8; 13] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+    const INFO: [u8; 10] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9];
+    const PRK: [u8; HASH_LEN] = [
+        0x07, 0x77, 0x09, 0x36, 0x2c, 0x2e, 0x32, 0xdf, 0x0d, 0xdc, 0x3f, 0x0d, 0xc4, 0x7b, 0xba,
+        0x63, 0x90, 0xb6, 0xc7, 0x3b, 0xb5, 0x0f, 0x9c, 0x31, 0x22, 0xec, 0x84, 0x4a, 0xd7, 0xc2,
+        0xb3, 0xe5,
+    ];
+    const OKM: [u8; 42] = [
+        0x3c, 0xb2, 0x5f, 0x25, 0xfa, 0xac, 0xd5, 0x7a, 0x90, 0x43, 0x4f, 0x64, 0xd0, 0x36, 0x2f,
+        0x2a, 0x2d, 0x2d, 0x0a, 0x90, 0xcf, 0x1a, 0x5a, 0x4c, 0x5d, 0xb0, 0x2d, 0x56, 0xec, 0xc4,
+        0xc5, 0xbf, 0x34, 0x00, 0x72, 0x08, 0xd5, 0xb8, 0x87, 0x18, 0x58, 0x65,
+    ];
+
+    #[test]
+    fn test_hkdf_native() {
+        for (key, message, mac) in hmac_vectors() {
+            assert_eq!(hmac_sha256_native(&key, &message), mac);
+        }
+        assert_eq!(hkdf_extract_native(&SALT, &IKM), PRK);
+        assert_eq!(hkdf_expand_native(&PRK, &INFO, OKM.len()), OKM);
+
+        // test case 3 of RFC 5869, with empty salt and info
+        let prk = hkdf_extract_native(&[], &IKM);
+        assert_eq!(
+            hkdf_expand_native(&prk, &[], 42)[..8],
+            [0x8d, 0xa4, 0xe7, 0x75, 0xa5, 0x63, 0xc1, 0x8f]
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha256() -> Result<(), SynthesisError> {
+        for (key, message, mac) in hmac_vectors() {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let key = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(key))?;
+            let message = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(message))?;
+            assert_eq!(hmac_sha256_gadget(&key, &message)?.value()?, mac);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_hkdf() -> Result<(), SynthesisError> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let salt = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(SALT.to_vec()))?;
+        let ikm = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(IKM.to_vec()))?;
+        let info = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(INFO.to_vec()))?;
+        let prk = hkdf_extract_gadget(&salt, &ikm)?;
+        assert_eq!(prk.value()?, PRK);
+        let okm = hkdf_expand_gadget(&prk, &info, OKM.len())?;
+        assert_eq!(okm.value()?, OKM);
+        assert!(cs.is_satisfied()?);
+
+        assert!(matches!(
+            hkdf_expand_gadget(&prk, &info, 255 * HASH_LEN + 1),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+        Ok(())
+    }
+}
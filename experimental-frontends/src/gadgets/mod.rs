@@ -0,0 +1,10 @@
+//! R1CS gadgets of common primitives, to be used from the step circuits of the FCircuits.
+
+pub mod aes;
+pub mod chacha20;
+pub mod ecdsa;
+pub mod eddsa;
+pub mod hkdf;
+pub mod poly1305;
+pub mod sha256;
+pub mod u32ops;
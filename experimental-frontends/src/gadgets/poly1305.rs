@@ -0,0 +1,407 @@
+//! Poly1305 one-time authenticator ([RFC 7539](https://www.rfc-editor.org/rfc/rfc7539) §2.5) as
+//! R1CS constraints, with the arithmetic modulo `2^130 - 5` emulated by the [`NonNativeUintVar`]
+//! of folding-schemes, together with its native counterpart and the tag of the ChaCha20-Poly1305
+//! AEAD construction (RFC 7539 §2.8).
+//!
+//! As [`NonNativeUintVar`], the gadgets require a constraint field of more than 250 bits.
+
+use ark_ff::{
+    fields::{Fp192, MontBackend, MontConfig},
+    BigInteger, One, PrimeField, Zero,
+};
+use ark_r1cs_std::{
+    alloc::AllocVar, boolean::Boolean, convert::ToBitsGadget, eq::EqGadget, fields::fp::FpVar,
+    uint32::UInt32, R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use core::array;
+use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
+use num_bigint::BigUint;
+
+use super::chacha20::{chacha20_block_gadget, chacha20_block_native};
+
+#[derive(MontConfig)]
+#[modulus = "1361129467683753853853498429727072845819"]
+#[generator = "2"]
+pub struct Fp1305Config;
+/// The prime field of order `2^130 - 5` in which Poly1305 evaluates the message.
+pub type Fp1305 = Fp192<MontBackend<Fp1305Config, 3>>;
+
+/// Masks of the clamping of `r`, as little-endian 32-bit words.
+pub const POLY1305_CLAMP: [u32; 4] = [0x0fffffff, 0x0ffffffc, 0x0ffffffc, 0x0ffffffc];
+
+/// Clamps the first half `r` of the one-time key, given as 4 little-endian words.
+pub fn poly1305_clamp_gadget<F: PrimeField>(
+    r: &[UInt32<F>; 4],
+) -> Result<NonNativeUintVar<F>, SynthesisError> {
+    let mut bits = vec![];
+    for (word, mask) in r.iter().zip(POLY1305_CLAMP) {
+        for (i, bit) in word.to_bits_le()?.into_iter().enumerate() {
+            bits.push(if (mask >> i) & 1 == 1 {
+                bit
+            } else {
+                Boolean::FALSE
+            });
+        }
+    }
+    Ok(NonNativeUintVar::from(bits))
+}
+
+/// Absorbs a block of 1 to 16 bytes, given as its little-endian bits, into the accumulator `acc`,
+/// i.e. computes `(acc + block + 2^len(block)) * r mod 2^130 - 5`. The returned accumulator is
+/// reduced.
+pub fn poly1305_block_gadget<F: PrimeField>(
+    acc: &NonNativeUintVar<F>,
+    r: &NonNativeUintVar<F>,
+    block: &[Boolean<F>],
+) -> Result<NonNativeUintVar<F>, SynthesisError> {
+    if block.is_empty() || block.len() > 128 || block.len() % 8 != 0 {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let n = NonNativeUintVar::from([block, &[Boolean::TRUE]].concat());
+    acc.add_no_align(&n)?.mul_no_align(r)?.modulo::<Fp1305>()
+}
+
+/// Absorbs the full 16-byte blocks of `words`, whose length must be a multiple of 4, into the
+/// accumulator `acc`.
+pub fn poly1305_words_gadget<F: PrimeField>(
+    acc: &NonNativeUintVar<F>,
+    r: &NonNativeUintVar<F>,
+    words: &[UInt32<F>],
+) -> Result<NonNativeUintVar<F>, SynthesisError> {
+    if words.len() % 4 != 0 {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let mut acc = acc.clone();
+    for block in words.chunks(4) {
+        let bits = block
+            .iter()
+            .map(|word| word.to_bits_le())
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        acc = poly1305_block_gadget(&acc, r, &bits)?;
+    }
+    Ok(acc)
+}
+
+/// Computes the tag `(acc + s) mod 2^128` as 4 little-endian words, from a reduced accumulator
+/// (as returned by [`poly1305_block_gadget`]) and the second half `s` of the one-time key.
+pub fn poly1305_tag_gadget<F: PrimeField>(
+    acc: &NonNativeUintVar<F>,
+    s: &[UInt32<F>; 4],
+) -> Result<[UInt32<F>; 4], SynthesisError> {
+    let s_bits = s
+        .iter()
+        .map(|word| word.to_bits_le())
+        .collect::<Result<Vec<_>, _>>()?
+        .concat();
+    // acc < 2^130 and s < 2^128, so their sum fits in 131 bits, the low 128 of which are the tag
+    let sum = poly1305_acc_to_fp(acc) + Boolean::le_bits_to_fp(&s_bits)?;
+    let bits = enforce_bit_length(&sum, 131)?;
+    Ok(array::from_fn(|i| {
+        UInt32::from_bits_le(&bits[32 * i..32 * (i + 1)])
+    }))
+}
+
+/// Converts an accumulator carried as a single field element, e.g. in the state of an FCircuit,
+/// enforcing that it fits in 130 bits.
+pub fn poly1305_acc_from_fp<F: PrimeField>(
+    acc: &FpVar<F>,
+) -> Result<NonNativeUintVar<F>, SynthesisError> {
+    Ok(NonNativeUintVar::from(enforce_bit_length(acc, 130)?))
+}
+
+/// Converts an accumulator returned by the gadgets to a single field element, without constraints.
+pub fn poly1305_acc_to_fp<F: PrimeField>(acc: &NonNativeUintVar<F>) -> FpVar<F> {
+    acc.0
+        .iter()
+        .enumerate()
+        .map(|(i, limb)| {
+            &limb.v * F::from(BigUint::one() << (i * NonNativeUintVar::<F>::bits_per_limb()))
+        })
+        .sum()
+}
+
+/// Derives the Poly1305 one-time key of ChaCha20-Poly1305, i.e. the first 8 words of the ChaCha20
+/// block of counter 0.
+pub fn chacha20_poly1305_key_gadget<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    key: &[UInt32<F>; 8],
+    nonce: &[UInt32<F>; 3],
+) -> Result<[UInt32<F>; 8], SynthesisError> {
+    let block = chacha20_block_gadget(cs, key, nonce, &UInt32::constant(0))?;
+    Ok(array::from_fn(|i| block[i].clone()))
+}
+
+/// Returns the `n` low bits of `fp`, enforcing that `fp` has no other bits.
+fn enforce_bit_length<F: PrimeField>(
+    fp: &FpVar<F>,
+    n: usize,
+) -> Result<Vec<Boolean<F>>, SynthesisError> {
+    if let FpVar::Constant(c) = fp {
+        let c = c.into_bigint();
+        if c.num_bits() as usize > n {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        return Ok(c.to_bits_le()[..n]
+            .iter()
+            .map(|b| Boolean::constant(*b))
+            .collect());
+    }
+    let cs = fp.cs();
+    let bits = (0..n)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok(fp.value()?.into_bigint().get_bit(i))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp(&bits)?.enforce_equal(fp)?;
+    Ok(bits)
+}
+
+/// Native counterpart of the Poly1305 gadgets, which computes the tag of `msg` under the one-time
+/// key `key`.
+pub fn poly1305_native(key: [u32; 8], msg: &[u8]) -> [u32; 4] {
+    let p = (BigUint::one() << 130) - 5u32;
+    let clamped: Vec<u32> = key[..4]
+        .iter()
+        .zip(POLY1305_CLAMP)
+        .map(|(word, mask)| word & mask)
+        .collect();
+    let r = BigUint::from_slice(&clamped);
+    let s = BigUint::from_slice(&key[4..]);
+
+    let mut acc = BigUint::zero();
+    for block in msg.chunks(16) {
+        let n = BigUint::from_bytes_le(block) + (BigUint::one() << (8 * block.len()));
+        acc = (acc + n) * &r % &p;
+    }
+    let mut tag = ((acc + s) % (BigUint::one() << 128)).to_u32_digits();
+    tag.resize(4, 0);
+    array::from_fn(|i| tag[i])
+}
+
+/// Message authenticated by the Poly1305 tag of ChaCha20-Poly1305: the additional data and the
+/// ciphertext, each zero-padded to a multiple of 16 bytes, followed by their lengths as 64-bit
+/// little-endian integers.
+pub fn chacha20_poly1305_mac_data(aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let padding = |len: usize| vec![0u8; (16 - len % 16) % 16];
+    [
+        aad,
+        &padding(aad.len())[..],
+        ciphertext,
+        &padding(ciphertext.len())[..],
+        &(aad.len() as u64).to_le_bytes()[..],
+        &(ciphertext.len() as u64).to_le_bytes()[..],
+    ]
+    .concat()
+}
+
+/// Encrypts `plaintext` with ChaCha20-Poly1305, returning the ciphertext and the tag.
+pub fn chacha20_poly1305_native(
+    key: [u32; 8],
+    nonce: [u32; 3],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> (Vec<u8>, [u32; 4]) {
+    let one_time_key = chacha20_block_native(key, nonce, 0);
+    let ciphertext: Vec<u8> = plaintext
+        .chunks(64)
+        .enumerate()
+        .flat_map(|(i, block)| {
+            let keystream = chacha20_block_native(key, nonce, 1 + i as u32);
+            let keystream = keystream.iter().flat_map(|word| word.to_le_bytes());
+            block
+                .iter()
+                .zip(keystream)
+                .map(|(m, k)| m ^ k)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let tag = poly1305_native(
+        array::from_fn(|i| one_time_key[i]),
+        &chacha20_poly1305_mac_data(aad, &ciphertext),
+    );
+    (ciphertext, tag)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    fn bytes_to_words(bytes: &[u8]) -> Vec<u32> {
+        bytes
+            .chunks(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    fn hex_to_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn words_var<const N: usize>(
+        cs: ConstraintSystemRef<Fr>,
+        words: &[u32],
+    ) -> Result<[UInt32<Fr>; N], SynthesisError> {
+        let words = words
+            .iter()
+            .map(|&w| UInt32::new_witness(cs.clone(), || Ok(w)))
+            .collect::<Result<Vec<_>, _>>()?;
+        words.try_into().map_err(|_| SynthesisError::Unsatisfiable)
+    }
+
+    /// Computes the tag of `msg` in-circuit, with the bits of the message and the words of the
+    /// one-time key as witnesses.
+    fn poly1305_gadget(
+        cs: ConstraintSystemRef<Fr>,
+        key: &[UInt32<Fr>; 8],
+        msg: &[u8],
+    ) -> Result<[UInt32<Fr>; 4], SynthesisError> {
+        let r = poly1305_clamp_gadget(&array::from_fn(|i| key[i].clone()))?;
+        let s = array::from_fn(|i| key[4 + i].clone());
+        let mut acc = poly1305_acc_from_fp(&FpVar::constant(Fr::zero()))?;
+        for block in msg.chunks(16) {
+            let bits: Vec<bool> = block
+                .iter()
+                .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+                .collect();
+            let bits = Vec::<Boolean<Fr>>::new_witness(cs.clone(), || Ok(bits))?;
+            acc = poly1305_block_gadget(&acc, &r, &bits)?;
+        }
+        poly1305_tag_gadget(&acc, &s)
+    }
+
+    const AEAD_PLAINTEXT: &[u8] =
+        b"Ladies and Gentlemen of the class of '99: If I could offer you \
+        only one tip for the future, sunscreen would be it.";
+
+    /// Test vectors of RFC 7539 2.5.2, 2.6.2 and 2.8.2
+    #[test]
+    fn test_poly1305_native() {
+        let key = bytes_to_words(&hex_to_bytes(
+            "85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b",
+        ));
+        assert_eq!(
+            poly1305_native(
+                key.try_into().unwrap(),
+                b"Cryptographic Forum Research Group"
+            ),
+            [0xc11d06a8, 0xc6365130, 0xaf8b2bc2, 0xa927010c]
+        );
+
+        let key: [u32; 8] = array::from_fn(|i| 0x83828180 + 0x04040404 * i as u32);
+        let one_time_key = chacha20_block_native(key, [0x00000000, 0x03020100, 0x07060504], 0);
+        assert_eq!(
+            one_time_key[..8],
+            [
+                0x8ba0d58a, 0xcc815f90, 0x27405081, 0x7194b24a, 0x37b633a8, 0xa50dfde3, 0xe2b8db08,
+                0x46a6d1fd,
+            ]
+        );
+
+        let (ciphertext, tag) = chacha20_poly1305_native(
+            key,
+            [0x00000007, 0x43424140, 0x47464544],
+            &hex_to_bytes("50515253c0c1c2c3c4c5c6c7"),
+            AEAD_PLAINTEXT,
+        );
+        assert_eq!(
+            ciphertext,
+            hex_to_bytes(
+                "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca967\
+                 1282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee32809\
+                 1b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b6116"
+            )
+        );
+        assert_eq!(tag, [0x590be11a, 0x6ae2094f, 0xcb2e907e, 0x910660d0]);
+    }
+
+    /// Test vector of RFC 7539 2.5.2, whose last block is partial
+    #[test]
+    fn test_poly1305() -> Result<(), SynthesisError> {
+        let key = bytes_to_words(&hex_to_bytes(
+            "85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b",
+        ));
+        let msg = b"Cryptographic Forum Research Group";
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let key_var = words_var::<8>(cs.clone(), &key)?;
+        let tag = poly1305_gadget(cs.clone(), &key_var, msg)?;
+        assert_eq!(
+            tag.value()?,
+            [0xc11d06a8, 0xc6365130, 0xaf8b2bc2, 0xa927010c]
+        );
+        assert_eq!(tag.value()?, poly1305_native(key.try_into().unwrap(), msg));
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    /// Test vector of RFC 7539 2.8.2, with the one-time key derived in-circuit
+    #[test]
+    fn test_chacha20_poly1305() -> Result<(), SynthesisError> {
+        let key: [u32; 8] = array::from_fn(|i| 0x83828180 + 0x04040404 * i as u32);
+        let nonce = [0x00000007, 0x43424140, 0x47464544];
+        let aad = hex_to_bytes("50515253c0c1c2c3c4c5c6c7");
+        let (ciphertext, _) = chacha20_poly1305_native(key, nonce, &aad, AEAD_PLAINTEXT);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let key_var = words_var::<8>(cs.clone(), &key)?;
+        let nonce_var = words_var::<3>(cs.clone(), &nonce)?;
+        let one_time_key = chacha20_poly1305_key_gadget(cs.clone(), &key_var, &nonce_var)?;
+        let tag = poly1305_gadget(
+            cs.clone(),
+            &one_time_key,
+            &chacha20_poly1305_mac_data(&aad, &ciphertext),
+        )?;
+        assert_eq!(
+            tag.value()?,
+            [0x590be11a, 0x6ae2094f, 0xcb2e907e, 0x910660d0]
+        );
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_poly1305_words() -> Result<(), SynthesisError> {
+        let key: [u32; 8] = array::from_fn(|i| 0x01234567 * (i as u32 + 1));
+        let words: Vec<u32> = (0..16u32).map(|j| j.wrapping_mul(0x9e3779b9)).collect();
+        let msg: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let key_var = words_var::<8>(cs.clone(), &key)?;
+        let msg_var = words_var::<16>(cs.clone(), &words)?;
+        let r = poly1305_clamp_gadget(&array::from_fn(|i| key_var[i].clone()))?;
+        let acc = poly1305_acc_from_fp(&FpVar::constant(Fr::zero()))?;
+        let acc = poly1305_words_gadget(&acc, &r, &msg_var)?;
+        // the accumulator goes through a single field element, as in a folding state
+        let acc = poly1305_acc_from_fp(&poly1305_acc_to_fp(&acc))?;
+        let tag = poly1305_tag_gadget(&acc, &array::from_fn(|i| key_var[4 + i].clone()))?;
+        assert_eq!(tag.value()?, poly1305_native(key, &msg));
+        assert!(cs.is_satisfied()?);
+
+        assert!(poly1305_words_gadget(&acc, &r, &msg_var[..3]).is_err());
+        assert!(poly1305_block_gadget(&acc, &r, &[]).is_err());
+        Ok(())
+    }
+
+    /// Accumulators of more than 130 bits are rejected
+    #[test]
+    fn test_poly1305_acc_from_fp() -> Result<(), SynthesisError> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let acc = (BigUint::one() << 130) - 1u32;
+        let acc_var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(acc.clone())))?;
+        assert_eq!(poly1305_acc_from_fp(&acc_var)?.value()?, acc);
+        assert!(cs.is_satisfied()?);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let acc_var = FpVar::new_witness(cs.clone(), || Ok(Fr::from(BigUint::one() << 130)))?;
+        poly1305_acc_from_fp(&acc_var)?;
+        assert!(!cs.is_satisfied()?);
+
+        assert!(poly1305_acc_from_fp(&FpVar::constant(Fr::from(BigUint::one() << 130))).is_err());
+        Ok(())
+    }
+}
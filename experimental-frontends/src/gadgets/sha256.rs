@@ -0,0 +1,289 @@
+//! SHA-256 compression function ([FIPS 180-4](https://csrc.nist.gov/pubs/fips/180-4/upd1/final))
+//! as R1CS constraints over 32-bit words, together with its native counterpart and the padding of
+//! the messages.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{convert::ToBitsGadget, uint32::UInt32, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+use core::array;
+
+use super::u32ops::{and, rotr, shr, xor};
+
+/// Initial hash value of SHA-256.
+pub const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Round constants of SHA-256.
+pub const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Computes the SHA-256 compression function, i.e. the next hash value for the given hash value
+/// and 16-word block. The words are the big-endian words of the message, as in FIPS 180-4.
+pub fn sha256_compress_gadget<F: PrimeField>(
+    state: &[UInt32<F>; 8],
+    block: &[UInt32<F>; 16],
+) -> Result<[UInt32<F>; 8], SynthesisError> {
+    // message schedule
+    let mut w = block.to_vec();
+    for t in 16..64 {
+        let s0 = xor3(
+            &rotr(&w[t - 15], 7)?,
+            &rotr(&w[t - 15], 18)?,
+            &shr(&w[t - 15], 3)?,
+        )?;
+        let s1 = xor3(
+            &rotr(&w[t - 2], 17)?,
+            &rotr(&w[t - 2], 19)?,
+            &shr(&w[t - 2], 10)?,
+        )?;
+        let word = s1
+            .wrapping_add(&w[t - 7])
+            .wrapping_add(&s0)
+            .wrapping_add(&w[t - 16]);
+        w.push(word);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state.clone();
+    for t in 0..64 {
+        let s1 = xor3(&rotr(&e, 6)?, &rotr(&e, 11)?, &rotr(&e, 25)?)?;
+        let t1 = h
+            .wrapping_add(&s1)
+            .wrapping_add(&ch(&e, &f, &g)?)
+            .wrapping_add(&UInt32::constant(SHA256_K[t]))
+            .wrapping_add(&w[t]);
+        let s0 = xor3(&rotr(&a, 2)?, &rotr(&a, 13)?, &rotr(&a, 22)?)?;
+        let t2 = s0.wrapping_add(&maj(&a, &b, &c)?);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(&t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(&t2);
+    }
+    let working = [a, b, c, d, e, f, g, h];
+    Ok(array::from_fn(|i| state[i].wrapping_add(&working[i])))
+}
+
+fn xor3<F: PrimeField>(
+    a: &UInt32<F>,
+    b: &UInt32<F>,
+    c: &UInt32<F>,
+) -> Result<UInt32<F>, SynthesisError> {
+    xor(&xor(a, b)?, c)
+}
+
+/// `(e & f) ^ (!e & g)`, computed as `g ^ (e & (f ^ g))`
+fn ch<F: PrimeField>(
+    e: &UInt32<F>,
+    f: &UInt32<F>,
+    g: &UInt32<F>,
+) -> Result<UInt32<F>, SynthesisError> {
+    xor(g, &and(e, &xor(f, g)?)?)
+}
+
+/// `(a & b) ^ (a & c) ^ (b & c)`, computed as `(a & b) ^ (c & (a ^ b))`
+fn maj<F: PrimeField>(
+    a: &UInt32<F>,
+    b: &UInt32<F>,
+    c: &UInt32<F>,
+) -> Result<UInt32<F>, SynthesisError> {
+    xor(&and(a, b)?, &and(c, &xor(a, b)?)?)
+}
+
+/// Computes the SHA-256 hash of `msg`, as its 32 bytes. The length of `msg` is fixed at synthesis
+/// time, so that the padding is made of constants and the hash costs one compression per block of
+/// the padded message.
+pub fn sha256_gadget<F: PrimeField>(msg: &[UInt8<F>]) -> Result<[UInt8<F>; 32], SynthesisError> {
+    let mut padded = msg.to_vec();
+    padded.push(UInt8::constant(0x80));
+    while padded.len() % 64 != 56 {
+        padded.push(UInt8::constant(0));
+    }
+    padded.extend((8 * msg.len() as u64).to_be_bytes().map(UInt8::constant));
+
+    let mut state = SHA256_IV.map(UInt32::constant);
+    for block in padded.chunks(64) {
+        // big-endian words of the bytes
+        let words = block
+            .chunks(4)
+            .map(|word| {
+                let bits = word
+                    .iter()
+                    .rev()
+                    .map(|byte| byte.to_bits_le())
+                    .collect::<Result<Vec<_>, _>>()?
+                    .concat();
+                Ok(UInt32::from_bits_le(&bits))
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+        state = sha256_compress_gadget(&state, &array::from_fn(|i| words[i].clone()))?;
+    }
+
+    let mut bytes = Vec::with_capacity(32);
+    for word in &state {
+        let bits = word.to_bits_le()?;
+        for byte in (0..4).rev() {
+            bytes.push(UInt8::from_bits_le(&bits[8 * byte..8 * (byte + 1)]));
+        }
+    }
+    Ok(array::from_fn(|i| bytes[i].clone()))
+}
+
+/// Native counterpart of [`sha256_compress_gadget`].
+pub fn sha256_compress_native(state: [u32; 8], block: [u32; 16]) -> [u32; 8] {
+    let mut w = block.to_vec();
+    for t in 16..64 {
+        let s0 = w[t - 15].rotr(7) ^ w[t - 15].rotr(18) ^ (w[t - 15] >> 3);
+        let s1 = w[t - 2].rotr(17) ^ w[t - 2].rotr(19) ^ (w[t - 2] >> 10);
+        w.push(
+            s1.wrapping_add(w[t - 7])
+                .wrapping_add(s0)
+                .wrapping_add(w[t - 16]),
+        );
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    for t in 0..64 {
+        let s1 = e.rotr(6) ^ e.rotr(11) ^ e.rotr(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[t])
+            .wrapping_add(w[t]);
+        let s0 = a.rotr(2) ^ a.rotr(13) ^ a.rotr(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    let working = [a, b, c, d, e, f, g, h];
+    array::from_fn(|i| state[i].wrapping_add(working[i]))
+}
+
+/// Pads `msg` as in FIPS 180-4 §5.1.1, i.e. appends the byte `0x80`, the least number of zero
+/// bytes, and the length of `msg` in bits as a 64-bit big-endian integer, and returns the 16-word
+/// blocks of the padded message.
+pub fn sha256_pad(msg: &[u8]) -> Vec<[u32; 16]> {
+    let mut padded = msg.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend((8 * msg.len() as u64).to_be_bytes());
+    padded
+        .chunks(64)
+        .map(|block| array::from_fn(|i| u32::from_be_bytes(array::from_fn(|j| block[4 * i + j]))))
+        .collect()
+}
+
+/// Computes natively the SHA-256 hash of `msg`, as 8 big-endian words.
+pub fn sha256_native(msg: &[u8]) -> [u32; 8] {
+    sha256_pad(msg)
+        .into_iter()
+        .fold(SHA256_IV, sha256_compress_native)
+}
+
+/// Native counterpart of [`sha256_gadget`], i.e. the bytes of [`sha256_native`].
+pub fn sha256_bytes_native(msg: &[u8]) -> [u8; 32] {
+    let hash = sha256_native(msg);
+    array::from_fn(|i| hash[i / 4].to_be_bytes()[i % 4])
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    /// Test vectors of FIPS 180-2 Appendix B.1 and B.2, and the hash of the empty message
+    const VECTORS: [(&[u8], [u32; 8]); 3] = [
+        (
+            b"abc",
+            [
+                0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+                0xf20015ad,
+            ],
+        ),
+        (
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            [
+                0x248d6a61, 0xd20638b8, 0xe5c02693, 0x0c3e6039, 0xa33ce459, 0x64ff2167, 0xf6ecedd4,
+                0x19db06c1,
+            ],
+        ),
+        (
+            b"",
+            [
+                0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c, 0xa495991b,
+                0x7852b855,
+            ],
+        ),
+    ];
+
+    #[test]
+    fn test_sha256_native() {
+        for (msg, hash) in VECTORS {
+            assert_eq!(sha256_native(msg), hash);
+        }
+        assert_eq!(sha256_pad(b"abc").len(), 1);
+        // 56 bytes leave no room for the length in the first block
+        assert_eq!(sha256_pad(&[0; 55]).len(), 1);
+        assert_eq!(sha256_pad(&[0; 56]).len(), 2);
+    }
+
+    #[test]
+    fn test_sha256_compress() -> Result<(), SynthesisError> {
+        for (msg, hash) in VECTORS {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let mut state = SHA256_IV.map(UInt32::constant);
+            for block in sha256_pad(msg) {
+                let block = block
+                    .iter()
+                    .map(|&w| UInt32::new_witness(cs.clone(), || Ok(w)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                state = sha256_compress_gadget(&state, &array::from_fn(|i| block[i].clone()))?;
+            }
+            assert_eq!(state.value()?, hash);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha256() -> Result<(), SynthesisError> {
+        // 119 bytes take two blocks, and 120 bytes three
+        let long: Vec<u8> = (0..120).collect();
+        let mut vectors: Vec<(&[u8], [u8; 32])> = VECTORS
+            .iter()
+            .map(|&(msg, hash)| (msg, array::from_fn(|i| hash[i / 4].to_be_bytes()[i % 4])))
+            .collect();
+        vectors.push((&long[..119], sha256_bytes_native(&long[..119])));
+        vectors.push((&long, sha256_bytes_native(&long)));
+        for (msg, hash) in vectors {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let msg = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(msg.to_vec()))?;
+            assert_eq!(sha256_gadget(&msg)?.value()?, hash);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+//! Operations on 32-bit words as R1CS constraints, shared by the gadgets of the ciphers and hash
+//! functions, together with the conversions between words and field elements.
+//!
+//! The costs given in the documentation of each operation are for non-constant words, and are
+//! checked by the tests of this module. The operations on constant words add no constraints.
+
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::AllocVar, boolean::Boolean, convert::ToBitsGadget, eq::EqGadget, fields::fp::FpVar,
+    uint32::UInt32, R1CSVar,
+};
+use ark_relations::r1cs::SynthesisError;
+use core::ops::{BitAnd, BitXor, Not};
+
+/// Bitwise XOR, 32 constraints.
+pub fn xor<F: PrimeField>(a: &UInt32<F>, b: &UInt32<F>) -> Result<UInt32<F>, SynthesisError> {
+    let bits: Vec<Boolean<F>> = a
+        .to_bits_le()?
+        .iter()
+        .zip(&b.to_bits_le()?)
+        .map(|(a_bit, b_bit)| a_bit.clone().bitxor(b_bit))
+        .collect();
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+/// Bitwise AND, 32 constraints.
+pub fn and<F: PrimeField>(a: &UInt32<F>, b: &UInt32<F>) -> Result<UInt32<F>, SynthesisError> {
+    let bits: Vec<Boolean<F>> = a
+        .to_bits_le()?
+        .iter()
+        .zip(&b.to_bits_le()?)
+        .map(|(a_bit, b_bit)| a_bit.clone().bitand(b_bit))
+        .collect();
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+/// Bitwise NOT, which adds no constraints.
+pub fn not<F: PrimeField>(a: &UInt32<F>) -> Result<UInt32<F>, SynthesisError> {
+    let bits: Vec<Boolean<F>> = a.to_bits_le()?.into_iter().map(Boolean::not).collect();
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+/// Left rotation by `n` bits, which only rewires the bits and thus adds no constraints.
+pub fn rotl<F: PrimeField>(x: &UInt32<F>, n: usize) -> Result<UInt32<F>, SynthesisError> {
+    let bits = x.to_bits_le()?;
+    let rotated: Vec<Boolean<F>> = (0..32)
+        .map(|i| bits[(i + 32 - n % 32) % 32].clone())
+        .collect();
+    Ok(UInt32::from_bits_le(&rotated))
+}
+
+/// Right rotation by `n` bits, which adds no constraints.
+pub fn rotr<F: PrimeField>(x: &UInt32<F>, n: usize) -> Result<UInt32<F>, SynthesisError> {
+    rotl(x, 32 - n % 32)
+}
+
+/// Right shift by `n` bits, which adds no constraints.
+pub fn shr<F: PrimeField>(x: &UInt32<F>, n: usize) -> Result<UInt32<F>, SynthesisError> {
+    let bits = x.to_bits_le()?;
+    let shifted: Vec<Boolean<F>> = (0..32)
+        .map(|i| bits.get(i + n).cloned().unwrap_or(Boolean::FALSE))
+        .collect();
+    Ok(UInt32::from_bits_le(&shifted))
+}
+
+/// Reverses the order of the bytes, e.g. to read a big-endian word as a little-endian one, which
+/// adds no constraints.
+pub fn swap_bytes<F: PrimeField>(x: &UInt32<F>) -> Result<UInt32<F>, SynthesisError> {
+    let bits = x.to_bits_le()?;
+    let swapped: Vec<Boolean<F>> = (0..32)
+        .map(|i| bits[8 * (3 - i / 8) + i % 8].clone())
+        .collect();
+    Ok(UInt32::from_bits_le(&swapped))
+}
+
+/// Addition modulo 2^32, 34 constraints: the 33 bits of the sum and their recomposition.
+pub fn wrapping_add<F: PrimeField>(a: &UInt32<F>, b: &UInt32<F>) -> UInt32<F> {
+    a.wrapping_add(b)
+}
+
+/// Packs the words into a field element, as the little-endian number whose 32-bit limbs are the
+/// words, i.e. the first word is the least significant one. This adds no constraints, since the
+/// bits of the words are already boolean. The packing is injective as long as the words fit in
+/// fewer bits than the modulus, otherwise `SynthesisError::Unsatisfiable` is returned.
+pub fn pack_u32s_to_fp<F: PrimeField>(words: &[UInt32<F>]) -> Result<FpVar<F>, SynthesisError> {
+    if 32 * words.len() >= F::MODULUS_BIT_SIZE as usize {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let bits = words
+        .iter()
+        .map(|word| word.to_bits_le())
+        .collect::<Result<Vec<_>, _>>()?
+        .concat();
+    Boolean::le_bits_to_fp(&bits)
+}
+
+/// Packs a single word into a field element, see [`pack_u32s_to_fp`].
+pub fn u32_to_fp<F: PrimeField>(word: &UInt32<F>) -> Result<FpVar<F>, SynthesisError> {
+    pack_u32s_to_fp(core::slice::from_ref(word))
+}
+
+/// Unpacks `fp` into `n` words, the inverse of [`pack_u32s_to_fp`], enforcing that the value of
+/// `fp` fits in `32 * n` bits. This costs `32 * n + 1` constraints: the booleanity of the bits of
+/// the words and their recomposition.
+pub fn unpack_fp_to_u32s<F: PrimeField>(
+    fp: &FpVar<F>,
+    n: usize,
+) -> Result<Vec<UInt32<F>>, SynthesisError> {
+    if 32 * n >= F::MODULUS_BIT_SIZE as usize {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    if let FpVar::Constant(c) = fp {
+        let c = c.into_bigint();
+        if c.num_bits() as usize > 32 * n {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        return Ok((0..n)
+            .map(|j| {
+                let word =
+                    (0..32).fold(0u32, |word, i| word | ((c.get_bit(32 * j + i) as u32) << i));
+                UInt32::constant(word)
+            })
+            .collect());
+    }
+    let cs = fp.cs();
+    let bits = (0..32 * n)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok(fp.value()?.into_bigint().get_bit(i))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp(&bits)?.enforce_equal(fp)?;
+    Ok(bits.chunks(32).map(UInt32::from_bits_le).collect())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::One;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    #[test]
+    fn test_u32ops() -> Result<(), SynthesisError> {
+        for (a, b) in [
+            (0x00000000, 0xffffffff),
+            (0xdeadbeef, 0x01234567),
+            (0xffffffff, 0x00000001),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a_var = UInt32::new_witness(cs.clone(), || Ok(a))?;
+            let b_var = UInt32::new_witness(cs.clone(), || Ok(b))?;
+
+            let mut n_constraints = cs.num_constraints();
+            let mut check = |value: u32, expected: u32, cost: usize| {
+                assert_eq!(value, expected);
+                assert_eq!(cs.num_constraints() - n_constraints, cost);
+                n_constraints = cs.num_constraints();
+            };
+            check(xor(&a_var, &b_var)?.value()?, a ^ b, 32);
+            check(and(&a_var, &b_var)?.value()?, a & b, 32);
+            check(not(&a_var)?.value()?, !a, 0);
+            for n in [0, 1, 7, 16, 31] {
+                check(rotl(&a_var, n)?.value()?, a.rotate_left(n as u32), 0);
+                check(rotr(&a_var, n)?.value()?, a.rotate_right(n as u32), 0);
+                check(shr(&a_var, n)?.value()?, a >> n, 0);
+            }
+            check(swap_bytes(&a_var)?.value()?, a.swap_bytes(), 0);
+            check(wrapping_add(&a_var, &b_var).value()?, a.wrapping_add(b), 34);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_pack_unpack() -> Result<(), SynthesisError> {
+        let words = [0xdeadbeef, 0x01234567, 0xffffffff];
+        let value = Fr::from(0xffffffff_01234567_deadbeef_u128);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let vars = words
+            .iter()
+            .map(|&w| UInt32::new_witness(cs.clone(), || Ok(w)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let n_constraints = cs.num_constraints();
+        assert_eq!(pack_u32s_to_fp(&vars)?.value()?, value);
+        assert_eq!(u32_to_fp(&vars[0])?.value()?, Fr::from(words[0]));
+        assert_eq!(cs.num_constraints(), n_constraints);
+
+        let fp = FpVar::new_witness(cs.clone(), || Ok(value))?;
+        let n_constraints = cs.num_constraints();
+        let unpacked = unpack_fp_to_u32s(&fp, 3)?;
+        assert_eq!(cs.num_constraints() - n_constraints, 3 * 32 + 1);
+        assert_eq!(unpacked.value()?, words);
+        assert!(cs.is_satisfied()?);
+
+        // values which do not fit in the words are rejected
+        for n in [1, 2] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let fp = FpVar::new_witness(cs.clone(), || Ok(value))?;
+            unpack_fp_to_u32s(&fp, n)?;
+            assert!(!cs.is_satisfied()?);
+        }
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let fp = FpVar::new_witness(cs.clone(), || Ok(-Fr::one()))?;
+        unpack_fp_to_u32s(&fp, 7)?;
+        assert!(!cs.is_satisfied()?);
+
+        // constants
+        assert_eq!(
+            unpack_fp_to_u32s(&FpVar::Constant(value), 3)?.value()?,
+            words
+        );
+        assert!(unpack_fp_to_u32s(&FpVar::Constant(value), 2).is_err());
+
+        // at most 7 words fit in the 254 bits of the modulus
+        assert!(unpack_fp_to_u32s(&fp, 8).is_err());
+        assert!(pack_u32s_to_fp(&[vars.clone(), vars.clone(), vars].concat()).is_err());
+        Ok(())
+    }
+}
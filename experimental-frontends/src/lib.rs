@@ -1,4 +1,51 @@
 pub mod circom;
+pub mod gadgets;
 pub mod noir;
 pub mod noname;
 pub mod utils;
+
+/// Errors specific to the frontends, which are returned to the folding schemes wrapped in
+/// [`folding_schemes::Error::Frontend`].
+#[derive(Debug, thiserror::Error)]
+pub enum FrontendError {
+    #[error("The Noir black box function {0} is not supported")]
+    UnsupportedBlackBox(String),
+    #[error("The Noir oracle '{0}' is not supported, the circuit must be solvable without external oracles")]
+    NoirOracleNotSupported(String),
+    #[error("Noir assertion of opcode {opcode_index} failed at step {step}: {message}")]
+    NoirAssertionFailed {
+        step: usize,
+        opcode_index: usize,
+        message: String,
+    },
+    #[error("Unsupported Noir version {found}, the supported versions are {supported:?}")]
+    UnsupportedNoirVersion {
+        found: String,
+        supported: Vec<String>,
+    },
+    #[error("Unsupported ACIR bytecode, compiled with Noir {0}: {1}")]
+    UnsupportedAcirVersion(String, String),
+    #[error("The circuit is defined over the field of modulus {found}, but it was loaded over the field of modulus {expected}")]
+    FieldMismatch { expected: String, found: String },
+}
+
+impl From<FrontendError> for folding_schemes::Error {
+    fn from(err: FrontendError) -> Self {
+        folding_schemes::Error::Frontend(Box::new(err))
+    }
+}
+
+impl TryFrom<folding_schemes::Error> for FrontendError {
+    type Error = folding_schemes::Error;
+
+    /// Recovers the [`FrontendError`] wrapped in `err`, or gives `err` back if it is another error.
+    fn try_from(err: folding_schemes::Error) -> Result<Self, Self::Error> {
+        match err {
+            folding_schemes::Error::Frontend(err) => err
+                .downcast::<FrontendError>()
+                .map(|err| *err)
+                .map_err(folding_schemes::Error::Frontend),
+            err => Err(err),
+        }
+    }
+}
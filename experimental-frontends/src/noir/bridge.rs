@@ -4,12 +4,17 @@ use std::convert::TryInto;
 
 use acvm::acir::{
     acir_field::GenericFieldElement,
-    circuit::{Circuit, Opcode, PublicInputs},
+    circuit::{
+        opcodes::{BlackBoxFuncCall, ConstantOrWitnessEnum, FunctionInput},
+        Circuit, Opcode, PublicInputs,
+    },
     native_types::{Expression, Witness, WitnessMap},
 };
-use ark_ff::{Field, PrimeField};
+use ark_ff::{BigInteger, Field, PrimeField};
 use ark_r1cs_std::alloc::AllocVar;
-use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::{AllocatedFp, FpVar};
 use ark_relations::{
     lc,
     r1cs::{
@@ -34,12 +39,22 @@ use ark_relations::{
 // - verify(index_vk, verifier, rng)
 #[derive(Clone)]
 pub struct AcirCircuitSonobe<'a, F: Field + PrimeField> {
-    pub(crate) gates: Vec<Expression<GenericFieldElement<F>>>,
-    pub(crate) public_inputs: PublicInputs,
+    pub(crate) lowered: &'a LoweredAcirCircuit<F>,
     pub(crate) values: BTreeMap<Witness, F>,
     pub already_assigned_witnesses: HashMap<Witness, &'a FpVar<F>>,
 }
 
+/// Step independent part of the lowering of an ACIR circuit, which is extracted once from the
+/// circuit and shared by the `AcirCircuitSonobe` of every step.
+#[derive(Clone, Debug)]
+pub struct LoweredAcirCircuit<F: PrimeField> {
+    pub(crate) gates: Vec<Expression<GenericFieldElement<F>>>,
+    // black box calls lowered to constraints, see `is_supported_black_box`
+    pub(crate) black_box_calls: Vec<BlackBoxFuncCall<GenericFieldElement<F>>>,
+    pub(crate) public_inputs: PublicInputs,
+    pub(crate) num_variables: usize,
+}
+
 impl<'a, ConstraintF: Field + PrimeField> ConstraintSynthesizer<ConstraintF>
     for AcirCircuitSonobe<'a, ConstraintF>
 {
@@ -58,7 +73,7 @@ impl<'a, ConstraintF: Field + PrimeField> ConstraintSynthesizer<ConstraintF>
                 } else {
                     return Err(SynthesisError::Unsatisfiable);
                 }
-            } else if self.public_inputs.contains(i.0.try_into().unwrap()) {
+            } else if self.lowered.public_inputs.contains(i.0.try_into().unwrap()) {
                 cs.new_witness_variable(|| Ok(*val))?
             } else {
                 cs.new_witness_variable(|| Ok(*val))?
@@ -67,11 +82,11 @@ impl<'a, ConstraintF: Field + PrimeField> ConstraintSynthesizer<ConstraintF>
         }
 
         // Now iterate each gate and add it to the constraint system
-        for gate in self.gates {
+        for gate in &self.lowered.gates {
             let mut arith_gate = LinearCombination::<ConstraintF>::new();
 
             // Process mul terms
-            for mul_term in gate.mul_terms {
+            for mul_term in &gate.mul_terms {
                 let coeff = mul_term.0;
                 let left_val = self.values[&mul_term.1];
                 let right_val = self.values[&mul_term.2];
@@ -85,7 +100,7 @@ impl<'a, ConstraintF: Field + PrimeField> ConstraintSynthesizer<ConstraintF>
             }
 
             // Process Add terms
-            for add_term in gate.linear_combinations {
+            for add_term in &gate.linear_combinations {
                 let coeff = add_term.0;
                 let add_var = &variables[add_term.1.as_usize()];
                 arith_gate += (coeff.into_repr(), *add_var);
@@ -97,26 +112,85 @@ impl<'a, ConstraintF: Field + PrimeField> ConstraintSynthesizer<ConstraintF>
             cs.enforce_constraint(lc!() + Variable::One, arith_gate, lc!())?;
         }
 
+        // Lower the black box calls through the bit decomposition of their inputs
+        let input_var = |input: &FunctionInput<GenericFieldElement<ConstraintF>>| match input
+            .input_ref()
+        {
+            ConstantOrWitnessEnum::Constant(c) => (FpVar::constant(c.into_repr()), c.into_repr()),
+            ConstantOrWitnessEnum::Witness(w) => (
+                FpVar::Var(AllocatedFp::new(
+                    Some(self.values[w]),
+                    variables[w.as_usize()],
+                    cs.clone(),
+                )),
+                self.values[w],
+            ),
+        };
+        for call in &self.lowered.black_box_calls {
+            match call {
+                BlackBoxFuncCall::RANGE { input: range_input } => {
+                    let (var, value) = input_var(range_input);
+                    to_bits_le(cs.clone(), &var, value, range_input.num_bits())?;
+                }
+                BlackBoxFuncCall::AND { lhs, rhs, output }
+                | BlackBoxFuncCall::XOR { lhs, rhs, output } => {
+                    let (lhs_var, lhs_value) = input_var(lhs);
+                    let (rhs_var, rhs_value) = input_var(rhs);
+                    let lhs_bits = to_bits_le(cs.clone(), &lhs_var, lhs_value, lhs.num_bits())?;
+                    let rhs_bits = to_bits_le(cs.clone(), &rhs_var, rhs_value, rhs.num_bits())?;
+                    let output_bits = lhs_bits
+                        .iter()
+                        .zip(&rhs_bits)
+                        .map(|(l, r)| match call {
+                            BlackBoxFuncCall::AND { .. } => l & r,
+                            _ => l ^ r,
+                        })
+                        .collect::<Vec<_>>();
+                    let output_var = FpVar::Var(AllocatedFp::new(
+                        Some(self.values[output]),
+                        variables[output.as_usize()],
+                        cs.clone(),
+                    ));
+                    Boolean::le_bits_to_fp(&output_bits)?.enforce_equal(&output_var)?;
+                }
+                _ => return Err(SynthesisError::Unsatisfiable),
+            }
+        }
+
         Ok(())
     }
 }
 
-impl<'a, F: PrimeField>
-    From<(
-        &Circuit<GenericFieldElement<F>>,
-        WitnessMap<GenericFieldElement<F>>,
-    )> for AcirCircuitSonobe<'a, F>
-{
-    fn from(
-        circ_val: (
-            &Circuit<GenericFieldElement<F>>,
-            WitnessMap<GenericFieldElement<F>>,
-        ),
-    ) -> AcirCircuitSonobe<'a, F> {
-        // Currently non-arithmetic gates are not supported
-        // so we extract all of the arithmetic gates only
-        let (circuit, witness_map) = circ_val;
+/// Returns whether the black box function of `call` can be lowered to constraints.
+pub(crate) fn is_supported_black_box<F>(call: &BlackBoxFuncCall<F>) -> bool {
+    matches!(
+        call,
+        BlackBoxFuncCall::RANGE { .. }
+            | BlackBoxFuncCall::AND { .. }
+            | BlackBoxFuncCall::XOR { .. }
+    )
+}
+
+/// Allocates the `num_bits` little-endian bits of `var`, whose value is `value`, and enforces that
+/// they are its decomposition, which also enforces that `var` fits in `num_bits` bits.
+fn to_bits_le<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    var: &FpVar<F>,
+    value: F,
+    num_bits: u32,
+) -> Result<Vec<Boolean<F>>, SynthesisError> {
+    let value_bits = value.into_bigint().to_bits_le();
+    let bits = (0..num_bits as usize)
+        .map(|i| Boolean::new_witness(cs.clone(), || Ok(value_bits.get(i) == Some(&true))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp(&bits)?.enforce_equal(var)?;
+    Ok(bits)
+}
 
+impl<F: PrimeField> From<&Circuit<GenericFieldElement<F>>> for LoweredAcirCircuit<F> {
+    fn from(circuit: &Circuit<GenericFieldElement<F>>) -> LoweredAcirCircuit<F> {
+        // Currently non-arithmetic gates are not supported (besides some black box calls)
+        // so we extract all of the arithmetic gates only
         let public_inputs = circuit.public_inputs();
         let arith_gates: Vec<_> = circuit
             .opcodes
@@ -130,9 +204,43 @@ impl<'a, F: PrimeField>
             })
             .collect();
 
+        let black_box_calls: Vec<_> = circuit
+            .opcodes
+            .iter()
+            .filter_map(|opcode| match opcode {
+                Opcode::BlackBoxFuncCall(call) if is_supported_black_box(call) => {
+                    Some(call.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
         let num_variables: usize = circuit.num_vars().try_into().unwrap();
 
-        let values: BTreeMap<Witness, _> = (0..num_variables)
+        LoweredAcirCircuit {
+            gates: arith_gates,
+            black_box_calls,
+            public_inputs,
+            num_variables,
+        }
+    }
+}
+
+impl<'a, F: PrimeField>
+    From<(
+        &'a LoweredAcirCircuit<F>,
+        WitnessMap<GenericFieldElement<F>>,
+    )> for AcirCircuitSonobe<'a, F>
+{
+    fn from(
+        circ_val: (
+            &'a LoweredAcirCircuit<F>,
+            WitnessMap<GenericFieldElement<F>>,
+        ),
+    ) -> AcirCircuitSonobe<'a, F> {
+        let (lowered, witness_map) = circ_val;
+
+        let values: BTreeMap<Witness, _> = (0..lowered.num_variables)
             .map(|witness_index| {
                 // Get the value if it exists. If i does not, then we fill it with the zero value
                 let witness = Witness(witness_index as u32);
@@ -145,10 +253,88 @@ impl<'a, F: PrimeField>
             .collect();
 
         AcirCircuitSonobe {
-            gates: arith_gates,
+            lowered,
             values,
-            public_inputs,
             already_assigned_witnesses: HashMap::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use super::*;
+
+    /// Synthesizes a circuit made of the single black box `call`, over the witnesses 0 (lhs), 1
+    /// (rhs) and 2 (output) set to `values`, returning its number of constraints and whether they
+    /// are satisfied.
+    fn synthesize_black_box(
+        call: BlackBoxFuncCall<GenericFieldElement<Fr>>,
+        values: [u64; 3],
+    ) -> Result<(usize, bool), SynthesisError> {
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![Opcode::BlackBoxFuncCall(call)],
+            ..Default::default()
+        };
+        let mut witness_map = WitnessMap::new();
+        for (i, value) in values.into_iter().enumerate() {
+            witness_map.insert(
+                Witness(i as u32),
+                GenericFieldElement::from_repr(Fr::from(value)),
+            );
+        }
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let lowered = LoweredAcirCircuit::from(&circuit);
+        AcirCircuitSonobe::from((&lowered, witness_map)).generate_constraints(cs.clone())?;
+        Ok((cs.num_constraints(), cs.is_satisfied()?))
+    }
+
+    /// Snapshot of the number of constraints of each supported black box on 8 bits: each input is
+    /// decomposed into 8 booleans (8 constraints) recomposed into the input (1 constraint), and
+    /// the AND and XOR compute each bit of the output with 1 constraint, and recompose it into
+    /// the output with 1 more constraint.
+    #[test]
+    fn test_black_box_constraints() -> Result<(), SynthesisError> {
+        let input = |i| FunctionInput::witness(Witness(i), 8);
+
+        let range = BlackBoxFuncCall::RANGE { input: input(0) };
+        assert_eq!(
+            synthesize_black_box(range.clone(), [0xa5, 0, 0])?,
+            (9, true)
+        );
+        assert_eq!(synthesize_black_box(range, [0x1a5, 0, 0])?, (9, false));
+
+        let and = BlackBoxFuncCall::AND {
+            lhs: input(0),
+            rhs: input(1),
+            output: Witness(2),
+        };
+        assert_eq!(
+            synthesize_black_box(and.clone(), [0xa5, 0x3c, 0xa5 & 0x3c])?,
+            (27, true)
+        );
+        assert_eq!(
+            synthesize_black_box(and, [0xa5, 0x3c, 0xa5 ^ 0x3c])?,
+            (27, false)
+        );
+
+        let xor = BlackBoxFuncCall::XOR {
+            lhs: input(0),
+            rhs: input(1),
+            output: Witness(2),
+        };
+        assert_eq!(
+            synthesize_black_box(xor.clone(), [0xa5, 0x3c, 0xa5 ^ 0x3c])?,
+            (27, true)
+        );
+        assert_eq!(
+            synthesize_black_box(xor, [0xa5, 0x3c, 0xa5 & 0x3c])?,
+            (27, false)
+        );
+        Ok(())
+    }
+}
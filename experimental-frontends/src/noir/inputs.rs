@@ -0,0 +1,397 @@
+//! Reading of the external inputs of the steps of a Noir circuit from `Prover.toml`-style files,
+//! as read by `nargo execute`.
+//!
+//! The private parameters of `main` are given by their name, either at the top level of the file
+//! for a single step, or in one `[[steps]]` table per step:
+//!
+//! ```toml
+//! [[steps]]
+//! private_inputs = ["3", "0x07"]
+//!
+//! [[steps]]
+//! private_inputs = [2, "-1"]
+//! ```
+//!
+//! The public parameters, which form the state of the folded circuit, are ignored if present.
+
+use ark_ff::PrimeField;
+use num_bigint::{BigInt, BigUint, Sign};
+use std::path::Path;
+
+use super::NoirAbi;
+use crate::utils::VecF;
+use folding_schemes::Error;
+
+/// Reads the external inputs of the steps of a Noir circuit with the given `abi` from the TOML
+/// file at `path`. See [`parse_external_inputs_toml`].
+pub fn load_external_inputs_toml<F: PrimeField, const L: usize>(
+    path: impl AsRef<Path>,
+    abi: &NoirAbi,
+) -> Result<Vec<VecF<F, L>>, Error> {
+    parse_external_inputs_toml(&std::fs::read_to_string(path)?, abi)
+}
+
+/// Parses the external inputs of the steps of a Noir circuit with the given `abi` from a
+/// `Prover.toml`-style document, returning them flattened in the order expected by the
+/// `generate_step_constraints` of [`super::NoirFCircuit`].
+///
+/// Fields and integers are given as TOML integers or as decimal or `0x`-prefixed hexadecimal
+/// strings, optionally negative, booleans as TOML booleans, strings as TOML strings, arrays and
+/// tuples as TOML arrays and structs as TOML tables. Values that do not fit in their type and
+/// arrays of the wrong length are rejected, naming the offending value, eg. `steps[1].xs[2]`.
+pub fn parse_external_inputs_toml<F: PrimeField, const L: usize>(
+    contents: &str,
+    abi: &NoirAbi,
+) -> Result<Vec<VecF<F, L>>, Error> {
+    let document: toml::Table = contents
+        .parse()
+        .map_err(|err: toml::de::Error| Error::Other(format!("invalid TOML: {}", err)))?;
+    let parameters = abi.parameters()?;
+
+    // a parameter named `steps` takes precedence over the list of steps
+    let has_steps_parameter = parameters
+        .iter()
+        .any(|parameter| parameter["name"] == "steps");
+    let steps = match document.get("steps") {
+        Some(toml::Value::Array(steps)) if !has_steps_parameter => steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let path = format!("steps[{}]", i);
+                let step = step
+                    .as_table()
+                    .ok_or_else(|| conversion_error(&path, "table", "expected a table"))?;
+                Ok((format!("{}.", path), step))
+            })
+            .collect::<Result<Vec<_>, Error>>()?,
+        _ => vec![(String::new(), &document)],
+    };
+
+    steps
+        .into_iter()
+        .map(|(prefix, step)| {
+            let mut inputs = Vec::with_capacity(L);
+            for parameter in parameters {
+                if parameter["visibility"] == "public" {
+                    continue;
+                }
+                let name = parameter["name"].as_str().unwrap_or_default();
+                let path = format!("{}{}", prefix, name);
+                let value = step.get(name).ok_or(Error::MissingValue(path.clone()))?;
+                flatten_value(value, &parameter["type"], &path, &mut inputs)?;
+            }
+            if inputs.len() != L {
+                return Err(Error::NotExpectedLength(inputs.len(), L));
+            }
+            Ok(VecF(inputs))
+        })
+        .collect()
+}
+
+/// Appends to `inputs` the elements of `value`, of the given ABI type, flattened as in
+/// [`super::NoirIoLayout`]. `path` names the value in the errors.
+fn flatten_value<F: PrimeField>(
+    value: &toml::Value,
+    abi_type: &serde_json::Value,
+    path: &str,
+    inputs: &mut Vec<F>,
+) -> Result<(), Error> {
+    let type_name = abi_type_name(abi_type);
+    let length = || abi_type["length"].as_u64().unwrap_or_default() as usize;
+    let elements = |len: usize| match value {
+        toml::Value::Array(elements) if elements.len() == len => Ok(elements),
+        toml::Value::Array(elements) => Err(conversion_error(
+            path,
+            &type_name,
+            &format!("expected {} elements, got {}", len, elements.len()),
+        )),
+        _ => Err(conversion_error(path, &type_name, "expected an array")),
+    };
+
+    match abi_type["kind"].as_str().unwrap_or_default() {
+        "field" => {
+            let n = parse_number(value, path, &type_name)?;
+            let modulus: BigUint = F::MODULUS.into();
+            let modulus = BigInt::from(modulus);
+            if n >= modulus || n <= -modulus {
+                return Err(conversion_error(path, &type_name, "out of range"));
+            }
+            let element = F::from(n.magnitude().clone());
+            inputs.push(match n.sign() {
+                Sign::Minus => -element,
+                _ => element,
+            });
+        }
+        "integer" => {
+            let n = parse_number(value, path, &type_name)?;
+            let width = abi_type["width"].as_u64().unwrap_or_default() as usize;
+            // the bounds of signed integers are computed from `width - 1`
+            if width == 0 {
+                return Err(conversion_error(path, &type_name, "integers of width 0"));
+            }
+            let (min, max) = match abi_type["sign"].as_str() {
+                Some("signed") => (
+                    -(BigInt::from(1) << (width - 1)),
+                    BigInt::from(1) << (width - 1),
+                ),
+                _ => (BigInt::from(0), BigInt::from(1) << width),
+            };
+            if n < min || n >= max {
+                return Err(conversion_error(path, &type_name, "out of range"));
+            }
+            // signed integers are represented in two's complement
+            let n = if n.sign() == Sign::Minus {
+                n + (BigInt::from(1) << width)
+            } else {
+                n
+            };
+            inputs.push(F::from(n.magnitude().clone()));
+        }
+        "boolean" => match value {
+            toml::Value::Boolean(b) => inputs.push(F::from(*b)),
+            _ => return Err(conversion_error(path, &type_name, "expected a boolean")),
+        },
+        "string" => match value {
+            toml::Value::String(s) if s.len() == length() => {
+                inputs.extend(s.bytes().map(F::from));
+            }
+            toml::Value::String(s) => {
+                return Err(conversion_error(
+                    path,
+                    &type_name,
+                    &format!("expected {} characters, got {}", length(), s.len()),
+                ));
+            }
+            _ => return Err(conversion_error(path, &type_name, "expected a string")),
+        },
+        "array" => {
+            for (i, element) in elements(length())?.iter().enumerate() {
+                flatten_value(
+                    element,
+                    &abi_type["type"],
+                    &format!("{}[{}]", path, i),
+                    inputs,
+                )?;
+            }
+        }
+        "tuple" => {
+            let fields = abi_type["fields"].as_array().cloned().unwrap_or_default();
+            for (i, (element, field)) in elements(fields.len())?.iter().zip(&fields).enumerate() {
+                flatten_value(element, field, &format!("{}.{}", path, i), inputs)?;
+            }
+        }
+        "struct" => {
+            let table = value
+                .as_table()
+                .ok_or_else(|| conversion_error(path, &type_name, "expected a table"))?;
+            for field in abi_type["fields"].as_array().into_iter().flatten() {
+                let name = field["name"].as_str().unwrap_or_default();
+                let path = format!("{}.{}", path, name);
+                let value = table.get(name).ok_or(Error::MissingValue(path.clone()))?;
+                flatten_value(value, &field["type"], &path, inputs)?;
+            }
+        }
+        _ => {
+            return Err(Error::NotSupportedYet(format!(
+                "Noir ABI type {}",
+                abi_type
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Parses an integer given as a TOML integer or as a decimal or `0x`-prefixed hexadecimal string,
+/// optionally negative.
+fn parse_number(value: &toml::Value, path: &str, type_name: &str) -> Result<BigInt, Error> {
+    let s = match value {
+        toml::Value::Integer(n) => return Ok(BigInt::from(*n)),
+        toml::Value::String(s) => s.trim(),
+        _ => return Err(conversion_error(path, type_name, "expected a number")),
+    };
+    let (sign, digits) = match s.strip_prefix('-') {
+        Some(digits) => (Sign::Minus, digits),
+        None => (Sign::Plus, s),
+    };
+    let magnitude = match digits.strip_prefix("0x") {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(digits.as_bytes(), 10),
+    }
+    .ok_or_else(|| conversion_error(path, type_name, &format!("`{}` is not a number", s)))?;
+    Ok(BigInt::from_biguint(sign, magnitude))
+}
+
+/// Returns the name of the given ABI type in Noir syntax, for the errors.
+fn abi_type_name(abi_type: &serde_json::Value) -> String {
+    let length = &abi_type["length"];
+    match abi_type["kind"].as_str().unwrap_or_default() {
+        "field" => "Field".to_string(),
+        "boolean" => "bool".to_string(),
+        "integer" => format!(
+            "{}{}",
+            if abi_type["sign"] == "signed" {
+                "i"
+            } else {
+                "u"
+            },
+            abi_type["width"]
+        ),
+        "string" => format!("str<{}>", length),
+        "array" => format!("[{}; {}]", abi_type_name(&abi_type["type"]), length),
+        "tuple" => format!(
+            "({})",
+            abi_type["fields"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(abi_type_name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        "struct" => abi_type["path"].as_str().unwrap_or("struct").to_string(),
+        _ => abi_type.to_string(),
+    }
+}
+
+fn conversion_error(path: &str, type_name: &str, reason: &str) -> Error {
+    Error::ConversionError(path.to_string(), type_name.to_string(), reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use serde_json::json;
+
+    use super::*;
+
+    fn abi() -> NoirAbi {
+        let u8_type = json!({ "kind": "integer", "sign": "unsigned", "width": 8 });
+        NoirAbi(json!({
+            "parameters": [
+                { "name": "z", "type": { "kind": "field" }, "visibility": "public" },
+                { "name": "x", "type": { "kind": "field" }, "visibility": "private" },
+                {
+                    "name": "p",
+                    "type": {
+                        "kind": "struct",
+                        "path": "Point",
+                        "fields": [
+                            { "name": "coords", "type": { "kind": "array", "length": 2, "type": u8_type } },
+                            { "name": "sign", "type": { "kind": "integer", "sign": "signed", "width": 8 } },
+                            { "name": "flag", "type": { "kind": "boolean" } },
+                        ],
+                    },
+                    "visibility": "private",
+                },
+                { "name": "tag", "type": { "kind": "string", "length": 2 }, "visibility": "private" },
+            ],
+            "return_type": null,
+        }))
+    }
+
+    #[test]
+    fn test_inputs_are_flattened() -> Result<(), Error> {
+        let toml = r#"
+            [[steps]]
+            z = "1"
+            x = "0x10"
+            tag = "ok"
+            p = { coords = [1, "255"], sign = "-1", flag = true }
+
+            [[steps]]
+            x = "-1"
+            tag = "no"
+            [steps.p]
+            coords = ["0x00", 7]
+            sign = 127
+            flag = false
+        "#;
+        let inputs = parse_external_inputs_toml::<Fr, 7>(toml, &abi())?;
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(
+            inputs[0].0,
+            [16, 1, 255, 255, 1, b'o' as u64, b'k' as u64].map(Fr::from)
+        );
+        assert_eq!(inputs[1].0[0], -Fr::from(1));
+        assert_eq!(
+            inputs[1].0[1..],
+            [0, 7, 127, 0, b'n' as u64, b'o' as u64].map(Fr::from)
+        );
+
+        // without `[[steps]]`, the document holds the inputs of a single step
+        let single = r#"
+            x = 3
+            tag = "ok"
+            p = { coords = [1, 2], sign = 0, flag = false }
+        "#;
+        assert_eq!(
+            parse_external_inputs_toml::<Fr, 7>(single, &abi())?.len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_inputs_are_rejected() {
+        let parse = |p: &str, x: &str| {
+            let toml = format!("x = {}\ntag = \"ok\"\np = {}", x, p);
+            parse_external_inputs_toml::<Fr, 7>(&toml, &abi())
+        };
+        let valid = "{ coords = [1, 2], sign = 0, flag = false }";
+        assert!(parse(valid, "1").is_ok());
+
+        let is_conversion_error = |result: Result<_, Error>, expected_path: &str| matches!(result, Err(Error::ConversionError(path, ..)) if path == expected_path);
+        // out of range integers
+        assert!(is_conversion_error(
+            parse("{ coords = [1, 256], sign = 0, flag = false }", "1"),
+            "p.coords[1]"
+        ));
+        assert!(is_conversion_error(
+            parse("{ coords = [-1, 2], sign = 0, flag = false }", "1"),
+            "p.coords[0]"
+        ));
+        assert!(is_conversion_error(
+            parse("{ coords = [1, 2], sign = -129, flag = false }", "1"),
+            "p.sign"
+        ));
+        assert!(is_conversion_error(
+            parse(valid, &format!("\"{}\"", Fr::MODULUS)),
+            "x"
+        ));
+        // wrong arity
+        assert!(is_conversion_error(
+            parse("{ coords = [1, 2, 3], sign = 0, flag = false }", "1"),
+            "p.coords"
+        ));
+        // wrong types
+        assert!(is_conversion_error(
+            parse("{ coords = [1, 2], sign = 0, flag = 1 }", "1"),
+            "p.flag"
+        ));
+        assert!(is_conversion_error(parse(valid, "\"0xzz\""), "x"));
+        // missing values
+        assert!(matches!(
+            parse("{ coords = [1, 2], sign = 0 }", "1"),
+            Err(Error::MissingValue(path)) if path == "p.flag"
+        ));
+        // wrong number of external inputs
+        assert!(matches!(
+            parse_external_inputs_toml::<Fr, 6>(
+                "x = 1\ntag = \"ok\"\np = { coords = [1, 2], sign = 0, flag = false }",
+                &abi()
+            ),
+            Err(Error::NotExpectedLength(7, 6))
+        ));
+        // integer types of width 0, or without width
+        for abi_type in [
+            json!({ "kind": "integer", "sign": "signed", "width": 0 }),
+            json!({ "kind": "integer", "sign": "unsigned", "width": 0 }),
+            json!({ "kind": "integer", "sign": "signed" }),
+        ] {
+            assert!(matches!(
+                flatten_value::<Fr>(&toml::Value::Integer(0), &abi_type, "w", &mut vec![]),
+                Err(Error::ConversionError(path, ..)) if path == "w"
+            ));
+        }
+    }
+}
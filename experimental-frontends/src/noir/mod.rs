@@ -1,27 +1,125 @@
 use acvm::{
     acir::{
         acir_field::GenericFieldElement,
-        circuit::{Circuit, Program},
+        brillig::{ForeignCallResult, Opcode as BrilligOpcode},
+        circuit::{brillig::BrilligBytecode, Circuit, Opcode, Program},
         native_types::{Witness as AcvmWitness, WitnessMap},
     },
     blackbox_solver::StubbedBlackBoxSolver,
-    pwg::ACVM,
+    pwg::{ACVMStatus, OpcodeResolutionError, ResolvedAssertionPayload, ACVM},
 };
 use ark_ff::PrimeField;
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use serde::{self, Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
 
-use self::bridge::AcirCircuitSonobe;
-use crate::utils::{VecF, VecFpVar};
+use self::bridge::{is_supported_black_box, AcirCircuitSonobe, LoweredAcirCircuit};
+use crate::utils::{VecF, VecFpVar, VecFpVarDyn};
+use crate::FrontendError;
 use folding_schemes::{frontend::FCircuit, utils::PathOrBin, Error};
 
 mod bridge;
+mod inputs;
+mod v0_35;
+
+pub use self::inputs::{load_external_inputs_toml, parse_external_inputs_toml};
 
 #[derive(Clone, Debug)]
 pub struct NoirFCircuit<F: PrimeField, const SL: usize, const EIL: usize> {
-    pub circuit: Circuit<GenericFieldElement<F>>,
+    /// ACIR of the circuit. It is shared by the clones of the NoirFCircuit, so it has to be cloned
+    /// (eg. with `(*f_circuit.circuit).clone()`) to be moved out or modified.
+    pub circuit: Arc<Circuit<GenericFieldElement<F>>>,
+    /// Brillig bytecode of the unconstrained functions called by the circuit, which are executed
+    /// to compute the witness but are not lowered to constraints. It is shared as `circuit`.
+    pub unconstrained_functions: Arc<Vec<BrilligBytecode<GenericFieldElement<F>>>>,
+    /// ABI of the circuit, used to read the external inputs of the steps with
+    /// [`load_external_inputs_toml`].
+    pub abi: Arc<NoirAbi>,
+    // the step independent data is computed once when loading the circuit, and shared by its
+    // clones, so that only the witness is computed and the constraints are instantiated at each
+    // step
+    lowered: Arc<LoweredAcirCircuit<F>>,
+    layout: Arc<NoirIoLayout>,
+    diagnostics: Arc<NoirDiagnostics>,
+}
+
+/// ABI of a compiled Noir program, as found in its JSON artifact, which describes the names, types
+/// and visibility of the parameters of its `main` function and the type of its return value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoirAbi(serde_json::Value);
+
+impl NoirAbi {
+    /// Returns the parameters of `main`, in their declaration order.
+    fn parameters(&self) -> Result<&Vec<serde_json::Value>, Error> {
+        self.0["parameters"].as_array().ok_or_else(|| {
+            Error::JSONSerdeError("missing field `parameters` in the Noir ABI".to_string())
+        })
+    }
+}
+
+/// Witnesses of the state, external inputs and next state of a Noir circuit.
+///
+/// They follow the flattening of the ABI of the circuit: the parameters of `main` are laid out in
+/// their declaration order, the public ones forming the state `z_i` and the private ones the
+/// external inputs, and its return value forms the next state `z_{i+1}`. Each value is flattened
+/// recursively: fields, integers and booleans are one element, arrays are laid out element by
+/// element (so nested arrays in row-major order), structs field by field in their declaration
+/// order, tuples element by element, and strings character by character. For example, the
+/// return value `State { values: [[1, 2], [3, 4]], digest: 5 }` is flattened into
+/// `[1, 2, 3, 4, 5]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct NoirIoLayout {
+    state: Vec<AcvmWitness>,
+    external_inputs: Vec<AcvmWitness>,
+    next_state: Vec<AcvmWitness>,
+}
+
+/// Data used to report the failures of the steps of a Noir circuit, shared by the clones of the
+/// FCircuit, since the folding schemes synthesize the steps with their own clone.
+#[derive(Debug, Default)]
+struct NoirDiagnostics {
+    /// messages of the assertions of the circuit, indexed by their error selector
+    error_messages: HashMap<u64, String>,
+    step_error: Mutex<Option<Error>>,
+}
+
+impl NoirDiagnostics {
+    fn record(&self, err: Error) {
+        if let Ok(mut step_error) = self.step_error.lock() {
+            *step_error = Some(err);
+        }
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.step_error.lock().ok()?.take()
+    }
+
+    /// Returns the message of the assertion that made the solver fail, or the solver error if the
+    /// assertion has none.
+    fn failure_message<F: PrimeField>(
+        &self,
+        err: &OpcodeResolutionError<GenericFieldElement<F>>,
+    ) -> String {
+        let payload = match err {
+            OpcodeResolutionError::UnsatisfiedConstrain { payload, .. }
+            | OpcodeResolutionError::BrilligFunctionFailed { payload, .. } => payload.as_ref(),
+            _ => None,
+        };
+        match payload {
+            Some(ResolvedAssertionPayload::String(message)) => message.clone(),
+            Some(ResolvedAssertionPayload::Raw(raw)) => {
+                let selector = raw.selector.as_u64();
+                self.error_messages
+                    .get(&selector)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{} (error selector {})", err, selector))
+            }
+            None => err.to_string(),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -33,35 +131,57 @@ pub struct ProgramArtifactGeneric<F: PrimeField> {
     pub bytecode: Program<GenericFieldElement<F>>,
 }
 
+impl<F: PrimeField, const SL: usize, const EIL: usize> NoirFCircuit<F, SL, EIL> {
+    /// Loads the circuit from the bytes of its JSON artifact, as output by `nargo compile`, eg.
+    /// embedded with `include_bytes!` or received over the network.
+    pub fn from_bytes(artifact: &[u8]) -> Result<Self, Error> {
+        Self::new(PathOrBin::Bin(artifact.to_vec()))
+    }
+
+    /// Loads the circuit from a reader of its JSON artifact.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut artifact = Vec::new();
+        reader.read_to_end(&mut artifact)?;
+        Self::new(PathOrBin::Bin(artifact))
+    }
+}
+
 impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for NoirFCircuit<F, SL, EIL> {
     type Params = PathOrBin;
     type ExternalInputs = VecF<F, EIL>;
     type ExternalInputsVar = VecFpVar<F, EIL>;
+    type WitnessHint = ();
 
     fn new(source: Self::Params) -> Result<Self, Error> {
-        let input_string = match source {
-            PathOrBin::Path(path) => {
-                let file_path = path.with_extension("json");
-                std::fs::read(&file_path).map_err(|_| Error::Other(format!("{} is not a valid path\nRun either `nargo compile` to generate missing build artifacts or `nargo prove` to construct a proof", file_path.display())))?
-            }
-            PathOrBin::Bin(bin) => bin,
-        };
-        let program: ProgramArtifactGeneric<F> = serde_json::from_slice(&input_string)
-            .map_err(|err| Error::JSONSerdeError(err.to_string()))?;
-        let circuit: Circuit<GenericFieldElement<F>> = program.bytecode.functions[0].clone();
-        let ivc_input_length = circuit.public_parameters.0.len();
-        let ivc_return_length = circuit.return_values.0.len();
-
-        if ivc_input_length != ivc_return_length {
-            return Err(Error::NotSameLength(
-                "IVC input: ".to_string(),
-                ivc_input_length,
-                "IVC output: ".to_string(),
-                ivc_return_length,
-            ));
+        let NoirProgram {
+            circuit,
+            unconstrained_functions,
+            abi,
+            lowered,
+            layout,
+            error_messages,
+        } = load_noir_program(source)?;
+
+        // the state and external inputs are mapped to the public and private parameters of the
+        // Noir circuit, so their lengths must match to avoid indexing out of bounds at the step
+        if layout.state.len() != SL {
+            return Err(Error::NotExpectedLength(layout.state.len(), SL));
+        }
+        if layout.external_inputs.len() != EIL {
+            return Err(Error::NotExpectedLength(layout.external_inputs.len(), EIL));
         }
 
-        Ok(NoirFCircuit { circuit })
+        Ok(NoirFCircuit {
+            circuit: Arc::new(circuit),
+            unconstrained_functions: Arc::new(unconstrained_functions),
+            abi: Arc::new(abi),
+            lowered: Arc::new(lowered),
+            layout: Arc::new(layout),
+            diagnostics: Arc::new(NoirDiagnostics {
+                error_messages,
+                ..Default::default()
+            }),
+        })
     }
 
     fn state_len(&self) -> usize {
@@ -71,83 +191,531 @@ impl<F: PrimeField, const SL: usize, const EIL: usize> FCircuit<F> for NoirFCirc
     fn generate_step_constraints(
         &self,
         cs: ConstraintSystemRef<F>,
-        _i: usize,
+        i: usize,
         z_i: Vec<FpVar<F>>,
         external_inputs: Self::ExternalInputsVar, // inputs that are not part of the state
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
-        let mut acvm = ACVM::new(
-            &StubbedBlackBoxSolver,
-            &self.circuit.opcodes,
-            WitnessMap::new(),
-            &[],
-            &[],
-        );
+        generate_noir_step_constraints(
+            &self.circuit,
+            &self.unconstrained_functions,
+            &self.lowered,
+            &self.layout,
+            &self.diagnostics,
+            cs,
+            i,
+            z_i,
+            &external_inputs.0,
+        )
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.diagnostics.take_step_error()
+    }
+}
 
-        let mut already_assigned_witness_values = HashMap::new();
+/// [`NoirFCircuit`] whose state and external inputs lengths are read from the public and private
+/// parameters of the compiled circuit, instead of being fixed by const generics. The external
+/// inputs of each step are given as a vector of length
+/// [`NoirFCircuitDyn::external_inputs_len`].
+#[derive(Clone, Debug)]
+pub struct NoirFCircuitDyn<F: PrimeField> {
+    pub circuit: Arc<Circuit<GenericFieldElement<F>>>,
+    pub unconstrained_functions: Arc<Vec<BrilligBytecode<GenericFieldElement<F>>>>,
+    pub abi: Arc<NoirAbi>,
+    lowered: Arc<LoweredAcirCircuit<F>>,
+    layout: Arc<NoirIoLayout>,
+    state_len: usize,
+    external_inputs_len: usize,
+    diagnostics: Arc<NoirDiagnostics>,
+}
 
-        self.circuit.public_parameters.0.iter().for_each(|witness| {
-            let idx: usize = witness.as_usize();
-            let witness = AcvmWitness(witness.witness_index());
-            already_assigned_witness_values.insert(witness, &z_i[idx]);
+impl<F: PrimeField> NoirFCircuitDyn<F> {
+    /// Loads the circuit from the bytes of its JSON artifact, as output by `nargo compile`.
+    pub fn from_bytes(artifact: &[u8]) -> Result<Self, Error> {
+        Self::new(PathOrBin::Bin(artifact.to_vec()))
+    }
 
-            let val = z_i[idx].value().unwrap_or_default();
+    /// Loads the circuit from a reader of its JSON artifact.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut artifact = Vec::new();
+        reader.read_to_end(&mut artifact)?;
+        Self::new(PathOrBin::Bin(artifact))
+    }
 
-            let f = GenericFieldElement::<F>::from_repr(val);
-            acvm.overwrite_witness(witness, f);
-        });
+    /// Returns the number of external inputs of each step.
+    pub fn external_inputs_len(&self) -> usize {
+        self.external_inputs_len
+    }
+}
+
+impl<F: PrimeField> FCircuit<F> for NoirFCircuitDyn<F> {
+    type Params = PathOrBin;
+    type ExternalInputs = Vec<F>;
+    type ExternalInputsVar = VecFpVarDyn<F>;
+    type WitnessHint = ();
 
-        // write witness values for external_inputs
-        self.circuit.private_parameters.iter().for_each(|witness| {
-            let idx = witness.as_usize() - z_i.len();
-            let witness = AcvmWitness(witness.witness_index());
-            already_assigned_witness_values.insert(witness, &external_inputs.0[idx]);
+    fn new(source: Self::Params) -> Result<Self, Error> {
+        let NoirProgram {
+            circuit,
+            unconstrained_functions,
+            abi,
+            lowered,
+            layout,
+            error_messages,
+        } = load_noir_program(source)?;
+        Ok(NoirFCircuitDyn {
+            state_len: layout.next_state.len(),
+            external_inputs_len: layout.external_inputs.len(),
+            circuit: Arc::new(circuit),
+            unconstrained_functions: Arc::new(unconstrained_functions),
+            abi: Arc::new(abi),
+            lowered: Arc::new(lowered),
+            layout: Arc::new(layout),
+            diagnostics: Arc::new(NoirDiagnostics {
+                error_messages,
+                ..Default::default()
+            }),
+        })
+    }
 
-            let val = external_inputs.0[idx].value().unwrap_or_default();
+    fn state_len(&self) -> usize {
+        self.state_len
+    }
 
-            let f = GenericFieldElement::<F>::from_repr(val);
-            acvm.overwrite_witness(witness, f);
-        });
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar, // inputs that are not part of the state
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let external_inputs = external_inputs.allocate(self.external_inputs_len)?;
+        generate_noir_step_constraints(
+            &self.circuit,
+            &self.unconstrained_functions,
+            &self.lowered,
+            &self.layout,
+            &self.diagnostics,
+            cs,
+            i,
+            z_i,
+            &external_inputs,
+        )
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.diagnostics.take_step_error()
+    }
+}
+
+/// Name of the oracle called by the `print` and `println` functions of Noir.
+const NOIR_PRINT_ORACLE: &str = "print";
+
+/// Moduli of the fields over which `nargo` compiles the Noir programs: the BN254 scalar field
+/// by default, and the BLS12-381 one when built with its `bls12_381` feature. The artifacts do
+/// not record which of them they were compiled for.
+const NOIR_FIELD_MODULI: [&str; 2] = [
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    "52435875175126190479447740508185965837690552500527637822603658699938581184513",
+];
+
+/// Versions of `nargo` whose artifacts can be loaded, which determine how the ACIR bytecode of
+/// the artifacts is serialized. The artifacts of other versions may be deserialized without errors
+/// into a different circuit (eg. with shifted witness indices), so they are rejected upfront.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NoirArtifactVersion {
+    /// `nargo` 0.35, whose circuits still carry the `recursive` flag removed from ACIR in 0.36
+    V0_35,
+    /// `nargo` 0.36, whose bytecode is the base64 encoding of the gzipped ACIR `Program`
+    V0_36,
+}
+
+impl NoirArtifactVersion {
+    const SUPPORTED: [Self; 2] = [Self::V0_35, Self::V0_36];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::V0_35 => "0.35",
+            Self::V0_36 => "0.36",
+        }
+    }
+
+    /// Returns the version of the artifacts written by the `nargo` of version `noir_version` (eg.
+    /// `0.36.0+801c71a8`), from its major and minor versions.
+    fn detect(noir_version: &str) -> Option<Self> {
+        let version = noir_version.split('+').next()?;
+        Self::SUPPORTED.into_iter().find(|supported| {
+            version
+                .strip_prefix(supported.name())
+                .is_some_and(|patch| patch.starts_with('.'))
+        })
+    }
 
-        // computes the witness
-        let _ = acvm.solve();
-        let witness_map = acvm.finalize();
+    fn deserialize_program<F: PrimeField>(
+        self,
+        artifact: &serde_json::Value,
+    ) -> Result<Program<GenericFieldElement<F>>, serde_json::Error> {
+        match self {
+            Self::V0_35 => v0_35::deserialize_program(artifact),
+            Self::V0_36 => {
+                ProgramArtifactGeneric::<F>::deserialize(artifact).map(|artifact| artifact.bytecode)
+            }
+        }
+    }
+}
+
+/// Compiled Noir program, whose first function is the circuit being folded.
+struct NoirProgram<F: PrimeField> {
+    circuit: Circuit<GenericFieldElement<F>>,
+    unconstrained_functions: Vec<BrilligBytecode<GenericFieldElement<F>>>,
+    abi: NoirAbi,
+    lowered: LoweredAcirCircuit<F>,
+    layout: NoirIoLayout,
+    /// messages of the assertions of the program, indexed by their error selector
+    error_messages: HashMap<u64, String>,
+}
+
+/// Loads the compiled Noir program at `source` (or given as bytes), checking that its first
+/// function can be folded.
+fn load_noir_program<F: PrimeField>(source: PathOrBin) -> Result<NoirProgram<F>, Error> {
+    let input_string = match source {
+        PathOrBin::Path(path) => {
+            let file_path = path.with_extension("json");
+            std::fs::read(&file_path).map_err(|_| Error::Other(format!("{} is not a valid path\nRun either `nargo compile` to generate missing build artifacts or `nargo prove` to construct a proof", file_path.display())))?
+        }
+        PathOrBin::Bin(bin) => bin,
+    };
+    parse_noir_program(&input_string)
+}
+
+/// Parses the JSON artifact of a compiled Noir program, as output by `nargo compile`, checking
+/// that its first function can be folded. The messages of the assertions are read from the ABI
+/// of the program.
+fn parse_noir_program<F: PrimeField>(artifact: &[u8]) -> Result<NoirProgram<F>, Error> {
+    let modulus = F::MODULUS.to_string();
+    if !NOIR_FIELD_MODULI.contains(&modulus.as_str()) {
+        return Err(FrontendError::FieldMismatch {
+            expected: modulus,
+            found: NOIR_FIELD_MODULI.join(" or "),
+        }
+        .into());
+    }
+
+    // parse the JSON first, so that a malformed artifact is told apart from a well formed one
+    // whose bytecode was serialized by a version of ACIR that we can not read
+    let artifact: serde_json::Value =
+        serde_json::from_slice(artifact).map_err(|err| Error::JSONSerdeError(err.to_string()))?;
+    if artifact.get("bytecode").is_none() {
+        return Err(Error::JSONSerdeError(
+            "missing field `bytecode` in the Noir artifact".to_string(),
+        ));
+    }
+    let noir_version = artifact
+        .get("noir_version")
+        .and_then(|version| version.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let program = NoirArtifactVersion::detect(&noir_version)
+        .ok_or_else(|| FrontendError::UnsupportedNoirVersion {
+            found: noir_version.clone(),
+            supported: NoirArtifactVersion::SUPPORTED
+                .iter()
+                .map(|version| version.name().to_string())
+                .collect(),
+        })?
+        .deserialize_program::<F>(&artifact)
+        .map_err(|err| FrontendError::UnsupportedAcirVersion(noir_version, err.to_string()))?;
+    // `"error_types": { "<selector>": { "error_kind": "string", "string": "<message>" }, ... }`
+    let error_messages = artifact["abi"]["error_types"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(selector, error_type)| {
+            Some((
+                selector.parse::<u64>().ok()?,
+                error_type.get("string")?.as_str()?.to_string(),
+            ))
+        })
+        .collect();
+
+    let circuit: Circuit<GenericFieldElement<F>> =
+        program.functions.first().cloned().ok_or(Error::Empty)?;
+    let abi = NoirAbi(artifact.get("abi").cloned().unwrap_or_default());
+    let layout = match abi.0 {
+        serde_json::Value::Null => NoirIoLayout::from_circuit(&circuit),
+        _ => NoirIoLayout::from_abi(&abi, &circuit)?,
+    };
+    let ivc_input_length = layout.state.len();
+    let ivc_return_length = layout.next_state.len();
+
+    if ivc_input_length != ivc_return_length {
+        return Err(Error::NotSameLength(
+            "IVC input: ".to_string(),
+            ivc_input_length,
+            "IVC output: ".to_string(),
+            ivc_return_length,
+        ));
+    }
+
+    // only the AssertZero opcodes and the RANGE, AND and XOR black box calls are lowered to R1CS
+    // constraints (and from there to CCS), while Brillig calls are unconstrained witness
+    // generation hints. Any other opcode would be silently dropped by the bridge, making the
+    // folded circuit unsound, so reject it.
+    for (index, opcode) in circuit.opcodes.iter().enumerate() {
+        match opcode {
+            Opcode::AssertZero(_) | Opcode::BrilligCall { .. } => {}
+            Opcode::BlackBoxFuncCall(call) if is_supported_black_box(call) => {}
+            Opcode::BlackBoxFuncCall(call) => {
+                return Err(FrontendError::UnsupportedBlackBox(call.name().to_string()).into());
+            }
+            _ => {
+                return Err(Error::NotSupportedYet(format!(
+                    "lowering of the ACIR opcode {} ({:?})",
+                    index, opcode
+                )));
+            }
+        }
+    }
+
+    // the unconstrained functions are executed by the Brillig VM of the ACVM, which can not
+    // answer the calls to oracles, other than the prints, which do not return anything
+    let unconstrained_functions = program.unconstrained_functions;
+    if let Some(oracle) = unconstrained_functions
+        .iter()
+        .flat_map(|function| function.bytecode.iter())
+        .find_map(|opcode| match opcode {
+            BrilligOpcode::ForeignCall { function, .. } if function != NOIR_PRINT_ORACLE => {
+                Some(function.clone())
+            }
+            _ => None,
+        })
+    {
+        return Err(FrontendError::NoirOracleNotSupported(oracle).into());
+    }
+
+    Ok(NoirProgram {
+        lowered: LoweredAcirCircuit::from(&circuit),
+        abi,
+        layout,
+        circuit,
+        unconstrained_functions,
+        error_messages,
+    })
+}
+
+impl NoirIoLayout {
+    /// Lays out the witnesses of `circuit` following the flattening of its `abi`.
+    fn from_abi<F: PrimeField>(
+        abi: &NoirAbi,
+        circuit: &Circuit<GenericFieldElement<F>>,
+    ) -> Result<Self, Error> {
+        let parameters = abi.parameters()?;
+
+        // the parameters are assigned consecutive witnesses, starting from 0
+        let mut layout = Self::default();
+        let mut next_witness = 0;
+        for parameter in parameters {
+            let len = abi_type_len(&parameter["type"])? as u32;
+            let witnesses = (next_witness..next_witness + len).map(AcvmWitness);
+            match parameter["visibility"].as_str() {
+                Some("public") => layout.state.extend(witnesses),
+                _ => layout.external_inputs.extend(witnesses),
+            }
+            next_witness += len;
+        }
+        if !layout.state.iter().eq(circuit.public_parameters.0.iter())
+            || !layout
+                .external_inputs
+                .iter()
+                .eq(circuit.private_parameters.iter())
+        {
+            return Err(Error::Other(
+                "the parameters of the Noir ABI do not match the ones of its circuit".to_string(),
+            ));
+        }
+
+        // the returned values are assigned witnesses in the order of their flattening
+        let return_len = match &abi.0["return_type"] {
+            serde_json::Value::Null => 0,
+            return_type => abi_type_len(&return_type["abi_type"])?,
+        };
+        layout.next_state = circuit.return_values.0.iter().copied().collect();
+        if layout.next_state.len() != return_len {
+            return Err(Error::NotExpectedLength(
+                layout.next_state.len(),
+                return_len,
+            ));
+        }
+        Ok(layout)
+    }
 
-        // get the z_{i+1} output state
-        let assigned_z_i1 = self
-            .circuit
-            .return_values
-            .0
+    /// Lays out the witnesses of `circuit` in increasing order, for the artifacts without ABI.
+    fn from_circuit<F: PrimeField>(circuit: &Circuit<GenericFieldElement<F>>) -> Self {
+        Self {
+            state: circuit.public_parameters.0.iter().copied().collect(),
+            external_inputs: circuit.private_parameters.iter().copied().collect(),
+            next_state: circuit.return_values.0.iter().copied().collect(),
+        }
+    }
+}
+
+/// Returns the number of elements of a value of the given ABI type once flattened, as described
+/// in [`NoirIoLayout`].
+fn abi_type_len(abi_type: &serde_json::Value) -> Result<usize, Error> {
+    let unsupported = || Error::NotSupportedYet(format!("Noir ABI type {}", abi_type));
+    let length = || {
+        abi_type["length"]
+            .as_u64()
+            .map(|length| length as usize)
+            .ok_or_else(unsupported)
+    };
+    match abi_type["kind"].as_str().ok_or_else(unsupported)? {
+        "field" | "integer" | "boolean" => Ok(1),
+        "string" => length(),
+        "array" => Ok(length()? * abi_type_len(&abi_type["type"])?),
+        "struct" => abi_type["fields"]
+            .as_array()
+            .ok_or_else(unsupported)?
+            .iter()
+            .map(|field| abi_type_len(&field["type"]))
+            .sum(),
+        "tuple" => abi_type["fields"]
+            .as_array()
+            .ok_or_else(unsupported)?
             .iter()
-            .map(|witness| {
-                let noir_field_element = witness_map
+            .map(abi_type_len)
+            .sum(),
+        _ => Err(unsupported()),
+    }
+}
+
+/// Generates the constraints of the `step`-th step of the Noir `circuit`, whose public parameters
+/// are the state `z_i` and whose private parameters are the `external_inputs`, laid out as in
+/// `layout`. If an assertion of the circuit fails, it is recorded in `diagnostics` as
+/// [`FrontendError::NoirAssertionFailed`].
+#[allow(clippy::too_many_arguments)]
+fn generate_noir_step_constraints<F: PrimeField>(
+    circuit: &Circuit<GenericFieldElement<F>>,
+    unconstrained_functions: &[BrilligBytecode<GenericFieldElement<F>>],
+    lowered: &LoweredAcirCircuit<F>,
+    layout: &NoirIoLayout,
+    diagnostics: &NoirDiagnostics,
+    cs: ConstraintSystemRef<F>,
+    step: usize,
+    z_i: Vec<FpVar<F>>,
+    external_inputs: &[FpVar<F>],
+) -> Result<Vec<FpVar<F>>, SynthesisError> {
+    let mut acvm = ACVM::new(
+        &StubbedBlackBoxSolver,
+        &circuit.opcodes,
+        WitnessMap::new(),
+        unconstrained_functions,
+        &circuit.assert_messages,
+    );
+
+    let mut already_assigned_witness_values = HashMap::new();
+
+    // write witness values for z_i and external_inputs
+    let inputs = layout
+        .state
+        .iter()
+        .zip(&z_i)
+        .chain(layout.external_inputs.iter().zip(external_inputs));
+    for (witness, var) in inputs {
+        already_assigned_witness_values.insert(*witness, var);
+
+        let val = var.value().unwrap_or_default();
+
+        let f = GenericFieldElement::<F>::from_repr(val);
+        acvm.overwrite_witness(*witness, f);
+    }
+
+    // computes the witness, executing the Brillig calls. The prints are skipped, and the calls to
+    // other oracles are rejected when loading the program.
+    let status = loop {
+        match acvm.solve() {
+            ACVMStatus::RequiresForeignCall(call) if call.function == NOIR_PRINT_ORACLE => {
+                acvm.resolve_pending_foreign_call(ForeignCallResult::default())
+            }
+            status => break status,
+        }
+    };
+    // the witness can only be read once the solver succeeded, as finalizing the ACVM otherwise
+    // panics
+    let witness_map = match status {
+        ACVMStatus::Solved => acvm.finalize(),
+        // in setup mode the inputs are dummy values, which may not satisfy the assertions of the
+        // circuit, and only the shape of the constraints is needed, so the failures of the solver
+        // are only reported when proving
+        _ if cs.is_in_setup_mode() => WitnessMap::new(),
+        ACVMStatus::Failure(err) => {
+            diagnostics.record(
+                FrontendError::NoirAssertionFailed {
+                    step,
+                    opcode_index: acvm.instruction_pointer(),
+                    message: diagnostics.failure_message(&err),
+                }
+                .into(),
+            );
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        ACVMStatus::RequiresForeignCall(call) => {
+            diagnostics.record(FrontendError::NoirOracleNotSupported(call.function).into());
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        _ => return Err(SynthesisError::Unsatisfiable),
+    };
+
+    // get the z_{i+1} output state
+    let assigned_z_i1 = layout
+        .next_state
+        .iter()
+        .map(|witness| {
+            FpVar::<F>::new_witness(cs.clone(), || {
+                witness_map
                     .get(witness)
-                    .ok_or(SynthesisError::AssignmentMissing)?;
-                FpVar::<F>::new_witness(cs.clone(), || Ok(noir_field_element.into_repr()))
+                    .map(|noir_field_element| noir_field_element.into_repr())
+                    .ok_or(SynthesisError::AssignmentMissing)
             })
-            .collect::<Result<Vec<FpVar<F>>, SynthesisError>>()?;
+        })
+        .collect::<Result<Vec<FpVar<F>>, SynthesisError>>()?;
 
-        // initialize circuit and set already assigned values
-        let mut acir_circuit = AcirCircuitSonobe::from((&self.circuit, witness_map));
-        acir_circuit.already_assigned_witnesses = already_assigned_witness_values;
+    // initialize circuit and set already assigned values
+    let mut acir_circuit = AcirCircuitSonobe::from((lowered, witness_map));
+    acir_circuit.already_assigned_witnesses = already_assigned_witness_values;
 
-        acir_circuit.generate_constraints(cs.clone())?;
+    acir_circuit.generate_constraints(cs.clone())?;
 
-        Ok(assigned_z_i1)
-    }
+    Ok(assigned_z_i1)
 }
 
 #[cfg(test)]
 mod tests {
-    use ark_bn254::Fr;
+    use ark_bn254::{Fq, Fr, G1Projective as Projective};
     use ark_ff::PrimeField;
+    use ark_grumpkin::Projective as Projective2;
     use ark_r1cs_std::R1CSVar;
     use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
-    use ark_relations::r1cs::ConstraintSystem;
-    use folding_schemes::{frontend::FCircuit, Error};
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisMode};
+    use folding_schemes::{
+        commitment::pedersen::Pedersen,
+        folding::{
+            hypernova::HyperNova,
+            nova::{Nova, PreprocessorParam},
+        },
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        Error, FoldingScheme,
+    };
     use std::env;
 
-    use crate::noir::NoirFCircuit;
-    use crate::utils::VecFpVar;
+    use crate::noir::{
+        abi_type_len, load_external_inputs_toml, NoirArtifactVersion, NoirFCircuit,
+        NoirFCircuitDyn, NOIR_FIELD_MODULI,
+    };
+    use crate::utils::{VecF, VecFpVar, VecFpVarDyn};
+    use crate::FrontendError;
+    use acvm::acir::circuit::Opcode;
 
     /// Native implementation of `src/noir/test_folder/test_circuit`
     fn external_inputs_step_native<F: PrimeField>(z_i: Vec<F>, external_inputs: Vec<F>) -> Vec<F> {
@@ -212,4 +780,576 @@ mod tests {
         assert_eq!(output[1].value()?, Fr::from(25));
         Ok(())
     }
+
+    #[test]
+    fn test_wrong_state_len() -> Result<(), Error> {
+        let cur_path = env::current_dir()?;
+        let path = cur_path.join("src/noir/test_folder/test_circuit/target/test_circuit.json");
+        // the circuit has 2 state elements and 2 external inputs
+        assert!(matches!(
+            NoirFCircuit::<Fr, 3, 2>::new(path.clone().into()),
+            Err(Error::NotExpectedLength(2, 3))
+        ));
+        assert!(matches!(
+            NoirFCircuit::<Fr, 2, 1>::new(path.into()),
+            Err(Error::NotExpectedLength(2, 1))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_from_bytes() -> Result<(), Error> {
+        let path =
+            env::current_dir()?.join("src/noir/test_folder/test_circuit/target/test_circuit.json");
+        let artifact = std::fs::read(&path)?;
+
+        let from_path = NoirFCircuit::<Fr, 2, 2>::new(path.into())?;
+        let from_bytes = NoirFCircuit::<Fr, 2, 2>::from_bytes(&artifact)?;
+        let from_reader = NoirFCircuit::<Fr, 2, 2>::from_reader(artifact.as_slice())?;
+        assert_eq!(from_bytes.circuit, from_path.circuit);
+        assert_eq!(from_reader.circuit, from_path.circuit);
+        assert_eq!(
+            NoirFCircuitDyn::<Fr>::from_bytes(&artifact)?.circuit,
+            from_path.circuit
+        );
+
+        assert!(matches!(
+            NoirFCircuit::<Fr, 2, 2>::from_bytes(&artifact[1..]),
+            Err(Error::JSONSerdeError(_))
+        ));
+        let mut artifact_json: serde_json::Value = serde_json::from_slice(&artifact).unwrap();
+        artifact_json["bytecode"] = "AAAA".into();
+        assert!(matches!(
+            NoirFCircuit::<Fr, 2, 2>::from_bytes(&serde_json::to_vec(&artifact_json).unwrap())
+                .err()
+                .map(FrontendError::try_from),
+            Some(Ok(FrontendError::UnsupportedAcirVersion(..)))
+        ));
+        // BN254's base field is not one of the fields of Noir, while BLS12-381's scalar field is
+        assert!(matches!(
+            NoirFCircuit::<Fq, 2, 2>::from_bytes(&artifact)
+                .err()
+                .map(FrontendError::try_from),
+            Some(Ok(FrontendError::FieldMismatch { .. }))
+        ));
+        assert!(NOIR_FIELD_MODULI.contains(&ark_bls12_381::Fr::MODULUS.to_string().as_str()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_noir_version_is_checked() -> Result<(), Error> {
+        assert_eq!(
+            NoirArtifactVersion::detect("0.36.0+801c71a8"),
+            Some(NoirArtifactVersion::V0_36)
+        );
+        assert_eq!(
+            NoirArtifactVersion::detect("0.36.1"),
+            Some(NoirArtifactVersion::V0_36)
+        );
+        assert_eq!(
+            NoirArtifactVersion::detect("0.35.0+b1a3d8f4"),
+            Some(NoirArtifactVersion::V0_35)
+        );
+        assert_eq!(NoirArtifactVersion::detect("0.34.0+a0f26a1b"), None);
+        assert_eq!(NoirArtifactVersion::detect("0.360.0"), None);
+        assert_eq!(NoirArtifactVersion::detect("1.0.0-beta.3"), None);
+
+        // the artifacts compiled with the supported version are loaded
+        let path =
+            env::current_dir()?.join("src/noir/test_folder/test_circuit/target/test_circuit.json");
+        let artifact: serde_json::Value = serde_json::from_slice(&std::fs::read(path)?).unwrap();
+        assert!(NoirArtifactVersion::detect(artifact["noir_version"].as_str().unwrap()).is_some());
+        NoirFCircuit::<Fr, 2, 2>::from_bytes(&serde_json::to_vec(&artifact).unwrap())?;
+
+        // while the others are rejected before parsing their bytecode
+        for (noir_version, found) in [
+            (Some("1.0.0-beta.3+ceaa1986"), "1.0.0-beta.3+ceaa1986"),
+            (None, "unknown"),
+        ] {
+            let mut artifact = artifact.clone();
+            match noir_version {
+                Some(noir_version) => artifact["noir_version"] = noir_version.into(),
+                None => {
+                    artifact.as_object_mut().unwrap().remove("noir_version");
+                }
+            }
+            match NoirFCircuit::<Fr, 2, 2>::from_bytes(&serde_json::to_vec(&artifact).unwrap())
+                .err()
+                .map(FrontendError::try_from)
+            {
+                Some(Ok(FrontendError::UnsupportedNoirVersion {
+                    found: found_version,
+                    supported,
+                })) => {
+                    assert_eq!(found_version, found);
+                    assert_eq!(supported, vec!["0.35".to_string(), "0.36".to_string()]);
+                }
+                r => panic!(
+                    "expected FrontendError::UnsupportedNoirVersion, got {:?}",
+                    r
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// folds with Nova the `test_circuit` artifacts compiled by each of the supported versions of
+    /// `nargo`, whose bytecode is serialized differently
+    #[test]
+    fn test_noir_versions_nova() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            NoirFCircuit<Fr, 2, 2>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let mut rng = ark_std::test_rng();
+        let target = env::current_dir()?.join("src/noir/test_folder/test_circuit/target");
+        for (file, version) in [
+            ("test_circuit_v0_35.json", NoirArtifactVersion::V0_35),
+            ("test_circuit.json", NoirArtifactVersion::V0_36),
+        ] {
+            let artifact = std::fs::read(target.join(file))?;
+            let artifact_json: serde_json::Value = serde_json::from_slice(&artifact).unwrap();
+            assert_eq!(
+                NoirArtifactVersion::detect(artifact_json["noir_version"].as_str().unwrap()),
+                Some(version)
+            );
+
+            let f_circuit = NoirFCircuit::<Fr, 2, 2>::from_bytes(&artifact)?;
+            let poseidon_config = poseidon_canonical_config::<Fr>();
+            let prep_param = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+            let params = N::preprocess(&mut rng, &prep_param)?;
+            let z_0 = vec![Fr::from(2), Fr::from(5)];
+            let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+            let mut z_i = z_0;
+            for _ in 0..3 {
+                let external_inputs = vec![Fr::from(3), Fr::from(7)];
+                z_i = external_inputs_step_native(z_i, external_inputs.clone());
+                nova.prove_step(&mut rng, VecF(external_inputs), None)?;
+            }
+            assert_eq!(nova.state(), z_i);
+            N::verify(params.1, nova.ivc_proof())?;
+        }
+        Ok(())
+    }
+
+    /// folds the `test_assert` circuit, whose assertion fails when the external input is not the
+    /// state plus one, and checks that the failure is reported with its step and message
+    #[test]
+    fn test_noir_assertion_failure_is_reported() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let cur_path = env::current_dir()?;
+        let f_circuit = NoirFCircuit::<Fr, 1, 1>::new(
+            cur_path
+                .join("src/noir/test_folder/test_assert/target/test_assert.json")
+                .into(),
+        )?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            NoirFCircuit<Fr, 1, 1>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, f_circuit.clone(), vec![Fr::from(0)])?;
+        nova.prove_step(&mut rng, VecF(vec![Fr::from(1)]), None)?;
+        nova.prove_step(&mut rng, VecF(vec![Fr::from(2)]), None)?;
+
+        match nova
+            .prove_step(&mut rng, VecF(vec![Fr::from(5)]), None)
+            .err()
+            .map(FrontendError::try_from)
+        {
+            Some(Ok(FrontendError::NoirAssertionFailed {
+                step,
+                opcode_index,
+                message,
+            })) => {
+                assert_eq!(step, 2);
+                assert!(matches!(
+                    f_circuit.circuit.opcodes[opcode_index],
+                    Opcode::AssertZero(_)
+                ));
+                assert_eq!(message, "the external input must be the state plus one");
+            }
+            r => panic!("expected FrontendError::NoirAssertionFailed, got {:?}", r),
+        }
+        // the error is only reported once
+        assert!(f_circuit.take_step_error().is_none());
+        Ok(())
+    }
+
+    /// generates the constraints of the `test_assert` circuit in setup mode, where the dummy
+    /// inputs fail its assertion, which must not stop the circuit from being shaped
+    #[test]
+    fn test_noir_setup_mode_with_failing_assertion() -> Result<(), Error> {
+        let f_circuit = NoirFCircuit::<Fr, 1, 1>::new(
+            env::current_dir()?
+                .join("src/noir/test_folder/test_assert/target/test_assert.json")
+                .into(),
+        )?;
+
+        let n_constraints = |mode: SynthesisMode, external_input: u64| -> Result<usize, Error> {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            cs.set_mode(mode);
+            let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(0)]))?;
+            let external_inputs =
+                Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(external_input)]))?;
+            f_circuit.generate_step_constraints(cs.clone(), 0, z_i, VecFpVar(external_inputs))?;
+            Ok(cs.num_constraints())
+        };
+        // in setup mode the inputs are read as zeros, for which the assertion fails
+        assert_eq!(
+            n_constraints(SynthesisMode::Setup, 0)?,
+            n_constraints(
+                SynthesisMode::Prove {
+                    construct_matrices: true
+                },
+                1
+            )?
+        );
+        assert!(f_circuit.take_step_error().is_none());
+
+        // while when proving the failure is reported
+        assert!(n_constraints(
+            SynthesisMode::Prove {
+                construct_matrices: true
+            },
+            0
+        )
+        .is_err());
+        assert!(f_circuit.take_step_error().is_some());
+        Ok(())
+    }
+
+    /// folds the `test_brillig` circuit, which divides the state by seven through an
+    /// unconstrained function executed by the Brillig VM
+    #[test]
+    fn test_noir_brillig_nova() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let cur_path = env::current_dir()?;
+        let f_circuit = NoirFCircuit::<Fr, 1, 0>::new(
+            cur_path
+                .join("src/noir/test_folder/test_brillig/target/test_brillig.json")
+                .into(),
+        )?;
+        assert!(!f_circuit.unconstrained_functions.is_empty());
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            NoirFCircuit<Fr, 1, 0>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, f_circuit, vec![Fr::from(7 * 7 * 7)])?;
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, VecF(vec![]), None)?;
+        }
+        assert_eq!(nova.state(), vec![Fr::from(1)]);
+        N::verify(params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
+    /// folds `test_circuit` with the external inputs read from `test_circuit/steps.toml`
+    #[test]
+    fn test_noir_toml_inputs_nova() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let cur_path = env::current_dir()?;
+        let f_circuit = NoirFCircuit::<Fr, 2, 2>::new(
+            cur_path
+                .join("src/noir/test_folder/test_circuit/target/test_circuit.json")
+                .into(),
+        )?;
+        let external_inputs = load_external_inputs_toml::<Fr, 2>(
+            cur_path.join("src/noir/test_folder/test_circuit/steps.toml"),
+            &f_circuit.abi,
+        )?;
+        assert_eq!(external_inputs.len(), 3);
+        assert_eq!(external_inputs[1].0, vec![Fr::from(2), -Fr::from(1)]);
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            NoirFCircuit<Fr, 2, 2>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::from(1), Fr::from(1)];
+        let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+        let mut z_i = z_0;
+        for inputs in external_inputs {
+            z_i = external_inputs_step_native(z_i, inputs.0.clone());
+            nova.prove_step(&mut rng, inputs, None)?;
+        }
+        assert_eq!(nova.state(), z_i);
+        assert_eq!(z_i, vec![Fr::from(60), -Fr::from(35)]);
+        N::verify(params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_noir_oracle_is_rejected() -> Result<(), Error> {
+        let cur_path = env::current_dir()?;
+        let f_circuit = NoirFCircuit::<Fr, 1, 0>::new(
+            cur_path
+                .join("src/noir/test_folder/test_oracle/target/test_oracle.json")
+                .into(),
+        );
+        assert!(matches!(
+            f_circuit.err().map(FrontendError::try_from),
+            Some(Ok(FrontendError::NoirOracleNotSupported(oracle))) if oracle == "get_value"
+        ));
+        Ok(())
+    }
+
+    /// folds the `test_black_box` circuit, which range checks its u32 state and computes the AND
+    /// and XOR of its elements through black box calls
+    #[test]
+    fn test_noir_black_box_nova() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let cur_path = env::current_dir()?;
+        let f_circuit = NoirFCircuit::<Fr, 2, 0>::new(
+            cur_path
+                .join("src/noir/test_folder/test_black_box/target/test_black_box.json")
+                .into(),
+        )?;
+        for name in ["range", "and", "xor"] {
+            assert!(f_circuit.circuit.opcodes.iter().any(
+                |opcode| matches!(opcode, Opcode::BlackBoxFuncCall(call) if call.name() == name)
+            ));
+        }
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            NoirFCircuit<Fr, 2, 0>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+
+        let (mut a, mut b) = (0xdeadbeef_u32, 0x12345678_u32);
+        let mut nova = N::init(&params, f_circuit, vec![Fr::from(a), Fr::from(b)])?;
+        for _ in 0..3 {
+            (a, b) = (a & b, a ^ b);
+            nova.prove_step(&mut rng, VecF(vec![]), None)?;
+        }
+        assert_eq!(nova.state(), vec![Fr::from(a), Fr::from(b)]);
+        N::verify(params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_type_len() -> Result<(), Error> {
+        let state = serde_json::json!({
+            "kind": "struct",
+            "path": "State",
+            "fields": [
+                {
+                    "name": "values",
+                    "type": {
+                        "kind": "array",
+                        "length": 2,
+                        "type": { "kind": "array", "length": 2, "type": { "kind": "field" } }
+                    }
+                },
+                { "name": "digest", "type": { "kind": "field" } }
+            ]
+        });
+        assert_eq!(abi_type_len(&state)?, 5);
+        let tuple = serde_json::json!({
+            "kind": "tuple",
+            "fields": [
+                { "kind": "integer", "sign": "unsigned", "width": 32 },
+                { "kind": "string", "length": 3 },
+                { "kind": "array", "length": 4, "type": state },
+            ]
+        });
+        assert_eq!(abi_type_len(&tuple)?, 1 + 3 + 4 * 5);
+        assert!(matches!(
+            abi_type_len(&serde_json::json!({ "kind": "slice" })),
+            Err(Error::NotSupportedYet(_))
+        ));
+        Ok(())
+    }
+
+    /// the `test_struct` circuit takes its external input first, and its state is a struct with
+    /// a nested array, which is flattened following its ABI
+    #[test]
+    fn test_struct_state_is_flattened() -> Result<(), Error> {
+        let path =
+            env::current_dir()?.join("src/noir/test_folder/test_struct/target/test_struct.json");
+        let f_circuit = NoirFCircuitDyn::<Fr>::new(path.clone().into())?;
+        assert_eq!(f_circuit.state_len(), 5);
+        assert_eq!(f_circuit.external_inputs_len(), 1);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        // z_i = State { values: [[1, 2], [3, 4]], digest: 5 }
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || {
+            Ok((1..=5).map(Fr::from).collect::<Vec<_>>())
+        })?;
+        let external_inputs = VecFpVar(Vec::new_witness(cs.clone(), || Ok(vec![Fr::from(10)]))?);
+        let z_i1 = NoirFCircuit::<Fr, 5, 1>::new(path.into())?.generate_step_constraints(
+            cs.clone(),
+            0,
+            z_i,
+            external_inputs,
+        )?;
+        // values + x, and digest * x + values[1][0]
+        assert_eq!(
+            z_i1.iter()
+                .map(|v| v.value())
+                .collect::<Result<Vec<_>, _>>()?,
+            [11, 12, 13, 14, 5 * 10 + 13].map(Fr::from).to_vec()
+        );
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_noir_fcircuits_are_send_sync() {
+        // the step independent data is shared by the clones of the circuits, which must be able
+        // to generate the witness of different steps in parallel
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<NoirFCircuit<Fr, 2, 2>>();
+        assert_send_sync::<NoirFCircuitDyn<Fr>>();
+    }
+
+    #[test]
+    fn test_dyn_sizes_are_inferred() -> Result<(), Error> {
+        let cur_path = env::current_dir()?;
+        for (name, state_len, external_inputs_len) in
+            [("test_circuit", 2, 2), ("test_no_external_inputs", 2, 0)]
+        {
+            let path = cur_path.join(format!("src/noir/test_folder/{name}/target/{name}.json"));
+            let f_circuit = NoirFCircuitDyn::<Fr>::new(path.into())?;
+            assert_eq!(f_circuit.state_len(), state_len);
+            assert_eq!(f_circuit.external_inputs_len(), external_inputs_len);
+        }
+
+        // the dynamically sized circuit computes the same step as the const-generic one
+        let path = cur_path.join("src/noir/test_folder/test_circuit/target/test_circuit.json");
+        let inputs = vec![Fr::from(2), Fr::from(5)];
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone()))?;
+        let external_inputs = VecFpVar(Vec::new_witness(cs.clone(), || Ok(inputs.clone()))?);
+        let output = NoirFCircuit::<Fr, 2, 2>::new(path.clone().into())?
+            .generate_step_constraints(cs.clone(), 0, z_i, external_inputs)?;
+
+        let cs_dyn = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs_dyn.clone(), || Ok(inputs.clone()))?;
+        let external_inputs = VecFpVarDyn::new_witness(cs_dyn.clone(), || Ok(inputs.clone()))?;
+        let output_dyn = NoirFCircuitDyn::<Fr>::new(path.into())?.generate_step_constraints(
+            cs_dyn.clone(),
+            0,
+            z_i,
+            external_inputs,
+        )?;
+        for (x, y) in output.iter().zip(&output_dyn) {
+            assert_eq!(x.value()?, y.value()?);
+        }
+        assert_eq!(cs.num_constraints(), cs_dyn.num_constraints());
+        assert_eq!(cs.num_witness_variables(), cs_dyn.num_witness_variables());
+        assert!(cs_dyn.is_satisfied()?);
+        Ok(())
+    }
+
+    /// folds the Noir test circuit with Nova through the dynamically sized NoirFCircuitDyn, whose
+    /// default (empty) external inputs are allocated at their actual length
+    #[test]
+    fn test_noir_dyn_nova() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let cur_path = env::current_dir()?;
+        let f_circuit = NoirFCircuitDyn::<Fr>::new(
+            cur_path
+                .join("src/noir/test_folder/test_circuit/target/test_circuit.json")
+                .into(),
+        )?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            NoirFCircuitDyn<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(2), Fr::from(5)];
+        let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+        let mut z_i = z_0;
+        for _ in 0..3 {
+            let external_inputs = vec![Fr::from(3), Fr::from(7)];
+            z_i = external_inputs_step_native(z_i, external_inputs.clone());
+            nova.prove_step(&mut rng, external_inputs, None)?;
+        }
+        assert_eq!(nova.state(), z_i);
+        N::verify(params.1, nova.ivc_proof())?;
+
+        // external inputs of the wrong length are rejected
+        assert!(nova.prove_step(&mut rng, vec![Fr::from(3)], None).is_err());
+        Ok(())
+    }
+
+    /// folds the Noir test circuit with HyperNova, checking that the CCS conversion of the
+    /// circuits produced by the Noir frontend works end to end
+    #[test]
+    fn test_noir_hypernova() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let cur_path = env::current_dir()?;
+        let f_circuit = NoirFCircuit::<Fr, 2, 2>::new(
+            cur_path
+                .join("src/noir/test_folder/test_circuit/target/test_circuit.json")
+                .into(),
+        )?;
+
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            NoirFCircuit<Fr, 2, 2>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            1,
+            1,
+            false,
+        >;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit.clone());
+        let params = HN::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(2), Fr::from(5)];
+        let mut hypernova = HN::init(&params, f_circuit, z_0.clone())?;
+        let mut z_i = z_0;
+        for _ in 0..3 {
+            let external_inputs = vec![Fr::from(3), Fr::from(7)];
+            z_i = external_inputs_step_native(z_i, external_inputs.clone());
+            hypernova.prove_step(&mut rng, VecF(external_inputs), None)?;
+        }
+        assert_eq!(hypernova.state(), z_i);
+        HN::verify(params.1, hypernova.ivc_proof())?;
+        Ok(())
+    }
 }
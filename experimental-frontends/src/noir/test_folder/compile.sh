@@ -1,7 +1,7 @@
 #!/bin/bash
 CUR_DIR=$(pwd)
 TEST_PATH="${CUR_DIR}/experimental-frontends/src/noir/test_folder/"
-for test_path in test_circuit test_mimc test_no_external_inputs; do
+for test_path in test_circuit test_mimc test_no_external_inputs test_assert test_brillig test_oracle test_black_box test_struct test_state_len_1 test_state_len_4 test_state_len_28; do
 	FOLDER="${TEST_PATH}${test_path}/"
 	cd ${FOLDER} && nargo compile && cd ${TEST_PATH}
 done
@@ -0,0 +1,8 @@
+#!/bin/bash
+# Compiles the artifacts of nargo 0.35, which are loaded next to the ones of compile.sh
+CUR_DIR=$(pwd)
+TEST_PATH="${CUR_DIR}/experimental-frontends/src/noir/test_folder/"
+for test_path in test_circuit; do
+	FOLDER="${TEST_PATH}${test_path}/"
+	cd ${FOLDER} && nargo compile && mv target/${test_path}.json target/${test_path}_v0_35.json && cd ${TEST_PATH}
+done
@@ -0,0 +1,79 @@
+//! Bytecode of the artifacts of `nargo` 0.35, whose circuits end with a `recursive` flag that
+//! 0.36 moved out of ACIR, into the API of the proving backends. The rest of the program is
+//! serialized as in 0.36, so it is read into the types of the current ACIR, skipping the flag.
+
+use acvm::acir::{
+    acir_field::GenericFieldElement,
+    circuit::{
+        brillig::BrilligBytecode, AssertionPayload, Circuit, ExpressionWidth, Opcode,
+        OpcodeLocation, Program, PublicInputs,
+    },
+    native_types::Witness,
+};
+use ark_ff::PrimeField;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use serde::{de::Error as _, Deserialize};
+use std::collections::BTreeSet;
+use std::io::Read;
+
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct ProgramV0_35<F: PrimeField> {
+    functions: Vec<CircuitV0_35<F>>,
+    unconstrained_functions: Vec<BrilligBytecode<GenericFieldElement<F>>>,
+}
+
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct CircuitV0_35<F: PrimeField> {
+    current_witness_index: u32,
+    opcodes: Vec<Opcode<GenericFieldElement<F>>>,
+    expression_width: ExpressionWidth,
+    private_parameters: BTreeSet<Witness>,
+    public_parameters: PublicInputs,
+    return_values: PublicInputs,
+    assert_messages: Vec<(OpcodeLocation, AssertionPayload<GenericFieldElement<F>>)>,
+    // only tells the backends whether to prove the circuit for recursion, which does not change
+    // its constraints
+    #[allow(dead_code)]
+    recursive: bool,
+}
+
+impl<F: PrimeField> From<CircuitV0_35<F>> for Circuit<GenericFieldElement<F>> {
+    fn from(circuit: CircuitV0_35<F>) -> Self {
+        Circuit {
+            current_witness_index: circuit.current_witness_index,
+            opcodes: circuit.opcodes,
+            expression_width: circuit.expression_width,
+            private_parameters: circuit.private_parameters,
+            public_parameters: circuit.public_parameters,
+            return_values: circuit.return_values,
+            assert_messages: circuit.assert_messages,
+        }
+    }
+}
+
+/// Reads the `bytecode` of a `nargo` 0.35 artifact, which is the base64 encoding of the gzipped
+/// bincode serialization of its program.
+pub(super) fn deserialize_program<F: PrimeField>(
+    artifact: &serde_json::Value,
+) -> Result<Program<GenericFieldElement<F>>, serde_json::Error> {
+    let bytecode = artifact["bytecode"]
+        .as_str()
+        .ok_or_else(|| serde_json::Error::custom("the bytecode is not a string"))?;
+    let gzipped = base64::engine::general_purpose::STANDARD
+        .decode(bytecode)
+        .map_err(serde_json::Error::custom)?;
+    let mut serialized = Vec::new();
+    GzDecoder::new(gzipped.as_slice())
+        .read_to_end(&mut serialized)
+        .map_err(serde_json::Error::custom)?;
+    let program: ProgramV0_35<F> =
+        bincode::deserialize(&serialized).map_err(serde_json::Error::custom)?;
+
+    Ok(Program {
+        functions: program.functions.into_iter().map(Circuit::from).collect(),
+        unconstrained_functions: program.unconstrained_functions,
+    })
+}
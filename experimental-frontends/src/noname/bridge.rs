@@ -13,64 +13,46 @@ use noname::backends::{
 use noname::witness::CompiledCircuit;
 use num_bigint::BigUint;
 
-pub struct NonameSonobeCircuit<'a, 'b, 'c, F: PrimeField, BF: BackendField> {
+pub struct NonameSonobeCircuit<'a, 'b, F: PrimeField, BF: BackendField> {
     pub compiled_circuit: CompiledCircuit<R1CS<BF>>,
     pub witness: GeneratedWitness<BF>,
-    pub assigned_z_i: &'a Vec<FpVar<F>>,
-    pub assigned_external_inputs: &'b Vec<FpVar<F>>,
-    pub assigned_z_i1: &'c Vec<FpVar<F>>,
+    pub assigned_z_i1: &'a [FpVar<F>],
+    /// the arguments of `main` (the state z_i, the external inputs and the step index), in the
+    /// order with which they appear in the witness: the public ones first, each group in the
+    /// order of declaration of the arguments
+    pub assigned_inputs: &'b [FpVar<F>],
 }
 
-impl<'a, 'b, 'c, F: PrimeField, BF: BackendField> ConstraintSynthesizer<F>
-    for NonameSonobeCircuit<'a, 'b, 'c, F, BF>
+impl<'a, 'b, F: PrimeField, BF: BackendField> ConstraintSynthesizer<F>
+    for NonameSonobeCircuit<'a, 'b, F, BF>
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
-        let public_io_length = self.assigned_z_i.len() * 2;
-        let external_inputs_len = self.assigned_external_inputs.len();
+        let outputs_len = self.assigned_z_i1.len();
+        let io_len = outputs_len + self.assigned_inputs.len();
 
         // we need to map noname r1cs indexes with sonobe
         let mut idx_to_var = HashMap::new();
 
-        // for both the  z_i, z_i1 vectors, we assume that they have been assigned in the order
-        // with which it will appear in the witness
-        let mut z_i_pointer = 0;
-        let mut z_i1_pointer = 0;
-        let mut external_inputs_pointer = 0;
-
         // arkworks assigns by default the 1 constant
         // assumes witness is: [1, public_outputs, public_inputs, private_inputs, aux]
         let witness_size = self.witness.witness.len();
+        if witness_size <= io_len {
+            return Err(SynthesisError::AssignmentMissing);
+        }
         for idx in 1..witness_size {
-            if idx <= public_io_length {
-                if idx <= self.assigned_z_i.len() {
-                    // in noname public outputs come first
-                    // we are in the case of public outputs (z_i1 vector)
-                    // those have already been assigned at specific indexes by sonobe
-                    let var = match &self.assigned_z_i1[z_i1_pointer] {
-                        FpVar::Var(allocated_fp) => allocated_fp.variable,
-                        _ => return Err(SynthesisError::Unsatisfiable),
-                    };
-                    idx_to_var.insert(idx, var);
-                    z_i1_pointer += 1;
-                } else {
-                    // we are in the case of public inputs (z_i values)
-                    // those have already been assigned at specific indexes by sonobe
-                    let var = match &self.assigned_z_i[z_i_pointer] {
-                        FpVar::Var(allocated_fp) => allocated_fp.variable,
-                        _ => return Err(SynthesisError::Unsatisfiable),
-                    };
-                    idx_to_var.insert(idx, var);
-                    z_i_pointer += 1;
-                }
-            } else if idx <= public_io_length + external_inputs_len {
-                // we are in the case of external inputs
-                // those have already been assigned at specific indexes
-                let var = match &self.assigned_external_inputs[external_inputs_pointer] {
+            if idx <= io_len {
+                // in noname public outputs come first, we are either in the case of public
+                // outputs (z_i1 vector) or of the inputs, which have already been assigned at
+                // specific indexes by sonobe
+                let assigned = match idx <= outputs_len {
+                    true => &self.assigned_z_i1[idx - 1],
+                    false => &self.assigned_inputs[idx - 1 - outputs_len],
+                };
+                let var = match assigned {
                     FpVar::Var(allocated_fp) => allocated_fp.variable,
                     _ => return Err(SynthesisError::Unsatisfiable),
                 };
                 idx_to_var.insert(idx, var);
-                external_inputs_pointer += 1;
             } else {
                 // we are in the case of auxiliary private inputs
                 // we need to assign those
@@ -81,11 +63,6 @@ impl<'a, 'b, 'c, F: PrimeField, BF: BackendField> ConstraintSynthesizer<F>
             }
         }
 
-        if (z_i_pointer != self.assigned_z_i.len())
-            || (external_inputs_pointer != self.assigned_external_inputs.len())
-        {
-            return Err(SynthesisError::AssignmentMissing);
-        }
         let make_index = |index: usize| match index == 0 {
             true => Ok(Variable::One),
             false => {
@@ -14,12 +14,27 @@ pub mod utils;
 use crate::utils::{VecF, VecFpVar};
 
 use self::bridge::NonameSonobeCircuit;
-use self::utils::{compile_source_code, NonameInputs};
+use self::utils::{compile_source_code, parse_main_args, NonameArg, NonameInputs};
 
-// `L` indicates the length of the ExternalInputs vector of field elements.
+/// Name of the argument of `main` holding the state.
+const STATE_ARG: &str = "ivc_inputs";
+/// Name of the argument of `main` holding the external inputs.
+const EXTERNAL_INPUTS_ARG: &str = "external_inputs";
+/// Name of the argument of `main` holding the step index.
+const STEP_ARG: &str = "step";
+
+/// FCircuit of a noname circuit, whose `main` function takes the state as the public argument
+/// `ivc_inputs: [Field; SL]` and returns the next state, and optionally the external inputs as the
+/// (public or private) argument `external_inputs: [Field; EIL]`, and the step index as the
+/// (public or private) argument `step: Field`.
+///
+/// Notice that the step index is given to the FCircuit as a value, so the `step` argument is a
+/// witness which is not constrained to be the step counter of the folding scheme.
 #[derive(Debug, Clone)]
 pub struct NonameFCircuit<F: PrimeField, BF: BackendField, const SL: usize, const EIL: usize> {
     pub circuit: CompiledCircuit<R1CSNoname<BF>>,
+    // arguments of `main`, in the order with which they appear in the witness
+    args: Vec<NonameArg>,
     _f: PhantomData<F>,
 }
 
@@ -29,13 +44,53 @@ impl<F: PrimeField, BF: BackendField, const SL: usize, const EIL: usize> FCircui
     type Params = String;
     type ExternalInputs = VecF<F, EIL>;
     type ExternalInputsVar = VecFpVar<F, EIL>;
+    type WitnessHint = ();
 
     fn new(code: Self::Params) -> Result<Self, Error> {
+        let mut args = parse_main_args(&code)?;
+        for arg in &args {
+            let expected_len = match arg.name.as_str() {
+                STATE_ARG if !arg.public => {
+                    return Err(Error::Other(format!(
+                        "The noname argument '{}' must be public",
+                        STATE_ARG
+                    )))
+                }
+                STATE_ARG => SL,
+                EXTERNAL_INPUTS_ARG => EIL,
+                STEP_ARG => 1,
+                name => {
+                    return Err(Error::Other(format!(
+                        "Unexpected noname argument '{}', expected '{}', '{}' or '{}'",
+                        name, STATE_ARG, EXTERNAL_INPUTS_ARG, STEP_ARG
+                    )))
+                }
+            };
+            if arg.len != expected_len {
+                return Err(Error::NotExpectedLength(arg.len, expected_len));
+            }
+        }
+        if !args.iter().any(|arg| arg.name == STATE_ARG) {
+            return Err(Error::MissingValue(format!(
+                "noname argument '{}'",
+                STATE_ARG
+            )));
+        }
+        if EIL > 0 && !args.iter().any(|arg| arg.name == EXTERNAL_INPUTS_ARG) {
+            return Err(Error::MissingValue(format!(
+                "noname argument '{}'",
+                EXTERNAL_INPUTS_ARG
+            )));
+        }
+        // the public inputs come first in the witness, the sort being stable
+        args.sort_by_key(|arg| !arg.public);
+
         let compiled_circuit = compile_source_code::<BF>(&code).map_err(|_| {
             Error::Other("Encountered an error while compiling a noname circuit".to_owned())
         })?;
         Ok(NonameFCircuit {
             circuit: compiled_circuit,
+            args,
             _f: PhantomData,
         })
     }
@@ -47,16 +102,29 @@ impl<F: PrimeField, BF: BackendField, const SL: usize, const EIL: usize> FCircui
     fn generate_step_constraints(
         &self,
         cs: ConstraintSystemRef<F>,
-        _i: usize,
+        i: usize,
         z_i: Vec<FpVar<F>>,
         external_inputs: Self::ExternalInputsVar,
     ) -> Result<Vec<FpVar<F>>, SynthesisError> {
-        let wtns_external_inputs =
-            NonameInputs::from_fpvars((&external_inputs.0, "external_inputs".to_string()));
-        let wtns_ivc_inputs = NonameInputs::from_fpvars((&z_i, "ivc_inputs".to_string()));
+        let mut wtns_public_inputs = NonameInputs::empty();
+        let mut wtns_private_inputs = NonameInputs::empty();
+        let mut assigned_inputs = vec![];
+        for arg in &self.args {
+            let values = match arg.name.as_str() {
+                STATE_ARG => z_i.clone(),
+                EXTERNAL_INPUTS_ARG => external_inputs.0.clone(),
+                _ => vec![FpVar::new_witness(cs.clone(), || Ok(F::from(i as u64)))?],
+            };
+            let wtns_inputs = match arg.public {
+                true => &mut wtns_public_inputs,
+                false => &mut wtns_private_inputs,
+            };
+            wtns_inputs.extend(NonameInputs::from_fpvars((&values, arg.name.clone())));
+            assigned_inputs.extend(values);
+        }
         let noname_witness = self
             .circuit
-            .generate_witness(wtns_ivc_inputs.0, wtns_external_inputs.0)
+            .generate_witness(wtns_public_inputs.0, wtns_private_inputs.0)
             .map_err(|_| SynthesisError::Unsatisfiable)?;
         let z_i1_end_index = z_i.len() + 1;
         let assigned_z_i1: Vec<FpVar<F>> = (1..z_i1_end_index)
@@ -74,9 +142,8 @@ impl<F: PrimeField, BF: BackendField, const SL: usize, const EIL: usize> FCircui
         let noname_circuit = NonameSonobeCircuit {
             compiled_circuit: self.circuit.clone(),
             witness: noname_witness,
-            assigned_z_i: &z_i,
-            assigned_external_inputs: &external_inputs.0,
             assigned_z_i1: &assigned_z_i1,
+            assigned_inputs: &assigned_inputs,
         };
         noname_circuit.generate_constraints(cs.clone())?;
 
@@ -86,16 +153,24 @@ impl<F: PrimeField, BF: BackendField, const SL: usize, const EIL: usize> FCircui
 
 #[cfg(test)]
 mod tests {
-    use ark_bn254::Fr;
+    use ark_bn254::{Fr, G1Projective as Projective};
     use ark_ff::PrimeField;
+    use ark_grumpkin::Projective as Projective2;
     use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
     use ark_relations::r1cs::ConstraintSystem;
     use noname::backends::r1cs::R1csBn254Field;
 
-    use folding_schemes::{frontend::FCircuit, Error};
+    use folding_schemes::{
+        commitment::pedersen::Pedersen,
+        folding::nova::{Nova, PreprocessorParam},
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        Error, FoldingScheme,
+    };
 
+    use super::utils::{parse_main_args, NonameArg};
     use super::NonameFCircuit;
-    use crate::utils::VecFpVar;
+    use crate::utils::{VecF, VecFpVar};
 
     /// Native implementation of `NONAME_CIRCUIT_EXTERNAL_INPUTS`
     fn external_inputs_step_native<F: PrimeField>(z_i: Vec<F>, external_inputs: Vec<F>) -> Vec<F> {
@@ -119,6 +194,141 @@ mod tests {
     return [out, ivc_inputs[1]];
 }";
 
+    /// Native implementation of `NONAME_CIRCUIT_XOR`
+    fn xor_step_native<F: PrimeField>(i: usize, z_i: Vec<F>, external_inputs: Vec<F>) -> Vec<F> {
+        let mut z_i1: Vec<F> = z_i[..4]
+            .iter()
+            .zip(&external_inputs)
+            .map(|(&lhs, &rhs)| lhs + rhs - F::from(2) * lhs * rhs)
+            .collect();
+        z_i1.push(z_i[4] + F::from(i as u64));
+        z_i1
+    }
+
+    // XORs the bits of the external inputs into the first 4 elements of the state, and
+    // accumulates the step index in its last element.
+    const NONAME_CIRCUIT_XOR: &str = "fn xor(lhs: Field, rhs: Field) -> Field {
+    assert_eq(rhs * rhs, rhs);
+    return lhs + rhs - (2 * lhs * rhs);
+}
+
+fn main(pub ivc_inputs: [Field; 5], external_inputs: [Field; 4], step: Field) -> [Field; 5] {
+    return [
+        xor(ivc_inputs[0], external_inputs[0]),
+        xor(ivc_inputs[1], external_inputs[1]),
+        xor(ivc_inputs[2], external_inputs[2]),
+        xor(ivc_inputs[3], external_inputs[3]),
+        ivc_inputs[4] + step,
+    ];
+}";
+
+    #[test]
+    fn test_parse_main_args() -> Result<(), Error> {
+        let arg = |name: &str, public, len| NonameArg {
+            name: name.to_string(),
+            public,
+            len,
+        };
+        assert_eq!(
+            parse_main_args(NONAME_CIRCUIT_XOR)?,
+            vec![
+                arg("ivc_inputs", true, 5),
+                arg("external_inputs", false, 4),
+                arg("step", false, 1),
+            ]
+        );
+        assert_eq!(
+            parse_main_args(
+                "fn main(pub ivc_inputs: [Field; 2], pub external_inputs: [[Field; 2]; 3],) -> Field {"
+            )?,
+            vec![arg("ivc_inputs", true, 2), arg("external_inputs", true, 6)]
+        );
+        assert!(matches!(
+            parse_main_args("fn main(pub ivc_inputs: [Field; LEN]) -> Field {"),
+            Err(Error::NotSupportedYet(_))
+        ));
+
+        // the arguments must match the state and external inputs lengths
+        assert!(matches!(
+            NonameFCircuit::<Fr, R1csBn254Field, 5, 3>::new(NONAME_CIRCUIT_XOR.to_owned()),
+            Err(Error::NotExpectedLength(4, 3))
+        ));
+        assert!(matches!(
+            NonameFCircuit::<Fr, R1csBn254Field, 2, 2>::new(
+                NONAME_CIRCUIT_EXTERNAL_INPUTS.replace("pub ivc_inputs", "ivc_inputs")
+            ),
+            Err(Error::Other(_))
+        ));
+        Ok(())
+    }
+
+    // Tests that the external inputs can be given as a public argument.
+    #[test]
+    fn test_public_external_inputs() -> Result<(), Error> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let params =
+            NONAME_CIRCUIT_EXTERNAL_INPUTS.replace("external_inputs:", "pub external_inputs:");
+        let circuit = NonameFCircuit::<Fr, R1csBn254Field, 2, 2>::new(params)?;
+        let inputs_public = vec![Fr::from(2), Fr::from(5)];
+        let inputs_private = vec![Fr::from(8), Fr::from(2)];
+
+        let ivc_inputs_var =
+            Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs_public.clone()))?;
+        let external_inputs_var =
+            Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs_private.clone()))?;
+        let z_i1 = circuit.generate_step_constraints(
+            cs.clone(),
+            0,
+            ivc_inputs_var,
+            VecFpVar(external_inputs_var),
+        )?;
+        assert!(cs.is_satisfied()?);
+        assert_eq!(
+            z_i1.value()?,
+            external_inputs_step_native(inputs_public, inputs_private)
+        );
+        Ok(())
+    }
+
+    // Folds `NONAME_CIRCUIT_XOR`, which takes external inputs and the step index, under Nova.
+    #[test]
+    fn test_xor_external_inputs_nova() -> Result<(), Error> {
+        type FC = NonameFCircuit<Fr, R1csBn254Field, 5, 4>;
+        type N =
+            Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+        let mut rng = ark_std::test_rng();
+        let f_circuit = FC::new(NONAME_CIRCUIT_XOR.to_owned())?;
+        let prep_param =
+            PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::from(0); 5];
+        let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+
+        let mut z_i = z_0;
+        for i in 0..6 {
+            let external_inputs: Vec<Fr> =
+                (0..4).map(|j| Fr::from(((i >> j) & 1) as u64)).collect();
+            nova.prove_step(&mut rng, VecF(external_inputs.clone()), None)?;
+            z_i = xor_step_native(i, z_i, external_inputs);
+        }
+        assert_eq!(nova.state(), z_i);
+        // the XOR of the bits of 0..6, and the sum of the step indexes
+        assert_eq!(z_i, [1, 0, 0, 0, 15].map(Fr::from).to_vec());
+        N::verify(params.1, nova.ivc_proof())?;
+
+        // the external inputs must be bits
+        let f_circuit = FC::new(NONAME_CIRCUIT_XOR.to_owned())?;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(0); 5]))?;
+        let external_inputs_var =
+            Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(2); 4]))?;
+        assert!(f_circuit
+            .generate_step_constraints(cs.clone(), 0, z_i_var, VecFpVar(external_inputs_var))
+            .is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_step_native() -> Result<(), Error> {
         let cs = ConstraintSystem::<Fr>::new_ref();
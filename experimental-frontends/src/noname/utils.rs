@@ -12,6 +12,8 @@ use noname::{
 };
 use serde_json::json;
 
+use folding_schemes::Error;
+
 pub struct NonameInputs(pub JsonInputs);
 
 impl<F: PrimeField> From<(&Vec<F>, String)> for NonameInputs {
@@ -38,13 +40,25 @@ impl<F: PrimeField> From<(&Vec<F>, String)> for NonameInputs {
 }
 
 impl NonameInputs {
+    pub fn empty() -> Self {
+        NonameInputs(JsonInputs(HashMap::new()))
+    }
+
+    /// Adds the entries of `inputs`.
+    pub fn extend(&mut self, inputs: NonameInputs) {
+        self.0 .0.extend(inputs.0 .0);
+    }
+
     pub fn from_fpvars<F: PrimeField>(value: (&Vec<FpVar<F>>, String)) -> Self {
         let (values, key) = value;
         let mut inputs = HashMap::new();
         if !values.is_empty() {
             let field_elements: Vec<String> = values
                 .iter()
-                .map(|var| var.value().unwrap_or_default().to_string())
+                .map(|var| match var.value().unwrap_or_default() {
+                    value if value.is_zero() => "0".to_string(),
+                    value => value.to_string(),
+                })
                 .collect::<Vec<String>>();
             inputs.insert(key, json!(field_elements));
         }
@@ -52,6 +66,76 @@ impl NonameInputs {
     }
 }
 
+/// Argument of the `main` function of a noname circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonameArg {
+    pub name: String,
+    pub public: bool,
+    /// number of field elements of the argument
+    pub len: usize,
+}
+
+/// Parses the arguments of the `main` function of the noname `code`, which can be field elements
+/// or (nested) arrays of field elements of literal lengths.
+pub fn parse_main_args(code: &str) -> Result<Vec<NonameArg>, Error> {
+    let invalid = |reason: &str| Error::Other(format!("Invalid noname main function: {}", reason));
+    let start = code.find("fn main(").ok_or_else(|| invalid("not found"))? + "fn main(".len();
+
+    // split the arguments at the commas which are not inside the type of an array
+    let mut args = vec![];
+    let mut depth = 0;
+    let mut arg_start = start;
+    for (i, c) in code[start..].char_indices().map(|(i, c)| (start + i, c)) {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(&code[arg_start..i]);
+                arg_start = i + 1;
+            }
+            ')' if depth == 0 => {
+                args.push(&code[arg_start..i]);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    args.into_iter()
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| {
+            let (public, arg) = match arg.strip_prefix("pub ") {
+                Some(arg) => (true, arg),
+                None => (false, arg),
+            };
+            let (name, ty) = arg
+                .split_once(':')
+                .ok_or_else(|| invalid(&format!("argument '{}' has no type", arg)))?;
+            Ok(NonameArg {
+                name: name.trim().to_string(),
+                public,
+                len: type_len(ty.trim())?,
+            })
+        })
+        .collect()
+}
+
+/// Returns the number of field elements of the noname type `ty`.
+fn type_len(ty: &str) -> Result<usize, Error> {
+    if ty == "Field" {
+        return Ok(1);
+    }
+    let unsupported = || Error::NotSupportedYet(format!("noname arguments of type {}", ty));
+    let (elem_ty, len) = ty
+        .strip_prefix('[')
+        .and_then(|ty| ty.strip_suffix(']'))
+        .and_then(|ty| ty.rsplit_once(';'))
+        .ok_or_else(unsupported)?;
+    let len: usize = len.trim().parse().map_err(|_| unsupported())?;
+    Ok(type_len(elem_ty.trim())? * len)
+}
+
 // from: https://github.com/zksecurity/noname/blob/main/src/tests/modules.rs
 // TODO: this will not work in the case where we are using libraries
 pub fn compile_source_code<BF: BackendField>(
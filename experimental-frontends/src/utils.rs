@@ -3,7 +3,7 @@ use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
     fields::fp::FpVar,
 };
-use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
 use ark_std::fmt::Debug;
 use core::borrow::Borrow;
 
@@ -36,3 +36,53 @@ impl<F: PrimeField, const L: usize> Default for VecFpVar<F, L> {
         VecFpVar(vec![FpVar::<F>::Constant(F::zero()); L])
     }
 }
+
+/// In-circuit external inputs of a length known by the FCircuit but not by the folding scheme,
+/// which allocates them from `Vec::default()` when building the shape of the circuit. Allocating
+/// them then would lay out the variables differently than when proving a step, so the allocation
+/// is deferred to [`VecFpVarDyn::allocate`], which the FCircuit calls with their length.
+#[derive(Clone, Debug)]
+pub struct VecFpVarDyn<F: PrimeField> {
+    cs: ConstraintSystemRef<F>,
+    // `None` when the values are not available, eg. in setup mode
+    values: Option<Vec<F>>,
+    mode: AllocationMode,
+}
+impl<F: PrimeField> AllocVar<Vec<F>, F> for VecFpVarDyn<F> {
+    fn new_variable<T: Borrow<Vec<F>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        Ok(VecFpVarDyn {
+            cs: cs.into().cs(),
+            values: f().ok().map(|val| val.borrow().clone()),
+            mode,
+        })
+    }
+}
+impl<F: PrimeField> VecFpVarDyn<F> {
+    /// Allocates the `len` inputs. An empty vector of values, as the default external inputs,
+    /// is allocated as `len` zeros, while values of any other length than `len` are rejected.
+    pub fn allocate(self, len: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let values = match self.values {
+            Some(values) if values.is_empty() => Some(vec![F::zero(); len]),
+            Some(values) if values.len() != len => return Err(SynthesisError::Unsatisfiable),
+            values => values,
+        };
+        (0..len)
+            .map(|k| {
+                FpVar::new_variable(
+                    self.cs.clone(),
+                    || {
+                        values
+                            .as_ref()
+                            .map(|values| values[k])
+                            .ok_or(SynthesisError::AssignmentMissing)
+                    },
+                    self.mode,
+                )
+            })
+            .collect()
+    }
+}
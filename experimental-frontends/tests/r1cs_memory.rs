@@ -0,0 +1,108 @@
+//! Peak heap usage of the streaming `.r1cs` reader, compared with reading the whole file and
+//! parsing it with ark-circom, as the Circom frontend did before. The allocator of this test
+//! binary counts the allocated bytes, so it is kept out of the unit tests of the crate.
+
+use ark_bn254::Fr;
+use ark_circom::circom::r1cs_reader::{R1CSFile, R1CS};
+use ark_ff::{BigInteger, PrimeField};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use experimental_frontends::circom::r1cs::CircomR1cs;
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the peak of the bytes allocated by `f` on top of the ones allocated before calling it.
+fn peak_heap<T>(f: impl FnOnce() -> T) -> usize {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    drop(f());
+    PEAK.load(Ordering::Relaxed) - base
+}
+
+const LOG_N_CONSTRAINTS: u32 = 16;
+
+/// Writes a `.r1cs` file over BN254 whose `i`-th constraint is `(w_i + w_{i+1}) * w_i = w_{i+2}`.
+fn synthetic_r1cs(n_constraints: u32) -> Vec<u8> {
+    let n_wires = n_constraints + 2;
+    let mut constraints = vec![];
+    for i in 0..n_constraints {
+        for terms in [vec![i, i + 1], vec![i], vec![i + 2]] {
+            constraints.extend((terms.len() as u32).to_le_bytes());
+            for wire in terms {
+                constraints.extend(wire.to_le_bytes());
+                constraints.extend(Fr::from(1_u32).into_bigint().to_bytes_le());
+            }
+        }
+    }
+    let header = [
+        32u32.to_le_bytes().to_vec(),
+        Fr::MODULUS.to_bytes_le(),
+        n_wires.to_le_bytes().to_vec(),
+        1u32.to_le_bytes().to_vec(), // public outputs
+        1u32.to_le_bytes().to_vec(), // public inputs
+        0u32.to_le_bytes().to_vec(), // private inputs
+        (n_wires as u64).to_le_bytes().to_vec(),
+        n_constraints.to_le_bytes().to_vec(),
+    ]
+    .concat();
+    [
+        b"r1cs".to_vec(),
+        1u32.to_le_bytes().to_vec(),
+        2u32.to_le_bytes().to_vec(),
+        1u32.to_le_bytes().to_vec(),
+        (header.len() as u64).to_le_bytes().to_vec(),
+        header,
+        2u32.to_le_bytes().to_vec(),
+        (constraints.len() as u64).to_le_bytes().to_vec(),
+        constraints,
+    ]
+    .concat()
+}
+
+#[test]
+fn test_r1cs_streaming_peak_heap() {
+    let n_constraints = 1 << LOG_N_CONSTRAINTS;
+    let path = std::env::temp_dir().join(format!("sonobe_r1cs_memory_{}.r1cs", std::process::id()));
+    std::fs::write(&path, synthetic_r1cs(n_constraints)).unwrap();
+    let file_size = std::fs::metadata(&path).unwrap().len() as usize;
+
+    let streamed = peak_heap(|| CircomR1cs::<Fr>::read(&path.clone().into()).unwrap());
+    let eager = peak_heap(|| {
+        let bytes = std::fs::read(&path).unwrap();
+        let r1cs_file = R1CSFile::<Fr>::new(Cursor::new(&bytes)).unwrap();
+        (R1CS::from(r1cs_file), bytes)
+    });
+    std::fs::remove_file(&path).unwrap();
+
+    // payload of the matrices: 4 terms (a column and a value) per constraint, and the offsets of
+    // the rows of the 3 matrices
+    let matrices_size = n_constraints as usize * (4 * (4 + 32) + 3 * std::mem::size_of::<usize>());
+    println!(
+        "2^{} constraints, .r1cs file of {} bytes: peak heap of {} bytes when streaming, {} bytes \
+         when reading the file with ark-circom",
+        LOG_N_CONSTRAINTS, file_size, streamed, eager
+    );
+    // the file is never held in memory, and the matrices are grown by doubling their capacity
+    assert!(streamed < eager);
+    assert!(streamed <= 3 * matrices_size);
+}
@@ -0,0 +1,212 @@
+//! Procedural macros of `folding-schemes`, re-exported from its `frontend` module.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Expr, FnArg, GenericArgument, GenericParam, ItemFn, ItemStruct,
+    PathArguments, ReturnType, Type,
+};
+
+/// Defines an `FCircuit` from a step function, sparing the boilerplate of implementing the trait
+/// by hand.
+///
+/// ```ignore
+/// fcircuit! {
+///     /// Checks `x^3 + x + 5 = y`, with `z_i` as `x` and `z_{i+1}` as `y`.
+///     pub struct CubicFCircuit;
+///
+///     fn step<F: PrimeField>(
+///         cs: ConstraintSystemRef<F>,
+///         _i: usize,
+///         z: [FpVar<F>; 1],
+///         _ext: [FpVar<F>; 0],
+///     ) -> Result<[FpVar<F>; 1], SynthesisError> {
+///         let five = FpVar::new_constant(cs, F::from(5u32))?;
+///         Ok([&z[0] * &z[0] * &z[0] + &z[0] + five])
+///     }
+/// }
+/// ```
+///
+/// The struct, made generic over the field `F` of the step function, implements `FCircuit<F>`
+/// with `()` as `Params`, the length `N` of `z` as `state_len`, and `[F; M]` (whose `Default`
+/// requires `M <= 32`) and `[FpVar<F>; M]` as the external inputs, `M` being the length of `ext`.
+/// The step function becomes an associated function of the struct.
+#[proc_macro]
+pub fn fcircuit(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as FCircuitInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct FCircuitInput {
+    item_struct: ItemStruct,
+    step: ItemFn,
+}
+
+impl Parse for FCircuitInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            item_struct: input.parse()?,
+            step: input.parse()?,
+        })
+    }
+}
+
+fn expand(input: FCircuitInput) -> syn::Result<TokenStream2> {
+    let FCircuitInput { item_struct, step } = input;
+    if !matches!(item_struct.fields, syn::Fields::Unit) || !item_struct.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_struct,
+            "expected a unit struct, which is made generic over the field of the step function",
+        ));
+    }
+
+    let sig = &step.sig;
+    let mut type_params = sig.generics.params.iter().filter_map(|param| match param {
+        GenericParam::Type(param) => Some(&param.ident),
+        _ => None,
+    });
+    let (Some(f), None) = (type_params.next(), type_params.next()) else {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "expected the step function to be generic over the field only, as in `step<F: PrimeField>`",
+        ));
+    };
+
+    if sig.inputs.len() != 4 {
+        return Err(syn::Error::new(
+            sig.ident.span(),
+            format!(
+                "expected the 4 arguments `cs, i, z, ext` of the step function, found {}",
+                sig.inputs.len()
+            ),
+        ));
+    }
+    let arg_types = sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(arg) => Ok(&*arg.ty),
+            FnArg::Receiver(arg) => Err(syn::Error::new_spanned(
+                arg,
+                "the step function can not take `self`",
+            )),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let state_len = array_len(arg_types[2], "`z`")?;
+    let external_inputs_len = array_len(arg_types[3], "`ext`")?;
+
+    let ReturnType::Type(_, output) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "expected the step function to return `Result<[FpVar<F>; N], SynthesisError>`",
+        ));
+    };
+    let next_state_len = result_ok_type(output).and_then(|ty| array_len(ty, "the next state"))?;
+    if next_state_len.to_token_stream().to_string() != state_len.to_token_stream().to_string() {
+        return Err(syn::Error::new_spanned(
+            next_state_len,
+            format!(
+                "the next state must have the length `{}` of `z`",
+                state_len.to_token_stream()
+            ),
+        ));
+    }
+
+    let attrs = &item_struct.attrs;
+    let vis = &item_struct.vis;
+    let name = &item_struct.ident;
+    let (impl_generics, ty_generics, where_clause) = sig.generics.split_for_impl();
+    let step_ident = &sig.ident;
+    let step_fn = ItemFn {
+        sig: syn::Signature {
+            generics: Default::default(),
+            ..sig.clone()
+        },
+        ..step.clone()
+    };
+    let fp_var = quote!(::ark_r1cs_std::fields::fp::FpVar);
+    let synthesis_error = quote!(::ark_relations::r1cs::SynthesisError);
+
+    Ok(quote! {
+        #(#attrs)*
+        #[derive(Clone, Copy, Debug)]
+        #vis struct #name #impl_generics #where_clause {
+            _f: ::core::marker::PhantomData<#f>,
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #step_fn
+        }
+
+        impl #impl_generics ::folding_schemes::frontend::FCircuit<#f> for #name #ty_generics
+            #where_clause
+        {
+            type Params = ();
+            type ExternalInputs = [#f; #external_inputs_len];
+            type ExternalInputsVar = [#fp_var<#f>; #external_inputs_len];
+            type WitnessHint = ();
+
+            fn new(_params: Self::Params) -> ::core::result::Result<Self, ::folding_schemes::Error> {
+                Ok(Self {
+                    _f: ::core::marker::PhantomData,
+                })
+            }
+
+            fn state_len(&self) -> usize {
+                #state_len
+            }
+
+            fn generate_step_constraints(
+                &self,
+                cs: ::ark_relations::r1cs::ConstraintSystemRef<#f>,
+                i: usize,
+                z_i: Vec<#fp_var<#f>>,
+                external_inputs: Self::ExternalInputsVar,
+            ) -> ::core::result::Result<Vec<#fp_var<#f>>, #synthesis_error> {
+                let z_i: [#fp_var<#f>; #state_len] = z_i
+                    .try_into()
+                    .map_err(|_| #synthesis_error::Unsatisfiable)?;
+                Self::#step_ident(cs, i, z_i, external_inputs).map(Vec::from)
+            }
+        }
+    })
+}
+
+/// Returns the length of the array type `ty`, the type of `what`.
+fn array_len<'a>(ty: &'a Type, what: &str) -> syn::Result<&'a Expr> {
+    match ty {
+        Type::Array(array) => Ok(&array.len),
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            format!("expected {} to be an array `[FpVar<F>; N]`", what),
+        )),
+    }
+}
+
+/// Returns the type `T` of the type `Result<T, _>`.
+fn result_ok_type(ty: &Type) -> syn::Result<&Type> {
+    let Type::Path(path) = ty else {
+        return Err(result_error(ty));
+    };
+    let segment = path.path.segments.last().ok_or_else(|| result_error(ty))?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if segment.ident == "Result" => {
+            match args.args.first() {
+                Some(GenericArgument::Type(ty)) => Ok(ty),
+                _ => Err(result_error(ty)),
+            }
+        }
+        _ => Err(result_error(ty)),
+    }
+}
+
+fn result_error(ty: &Type) -> syn::Error {
+    syn::Error::new_spanned(
+        ty,
+        "expected the step function to return `Result<[FpVar<F>; N], SynthesisError>`",
+    )
+}
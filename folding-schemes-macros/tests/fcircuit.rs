@@ -0,0 +1,133 @@
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_ff::PrimeField;
+use ark_grumpkin::Projective as Projective2;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use ark_std::marker::PhantomData;
+
+use folding_schemes::{
+    commitment::pedersen::Pedersen,
+    folding::nova::{IVCProof, Nova, PreprocessorParam},
+    frontend::{fcircuit, FCircuit},
+    transcript::poseidon::poseidon_canonical_config,
+    Error, FoldingScheme,
+};
+
+fcircuit! {
+    /// Checks `x^3 + x + 5 = y`, with `z_i` as `x` and `z_{i+1}` as `y`.
+    pub struct MacroCubicFCircuit;
+
+    fn step<F: PrimeField>(
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z: [FpVar<F>; 1],
+        _ext: [FpVar<F>; 0],
+    ) -> Result<[FpVar<F>; 1], SynthesisError> {
+        let five = FpVar::<F>::new_constant(cs.clone(), F::from(5u32))?;
+        let z = &z[0];
+        Ok([z * z * z + z + &five])
+    }
+}
+
+fcircuit! {
+    /// Adds the external inputs and the step index to the state.
+    struct MacroAddFCircuit;
+
+    fn step<F: PrimeField>(
+        cs: ConstraintSystemRef<F>,
+        i: usize,
+        z: [FpVar<F>; 2],
+        ext: [FpVar<F>; 2],
+    ) -> Result<[FpVar<F>; 2], SynthesisError> {
+        let i = FpVar::<F>::new_witness(cs, || Ok(F::from(i as u64)))?;
+        Ok([&z[0] + &ext[0] + &i, &z[1] + &ext[1]])
+    }
+}
+
+/// Handwritten counterpart of `MacroCubicFCircuit`, as the `CubicFCircuit` of the tests of
+/// `folding-schemes`.
+#[derive(Clone, Copy, Debug)]
+pub struct CubicFCircuit<F: PrimeField> {
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> FCircuit<F> for CubicFCircuit<F> {
+    type Params = ();
+    type ExternalInputs = ();
+    type ExternalInputsVar = ();
+    type WitnessHint = ();
+
+    fn new(_params: Self::Params) -> Result<Self, Error> {
+        Ok(Self { _f: PhantomData })
+    }
+    fn state_len(&self) -> usize {
+        1
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        _external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let five = FpVar::<F>::new_constant(cs.clone(), F::from(5u32))?;
+        let z_i = z_i[0].clone();
+
+        Ok(vec![&z_i * &z_i * &z_i + &z_i + &five])
+    }
+}
+
+type N<FC> = Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+fn fold<FC: FCircuit<Fr, Params = ()>>(
+    external_inputs: Vec<FC::ExternalInputs>,
+) -> Result<IVCProof<Projective, Projective2>, Error> {
+    let mut rng = ark_std::test_rng();
+    let f_circuit = FC::new(())?;
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+    let params = N::<FC>::preprocess(&mut rng, &prep_param)?;
+    let z_0 = vec![Fr::from(3u32); f_circuit.state_len()];
+    let mut nova = N::<FC>::init(&params, f_circuit, z_0)?;
+    for inputs in external_inputs {
+        nova.prove_step(&mut rng, inputs, None)?;
+    }
+    N::<FC>::verify(params.1, nova.ivc_proof())?;
+    Ok(nova.ivc_proof())
+}
+
+#[test]
+fn test_macro_cubic_folds_as_handwritten() -> Result<(), Error> {
+    let macro_proof = fold::<MacroCubicFCircuit<Fr>>(vec![[]; 5])?;
+    let handwritten_proof = fold::<CubicFCircuit<Fr>>(vec![(); 5])?;
+    assert_eq!(macro_proof, handwritten_proof);
+    Ok(())
+}
+
+#[test]
+fn test_macro_external_inputs() -> Result<(), Error> {
+    let f_circuit = MacroAddFCircuit::<Fr>::new(())?;
+    assert_eq!(f_circuit.state_len(), 2);
+    assert_eq!(
+        <MacroAddFCircuit<Fr> as FCircuit<Fr>>::ExternalInputs::default(),
+        [Fr::from(0u32); 2]
+    );
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(1u32); 2]))?;
+    let ext = <[FpVar<Fr>; 2]>::new_witness(cs.clone(), || Ok([Fr::from(2u32), Fr::from(3u32)]))?;
+    let z_i1 = f_circuit.generate_step_constraints(cs.clone(), 4, z_i, ext)?;
+    assert_eq!(z_i1.value()?, vec![Fr::from(7u32), Fr::from(4u32)]);
+    assert!(cs.is_satisfied()?);
+
+    // the state must be of length `state_len`
+    let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(1u32); 3]))?;
+    let ext = <[FpVar<Fr>; 2]>::new_witness(cs.clone(), || Ok([Fr::from(0u32); 2]))?;
+    assert!(f_circuit
+        .generate_step_constraints(cs, 0, z_i, ext)
+        .is_err());
+
+    let proof = fold::<MacroAddFCircuit<Fr>>(vec![[Fr::from(1u32), Fr::from(2u32)]; 3])?;
+    // 3 + 3 * 1 + (0 + 1 + 2), 3 + 3 * 2
+    assert_eq!(proof.z_i, vec![Fr::from(9u32), Fr::from(9u32)]);
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use folding_schemes::frontend::fcircuit;
+
+fcircuit! {
+    pub struct WrongArityFCircuit;
+
+    fn step<F: ark_ff::PrimeField>(
+        cs: ark_relations::r1cs::ConstraintSystemRef<F>,
+        z: [ark_r1cs_std::fields::fp::FpVar<F>; 1],
+        ext: [ark_r1cs_std::fields::fp::FpVar<F>; 0],
+    ) -> Result<[ark_r1cs_std::fields::fp::FpVar<F>; 1], ark_relations::r1cs::SynthesisError> {
+        Ok(z)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use folding_schemes::frontend::fcircuit;
+
+fcircuit! {
+    pub struct WrongStateLenFCircuit;
+
+    fn step<F: ark_ff::PrimeField>(
+        cs: ark_relations::r1cs::ConstraintSystemRef<F>,
+        i: usize,
+        z: [ark_r1cs_std::fields::fp::FpVar<F>; 1],
+        ext: [ark_r1cs_std::fields::fp::FpVar<F>; 0],
+    ) -> Result<[ark_r1cs_std::fields::fp::FpVar<F>; 2], ark_relations::r1cs::SynthesisError> {
+        Ok([z[0].clone(), z[0].clone()])
+    }
+}
+
+fn main() {}
@@ -0,0 +1,356 @@
+//! Lookup arguments over the CCS vector `z`, following [LogUp](https://eprint.iacr.org/2022/1530).
+//!
+//! A [`Lookup`] claims that some tuples of entries of `z` are rows of a fixed [`LookupTable`]
+//! known at preprocess time. The prover sends the multiplicities `m_j` of the table rows
+//! ([`Lookup::prove`]), and the verifier checks the LogUp identity
+//! `\sum_i 1/(alpha - v_i) = \sum_j m_j/(alpha - t_j)` ([`Lookup::verify`]), where `v_i` and
+//! `t_j` are the claimed tuples and the table rows compressed with `beta`. The challenges `alpha`
+//! and `beta` are derived after absorbing the claimed tuples and the multiplicities, and the
+//! verifier computes the table side of the identity from its own copy of the table, so a claimed
+//! tuple which is not a row of the table makes the check fail for any multiplicities, except with
+//! probability `O((n_claims + n_rows) / |F|)`.
+//!
+//! Notice that the claims are checked on a (non-folded) `z`: they are not part of the CCS
+//! relation, nor of HyperNova's multifolding. So they do not reduce the number of constraints of
+//! a folded circuit, which still has to enforce by itself the relations that it looks up.
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::{batch_inversion, PrimeField};
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystem, SynthesisError, Variable};
+use ark_std::{collections::HashMap, Zero};
+
+use crate::transcript::Transcript;
+use crate::Error;
+
+/// A fixed table, where all the rows have the same width.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LookupTable<F: PrimeField> {
+    pub rows: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> LookupTable<F> {
+    pub fn new(rows: Vec<Vec<F>>) -> Result<Self, Error> {
+        let width = rows.first().ok_or(Error::Empty)?.len();
+        if width == 0 {
+            return Err(Error::Empty);
+        }
+        if let Some(row) = rows.iter().find(|row| row.len() != width) {
+            return Err(Error::NotExpectedLength(row.len(), width));
+        }
+        Ok(Self { rows })
+    }
+
+    /// returns the number of columns of the table
+    pub fn width(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    /// returns the table of the 8-bit XOR, whose rows are `(a, b, a ^ b)`
+    pub fn xor8() -> Self {
+        let rows = (0..1u32 << 16)
+            .map(|ab| {
+                let (a, b) = (ab & 0xff, ab >> 8);
+                vec![F::from(a), F::from(b), F::from(a ^ b)]
+            })
+            .collect();
+        Self { rows }
+    }
+}
+
+/// Compresses a tuple into a single field element as `\sum_k beta^k * row_k`.
+fn compress<F: PrimeField>(row: &[F], beta: F) -> F {
+    row.iter().rev().fold(F::zero(), |acc, v| acc * beta + v)
+}
+
+/// Lookup claims over a CCS: for each claim, the entries of `z` at the given indices must be a
+/// row of `table`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Lookup<F: PrimeField> {
+    pub table: LookupTable<F>,
+    pub claims: Vec<Vec<usize>>,
+}
+
+impl<F: PrimeField> Lookup<F> {
+    pub fn new(table: LookupTable<F>, claims: Vec<Vec<usize>>) -> Result<Self, Error> {
+        if let Some(claim) = claims.iter().find(|claim| claim.len() != table.width()) {
+            return Err(Error::NotExpectedLength(claim.len(), table.width()));
+        }
+        Ok(Self { table, claims })
+    }
+
+    /// returns the tuples of `z` claimed to be in the table
+    fn claimed_rows(&self, z: &[F]) -> Result<Vec<Vec<F>>, Error> {
+        self.claims
+            .iter()
+            .map(|claim| {
+                claim
+                    .iter()
+                    .map(|&i| z.get(i).copied().ok_or(Error::OutOfBounds))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Computes the multiplicities `m_j` of the table rows in the claimed tuples of `z`. Returns
+    /// `Error::NotSatisfied` if some claimed tuple is not a row of the table.
+    pub fn multiplicities(&self, z: &[F]) -> Result<Vec<F>, Error> {
+        let positions: HashMap<&[F], usize> = self
+            .table
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(j, row)| (row.as_slice(), j))
+            .collect();
+        let mut m = vec![0u64; self.table.rows.len()];
+        for row in self.claimed_rows(z)? {
+            let j = positions.get(row.as_slice()).ok_or(Error::NotSatisfied)?;
+            m[*j] += 1;
+        }
+        Ok(m.into_iter().map(F::from).collect())
+    }
+
+    /// Evaluates the LogUp identity `\sum_i 1/(alpha - v_i) - \sum_j m_j/(alpha - t_j)` at the
+    /// given challenges, which is zero when the claims are satisfied.
+    pub fn eval_logup(&self, z: &[F], m: &[F], alpha: F, beta: F) -> Result<F, Error> {
+        if m.len() != self.table.rows.len() {
+            return Err(Error::NotSameLength(
+                "m.len()".to_string(),
+                m.len(),
+                "table.rows.len()".to_string(),
+                self.table.rows.len(),
+            ));
+        }
+        let mut claimed: Vec<F> = self
+            .claimed_rows(z)?
+            .iter()
+            .map(|row| alpha - compress(row, beta))
+            .collect();
+        let mut table: Vec<F> = self
+            .table
+            .rows
+            .iter()
+            .map(|row| alpha - compress(row, beta))
+            .collect();
+        if claimed.iter().chain(table.iter()).any(|d| d.is_zero()) {
+            return Err(Error::NotSatisfied);
+        }
+        batch_inversion(&mut claimed);
+        batch_inversion(&mut table);
+
+        let lhs: F = claimed.iter().sum();
+        let rhs: F = table.iter().zip(m).map(|(t, m)| *t * m).sum();
+        Ok(lhs - rhs)
+    }
+}
+
+/// Proof of the lookup claims over a `z` vector, which consists of the multiplicities of the
+/// table rows.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LookupProof<F: PrimeField> {
+    pub m: Vec<F>,
+}
+
+impl<F: PrimeField + Absorb> Lookup<F> {
+    // absorbs the claimed tuples of `z` and the multiplicities, and returns the challenges
+    // `(alpha, beta)` of the LogUp identity
+    fn challenges<T: Transcript<F>>(
+        &self,
+        transcript: &mut T,
+        z: &[F],
+        m: &[F],
+    ) -> Result<(F, F), Error> {
+        transcript.absorb(&self.claimed_rows(z)?.concat());
+        transcript.absorb(&m.to_vec());
+        let beta = transcript.get_challenge();
+        let alpha = transcript.get_challenge();
+        Ok((alpha, beta))
+    }
+
+    /// Proves the lookup claims over `z`. Returns `Error::NotSatisfied` if some claimed tuple is
+    /// not a row of the table.
+    pub fn prove<T: Transcript<F>>(
+        &self,
+        transcript: &mut T,
+        z: &[F],
+    ) -> Result<LookupProof<F>, Error> {
+        let m = self.multiplicities(z)?;
+        self.challenges(transcript, z, &m)?;
+        Ok(LookupProof { m })
+    }
+
+    /// Verifies the lookup claims over `z` with the multiplicities given by the prover, checking
+    /// the LogUp identity at the challenges derived from the transcript.
+    pub fn verify<T: Transcript<F>>(
+        &self,
+        transcript: &mut T,
+        z: &[F],
+        proof: &LookupProof<F>,
+    ) -> Result<(), Error> {
+        let (alpha, beta) = self.challenges(transcript, z, &proof.m)?;
+        self.eval_logup(z, &proof.m, alpha, beta)?
+            .is_zero()
+            .then_some(())
+            .ok_or(Error::NotSatisfied)
+    }
+}
+
+/// Collects the lookup claims made by a circuit while generating its constraints, so that they
+/// can be turned into a [`Lookup`] over the `z` vector of the resulting CCS.
+#[derive(Debug, Clone, Default)]
+pub struct LookupClaims {
+    claims: Vec<Vec<Variable>>,
+}
+
+impl LookupClaims {
+    /// Claims that the given (allocated) variables form a row of the table.
+    pub fn claim<F: PrimeField>(&mut self, row: &[FpVar<F>]) -> Result<(), SynthesisError> {
+        let row = row
+            .iter()
+            .map(|v| match v {
+                FpVar::Var(v) => Ok(v.variable),
+                // constants are not part of `z`
+                FpVar::Constant(_) => Err(SynthesisError::AssignmentMissing),
+            })
+            .collect::<Result<_, _>>()?;
+        self.claims.push(row);
+        Ok(())
+    }
+
+    /// Returns the [`Lookup`] of the claims, where the variables are mapped to their position in
+    /// the `z = [1, x, w]` vector of `cs`. It must be called once all the public inputs of `cs`
+    /// have been allocated.
+    pub fn into_lookup<F: PrimeField>(
+        self,
+        table: LookupTable<F>,
+        cs: &ConstraintSystem<F>,
+    ) -> Result<Lookup<F>, Error> {
+        let claims = self
+            .claims
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|v| match v {
+                        Variable::Instance(i) => Ok(i),
+                        Variable::Witness(i) => Ok(cs.num_instance_variables + i),
+                        _ => Err(Error::OutOfBounds),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, Error>>()?;
+        Lookup::new(table, claims)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_relations::r1cs::ConstraintSystemRef;
+    use ark_std::{rand::RngCore, test_rng};
+
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+
+    const N_XORS: usize = 32;
+
+    fn z_of(cs: ConstraintSystemRef<Fr>) -> (ConstraintSystem<Fr>, Vec<Fr>) {
+        let cs = cs.into_inner().unwrap();
+        let z = [
+            cs.instance_assignment.clone(),
+            cs.witness_assignment.clone(),
+        ]
+        .concat();
+        (cs, z)
+    }
+
+    /// proves and verifies 8-bit XORs claimed by a circuit through the XOR table
+    #[test]
+    fn test_xor8_lookup() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut lookup_claims = LookupClaims::default();
+        for _ in 0..N_XORS {
+            let (a, b) = (rng.next_u32() as u8, rng.next_u32() as u8);
+            let row = [a, b, a ^ b]
+                .iter()
+                .map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))))
+                .collect::<Result<Vec<_>, _>>()?;
+            lookup_claims.claim(&row)?;
+        }
+        let (cs, mut z) = z_of(cs);
+        let lookup = lookup_claims.into_lookup(LookupTable::xor8(), &cs)?;
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let proof = lookup.prove(&mut PoseidonSponge::<Fr>::new(&poseidon_config), &z)?;
+        lookup.verify(&mut PoseidonSponge::<Fr>::new(&poseidon_config), &z, &proof)?;
+
+        // a wrong XOR output is not a row of the table, so the proof does not verify anymore,
+        // and the prover can not compute the multiplicities
+        let c_index = lookup.claims[0][2];
+        z[c_index] += Fr::from(1u64);
+        assert!(matches!(
+            lookup.verify(&mut PoseidonSponge::<Fr>::new(&poseidon_config), &z, &proof),
+            Err(Error::NotSatisfied)
+        ));
+        assert!(matches!(
+            lookup.prove(&mut PoseidonSponge::<Fr>::new(&poseidon_config), &z),
+            Err(Error::NotSatisfied)
+        ));
+        Ok(())
+    }
+
+    /// a value outside of the table is rejected whatever the multiplicities given by the prover
+    #[test]
+    fn test_logup_soundness() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let table = LookupTable::new((0..16u32).map(|v| vec![Fr::from(v)]).collect())?;
+        let lookup = Lookup::new(table, vec![vec![1], vec![2], vec![3]])?;
+
+        let z: Vec<Fr> = [1u32, 3, 3, 7].iter().map(|v| Fr::from(*v)).collect();
+        let proof = lookup.prove(&mut PoseidonSponge::<Fr>::new(&poseidon_config), &z)?;
+        assert_eq!(proof.m[3], Fr::from(2u64));
+        assert_eq!(proof.m[7], Fr::from(1u64));
+        lookup.verify(&mut PoseidonSponge::<Fr>::new(&poseidon_config), &z, &proof)?;
+
+        // 16 is not in the table. The prover tries the multiplicities of the honest claims, of
+        // the claims with 16 replaced by each of the rows of the table, and the multiplicities
+        // where 16 is not counted at all
+        let bad_z: Vec<Fr> = [1u32, 3, 3, 16].iter().map(|v| Fr::from(*v)).collect();
+        let mut candidates = vec![proof.m.clone()];
+        for j in 0..16 {
+            let mut m = proof.m.clone();
+            m[7] = Fr::zero();
+            m[j] += Fr::from(1u64);
+            candidates.push(m);
+        }
+        let mut m = proof.m.clone();
+        m[7] = Fr::zero();
+        candidates.push(m);
+        for m in candidates {
+            assert!(matches!(
+                lookup.verify(
+                    &mut PoseidonSponge::<Fr>::new(&poseidon_config),
+                    &bad_z,
+                    &LookupProof { m }
+                ),
+                Err(Error::NotSatisfied)
+            ));
+        }
+
+        // wrong multiplicities for valid claims are rejected too
+        let mut wrong_m = proof.m.clone();
+        wrong_m.swap(3, 4);
+        assert!(lookup
+            .verify(
+                &mut PoseidonSponge::<Fr>::new(&poseidon_config),
+                &z,
+                &LookupProof { m: wrong_m }
+            )
+            .is_err());
+
+        // the claims must match the width of the table
+        assert!(Lookup::new(LookupTable::<Fr>::xor8(), vec![vec![1, 2]]).is_err());
+        Ok(())
+    }
+}
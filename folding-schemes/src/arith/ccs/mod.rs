@@ -1,15 +1,17 @@
-use ark_ff::PrimeField;
+use ark_ff::{BigInteger, PrimeField};
 use ark_std::log2;
+use sha3::{Digest, Sha3_256};
 
 use crate::utils::vec::{
     hadamard, is_zero_vec, mat_vec_mul, vec_add, vec_scalar_mul, SparseMatrix,
 };
 use crate::Error;
 
-use super::{r1cs::R1CS, ArithRelation};
+use super::{r1cs::R1CS, ArithError, ArithRelation};
 use super::{Arith, ArithSerializer};
 
 pub mod circuits;
+pub mod lookup;
 
 /// CCS represents the Customizable Constraint Systems structure defined in
 /// the [CCS paper](https://eprint.iacr.org/2023/552)
@@ -39,6 +41,38 @@ pub struct CCS<F: PrimeField> {
 }
 
 impl<F: PrimeField> CCS<F> {
+    /// Builds the CCS of the given R1CS, which has the same `m` constraints and `n` variables,
+    /// `t = 3` matrices `(A, B, C)`, `q = 2` multisets `{0, 1}` and `{2}` with coefficients
+    /// `(1, -1)`, and degree `d = 2`, so that its relation is `Az ∘ Bz - Cz = 0`.
+    pub fn from_r1cs(r1cs: &R1CS<F>) -> Self {
+        r1cs.clone().into()
+    }
+
+    /// returns the number of multisets `q`
+    pub fn n_multisets(&self) -> usize {
+        self.q
+    }
+
+    /// returns the sparse matrices `M_j`
+    pub fn matrices(&self) -> &[SparseMatrix<F>] {
+        &self.M
+    }
+
+    /// Checks that the given vector of assignments `z = (1, x, w)` satisfies the CCS relation
+    pub fn check_z(&self, z: &[F]) -> Result<(), Error> {
+        if z.len() != self.n {
+            return Err(Error::NotSameLength(
+                "z.len()".to_string(),
+                z.len(),
+                "ccs.n".to_string(),
+                self.n,
+            ));
+        }
+        is_zero_vec(&self.eval_at_z(z)?)
+            .then_some(())
+            .ok_or(Error::NotSatisfied)
+    }
+
     /// Evaluates the CCS relation at a given vector of assignments `z`
     pub fn eval_at_z(&self, z: &[F]) -> Result<Vec<F>, Error> {
         let mut result = vec![F::zero(); self.m];
@@ -62,6 +96,47 @@ impl<F: PrimeField> CCS<F> {
 
         Ok(result)
     }
+
+    /// Checks that the witness `w` and the public inputs `x` satisfy the CCS relation
+    /// `Σ_i c_i ∘_{j ∈ S_i} M_j z = 0`, where `z = [1, x, w]`.
+    ///
+    /// Unlike [`ArithRelation::check_relation`], the error reports the violated constraints: their
+    /// number, and the index and the value of the left-hand side of the first
+    /// [`super::MAX_REPORTED_VIOLATIONS`] ones.
+    pub fn check_relation_detailed(&self, w: &[F], x: &[F]) -> Result<(), ArithError<F>> {
+        if x.len() != self.l {
+            return Err(ArithError::WrongLength("x".into(), x.len(), self.l));
+        }
+        if w.len() != self.n_witnesses() {
+            return Err(ArithError::WrongLength(
+                "w".into(),
+                w.len(),
+                self.n_witnesses(),
+            ));
+        }
+
+        let z = [&[F::one()], x, w].concat();
+        let eval_row = |M: &SparseMatrix<F>, i| -> F {
+            M.row(i)
+                .iter()
+                .map(|(value, col_i)| *value * z[*col_i])
+                .sum()
+        };
+        ArithError::check_violations(
+            self.m,
+            (0..self.m).filter_map(|i| {
+                let left = self
+                    .S
+                    .iter()
+                    .zip(&self.c)
+                    .map(|(S_i, c_i)| {
+                        *c_i * S_i.iter().map(|j| eval_row(&self.M[*j], i)).product::<F>()
+                    })
+                    .sum::<F>();
+                (!left.is_zero()).then_some((i, left, F::zero()))
+            }),
+        )
+    }
 }
 
 impl<F: PrimeField> Arith for CCS<F> {
@@ -109,15 +184,30 @@ impl<F: PrimeField, W: AsRef<[F]>, U: AsRef<[F]>> ArithRelation<W, U> for CCS<F>
 
 impl<F: PrimeField> ArithSerializer for CCS<F> {
     fn params_to_le_bytes(&self) -> Vec<u8> {
-        [
-            self.l.to_le_bytes(),
-            self.m.to_le_bytes(),
-            self.n.to_le_bytes(),
-            self.t.to_le_bytes(),
-            self.q.to_le_bytes(),
-            self.d.to_le_bytes(),
-        ]
-        .concat()
+        [self.l, self.m, self.n, self.t, self.q, self.d]
+            .iter()
+            .flat_map(|x| (*x as u64).to_le_bytes())
+            .collect()
+    }
+
+    /// Digest of the matrices `M_j` (see [`SparseMatrix::update_hasher`]), followed by the
+    /// multisets `S_i`, each one as its length and its elements, as little-endian `u64`s, and by
+    /// the canonical little-endian representations of the coefficients `c_i`.
+    fn matrices_digest(&self) -> [u8; 32] {
+        let mut hasher = Sha3_256::new();
+        for M_j in &self.M {
+            M_j.update_hasher(&mut hasher);
+        }
+        for S_i in &self.S {
+            hasher.update((S_i.len() as u64).to_le_bytes());
+            for j in S_i {
+                hasher.update((*j as u64).to_le_bytes());
+            }
+        }
+        for c_i in &self.c {
+            hasher.update(c_i.into_bigint().to_bytes_le());
+        }
+        hasher.finalize().into()
     }
 }
 
@@ -145,10 +235,14 @@ impl<F: PrimeField> From<R1CS<F>> for CCS<F> {
 pub mod tests {
     use super::*;
     use crate::{
-        arith::r1cs::tests::{get_test_r1cs, get_test_z as r1cs_get_test_z, get_test_z_split},
+        arith::{
+            r1cs::tests::{get_test_r1cs, get_test_z as r1cs_get_test_z, get_test_z_split},
+            ViolatedConstraint,
+        },
         utils::vec::is_zero_vec,
     };
     use ark_pallas::Fr;
+    use ark_std::{rand::RngCore, One, UniformRand, Zero};
 
     pub fn get_test_ccs<F: PrimeField>() -> CCS<F> {
         get_test_r1cs::<F>().into()
@@ -180,4 +274,106 @@ pub mod tests {
         ccs.check_relation(&w, &x)?;
         Ok(())
     }
+    #[test]
+    fn test_check_ccs_relation_detailed() -> Result<(), Error> {
+        let ccs = get_test_ccs::<Fr>();
+        let (_, x, w) = get_test_z_split::<Fr>(3);
+        ccs.check_relation_detailed(&w, &x)?;
+
+        // x^2 appears in the first two rows: x * x = x^2 and x^2 * x = x^3
+        let mut w_0 = w.clone();
+        w_0[1] += Fr::one();
+        assert_eq!(
+            ccs.check_relation_detailed(&w_0, &x),
+            Err(ArithError::Unsatisfied {
+                n_constraints: 4,
+                n_violated: 2,
+                first: vec![
+                    ViolatedConstraint {
+                        index: 0,
+                        left: -Fr::one(),
+                        right: Fr::zero(),
+                    },
+                    ViolatedConstraint {
+                        index: 1,
+                        left: Fr::from(3_u32),
+                        right: Fr::zero(),
+                    },
+                ],
+            })
+        );
+
+        // y only appears in the last row: (x^3 + x + 5) * 1 = y
+        let mut w_y = w.clone();
+        w_y[0] += Fr::one();
+        assert_eq!(
+            ccs.check_relation_detailed(&w_y, &x),
+            Err(ArithError::Unsatisfied {
+                n_constraints: 4,
+                n_violated: 1,
+                first: vec![ViolatedConstraint {
+                    index: 3,
+                    left: -Fr::one(),
+                    right: Fr::zero(),
+                }],
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_r1cs_params() {
+        let r1cs = get_test_r1cs::<Fr>();
+        let ccs = CCS::from_r1cs(&r1cs);
+        assert_eq!(ccs.n_constraints(), r1cs.n_constraints());
+        assert_eq!(ccs.n_variables(), r1cs.n_variables());
+        assert_eq!(ccs.n_public_inputs(), r1cs.n_public_inputs());
+        assert_eq!(ccs.t, 3);
+        assert_eq!(ccs.n_multisets(), 2);
+        assert_eq!(ccs.degree(), 2);
+        assert_eq!(ccs.matrices(), &[r1cs.A, r1cs.B, r1cs.C]);
+    }
+
+    /// checks over random assignments that the CCS of an R1CS is satisfied exactly when the R1CS
+    /// is satisfied
+    #[test]
+    fn test_from_r1cs_satisfiability() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let r1cs = get_test_r1cs::<Fr>();
+        let ccs = CCS::from_r1cs(&r1cs);
+        for _ in 0..32 {
+            // satisfying witnesses of the test circuit
+            let mut z = get_test_z::<Fr>((rng.next_u32() % 1000) as usize);
+            assert!(is_zero_vec(&r1cs.eval_at_z(&z)?));
+            assert_eq!(ccs.eval_at_z(&z)?, vec![Fr::zero(); ccs.n_constraints()]);
+            assert!(ccs.check_z(&z).is_ok());
+
+            // a random change on the witness makes both unsatisfied, at the same constraints
+            let i = 1 + r1cs.n_public_inputs() + (rng.next_u32() as usize) % r1cs.n_witnesses();
+            z[i] += Fr::rand(&mut rng);
+            assert!(!is_zero_vec(&r1cs.eval_at_z(&z)?));
+            assert_eq!(ccs.eval_at_z(&z)?, r1cs.eval_at_z(&z)?);
+            assert!(matches!(ccs.check_z(&z), Err(Error::NotSatisfied)));
+        }
+
+        // over random R1CS and assignments, both relations evaluate to the same vector
+        for _ in 0..32 {
+            let r1cs = R1CS::<Fr>::rand(&mut rng, 8, 16);
+            let ccs = CCS::from_r1cs(&r1cs);
+            let z: Vec<Fr> = [
+                vec![Fr::one()],
+                (1..16).map(|_| Fr::rand(&mut rng)).collect(),
+            ]
+            .concat();
+            assert_eq!(ccs.eval_at_z(&z)?, r1cs.eval_at_z(&z)?);
+            assert_eq!(is_zero_vec(&r1cs.eval_at_z(&z)?), ccs.check_z(&z).is_ok());
+        }
+
+        // z must have length n
+        assert!(matches!(
+            ccs.check_z(&[Fr::one()]),
+            Err(Error::NotSameLength(..))
+        ));
+        Ok(())
+    }
 }
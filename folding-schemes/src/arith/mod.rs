@@ -7,6 +7,76 @@ use crate::{commitment::CommitmentScheme, folding::traits::Dummy, Curve, Error};
 pub mod ccs;
 pub mod r1cs;
 
+/// The maximum number of violated constraints listed in [`ArithError::Unsatisfied`].
+pub const MAX_REPORTED_VIOLATIONS: usize = 8;
+
+/// A constraint that is not satisfied, with the values of both sides of its equation (e.g.,
+/// `(Az)_i * (Bz)_i` and `u * (Cz)_i + E_i` for the relaxed R1CS).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViolatedConstraint<F: PrimeField> {
+    pub index: usize,
+    pub left: F,
+    pub right: F,
+}
+
+impl<F: PrimeField> ark_std::fmt::Display for ViolatedConstraint<F> {
+    fn fmt(&self, f: &mut ark_std::fmt::Formatter<'_>) -> ark_std::fmt::Result {
+        write!(f, "#{}: {} != {}", self.index, self.left, self.right)
+    }
+}
+
+/// Errors of the detailed satisfiability checks of the constraint systems, such as
+/// [`r1cs::R1CS::check_relaxed_relation`] and [`ccs::CCS::check_relation_detailed`]. They are
+/// reported through [`Error::Arith`], from which they can be recovered by downcasting them.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ArithError<F: PrimeField> {
+    #[error("{0} has {1} elements, but the constraint system expects {2}")]
+    WrongLength(String, usize, usize),
+    #[error(
+        "{n_violated} of the {n_constraints} constraints are not satisfied, the first ones being: {}",
+        .first.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    Unsatisfied {
+        n_constraints: usize,
+        n_violated: usize,
+        /// the first (at most [`MAX_REPORTED_VIOLATIONS`]) violated constraints, by index
+        first: Vec<ViolatedConstraint<F>>,
+    },
+}
+
+impl<F: PrimeField> From<ArithError<F>> for Error {
+    fn from(err: ArithError<F>) -> Self {
+        Error::Arith(Box::new(err))
+    }
+}
+
+impl<F: PrimeField> ArithError<F> {
+    /// Returns `Ok(())` if there is no violated constraint, and [`ArithError::Unsatisfied`]
+    /// reporting them otherwise. `violations` yields the index and both sides of the violated
+    /// constraints in increasing order of index.
+    pub(crate) fn check_violations(
+        n_constraints: usize,
+        violations: impl Iterator<Item = (usize, F, F)>,
+    ) -> Result<(), Self> {
+        let mut n_violated = 0;
+        let mut first = vec![];
+        for (index, left, right) in violations {
+            if first.len() < MAX_REPORTED_VIOLATIONS {
+                first.push(ViolatedConstraint { index, left, right });
+            }
+            n_violated += 1;
+        }
+        if n_violated == 0 {
+            return Ok(());
+        }
+        Err(Self::Unsatisfied {
+            n_constraints,
+            n_violated,
+            first,
+        })
+    }
+}
+
 /// [`Arith`] is a trait about constraint systems (R1CS, CCS, etc.), where we
 /// define methods for getting information about the constraint system.
 pub trait Arith: Clone {
@@ -120,15 +190,17 @@ pub trait ArithRelation<W, U>: Arith {
     }
 }
 
-/// `ArithSerializer` is for serializing constraint systems.
-///
-/// Currently we only support converting parameters to bytes, but in the future
-/// we may consider implementing methods for serializing the actual data (e.g.,
-/// R1CS matrices).
+/// `ArithSerializer` is for serializing constraint systems, as done to compute the hash of the
+/// public parameters of the folding schemes (see [`crate::utils::pp_hash`]).
 pub trait ArithSerializer {
     /// Returns the bytes that represent the parameters, that is, the matrices sizes, the amount of
-    /// public inputs, etc, without the matrices/polynomials values.
+    /// public inputs, etc, each one as a little-endian `u64`, without the matrices/polynomials
+    /// values.
     fn params_to_le_bytes(&self) -> Vec<u8>;
+
+    /// Returns the SHA3-256 digest of the matrices/polynomials values, which binds the constraints
+    /// themselves and not only their sizes.
+    fn matrices_digest(&self) -> [u8; 32];
 }
 
 /// `ArithSampler` allows sampling random pairs of witness and instance that
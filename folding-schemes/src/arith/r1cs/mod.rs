@@ -1,10 +1,11 @@
 use ark_ff::PrimeField;
-use ark_relations::r1cs::ConstraintSystem;
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::Rng;
+use sha3::{Digest, Sha3_256};
 
 use super::ccs::CCS;
-use super::{Arith, ArithRelation, ArithSerializer};
+use super::{Arith, ArithError, ArithRelation, ArithSerializer};
 use crate::folding::traits::Dummy;
 use crate::utils::vec::{
     hadamard, is_zero_vec, mat_vec_mul, vec_scalar_mul, vec_sub, SparseMatrix,
@@ -42,6 +43,55 @@ impl<F: PrimeField> R1CS<F> {
         let AzBz = hadamard(&Az, &Bz)?;
         vec_sub(&AzBz, &uCz)
     }
+
+    /// Checks that the witness `w` and the public inputs `x` satisfy the relaxed R1CS relation
+    /// `Az ∘ Bz = uCz + E`, where `z = [u, x, w]`. The plain R1CS relation is checked with `u = 1`
+    /// and `E = 0`.
+    ///
+    /// Unlike [`ArithRelation::check_relation`], the error reports the violated constraints: their
+    /// number, and the index and the values `(Az)_i * (Bz)_i` and `u * (Cz)_i + E_i` of the first
+    /// [`super::MAX_REPORTED_VIOLATIONS`] ones.
+    pub fn check_relaxed_relation(
+        &self,
+        w: &[F],
+        x: &[F],
+        u: F,
+        E: &[F],
+    ) -> Result<(), ArithError<F>> {
+        if x.len() != self.l {
+            return Err(ArithError::WrongLength("x".into(), x.len(), self.l));
+        }
+        if w.len() != self.n_witnesses() {
+            return Err(ArithError::WrongLength(
+                "w".into(),
+                w.len(),
+                self.n_witnesses(),
+            ));
+        }
+        if E.len() != self.n_constraints() {
+            return Err(ArithError::WrongLength(
+                "E".into(),
+                E.len(),
+                self.n_constraints(),
+            ));
+        }
+
+        let z = [&[u], x, w].concat();
+        let eval_row = |M: &SparseMatrix<F>, i| -> F {
+            M.row(i)
+                .iter()
+                .map(|(value, col_i)| *value * z[*col_i])
+                .sum()
+        };
+        ArithError::check_violations(
+            self.n_constraints(),
+            (0..self.n_constraints()).filter_map(|i| {
+                let left = eval_row(&self.A, i) * eval_row(&self.B, i);
+                let right = u * eval_row(&self.C, i) + E[i];
+                (left != right).then_some((i, left, right))
+            }),
+        )
+    }
 }
 
 impl<F: PrimeField> Arith for R1CS<F> {
@@ -89,12 +139,19 @@ impl<F: PrimeField, W: AsRef<[F]>, U: AsRef<[F]>> ArithRelation<W, U> for R1CS<F
 
 impl<F: PrimeField> ArithSerializer for R1CS<F> {
     fn params_to_le_bytes(&self) -> Vec<u8> {
-        [
-            self.l.to_le_bytes(),
-            self.A.n_rows.to_le_bytes(),
-            self.A.n_cols.to_le_bytes(),
-        ]
-        .concat()
+        [self.l, self.A.n_rows, self.A.n_cols]
+            .iter()
+            .flat_map(|x| (*x as u64).to_le_bytes())
+            .collect()
+    }
+
+    /// Digest of the matrices `A`, `B` and `C`, in this order, see [`SparseMatrix::update_hasher`].
+    fn matrices_digest(&self) -> [u8; 32] {
+        let mut hasher = Sha3_256::new();
+        for M in [&self.A, &self.B, &self.C] {
+            M.update_hasher(&mut hasher);
+        }
+        hasher.finalize().into()
     }
 }
 
@@ -145,24 +202,11 @@ pub fn extract_r1cs<F: PrimeField>(cs: &ConstraintSystem<F>) -> Result<R1CS<F>,
         )
     })?;
 
-    let n_rows = cs.num_constraints;
     let n_cols = cs.num_instance_variables + cs.num_witness_variables; // cs.num_instance_variables already counts the 1
 
-    let A = SparseMatrix::<F> {
-        n_rows,
-        n_cols,
-        coeffs: m.a,
-    };
-    let B = SparseMatrix::<F> {
-        n_rows,
-        n_cols,
-        coeffs: m.b,
-    };
-    let C = SparseMatrix::<F> {
-        n_rows,
-        n_cols,
-        coeffs: m.c,
-    };
+    let A = SparseMatrix::<F>::from_rows(n_cols, m.a);
+    let B = SparseMatrix::<F>::from_rows(n_cols, m.b);
+    let C = SparseMatrix::<F>::from_rows(n_cols, m.c);
 
     Ok(R1CS::<F> {
         l: cs.num_instance_variables - 1, // -1 to subtract the first '1'
@@ -181,15 +225,55 @@ pub fn extract_w_x<F: PrimeField>(cs: &ConstraintSystem<F>) -> (Vec<F>, Vec<F>)
     )
 }
 
+/// checks that the given (not finalized) ConstraintSystem `cs`, which has been synthesized from
+/// the circuit whose constraints are described by `r1cs`, is satisfied. Otherwise returns
+/// [`Error::UnsatisfiedConstraint`] containing the given `step` together with the index and the
+/// name of the first unsatisfied constraint.
+///
+/// The name is obtained from `ConstraintSystemRef::which_is_unsatisfied`, which returns the trace
+/// of the namespaces where the constraint was created when a `ark_relations::r1cs::ConstraintLayer`
+/// is set as tracing subscriber, and the index of the constraint otherwise.
+pub fn check_cs_satisfied<F: PrimeField>(
+    r1cs: &R1CS<F>,
+    cs: &ConstraintSystemRef<F>,
+    step: usize,
+) -> Result<(), Error> {
+    if cs.is_satisfied()? {
+        return Ok(());
+    }
+    let name = cs.which_is_unsatisfied()?.unwrap_or_default();
+    let z = {
+        let cs = cs.borrow().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w, x) = extract_w_x(&cs);
+        [vec![F::one()], x, w].concat()
+    };
+    let index = r1cs
+        .eval_at_z(&z)?
+        .iter()
+        .position(|e| !e.is_zero())
+        .ok_or(Error::NotSatisfied)?;
+    Err(Error::UnsatisfiedConstraint { step, index, name })
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::arith::{ViolatedConstraint, MAX_REPORTED_VIOLATIONS};
+    use crate::frontend::{
+        utils::{
+            cubic_step_native, custom_step_native, CubicFCircuit, CustomFCircuit, WrapperCircuit,
+        },
+        FCircuit,
+    };
     use crate::utils::vec::{
         is_zero_vec,
         tests::{to_F_matrix, to_F_vec},
+        R1CSMatrix,
     };
 
     use ark_pallas::Fr;
+    use ark_relations::r1cs::ConstraintSynthesizer;
+    use ark_std::{One, UniformRand, Zero};
 
     pub fn get_test_r1cs<F: PrimeField>() -> R1CS<F> {
         // R1CS for: x^3 + x + 5 = y (example from article
@@ -266,4 +350,165 @@ pub mod tests {
         r1cs.check_relation(&w, &x)?;
         Ok(())
     }
+
+    #[test]
+    fn test_check_relaxed_relation() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let r1cs = get_test_r1cs::<Fr>();
+        let (_, x, w) = get_test_z_split::<Fr>(3);
+        let E = vec![Fr::zero(); r1cs.n_constraints()];
+
+        // satisfied, as plain R1CS and as relaxed R1CS with a random u and the matching E
+        r1cs.check_relaxed_relation(&w, &x, Fr::one(), &E)?;
+        let u = Fr::rand(&mut rng);
+        let w_rand: Vec<Fr> = (0..w.len()).map(|_| Fr::rand(&mut rng)).collect();
+        let E_rand = r1cs.eval_at_z(&[&[u], &x[..], &w_rand[..]].concat())?;
+        r1cs.check_relaxed_relation(&w_rand, &x, u, &E_rand)?;
+
+        // unsatisfied at the first row only: x * x = x^2 + E_0
+        let mut E_0 = E.clone();
+        E_0[0] = Fr::one();
+        assert_eq!(
+            r1cs.check_relaxed_relation(&w, &x, Fr::one(), &E_0),
+            Err(ArithError::Unsatisfied {
+                n_constraints: 4,
+                n_violated: 1,
+                first: vec![ViolatedConstraint {
+                    index: 0,
+                    left: Fr::from(9_u32),
+                    right: Fr::from(10_u32),
+                }],
+            })
+        );
+
+        // unsatisfied at the last row only: (x^3 + x + 5) * 1 = y
+        let mut w_y = w.clone();
+        w_y[0] += Fr::one();
+        assert_eq!(
+            r1cs.check_relaxed_relation(&w_y, &x, Fr::one(), &E),
+            Err(ArithError::Unsatisfied {
+                n_constraints: 4,
+                n_violated: 1,
+                first: vec![ViolatedConstraint {
+                    index: 3,
+                    left: Fr::from(35_u32),
+                    right: Fr::from(36_u32),
+                }],
+            })
+        );
+        // which is reported through the crate's error
+        let err: Error = r1cs
+            .check_relaxed_relation(&w_y, &x, Fr::one(), &E)
+            .unwrap_err()
+            .into();
+        assert!(matches!(
+            err,
+            Error::Arith(e) if matches!(
+                e.downcast_ref::<ArithError<Fr>>(),
+                Some(ArithError::Unsatisfied { n_violated: 1, .. })
+            )
+        ));
+
+        // only the first violations are listed
+        let r1cs = R1CS::<Fr>::rand(&mut rng, 64, 16);
+        let w: Vec<Fr> = (0..r1cs.n_witnesses())
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let x: Vec<Fr> = (0..r1cs.n_public_inputs())
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let E = vec![Fr::zero(); r1cs.n_constraints()];
+        match r1cs.check_relaxed_relation(&w, &x, Fr::one(), &E) {
+            Err(ArithError::Unsatisfied {
+                n_constraints,
+                n_violated,
+                first,
+            }) => {
+                assert_eq!(n_constraints, 64);
+                assert!(n_violated > MAX_REPORTED_VIOLATIONS);
+                assert_eq!(first.len(), MAX_REPORTED_VIOLATIONS);
+                assert!(first.windows(2).all(|c| c[0].index < c[1].index));
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        // wrong lengths
+        assert_eq!(
+            r1cs.check_relaxed_relation(&w, &x, Fr::one(), &E[1..]),
+            Err(ArithError::WrongLength("E".into(), 63, 64))
+        );
+        Ok(())
+    }
+
+    // the previous representation of the matrices, which kept each row in its own vector as
+    // `ark_relations::r1cs::Matrix` does
+    fn mat_vec_mul_rows<F: PrimeField>(M: &R1CSMatrix<F>, z: &[F]) -> Vec<F> {
+        M.iter()
+            .map(|row| row.iter().map(|(value, col_i)| *value * z[*col_i]).sum())
+            .collect()
+    }
+
+    fn check_extracted_r1cs<FC: FCircuit<Fr>>(
+        fc: FC,
+        z_i: Vec<Fr>,
+        z_i1: Vec<Fr>,
+    ) -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        WrapperCircuit::<Fr, FC> {
+            FC: fc,
+            z_i: Some(z_i),
+            z_i1: Some(z_i1),
+        }
+        .generate_constraints(cs.clone())?;
+        cs.finalize();
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let r1cs = extract_r1cs(&cs)?;
+        let m = cs.to_matrices().unwrap();
+        let (w, x) = extract_w_x(&cs);
+        r1cs.check_relation(&w, &x)?;
+
+        let z: Vec<Fr> = (0..r1cs.n_variables())
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        for (M, rows) in [(&r1cs.A, &m.a), (&r1cs.B, &m.b), (&r1cs.C, &m.c)] {
+            assert_eq!(M.n_rows, cs.num_constraints);
+            assert!(M.rows().eq(rows.iter().map(|row| &row[..])));
+            assert_eq!(mat_vec_mul(M, &z)?, mat_vec_mul_rows(rows, &z));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_r1cs_csr() -> Result<(), Error> {
+        let z_i = vec![Fr::from(3_u32)];
+        check_extracted_r1cs(
+            CubicFCircuit::<Fr>::new(())?,
+            z_i.clone(),
+            cubic_step_native(z_i),
+        )?;
+
+        let n_constraints = 10_000;
+        let z_i = vec![Fr::from(5_u32)];
+        check_extracted_r1cs(
+            CustomFCircuit::<Fr>::new(n_constraints)?,
+            z_i.clone(),
+            custom_step_native(z_i, n_constraints),
+        )
+    }
+
+    #[test]
+    fn test_pad_csr_matrix() -> Result<(), Error> {
+        let r1cs = get_test_r1cs::<Fr>();
+        let z = get_test_z::<Fr>(3);
+        let mut A = r1cs.A.clone();
+        A.pad(8, 6);
+        assert_eq!(A.row_offsets.len(), 9);
+        assert_eq!(
+            mat_vec_mul(&A, &z)?,
+            [mat_vec_mul(&r1cs.A, &z)?, vec![Fr::zero(); 4]].concat()
+        );
+        assert_eq!(A.to_dense()[..4], r1cs.A.to_dense());
+        Ok(())
+    }
 }
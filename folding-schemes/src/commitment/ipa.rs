@@ -7,8 +7,10 @@
 /// i. <s, b> computation is done in log time following a modification of the equation 3 in section
 /// 3.2 from the paper.
 /// ii. s computation is done in 2^{k+1}-2 instead of k*2^k.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
 use ark_ec::AffineRepr;
 use ark_ff::{Field, PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
     boolean::Boolean,
@@ -23,9 +25,9 @@ use ark_std::{cfg_iter, rand::RngCore, UniformRand, Zero};
 use core::{borrow::Borrow, marker::PhantomData};
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use super::{pedersen::Params as PedersenParams, CommitmentScheme};
+use super::{msm::msm, pedersen::Params as PedersenParams, CommitmentScheme, EvaluationProof};
 use crate::folding::circuits::CF2;
-use crate::transcript::Transcript;
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
 use crate::utils::{
     powers_of,
     vec::{vec_add, vec_scalar_mul},
@@ -35,14 +37,28 @@ use crate::{Curve, Error};
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Proof<C: Curve> {
     a: C::ScalarField,
-    l: Vec<C::ScalarField>,
-    r: Vec<C::ScalarField>,
+    /// commitment to the masking vector, only used in hiding mode
+    S: Option<C>,
     L: Vec<C>,
     R: Vec<C>,
 }
 
+// (proof, v=p(x), r=blinding factor of the folded commitment)
+impl<C: Curve> EvaluationProof<C::ScalarField> for (Proof<C>, C::ScalarField, C::ScalarField) {
+    fn eval(&self) -> C::ScalarField {
+        self.1
+    }
+}
+
 /// IPA implements the Inner Product Argument protocol following the CommitmentScheme trait. The
 /// `H` parameter indicates if to use the commitment in hiding mode or not.
+///
+/// It has a transparent setup (the params are the same as Pedersen's), so it can be used as the
+/// commitment scheme of the main curve instead of KZG, eg. `Nova<C1, C2, FC, IPA<C1>, IPA<C2>>`
+/// with the offchain decider. Compared to KZG, the opening proofs have size `O(log n)` (`2⋅log n`
+/// group elements instead of 1) and their native verification takes `O(n)` (an MSM of the size of
+/// the committed vector instead of a pairing check), which is why the onchain deciders keep using
+/// KZG. Note that its commitments are the same as Pedersen's.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct IPA<C: Curve, const H: bool = false> {
     _c: PhantomData<C>,
@@ -53,8 +69,9 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
     type ProverParams = PedersenParams<C>;
     type VerifierParams = PedersenParams<C>;
     type Proof = (Proof<C>, C::ScalarField, C::ScalarField); // (proof, v=p(x), r=blinding factor)
-    type ProverChallenge = (C::ScalarField, C, Vec<C::ScalarField>);
-    type Challenge = (C::ScalarField, C, Vec<C::ScalarField>);
+    /// evaluation point, the rest of the IPA challenges are derived from it and the commitment
+    type ProverChallenge = C::ScalarField;
+    type Challenge = C::ScalarField;
 
     fn is_hiding() -> bool {
         if H {
@@ -63,6 +80,14 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         false
     }
 
+    fn max_len(params: &Self::ProverParams) -> usize {
+        // the vectors are padded to the next power of two when proving
+        match params.generators.len() {
+            0 => 0,
+            n => 1 << n.ilog2(),
+        }
+    }
+
     fn setup(
         mut rng: impl RngCore,
         len: usize,
@@ -70,10 +95,7 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         let generators: Vec<C::Affine> = std::iter::repeat_with(|| C::Affine::rand(&mut rng))
             .take(len.next_power_of_two())
             .collect();
-        let p = PedersenParams::<C> {
-            h: C::rand(&mut rng),
-            generators,
-        };
+        let p = PedersenParams::<C>::new(C::rand(&mut rng), generators);
         Ok((p.clone(), p))
     }
 
@@ -90,11 +112,11 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         }
 
         // h⋅r + <g, a>
-        // use msm_unchecked because we already ensured at the if that lengths match
+        // we already ensured at the if that lengths match
         if !H {
-            return Ok(C::msm_unchecked(&params.generators[..a.len()], a));
+            return Ok(msm(&params.generators[..a.len()], a));
         }
-        Ok(params.h.mul(r) + C::msm_unchecked(&params.generators[..a.len()], a))
+        Ok(params.h.mul(r) + msm(&params.generators[..a.len()], a))
     }
 
     fn prove(
@@ -105,6 +127,105 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         blind: &C::ScalarField,
         rng: Option<&mut dyn RngCore>,
     ) -> Result<Self::Proof, Error> {
+        transcript.absorb_nonnative(P);
+        let x = transcript.get_challenge(); // challenge value at which we evaluate
+        Self::prove_at(params, transcript, x, Basis::Coefficients, a, blind, rng)
+    }
+
+    /// Opens the vector `a` at the given challenge `x`, interpreting `a` as the evaluations of a
+    /// polynomial over the domain of size `a.len().next_power_of_two()` (as KZG and the deciders
+    /// do). The rest of the challenges are obtained from a Poseidon transcript that absorbs the
+    /// commitment and `x`.
+    fn prove_with_challenge(
+        params: &Self::ProverParams,
+        challenge: Self::ProverChallenge,
+        a: &[C::ScalarField], // vector
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        let x = challenge;
+        let mut a = a.to_vec();
+        a.resize(a.len().next_power_of_two(), C::ScalarField::zero());
+        let P = Self::commit(params, &a, blind)?;
+        let mut transcript = Self::challenge_transcript(&P, &x);
+        Self::prove_at(
+            params,
+            &mut transcript,
+            x,
+            Basis::Evaluations,
+            &a,
+            blind,
+            rng,
+        )
+    }
+
+    fn verify(
+        params: &Self::VerifierParams,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &C, // commitment
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        transcript.absorb_nonnative(P);
+        let x = transcript.get_challenge(); // challenge value at which we evaluate
+        Self::verify_at(params, transcript, x, Basis::Coefficients, P, proof)
+    }
+
+    /// Verifies a proof generated by `prove_with_challenge`, ie. an opening at the challenge `x`
+    /// of the vector interpreted as the evaluations of a polynomial.
+    fn verify_with_challenge(
+        params: &Self::VerifierParams,
+        challenge: Self::Challenge,
+        P: &C, // commitment
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        let x = challenge;
+        let mut transcript = Self::challenge_transcript(P, &x);
+        Self::verify_at(params, &mut transcript, x, Basis::Evaluations, P, proof)
+    }
+}
+
+/// Basis in which the committed vector is interpreted when opening it at a point `x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Basis {
+    /// The vector contains the coefficients of the polynomial, so `b = [1, x, x^2, ..., x^{d-1}]`.
+    /// This is what the `IPAGadget` verifies in-circuit.
+    Coefficients,
+    /// The vector contains the evaluations of the polynomial over the domain of size `d`, so `b`
+    /// contains the Lagrange basis polynomials evaluated at `x`.
+    Evaluations,
+}
+
+impl Basis {
+    fn b<F: PrimeField>(&self, x: F, d: usize) -> Result<Vec<F>, Error> {
+        match self {
+            Self::Coefficients => Ok(powers_of(x, d)),
+            Self::Evaluations => Ok(GeneralEvaluationDomain::<F>::new(d)
+                .ok_or(Error::NewDomainFail)?
+                .evaluate_all_lagrange_coefficients(x)),
+        }
+    }
+}
+
+impl<C: Curve, const H: bool> IPA<C, H> {
+    /// Transcript used by `prove_with_challenge` and `verify_with_challenge` to derive the rest of
+    /// the IPA challenges from the commitment and the given evaluation point.
+    fn challenge_transcript(P: &C, x: &C::ScalarField) -> PoseidonSponge<C::ScalarField> {
+        let mut transcript =
+            PoseidonSponge::<C::ScalarField>::new(&poseidon_canonical_config::<C::ScalarField>());
+        transcript.absorb_nonnative(P);
+        transcript.absorb(x);
+        transcript
+    }
+
+    fn prove_at(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        x: C::ScalarField,    // challenge value at which we evaluate
+        basis: Basis,         // basis in which `a` is interpreted
+        a: &[C::ScalarField], // vector
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<(Proof<C>, C::ScalarField, C::ScalarField), Error> {
         if !a.len().is_power_of_two() {
             return Err(Error::NotPowerOfTwo("a".to_string(), a.len()));
         }
@@ -117,32 +238,52 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         if params.generators.len() < a.len() {
             return Err(Error::PedersenParamsLen(params.generators.len(), a.len()));
         }
-        // blinding factors
-        let l: Vec<C::ScalarField>;
-        let r: Vec<C::ScalarField>;
-        if H {
+        let mut a = a.to_owned();
+        let mut b = basis.b(x, d)?;
+        let v = inner_prod(&a, &b)?;
+
+        // In hiding mode, `a` is masked with a random vector `m` such that <m, b>=0, which does not
+        // change the opened value `v` but prevents the last `a` of the proof from leaking a linear
+        // combination of the committed vector. The blinding factors l, r of the L, R commitments
+        // are folded into the returned blinding factor, so they are not revealed either.
+        let mut blind = *blind;
+        let (S, l, r) = if H {
             let rng = rng.ok_or(Error::MissingRandomness)?;
-            l = std::iter::repeat_with(|| C::ScalarField::rand(rng))
+            let mut m: Vec<C::ScalarField> = std::iter::repeat_with(|| C::ScalarField::rand(rng))
+                .take(d)
+                .collect();
+            let i = b
+                .iter()
+                .position(|b_i| !b_i.is_zero())
+                .ok_or(Error::Other("b is zero".to_string()))?;
+            m[i] = C::ScalarField::zero();
+            m[i] = -inner_prod(&m, &b)?
+                * b[i]
+                    .inverse()
+                    .ok_or(Error::Other("error on computing inverse".to_string()))?;
+            let r_m = C::ScalarField::rand(rng);
+            let S = C::msm_unchecked(&params.generators[..d], &m) + params.h.mul(r_m);
+
+            transcript.absorb_nonnative(&S);
+            let xi = transcript.get_challenge();
+            a = vec_add(&a, &vec_scalar_mul(&m, &xi))?;
+            blind += xi * r_m;
+
+            let l: Vec<C::ScalarField> = std::iter::repeat_with(|| C::ScalarField::rand(rng))
                 .take(k)
                 .collect();
-            r = std::iter::repeat_with(|| C::ScalarField::rand(rng))
+            let r: Vec<C::ScalarField> = std::iter::repeat_with(|| C::ScalarField::rand(rng))
                 .take(k)
                 .collect();
+            (Some(S), l, r)
         } else {
-            l = vec![];
-            r = vec![];
-        }
+            (None, vec![], vec![])
+        };
 
-        transcript.absorb_nonnative(P);
-        let x = transcript.get_challenge(); // challenge value at which we evaluate
         let s = transcript.get_challenge();
         let U = C::generator().mul(s);
 
-        let mut a = a.to_owned();
-        let mut b = powers_of(x, d);
-        let v = inner_prod(&a, &b)?;
-
-        let mut G = params.generators.clone();
+        let mut G = params.generators[..d].to_vec();
 
         let mut L: Vec<C> = vec![C::zero(); k];
         let mut R: Vec<C> = vec![C::zero(); k];
@@ -172,6 +313,9 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
             let uj_inv = u[j]
                 .inverse()
                 .ok_or(Error::Other("error on computing inverse".to_string()))?;
+            if H {
+                blind += l[j] * uj.square() + r[j] * uj_inv.square();
+            }
 
             // a_hi * uj^-1 + a_lo * uj
             a = vec_add(
@@ -202,80 +346,53 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         }
 
         Ok((
-            Proof {
-                a: a[0],
-                l: l.clone(),
-                r: r.clone(),
-                L,
-                R,
-            },
-            v,      // evaluation at challenge, v=p(x)
-            *blind, // blind factor
+            Proof { a: a[0], S, L, R },
+            v,     // evaluation at challenge, v=p(x)
+            blind, // blind factor of the folded commitment
         ))
     }
 
-    fn prove_with_challenge(
-        _params: &Self::ProverParams,
-        _challenge: Self::ProverChallenge,
-        _a: &[C::ScalarField], // vector
-        _blind: &C::ScalarField,
-        _rng: Option<&mut dyn RngCore>,
-    ) -> Result<Self::Proof, Error> {
-        // not supported because the prover logic computes challenges as it advances on the logic
-        Err(Error::NotSupported("IPA::prove_with_challenge".to_string()))
-    }
-
-    fn verify(
-        params: &Self::VerifierParams,
+    fn verify_at(
+        params: &PedersenParams<C>,
         transcript: &mut impl Transcript<C::ScalarField>,
-        P: &C, // commitment
-        proof: &Self::Proof,
-    ) -> Result<(), Error> {
-        let (p, _r) = (proof.0.clone(), proof.1);
-        let k = p.L.len();
-
-        transcript.absorb_nonnative(P);
-        let x = transcript.get_challenge(); // challenge value at which we evaluate
-        let s = transcript.get_challenge();
-        let U = C::generator().mul(s);
-        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
-        for i in (0..k).rev() {
-            transcript.absorb_nonnative(&p.L[i]);
-            transcript.absorb_nonnative(&p.R[i]);
-            u[i] = transcript.get_challenge();
-        }
-        let challenge = (x, U, u);
-
-        Self::verify_with_challenge(params, challenge, P, proof)
-    }
-
-    fn verify_with_challenge(
-        params: &Self::VerifierParams,
-        challenge: Self::Challenge,
-        P: &C, // commitment
-        proof: &Self::Proof,
+        x: C::ScalarField, // challenge value at which we evaluate
+        basis: Basis,      // basis in which the committed vector is interpreted
+        P: &C,             // commitment
+        proof: &(Proof<C>, C::ScalarField, C::ScalarField),
     ) -> Result<(), Error> {
         let (p, v, r) = (proof.0.clone(), proof.1, proof.2);
-        let (x, U, u) = challenge;
 
         let k = p.L.len();
         if p.R.len() != k {
             return Err(Error::CommitmentVerificationFail);
         }
+
         if !H && (!r.is_zero()) {
             return Err(Error::BlindingNotZero);
         }
-        if !H && (!p.l.is_empty() || !p.r.is_empty()) {
-            return Err(Error::CommitmentVerificationFail);
-        }
-        if H && (p.l.len() != k || p.r.len() != k) {
-            return Err(Error::CommitmentVerificationFail);
+        // add the commitment to the masking vector
+        let P = match (H, p.S) {
+            (true, Some(S)) => {
+                transcript.absorb_nonnative(&S);
+                let xi = transcript.get_challenge();
+                *P + S.mul(xi)
+            }
+            (false, None) => *P,
+            _ => return Err(Error::CommitmentVerificationFail),
+        };
+
+        let s = transcript.get_challenge();
+        let U = C::generator().mul(s);
+        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
+        for i in (0..k).rev() {
+            transcript.absorb_nonnative(&p.L[i]);
+            transcript.absorb_nonnative(&p.R[i]);
+            u[i] = transcript.get_challenge();
         }
 
-        let P = *P + U.mul(v); // where v=p(x)
+        let P = P + U.mul(v); // where v=p(x)
 
         let mut q_0 = P;
-        let mut r = r;
 
         // compute u[i]^-1 once
         let mut u_invs = vec![C::ScalarField::zero(); u.len()];
@@ -287,12 +404,16 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
 
         // compute b & G from s
         let s = build_s(&u, &u_invs, k)?;
-        // b = <s, b_vec> = <s, [1, x, x^2, ..., x^d-1]>
-        let b = s_b_inner(&u, &x)?;
         let d: usize = 2_u64.pow(k as u32) as usize;
         if params.generators.len() < d {
             return Err(Error::PedersenParamsLen(params.generators.len(), d));
         }
+        let b = match basis {
+            // b = <s, b_vec> = <s, [1, x, x^2, ..., x^d-1]>, computed in log time
+            Basis::Coefficients => s_b_inner(&u, &x)?,
+            // b = <s, b_vec> = <s, [L_0(x), L_1(x), ..., L_{d-1}(x)]>
+            Basis::Evaluations => inner_prod(&s, &basis.b(x, d)?)?,
+        };
         let G = C::msm_unchecked(&params.generators, &s);
 
         for (j, u_j) in u.iter().enumerate() {
@@ -300,9 +421,6 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
             let uj_inv2 = u_invs[j].square();
 
             q_0 = q_0 + p.L[j].mul(uj2) + p.R[j].mul(uj_inv2);
-            if H {
-                r = r + p.l[j] * uj2 + p.r[j] * uj_inv2;
-            }
         }
 
         let q_1 = if H {
@@ -438,8 +556,7 @@ fn s_b_inner_gadget<F: PrimeField, CF: PrimeField>(
 
 pub struct ProofVar<C: Curve> {
     a: EmulatedFpVar<C::ScalarField, CF2<C>>,
-    l: Vec<EmulatedFpVar<C::ScalarField, CF2<C>>>,
-    r: Vec<EmulatedFpVar<C::ScalarField, CF2<C>>>,
+    S: Option<C::Var>,
     L: Vec<C::Var>,
     R: Vec<C::Var>,
 }
@@ -457,16 +574,17 @@ impl<C: Curve> AllocVar<Proof<C>, CF2<C>> for ProofVar<C> {
                 || Ok(val.borrow().a),
                 mode,
             )?;
-            let l: Vec<EmulatedFpVar<C::ScalarField, CF2<C>>> =
-                Vec::new_variable(cs.clone(), || Ok(val.borrow().l.clone()), mode)?;
-            let r: Vec<EmulatedFpVar<C::ScalarField, CF2<C>>> =
-                Vec::new_variable(cs.clone(), || Ok(val.borrow().r.clone()), mode)?;
+            let S: Option<C::Var> = val
+                .borrow()
+                .S
+                .map(|S| C::Var::new_variable(cs.clone(), || Ok(S), mode))
+                .transpose()?;
             let L: Vec<C::Var> =
                 Vec::new_variable(cs.clone(), || Ok(val.borrow().L.clone()), mode)?;
             let R: Vec<C::Var> =
                 Vec::new_variable(cs.clone(), || Ok(val.borrow().R.clone()), mode)?;
 
-            Ok(Self { a, l, r, L, R })
+            Ok(Self { a, S, L, R })
         })
     }
 }
@@ -490,7 +608,8 @@ impl<C: Curve, const H: bool> IPAGadget<C, H> {
         v: &EmulatedFpVar<C::ScalarField, CF2<C>>, // value at evaluation point
         P: &C::Var,                                // commitment
         p: &ProofVar<C>,
-        r: &EmulatedFpVar<C::ScalarField, CF2<C>>, // blinding factor
+        r: &EmulatedFpVar<C::ScalarField, CF2<C>>, // blinding factor, as returned by the prover
+        xi: Option<&EmulatedFpVar<C::ScalarField, CF2<C>>>, // masking challenge, only if hiding
         u: &[EmulatedFpVar<C::ScalarField, CF2<C>>; K], // challenges
         U: &C::Var,                                // challenge
     ) -> Result<Boolean<CF2<C>>, SynthesisError> {
@@ -498,9 +617,14 @@ impl<C: Curve, const H: bool> IPAGadget<C, H> {
             return Err(SynthesisError::Unsatisfiable);
         }
 
+        // add the commitment to the masking vector
+        let P = match (H, &p.S, xi) {
+            (true, Some(S), Some(xi)) => S.scalar_mul_le(xi.to_bits_le()?.iter())? + P,
+            (false, None, None) => P.clone(),
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
         let P_ = U.scalar_mul_le(v.to_bits_le()?.iter())? + P;
         let mut q_0 = P_;
-        let mut r = r.clone();
 
         // compute u[i]^-1 once
         let mut u_invs = vec![EmulatedFpVar::<C::ScalarField, CF2<C>>::zero(); u.len()];
@@ -544,9 +668,6 @@ impl<C: Curve, const H: bool> IPAGadget<C, H> {
             q_0 = q_0
                 + p.L[j].scalar_mul_le(uj2.to_bits_le()?.iter())?
                 + p.R[j].scalar_mul_le(uj_inv2.to_bits_le()?.iter())?;
-            if H {
-                r = r + &p.l[j] * &uj2 + &p.r[j] * &uj_inv2;
-            }
         }
 
         let q_1 = if H {
@@ -577,6 +698,7 @@ mod tests {
     use ark_relations::r1cs::ConstraintSystem;
 
     use super::*;
+    use crate::folding::circuits::decider::EvalGadget;
     use crate::transcript::poseidon::poseidon_canonical_config;
 
     #[test]
@@ -621,6 +743,59 @@ mod tests {
         )?;
 
         IPA::<Projective, hiding>::verify(&params, &mut transcript_v, &cm, &proof)?;
+
+        if hiding {
+            // proving again the same opening gives a different proof, which reveals neither the
+            // original blinding factor nor the committed vector
+            let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let proof2 = IPA::<Projective, hiding>::prove(
+                &params,
+                &mut transcript_p,
+                &cm,
+                &a,
+                &r_blind,
+                Some(&mut rng),
+            )?;
+            assert_ne!(proof.0.a, proof2.0.a);
+            assert_ne!(proof.2, proof2.2);
+            assert_ne!(proof.2, r_blind);
+            let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+            IPA::<Projective, hiding>::verify(&params, &mut transcript_v, &cm, &proof2)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_with_challenge() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        // non power of two length, as the witness vectors opened by the deciders
+        let n: usize = 13;
+        let (params, _) = IPA::<Projective>::setup(&mut rng, n)?;
+
+        let a: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(n)
+            .collect();
+        let cm = IPA::<Projective>::commit(&params, &a, &Fr::zero())?;
+
+        let x = Fr::rand(&mut rng);
+        let proof = IPA::<Projective>::prove_with_challenge(&params, x, &a, &Fr::zero(), None)?;
+        // the opened value is the evaluation of the polynomial interpolating `a`, as in KZG
+        assert_eq!(proof.eval(), EvalGadget::evaluate_native(&a, x)?);
+        IPA::<Projective>::verify_with_challenge(&params, x, &cm, &proof)?;
+
+        // wrong evaluation point
+        assert!(matches!(
+            IPA::<Projective>::verify_with_challenge(&params, x + Fr::from(1), &cm, &proof),
+            Err(Error::CommitmentVerificationFail)
+        ));
+        // wrong evaluation
+        let mut bad_proof = proof.clone();
+        bad_proof.1 += Fr::from(1);
+        assert!(matches!(
+            IPA::<Projective>::verify_with_challenge(&params, x, &cm, &bad_proof),
+            Err(Error::CommitmentVerificationFail)
+        ));
         Ok(())
     }
 
@@ -673,6 +848,10 @@ mod tests {
         let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
         transcript_v.absorb_nonnative(&cm);
         let challenge = transcript_v.get_challenge(); // challenge value at which we evaluate
+        let xi = proof.0.S.map(|S| {
+            transcript_v.absorb_nonnative(&S);
+            transcript_v.get_challenge()
+        });
         let s = transcript_v.get_challenge();
         let U = Projective::generator() * s;
         let mut u: Vec<Fr> = vec![Fr::zero(); k];
@@ -689,7 +868,10 @@ mod tests {
         let vVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(proof.1))?;
         let cmVar = GVar::new_witness(cs.clone(), || Ok(cm))?;
         let proofVar = ProofVar::<Projective>::new_witness(cs.clone(), || Ok(proof.0))?;
-        let r_blindVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(r_blind))?;
+        let r_blindVar = EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(proof.2))?;
+        let xiVar = xi
+            .map(|xi| EmulatedFpVar::<Fr, Fq>::new_witness(cs.clone(), || Ok(xi)))
+            .transpose()?;
         let uVar_vec = Vec::<EmulatedFpVar<Fr, Fq>>::new_witness(cs.clone(), || Ok(u))?;
         let uVar: [EmulatedFpVar<Fr, Fq>; k] = uVar_vec.try_into().map_err(|_| {
             Error::ConversionError(
@@ -708,6 +890,7 @@ mod tests {
             &cmVar,
             &proofVar,
             &r_blindVar,
+            xiVar.as_ref(),
             &uVar,
             &UVar,
         )?;
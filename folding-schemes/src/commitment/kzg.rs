@@ -5,6 +5,7 @@
 /// vectors indistinctly, and the arkworks KZG10 implementation contains all the methods under the
 /// same trait, which requires the Pairing trait, where the prover does not need access to the
 /// Pairing but only to G1.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
 use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
 use ark_ff::PrimeField;
 use ark_poly::{
@@ -18,11 +19,10 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
 use ark_std::rand::RngCore;
 use ark_std::{borrow::Cow, fmt::Debug};
 use ark_std::{One, Zero};
-use core::marker::PhantomData;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use core::{iter::successors, marker::PhantomData};
 
-use super::CommitmentScheme;
-use crate::transcript::Transcript;
+use super::{check_batch_lengths, msm::msm, CommitmentScheme, EvaluationProof};
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
 use crate::utils::vec::poly_from_vec;
 use crate::{Curve, Error};
 
@@ -76,6 +76,12 @@ pub struct Proof<C: Curve> {
     pub proof: C,
 }
 
+impl<C: Curve> EvaluationProof<C::ScalarField> for Proof<C> {
+    fn eval(&self) -> C::ScalarField {
+        self.eval
+    }
+}
+
 /// KZG implements the CommitmentScheme trait for the KZG commitment scheme.
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct KZG<'a, E: Pairing, const H: bool = false> {
@@ -97,6 +103,15 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         false
     }
 
+    fn max_len(params: &Self::ProverParams) -> usize {
+        // the vectors are interpolated over a domain of size their length's next power of two, so
+        // the resulting polynomial needs up to that number of powers
+        match params.powers_of_g.len() {
+            0 => 0,
+            n => 1 << n.ilog2(),
+        }
+    }
+
     /// setup returns the tuple (ProverKey, VerifierKey). For real world deployments the setup must
     /// be computed in the most trustless way possible, usually through an MPC ceremony.
     fn setup(
@@ -138,12 +153,8 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         let polynomial = poly_from_vec(v.to_vec())?;
         check_degree_is_too_large(polynomial.degree(), params.powers_of_g.len())?;
 
-        let (num_leading_zeros, plain_coeffs) =
-            skip_first_zero_coeffs_and_convert_to_bigints(&polynomial);
-        let commitment = <E::G1 as VariableBaseMSM>::msm_bigint(
-            &params.powers_of_g[num_leading_zeros..],
-            &plain_coeffs,
-        );
+        let (num_leading_zeros, plain_coeffs) = skip_first_zero_coeffs(&polynomial);
+        let commitment = msm::<E::G1>(&params.powers_of_g[num_leading_zeros..], plain_coeffs);
         Ok(commitment)
     }
 
@@ -199,12 +210,8 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         };
 
         check_degree_is_too_large(witness_poly.degree(), params.powers_of_g.len())?;
-        let (num_leading_zeros, witness_coeffs) =
-            skip_first_zero_coeffs_and_convert_to_bigints(&witness_poly);
-        let proof = <E::G1 as VariableBaseMSM>::msm_bigint(
-            &params.powers_of_g[num_leading_zeros..],
-            &witness_coeffs,
-        );
+        let (num_leading_zeros, witness_coeffs) = skip_first_zero_coeffs(&witness_poly);
+        let proof = msm::<E::G1>(&params.powers_of_g[num_leading_zeros..], witness_coeffs);
 
         Ok(Proof { eval, proof })
     }
@@ -246,6 +253,116 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         }
         Ok(())
     }
+
+    /// batch_verify_with_challenges checks all the given KZG proofs with a single pairing check.
+    /// Each proof satisfies `e(π_j, τ⋅H) == e(C_j - y_j⋅G + z_j⋅π_j, H)`, where `z_j` is the
+    /// challenge and `y_j` the evaluation, so the equations are combined with the powers of a
+    /// challenge `ρ` derived from all the inputs into
+    /// `e(Σ ρ^j⋅π_j, τ⋅H) == e(Σ ρ^j⋅(C_j - y_j⋅G + z_j⋅π_j), H)`.
+    fn batch_verify_with_challenges(
+        params: &Self::VerifierParams,
+        challenges: &[Self::Challenge],
+        cms: &[E::G1],
+        proofs: &[Self::Proof],
+    ) -> Result<(), Error> {
+        if H {
+            return Err(Error::NotSupportedYet("hiding".to_string()));
+        }
+        check_batch_lengths(challenges.len(), cms.len(), proofs.len())?;
+        if cms.is_empty() {
+            return Ok(());
+        }
+
+        let mut transcript =
+            PoseidonSponge::<E::ScalarField>::new(&poseidon_canonical_config::<E::ScalarField>());
+        for ((z, cm), proof) in challenges.iter().zip(cms).zip(proofs) {
+            transcript.absorb_nonnative(cm);
+            transcript.absorb(z);
+            transcript.absorb(&proof.eval);
+            transcript.absorb_nonnative(&proof.proof);
+        }
+        let rho: E::ScalarField = transcript.get_challenge();
+        let rhos: Vec<E::ScalarField> =
+            successors(Some(E::ScalarField::one()), |rho_j| Some(*rho_j * rho))
+                .take(cms.len())
+                .collect();
+
+        let cms = E::G1::normalize_batch(cms);
+        let pis = E::G1::normalize_batch(&proofs.iter().map(|p| p.proof).collect::<Vec<_>>());
+        let rhos_z: Vec<E::ScalarField> =
+            rhos.iter().zip(challenges).map(|(r, z)| *r * z).collect();
+        let y: E::ScalarField = rhos.iter().zip(proofs).map(|(r, p)| *r * p.eval).sum();
+
+        // Σ ρ^j⋅π_j
+        let lhs = E::G1::msm_unchecked(&pis, &rhos);
+        // Σ ρ^j⋅C_j + Σ ρ^j⋅z_j⋅π_j - (Σ ρ^j⋅y_j)⋅G
+        let rhs =
+            E::G1::msm_unchecked(&cms, &rhos) + E::G1::msm_unchecked(&pis, &rhos_z) - params.g * y;
+        if !E::multi_pairing([lhs, -rhs], [params.beta_h, params.h]).is_zero() {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        Ok(())
+    }
+}
+
+/// Default number of bits of the random coefficients used by [`KZG::verify_srs`], which gives a
+/// soundness error of about 2^-128.
+pub const SRS_CHECK_SECURITY_BITS: usize = 128;
+
+impl<'a, E: Pairing<G1: Curve>, const H: bool> KZG<'a, E, H> {
+    /// Checks the structural consistency of an SRS coming from an untrusted source: that all the
+    /// points are in the correct subgroup, that the first power is the verifier's generator, and
+    /// that the powers are consistent, ie. `e(τ^i⋅G, H) == e(τ^{i-1}⋅G, τ⋅H)` for all `i`.
+    ///
+    /// The consistency of the powers is checked at once through a random linear combination with
+    /// coefficients of `security_bits` bits, so that a wrong SRS passes the check with probability
+    /// at most `2^-security_bits`. Lower values make the check faster.
+    pub fn verify_srs(
+        params: &(ProverKey<'a, E::G1>, VerifierKey<E>),
+        security_bits: usize,
+        mut rng: impl RngCore,
+    ) -> Result<(), Error> {
+        if security_bits == 0 || security_bits >= E::ScalarField::MODULUS_BIT_SIZE as usize {
+            return Err(Error::OutOfBounds);
+        }
+        let (pk, vk) = params;
+        pk.check()
+            .map_err(|e| Error::InvalidSRS(format!("invalid prover key points: {}", e)))?;
+        vk.check()
+            .map_err(|e| Error::InvalidSRS(format!("invalid verifier key points: {}", e)))?;
+
+        let powers = &pk.powers_of_g;
+        if powers.first() != Some(&vk.g) {
+            return Err(Error::InvalidSRS(
+                "the first power does not match the verifier key".to_string(),
+            ));
+        }
+        if powers.len() < 2 {
+            return Ok(());
+        }
+
+        // r_i random of `security_bits` bits
+        let n_bytes = security_bits.div_ceil(8);
+        let r: Vec<E::ScalarField> = (0..powers.len() - 1)
+            .map(|_| {
+                let mut bytes = vec![0u8; n_bytes];
+                rng.fill_bytes(&mut bytes);
+                if security_bits % 8 != 0 {
+                    bytes[n_bytes - 1] &= (1 << (security_bits % 8)) - 1;
+                }
+                E::ScalarField::from_le_bytes_mod_order(&bytes)
+            })
+            .collect();
+        // e(Σ r_i⋅τ^{i+1}⋅G, H) == e(Σ r_i⋅τ^i⋅G, τ⋅H)
+        let lhs = E::G1::msm_unchecked(&powers[1..], &r);
+        let rhs = E::G1::msm_unchecked(&powers[..powers.len() - 1], &r);
+        if !E::multi_pairing([lhs, -rhs], [vk.h, vk.beta_h]).is_zero() {
+            return Err(Error::InvalidSRS(
+                "the powers are not consistent".to_string(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 fn check_degree_is_too_large(
@@ -263,31 +380,21 @@ fn check_degree_is_too_large(
     }
 }
 
-fn skip_first_zero_coeffs_and_convert_to_bigints<F: PrimeField, P: DenseUVPolynomial<F>>(
-    p: &P,
-) -> (usize, Vec<F::BigInt>) {
+fn skip_first_zero_coeffs<F: PrimeField, P: DenseUVPolynomial<F>>(p: &P) -> (usize, &[F]) {
     let mut num_leading_zeros = 0;
     while num_leading_zeros < p.coeffs().len() && p.coeffs()[num_leading_zeros].is_zero() {
         num_leading_zeros += 1;
     }
-    let coeffs = convert_to_bigints(&p.coeffs()[num_leading_zeros..]);
-    (num_leading_zeros, coeffs)
-}
-
-fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
-    ark_std::cfg_iter!(p)
-        .map(|s| s.into_bigint())
-        .collect::<Vec<_>>()
+    (num_leading_zeros, &p.coeffs()[num_leading_zeros..])
 }
 
 #[cfg(test)]
 mod tests {
-    use ark_bn254::{Bn254, Fr, G1Projective as G1};
-    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective as G1};
+    use ark_ec::PrimeGroup;
     use ark_std::{test_rng, UniformRand};
 
     use super::*;
-    use crate::transcript::poseidon::poseidon_canonical_config;
 
     #[test]
     fn test_kzg_commitment_scheme() -> Result<(), Error> {
@@ -308,4 +415,111 @@ mod tests {
         KZG::<Bn254>::verify(&vk, transcript_v, &cm, &proof)?;
         Ok(())
     }
+
+    #[test]
+    fn test_kzg_batch_verify() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let n = 10;
+        let (pk, vk): (ProverKey<G1>, VerifierKey<Bn254>) = KZG::<Bn254>::setup(&mut rng, n)?;
+
+        let vs: Vec<Vec<Fr>> = (0..3)
+            .map(|_| std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect())
+            .collect();
+        let cms = vs
+            .iter()
+            .map(|v| KZG::<Bn254>::commit(&pk, v, &Fr::zero()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let challenges: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(3).collect();
+        let openings: Vec<(&[Fr], Fr)> = vs.iter().map(|v| (&v[..], Fr::zero())).collect();
+        let proofs = KZG::<Bn254>::batch_prove_with_challenges(&pk, &challenges, &openings, None)?;
+
+        KZG::<Bn254>::batch_verify_with_challenges(&vk, &challenges, &cms, &proofs)?;
+        // the batched check accepts the same proofs as the individual checks
+        for ((&c, cm), proof) in challenges.iter().zip(&cms).zip(&proofs) {
+            KZG::<Bn254>::verify_with_challenge(&vk, c, cm, proof)?;
+        }
+
+        // a single wrong evaluation makes the whole batch fail
+        let mut wrong_proofs = proofs.clone();
+        wrong_proofs[1].eval += Fr::one();
+        assert!(matches!(
+            KZG::<Bn254>::batch_verify_with_challenges(&vk, &challenges, &cms, &wrong_proofs),
+            Err(Error::CommitmentVerificationFail)
+        ));
+        // as does a proof for another commitment
+        let mut wrong_proofs = proofs.clone();
+        wrong_proofs.swap(0, 2);
+        assert!(
+            KZG::<Bn254>::batch_verify_with_challenges(&vk, &challenges, &cms, &wrong_proofs)
+                .is_err()
+        );
+        assert!(matches!(
+            KZG::<Bn254>::batch_verify_with_challenges(&vk, &challenges, &cms[1..], &proofs),
+            Err(Error::NotSameLength(..))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_verify_srs() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let params = KZG::<Bn254>::setup(&mut rng, 64)?;
+        KZG::<Bn254>::verify_srs(&params, SRS_CHECK_SECURITY_BITS, &mut rng)?;
+        // smaller coefficients, not multiple of 8 bits
+        KZG::<Bn254>::verify_srs(&params, 30, &mut rng)?;
+
+        // a single corrupted power is detected
+        let (mut pk, vk) = params.clone();
+        let mut powers = pk.powers_of_g.to_vec();
+        powers[17] = (powers[17] + G1::generator()).into();
+        pk.powers_of_g = Cow::Owned(powers);
+        assert!(matches!(
+            KZG::<Bn254>::verify_srs(&(pk, vk), SRS_CHECK_SECURITY_BITS, &mut rng),
+            Err(Error::InvalidSRS(_))
+        ));
+
+        // a τ⋅H that does not match the powers of G1 is detected
+        let (pk, mut vk) = params.clone();
+        vk.beta_h = (vk.beta_h + vk.h).into();
+        assert!(matches!(
+            KZG::<Bn254>::verify_srs(&(pk, vk), SRS_CHECK_SECURITY_BITS, &mut rng),
+            Err(Error::InvalidSRS(_))
+        ));
+
+        // a point that is not on the curve is detected
+        let (mut pk, vk) = params;
+        let mut powers = pk.powers_of_g.to_vec();
+        powers[3] = G1Affine::new_unchecked(powers[3].x, powers[3].y + Fq::from(1));
+        pk.powers_of_g = Cow::Owned(powers);
+        assert!(matches!(
+            KZG::<Bn254>::verify_srs(&(pk, vk), SRS_CHECK_SECURITY_BITS, &mut rng),
+            Err(Error::InvalidSRS(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_commitment_scheme_bls12_381() -> Result<(), Error> {
+        use ark_bls12_381::{Bls12_381, Fr as BlsFr, G1Projective as BlsG1};
+
+        let mut rng = &mut test_rng();
+        let poseidon_config = poseidon_canonical_config::<BlsFr>();
+        let transcript_p = &mut PoseidonSponge::<BlsFr>::new(&poseidon_config);
+        let transcript_v = &mut PoseidonSponge::<BlsFr>::new(&poseidon_config);
+
+        let n = 10;
+        let (pk, vk): (ProverKey<BlsG1>, VerifierKey<Bls12_381>) =
+            KZG::<Bls12_381>::setup(&mut rng, n)?;
+
+        let v: Vec<BlsFr> = std::iter::repeat_with(|| BlsFr::rand(rng))
+            .take(n)
+            .collect();
+        let cm = KZG::<Bls12_381>::commit(&pk, &v, &BlsFr::zero())?;
+
+        let proof = KZG::<Bls12_381>::prove(&pk, transcript_p, &cm, &v, &BlsFr::zero(), None)?;
+
+        // verify the proof:
+        KZG::<Bls12_381>::verify(&vk, transcript_v, &cm, &proof)?;
+        Ok(())
+    }
 }
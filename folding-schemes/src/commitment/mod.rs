@@ -3,11 +3,14 @@ use ark_std::fmt::Debug;
 use ark_std::rand::RngCore;
 
 use crate::transcript::Transcript;
+use crate::utils::vec::sparse_vec_to_dense;
 use crate::{Curve, Error};
 
 pub mod ipa;
 pub mod kzg;
+pub mod msm;
 pub mod pedersen;
+pub mod ptau;
 
 /// CommitmentScheme defines the vector commitment scheme trait. Where `H` indicates if to use the
 /// commitment in hiding mode or not.
@@ -20,6 +23,9 @@ pub trait CommitmentScheme<C: Curve, const H: bool = false>: Clone + Debug {
 
     fn is_hiding() -> bool;
 
+    /// Returns the maximum length of the vectors that can be committed with the given params.
+    fn max_len(params: &Self::ProverParams) -> usize;
+
     fn setup(
         rng: impl RngCore,
         len: usize,
@@ -31,6 +37,20 @@ pub trait CommitmentScheme<C: Curve, const H: bool = false>: Clone + Debug {
         blind: &C::ScalarField,
     ) -> Result<C, Error>;
 
+    /// same as `commit` but for a sparse vector of length `len`, given by its non-zero
+    /// `(index, value)` entries, where the entries with the same index are added. The length is
+    /// needed since some schemes (eg. KZG) interpret the vector over a domain that depends on it.
+    /// By default the vector is densified, schemes whose cost depends on the number of non-zero
+    /// entries (eg. Pedersen) override it.
+    fn commit_sparse(
+        params: &Self::ProverParams,
+        v: &[(usize, C::ScalarField)],
+        len: usize,
+        blind: &C::ScalarField,
+    ) -> Result<C, Error> {
+        Self::commit(params, &sparse_vec_to_dense(v, len)?, blind)
+    }
+
     fn prove(
         params: &Self::ProverParams,
         transcript: &mut impl Transcript<C::ScalarField>,
@@ -65,6 +85,86 @@ pub trait CommitmentScheme<C: Curve, const H: bool = false>: Clone + Debug {
         cm: &C,
         proof: &Self::Proof,
     ) -> Result<(), Error>;
+
+    /// same as `prove_with_challenge` but for multiple vectors, where the i-th `(v, blind)` opening
+    /// is proven at the i-th challenge. By default the proofs are computed one by one.
+    fn batch_prove_with_challenges(
+        params: &Self::ProverParams,
+        challenges: &[Self::ProverChallenge],
+        openings: &[(&[C::ScalarField], C::ScalarField)],
+        mut rng: Option<&mut dyn RngCore>,
+    ) -> Result<Vec<Self::Proof>, Error> {
+        if challenges.len() != openings.len() {
+            return Err(Error::NotSameLength(
+                "challenges.len()".to_string(),
+                challenges.len(),
+                "openings.len()".to_string(),
+                openings.len(),
+            ));
+        }
+        challenges
+            .iter()
+            .zip(openings)
+            .map(|(c, (v, blind))| {
+                let rng = rng.as_mut().map(|rng| &mut **rng as &mut dyn RngCore);
+                Self::prove_with_challenge(params, c.clone(), v, blind, rng)
+            })
+            .collect()
+    }
+
+    /// same as `verify_with_challenge` but for multiple commitments, where the i-th proof opens
+    /// the i-th commitment at the i-th challenge. By default the proofs are verified one by one,
+    /// schemes that can aggregate the checks (eg. KZG) override it.
+    fn batch_verify_with_challenges(
+        params: &Self::VerifierParams,
+        challenges: &[Self::Challenge],
+        cms: &[C],
+        proofs: &[Self::Proof],
+    ) -> Result<(), Error> {
+        check_batch_lengths(challenges.len(), cms.len(), proofs.len())?;
+        challenges
+            .iter()
+            .zip(cms)
+            .zip(proofs)
+            .try_for_each(|((c, cm), proof)| {
+                Self::verify_with_challenge(params, c.clone(), cm, proof)
+            })
+    }
+}
+
+/// Checks that the challenges, commitments and proofs given to `batch_verify_with_challenges`
+/// have the same length.
+pub(crate) fn check_batch_lengths(
+    n_challenges: usize,
+    n_cms: usize,
+    n_proofs: usize,
+) -> Result<(), Error> {
+    if n_challenges != n_cms {
+        return Err(Error::NotSameLength(
+            "challenges.len()".to_string(),
+            n_challenges,
+            "cms.len()".to_string(),
+            n_cms,
+        ));
+    }
+    if n_cms != n_proofs {
+        return Err(Error::NotSameLength(
+            "cms.len()".to_string(),
+            n_cms,
+            "proofs.len()".to_string(),
+            n_proofs,
+        ));
+    }
+    Ok(())
+}
+
+/// EvaluationProof is implemented by the proofs of the commitment schemes that open the committed
+/// vector, interpreted as the evaluations of a polynomial, at the challenge point (eg. KZG and IPA
+/// through `prove_with_challenge`). The offchain deciders use it to link the evaluations computed
+/// in-circuit with the commitments.
+pub trait EvaluationProof<F> {
+    /// Returns the evaluation of the committed polynomial at the challenge point.
+    fn eval(&self) -> F;
 }
 
 #[cfg(test)]
@@ -83,6 +183,7 @@ mod tests {
     use super::kzg::{ProverKey, KZG};
     use super::pedersen::Pedersen;
     use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::utils::vec::dense_vec_to_sparse;
 
     #[test]
     fn test_homomorphic_property_using_Commitment_trait() -> Result<(), Error> {
@@ -130,6 +231,72 @@ mod tests {
         Ok(())
     }
 
+    /// checks that committing to random sparse vectors (90% of zeros) with `commit_sparse` gives
+    /// the same commitments as committing to their dense representation
+    #[test]
+    fn test_commit_sparse() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let n: usize = 100;
+
+        let (pedersen_params, _) = Pedersen::<G1>::setup(&mut rng, n)?;
+        let mut pedersen_params_tables = pedersen_params.clone();
+        pedersen_params_tables.precompute(8)?;
+        let (kzg_pk, _): (ProverKey<G1>, VerifierKey<Bn254>) = KZG::<Bn254>::setup(&mut rng, n)?;
+
+        for _ in 0..5 {
+            let v: Vec<Fr> = (0..n)
+                .map(|_| {
+                    if rng.next_u32() % 10 == 0 {
+                        Fr::rand(rng)
+                    } else {
+                        Fr::zero()
+                    }
+                })
+                .collect();
+            let v_sparse = dense_vec_to_sparse(&v);
+            assert!(v_sparse.len() < n / 2);
+            let blind = Fr::rand(rng);
+
+            test_commit_sparse_opt::<G1, Pedersen<G1>, false>(&pedersen_params, &v, &Fr::zero())?;
+            test_commit_sparse_opt::<G1, Pedersen<G1>, false>(
+                &pedersen_params_tables,
+                &v,
+                &Fr::zero(),
+            )?;
+            test_commit_sparse_opt::<G1, Pedersen<G1, true>, true>(&pedersen_params, &v, &blind)?;
+            test_commit_sparse_opt::<G1, IPA<G1, true>, true>(&pedersen_params, &v, &blind)?;
+            test_commit_sparse_opt::<G1, KZG<Bn254>, false>(&kzg_pk, &v, &Fr::zero())?;
+        }
+
+        // entries out of the vector's length are rejected
+        assert!(matches!(
+            Pedersen::<G1>::commit_sparse(&pedersen_params, &[(n, Fr::from(1))], n, &Fr::zero()),
+            Err(Error::OutOfBounds)
+        ));
+        assert!(matches!(
+            KZG::<Bn254>::commit_sparse(&kzg_pk, &[(n, Fr::from(1))], n, &Fr::zero()),
+            Err(Error::OutOfBounds)
+        ));
+        Ok(())
+    }
+
+    fn test_commit_sparse_opt<C: Curve, CS: CommitmentScheme<C, H>, const H: bool>(
+        params: &CS::ProverParams,
+        v: &[C::ScalarField],
+        blind: &C::ScalarField,
+    ) -> Result<(), Error> {
+        let cm = CS::commit(params, v, blind)?;
+        assert_eq!(
+            CS::commit_sparse(params, &dense_vec_to_sparse(v), v.len(), blind)?,
+            cm
+        );
+        // the zero entries and the order of the entries do not change the commitment
+        let mut v_sparse: Vec<_> = v.iter().copied().enumerate().rev().collect();
+        v_sparse.push((0, C::ScalarField::zero()));
+        assert_eq!(CS::commit_sparse(params, &v_sparse, v.len(), blind)?, cm);
+        Ok(())
+    }
+
     fn test_homomorphic_property_using_Commitment_trait_opt<C: Curve, CS: CommitmentScheme<C>>(
         poseidon_config: &PoseidonConfig<C::ScalarField>,
         prover_params: &CS::ProverParams,
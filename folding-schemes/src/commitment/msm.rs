@@ -0,0 +1,221 @@
+/// This module contains the [`MsmBackend`] trait, which allows to plug external implementations
+/// (eg. GPU accelerated ones) of the multi-scalar multiplications computed by the commitment
+/// schemes, and the [`msm`] method that the commitment schemes use to compute them.
+///
+/// By default the MSMs are computed on the CPU with arkworks' `VariableBaseMSM`. With the `gpu`
+/// feature, a backend can be registered for a curve through [`set_msm_backend`] (eg. a CUDA
+/// implementation wrapping icicle's MSM, which is kept outside of this crate so that building it
+/// does not require the CUDA toolchain). The MSMs over that curve are then routed through the
+/// backend, falling back to the CPU when the backend is not available at runtime or when it fails.
+use ark_ec::{CurveGroup, VariableBaseMSM};
+
+use crate::Error;
+
+#[cfg(feature = "gpu")]
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+/// MsmBackend defines an implementation of the multi-scalar multiplication over the curve `G`,
+/// taking the bases and scalars in their arkworks representation.
+pub trait MsmBackend<G: CurveGroup>: Send + Sync {
+    /// Name of the backend, used when logging its failures.
+    fn name(&self) -> &str;
+
+    /// Returns if the backend can be used at runtime (eg. if there is a GPU device available).
+    fn is_available(&self) -> bool;
+
+    /// Computes `<bases, scalars>`, where `bases` and `scalars` have the same length.
+    fn msm(&self, bases: &[G::Affine], scalars: &[G::ScalarField]) -> Result<G, Error>;
+}
+
+/// CPU reference implementation of the [`MsmBackend`], using arkworks' `VariableBaseMSM`. It is
+/// the fallback used when no other backend is available.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuMsm;
+
+impl<G: CurveGroup> MsmBackend<G> for CpuMsm {
+    fn name(&self) -> &str {
+        "cpu"
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn msm(&self, bases: &[G::Affine], scalars: &[G::ScalarField]) -> Result<G, Error> {
+        if bases.len() != scalars.len() {
+            return Err(Error::NotSameLength(
+                "bases.len()".to_string(),
+                bases.len(),
+                "scalars.len()".to_string(),
+                scalars.len(),
+            ));
+        }
+        Ok(G::msm_unchecked(bases, scalars))
+    }
+}
+
+/// Registered backends, indexed by the `TypeId` of their curve, where each value is an
+/// `Arc<dyn MsmBackend<G>>`.
+#[cfg(feature = "gpu")]
+type Registry = RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>;
+
+#[cfg(feature = "gpu")]
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers the backend used to compute the MSMs over the curve `G`, replacing the previous one.
+#[cfg(feature = "gpu")]
+pub fn set_msm_backend<G: CurveGroup>(backend: Arc<dyn MsmBackend<G>>) {
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(TypeId::of::<G>(), Box::new(backend));
+}
+
+/// Removes the backend registered for the curve `G`, so that its MSMs are computed on the CPU.
+#[cfg(feature = "gpu")]
+pub fn clear_msm_backend<G: CurveGroup>() {
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&TypeId::of::<G>());
+}
+
+/// Returns the backend registered for the curve `G`, if any.
+#[cfg(feature = "gpu")]
+pub fn msm_backend<G: CurveGroup>() -> Option<Arc<dyn MsmBackend<G>>> {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&TypeId::of::<G>())?
+        .downcast_ref::<Arc<dyn MsmBackend<G>>>()
+        .cloned()
+}
+
+/// Computes `<bases, scalars>` over the first `min(bases.len(), scalars.len())` elements (as
+/// `VariableBaseMSM::msm_unchecked` does). With the `gpu` feature, it uses the backend registered
+/// for `G` if it is available, and the CPU otherwise.
+pub fn msm<G: CurveGroup>(bases: &[G::Affine], scalars: &[G::ScalarField]) -> G {
+    let n = bases.len().min(scalars.len());
+    let (bases, scalars) = (&bases[..n], &scalars[..n]);
+
+    #[cfg(feature = "gpu")]
+    if let Some(backend) = msm_backend::<G>().filter(|backend| backend.is_available()) {
+        match backend.msm(bases, scalars) {
+            Ok(res) => return res,
+            Err(e) => log::warn!(
+                "MSM backend '{}' failed, falling back to the CPU: {}",
+                backend.name(),
+                e
+            ),
+        }
+    }
+
+    G::msm_unchecked(bases, scalars)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Fr, G1Projective as G1};
+    use ark_std::{test_rng, UniformRand, Zero};
+
+    use super::*;
+
+    #[test]
+    fn test_cpu_msm() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let n = 100;
+        let bases: Vec<_> = std::iter::repeat_with(|| G1::rand(&mut rng).into_affine())
+            .take(n)
+            .collect();
+        let scalars: Vec<_> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(n)
+            .collect();
+
+        let expected = G1::msm(&bases, &scalars).map_err(|e| Error::Other(e.to_string()))?;
+        let naive = bases
+            .iter()
+            .zip(&scalars)
+            .fold(G1::zero(), |acc, (b, s)| acc + *b * s);
+        assert_eq!(expected, naive);
+
+        assert_eq!(MsmBackend::<G1>::msm(&CpuMsm, &bases, &scalars)?, expected);
+        assert_eq!(msm::<G1>(&bases, &scalars), expected);
+        // the extra bases are ignored
+        assert_eq!(
+            msm::<G1>(&bases, &scalars[..n / 2]),
+            G1::msm_unchecked(&bases, &scalars[..n / 2])
+        );
+
+        assert!(matches!(
+            MsmBackend::<G1>::msm(&CpuMsm, &bases, &scalars[1..]),
+            Err(Error::NotSameLength(..))
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "gpu")]
+    #[test]
+    fn test_msm_backend_fallback() -> Result<(), Error> {
+        use ark_bn254::G2Projective as G2;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Backend that counts its calls, and that fails or is unavailable when set so.
+        struct MockBackend {
+            available: bool,
+            fails: bool,
+            calls: AtomicUsize,
+        }
+        impl MsmBackend<G2> for MockBackend {
+            fn name(&self) -> &str {
+                "mock"
+            }
+            fn is_available(&self) -> bool {
+                self.available
+            }
+            fn msm(
+                &self,
+                bases: &[<G2 as CurveGroup>::Affine],
+                scalars: &[Fr],
+            ) -> Result<G2, Error> {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                if self.fails {
+                    return Err(Error::Other("mock failure".to_string()));
+                }
+                MsmBackend::<G2>::msm(&CpuMsm, bases, scalars)
+            }
+        }
+
+        let mut rng = test_rng();
+        let bases: Vec<_> = std::iter::repeat_with(|| G2::rand(&mut rng).into_affine())
+            .take(10)
+            .collect();
+        let scalars: Vec<_> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(10)
+            .collect();
+        let expected = G2::msm_unchecked(&bases, &scalars);
+
+        // (available, fails, expected number of calls)
+        for (available, fails, calls) in [(true, false, 1), (true, true, 1), (false, false, 0)] {
+            let backend = Arc::new(MockBackend {
+                available,
+                fails,
+                calls: AtomicUsize::new(0),
+            });
+            set_msm_backend::<G2>(backend.clone());
+            assert_eq!(msm::<G2>(&bases, &scalars), expected);
+            assert_eq!(backend.calls.load(Ordering::SeqCst), calls);
+        }
+
+        clear_msm_backend::<G2>();
+        assert!(msm_backend::<G2>().is_none());
+        assert_eq!(msm::<G2>(&bases, &scalars), expected);
+        Ok(())
+    }
+}
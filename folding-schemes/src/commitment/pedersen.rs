@@ -1,9 +1,16 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{AdditiveGroup, PrimeField};
 use ark_r1cs_std::{boolean::Boolean, convert::ToBitsGadget, prelude::CurveVar};
 use ark_relations::r1cs::SynthesisError;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
 use ark_std::{marker::PhantomData, rand::RngCore, UniformRand, Zero};
+use rayon::prelude::*;
+use std::sync::{Arc, OnceLock};
 
-use super::CommitmentScheme;
+use super::{msm::msm, CommitmentScheme};
 use crate::folding::circuits::CF2;
 use crate::transcript::Transcript;
 use crate::utils::vec::{vec_add, vec_scalar_mul};
@@ -16,10 +23,234 @@ pub struct Proof<C: Curve> {
     pub r_u: C::ScalarField, // blind
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+/// Maximum window size (in bits) allowed for the fixed-base tables.
+pub const MAX_TABLE_WINDOW_BITS: usize = 16;
+
+#[derive(Debug, Clone)]
 pub struct Params<C: Curve> {
     pub h: C,
     pub generators: Vec<C::Affine>,
+    /// Window size (in bits) of the fixed-base tables, if enabled through [`Params::precompute`].
+    /// This is a local setting of the prover: it is not part of the serialized parameters, so it
+    /// does not affect the public parameters hash.
+    window_bits: Option<usize>,
+    /// Fixed-base tables of the generators, built lazily on the first commitment that uses them.
+    tables: OnceLock<Arc<FixedBaseTables<C>>>,
+}
+
+/// Fixed-base tables for the Pedersen generators: for each generator `g_i` and each window `k`,
+/// it stores `g_i⋅2^(window_bits⋅k)`, so that `<g, v>` can be computed with a single bucket pass
+/// over the `window_bits`-bit digits of `v` and without any doubling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FixedBaseTables<C: Curve> {
+    window_bits: usize,
+    n_windows: usize,
+    /// `bases[i * n_windows + k] = g_i⋅2^(window_bits⋅k)`
+    bases: Vec<C::Affine>,
+}
+
+impl<C: Curve> FixedBaseTables<C> {
+    fn new(generators: &[C::Affine], window_bits: usize) -> Self {
+        let n_windows = (C::ScalarField::MODULUS_BIT_SIZE as usize).div_ceil(window_bits);
+        let bases = generators
+            .par_iter()
+            .flat_map_iter(|g| {
+                let mut acc = g.into_group();
+                let shifted = (0..n_windows)
+                    .map(|_| {
+                        let current = acc;
+                        (0..window_bits).for_each(|_| {
+                            acc.double_in_place();
+                        });
+                        current
+                    })
+                    .collect::<Vec<C>>();
+                C::normalize_batch(&shifted)
+            })
+            .collect();
+        Self {
+            window_bits,
+            n_windows,
+            bases,
+        }
+    }
+
+    /// Computes `<g, v>` using the precomputed tables. Assumes that `v.len()` is not bigger than
+    /// the number of generators.
+    fn msm(&self, v: &[C::ScalarField]) -> C {
+        self.msm_entries(v.len(), |j| (j, v[j]))
+    }
+
+    /// Computes `<g, v>` for the sparse vector `v` given by its `(index, value)` entries. Assumes
+    /// that the indexes are smaller than the number of generators.
+    fn msm_sparse(&self, v: &[(usize, C::ScalarField)]) -> C {
+        self.msm_entries(v.len(), |j| v[j])
+    }
+
+    /// Computes `Σ_j v_j⋅g_{i_j}`, where `(i_j, v_j) = entry(j)` for `j` in `0..n`.
+    fn msm_entries(&self, n: usize, entry: impl Fn(usize) -> (usize, C::ScalarField) + Sync) -> C {
+        let n_buckets = (1 << self.window_bits) - 1;
+        let chunk_size = n.div_ceil(rayon::current_num_threads()).max(1);
+        (0..n.div_ceil(chunk_size))
+            .into_par_iter()
+            .map(|c| {
+                let mut buckets = vec![C::zero(); n_buckets];
+                for j in c * chunk_size..n.min((c + 1) * chunk_size) {
+                    let (i, v_i) = entry(j);
+                    let bases = &self.bases[i * self.n_windows..];
+                    let v_i = v_i.into_bigint();
+                    for (k, base) in bases.iter().take(self.n_windows).enumerate() {
+                        let digit =
+                            window_digit(v_i.as_ref(), k * self.window_bits, self.window_bits);
+                        if digit != 0 {
+                            buckets[digit - 1] += *base;
+                        }
+                    }
+                }
+                // sum_d d⋅bucket_d, through running sums
+                let (mut running_sum, mut res) = (C::zero(), C::zero());
+                for bucket in buckets.into_iter().rev() {
+                    running_sum += bucket;
+                    res += running_sum;
+                }
+                res
+            })
+            .reduce(C::zero, |a, b| a + b)
+    }
+}
+
+/// Returns the `window_bits`-bit digit of the little-endian limbs starting at bit `offset`.
+fn window_digit(limbs: &[u64], offset: usize, window_bits: usize) -> usize {
+    let (limb, shift) = (offset / 64, offset % 64);
+    let mut digit = limbs[limb] >> shift;
+    if shift + window_bits > 64 && limb + 1 < limbs.len() {
+        digit |= limbs[limb + 1] << (64 - shift);
+    }
+    (digit & ((1 << window_bits) - 1)) as usize
+}
+
+impl<C: Curve> Params<C> {
+    pub fn new(h: C, generators: Vec<C::Affine>) -> Self {
+        Self {
+            h,
+            generators,
+            window_bits: None,
+            tables: OnceLock::new(),
+        }
+    }
+
+    /// Enables the fixed-base tables with windows of `window_bits` bits, which are then used by
+    /// `commit`, `prove` and `verify` instead of the generic MSM. The tables store
+    /// `ceil(|F|/window_bits)` points per generator, trading memory for speed.
+    ///
+    /// The tables are not serialized: after deserializing the params, they have to be enabled
+    /// again, either with this method (which builds them lazily on the first commitment) or by
+    /// deserializing with [`Params::deserialize_with_tables`] (which builds them right away).
+    pub fn precompute(&mut self, window_bits: usize) -> Result<(), Error> {
+        if window_bits == 0 || window_bits > MAX_TABLE_WINDOW_BITS {
+            return Err(Error::OutOfBounds);
+        }
+        if self.window_bits != Some(window_bits) {
+            self.window_bits = Some(window_bits);
+            self.tables = OnceLock::new();
+        }
+        Ok(())
+    }
+
+    /// Window size of the fixed-base tables, if they are enabled.
+    pub fn window_bits(&self) -> Option<usize> {
+        self.window_bits
+    }
+
+    /// Deserializes the params and rebuilds their fixed-base tables with windows of
+    /// `window_bits` bits, since the tables are not part of the serialization.
+    pub fn deserialize_with_tables<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+        window_bits: usize,
+    ) -> Result<Self, Error> {
+        let mut params = Self::deserialize_with_mode(reader, compress, validate)?;
+        params.precompute(window_bits)?;
+        params.tables(window_bits);
+        Ok(params)
+    }
+
+    /// Returns the fixed-base tables with windows of `window_bits` bits, building them if they
+    /// were not built yet.
+    fn tables(&self, window_bits: usize) -> &FixedBaseTables<C> {
+        self.tables
+            .get_or_init(|| Arc::new(FixedBaseTables::new(&self.generators, window_bits)))
+    }
+
+    /// Computes `<g, v>`, using the fixed-base tables if they are enabled. Assumes that
+    /// `v.len()` is not bigger than the number of generators.
+    fn msm(&self, v: &[C::ScalarField]) -> C {
+        match self.window_bits {
+            Some(window_bits) => self.tables(window_bits).msm(v),
+            // the callers already ensured that lengths match
+            None => msm(&self.generators[..v.len()], v),
+        }
+    }
+
+    /// Computes `<g, v>` for the sparse vector `v` given by its `(index, value)` entries, skipping
+    /// the zero ones and using the fixed-base tables if they are enabled. Assumes that the indexes
+    /// are smaller than the number of generators.
+    fn msm_sparse(&self, v: &[(usize, C::ScalarField)]) -> C {
+        let v: Vec<(usize, C::ScalarField)> = v
+            .iter()
+            .filter(|(_, v_i)| !v_i.is_zero())
+            .copied()
+            .collect();
+        match self.window_bits {
+            Some(window_bits) => self.tables(window_bits).msm_sparse(&v),
+            None => {
+                let (bases, scalars): (Vec<C::Affine>, Vec<C::ScalarField>) =
+                    v.iter().map(|(i, v_i)| (self.generators[*i], *v_i)).unzip();
+                msm(&bases, &scalars)
+            }
+        }
+    }
+}
+
+impl<C: Curve> PartialEq for Params<C> {
+    fn eq(&self, other: &Self) -> bool {
+        // the tables are derived from the generators, so they are not compared
+        self.h == other.h && self.generators == other.generators
+    }
+}
+impl<C: Curve> Eq for Params<C> {}
+
+impl<C: Curve> CanonicalSerialize for Params<C> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.h.serialize_with_mode(&mut writer, compress)?;
+        self.generators.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.h.serialized_size(compress) + self.generators.serialized_size(compress)
+    }
+}
+impl<C: Curve> Valid for Params<C> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.h.check()?;
+        self.generators.check()
+    }
+}
+impl<C: Curve> CanonicalDeserialize for Params<C> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let h = C::deserialize_with_mode(&mut reader, compress, validate)?;
+        let generators = Vec::<C::Affine>::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self::new(h, generators))
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -42,6 +273,10 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
         false
     }
 
+    fn max_len(params: &Self::ProverParams) -> usize {
+        params.generators.len()
+    }
+
     fn setup(
         mut rng: impl RngCore,
         len: usize,
@@ -49,10 +284,7 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
         let generators: Vec<C::Affine> = std::iter::repeat_with(|| C::Affine::rand(&mut rng))
             .take(len.next_power_of_two())
             .collect();
-        let p = Params::<C> {
-            h: C::rand(&mut rng),
-            generators,
-        };
+        let p = Params::<C>::new(C::rand(&mut rng), generators);
         Ok((p.clone(), p))
     }
 
@@ -69,11 +301,35 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
         }
 
         // h⋅r + <g, v>
-        // use msm_unchecked because we already ensured at the if that lengths match
         if !H {
-            return Ok(C::msm_unchecked(&params.generators[..v.len()], v));
+            return Ok(params.msm(v));
+        }
+        Ok(params.h.mul(r) + params.msm(v))
+    }
+
+    /// Commits to the sparse vector skipping its zero entries, so its cost depends on the number
+    /// of non-zero entries instead of on `len`.
+    fn commit_sparse(
+        params: &Self::ProverParams,
+        v: &[(usize, C::ScalarField)],
+        len: usize,
+        r: &C::ScalarField, // blinding factor
+    ) -> Result<C, Error> {
+        if params.generators.len() < len {
+            return Err(Error::PedersenParamsLen(params.generators.len(), len));
+        }
+        if v.iter().any(|(i, _)| *i >= len) {
+            return Err(Error::OutOfBounds);
+        }
+        if !H && (!r.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+
+        // h⋅r + <g, v>
+        if !H {
+            return Ok(params.msm_sparse(v));
         }
-        Ok(params.h.mul(r) + C::msm_unchecked(&params.generators[..v.len()], v))
+        Ok(params.h.mul(r) + params.msm_sparse(v))
     }
 
     fn prove(
@@ -88,9 +344,11 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
         let r1 = transcript.get_challenge();
         let d = transcript.get_challenges(v.len());
 
+        if params.generators.len() < d.len() {
+            return Err(Error::PedersenParamsLen(params.generators.len(), d.len()));
+        }
         // R = h⋅r_1 + <g, d>
-        // use msm_unchecked because we already ensured at the if that lengths match
-        let mut R: C = C::msm_unchecked(&params.generators[..d.len()], &d);
+        let mut R: C = params.msm(&d);
         if H {
             R += params.h.mul(r1);
         }
@@ -162,8 +420,7 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
 
         // check that: R + cm⋅e == h⋅r_u + <g, u>
         let lhs = proof.R + cm.mul(e);
-        // use msm_unchecked because we already ensured at the if that lengths match
-        let mut rhs = C::msm_unchecked(&params.generators[..proof.u.len()], &proof.u);
+        let mut rhs = params.msm(&proof.u);
         if H {
             rhs += params.h.mul(proof.r_u);
         }
@@ -256,6 +513,96 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_pedersen_fixed_base_tables() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        let n: usize = 100;
+        let (params, _) = Pedersen::<Projective>::setup(&mut rng, n)?;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let mut v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(n)
+            .collect();
+        // include the edge cases of the digits decomposition
+        v[0] = Fr::zero();
+        v[1] = -Fr::from(1);
+        let cm = Pedersen::<Projective>::commit(&params, &v, &Fr::zero())?;
+
+        for window_bits in [1, 3, 4, 8, 13] {
+            let mut params_tables = params.clone();
+            params_tables.precompute(window_bits)?;
+            assert_eq!(params_tables.window_bits(), Some(window_bits));
+            assert_eq!(params_tables, params);
+
+            // commitments (of full and partial length vectors) match the ones without tables
+            assert_eq!(
+                Pedersen::<Projective>::commit(&params_tables, &v, &Fr::zero())?,
+                cm
+            );
+            assert_eq!(
+                Pedersen::<Projective>::commit(&params_tables, &v[..7], &Fr::zero())?,
+                Pedersen::<Projective>::commit(&params, &v[..7], &Fr::zero())?
+            );
+
+            // proofs computed with the tables verify against params without them
+            let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let proof = Pedersen::<Projective>::prove(
+                &params_tables,
+                &mut transcript_p,
+                &cm,
+                &v,
+                &Fr::zero(),
+                None,
+            )?;
+            Pedersen::<Projective>::verify(&params, &mut transcript_v, &cm, &proof)?;
+
+            // the tables are not serialized, and the serialization matches the one without them
+            let mut bytes = vec![];
+            params_tables.serialize_compressed(&mut bytes)?;
+            let mut bytes_no_tables = vec![];
+            params.serialize_compressed(&mut bytes_no_tables)?;
+            assert_eq!(bytes, bytes_no_tables);
+            let mut params_deserialized = Params::<Projective>::deserialize_compressed(&bytes[..])?;
+            assert_eq!(params_deserialized.window_bits(), None);
+            assert_eq!(params_deserialized, params);
+            // re-enabling them after deserialization gives the same commitments
+            params_deserialized.precompute(window_bits)?;
+            assert_eq!(
+                Pedersen::<Projective>::commit(&params_deserialized, &v, &Fr::zero())?,
+                cm
+            );
+            // or they can be rebuilt when deserializing
+            let params_deserialized = Params::<Projective>::deserialize_with_tables(
+                &bytes[..],
+                Compress::Yes,
+                Validate::Yes,
+                window_bits,
+            )?;
+            assert_eq!(params_deserialized.window_bits(), Some(window_bits));
+            assert_eq!(
+                params_deserialized.tables.get().map(|t| t.window_bits),
+                Some(window_bits)
+            );
+            assert_eq!(
+                Pedersen::<Projective>::commit(&params_deserialized, &v, &Fr::zero())?,
+                cm
+            );
+        }
+
+        let mut params_tables = params.clone();
+        assert!(matches!(
+            params_tables.precompute(0),
+            Err(Error::OutOfBounds)
+        ));
+        assert!(matches!(
+            params_tables.precompute(MAX_TABLE_WINDOW_BITS + 1),
+            Err(Error::OutOfBounds)
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_pedersen_circuit() -> Result<(), Error> {
         let _ = test_pedersen_circuit_opt::<false>()?;
@@ -0,0 +1,375 @@
+/// This file implements a loader of the KZG SRS from the powers of tau files (`.ptau`) generated by
+/// snarkjs, such as the ones from the Perpetual Powers of Tau ceremony (used by Hermez/Polygon and
+/// others), so that the KZG parameters used in production do not come from a local RNG.
+///
+/// The `.ptau` format is a binary file with the magic `ptau`, a version, and a list of sections,
+/// each one with a type and a size. Only the following sections are used:
+/// - 1 (header): field element size `n8`, base field modulus, `power` and ceremony power.
+/// - 2 (tauG1): the `2^power⋅2-1` points `τ^i⋅G1`.
+/// - 3 (tauG2): the `2^power` points `τ^i⋅G2`.
+///
+/// The coordinates of the points are stored in little-endian Montgomery form.
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{BigInt, BigInteger, PrimeField};
+use ark_poly_commit::kzg10::VerifierKey;
+use ark_std::borrow::Cow;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use super::kzg::ProverKey;
+use crate::Error;
+
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+const PTAU_VERSION: u32 = 1;
+const SECTION_HEADER: u32 = 1;
+const SECTION_TAU_G1: u32 = 2;
+const SECTION_TAU_G2: u32 = 3;
+
+/// Size in bytes of the BN254 base field elements
+const N8: usize = 32;
+const G1_SIZE: usize = 2 * N8;
+const G2_SIZE: usize = 4 * N8;
+
+/// Reads the KZG SRS over BN254 from a snarkjs `.ptau` file. See [`kzg_srs_from_ptau`].
+pub fn kzg_srs_from_ptau_file(
+    path: impl AsRef<Path>,
+    len: Option<usize>,
+) -> Result<(ProverKey<'static, G1Projective>, VerifierKey<Bn254>), Error> {
+    kzg_srs_from_ptau(BufReader::new(File::open(path)?), len)
+}
+
+/// Reads the KZG SRS over BN254 from the contents of a snarkjs `.ptau` file, returning the KZG
+/// prover and verifier keys. If `len` is given, only the first `len` powers of `τ⋅G1` are loaded,
+/// returning an error if the file contains less than that, otherwise all of them are loaded.
+///
+/// All the loaded points are checked to be on the curve and in the prime order subgroup, and the
+/// G1 and G2 powers are checked to be consistent through a pairing check.
+pub fn kzg_srs_from_ptau<R: Read>(
+    mut reader: R,
+    len: Option<usize>,
+) -> Result<(ProverKey<'static, G1Projective>, VerifierKey<Bn254>), Error> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != PTAU_MAGIC {
+        return Err(Error::InvalidPtau("wrong magic".to_string()));
+    }
+    let version = read_u32(&mut reader)?;
+    if version != PTAU_VERSION {
+        return Err(Error::InvalidPtau(format!(
+            "unsupported version {}",
+            version
+        )));
+    }
+    let n_sections = read_u32(&mut reader)?;
+
+    let mut power: Option<u32> = None;
+    let mut powers_of_g: Option<Vec<G1Affine>> = None;
+    let mut powers_of_h: Option<Vec<G2Affine>> = None;
+    for _ in 0..n_sections {
+        let section = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        let mut section_reader = (&mut reader).take(size);
+        match section {
+            SECTION_HEADER => {
+                let n8 = read_u32(&mut section_reader)? as usize;
+                if n8 != N8 {
+                    return Err(Error::InvalidPtau(format!("unsupported n8 {}", n8)));
+                }
+                let mut q = vec![0u8; n8];
+                section_reader.read_exact(&mut q)?;
+                if q != Fq::MODULUS.to_bytes_le() {
+                    return Err(Error::InvalidPtau("the curve is not BN254".to_string()));
+                }
+                power = Some(read_u32(&mut section_reader)?);
+            }
+            SECTION_TAU_G1 => {
+                let power = power.ok_or(Error::InvalidPtau("missing header".to_string()))?;
+                let n_points = (1_usize << power) * 2 - 1;
+                let len = len.unwrap_or(n_points);
+                if len > n_points {
+                    return Err(Error::CommitmentParamsLen(n_points, len));
+                }
+                powers_of_g = Some(read_points(
+                    &mut section_reader,
+                    len,
+                    G1_SIZE,
+                    g1_from_bytes,
+                )?);
+            }
+            SECTION_TAU_G2 => {
+                // only τ^0⋅G2 and τ^1⋅G2 are used by the KZG verifier
+                powers_of_h = Some(read_points(&mut section_reader, 2, G2_SIZE, g2_from_bytes)?);
+            }
+            _ => {}
+        }
+        // skip the rest of the section
+        std::io::copy(&mut section_reader, &mut std::io::sink())?;
+        if section_reader.limit() != 0 {
+            return Err(Error::InvalidPtau("truncated section".to_string()));
+        }
+    }
+
+    let powers_of_g = powers_of_g.ok_or(Error::InvalidPtau("missing tauG1 section".to_string()))?;
+    let powers_of_h = powers_of_h.ok_or(Error::InvalidPtau("missing tauG2 section".to_string()))?;
+
+    // τ^0 must be the generators, and the powers of G1 and G2 must use the same τ, ie.
+    // e(τ⋅G1, G2) == e(G1, τ⋅G2)
+    let (g, h, beta_h) = (G1Affine::generator(), powers_of_h[0], powers_of_h[1]);
+    if powers_of_g.first() != Some(&g) || h != G2Affine::generator() {
+        return Err(Error::InvalidPtau("τ^0 is not the generator".to_string()));
+    }
+    if powers_of_g.len() > 1 && Bn254::pairing(powers_of_g[1], h) != Bn254::pairing(g, beta_h) {
+        return Err(Error::InvalidPtau(
+            "the powers of G1 and G2 are not consistent".to_string(),
+        ));
+    }
+
+    let pk = ProverKey::<G1Projective> {
+        powers_of_g: Cow::Owned(powers_of_g),
+    };
+    let vk = VerifierKey::<Bn254> {
+        g,
+        // only used for hiding commitments, which are not supported by KZG
+        gamma_g: G1Affine::zero(),
+        h,
+        beta_h,
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    };
+    Ok((pk, vk))
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, Error> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, Error> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads `n` points of `point_size` bytes each, decoding (and checking) them in parallel.
+fn read_points<P: Send>(
+    reader: &mut impl Read,
+    n: usize,
+    point_size: usize,
+    from_bytes: impl Fn(&[u8]) -> Result<P, Error> + Sync,
+) -> Result<Vec<P>, Error> {
+    let mut bytes = vec![0u8; n * point_size];
+    reader.read_exact(&mut bytes)?;
+    bytes.par_chunks(point_size).map(from_bytes).collect()
+}
+
+/// Reads a base field element stored in little-endian Montgomery form.
+fn fq_from_bytes(bytes: &[u8]) -> Result<Fq, Error> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().map_err(|_| Error::OutOfBounds)?);
+    }
+    let montgomery = BigInt::new(limbs);
+    if montgomery >= Fq::MODULUS {
+        return Err(Error::InvalidPtau(
+            "field element is not reduced".to_string(),
+        ));
+    }
+    Ok(Fq::new_unchecked(montgomery))
+}
+
+fn g1_from_bytes(bytes: &[u8]) -> Result<G1Affine, Error> {
+    let p = G1Affine::new_unchecked(fq_from_bytes(&bytes[..N8])?, fq_from_bytes(&bytes[N8..])?);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPtau("invalid G1 point".to_string()));
+    }
+    Ok(p)
+}
+
+fn g2_from_bytes(bytes: &[u8]) -> Result<G2Affine, Error> {
+    let x = Fq2::new(
+        fq_from_bytes(&bytes[..N8])?,
+        fq_from_bytes(&bytes[N8..2 * N8])?,
+    );
+    let y = Fq2::new(
+        fq_from_bytes(&bytes[2 * N8..3 * N8])?,
+        fq_from_bytes(&bytes[3 * N8..])?,
+    );
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPtau("invalid G2 point".to_string()));
+    }
+    Ok(p)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Fr, G2Projective};
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_ec::{scalar_mul::ScalarMul, PrimeGroup};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_std::{UniformRand, Zero};
+
+    use super::*;
+    use crate::arith::Arith;
+    use crate::commitment::{
+        kzg::{KZG, SRS_CHECK_SECURITY_BITS},
+        pedersen::Pedersen,
+        CommitmentScheme,
+    };
+    use crate::folding::nova::{get_r1cs, Nova, PreprocessorParam};
+    use crate::frontend::{utils::CubicFCircuit, FCircuit};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    fn write_fq(bytes: &mut Vec<u8>, x: Fq) {
+        // Montgomery form, as snarkjs does
+        bytes.extend(x.0.to_bytes_le());
+    }
+
+    fn write_section(bytes: &mut Vec<u8>, section: u32, data: &[u8]) {
+        bytes.extend(section.to_le_bytes());
+        bytes.extend((data.len() as u64).to_le_bytes());
+        bytes.extend(data);
+    }
+
+    /// Generates a `.ptau` file with the given τ, in the same format as snarkjs
+    pub(crate) fn ptau_fixture(tau: Fr, power: u32) -> Vec<u8> {
+        let n_g1 = (1 << power) * 2 - 1;
+        let powers_of_tau = std::iter::successors(Some(Fr::from(1)), |p| Some(*p * tau))
+            .take(n_g1)
+            .collect::<Vec<_>>();
+
+        let mut header = vec![];
+        header.extend((N8 as u32).to_le_bytes());
+        header.extend(Fq::MODULUS.to_bytes_le());
+        header.extend(power.to_le_bytes());
+        header.extend(power.to_le_bytes()); // ceremony power
+
+        let mut tau_g1 = vec![];
+        for p in G1Projective::generator().batch_mul(&powers_of_tau) {
+            write_fq(&mut tau_g1, p.x);
+            write_fq(&mut tau_g1, p.y);
+        }
+        let mut tau_g2 = vec![];
+        for p in G2Projective::generator().batch_mul(&powers_of_tau[..1 << power]) {
+            for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1] {
+                write_fq(&mut tau_g2, c);
+            }
+        }
+
+        let mut bytes = PTAU_MAGIC.to_vec();
+        bytes.extend(PTAU_VERSION.to_le_bytes());
+        bytes.extend(4_u32.to_le_bytes()); // number of sections
+        write_section(&mut bytes, SECTION_HEADER, &header);
+        write_section(&mut bytes, SECTION_TAU_G1, &tau_g1);
+        write_section(&mut bytes, SECTION_TAU_G2, &tau_g2);
+        // contributions section, which is skipped by the loader
+        write_section(&mut bytes, 7, &[0u8; 10]);
+        bytes
+    }
+
+    #[test]
+    fn test_kzg_srs_from_ptau() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let tau = Fr::rand(&mut rng);
+        let power = 4;
+        let ptau = ptau_fixture(tau, power);
+
+        let (pk, vk) = kzg_srs_from_ptau(&ptau[..], None)?;
+        assert_eq!(pk.powers_of_g.len(), (1 << power) * 2 - 1);
+        assert_eq!(
+            pk.powers_of_g[3],
+            (G1Projective::generator() * tau * tau * tau).into()
+        );
+        assert_eq!(vk.beta_h, (G2Projective::generator() * tau).into());
+
+        // only load the needed powers
+        let (pk_short, _) = kzg_srs_from_ptau(&ptau[..], Some(16))?;
+        assert_eq!(pk_short.powers_of_g[..], pk.powers_of_g[..16]);
+        assert!(matches!(
+            kzg_srs_from_ptau(&ptau[..], Some(1 << (power + 1))),
+            Err(Error::CommitmentParamsLen(..))
+        ));
+
+        // KZG proofs using the loaded SRS verify
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(16)
+            .collect();
+        let cm = KZG::<Bn254>::commit(&pk, &v, &Fr::zero())?;
+        let proof = KZG::<Bn254>::prove(&pk, &mut transcript_p, &cm, &v, &Fr::zero(), None)?;
+        KZG::<Bn254>::verify(&vk, &mut transcript_v, &cm, &proof)?;
+
+        // a point that is not on the curve is rejected
+        let mut bad_ptau = ptau.clone();
+        let tau_g1_start = bad_ptau.len() - 10 - 12 - (1 << power) * G2_SIZE - 12;
+        let tau_g1_start = tau_g1_start - ((1 << power) * 2 - 1) * G1_SIZE;
+        bad_ptau[tau_g1_start + G1_SIZE + N8] ^= 1;
+        assert!(matches!(
+            kzg_srs_from_ptau(&bad_ptau[..], None),
+            Err(Error::InvalidPtau(_))
+        ));
+        // a file whose G1 and G2 powers use different τ is rejected
+        let mut mixed_ptau = ptau_fixture(tau + Fr::from(1), power);
+        let g2_section_len = 12 + (1 << power) * G2_SIZE + 12 + 10;
+        let ptau_g2 = &ptau[ptau.len() - g2_section_len..];
+        let mixed_len = mixed_ptau.len();
+        mixed_ptau[mixed_len - g2_section_len..].copy_from_slice(ptau_g2);
+        assert!(matches!(
+            kzg_srs_from_ptau(&mixed_ptau[..], None),
+            Err(Error::InvalidPtau(_))
+        ));
+        // wrong magic
+        assert!(matches!(
+            kzg_srs_from_ptau(&b"zkey"[..], None),
+            Err(Error::InvalidPtau(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_nova_with_ptau_srs() -> Result<(), Error> {
+        type N = Nova<
+            G1Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // size the ceremony to the circuit
+        let (r1cs, _) = get_r1cs::<G1Projective, Projective2, _>(&poseidon_config, F_circuit)?;
+        let cs_len = r1cs.n_constraints().max(r1cs.n_witnesses());
+        let power = cs_len.next_power_of_two().ilog2();
+        let tau = Fr::rand(&mut rng);
+
+        let srs = kzg_srs_from_ptau(&ptau_fixture(tau, power)[..], None)?;
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), F_circuit)
+            .with_verified_kzg_srs(srs, SRS_CHECK_SECURITY_BITS, &mut rng)?;
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, vec![Fr::from(3_u32)])?;
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+
+        // an SRS smaller than the circuit is rejected at preprocess
+        let srs = kzg_srs_from_ptau(&ptau_fixture(tau, power - 1)[..], None)?;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit).with_kzg_srs(srs);
+        assert!(matches!(
+            N::preprocess(&mut rng, &prep_param),
+            Err(Error::CommitmentParamsLen(..))
+        ));
+        Ok(())
+    }
+}
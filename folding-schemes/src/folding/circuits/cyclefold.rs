@@ -10,10 +10,12 @@ use ark_r1cs_std::{
     eq::EqGadget,
     fields::fp::FpVar,
     prelude::CurveVar,
+    select::CondSelectGadget,
     R1CSVar,
 };
 use ark_relations::r1cs::{
     ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, Namespace, SynthesisError,
+    SynthesisMode,
 };
 use ark_std::fmt::Debug;
 use ark_std::rand::RngCore;
@@ -28,7 +30,10 @@ use crate::arith::{
 use crate::commitment::CommitmentScheme;
 use crate::constants::NOVA_N_BITS_RO;
 use crate::folding::{
-    nova::nifs::{nova::NIFS, NIFSTrait},
+    nova::nifs::{
+        nova::{ProverScratch, NIFS},
+        NIFSTrait,
+    },
     traits::InputizeNonNative,
 };
 use crate::transcript::{AbsorbNonNative, AbsorbNonNativeGadget, Transcript, TranscriptVar};
@@ -324,6 +329,15 @@ pub trait CycleFoldConfig {
     /// `N_INPUT_POINTS` specifies the number of input points that are folded in
     /// [`CycleFoldCircuit`] via random linear combinations.
     const N_INPUT_POINTS: usize;
+    /// `N_RLCS` is the number of independent random linear combinations, of
+    /// `N_INPUT_POINTS` points each and all of them with the same randomness
+    /// `r`, that are checked by a single [`CycleFoldCircuit`] instance.
+    ///
+    /// E.g. Nova sets it to 2 in order to check the folding of both `cmW` and
+    /// `cmE` in a single CycleFold instance, which saves a commitment to a
+    /// CycleFold witness and to a cross term per step, and one in-circuit
+    /// CycleFold NIFS verification in the augmented circuit.
+    const N_RLCS: usize = 1;
     /// `RANDOMNESS_BIT_LENGTH` is the (maximum) bit length of randomness `r`.
     const RANDOMNESS_BIT_LENGTH: usize;
     /// `FIELD_CAPACITY` is the maximum number of bits that can be stored in a
@@ -333,18 +347,62 @@ pub trait CycleFoldConfig {
     /// `RANDOMNESS_BIT_LENGTH / FIELD_CAPACITY` field elements to represent `r`
     /// compactly in-circuit.
     const FIELD_CAPACITY: usize = CF2::<Self::C>::MODULUS_BIT_SIZE as usize - 1;
+    /// `SCALAR_MUL_WINDOW_SIZE` is the window size (in bits) used by the
+    /// in-circuit scalar multiplications of [`CycleFoldCircuit`].
+    ///
+    /// By default (and for any value larger than 1), the scalar
+    /// multiplications use [`windowed_scalar_mul_le`] with windows of 2 bits,
+    /// which trades doublings and additions for lookups in a table of
+    /// `2^SCALAR_MUL_WINDOW_SIZE` multiples of the point. A value of 1 uses
+    /// the double-and-add of `CurveVar::scalar_mul_le` instead. Which one
+    /// results in less constraints depends on the curve's gadget, so
+    /// [`CycleFoldCircuit::size`] can be used to compare them.
+    const SCALAR_MUL_WINDOW_SIZE: usize = 2;
 
     /// Public inputs length for the CycleFoldCircuit.
-    /// * For Nova this is: `|[r, p1.x,y, p2.x,y, p3.x,y]|`
-    /// * In general, `|[r, (p_i.x,y)*n_points, p_folded.x,y]|`.
+    /// * For a single random linear combination of 2 points this is:
+    ///   `|[r, p1.x,y, p2.x,y, p3.x,y]|`
+    /// * In general, `|[r, ((p_i.x,y)*n_points, p_folded.x,y)*N_RLCS]|`.
     ///
     /// Thus, `IO_LEN` is:
-    /// `RANDOMNESS_BIT_LENGTH / FIELD_CAPACITY  + 2 * N_INPUT_POINTS + 2`
+    /// `RANDOMNESS_BIT_LENGTH / FIELD_CAPACITY  + N_RLCS * (2 * N_INPUT_POINTS + 2)`
     const IO_LEN: usize = {
-        Self::RANDOMNESS_BIT_LENGTH.div_ceil(Self::FIELD_CAPACITY) + 2 * Self::N_INPUT_POINTS + 2
+        Self::RANDOMNESS_BIT_LENGTH.div_ceil(Self::FIELD_CAPACITY)
+            + Self::N_RLCS * (2 * Self::N_INPUT_POINTS + 2)
     };
 
     type C: Curve;
+
+    /// Public inputs length for a CycleFoldCircuit that folds `n_points`
+    /// points in each of its random linear combinations, for the schemes where
+    /// the number of points is only known at runtime (e.g. HyperNova with
+    /// runtime `mu` & `nu`).
+    fn io_len(n_points: usize) -> usize {
+        Self::RANDOMNESS_BIT_LENGTH.div_ceil(Self::FIELD_CAPACITY)
+            + Self::N_RLCS * (2 * n_points + 2)
+    }
+}
+
+/// Generic [`CycleFoldConfig`] that checks `N_POINTS` group operations (ie. the random linear
+/// combination of `N_POINTS` points) per CycleFold instance, with a randomness of
+/// `NOVA_N_BITS_RO` bits. `N_RLCS` (1 by default) sets how many of these random linear
+/// combinations, sharing the same randomness, are checked by the same instance (see
+/// [`CycleFoldConfig::N_RLCS`]).
+///
+/// It can be used by custom folding variants that need a different number of points than the
+/// ones of the folding schemes implemented in this crate. When the number of points is only known
+/// at runtime, use it together with [`CycleFoldCircuit::empty_with_n_points`].
+pub struct GenericCycleFoldConfig<C: Curve, const N_POINTS: usize, const N_RLCS: usize = 1> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve, const N_POINTS: usize, const N_RLCS: usize> CycleFoldConfig
+    for GenericCycleFoldConfig<C, N_POINTS, N_RLCS>
+{
+    const RANDOMNESS_BIT_LENGTH: usize = NOVA_N_BITS_RO;
+    const N_INPUT_POINTS: usize = N_POINTS;
+    const N_RLCS: usize = N_RLCS;
+    type C = C;
 }
 
 /// CycleFoldCircuit contains the constraints that check the correct fold of the committed
@@ -355,18 +413,81 @@ pub struct CycleFoldCircuit<CFG: CycleFoldConfig> {
     /// r_bits is the bit representation of the r whose powers are used in the
     /// random-linear-combination inside the CycleFoldCircuit
     pub r_bits: Option<Vec<bool>>,
-    /// points to be folded in the CycleFoldCircuit
+    /// points to be folded in the CycleFoldCircuit. When `CFG::N_RLCS > 1`,
+    /// this contains the points of each random linear combination one after
+    /// the other.
     pub points: Option<Vec<CFG::C>>,
 }
 
 impl<CFG: CycleFoldConfig> CycleFoldCircuit<CFG> {
-    /// n_points indicates the number of points being folded in the CycleFoldCircuit
+    /// returns an empty CycleFoldCircuit that folds `CFG::N_INPUT_POINTS` points
     pub fn empty() -> Self {
         Self {
             r_bits: None,
             points: None,
         }
     }
+
+    /// returns an empty CycleFoldCircuit that folds `n_points` points in each
+    /// random linear combination, which overrides `CFG::N_INPUT_POINTS`
+    pub fn empty_with_n_points(n_points: usize) -> Self {
+        Self {
+            r_bits: None,
+            points: Some(vec![CFG::C::zero(); CFG::N_RLCS * n_points]),
+        }
+    }
+
+    /// returns the number of constraints and the number of witness variables
+    /// of the CycleFoldCircuit that folds `n_points` points in each random
+    /// linear combination
+    pub fn size(n_points: usize) -> Result<(usize, usize), Error> {
+        let cs = ConstraintSystem::<CF2<CFG::C>>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        Self::empty_with_n_points(n_points).generate_constraints(cs.clone())?;
+        cs.finalize();
+        Ok((cs.num_constraints(), cs.num_witness_variables()))
+    }
+}
+
+/// Computes `bits * p` in-circuit, where `bits` is the little-endian
+/// representation of the scalar, processing `window_size` bits at a time:
+/// for each window (starting from the most significant one), the accumulator
+/// is doubled `window_size` times, and the multiple of `p` selected by the
+/// window bits from a precomputed table is added to it.
+pub fn windowed_scalar_mul_le<C: Curve>(
+    p: &C::Var,
+    bits: &[Boolean<CF2<C>>],
+    window_size: usize,
+) -> Result<C::Var, SynthesisError> {
+    if window_size == 0 {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    // table[j] = j * p, for j in 0..2^window_size
+    let mut table = vec![C::Var::zero(), p.clone()];
+    for j in 2..1 << window_size {
+        table.push(table[j - 1].clone() + p);
+    }
+
+    let mut acc: Option<C::Var> = None;
+    for window in bits.chunks(window_size).rev() {
+        // `conditionally_select_power_of_two_vector` takes the position in
+        // big-endian, and the (most significant) window may be shorter than
+        // `window_size`, in which case it is padded with zeros
+        let mut position = vec![Boolean::FALSE; window_size - window.len()];
+        position.extend(window.iter().rev().cloned());
+        let selected = C::Var::conditionally_select_power_of_two_vector(&position, &table)?;
+
+        acc = Some(match acc {
+            None => selected,
+            Some(mut acc) => {
+                for _ in 0..window.len() {
+                    acc.double_in_place()?;
+                }
+                acc + selected
+            }
+        });
+    }
+    Ok(acc.unwrap_or_else(C::Var::zero))
 }
 
 impl<CFG: CycleFoldConfig> ConstraintSynthesizer<CF2<CFG::C>> for CycleFoldCircuit<CFG> {
@@ -382,15 +503,18 @@ impl<CFG: CycleFoldConfig> ConstraintSynthesizer<CF2<CFG::C>> for CycleFoldCircu
         let points = Vec::<<CFG::C as Curve>::Var>::new_witness(cs.clone(), || {
             Ok(self
                 .points
-                .unwrap_or(vec![CFG::C::zero(); CFG::N_INPUT_POINTS]))
+                .unwrap_or(vec![CFG::C::zero(); CFG::N_RLCS * CFG::N_INPUT_POINTS]))
         })?;
 
-        #[cfg(test)]
-        {
-            assert_eq!(CFG::N_INPUT_POINTS, points.len());
-            assert_eq!(CFG::RANDOMNESS_BIT_LENGTH, r_bits.len());
+        // number of points of each random linear combination
+        let n_points = points.len() / CFG::N_RLCS;
+        if n_points == 0 || points.len() % CFG::N_RLCS != 0 {
+            return Err(SynthesisError::Unsatisfiable);
         }
 
+        #[cfg(test)]
+        assert_eq!(CFG::RANDOMNESS_BIT_LENGTH, r_bits.len());
+
         // Fold the original points of the instances natively in CycleFold.
         // In Nova,
         // - for the cmW we're computing: U_i1.cmW = U_i.cmW + r * u_i.cmW
@@ -400,36 +524,42 @@ impl<CFG: CycleFoldConfig> ConstraintSynthesizer<CF2<CFG::C>> for CycleFoldCircu
         // P_folded = p_0 + r * P_1 + r^2 * P_2 + r^3 * P_3 + ... + r^{n-2} * P_{n-2} + r^{n-1} * P_{n-1}
         // so in order to do it more efficiently (less constraints) we do
         // P_folded = (((P_{n-1} * r + P_{n-2}) * r + P_{n-3})... ) * r + P_0
-        let mut p_folded = points[CFG::N_INPUT_POINTS - 1].clone();
-        for i in (0..CFG::N_INPUT_POINTS - 1).rev() {
-            p_folded = p_folded.scalar_mul_le(r_bits.iter())? + points[i].clone();
-        }
-
+        // for each of the `CFG::N_RLCS` random linear combinations.
+        //
         // Check that the points coordinates are placed as the public input x:
-        // In Nova, this is: x == [r, p1, p2, p3] (wheere p3 is the p_folded).
+        // For a single random linear combination of 2 points, this is: x == [r, p1, p2, p3] (where
+        // p3 is the p_folded).
         // In multifolding schemes such as HyperNova, this is:
         // computed_x = [r, p_0, p_1, p_2, ..., p_n, p_folded],
         // where each p_i is in fact p_i.to_constraint_field()
+        // In Nova, where the folds of cmW and cmE are checked in the same instance, this is:
+        // computed_x = [r, U_i.cmW, u_i.cmW, U_i1.cmW, U_i.cmE, cmT, U_i1.cmE]
         let r_fp = r_bits
             .chunks(CF2::<CFG::C>::MODULUS_BIT_SIZE as usize - 1)
             .map(Boolean::le_bits_to_fp)
             .collect::<Result<Vec<_>, _>>()?;
-        let points_aux = points
-            .iter()
-            .map(|p_i| Ok(p_i.to_constraint_field()?[..2].to_vec()))
-            .collect::<Result<Vec<_>, SynthesisError>>()?
-            .into_iter()
-            .flatten()
-            .collect();
-
-        let x = [
-            r_fp,
-            points_aux,
-            p_folded.to_constraint_field()?[..2].to_vec(),
-        ]
-        .concat();
+        let mut x = r_fp;
+        for points in points.chunks(n_points) {
+            let mut p_folded = points[n_points - 1].clone();
+            for i in (0..n_points - 1).rev() {
+                let p_r = if CFG::SCALAR_MUL_WINDOW_SIZE > 1 {
+                    windowed_scalar_mul_le::<CFG::C>(
+                        &p_folded,
+                        &r_bits,
+                        CFG::SCALAR_MUL_WINDOW_SIZE,
+                    )?
+                } else {
+                    p_folded.scalar_mul_le(r_bits.iter())?
+                };
+                p_folded = p_r + points[i].clone();
+            }
+
+            for p_i in points.iter().chain([&p_folded]) {
+                x.extend_from_slice(&p_i.to_constraint_field()?[..2]);
+            }
+        }
         #[cfg(test)]
-        assert_eq!(x.len(), CFG::IO_LEN); // non-constrained sanity check
+        assert_eq!(x.len(), CFG::io_len(n_points)); // non-constrained sanity check
 
         // This line "converts" `x` from a witness to a public input.
         // Instead of directly modifying the constraint system, we explicitly
@@ -459,20 +589,18 @@ pub struct CycleFoldNIFS<C2: Curve, CS2: CommitmentScheme<C2, H>, const H: bool
     _cs: PhantomData<CS2>,
 }
 impl<C2: Curve, CS2: CommitmentScheme<C2, H>, const H: bool> CycleFoldNIFS<C2, CS2, H> {
+    #[allow(clippy::ptr_arg)]
     fn prove(
         cf_r_Fq: C2::ScalarField, // C2::Fr==C1::Fq
         cf_W_i: &CycleFoldWitness<C2>,
         cf_U_i: &CycleFoldCommittedInstance<C2>,
         cf_w_i: &CycleFoldWitness<C2>,
         cf_u_i: &CycleFoldCommittedInstance<C2>,
-        aux_p: &[C2::ScalarField], // = cf_T
-        aux_v: C2,                 // = cf_cmT
+        aux_p: &Vec<C2::ScalarField>, // = cf_T
+        aux_v: C2,                    // = cf_cmT
     ) -> Result<(CycleFoldWitness<C2>, CycleFoldCommittedInstance<C2>), Error> {
         let w = NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_witness(
-            cf_r_Fq,
-            cf_W_i,
-            cf_w_i,
-            &aux_p.to_vec(),
+            cf_r_Fq, cf_W_i, cf_w_i, aux_p,
         )?;
         let ci = Self::verify(cf_r_Fq, cf_U_i, cf_u_i, &aux_v)?;
         Ok((w, ci))
@@ -493,17 +621,60 @@ impl<C2: Curve, CS2: CommitmentScheme<C2, H>, const H: bool> CycleFoldNIFS<C2, C
 
 /// Folds the given cyclefold circuit and its instances. This method is abstracted from any folding
 /// scheme struct because it is used both by Nova & HyperNova's CycleFold.
+/// The CycleFold R1CS, the commitment key and the running instance are taken by reference, so that
+/// the callers do not need to clone them at every folding step.
 #[allow(clippy::type_complexity)]
 #[allow(clippy::too_many_arguments)]
 pub fn fold_cyclefold_circuit<CFG, C2, CS2, const H: bool>(
     transcript: &mut impl Transcript<CF2<C2>>,
-    cf_r1cs: R1CS<C2::ScalarField>,
-    cf_cs_params: CS2::ProverParams,
-    pp_hash: CF2<C2>,                       // public params hash
-    cf_W_i: CycleFoldWitness<C2>,           // witness of the running instance
-    cf_U_i: CycleFoldCommittedInstance<C2>, // running instance
+    cf_r1cs: &R1CS<C2::ScalarField>,
+    cf_cs_params: &CS2::ProverParams,
+    pp_hash: CF2<C2>,                        // public params hash
+    cf_W_i: &CycleFoldWitness<C2>,           // witness of the running instance
+    cf_U_i: &CycleFoldCommittedInstance<C2>, // running instance
+    cf_circuit: CycleFoldCircuit<CFG>,
+    rng: impl RngCore,
+) -> Result<
+    (
+        CycleFoldCommittedInstance<C2>, // u_i
+        CycleFoldWitness<C2>,           // W_i1
+        CycleFoldCommittedInstance<C2>, // U_i1
+        C2,                             // cmT
+    ),
+    Error,
+>
+where
+    CFG: CycleFoldConfig,
+    C2: Curve<ScalarField = CF2<CFG::C>, BaseField = CF1<CFG::C>>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fold_cyclefold_circuit_with_scratch::<CFG, C2, CS2, H>(
+        transcript,
+        cf_r1cs,
+        cf_cs_params,
+        pp_hash,
+        cf_W_i,
+        cf_U_i,
+        cf_circuit,
+        rng,
+        &mut ProverScratch::default(),
+    )
+}
+
+/// Same as [`fold_cyclefold_circuit`], but reusing the buffers of `scratch` for the temporaries
+/// of the prover, so that the schemes that fold many steps do not reallocate them at each step.
+#[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments)]
+pub fn fold_cyclefold_circuit_with_scratch<CFG, C2, CS2, const H: bool>(
+    transcript: &mut impl Transcript<CF2<C2>>,
+    cf_r1cs: &R1CS<C2::ScalarField>,
+    cf_cs_params: &CS2::ProverParams,
+    pp_hash: CF2<C2>,                        // public params hash
+    cf_W_i: &CycleFoldWitness<C2>,           // witness of the running instance
+    cf_U_i: &CycleFoldCommittedInstance<C2>, // running instance
     cf_circuit: CycleFoldCircuit<CFG>,
     mut rng: impl RngCore,
+    scratch: &mut ProverScratch<C2::ScalarField>,
 ) -> Result<
     (
         CycleFoldCommittedInstance<C2>, // u_i
@@ -518,6 +689,12 @@ where
     C2: Curve<ScalarField = CF2<CFG::C>, BaseField = CF1<CFG::C>>,
     CS2: CommitmentScheme<C2, H>,
 {
+    #[cfg(test)]
+    let n_points = cf_circuit
+        .points
+        .as_ref()
+        .map_or(CFG::N_INPUT_POINTS, |points| points.len() / CFG::N_RLCS);
+
     let cs2 = ConstraintSystem::new_ref();
     cf_circuit.generate_constraints(cs2.clone())?;
 
@@ -525,21 +702,21 @@ where
     let (cf_w_i, cf_x_i) = extract_w_x(&cs2);
 
     #[cfg(test)]
-    assert_eq!(cf_x_i.len(), CFG::IO_LEN);
+    assert_eq!(cf_x_i.len(), CFG::io_len(n_points));
 
     // fold cyclefold instances
-    let cf_w_i =
-        CycleFoldWitness::<C2>::new::<H>(cf_w_i.clone(), cf_r1cs.n_constraints(), &mut rng);
-    let cf_u_i = cf_w_i.commit::<CS2, H>(&cf_cs_params, cf_x_i.clone())?;
-
-    // compute T* and cmT* for CycleFoldCircuit
-    let (cf_T, cf_cmT) = NIFS::<C2, CS2, PoseidonSponge<CF1<C2>>, H>::compute_cyclefold_cmT(
-        &cf_cs_params,
-        &cf_r1cs,
+    let cf_w_i = CycleFoldWitness::<C2>::new::<H>(cf_w_i, cf_r1cs.n_constraints(), &mut rng);
+    let cf_u_i = cf_w_i.commit::<CS2, H>(cf_cs_params, cf_x_i)?;
+
+    // compute T* and cmT* for CycleFoldCircuit, T* is kept in `scratch.T`
+    let cf_cmT = NIFS::<C2, CS2, PoseidonSponge<CF1<C2>>, H>::compute_cyclefold_cmT_into(
+        cf_cs_params,
+        cf_r1cs,
+        cf_W_i,
+        cf_U_i,
         &cf_w_i,
         &cf_u_i,
-        &cf_W_i,
-        &cf_U_i,
+        scratch,
     )?;
 
     let cf_r_bits = CycleFoldChallengeGadget::get_challenge_native(
@@ -553,7 +730,7 @@ where
         .expect("cf_r_bits out of bounds");
 
     let (cf_W_i1, cf_U_i1) = CycleFoldNIFS::<C2, CS2, H>::prove(
-        cf_r_Fq, &cf_W_i, &cf_U_i, &cf_w_i, &cf_u_i, &cf_T, cf_cmT,
+        cf_r_Fq, cf_W_i, cf_U_i, &cf_w_i, &cf_u_i, &scratch.T, cf_cmT,
     )?;
 
     #[cfg(test)]
@@ -593,6 +770,86 @@ pub mod tests {
         type C = C;
     }
 
+    struct TestWindowedCycleFoldConfig<C: Curve, const W: usize> {
+        _c: PhantomData<C>,
+    }
+
+    impl<C: Curve, const W: usize> CycleFoldConfig for TestWindowedCycleFoldConfig<C, W> {
+        const RANDOMNESS_BIT_LENGTH: usize = NOVA_N_BITS_RO;
+        const N_INPUT_POINTS: usize = 2;
+        const SCALAR_MUL_WINDOW_SIZE: usize = W;
+        type C = C;
+    }
+
+    /// returns the public inputs of the CycleFoldCircuit with the given config
+    /// for the given randomness and points
+    fn cyclefold_circuit_x<CFG: CycleFoldConfig<C = Projective>>(
+        r_bits: &[bool],
+        points: &[Projective],
+    ) -> Result<Vec<Fq>, Error> {
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        CycleFoldCircuit::<CFG> {
+            r_bits: Some(r_bits.to_vec()),
+            points: Some(points.to_vec()),
+        }
+        .generate_constraints(cs.clone())?;
+        assert!(cs.is_satisfied()?);
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        Ok(cs.instance_assignment[1..].to_vec())
+    }
+
+    #[test]
+    fn test_windowed_scalar_mul() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let points: Vec<Projective> = std::iter::repeat_with(|| Projective::rand(&mut rng))
+            .take(2)
+            .collect();
+        let r_bits = Fr::rand(&mut rng).into_bigint().to_bits_le()[..NOVA_N_BITS_RO].to_vec();
+
+        // the windowed scalar multiplication leads to the same folded point,
+        // and thus to the same public inputs, for any window size
+        let x =
+            cyclefold_circuit_x::<TestWindowedCycleFoldConfig<Projective, 1>>(&r_bits, &points)?;
+        assert_eq!(
+            x,
+            cyclefold_circuit_x::<TestWindowedCycleFoldConfig<Projective, 2>>(&r_bits, &points)?
+        );
+        assert_eq!(
+            x,
+            cyclefold_circuit_x::<TestWindowedCycleFoldConfig<Projective, 3>>(&r_bits, &points)?
+        );
+        assert_eq!(
+            x,
+            cyclefold_circuit_x::<TestWindowedCycleFoldConfig<Projective, 4>>(&r_bits, &points)?
+        );
+
+        // also for the zero point and the zero scalar
+        let zero_points = vec![Projective::zero(); 2];
+        let zero_bits = vec![false; NOVA_N_BITS_RO];
+        for (r_bits, points) in [(&r_bits, &zero_points), (&zero_bits, &points)] {
+            assert_eq!(
+                cyclefold_circuit_x::<TestWindowedCycleFoldConfig<Projective, 1>>(r_bits, points)?,
+                cyclefold_circuit_x::<TestWindowedCycleFoldConfig<Projective, 3>>(r_bits, points)?
+            );
+        }
+
+        // the sizes of the circuits are available without a witness
+        let (n_constraints, n_witnesses) =
+            CycleFoldCircuit::<TestWindowedCycleFoldConfig<Projective, 1>>::size(2)?;
+        assert!(n_constraints > 0 && n_witnesses > 0);
+
+        // the default config uses the windowed scalar multiplication, with windows of 2 bits
+        assert!(TestCycleFoldConfig::<Projective, 2>::SCALAR_MUL_WINDOW_SIZE >= 2);
+        let (n_constraints_default, _) =
+            CycleFoldCircuit::<TestCycleFoldConfig<Projective, 2>>::size(2)?;
+        assert_eq!(
+            n_constraints_default,
+            CycleFoldCircuit::<TestWindowedCycleFoldConfig<Projective, 2>>::size(2)?.0
+        );
+        assert_ne!(n_constraints_default, n_constraints);
+        Ok(())
+    }
+
     #[test]
     fn test_CycleFoldCircuit_n_points_constraints() -> Result<(), Error> {
         const n: usize = 16;
@@ -639,6 +896,111 @@ pub mod tests {
         Ok(())
     }
 
+    /// folds two CycleFold circuits with the given config, and checks the folding in-circuit, as
+    /// done by the augmented circuits
+    fn test_cyclefold_opt<CFG: CycleFoldConfig<C = Projective>>() -> Result<(), Error> {
+        use crate::arith::ArithRelation;
+        use crate::folding::nova::get_r1cs_from_cs;
+        use ark_grumpkin::Projective as Projective2;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fq>();
+        let pp_hash = Fq::rand(&mut rng);
+
+        let cf_r1cs = get_r1cs_from_cs(CycleFoldCircuit::<CFG>::empty())?;
+        assert_eq!(cf_r1cs.n_public_inputs(), CFG::IO_LEN);
+        let (cf_cs_pp, _) = Pedersen::<Projective2>::setup(
+            &mut rng,
+            cf_r1cs.n_constraints().max(cf_r1cs.n_witnesses()),
+        )?;
+        let (mut cf_W, mut cf_U): (CycleFoldWitness<Projective2>, _) =
+            cf_r1cs.dummy_witness_instance();
+
+        for _ in 0..2 {
+            let r_bits = Fr::rand(&mut rng).into_bigint().to_bits_le()[..NOVA_N_BITS_RO].to_vec();
+            let points: Vec<Projective> = std::iter::repeat_with(|| Projective::rand(&mut rng))
+                .take(CFG::N_RLCS * CFG::N_INPUT_POINTS)
+                .collect();
+            let cf_circuit = CycleFoldCircuit::<CFG> {
+                r_bits: Some(r_bits),
+                points: Some(points),
+            };
+
+            let mut transcript = PoseidonSponge::<Fq>::new(&poseidon_config);
+            let (cf_u, cf_W1, cf_U1, cf_cmT) =
+                fold_cyclefold_circuit::<CFG, Projective2, Pedersen<Projective2>, false>(
+                    &mut transcript,
+                    &cf_r1cs,
+                    &cf_cs_pp,
+                    pp_hash,
+                    &cf_W,
+                    &cf_U,
+                    cf_circuit,
+                    &mut rng,
+                )?;
+            cf_r1cs.check_relation(&cf_W1, &cf_U1)?;
+
+            // check the folding of the CycleFold instances in-circuit
+            let mut transcript = PoseidonSponge::<Fq>::new(&poseidon_config);
+            let cf_r_bits = CycleFoldChallengeGadget::<Projective2>::get_challenge_native(
+                &mut transcript,
+                pp_hash,
+                cf_U.clone(),
+                cf_u.clone(),
+                cf_cmT,
+            );
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let r_bitsVar = Vec::<Boolean<Fr>>::new_witness(cs.clone(), || Ok(cf_r_bits))?;
+            let cf_UVar =
+                CycleFoldCommittedInstanceVar::<Projective2>::new_witness(cs.clone(), || {
+                    Ok(cf_U.clone())
+                })?;
+            let cf_uVar =
+                CycleFoldCommittedInstanceVar::<Projective2>::new_witness(cs.clone(), || {
+                    Ok(cf_u.clone())
+                })?;
+            let cf_U1Var =
+                CycleFoldCommittedInstanceVar::<Projective2>::new_witness(cs.clone(), || {
+                    Ok(cf_U1.clone())
+                })?;
+            let cmTVar = <Projective2 as Curve>::Var::new_witness(cs.clone(), || Ok(cf_cmT))?;
+            NIFSFullGadget::<Projective2>::verify(r_bitsVar, cmTVar, cf_UVar, cf_uVar, cf_U1Var)?;
+            assert!(cs.is_satisfied()?);
+
+            (cf_W, cf_U) = (cf_W1, cf_U1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_cyclefold_n_points() -> Result<(), Error> {
+        test_cyclefold_opt::<GenericCycleFoldConfig<Projective, 1>>()?;
+        test_cyclefold_opt::<GenericCycleFoldConfig<Projective, 2>>()?;
+        test_cyclefold_opt::<GenericCycleFoldConfig<Projective, 4>>()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_cyclefold_batched_rlcs() -> Result<(), Error> {
+        // Nova's case, where the folds of cmW and cmE are checked by the same instance
+        test_cyclefold_opt::<GenericCycleFoldConfig<Projective, 2, 2>>()?;
+        test_cyclefold_opt::<GenericCycleFoldConfig<Projective, 3, 4>>()?;
+
+        // the public inputs contain each random linear combination, after the randomness
+        let mut rng = ark_std::test_rng();
+        let points: Vec<Projective> = std::iter::repeat_with(|| Projective::rand(&mut rng))
+            .take(4)
+            .collect();
+        let r_bits = Fr::rand(&mut rng).into_bigint().to_bits_le()[..NOVA_N_BITS_RO].to_vec();
+        let x = cyclefold_circuit_x::<GenericCycleFoldConfig<Projective, 2, 2>>(&r_bits, &points)?;
+        let x1 =
+            cyclefold_circuit_x::<GenericCycleFoldConfig<Projective, 2>>(&r_bits, &points[..2])?;
+        let x2 =
+            cyclefold_circuit_x::<GenericCycleFoldConfig<Projective, 2>>(&r_bits, &points[2..])?;
+        assert_eq!(x, [&x1[..], &x2[1..]].concat());
+        Ok(())
+    }
+
     #[test]
     fn test_nifs_full_gadget() -> Result<(), Error> {
         let mut rng = ark_std::test_rng();
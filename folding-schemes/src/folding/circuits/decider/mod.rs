@@ -1,15 +1,19 @@
 use ark_crypto_primitives::sponge::{
     poseidon::constraints::PoseidonSpongeVar, CryptographicSponge,
 };
-use ark_ff::PrimeField;
+use ark_ff::{BigInteger, PrimeField};
 use ark_poly::Polynomial;
 use ark_r1cs_std::{
+    boolean::Boolean,
+    convert::{ToBitsGadget, ToBytesGadget},
     fields::{fp::FpVar, FieldVar},
     poly::{domain::Radix2DomainVar, evaluations::univariate::EvaluationsVar},
 };
 use ark_relations::r1cs::SynthesisError;
 use ark_std::log2;
+use sha3::{Digest, Keccak256};
 
+use crate::folding::circuits::keccak::keccak256_gadget;
 use crate::folding::traits::{CommittedInstanceOps, CommittedInstanceVarOps, Dummy, WitnessOps};
 use crate::transcript::{Transcript, TranscriptVar};
 use crate::utils::vec::poly_from_vec;
@@ -88,6 +92,49 @@ impl EvalGadget {
     }
 }
 
+/// Gadget that computes the hash of an IVC state, which the onchain deciders can expose as public
+/// input instead of the state itself, so that the verifier contracts receive a single word per
+/// state regardless of its length.
+/// The hash is the keccak256 digest of the 32-byte big-endian encoding of the state elements (as
+/// Solidity's `abi.encodePacked` does), truncated to its first 31 bytes so that it fits in the
+/// field.
+/// It also offers the rust native implementation compatible with the gadget.
+pub struct StateHashGadget {}
+
+impl StateHashGadget {
+    /// Number of bytes of the digest that are kept.
+    const DIGEST_LEN: usize = 31;
+
+    pub fn hash_native<F: PrimeField>(z: &[F]) -> Result<F, Error> {
+        let n_bytes = (F::MODULUS_BIT_SIZE as usize).div_ceil(8);
+        let bytes = z
+            .iter()
+            .flat_map(|z_j| {
+                let mut bytes = z_j.into_bigint().to_bytes_le();
+                bytes.truncate(n_bytes);
+                bytes.into_iter().rev()
+            })
+            .collect::<Vec<_>>();
+        let digest = Keccak256::digest(bytes);
+        Ok(F::from_be_bytes_mod_order(&digest[..Self::DIGEST_LEN]))
+    }
+
+    pub fn hash_gadget<F: PrimeField>(z: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        let mut bytes = vec![];
+        for z_j in z {
+            bytes.extend(z_j.to_bytes_le()?.into_iter().rev());
+        }
+        let digest = keccak256_gadget(&bytes)?;
+        let bits = digest[..Self::DIGEST_LEN]
+            .iter()
+            .rev()
+            .map(|byte| byte.to_bits_le())
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        Boolean::le_bits_to_fp(&bits)
+    }
+}
+
 /// This is a temporary workaround for step 6 (running NIFS.V for group elements
 /// in circuit) in an NIFS-agnostic way, because different folding schemes have
 /// different interfaces of folding verification now.
@@ -178,6 +225,32 @@ pub mod tests {
         Ok(())
     }
 
+    // checks that the gadget and native implementations of the state hash match
+    #[test]
+    fn test_state_hash_gadget() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        for len in [1, 2, 28] {
+            let z: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(len)
+                .collect();
+            let h = StateHashGadget::hash_native(&z)?;
+            assert!(h.into_bigint().num_bits() <= 8 * StateHashGadget::DIGEST_LEN as u32);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let zVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z.clone()))?;
+            let hVar = StateHashGadget::hash_gadget(&zVar)?;
+            assert!(cs.is_satisfied()?);
+            assert_eq!(hVar.value()?, h);
+
+            // the hash binds the whole state
+            let mut z_modified = z.clone();
+            z_modified[len - 1] += Fr::from(1_u32);
+            assert_ne!(StateHashGadget::hash_native(&z_modified)?, h);
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_polynomial_interpolation() -> Result<(), Error> {
         let mut rng = ark_std::test_rng();
@@ -16,7 +16,7 @@ use crate::{
             cyclefold::{
                 CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar, CycleFoldWitness,
             },
-            decider::{EvalGadget, KZGChallengesGadget},
+            decider::{EvalGadget, KZGChallengesGadget, StateHashGadget},
             nonnative::affine::NonNativeAffineVar,
             CF1, CF2,
         },
@@ -43,6 +43,10 @@ use super::DeciderEnabledNIFS;
 ///   and the commitments in `cf_U_i` open to the values in `cf_W_i`.
 /// - `u_i` contains the correct hash of the initial and final states.
 ///
+/// When `hash_state` is set, the initial and final states are not public inputs of the circuit,
+/// which instead exposes their hashes (see [`StateHashGadget`]), so that the cost of verifying the
+/// proof does not grow with the state length.
+///
 /// To reduce the number of relation checks, the prover, before invoking the
 /// circuit, further folds `U_i, u_i` into `U_{i+1}`, and `W_i, w_i` into
 /// `W_{i+1}`.
@@ -83,6 +87,8 @@ pub struct GenericOnchainDeciderCircuit<
     pub z_0: Vec<CF1<C1>>,
     /// current i-th state
     pub z_i: Vec<CF1<C1>>,
+    /// whether `z_0` and `z_i` are exposed as public inputs through their hashes
+    pub hash_state: bool,
     /// Folding scheme instances
     pub U_i: RU,
     pub W_i: W,
@@ -154,6 +160,7 @@ impl<
             i: Zero::zero(),
             z_0: vec![Zero::zero(); state_len],
             z_i: vec![Zero::zero(); state_len],
+            hash_state: false,
             U_i: RU::dummy(&arith),
             W_i: W::dummy(&arith),
             u_i: IU::dummy(&arith),
@@ -190,8 +197,24 @@ where
 
         let pp_hash = FpVar::new_input(cs.clone(), || Ok(self.pp_hash))?;
         let i = FpVar::new_input(cs.clone(), || Ok(self.i))?;
-        let z_0 = Vec::new_input(cs.clone(), || Ok(self.z_0))?;
-        let z_i = Vec::new_input(cs.clone(), || Ok(self.z_i))?;
+        let (z_0, z_i) = if self.hash_state {
+            let h_0 = FpVar::new_input(cs.clone(), || {
+                StateHashGadget::hash_native(&self.z_0).map_err(|_| SynthesisError::Unsatisfiable)
+            })?;
+            let h_i = FpVar::new_input(cs.clone(), || {
+                StateHashGadget::hash_native(&self.z_i).map_err(|_| SynthesisError::Unsatisfiable)
+            })?;
+            let z_0 = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || Ok(self.z_0))?;
+            let z_i = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || Ok(self.z_i))?;
+            StateHashGadget::hash_gadget(&z_0)?.enforce_equal(&h_0)?;
+            StateHashGadget::hash_gadget(&z_i)?.enforce_equal(&h_i)?;
+            (z_0, z_i)
+        } else {
+            (
+                Vec::new_input(cs.clone(), || Ok(self.z_0))?,
+                Vec::new_input(cs.clone(), || Ok(self.z_i))?,
+            )
+        };
 
         let u_i = IU::Var::new_witness(cs.clone(), || Ok(self.u_i))?;
         let U_i = RU::Var::new_witness(cs.clone(), || Ok(self.U_i))?;
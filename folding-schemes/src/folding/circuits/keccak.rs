@@ -0,0 +1,169 @@
+//! Keccak-256 ([the Keccak reference](https://keccak.team/files/Keccak-reference-3.0.pdf), with
+//! the original `0x01` padding used by Ethereum's `keccak256`, not the `0x06` one of SHA3-256) as
+//! R1CS constraints over the bits of its 64-bit lanes.
+//!
+//! The lanes are kept as their little-endian bits, so that the rotations and permutations of the
+//! permutation only rewire the bits and the cost comes from the bitwise XOR, AND and NOT. The
+//! length of the message is fixed at synthesis time, so that the padding is made of constants.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::{boolean::Boolean, convert::ToBitsGadget, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+use core::array;
+use core::ops::{BitAnd, BitXor, Not};
+
+/// Rate of Keccak-256, in bytes.
+const RATE: usize = 136;
+
+/// Round constants of Keccak-f[1600].
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets of the rho step, indexed by `[x][y]`.
+const ROTATIONS: [[usize; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// A 64-bit lane, as its little-endian bits.
+type Lane<F> = Vec<Boolean<F>>;
+
+fn xor<F: PrimeField>(a: &Lane<F>, b: &Lane<F>) -> Lane<F> {
+    a.iter().zip(b).map(|(a, b)| a.clone().bitxor(b)).collect()
+}
+
+/// Left rotation by `n` bits, which adds no constraints.
+fn rotl<F: PrimeField>(a: &Lane<F>, n: usize) -> Lane<F> {
+    (0..64).map(|i| a[(i + 64 - n) % 64].clone()).collect()
+}
+
+/// Keccak-f[1600] permutation of the state, whose lane `(x, y)` is at index `x + 5 * y`.
+fn keccak_f<F: PrimeField>(a: &mut [Lane<F>; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let c: [Lane<F>; 5] =
+            array::from_fn(|x| (1..5).fold(a[x].clone(), |acc, y| xor(&acc, &a[x + 5 * y])));
+        for x in 0..5 {
+            let d = xor(&c[(x + 4) % 5], &rotl(&c[(x + 1) % 5], 1));
+            for y in 0..5 {
+                a[x + 5 * y] = xor(&a[x + 5 * y], &d);
+            }
+        }
+        // rho and pi
+        let mut b: [Lane<F>; 25] = array::from_fn(|_| vec![]);
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = rotl(&a[x + 5 * y], ROTATIONS[x][y]);
+            }
+        }
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                let not_and: Lane<F> = b[(x + 1) % 5 + 5 * y]
+                    .iter()
+                    .zip(&b[(x + 2) % 5 + 5 * y])
+                    .map(|(b1, b2)| b1.clone().not().bitand(b2))
+                    .collect();
+                a[x + 5 * y] = xor(&b[x + 5 * y], &not_and);
+            }
+        }
+        // iota, XORing with constants adds no constraints
+        a[0] = a[0]
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| bit.clone().bitxor(Boolean::constant((rc >> i) & 1 == 1)))
+            .collect();
+    }
+}
+
+/// Computes the Keccak-256 hash of `msg`, as its 32 bytes, matching Solidity's `keccak256`.
+pub fn keccak256_gadget<F: PrimeField>(msg: &[UInt8<F>]) -> Result<[UInt8<F>; 32], SynthesisError> {
+    let mut padded = msg.to_vec();
+    padded.push(UInt8::constant(0x01));
+    while padded.len() % RATE != 0 {
+        padded.push(UInt8::constant(0));
+    }
+    let last = padded.len() - 1;
+    padded[last] = padded[last].clone() | UInt8::constant(0x80);
+
+    let mut state: [Lane<F>; 25] = array::from_fn(|_| vec![Boolean::FALSE; 64]);
+    for block in padded.chunks(RATE) {
+        // the bytes of each lane are little-endian
+        for (i, lane) in block.chunks(8).enumerate() {
+            let bits = lane
+                .iter()
+                .map(|byte| byte.to_bits_le())
+                .collect::<Result<Vec<_>, _>>()?
+                .concat();
+            state[i] = xor(&state[i], &bits);
+        }
+        keccak_f(&mut state);
+    }
+
+    let bytes = state[..4]
+        .iter()
+        .flat_map(|lane| lane.chunks(8).map(UInt8::from_bits_le))
+        .collect::<Vec<_>>();
+    Ok(array::from_fn(|i| bytes[i].clone()))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use sha3::{Digest, Keccak256};
+
+    use crate::Error;
+
+    // checks the gadget against the native Keccak-256, for messages around the rate
+    #[test]
+    fn test_keccak256_gadget() -> Result<(), Error> {
+        for len in [0, 1, 64, RATE - 1, RATE, 2 * RATE + 5] {
+            let msg: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let msgVar = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(msg.clone()))?;
+            let digestVar = keccak256_gadget(&msgVar)?;
+            assert!(cs.is_satisfied()?);
+            assert_eq!(digestVar[..].value()?, Keccak256::digest(&msg).to_vec());
+        }
+
+        // known vector: keccak256("")
+        let digestVar = keccak256_gadget::<Fr>(&[])?;
+        assert_eq!(
+            digestVar[..].value()?,
+            hex::decode("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+                .unwrap()
+        );
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use ark_ff::Field;
 
 pub mod cyclefold;
 pub mod decider;
+pub mod keccak;
 pub mod nonnative;
 pub mod sum_check;
 pub mod utils;
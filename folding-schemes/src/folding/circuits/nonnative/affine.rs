@@ -217,6 +217,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_absorb_nonnative_random_points() -> Result<(), Error> {
+        use ark_bn254::G1Projective as BnG1;
+
+        fn check<C: Curve>(points: &[C]) -> Result<(), Error> {
+            let cs = ConstraintSystem::<C::ScalarField>::new_ref();
+            for p in points {
+                let pVar = NonNativeAffineVar::<C>::new_witness(cs.clone(), || Ok(*p))?;
+                let n_constraints = cs.num_constraints();
+                let elements = pVar.to_native_sponge_field_elements()?;
+                // the absorption only adds the linear combinations packing the
+                // limbs, which are already range checked by the allocation
+                assert_eq!(cs.num_constraints(), n_constraints);
+                // two elements per coordinate for the fields of ~255 bits
+                assert_eq!(elements.len(), 4);
+                assert_eq!(
+                    elements.value()?,
+                    p.to_native_sponge_field_elements_as_vec()
+                );
+            }
+            assert!(cs.is_satisfied()?);
+            Ok(())
+        }
+
+        let mut rng = ark_std::test_rng();
+        let mut pallas_points = vec![Projective::zero()];
+        pallas_points.extend((0..10).map(|_| Projective::rand(&mut rng)));
+        check(&pallas_points)?;
+        let mut bn254_points = vec![BnG1::zero()];
+        bn254_points.extend((0..10).map(|_| BnG1::rand(&mut rng)));
+        check(&bn254_points)?;
+        Ok(())
+    }
+
     #[test]
     fn test_inputize() -> Result<(), Error> {
         // check that point_to_nonnative_limbs returns the expected values
@@ -238,4 +272,27 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_bls12_381_nonnative_point() -> Result<(), Error> {
+        use ark_bls12_381::{Fr as BlsFr, G1Projective as BlsG1};
+
+        let mut rng = ark_std::test_rng();
+        let p = BlsG1::rand(&mut rng);
+
+        // the coordinates of BLS12-381's G1 live in a 381-bit field, which is
+        // larger than the constraint field
+        let cs = ConstraintSystem::<BlsFr>::new_ref();
+        let pVar = NonNativeAffineVar::<BlsG1>::new_witness(cs.clone(), || Ok(p))?;
+        assert_eq!(
+            [pVar.x.0.value()?, pVar.y.0.value()?].concat(),
+            p.inputize_nonnative()
+        );
+        assert_eq!(
+            pVar.to_native_sponge_field_elements()?.value()?,
+            p.to_native_sponge_field_elements_as_vec()
+        );
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
 }
@@ -800,33 +800,86 @@ impl<F: PrimeField, B: AsRef<[Boolean<F>]>> From<B> for NonNativeUintVar<F> {
 }
 
 impl<P: FpConfig<N>, const N: usize> AbsorbNonNative for Fp<P, N> {
+    /// Packs the canonical representative of `self` as [`NonNativeUintVar`]'s
+    /// `AbsorbNonNativeGadget` packs its limbs: the limbs of
+    /// `NonNativeUintVar::<F>::bits_per_limb()` bits are grouped by
+    /// `NonNativeUintVar::<F>::limbs_per_sponge_element()`, least significant
+    /// first, i.e., the integer is split into chunks of `bits_per_limb *
+    /// limbs_per_sponge_element` bits (220 bits for the 254-bit and 255-bit
+    /// fields), each one becoming a field element of `F`.
     fn to_native_sponge_field_elements<F: PrimeField>(&self, dest: &mut Vec<F>) {
-        let bits_per_limb = F::MODULUS_BIT_SIZE as usize - 1;
-        let num_limbs = (Fp::<P, N>::MODULUS_BIT_SIZE as usize).div_ceil(bits_per_limb);
+        let bits_per_element = NonNativeUintVar::<F>::bits_per_limb()
+            * NonNativeUintVar::<F>::limbs_per_sponge_element();
+        let num_elements = (Fp::<P, N>::MODULUS_BIT_SIZE as usize).div_ceil(bits_per_element);
 
-        let mut limbs = self
+        let mut elements = self
             .into_bigint()
             .to_bits_le()
-            .chunks(bits_per_limb)
+            .chunks(bits_per_element)
             .map(|chunk| F::from(F::BigInt::from_bits_le(chunk)))
             .collect::<Vec<F>>();
-        limbs.resize(num_limbs, F::zero());
+        elements.resize(num_elements, F::zero());
 
-        dest.extend(&limbs)
+        dest.extend(&elements)
     }
 }
 
+impl<F: PrimeField> NonNativeUintVar<F> {
+    /// Returns the number of limbs packed into each field element absorbed by
+    /// a sponge, i.e., the largest number of limbs whose concatenation is
+    /// always smaller than the modulus of `F`.
+    pub const fn limbs_per_sponge_element() -> usize {
+        (F::MODULUS_BIT_SIZE as usize - 1) / Self::bits_per_limb()
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Makes [`NonNativeUintVar`] absorb the packing of its bits instead of
+    /// its limbs, as it did before, so that the tests can measure the
+    /// constraints saved by packing the limbs.
+    pub(crate) static ABSORB_BY_BITS: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
 impl<F: PrimeField> AbsorbNonNativeGadget<F> for NonNativeUintVar<F> {
+    /// Packs the limbs into field elements of
+    /// [`NonNativeUintVar::limbs_per_sponge_element`] limbs each, as a linear
+    /// combination and thus without any constraint, instead of decomposing
+    /// them into bits. This requires the limbs to be aligned, i.e., each one
+    /// to have at most `bits_per_limb` bits (which holds for the allocated and
+    /// the reduced integers), so that the packing is canonical.
     fn to_native_sponge_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
-        let bits_per_limb = F::MODULUS_BIT_SIZE as usize - 1;
+        #[cfg(test)]
+        if ABSORB_BY_BITS.with(|by_bits| by_bits.get()) {
+            return self
+                .to_bits_le()?
+                .chunks(F::MODULUS_BIT_SIZE as usize - 1)
+                .map(Boolean::le_bits_to_fp)
+                .collect();
+        }
 
-        let limbs = self
-            .to_bits_le()?
-            .chunks(bits_per_limb)
-            .map(Boolean::le_bits_to_fp)
-            .collect::<Result<Vec<_>, _>>()?;
+        let bits_per_limb = Self::bits_per_limb();
+        if self
+            .0
+            .iter()
+            .any(|limb| limb.ub.bits() as usize > bits_per_limb)
+        {
+            // an unaligned integer has several limb representations, which
+            // would give different packings of the same value
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
-        Ok(limbs)
+        let shift = F::from(BigUint::one() << bits_per_limb);
+        Ok(self
+            .0
+            .chunks(Self::limbs_per_sponge_element())
+            .map(|limbs| {
+                limbs
+                    .iter()
+                    .rev()
+                    .fold(FpVar::zero(), |acc, limb| acc * shift + &limb.v)
+            })
+            .collect())
     }
 }
 
@@ -988,6 +1041,76 @@ mod tests {
         Ok(())
     }
 
+    /// BLS12-381's base field is 381 bits, so its elements are represented
+    /// with more limbs than the ~255 bits fields used in the other tests.
+    #[test]
+    fn test_mul_fq_bls12_381() -> Result<(), Error> {
+        use ark_bls12_381::{Fq as BlsFq, Fr as BlsFr};
+        let cs = ConstraintSystem::<BlsFr>::new_ref();
+
+        let rng = &mut test_rng();
+        let a = BlsFq::rand(rng);
+        let b = BlsFq::rand(rng);
+        let ab = a * b;
+
+        let a_var = NonNativeUintVar::new_witness(cs.clone(), || Ok(a))?;
+        let b_var = NonNativeUintVar::new_witness(cs.clone(), || Ok(b))?;
+        let ab_var = NonNativeUintVar::new_witness(cs.clone(), || Ok(ab))?;
+        assert_eq!(
+            a_var.0.len(),
+            (BlsFq::MODULUS_BIT_SIZE as usize).div_ceil(NonNativeUintVar::<BlsFr>::bits_per_limb())
+        );
+
+        a_var
+            .mul_no_align(&b_var)?
+            .enforce_congruent::<BlsFq>(&ab_var)?;
+        assert_eq!(ab, BlsFq::from(ab_var.value()?));
+
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_absorb_nonnative_limb_packing() -> Result<(), Error> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        assert_eq!(NonNativeUintVar::<Fr>::limbs_per_sponge_element(), 4);
+
+        let rng = &mut test_rng();
+        for _ in 0..10 {
+            let a = Fq::rand(rng);
+            let b = Fq::rand(rng);
+            let a_var = NonNativeUintVar::new_witness(cs.clone(), || Ok(a))?;
+            let b_var = NonNativeUintVar::new_witness(cs.clone(), || Ok(b))?;
+
+            // the packing of the limbs is a linear combination, and thus
+            // costs no constraint
+            let n_constraints = cs.num_constraints();
+            let a_elements = a_var.to_native_sponge_field_elements()?;
+            assert_eq!(cs.num_constraints(), n_constraints);
+            assert_eq!(
+                a_elements.value()?,
+                a.to_native_sponge_field_elements_as_vec::<Fr>()
+            );
+
+            // the reduced integers are aligned, and absorbed as the field
+            // elements they represent
+            let ab_var = a_var.mul_no_align(&b_var)?.modulo::<Fq>()?;
+            assert_eq!(
+                ab_var.to_native_sponge_field_elements()?.value()?,
+                (a * b).to_native_sponge_field_elements_as_vec::<Fr>()
+            );
+
+            // while the unaligned ones are rejected
+            assert!(a_var
+                .add_no_align(&b_var)?
+                .to_native_sponge_field_elements()
+                .is_err());
+        }
+
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
     #[test]
     fn test_pow() -> Result<(), Error> {
         let cs = ConstraintSystem::<Fr>::new_ref();
@@ -23,9 +23,10 @@ use core::{borrow::Borrow, marker::PhantomData};
 
 use super::{
     cccs::CCCS,
+    cf_io_len,
     lcccs::LCCCS,
     nimfs::{NIMFSProof, NIMFS},
-    HyperNovaCycleFoldConfig, Witness,
+    Witness,
 };
 use crate::arith::{
     ccs::CCS,
@@ -37,7 +38,7 @@ use crate::folding::{
     circuits::{
         cyclefold::{
             CycleFoldChallengeGadget, CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar,
-            CycleFoldConfig, NIFSFullGadget,
+            NIFSFullGadget,
         },
         nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
         sum_check::{IOPProofVar, SumCheckVerifierGadget, VPAuxInfoVar},
@@ -461,17 +462,13 @@ fn compute_c_gadget<F: PrimeField>(
 /// defined in [CycleFold](https://eprint.iacr.org/2023/1192.pdf). These extra
 /// constraints verify the correct folding of CycleFold instances.
 ///
-/// For multi-instance folding, one needs to specify the const generics below:
-/// * `MU` - the number of LCCCS instances to be folded
-/// * `NU` - the number of CCCS instances to be folded
+/// For multi-instance folding, one needs to specify the parameters below:
+/// * `mu` - the number of LCCCS instances to be folded
+/// * `nu` - the number of CCCS instances to be folded
 #[derive(Debug, Clone)]
-pub struct AugmentedFCircuit<
-    C1: Curve,
-    C2: Curve,
-    FC: FCircuit<CF1<C1>>,
-    const MU: usize,
-    const NU: usize,
-> {
+pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
+    pub(super) mu: usize,
+    pub(super) nu: usize,
     pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
     pub(super) ccs: CCS<C1::ScalarField>, // CCS of the AugmentedFCircuit
     pub(super) pp_hash: Option<CF1<C1>>,
@@ -494,7 +491,7 @@ pub struct AugmentedFCircuit<
     pub(super) cf_cmT: Option<C2>,
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -504,11 +501,15 @@ where
         poseidon_config: &PoseidonConfig<CF1<C1>>,
         F_circuit: FC,
         ccs: CCS<C1::ScalarField>,
+        mu: usize,
+        nu: usize,
     ) -> Result<Self, Error> {
-        if MU < 1 || NU < 1 {
+        if mu < 1 || nu < 1 {
             return Err(Error::CantBeZero("mu,nu".to_string()));
         }
         Ok(Self {
+            mu,
+            nu,
             poseidon_config: poseidon_config.clone(),
             ccs,
             pp_hash: None,
@@ -534,6 +535,8 @@ where
         poseidon_config: &PoseidonConfig<CF1<C1>>,
         F: FC, // FCircuit
         ccs: Option<CCS<C1::ScalarField>>,
+        mu: usize,
+        nu: usize,
     ) -> Result<Self, Error> {
         // create the initial ccs by converting from a dummy r1cs with m = 0,
         // n = 0, and l = 2 (i.e., 0 constraints, and 0 variables, and 2 public
@@ -546,7 +549,7 @@ where
         // will panic if `s = 0` (0 is arkworks' fallback value for `log(0)`).
         // Similarly, `s` will also be overwritten by `compute_concrete_ccs`.
         initial_ccs.s = 1;
-        let mut augmented_f_circuit = Self::default(poseidon_config, F, initial_ccs)?;
+        let mut augmented_f_circuit = Self::default(poseidon_config, F, initial_ccs, mu, nu)?;
         augmented_f_circuit.ccs = ccs
             .ok_or(())
             .or_else(|_| augmented_f_circuit.compute_concrete_ccs())?;
@@ -569,10 +572,10 @@ where
 
         let n_iters = 2;
         for _ in 0..n_iters {
-            let Us = vec![U_i.clone(); MU - 1];
-            let Ws = vec![W_i.clone(); MU - 1];
-            let us = vec![u_i.clone(); NU - 1];
-            let ws = vec![w_i.clone(); NU - 1];
+            let Us = vec![U_i.clone(); self.mu - 1];
+            let Ws = vec![W_i.clone(); self.mu - 1];
+            let us = vec![u_i.clone(); self.nu - 1];
+            let ws = vec![w_i.clone(); self.nu - 1];
 
             let all_Us = [vec![U_i.clone()], Us.clone()].concat();
             let all_us = [vec![u_i.clone()], us.clone()].concat();
@@ -592,6 +595,8 @@ where
             )?;
 
             let augmented_f_circuit = Self {
+                mu: self.mu,
+                nu: self.nu,
                 poseidon_config: self.poseidon_config.clone(),
                 ccs: ccs.clone(),
                 pp_hash: Some(C1::ScalarField::zero()),
@@ -642,7 +647,7 @@ where
     }
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -678,21 +683,20 @@ where
         let U_i =
             LCCCSVar::<C1>::new_witness(cs.clone(), || Ok(self.U_i.unwrap_or(U_dummy.clone())))?;
         let Us = Vec::<LCCCSVar<C1>>::new_witness(cs.clone(), || {
-            Ok(self.Us.unwrap_or(vec![U_dummy.clone(); MU - 1]))
+            Ok(self.Us.unwrap_or(vec![U_dummy.clone(); self.mu - 1]))
         })?;
         let us = Vec::<CCCSVar<C1>>::new_witness(cs.clone(), || {
-            Ok(self.us.unwrap_or(vec![u_dummy.clone(); NU - 1]))
+            Ok(self.us.unwrap_or(vec![u_dummy.clone(); self.nu - 1]))
         })?;
         let U_i1_C = NonNativeAffineVar::new_witness(cs.clone(), || {
             Ok(self.U_i1_C.unwrap_or_else(C1::zero))
         })?;
-        let nimfs_proof_dummy = NIMFSProof::<C1>::dummy((&self.ccs, MU, NU));
+        let nimfs_proof_dummy = NIMFSProof::<C1>::dummy((&self.ccs, self.mu, self.nu));
         let nimfs_proof = ProofVar::<C1>::new_witness(cs.clone(), || {
             Ok(self.nimfs_proof.unwrap_or(nimfs_proof_dummy))
         })?;
 
-        let cf_u_dummy =
-            CycleFoldCommittedInstance::dummy(HyperNovaCycleFoldConfig::<C1, MU, NU>::IO_LEN);
+        let cf_u_dummy = CycleFoldCommittedInstance::dummy(cf_io_len::<C1>(self.mu, self.nu));
         let cf_U_i = CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || {
             Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
         })?;
@@ -848,8 +852,7 @@ where
     }
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> ConstraintSynthesizer<CF1<C1>>
-    for AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -877,10 +880,10 @@ mod tests {
         },
         commitment::{pedersen::Pedersen, CommitmentScheme},
         folding::{
-            circuits::cyclefold::{fold_cyclefold_circuit, CycleFoldWitness},
+            circuits::cyclefold::{fold_cyclefold_circuit, CycleFoldConfig, CycleFoldWitness},
             hypernova::{
                 utils::{compute_c, compute_sigmas_thetas},
-                HyperNovaCycleFoldCircuit,
+                HyperNovaCycleFoldCircuit, HyperNovaCycleFoldConfig,
             },
             traits::CommittedInstanceOps,
         },
@@ -1168,12 +1171,11 @@ mod tests {
 
         let start = Instant::now();
         let F_circuit = CubicFCircuit::<Fr>::new(())?;
-        let mut augmented_f_circuit =
-            AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU>::empty(
-                &poseidon_config,
-                F_circuit,
-                None,
-            )?;
+        let mut augmented_f_circuit = AugmentedFCircuit::<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+        >::empty(&poseidon_config, F_circuit, None, MU, NU)?;
         let ccs = augmented_f_circuit.ccs.clone();
         println!("AugmentedFCircuit & CCS generation: {:?}", start.elapsed());
         println!("CCS m x n: {} x {}", ccs.n_constraints(), ccs.n_variables());
@@ -1259,7 +1261,9 @@ mod tests {
                 cf_u_i1_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
 
                 augmented_f_circuit =
-                    AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU> {
+                    AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>> {
+                        mu: MU,
+                        nu: NU,
                         poseidon_config: poseidon_config.clone(),
                         ccs: ccs.clone(),
                         pp_hash: Some(pp_hash),
@@ -1329,11 +1333,11 @@ mod tests {
                     false,
                 >(
                     &mut transcript_p,
-                    cf_r1cs.clone(),
-                    cf_pedersen_params.clone(),
+                    &cf_r1cs,
+                    &cf_pedersen_params,
                     pp_hash,
-                    cf_W_i.clone(), // CycleFold running instance witness
-                    cf_U_i.clone(), // CycleFold running instance
+                    &cf_W_i, // CycleFold running instance witness
+                    &cf_U_i, // CycleFold running instance
                     cf_circuit,
                     &mut rng,
                 )?;
@@ -1343,7 +1347,9 @@ mod tests {
                 cf_u_i1_x = cf_U_i1.hash_cyclefold(&sponge, pp_hash);
 
                 augmented_f_circuit =
-                    AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU> {
+                    AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>> {
+                        mu: MU,
+                        nu: NU,
                         poseidon_config: poseidon_config.clone(),
                         ccs: ccs.clone(),
                         pp_hash: Some(pp_hash),
@@ -34,6 +34,29 @@ where
     kzg_challenge: C1::ScalarField,
 }
 
+impl<C1, CS1, S> Proof<C1, CS1, S>
+where
+    C1: Curve,
+    CS1: CommitmentScheme<C1, ProverChallenge = C1::ScalarField, Challenge = C1::ScalarField>,
+    S: SNARK<C1::ScalarField>,
+{
+    pub fn snark_proof(&self) -> &S::Proof {
+        &self.snark_proof
+    }
+
+    pub fn kzg_proof(&self) -> &CS1::Proof {
+        &self.kzg_proof
+    }
+
+    pub fn rho(&self) -> C1::ScalarField {
+        self.rho
+    }
+
+    pub fn kzg_challenge(&self) -> C1::ScalarField {
+        self.kzg_challenge
+    }
+}
+
 /// Onchain Decider, for ethereum use cases
 #[derive(Clone, Debug)]
 pub struct Decider<C1, C2, FC, CS1, CS2, S, FS, const MU: usize, const NU: usize> {
@@ -96,13 +119,14 @@ where
 
         let s = hypernova_vp.ccs.s;
         let t = hypernova_vp.ccs.t;
+        let (mu, nu) = (hypernova_vp.mu, hypernova_vp.nu);
 
         let circuit = DeciderEthCircuit::<C1, C2>::dummy((
             hypernova_vp.ccs,
             hypernova_vp.cf_r1cs,
             hypernova_pp.cf_cs_pp,
             hypernova_pp.poseidon_config,
-            (s, t, MU, NU),
+            (s, t, mu, nu),
             (),
             state_len,
             1, // HyperNova's LCCCS contains 1 commitment
@@ -157,6 +157,7 @@ impl<
             i: hn.i,
             z_0: hn.z_0,
             z_i: hn.z_i,
+            hash_state: false,
             U_i: hn.U_i,
             W_i: hn.W_i,
             u_i: hn.u_i,
@@ -6,7 +6,9 @@ use ark_crypto_primitives::sponge::{
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::R1CSVar;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
 use ark_std::{cmp::max, fmt::Debug, marker::PhantomData, rand::RngCore, One, Zero};
 
 pub mod cccs;
@@ -26,7 +28,7 @@ use nimfs::NIMFS;
 use crate::arith::{
     ccs::CCS,
     r1cs::{extract_w_x, R1CS},
-    Arith, ArithRelation,
+    Arith, ArithRelation, ArithSerializer,
 };
 use crate::commitment::CommitmentScheme;
 use crate::constants::NOVA_N_BITS_RO;
@@ -61,6 +63,13 @@ impl<C: Curve, const MU: usize, const NU: usize> CycleFoldConfig
 pub type HyperNovaCycleFoldCircuit<C, const MU: usize, const NU: usize> =
     CycleFoldCircuit<HyperNovaCycleFoldConfig<C, MU, NU>>;
 
+/// Returns the public inputs length of HyperNova's CycleFold circuit when folding `mu` LCCCS and
+/// `nu` CCCS instances. Notice that the length does not depend on the const generics of
+/// `HyperNovaCycleFoldConfig`, which are only used as the default number of points.
+pub fn cf_io_len<C: Curve>(mu: usize, nu: usize) -> usize {
+    HyperNovaCycleFoldConfig::<C, 1, 1>::io_len(mu + nu)
+}
+
 /// Witness for the LCCCS & CCCS, containing the w vector, and the r_w used as randomness in the Pedersen commitment.
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Witness<F: PrimeField> {
@@ -90,6 +99,47 @@ impl<F: PrimeField> WitnessOps<F> for Witness<F> {
     }
 }
 
+/// Version of the serialization format of HyperNova's ProverParams, VerifierParams and IVCProof,
+/// which is written as the first byte of their serialization.
+pub const SERIALIZATION_VERSION: u8 = 1;
+
+fn check_serialization_version<R: std::io::prelude::Read>(
+    reader: R,
+    compress: Compress,
+    validate: Validate,
+) -> Result<(), Error> {
+    let version = u8::deserialize_with_mode(reader, compress, validate)?;
+    if version != SERIALIZATION_VERSION {
+        return Err(Error::UnsupportedSerializationVersion(
+            version,
+            SERIALIZATION_VERSION,
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the deserialized CCS parameters `(l, m, n, t, q, d)` match the ones of the CCS
+/// recomputed from the FCircuit.
+fn check_ccs_params<F: PrimeField>(ccs: &CCS<F>, params: &[u8]) -> Result<(), Error> {
+    if ccs.params_to_le_bytes() != params {
+        return Err(Error::DeserializedParamsMismatch("CCS".to_string()));
+    }
+    Ok(())
+}
+
+/// Checks that the commitment scheme parameters are long enough to commit to vectors of length
+/// `len`.
+fn check_cs_params_len<C: Curve, CS: CommitmentScheme<C, H>, const H: bool>(
+    params: &CS::ProverParams,
+    len: usize,
+) -> Result<(), Error> {
+    let max_len = CS::max_len(params);
+    if max_len < len {
+        return Err(Error::CommitmentParamsLen(max_len, len));
+    }
+    Ok(())
+}
+
 /// Proving parameters for HyperNova-based IVC
 #[derive(Debug, Clone)]
 pub struct ProverParams<C1, C2, CS1, CS2, const H: bool>
@@ -108,6 +158,27 @@ where
     /// CCS of the Augmented Function circuit
     /// If ccs is set, it will be used, if not, it will be computed at runtime
     pub ccs: Option<CCS<C1::ScalarField>>,
+    /// number of LCCCS instances folded at each step
+    pub mu: usize,
+    /// number of CCCS instances folded at each step
+    pub nu: usize,
+    /// Hash of the public parameters, computed at `preprocess` from the `VerifierParams` (see
+    /// [`VerifierParams::pp_hash`])
+    pub pp_hash: C1::ScalarField,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> ProverParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// returns the hash of the public parameters of HyperNova, which matches the one returned by
+    /// [`VerifierParams::pp_hash`] for the `VerifierParams` generated together with `self`.
+    pub fn pp_hash(&self) -> C1::ScalarField {
+        self.pp_hash
+    }
 }
 
 impl<
@@ -123,12 +194,30 @@ impl<
         mut writer: W,
         compress: Compress,
     ) -> Result<(), SerializationError> {
+        SERIALIZATION_VERSION.serialize_with_mode(&mut writer, compress)?;
         self.cs_pp.serialize_with_mode(&mut writer, compress)?;
-        self.cf_cs_pp.serialize_with_mode(&mut writer, compress)
+        self.cf_cs_pp.serialize_with_mode(&mut writer, compress)?;
+        self.mu.serialize_with_mode(&mut writer, compress)?;
+        self.nu.serialize_with_mode(&mut writer, compress)?;
+        self.pp_hash.serialize_with_mode(&mut writer, compress)?;
+        self.ccs
+            .as_ref()
+            .map(|ccs| ccs.params_to_le_bytes())
+            .serialize_with_mode(&mut writer, compress)
     }
 
     fn serialized_size(&self, compress: Compress) -> usize {
-        self.cs_pp.serialized_size(compress) + self.cf_cs_pp.serialized_size(compress)
+        SERIALIZATION_VERSION.serialized_size(compress)
+            + self.cs_pp.serialized_size(compress)
+            + self.cf_cs_pp.serialized_size(compress)
+            + self.mu.serialized_size(compress)
+            + self.nu.serialized_size(compress)
+            + self.pp_hash.serialized_size(compress)
+            + self
+                .ccs
+                .as_ref()
+                .map(|ccs| ccs.params_to_le_bytes())
+                .serialized_size(compress)
     }
 }
 
@@ -151,6 +240,10 @@ pub struct VerifierParams<
     pub cs_vp: CS1::VerifierParams,
     /// Verification parameters of the underlying commitment scheme over C2
     pub cf_cs_vp: CS2::VerifierParams,
+    /// number of LCCCS instances folded at each step
+    pub mu: usize,
+    /// number of CCCS instances folded at each step
+    pub nu: usize,
 }
 
 impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
@@ -160,17 +253,35 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
+    /// Serializes the VerifierParams together with its `pp_hash`, which is checked against the
+    /// one recomputed at deserialization, binding the serialized params to the FCircuit.
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
         mut writer: W,
         compress: ark_serialize::Compress,
     ) -> Result<(), ark_serialize::SerializationError> {
+        let pp_hash = self
+            .pp_hash()
+            .map_err(|_| SerializationError::InvalidData)?;
+        SERIALIZATION_VERSION.serialize_with_mode(&mut writer, compress)?;
         self.cs_vp.serialize_with_mode(&mut writer, compress)?;
-        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)
+        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)?;
+        self.mu.serialize_with_mode(&mut writer, compress)?;
+        self.nu.serialize_with_mode(&mut writer, compress)?;
+        pp_hash.serialize_with_mode(&mut writer, compress)?;
+        self.ccs
+            .params_to_le_bytes()
+            .serialize_with_mode(&mut writer, compress)
     }
 
     fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
-        self.cs_vp.serialized_size(compress) + self.cf_cs_vp.serialized_size(compress)
+        SERIALIZATION_VERSION.serialized_size(compress)
+            + self.cs_vp.serialized_size(compress)
+            + self.cf_cs_vp.serialized_size(compress)
+            + self.mu.serialized_size(compress)
+            + self.nu.serialized_size(compress)
+            + C1::ScalarField::zero().serialized_size(compress)
+            + self.ccs.params_to_le_bytes().serialized_size(compress)
     }
 }
 
@@ -193,12 +304,14 @@ where
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct IVCProof<C1, C2>
 where
     C1: Curve,
     C2: Curve,
 {
+    /// hash of the public parameters that the proof is bound to
+    pub pp_hash: C1::ScalarField,
     pub i: C1::ScalarField,
     pub z_0: Vec<C1::ScalarField>,
     pub z_i: Vec<C1::ScalarField>,
@@ -210,6 +323,95 @@ where
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
 }
 
+impl<C1: Curve, C2: Curve> Valid for IVCProof<C1, C2> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.pp_hash.check()?;
+        self.i.check()?;
+        self.z_0.check()?;
+        self.z_i.check()?;
+        self.W_i.check()?;
+        self.U_i.check()?;
+        self.w_i.check()?;
+        self.u_i.check()?;
+        self.cf_W_i.check()?;
+        self.cf_U_i.check()
+    }
+}
+
+impl<C1: Curve, C2: Curve> CanonicalSerialize for IVCProof<C1, C2> {
+    fn serialize_with_mode<W: std::io::prelude::Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        SERIALIZATION_VERSION.serialize_with_mode(&mut writer, compress)?;
+        self.pp_hash.serialize_with_mode(&mut writer, compress)?;
+        self.i.serialize_with_mode(&mut writer, compress)?;
+        self.z_0.serialize_with_mode(&mut writer, compress)?;
+        self.z_i.serialize_with_mode(&mut writer, compress)?;
+        self.W_i.serialize_with_mode(&mut writer, compress)?;
+        self.U_i.serialize_with_mode(&mut writer, compress)?;
+        self.w_i.serialize_with_mode(&mut writer, compress)?;
+        self.u_i.serialize_with_mode(&mut writer, compress)?;
+        self.cf_W_i.serialize_with_mode(&mut writer, compress)?;
+        self.cf_U_i.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        SERIALIZATION_VERSION.serialized_size(compress)
+            + self.pp_hash.serialized_size(compress)
+            + self.i.serialized_size(compress)
+            + self.z_0.serialized_size(compress)
+            + self.z_i.serialized_size(compress)
+            + self.W_i.serialized_size(compress)
+            + self.U_i.serialized_size(compress)
+            + self.w_i.serialized_size(compress)
+            + self.u_i.serialized_size(compress)
+            + self.cf_W_i.serialized_size(compress)
+            + self.cf_U_i.serialized_size(compress)
+    }
+}
+
+impl<C1: Curve, C2: Curve> IVCProof<C1, C2> {
+    /// Deserializes the IVCProof, returning [`Error::UnsupportedSerializationVersion`] if it was
+    /// serialized with a different [`SERIALIZATION_VERSION`].
+    pub fn deserialize_versioned<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, Error> {
+        check_serialization_version(&mut reader, compress, validate)?;
+        Ok(Self {
+            pp_hash: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            z_0: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            z_i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            W_i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            U_i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            w_i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            u_i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            cf_W_i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+            cf_U_i: CanonicalDeserialize::deserialize_with_mode(&mut reader, compress, validate)?,
+        })
+    }
+}
+
+impl<C1: Curve, C2: Curve> CanonicalDeserialize for IVCProof<C1, C2> {
+    /// Errors other than the ones of `ark_serialize` (ie. an unsupported serialization version)
+    /// are wrapped into an `io::Error`, use [`IVCProof::deserialize_versioned`] to get them as an
+    /// [`Error`].
+    fn deserialize_with_mode<R: std::io::prelude::Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Self::deserialize_versioned(reader, compress, validate).map_err(|e| match e {
+            Error::SerializationError(e) => e,
+            e => SerializationError::IoError(std::io::Error::other(e)),
+        })
+    }
+}
+
 /// Implements HyperNova+CycleFold's IVC, described in
 /// [HyperNova](https://eprint.iacr.org/2023/573.pdf) and
 /// [CycleFold](https://eprint.iacr.org/2023/1192.pdf), following the FoldingScheme trait
@@ -217,6 +419,10 @@ where
 /// For multi-instance folding, one needs to specify the const generics below:
 /// * `MU` - the number of LCCCS instances to be folded
 /// * `NU` - the number of CCCS instances to be folded
+///
+/// `MU` and `NU` are the values used by `FoldingScheme::preprocess`. To choose them at runtime
+/// instead, use [`HyperNova::preprocess_with_mu_nu`], whose values are recorded in the params and
+/// take precedence over the const generics in the rest of the methods.
 #[derive(Clone, Debug)]
 pub struct HyperNova<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool>
 where
@@ -226,6 +432,10 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
+    /// number of LCCCS instances folded at each step
+    pub mu: usize,
+    /// number of CCCS instances folded at each step
+    pub nu: usize,
     /// CCS of the Augmented Function circuit
     pub ccs: CCS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
@@ -324,6 +534,79 @@ where
     CS2: CommitmentScheme<C2, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
 {
+    /// Same as `FoldingScheme::preprocess`, but with the number of LCCCS (`mu`) and CCCS (`nu`)
+    /// instances folded at each step chosen at runtime instead of taken from the `MU` & `NU`
+    /// const generics. The values are recorded in the returned params, which are then used by
+    /// the rest of the methods.
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess_with_mu_nu(
+        mut rng: impl RngCore,
+        prep_param: &PreprocessorParam<C1, C2, FC, CS1, CS2, H>,
+        mu: usize,
+        nu: usize,
+    ) -> Result<
+        (
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        Error,
+    > {
+        if mu < 1 || nu < 1 {
+            return Err(Error::CantBeZero("mu,nu".to_string()));
+        }
+
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(
+            &prep_param.poseidon_config,
+            prep_param.F.clone(),
+            None,
+            mu,
+            nu,
+        )?;
+        let ccs = augmented_f_circuit.ccs.clone();
+
+        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty_with_n_points(mu + nu);
+        let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            // `CS1` is for committing to HyperNova's witness vector `w`, so we
+            // set `len` to the number of witnesses in `r1cs`.
+            _ => CS1::setup(&mut rng, ccs.n_witnesses())?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(
+                &mut rng,
+                // `CS2` is for committing to CycleFold's witness vector `w` and
+                // error term `e`, where the length of `e` is the number of
+                // constraints, so we set `len` to the maximum of `e` and `w`'s
+                // lengths.
+                max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
+            )?,
+        };
+
+        let vp = VerifierParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            ccs: ccs.clone(),
+            cf_r1cs,
+            cs_vp: cs_vp.clone(),
+            cf_cs_vp: cf_cs_vp.clone(),
+            mu,
+            nu,
+        };
+        let pp = ProverParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+            ccs: Some(ccs),
+            mu,
+            nu,
+            pp_hash: vp.pp_hash()?,
+        };
+        Ok((pp, vp))
+    }
+
     /// internal helper for new_running_instance & new_incoming_instance methods, returns the R1CS
     /// z=[u,x,w] vector to be used to create the LCCCS & CCCS fresh instances.
     fn new_instance_generic(
@@ -331,6 +614,9 @@ where
         state: Vec<C1::ScalarField>,
         external_inputs: FC::ExternalInputs,
     ) -> Result<Vec<C1::ScalarField>, Error> {
+        let hint = self.F.precompute_witness(0, &state, &external_inputs)?;
+        let external_inputs = self.F.with_witness_hint(external_inputs, hint);
+
         // prepare the initial dummy instances
         let U_i = LCCCS::<C1>::dummy(&self.ccs);
         let mut u_i = CCCS::<C1>::dummy(&self.ccs);
@@ -349,12 +635,14 @@ where
             ),
             cf_U_i.hash_cyclefold(&sponge, self.pp_hash),
         ];
-        let us = vec![u_i.clone(); NU - 1];
+        let us = vec![u_i.clone(); self.nu - 1];
 
         // compute u_{i+1}.x
         let U_i1 = LCCCS::dummy(&self.ccs);
 
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC> {
+            mu: self.mu,
+            nu: self.nu,
             poseidon_config: self.poseidon_config.clone(),
             ccs: self.ccs.clone(),
             pp_hash: Some(self.pp_hash),
@@ -378,7 +666,9 @@ where
         };
 
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-        augmented_f_circuit.generate_constraints(cs.clone())?;
+        augmented_f_circuit
+            .generate_constraints(cs.clone())
+            .map_err(|e| self.F.take_step_error().unwrap_or(e.into()))?;
         let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
 
         #[cfg(test)]
@@ -425,26 +715,50 @@ where
     ) -> Result<Self::ProverParam, Error> {
         let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
 
-        // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
-        // to serialize them, saving significant space in the VerifierParams serialized size.
+        // generate the ccs needed for the ProverParams. In this way we avoid needing to serialize
+        // it, and only serialize its parameters to check that they match with the FCircuit.
+
+        check_serialization_version(&mut reader, compress, validate)?;
+        let cs_pp = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_pp = CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let mu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let nu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let pp_hash = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let ccs_params = Option::<Vec<u8>>::deserialize_with_mode(&mut reader, compress, validate)?;
 
         // main circuit R1CS:
         let f_circuit = FC::new(fc_params)?;
-        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(
             &poseidon_config,
             f_circuit.clone(),
             None,
+            mu,
+            nu,
         )?;
         let ccs = augmented_F_circuit.ccs;
+        if let Some(ccs_params) = ccs_params {
+            check_ccs_params(&ccs, &ccs_params)?;
+        }
 
-        let cs_pp = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
-        let cf_cs_pp = CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        // CycleFold circuit R1CS
+        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty_with_n_points(mu + nu);
+        let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+
+        // check that the commitment schemes' params are long enough for the circuits
+        check_cs_params_len::<C1, CS1, H>(&cs_pp, ccs.n_witnesses())?;
+        check_cs_params_len::<C2, CS2, H>(
+            &cf_cs_pp,
+            max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
+        )?;
 
         Ok(ProverParams {
             poseidon_config,
             cs_pp,
             cf_cs_pp,
             ccs: Some(ccs),
+            mu,
+            nu,
+            pp_hash,
         })
     }
 
@@ -459,82 +773,51 @@ where
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
+        check_serialization_version(&mut reader, compress, validate)?;
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let mu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let nu = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let pp_hash = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let ccs_params = Vec::<u8>::deserialize_with_mode(&mut reader, compress, validate)?;
+
         // main circuit R1CS:
         let f_circuit = FC::new(fc_params)?;
-        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(
             &poseidon_config,
             f_circuit.clone(),
             None,
+            mu,
+            nu,
         )?;
         let ccs = augmented_F_circuit.ccs;
+        check_ccs_params(&ccs, &ccs_params)?;
 
         // CycleFold circuit R1CS
-        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty();
+        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty_with_n_points(mu + nu);
         let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
 
-        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
-        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
-
-        Ok(VerifierParams {
+        let vp = VerifierParams {
             poseidon_config,
             ccs,
             cf_r1cs,
             cs_vp,
             cf_cs_vp,
-        })
+            mu,
+            nu,
+        };
+        // the serialized pp_hash binds the commitment schemes' params to the circuits
+        if vp.pp_hash()? != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+        Ok(vp)
     }
 
     fn preprocess(
-        mut rng: impl RngCore,
+        rng: impl RngCore,
         prep_param: &Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        if MU < 1 || NU < 1 {
-            return Err(Error::CantBeZero("mu,nu".to_string()));
-        }
-
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
-            &prep_param.poseidon_config,
-            prep_param.F.clone(),
-            None,
-        )?;
-        let ccs = augmented_f_circuit.ccs.clone();
-
-        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty();
-        let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
-
-        // if cs params exist, use them, if not, generate new ones
-        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
-            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
-            // `CS1` is for committing to HyperNova's witness vector `w`, so we
-            // set `len` to the number of witnesses in `r1cs`.
-            _ => CS1::setup(&mut rng, ccs.n_witnesses())?,
-        };
-        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
-            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
-            _ => CS2::setup(
-                &mut rng,
-                // `CS2` is for committing to CycleFold's witness vector `w` and
-                // error term `e`, where the length of `e` is the number of
-                // constraints, so we set `len` to the maximum of `e` and `w`'s
-                // lengths.
-                max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
-            )?,
-        };
-
-        let pp = ProverParams::<C1, C2, CS1, CS2, H> {
-            poseidon_config: prep_param.poseidon_config.clone(),
-            cs_pp,
-            cf_cs_pp,
-            ccs: Some(ccs.clone()),
-        };
-        let vp = VerifierParams::<C1, C2, CS1, CS2, H> {
-            poseidon_config: prep_param.poseidon_config.clone(),
-            ccs,
-            cf_r1cs,
-            cs_vp: cs_vp.clone(),
-            cf_cs_vp: cf_cs_vp.clone(),
-        };
-        Ok((pp, vp))
+        Self::preprocess_with_mu_nu(rng, prep_param, MU, NU)
     }
 
     /// Initializes the HyperNova+CycleFold's IVC for the given parameters and initial state `z_0`.
@@ -544,28 +827,37 @@ where
         z_0: Vec<C1::ScalarField>,
     ) -> Result<Self, Error> {
         let (pp, vp) = params;
-        if MU < 1 || NU < 1 {
+        let (mu, nu) = (pp.mu, pp.nu);
+        if mu < 1 || nu < 1 {
             return Err(Error::CantBeZero("mu,nu".to_string()));
         }
+        if (mu, nu) != (vp.mu, vp.nu) {
+            return Err(Error::NotEqual);
+        }
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+        if pp.pp_hash != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
 
         // `sponge` is for digest computation.
         let sponge = PoseidonSponge::<C1::ScalarField>::new(&pp.poseidon_config);
 
         // prepare the HyperNova's AugmentedFCircuit and CycleFold's circuits and obtain its CCS
         // and R1CS respectively
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(
             &pp.poseidon_config,
             F.clone(),
             pp.ccs.clone(),
+            mu,
+            nu,
         )?;
         let ccs = augmented_f_circuit.ccs.clone();
 
-        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty();
+        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty_with_n_points(mu + nu);
         let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
 
-        // compute the public params hash
-        let pp_hash = vp.pp_hash()?;
-
         // setup the dummy instances
         let W_dummy = Witness::<C1::ScalarField>::dummy(&ccs);
         let U_dummy = LCCCS::<C1>::dummy(&ccs);
@@ -581,6 +873,8 @@ where
         // W_dummy=W_0 is a 'dummy witness', all zeroes, but with the size corresponding to the
         // R1CS that we're working with.
         Ok(Self {
+            mu,
+            nu,
             ccs,
             cf_r1cs,
             poseidon_config: pp.poseidon_config.clone(),
@@ -624,8 +918,9 @@ where
             }
         }
 
-        let (Us, Ws, us, ws) = if MU > 1 || NU > 1 {
-            let other_instances = other_instances.ok_or(Error::MissingOtherInstances(MU, NU))?;
+        let (Us, Ws, us, ws) = if self.mu > 1 || self.nu > 1 {
+            let other_instances =
+                other_instances.ok_or(Error::MissingOtherInstances(self.mu, self.nu))?;
 
             #[allow(clippy::type_complexity)]
             let (lcccs, cccs): (
@@ -636,20 +931,20 @@ where
             // recall, mu & nu is the number of all the LCCCS & CCCS respectively, including the
             // running and incoming instances that are not part of the 'other_instances', hence the +1
             // in the couple of following checks.
-            if lcccs.len() + 1 != MU {
+            if lcccs.len() + 1 != self.mu {
                 return Err(Error::NotSameLength(
                     "other_instances.lcccs.len()".to_string(),
                     lcccs.len(),
                     "hypernova.mu".to_string(),
-                    MU,
+                    self.mu,
                 ));
             }
-            if cccs.len() + 1 != NU {
+            if cccs.len() + 1 != self.nu {
                 return Err(Error::NotSameLength(
                     "other_instances.cccs.len()".to_string(),
                     cccs.len(),
                     "hypernova.nu".to_string(),
-                    NU,
+                    self.nu,
                 ));
             }
 
@@ -661,7 +956,7 @@ where
             (vec![], vec![], vec![], vec![])
         };
 
-        let augmented_f_circuit: AugmentedFCircuit<C1, C2, FC, MU, NU>;
+        let augmented_f_circuit: AugmentedFCircuit<C1, C2, FC>;
 
         if self.z_i.len() != self.F.state_len() {
             return Err(Error::NotSameLength(
@@ -692,6 +987,11 @@ where
             i_usize = usize::from_le_bytes(i_bytes);
         }
 
+        let hint = self
+            .F
+            .precompute_witness(i_usize, &self.z_i, &external_inputs)?;
+        let external_inputs = self.F.with_witness_hint(external_inputs, hint);
+
         let (U_i1, mut W_i1);
 
         if self.i == C1::ScalarField::zero() {
@@ -699,7 +999,9 @@ where
             W_i1.r_w = self.W_i.r_w;
             U_i1 = LCCCS::dummy(&self.ccs);
 
-            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                mu: self.mu,
+                nu: self.nu,
                 poseidon_config: self.poseidon_config.clone(),
                 ccs: self.ccs.clone(),
                 pp_hash: Some(self.pp_hash),
@@ -764,16 +1066,18 @@ where
             let (cf_u_i, cf_W_i1, cf_U_i1, cf_cmT) =
                 fold_cyclefold_circuit::<HyperNovaCycleFoldConfig<C1, MU, NU>, C2, CS2, H>(
                     &mut transcript_p,
-                    self.cf_r1cs.clone(),
-                    self.cf_cs_pp.clone(),
+                    &self.cf_r1cs,
+                    &self.cf_cs_pp,
                     self.pp_hash,
-                    self.cf_W_i.clone(), // CycleFold running instance witness
-                    self.cf_U_i.clone(), // CycleFold running instance
+                    &self.cf_W_i, // CycleFold running instance witness
+                    &self.cf_U_i, // CycleFold running instance
                     cf_circuit,
                     &mut rng,
                 )?;
 
-            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                mu: self.mu,
+                nu: self.nu,
                 poseidon_config: self.poseidon_config.clone(),
                 ccs: self.ccs.clone(),
                 pp_hash: Some(self.pp_hash),
@@ -803,7 +1107,8 @@ where
 
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
         let z_i1 = augmented_f_circuit
-            .compute_next_state(cs.clone())?
+            .compute_next_state(cs.clone())
+            .map_err(|e| self.F.take_step_error().unwrap_or(e.into()))?
             .value()?;
         let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
 
@@ -850,6 +1155,7 @@ where
 
     fn ivc_proof(&self) -> Self::IVCProof {
         Self::IVCProof {
+            pp_hash: self.pp_hash,
             i: self.i,
             z_0: self.z_0.clone(),
             z_i: self.z_i.clone(),
@@ -868,6 +1174,7 @@ where
         params: (Self::ProverParam, Self::VerifierParam),
     ) -> Result<Self, Error> {
         let IVCProof {
+            pp_hash,
             i,
             z_0,
             z_i,
@@ -880,25 +1187,37 @@ where
         } = ivc_proof;
         let (pp, vp) = params;
 
+        let (mu, nu) = (pp.mu, pp.nu);
+        if (mu, nu) != (vp.mu, vp.nu) {
+            return Err(Error::NotEqual);
+        }
+        if pp_hash != vp.pp_hash()? || pp_hash != pp.pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+
         let f_circuit = FC::new(fcircuit_params)?;
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(
             &pp.poseidon_config,
             f_circuit.clone(),
             None,
+            mu,
+            nu,
         )?;
-        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty();
+        let cf_circuit = HyperNovaCycleFoldCircuit::<C1, MU, NU>::empty_with_n_points(mu + nu);
 
         let ccs = augmented_f_circuit.ccs.clone();
         let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
 
         Ok(Self {
+            mu,
+            nu,
             ccs,
             cf_r1cs,
             poseidon_config: pp.poseidon_config,
             cs_pp: pp.cs_pp,
             cf_cs_pp: pp.cf_cs_pp,
             F: f_circuit,
-            pp_hash: vp.pp_hash()?,
+            pp_hash,
             i,
             z_0,
             z_i,
@@ -915,6 +1234,7 @@ where
     /// commitments verification, which is done in the Decider.
     fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
         let Self::IVCProof {
+            pp_hash,
             i: num_steps,
             z_0,
             z_i,
@@ -926,6 +1246,13 @@ where
             cf_U_i,
         } = ivc_proof;
 
+        if vp.mu < 1 || vp.nu < 1 {
+            return Err(Error::CantBeZero("mu,nu".to_string()));
+        }
+        if pp_hash != vp.pp_hash()? {
+            return Err(Error::PPHashMismatch);
+        }
+
         if num_steps == C1::ScalarField::zero() {
             if z_0 != z_i {
                 return Err(Error::IVCVerificationFail);
@@ -938,8 +1265,10 @@ where
         if u_i.x.len() != 2 || U_i.x.len() != 2 {
             return Err(Error::IVCVerificationFail);
         }
-
-        let pp_hash = vp.pp_hash()?;
+        // the CycleFold instance must fold the `mu + nu` commitments set in the params
+        if cf_U_i.x.len() != cf_io_len::<C1>(vp.mu, vp.nu) {
+            return Err(Error::IVCVerificationFail);
+        }
 
         // check that u_i's output points to the running instance
         // u_i.X[0] == H(i, z_0, z_i, U_i)
@@ -974,7 +1303,7 @@ mod tests {
 
     use super::*;
     use crate::commitment::pedersen::Pedersen;
-    use crate::frontend::utils::CubicFCircuit;
+    use crate::frontend::utils::{CubicFCircuit, CustomFCircuit};
     use crate::transcript::poseidon::poseidon_canonical_config;
 
     #[test]
@@ -1064,4 +1393,174 @@ mod tests {
         )?;
         Ok(())
     }
+
+    #[test]
+    pub fn test_ivc_runtime_mu_nu() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // the const generics are only used by `FoldingScheme::preprocess`, the actual mu & nu are
+        // the ones chosen at runtime below
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            1,
+            1,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+
+        for (mu, nu) in [(2, 2), (1, 3)] {
+            let params = HN::preprocess_with_mu_nu(&mut rng, &prep_param, mu, nu)?;
+            assert_eq!((params.0.mu, params.0.nu), (mu, nu));
+            assert_eq!((params.1.mu, params.1.nu), (mu, nu));
+
+            let z_0 = vec![Fr::from(3_u32)];
+            let mut hypernova = HN::init(&params, F_circuit, z_0)?;
+
+            let num_steps: usize = 3;
+            for _ in 0..num_steps {
+                let lcccs = (0..mu - 1)
+                    .map(|j| {
+                        hypernova.new_running_instance(&mut rng, vec![Fr::from(j as u32 + 85)], ())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let cccs = (0..nu - 1)
+                    .map(|j| {
+                        hypernova.new_incoming_instance(&mut rng, vec![Fr::from(j as u32 + 15)], ())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                hypernova.prove_step(&mut rng, (), Some((lcccs, cccs)))?;
+            }
+            HN::verify(params.1.clone(), hypernova.ivc_proof())?;
+
+            // the params are bound to the (mu, nu) used to generate them
+            let other_params = HN::preprocess_with_mu_nu(&mut rng, &prep_param, mu, nu + 1)?;
+            assert!(HN::verify(other_params.1, hypernova.ivc_proof()).is_err());
+        }
+
+        // mu & nu can not be zero
+        assert!(HN::preprocess_with_mu_nu(&mut rng, &prep_param, 0, 1).is_err());
+        Ok(())
+    }
+
+    type SerHN = HyperNova<
+        Projective,
+        Projective2,
+        CustomFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+        2,
+        2,
+        false,
+    >;
+    type SerHNProverParams =
+        ProverParams<Projective, Projective2, Pedersen<Projective>, Pedersen<Projective2>, false>;
+    type SerHNVerifierParams =
+        VerifierParams<Projective, Projective2, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+    const SER_N_CONSTRAINTS: usize = 10;
+
+    /// returns the serialized params and IVCProof of a few folding steps over `CustomFCircuit`
+    fn serialized_params_and_proof() -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CustomFCircuit::<Fr>::new(SER_N_CONSTRAINTS)?;
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = SerHN::preprocess(&mut rng, &prep_param)?;
+        let mut hypernova = SerHN::init(&params, F_circuit, vec![Fr::from(3_u32)])?;
+        for _ in 0..2 {
+            hypernova.prove_step(&mut rng, (), None)?;
+        }
+
+        let mut pp_bytes = vec![];
+        params.0.serialize_compressed(&mut pp_bytes)?;
+        let mut vp_bytes = vec![];
+        params.1.serialize_compressed(&mut vp_bytes)?;
+        let mut proof_bytes = vec![];
+        hypernova
+            .ivc_proof()
+            .serialize_compressed(&mut proof_bytes)?;
+        Ok((pp_bytes, vp_bytes, proof_bytes))
+    }
+
+    fn deserialize_pp(bytes: &[u8], n_constraints: usize) -> Result<SerHNProverParams, Error> {
+        SerHN::pp_deserialize_with_mode(bytes, Compress::Yes, Validate::Yes, n_constraints)
+    }
+
+    fn deserialize_vp(bytes: &[u8], n_constraints: usize) -> Result<SerHNVerifierParams, Error> {
+        SerHN::vp_deserialize_with_mode(bytes, Compress::Yes, Validate::Yes, n_constraints)
+    }
+
+    #[test]
+    fn test_serialization() -> Result<(), Error> {
+        let (pp_bytes, vp_bytes, proof_bytes) = serialized_params_and_proof()?;
+
+        let pp = deserialize_pp(&pp_bytes, SER_N_CONSTRAINTS)?;
+        let vp = deserialize_vp(&vp_bytes, SER_N_CONSTRAINTS)?;
+        let ivc_proof = IVCProof::<Projective, Projective2>::deserialize_compressed(&*proof_bytes)?;
+        assert_eq!(pp.pp_hash(), vp.pp_hash()?);
+        assert_eq!(ivc_proof.pp_hash, pp.pp_hash());
+        SerHN::verify(vp.clone(), ivc_proof.clone())?;
+
+        // the deserialized params can be used to resume the folding
+        let mut rng = ark_std::test_rng();
+        let mut hypernova = SerHN::from_ivc_proof(ivc_proof, SER_N_CONSTRAINTS, (pp, vp.clone()))?;
+        hypernova.prove_step(&mut rng, (), None)?;
+        SerHN::verify(vp, hypernova.ivc_proof())?;
+
+        // unknown serialization versions are rejected
+        let mut wrong_version = vp_bytes.clone();
+        wrong_version[0] = SERIALIZATION_VERSION + 1;
+        assert!(matches!(
+            deserialize_vp(&wrong_version, SER_N_CONSTRAINTS),
+            Err(Error::UnsupportedSerializationVersion(..))
+        ));
+        let mut wrong_version = pp_bytes.clone();
+        wrong_version[0] = SERIALIZATION_VERSION + 1;
+        assert!(matches!(
+            deserialize_pp(&wrong_version, SER_N_CONSTRAINTS),
+            Err(Error::UnsupportedSerializationVersion(..))
+        ));
+        let mut wrong_version = proof_bytes.clone();
+        wrong_version[0] = SERIALIZATION_VERSION + 1;
+        assert!(matches!(
+            IVCProof::<Projective, Projective2>::deserialize_versioned(
+                &*wrong_version,
+                Compress::Yes,
+                Validate::Yes
+            ),
+            Err(Error::UnsupportedSerializationVersion(..))
+        ));
+        assert!(
+            IVCProof::<Projective, Projective2>::deserialize_compressed(&*wrong_version).is_err()
+        );
+
+        // the params are bound to the FCircuit they were generated for
+        assert!(matches!(
+            deserialize_vp(&vp_bytes, 2 * SER_N_CONSTRAINTS),
+            Err(Error::DeserializedParamsMismatch(..))
+        ));
+        assert!(matches!(
+            deserialize_pp(&pp_bytes, 2 * SER_N_CONSTRAINTS),
+            Err(Error::DeserializedParamsMismatch(..))
+        ));
+
+        // without the CCS params, the commitment params are still checked to be long enough
+        let mut pp = deserialize_pp(&pp_bytes, SER_N_CONSTRAINTS)?;
+        pp.ccs = None;
+        let mut pp_bytes = vec![];
+        pp.serialize_compressed(&mut pp_bytes)?;
+        assert!(deserialize_pp(&pp_bytes, SER_N_CONSTRAINTS).is_ok());
+        assert!(matches!(
+            deserialize_pp(&pp_bytes, 1000 * SER_N_CONSTRAINTS),
+            Err(Error::CommitmentParamsLen(..))
+        ));
+        Ok(())
+    }
 }
@@ -16,6 +16,7 @@ use crate::folding::traits::Dummy;
 use crate::transcript::Transcript;
 use crate::utils::sum_check::structs::{IOPProof as SumCheckProof, IOPProverMessage};
 use crate::utils::sum_check::{IOPSumCheck, SumCheck};
+use crate::utils::vec::axpy_in_place;
 use crate::utils::virtual_polynomial::VPAuxInfo;
 use crate::{Curve, Error};
 
@@ -136,43 +137,22 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         w_lcccs: &[Witness<C::ScalarField>],
         w_cccs: &[Witness<C::ScalarField>],
         rho: C::ScalarField,
-    ) -> Witness<C::ScalarField> {
+    ) -> Result<Witness<C::ScalarField>, Error> {
         let mut w_folded: Vec<C::ScalarField> = vec![C::ScalarField::zero(); w_lcccs[0].w.len()];
         let mut r_w_folded = C::ScalarField::zero();
 
         let mut rho_i = C::ScalarField::one();
-        for i in 0..(w_lcccs.len() + w_cccs.len()) {
-            // let rho_i = rho.pow([i as u64]);
-            let w: Vec<C::ScalarField>;
-            let r_w: C::ScalarField;
-
-            if i < w_lcccs.len() {
-                w = w_lcccs[i].w.clone();
-                r_w = w_lcccs[i].r_w;
-            } else {
-                w = w_cccs[i - w_lcccs.len()].w.clone();
-                r_w = w_cccs[i - w_lcccs.len()].r_w;
-            }
-
-            w_folded = w_folded
-                .iter()
-                .zip(
-                    w.iter()
-                        .map(|x_i| *x_i * rho_i)
-                        .collect::<Vec<C::ScalarField>>(),
-                )
-                .map(|(a_i, b_i)| *a_i + b_i)
-                .collect();
-
-            r_w_folded += rho_i * r_w;
+        for w_i in w_lcccs.iter().chain(w_cccs) {
+            axpy_in_place(&mut w_folded, &rho_i, &w_i.w)?;
+            r_w_folded += rho_i * w_i.r_w;
 
             // compute the next power of rho
             rho_i *= rho;
         }
-        Witness {
+        Ok(Witness {
             w: w_folded,
             r_w: r_w_folded,
-        }
+        })
     }
 
     /// Performs the multifolding prover. Given μ LCCCS instances and ν CCS instances, fold them
@@ -269,7 +249,7 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         );
 
         // Step 8: Fold the witnesses
-        let folded_witness = Self::fold_witness(w_lcccs, w_cccs, rho);
+        let folded_witness = Self::fold_witness(w_lcccs, w_cccs, rho)?;
 
         Ok((
             NIMFSProof::<C> {
@@ -448,7 +428,7 @@ pub mod tests {
             rho,
         );
 
-        let w_folded = NIMFS::<Projective, PoseidonSponge<Fr>>::fold_witness(&[w1], &[w2], rho);
+        let w_folded = NIMFS::<Projective, PoseidonSponge<Fr>>::fold_witness(&[w1], &[w2], rho)?;
 
         // check lcccs relation
         ccs.check_relation(&w_folded, &folded)?;
@@ -32,7 +32,6 @@ use crate::folding::circuits::{
 };
 use crate::folding::traits::{CommittedInstanceVarOps, Dummy};
 use crate::frontend::FCircuit;
-use crate::transcript::AbsorbNonNativeGadget;
 use crate::Curve;
 
 /// `AugmentedFCircuit` enhances the original step function `F`, so that it can
@@ -64,13 +63,10 @@ pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
     pub(super) F: FC, // F circuit
 
     // cyclefold verifier on C1
-    // Here 'cf1, cf2' are for each of the CycleFold circuits, corresponding to the fold of cmW and
-    // cmE respectively
-    pub(super) cf1_u_i_cmW: Option<C2>, // input
-    pub(super) cf2_u_i_cmW: Option<C2>, // input
+    // A single CycleFold instance checks both the fold of cmW and the fold of cmE
+    pub(super) cf_u_i_cmW: Option<C2>, // input
     pub(super) cf_U_i: Option<CycleFoldCommittedInstance<C2>>, // input
-    pub(super) cf1_cmT: Option<C2>,
-    pub(super) cf2_cmT: Option<C2>,
+    pub(super) cf_cmT: Option<C2>,
 }
 
 impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC> {
@@ -90,11 +86,9 @@ impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC>
             cmT: None,
             F: F_circuit,
             // cyclefold values
-            cf1_u_i_cmW: None,
-            cf2_u_i_cmW: None,
+            cf_u_i_cmW: None,
             cf_U_i: None,
-            cf1_cmT: None,
-            cf2_cmT: None,
+            cf_cmT: None,
         }
     }
 }
@@ -147,10 +141,7 @@ where
         let cf_U_i = CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || {
             Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
         })?;
-        let cf1_cmT =
-            C2::Var::new_witness(cs.clone(), || Ok(self.cf1_cmT.unwrap_or_else(C2::zero)))?;
-        let cf2_cmT =
-            C2::Var::new_witness(cs.clone(), || Ok(self.cf2_cmT.unwrap_or_else(C2::zero)))?;
+        let cf_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf_cmT.unwrap_or_else(C2::zero)))?;
 
         // `sponge` is for digest computation.
         let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
@@ -244,72 +235,40 @@ where
         FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
 
         // CycleFold part
-        // C.1. Compute cf1_u_i.x and cf2_u_i.x
-        let cfW_x = vec![
-            r_nonnat.clone(),
-            U_i.cmW.x,
-            U_i.cmW.y,
-            u_i.cmW.x,
-            u_i.cmW.y,
-            U_i1.cmW.x,
-            U_i1.cmW.y,
-        ];
-        let cfE_x = vec![
-            r_nonnat, U_i.cmE.x, U_i.cmE.y, cmT.x, cmT.y, U_i1.cmE.x, U_i1.cmE.y,
+        // C.1. Compute cf_u_i.x, which contains the coordinates of the commitments cmW & cmE of
+        // the main instances U_i, u_i, U_i+1 (and of cmT), since a single CycleFold instance
+        // checks the fold of both
+        let cf_points_x = vec![
+            r_nonnat, U_i.cmW.x, U_i.cmW.y, u_i.cmW.x, u_i.cmW.y, U_i1.cmW.x, U_i1.cmW.y,
+            U_i.cmE.x, U_i.cmE.y, cmT.x, cmT.y, U_i1.cmE.x, U_i1.cmE.y,
         ];
 
-        // ensure that cf1_u & cf2_u have as public inputs the cmW & cmE from main instances U_i,
-        // u_i, U_i+1 coordinates of the commitments
-        // C.2. Construct `cf1_u_i` and `cf2_u_i`
-        let cf1_u_i = CycleFoldCommittedInstanceVar {
-            // cf1_u_i.cmE = 0
+        // C.2. Construct `cf_u_i`
+        let cf_u_i = CycleFoldCommittedInstanceVar {
+            // cf_u_i.cmE = 0
             cmE: C2::Var::zero(),
-            // cf1_u_i.u = 1
+            // cf_u_i.u = 1
             u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
-            // cf1_u_i.cmW is provided by the prover as witness
-            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW.unwrap_or(C2::zero())))?,
-            // cf1_u_i.x is computed in step 1
-            x: cfW_x,
-        };
-        let cf2_u_i = CycleFoldCommittedInstanceVar {
-            // cf2_u_i.cmE = 0
-            cmE: C2::Var::zero(),
-            // cf2_u_i.u = 1
-            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
-            // cf2_u_i.cmW is provided by the prover as witness
-            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW.unwrap_or(C2::zero())))?,
-            // cf2_u_i.x is computed in step 1
-            x: cfE_x,
+            // cf_u_i.cmW is provided by the prover as witness
+            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf_u_i.x is computed in step 1
+            x: cf_points_x,
         };
 
-        // C.3. nifs.verify, obtains cf1_U_{i+1} by folding cf1_u_i & cf_U_i, and then cf_U_{i+1}
-        // by folding cf2_u_i & cf1_U_{i+1}.
+        // C.3. nifs.verify, obtains cf_U_{i+1} by folding cf_u_i & cf_U_i.
 
-        // compute cf1_r = H(cf1_u_i, cf_U_i, cf1_cmT)
+        // compute cf_r = H(cf_u_i, cf_U_i, cf_cmT)
         // cf_r_bits is denoted by rho* in the paper.
-        let cf1_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+        let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
             &mut transcript,
             pp_hash.clone(),
             cf_U_i_vec,
-            cf1_u_i.clone(),
-            cf1_cmT.clone(),
-        )?;
-        // Fold cf1_u_i & cf_U_i into cf1_U_{i+1}
-        let cf1_U_i1 =
-            NIFSFullGadget::<C2>::fold_committed_instance(cf1_r_bits, cf1_cmT, cf_U_i, cf1_u_i)?;
-
-        // same for cf2_r:
-        let cf2_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
-            &mut transcript,
-            pp_hash.clone(),
-            cf1_U_i1.to_native_sponge_field_elements()?,
-            cf2_u_i.clone(),
-            cf2_cmT.clone(),
-        )?;
-        let cf_U_i1 = NIFSFullGadget::<C2>::fold_committed_instance(
-            cf2_r_bits, cf2_cmT, cf1_U_i1, // the output from NIFS.V(cf1_r, cf_U, cfE_u)
-            cf2_u_i,
+            cf_u_i.clone(),
+            cf_cmT.clone(),
         )?;
+        // Fold cf_u_i & cf_U_i into cf_U_{i+1}
+        let cf_U_i1 =
+            NIFSFullGadget::<C2>::fold_committed_instance(cf_r_bits, cf_cmT, cf_U_i, cf_u_i)?;
 
         // Back to Primary Part
         // P.4.b compute and check the second output of F'
@@ -6,13 +6,13 @@ use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
 use ark_std::rand::{CryptoRng, RngCore};
-use ark_std::{One, Zero};
+use ark_std::One;
 use core::marker::PhantomData;
 
 use super::decider_circuits::{DeciderCircuit1, DeciderCircuit2};
 use super::decider_eth_circuit::DeciderNovaGadget;
 use super::Nova;
-use crate::commitment::CommitmentScheme;
+use crate::commitment::{CommitmentScheme, EvaluationProof};
 use crate::folding::circuits::cyclefold::CycleFoldCommittedInstance;
 use crate::folding::circuits::decider::DeciderEnabledNIFS;
 use crate::folding::traits::{
@@ -24,12 +24,12 @@ use crate::{Curve, Error};
 use crate::{Decider as DeciderTrait, FoldingScheme};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub struct Proof<C1, C2, CS1, CS2, S1, S2>
+pub struct Proof<C1, C2, CS1, CS2, S1, S2, const H: bool = false>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
     S1: SNARK<C1::ScalarField>,
     S2: SNARK<C2::ScalarField>,
 {
@@ -79,9 +79,11 @@ where
     pub c2_cs_vp: CS2_VerifyingKey,
 }
 
-/// Onchain Decider, for ethereum use cases
+/// Offchain Decider. The `H` parameter indicates if the commitments of the folded Nova instance
+/// are hiding, in which case the opening proofs also hide the committed witness. Note that the
+/// evaluations of the committed vectors at the challenges are public inputs of the SNARKs.
 #[derive(Clone, Debug)]
-pub struct Decider<C1, C2, FC, CS1, CS2, S1, S2, FS> {
+pub struct Decider<C1, C2, FC, CS1, CS2, S1, S2, FS, const H: bool = false> {
     _c1: PhantomData<C1>,
     _c2: PhantomData<C2>,
     _fc: PhantomData<FC>,
@@ -92,38 +94,40 @@ pub struct Decider<C1, C2, FC, CS1, CS2, S1, S2, FS> {
     _fs: PhantomData<FS>,
 }
 
-impl<C1, C2, FC, CS1, CS2, S1, S2, FS> DeciderTrait<C1, C2, FC, FS>
-    for Decider<C1, C2, FC, CS1, CS2, S1, S2, FS>
+impl<C1, C2, FC, CS1, CS2, S1, S2, FS, const H: bool> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S1, S2, FS, H>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<
         C1,
+        H,
         ProverChallenge = C1::ScalarField,
         Challenge = C1::ScalarField,
-        Proof = crate::commitment::kzg::Proof<C1>,
+        Proof: EvaluationProof<C1::ScalarField>,
     >,
     CS2: CommitmentScheme<
         C2,
+        H,
         ProverChallenge = C2::ScalarField,
         Challenge = C2::ScalarField,
-        Proof = crate::commitment::kzg::Proof<C2>,
+        Proof: EvaluationProof<C2::ScalarField>,
     >,
     S1: SNARK<C1::ScalarField>,
     S2: SNARK<C2::ScalarField>,
     FS: FoldingScheme<C1, C2, FC>,
     // constrain FS into Nova, since this is a Decider specifically for Nova
-    Nova<C1, C2, FC, CS1, CS2, false>: From<FS>,
-    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, false>:
+    Nova<C1, C2, FC, CS1, CS2, H>: From<FS>,
+    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, H>:
         From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
-    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, false>:
+    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, H>:
         From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
 {
     type PreprocessorParam = ((FS::ProverParam, FS::VerifierParam), usize);
     type ProverParam =
         ProverParam<CS1::ProverParams, S1::ProvingKey, CS2::ProverParams, S2::ProvingKey>;
-    type Proof = Proof<C1, C2, CS1, CS2, S1, S2>;
+    type Proof = Proof<C1, C2, CS1, CS2, S1, S2, H>;
     type VerifierParam = VerifierParam<
         C1,
         CS1::VerifierParams,
@@ -139,13 +143,10 @@ where
         ((pp, vp), state_len): Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
         // get the FoldingScheme prover & verifier params from Nova
-        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<C1, C2, FC>>::ProverParam =
+        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, H> as FoldingScheme<C1, C2, FC>>::ProverParam =
             pp.into();
-        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<
-            C1,
-            C2,
-            FC,
-        >>::VerifierParam = vp.into();
+        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, H> as FoldingScheme<C1, C2, FC>>::VerifierParam =
+            vp.into();
         let pp_hash = nova_vp.pp_hash()?;
 
         let poseidon_config1 = nova_vp.poseidon_config;
@@ -212,25 +213,19 @@ where
         let cf_U_final = circuit1.cf_U_i.clone();
 
         let c1_kzg_challenges = circuit1.kzg_challenges.clone();
-        let c1_kzg_proofs = circuit1
-            .W_i1
-            .get_openings()
-            .iter()
-            .zip(&c1_kzg_challenges)
-            .map(|((v, _), &c)| {
-                CS1::prove_with_challenge(&pp.c1_cs_pp, c, v, &C1::ScalarField::zero(), None)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let c1_kzg_proofs = CS1::batch_prove_with_challenges(
+            &pp.c1_cs_pp,
+            &c1_kzg_challenges,
+            &circuit1.W_i1.get_openings(),
+            Some(&mut rng),
+        )?;
         let c2_kzg_challenges = circuit2.kzg_challenges.clone();
-        let c2_kzg_proofs = circuit2
-            .cf_W_i
-            .get_openings()
-            .iter()
-            .zip(&c2_kzg_challenges)
-            .map(|((v, _), &c)| {
-                CS2::prove_with_challenge(&pp.c2_cs_pp, c, v, &C2::ScalarField::zero(), None)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let c2_kzg_proofs = CS2::batch_prove_with_challenges(
+            &pp.c2_cs_pp,
+            &c2_kzg_challenges,
+            &circuit2.cf_W_i.get_openings(),
+            Some(&mut rng),
+        )?;
 
         let c1_snark_proof = S1::prove(&pp.c1_snark_pp, circuit1, &mut rng)
             .map_err(|e| Error::Other(e.to_string()))?;
@@ -289,7 +284,11 @@ where
             &U_final_commitments.inputize_nonnative(),
             &cf_U.inputize_nonnative(),
             &proof.cs1_challenges,
-            &proof.cs1_proofs.iter().map(|p| p.eval).collect::<Vec<_>>(),
+            &proof
+                .cs1_proofs
+                .iter()
+                .map(|p| p.eval())
+                .collect::<Vec<_>>(),
             &proof.cmT.inputize_nonnative(),
         ]
         .concat();
@@ -308,7 +307,11 @@ where
             &[pp_hash_Fq][..],
             &cf_U.inputize(),
             &proof.cs2_challenges,
-            &proof.cs2_proofs.iter().map(|p| p.eval).collect::<Vec<_>>(),
+            &proof
+                .cs2_proofs
+                .iter()
+                .map(|p| p.eval())
+                .collect::<Vec<_>>(),
         ]
         .concat();
 
@@ -319,23 +322,20 @@ where
         }
 
         // 7.3. check C1 commitments (main instance commitments)
-        for ((cm, &c), pi) in U_final_commitments
-            .iter()
-            .zip(&proof.cs1_challenges)
-            .zip(&proof.cs1_proofs)
-        {
-            CS1::verify_with_challenge(&vp.c1_cs_vp, c, cm, pi)?;
-        }
+        CS1::batch_verify_with_challenges(
+            &vp.c1_cs_vp,
+            &proof.cs1_challenges,
+            &U_final_commitments,
+            &proof.cs1_proofs,
+        )?;
 
         // 4.3. check C2 commitments (CycleFold instance commitments)
-        for ((cm, &c), pi) in cf_U
-            .get_commitments()
-            .iter()
-            .zip(&proof.cs2_challenges)
-            .zip(&proof.cs2_proofs)
-        {
-            CS2::verify_with_challenge(&vp.c2_cs_vp, c, cm, pi)?;
-        }
+        CS2::batch_verify_with_challenges(
+            &vp.c2_cs_vp,
+            &proof.cs2_challenges,
+            &cf_U.get_commitments(),
+            &proof.cs2_proofs,
+        )?;
 
         Ok(true)
     }
@@ -348,37 +348,66 @@ pub mod tests {
     // Note: do not use the MNTx_298 curves in practice, these are just for tests. Use the MNTx_753
     // curves instead.
     use ark_mnt4_298::{Fr, G1Projective as Projective, MNT4_298 as MNT4};
-    use ark_mnt6_298::{G1Projective as Projective2, MNT6_298 as MNT6};
+    use ark_mnt6_298::{Fr as Fr2, G1Projective as Projective2, MNT6_298 as MNT6};
     use std::time::Instant;
 
     use super::*;
-    use crate::commitment::kzg::KZG;
+    use crate::commitment::{ipa::IPA, kzg::KZG};
     use crate::folding::nova::PreprocessorParam;
     use crate::frontend::utils::CubicFCircuit;
     use crate::transcript::poseidon::poseidon_canonical_config;
 
+    // use Nova as FoldingScheme
+    type N<CS1, CS2, const H: bool = false> =
+        Nova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>;
+    type D<CS1, CS2, const H: bool = false> = Decider<
+        Projective,
+        Projective2,
+        CubicFCircuit<Fr>,
+        CS1,
+        CS2,
+        Groth16<MNT4>,
+        Groth16<MNT6>,
+        N<CS1, CS2, H>, // here we define the FoldingScheme to use
+        H,
+    >;
+
     #[test]
     fn test_decider() -> Result<(), Error> {
-        // use Nova as FoldingScheme
-        type N = Nova<
-            Projective,
-            Projective2,
-            CubicFCircuit<Fr>,
-            KZG<'static, MNT4>,
-            KZG<'static, MNT6>,
-            false,
-        >;
-        type D = Decider<
+        test_decider_opt::<KZG<'static, MNT4>, KZG<'static, MNT6>, false>()
+    }
+
+    /// Runs the fold+decider pipeline with IPA as the commitment scheme for both curves, which
+    /// does not need a trusted setup for the commitments.
+    #[test]
+    fn test_decider_ipa() -> Result<(), Error> {
+        test_decider_opt::<IPA<Projective>, IPA<Projective2>, false>()
+    }
+
+    /// Runs the fold+decider pipeline with Nova in zero-knowledge mode, where the commitments and
+    /// the opening proofs of the decider are hiding.
+    #[test]
+    fn test_decider_ipa_hiding() -> Result<(), Error> {
+        test_decider_opt::<IPA<Projective, true>, IPA<Projective2, true>, true>()
+    }
+
+    fn test_decider_opt<CS1, CS2, const H: bool>() -> Result<(), Error>
+    where
+        CS1: CommitmentScheme<
             Projective,
+            H,
+            ProverChallenge = Fr,
+            Challenge = Fr,
+            Proof: EvaluationProof<Fr>,
+        >,
+        CS2: CommitmentScheme<
             Projective2,
-            CubicFCircuit<Fr>,
-            KZG<'static, MNT4>,
-            KZG<'static, MNT6>,
-            Groth16<MNT4>,
-            Groth16<MNT6>,
-            N, // here we define the FoldingScheme to use
-        >;
-
+            H,
+            ProverChallenge = Fr2,
+            Challenge = Fr2,
+            Proof: EvaluationProof<Fr2>,
+        >,
+    {
         let mut rng = ark_std::test_rng();
         let poseidon_config = poseidon_canonical_config::<Fr>();
 
@@ -387,11 +416,11 @@ pub mod tests {
 
         let start = Instant::now();
         let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
-        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let nova_params = N::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
         println!("Nova preprocess, {:?}", start.elapsed());
 
         let start = Instant::now();
-        let mut nova = N::init(&nova_params, F_circuit, z_0.clone())?;
+        let mut nova = N::<CS1, CS2, H>::init(&nova_params, F_circuit, z_0.clone())?;
         println!("Nova initialized, {:?}", start.elapsed());
         let start = Instant::now();
         nova.prove_step(&mut rng, (), None)?;
@@ -403,17 +432,17 @@ pub mod tests {
         // prepare the Decider prover & verifier params
         let start = Instant::now();
         let (decider_pp, decider_vp) =
-            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+            D::<CS1, CS2, H>::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
         println!("Decider preprocess, {:?}", start.elapsed());
 
         // decider proof generation
         let start = Instant::now();
-        let proof = D::prove(rng, decider_pp, nova.clone())?;
+        let proof = D::<CS1, CS2, H>::prove(rng, decider_pp, nova.clone())?;
         println!("Decider prove, {:?}", start.elapsed());
 
         // decider proof verification
         let start = Instant::now();
-        let verified = D::verify(
+        let verified = D::<CS1, CS2, H>::verify(
             decider_vp,
             nova.i,
             nova.z_0,
@@ -6,14 +6,14 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
 use ark_std::{
     rand::{CryptoRng, RngCore},
-    One, Zero,
+    One,
 };
 use core::marker::PhantomData;
 
 pub use super::decider_eth_circuit::DeciderEthCircuit;
 use super::decider_eth_circuit::DeciderNovaGadget;
 use super::Nova;
-use crate::folding::circuits::decider::DeciderEnabledNIFS;
+use crate::folding::circuits::decider::{DeciderEnabledNIFS, StateHashGadget};
 use crate::folding::traits::{InputizeNonNative, WitnessOps};
 use crate::frontend::FCircuit;
 use crate::{
@@ -23,21 +23,32 @@ use crate::{
 use crate::{Curve, Error};
 use crate::{Decider as DeciderTrait, FoldingScheme};
 
+/// With the `serde` feature, the proof has a JSON representation, see [`crate::utils::json`]
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Proof<C, CS, S>
 where
     C: Curve,
     CS: CommitmentScheme<C, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
     S: SNARK<C::ScalarField>,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     snark_proof: S::Proof,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonicals"))]
     kzg_proofs: [CS::Proof; 2],
     // cmT and r are values for the last fold, U_{i+1}=NIFS.V(r, U_i, u_i, cmT), and they are
     // checked in-circuit
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     cmT: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     r: C::ScalarField,
     // the KZG challenges are provided by the prover, but in-circuit they are checked to match
     // the in-circuit computed ones.
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::fields"))]
     kzg_challenges: [C::ScalarField; 2],
 }
 
@@ -80,9 +91,14 @@ where
     pub cs_vp: CS_VerifyingKey,
 }
 
-/// Onchain Decider, for ethereum use cases
+/// Onchain Decider, for ethereum use cases.
+///
+/// When `HASHED_STATE` is set, the decider circuit exposes the hashes of the initial and final
+/// states (see [`StateHashGadget`]) as public inputs instead of the states themselves, which keeps
+/// the size of the public inputs (and thus the onchain verification cost) constant in the state
+/// length.
 #[derive(Clone, Debug)]
-pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
+pub struct Decider<C1, C2, FC, CS1, CS2, S, FS, const HASHED_STATE: bool = false> {
     _c1: PhantomData<C1>,
     _c2: PhantomData<C2>,
     _fc: PhantomData<FC>,
@@ -92,8 +108,8 @@ pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
     _fs: PhantomData<FS>,
 }
 
-impl<C1, C2, FC, CS1, CS2, S, FS> DeciderTrait<C1, C2, FC, FS>
-    for Decider<C1, C2, FC, CS1, CS2, S, FS>
+impl<C1, C2, FC, CS1, CS2, S, FS, const HASHED_STATE: bool> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S, FS, HASHED_STATE>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -138,7 +154,7 @@ where
 
         let pp_hash = nova_vp.pp_hash()?;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::dummy((
+        let mut circuit = DeciderEthCircuit::<C1, C2>::dummy((
             nova_vp.r1cs,
             nova_vp.cf_r1cs,
             nova_pp.cf_cs_pp,
@@ -148,6 +164,7 @@ where
             state_len,
             2, // Nova's running CommittedInstance contains 2 commitments
         ));
+        circuit.hash_state = HASHED_STATE;
 
         // get the Groth16 specific setup for the circuit
         let (g16_pk, g16_vk) = S::circuit_specific_setup(circuit, &mut rng)
@@ -169,7 +186,8 @@ where
     ) -> Result<Self::Proof, Error> {
         let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::try_from(Nova::from(folding_scheme))?;
+        let mut circuit = DeciderEthCircuit::<C1, C2>::try_from(Nova::from(folding_scheme))?;
+        circuit.hash_state = HASHED_STATE;
 
         let cmT = circuit.proof;
         let r = circuit.randomness;
@@ -179,15 +197,12 @@ where
         let kzg_challenges = circuit.kzg_challenges.clone();
 
         // generate KZG proofs
-        let kzg_proofs = circuit
-            .W_i1
-            .get_openings()
-            .iter()
-            .zip(&kzg_challenges)
-            .map(|((v, _), &c)| {
-                CS1::prove_with_challenge(&cs_pk, c, v, &C1::ScalarField::zero(), None)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let kzg_proofs = CS1::batch_prove_with_challenges(
+            &cs_pk,
+            &kzg_challenges,
+            &circuit.W_i1.get_openings(),
+            Some(&mut rng),
+        )?;
 
         let snark_proof =
             S::prove(&snark_pk, circuit, &mut rng).map_err(|e| Error::Other(e.to_string()))?;
@@ -233,6 +248,15 @@ where
             proof.r,
         )?;
 
+        let (z_0, z_i) = if HASHED_STATE {
+            (
+                vec![StateHashGadget::hash_native(&z_0)?],
+                vec![StateHashGadget::hash_native(&z_i)?],
+            )
+        } else {
+            (z_0, z_i)
+        };
+
         let public_input = [
             &[pp_hash, i][..],
             &z_0,
@@ -250,15 +274,14 @@ where
             return Err(Error::SNARKVerificationFail);
         }
 
-        // 7.3. Verify the KZG proofs
-        for ((cm, &c), pi) in U_final_commitments
-            .iter()
-            .zip(&proof.kzg_challenges)
-            .zip(&proof.kzg_proofs)
-        {
-            // we're at the Ethereum EVM case, so the CS1 is KZG commitments
-            CS1::verify_with_challenge(&cs_vp, c, cm, pi)?;
-        }
+        // 7.3. Verify the KZG proofs. We're at the Ethereum EVM case, so the CS1 is KZG
+        // commitments, whose proofs are batched into a single pairing check
+        CS1::batch_verify_with_challenges(
+            &cs_vp,
+            &proof.kzg_challenges,
+            &U_final_commitments,
+            &proof.kzg_proofs,
+        )?;
 
         Ok(true)
     }
@@ -354,6 +377,114 @@ pub mod tests {
         Ok(())
     }
 
+    /// round-trips a decider proof through its JSON representation
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_decider_proof_json() -> Result<(), Error> {
+        use crate::commitment::kzg::Proof as KZGProof;
+        use crate::utils::json::JsonSerde;
+        use ark_bn254::{G1Affine, G2Affine};
+        use ark_ec::{AffineRepr, PrimeGroup};
+
+        type P = Proof<Projective, KZG<'static, Bn254>, Groth16<Bn254>>;
+        let g = Projective::generator();
+        let proof = P {
+            snark_proof: ark_groth16::Proof {
+                a: G1Affine::generator(),
+                b: G2Affine::generator(),
+                c: -G1Affine::generator(),
+            },
+            kzg_proofs: [
+                KZGProof {
+                    eval: Fr::from(2u64),
+                    proof: g,
+                },
+                KZGProof {
+                    eval: -Fr::from(1u64),
+                    proof: -g,
+                },
+            ],
+            cmT: g * Fr::from(3u64),
+            r: Fr::from(4u64),
+            kzg_challenges: [Fr::from(5u64), Fr::from(6u64)],
+        };
+        let json = proof.to_json()?;
+        assert_eq!(P::from_json(&json)?, proof);
+
+        // the KZG challenges are an array of 2 elements
+        let mut value: serde_json::Value =
+            serde_json::from_str(&json).map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+        value["kzg_challenges"] = serde_json::json!([value["kzg_challenges"][0]]);
+        assert!(matches!(
+            P::from_json(&value.to_string()),
+            Err(Error::JSONSerdeError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decider_hashed_state() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Groth16<Bn254>,
+            N,
+            true,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &preprocessor_param)?;
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params.clone(), F_circuit.state_len()))?;
+
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+
+        let verified = D::verify(
+            decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+
+        // the proof does not verify for a different final state
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0.clone(),
+            vec![nova.z_i[0] + Fr::from(1_u32)],
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        );
+        assert!(matches!(verified, Err(Error::SNARKVerificationFail)));
+        Ok(())
+    }
+
     // Test to check the serialization and deserialization of diverse Decider related parameters.
     // This test is the same test as `test_decider` but it serializes values and then uses the
     // deserialized values to continue the checks.
@@ -135,6 +135,7 @@ impl<
             i: nova.i,
             z_0: nova.z_0,
             z_i: nova.z_i,
+            hash_state: false,
             U_i: nova.U_i,
             W_i: nova.W_i,
             u_i: nova.u_i,
@@ -0,0 +1,549 @@
+/// Distributed proving for Nova: the steps of a long computation are split into contiguous chunks
+/// that are folded in parallel by different workers, and a coordinator then folds the resulting
+/// chunk proofs into a single aggregated proof.
+///
+/// Each worker runs a regular Nova IVC over its chunk (see [`Nova::prove_chunk`]), starting at the
+/// state where the previous chunk ends, and ships the resulting [`ChunkProof`] to the coordinator.
+/// The [`FoldCoordinator`] then checks that the chunks chain together (the initial state of each
+/// chunk is the final state of the previous one), and folds the running and incoming instances
+/// of all the chunks (and their CycleFold instances) into a single pair of running instances, so
+/// that the final verifier only needs to check a single RelaxedR1CS relation on each curve, plus
+/// a couple of hashes per chunk.
+///
+/// What needs to be transferred from each worker to the coordinator is the [`ChunkProof`]
+/// (which is Nova's IVCProof of the chunk). Its size is dominated by the witnesses `W_i`, `w_i`
+/// and `cf_W_i`, which the coordinator needs in order to fold the instances. These are linear in
+/// the size of the augmented circuit (but independent of the number of steps of the chunk), so
+/// it is recommended to stream them with `serialize_with_mode` into the network writer, instead
+/// of buffering the whole serialized proof in memory. The witnesses are not part of the final
+/// [`DistributedProof`], which only contains the per-chunk [`ChunkStatement`]s and the witnesses
+/// of the accumulated instances.
+///
+/// Notice that since each chunk is a fresh IVC, the step index `i` received by the FCircuit is
+/// relative to the start of its chunk. Circuits whose logic depends on the global step index
+/// (eg. a block counter) should keep it as part of their state.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::RngCore, Zero};
+
+use super::{
+    nifs::{nova::NIFS, NIFSTrait},
+    CommittedInstance, IVCProof, Nova, ProverParams, VerifierParams, Witness,
+};
+use crate::arith::{r1cs::R1CS, ArithRelation};
+use crate::commitment::CommitmentScheme;
+use crate::folding::circuits::cyclefold::{
+    CycleFoldChallengeGadget, CycleFoldCommittedInstance, CycleFoldWitness,
+};
+use crate::folding::traits::CommittedInstanceOps;
+use crate::frontend::FCircuit;
+use crate::FoldingScheme;
+use crate::{Curve, Error};
+
+/// Proof of a chunk of contiguous steps, generated by a worker. It is the IVCProof of the Nova
+/// IVC run over the chunk, where `z_0` is the initial state of the chunk and `z_i` its final
+/// state, so it can also be verified on its own with `Nova::verify`.
+pub type ChunkProof<C1, C2> = IVCProof<C1, C2>;
+
+/// Public part of a [`ChunkProof`], which is kept by the coordinator for the final verification.
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ChunkStatement<C1: Curve, C2: Curve> {
+    /// number of steps of the chunk
+    pub i: C1::ScalarField,
+    /// initial state of the chunk
+    pub z_start: Vec<C1::ScalarField>,
+    /// final state of the chunk
+    pub z_end: Vec<C1::ScalarField>,
+    pub U_i: CommittedInstance<C1>,
+    pub u_i: CommittedInstance<C1>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1, C2> ChunkStatement<C1, C2>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+{
+    /// checks that the incoming instance `u_i` of the chunk points to its running instances and
+    /// boundary states, as done in Nova's IVC verifier.
+    fn check(
+        &self,
+        sponge: &PoseidonSponge<C1::ScalarField>,
+        pp_hash: C1::ScalarField,
+    ) -> Result<(), Error> {
+        if self.i.is_zero() {
+            return Err(Error::CantBeZero("chunk number of steps".to_string()));
+        }
+        if self.u_i.x.len() != 2 || self.U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[0] == H(i, z_start, z_end, U_i)
+        if self
+            .U_i
+            .hash(sponge, pp_hash, self.i, &self.z_start, &self.z_end)
+            != self.u_i.x[0]
+        {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        if self.cf_U_i.hash_cyclefold(sponge, pp_hash) != self.u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+        self.u_i.check_incoming()
+    }
+}
+
+/// Proof generated by the [`FoldCoordinator`], attesting that `z_i` is the result of applying
+/// `i` steps to `z_0`.
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DistributedProof<C1: Curve, C2: Curve> {
+    pub pp_hash: C1::ScalarField,
+    pub chunks: Vec<ChunkStatement<C1, C2>>,
+    /// cross-term commitments of the folds of the chunks' instances
+    pub cmTs: Vec<C1>,
+    /// cross-term commitments of the folds of the chunks' CycleFold instances
+    pub cf_cmTs: Vec<C2>,
+    /// witness of the accumulated instance
+    pub W: Witness<C1>,
+    /// witness of the accumulated CycleFold instance
+    pub cf_W: CycleFoldWitness<C2>,
+}
+
+impl<C1, C2> DistributedProof<C1, C2>
+where
+    C1: Curve,
+    C2: Curve,
+{
+    /// total number of steps proven
+    pub fn num_steps(&self) -> C1::ScalarField {
+        self.chunks.iter().map(|c| c.i).sum()
+    }
+    /// initial state of the computation
+    pub fn z_0(&self) -> Result<Vec<C1::ScalarField>, Error> {
+        Ok(self.chunks.first().ok_or(Error::Empty)?.z_start.clone())
+    }
+    /// final state of the computation
+    pub fn z_i(&self) -> Result<Vec<C1::ScalarField>, Error> {
+        Ok(self.chunks.last().ok_or(Error::Empty)?.z_end.clone())
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> Nova<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// Folds the chunk of steps defined by `external_inputs` (one element per step), starting at
+    /// the state `z_start`, and returns its [`ChunkProof`]. This is the method run by each of the
+    /// workers.
+    pub fn prove_chunk(
+        mut rng: impl RngCore,
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        F: FC,
+        z_start: Vec<C1::ScalarField>,
+        external_inputs: Vec<FC::ExternalInputs>,
+    ) -> Result<ChunkProof<C1, C2>, Error> {
+        if external_inputs.is_empty() {
+            return Err(Error::Empty);
+        }
+        let mut nova = Self::init(params, F, z_start)?;
+        for step_inputs in external_inputs {
+            nova.prove_step(&mut rng, step_inputs, None)?;
+        }
+        Ok(nova.ivc_proof())
+    }
+}
+
+/// Coordinator of the distributed proving, which folds the [`ChunkProof`]s generated by the
+/// workers (in the order of the computation) into a single [`DistributedProof`].
+///
+/// The coordinator does not extend its own IVC chain with the chunks (which would require
+/// re-proving their steps), but accumulates the instances of all the chunks into a single
+/// running instance (and a single CycleFold running instance), keeping the public statements of
+/// the chunks so that the verifier can check that they chain together.
+#[derive(Debug, Clone)]
+pub struct FoldCoordinator<C1, C2, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pp: ProverParams<C1, C2, CS1, CS2, H>,
+    r1cs: R1CS<C1::ScalarField>,
+    cf_r1cs: R1CS<C2::ScalarField>,
+    chunks: Vec<ChunkStatement<C1, C2>>,
+    cmTs: Vec<C1>,
+    cf_cmTs: Vec<C2>,
+    // accumulated instances, None until the first chunk is absorbed
+    acc: Option<(Witness<C1>, CommittedInstance<C1>)>,
+    cf_acc: Option<(CycleFoldWitness<C2>, CycleFoldCommittedInstance<C2>)>,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> FoldCoordinator<C1, C2, CS1, CS2, H>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub fn new(
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+        if pp.pp_hash != vp.pp_hash()? {
+            return Err(Error::PPHashMismatch);
+        }
+        Ok(Self {
+            pp: pp.clone(),
+            r1cs: vp.r1cs.clone(),
+            cf_r1cs: vp.cf_r1cs.clone(),
+            chunks: vec![],
+            cmTs: vec![],
+            cf_cmTs: vec![],
+            acc: None,
+            cf_acc: None,
+        })
+    }
+
+    /// final state of the chunks absorbed so far
+    pub fn state(&self) -> Option<Vec<C1::ScalarField>> {
+        self.chunks.last().map(|c| c.z_end.clone())
+    }
+
+    /// Absorbs the next chunk of the computation, folding its instances into the accumulated
+    /// ones. Chunks must be absorbed in the order of the computation.
+    pub fn absorb_chunk(&mut self, chunk: ChunkProof<C1, C2>) -> Result<(), Error> {
+        let IVCProof {
+            pp_hash,
+            i,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = chunk;
+        if pp_hash != self.pp.pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+        if let Some(z_end) = self.state() {
+            if z_end != z_0 {
+                return Err(Error::ChunkBoundaryMismatch);
+            }
+        }
+        let statement = ChunkStatement {
+            i,
+            z_start: z_0,
+            z_end: z_i,
+            U_i: U_i.clone(),
+            u_i: u_i.clone(),
+            cf_U_i: cf_U_i.clone(),
+        };
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.pp.poseidon_config);
+        statement.check(&sponge, pp_hash)?;
+
+        // fold the running and incoming instances of the chunk into the accumulator. The folds
+        // only borrow the current accumulators, which are replaced once all the folds succeeded,
+        // so that an error leaves the coordinator unchanged
+        let to_fold = if self.chunks.is_empty() {
+            vec![(&w_i, &u_i)]
+        } else {
+            vec![(&W_i, &U_i), (&w_i, &u_i)]
+        };
+        let mut acc: Option<(Witness<C1>, CommittedInstance<C1>)> = None;
+        let mut cmTs = Vec::with_capacity(to_fold.len());
+        for (w, u) in to_fold {
+            let (W, U) = match (&acc, &self.acc) {
+                (Some((W, U)), _) | (None, Some((W, U))) => (W, U),
+                (None, None) => (&W_i, &U_i),
+            };
+            let mut transcript = sponge.clone();
+            let (W1, U1, cmT, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                &self.pp.cs_pp,
+                &self.r1cs,
+                &mut transcript,
+                pp_hash,
+                W,
+                U,
+                w,
+                u,
+            )?;
+            acc = Some((W1, U1));
+            cmTs.push(cmT);
+        }
+
+        // fold the CycleFold running instance of the chunk into the CycleFold accumulator
+        let (cf_acc, cf_cmT) = match &self.cf_acc {
+            None => ((cf_W_i, cf_U_i), None),
+            Some((cf_W, cf_U)) => {
+                let (cf_T, cf_cmT) =
+                    NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::compute_cyclefold_cmT(
+                        &self.pp.cf_cs_pp,
+                        &self.cf_r1cs,
+                        cf_W,
+                        cf_U,
+                        &cf_W_i,
+                        &cf_U_i,
+                    )?;
+                let cf_r = cyclefold_challenge::<C1, C2>(&sponge, pp_hash, cf_U, &cf_U_i, cf_cmT)?;
+                let cf_W1 = NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_witness(
+                    cf_r, cf_W, &cf_W_i, &cf_T,
+                )?;
+                let cf_U1 =
+                    NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_committed_instances(
+                        cf_r, cf_U, &cf_U_i, &cf_cmT,
+                    );
+                ((cf_W1, cf_U1), Some(cf_cmT))
+            }
+        };
+
+        self.acc = acc;
+        self.cmTs.extend(cmTs);
+        self.cf_acc = Some(cf_acc);
+        self.cf_cmTs.extend(cf_cmT);
+        self.chunks.push(statement);
+        Ok(())
+    }
+
+    /// Returns the [`DistributedProof`] of all the chunks absorbed so far.
+    pub fn proof(&self) -> Result<DistributedProof<C1, C2>, Error> {
+        let (W, _) = self.acc.clone().ok_or(Error::Empty)?;
+        let (cf_W, _) = self.cf_acc.clone().ok_or(Error::Empty)?;
+        Ok(DistributedProof {
+            pp_hash: self.pp.pp_hash,
+            chunks: self.chunks.clone(),
+            cmTs: self.cmTs.clone(),
+            cf_cmTs: self.cf_cmTs.clone(),
+            W,
+            cf_W,
+        })
+    }
+
+    /// Verifies a [`DistributedProof`]. As in Nova's IVC verifier, this method checks the
+    /// relations with the full witnesses; the commitments opening is left to the Decider.
+    pub fn verify(
+        vp: &VerifierParams<C1, C2, CS1, CS2, H>,
+        proof: &DistributedProof<C1, C2>,
+    ) -> Result<(), Error> {
+        if proof.pp_hash != vp.pp_hash()? {
+            return Err(Error::PPHashMismatch);
+        }
+        let pp_hash = proof.pp_hash;
+        let chunks = &proof.chunks;
+        if chunks.is_empty() {
+            return Err(Error::Empty);
+        }
+        if proof.cmTs.len() != 2 * chunks.len() - 1 {
+            return Err(Error::NotExpectedLength(
+                proof.cmTs.len(),
+                2 * chunks.len() - 1,
+            ));
+        }
+        if proof.cf_cmTs.len() != chunks.len() - 1 {
+            return Err(Error::NotExpectedLength(
+                proof.cf_cmTs.len(),
+                chunks.len() - 1,
+            ));
+        }
+
+        // check the statement of each chunk and that they chain together
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        for (k, chunk) in chunks.iter().enumerate() {
+            if k > 0 && chunks[k - 1].z_end != chunk.z_start {
+                return Err(Error::ChunkBoundaryMismatch);
+            }
+            chunk.check(&sponge, pp_hash)?;
+        }
+
+        // recompute the accumulated instances
+        let instances = chunks
+            .iter()
+            .flat_map(|c| [c.U_i.clone(), c.u_i.clone()])
+            .collect::<Vec<_>>();
+        let mut U = instances[0].clone();
+        for (u, cmT) in instances[1..].iter().zip(&proof.cmTs) {
+            let mut transcript = sponge.clone();
+            (U, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::verify(
+                &mut transcript,
+                pp_hash,
+                &U,
+                u,
+                cmT,
+            )?;
+        }
+        let mut cf_U = chunks[0].cf_U_i.clone();
+        for (chunk, cf_cmT) in chunks[1..].iter().zip(&proof.cf_cmTs) {
+            let cf_r =
+                cyclefold_challenge::<C1, C2>(&sponge, pp_hash, &cf_U, &chunk.cf_U_i, *cf_cmT)?;
+            cf_U = NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_committed_instances(
+                cf_r,
+                &cf_U,
+                &chunk.cf_U_i,
+                cf_cmT,
+            );
+        }
+
+        // check RelaxedR1CS satisfiability of the accumulated instances
+        vp.r1cs.check_relation(&proof.W, &U)?;
+        vp.cf_r1cs.check_relation(&proof.cf_W, &cf_U)?;
+        Ok(())
+    }
+}
+
+// computes the challenge used to fold two CycleFold running instances
+fn cyclefold_challenge<C1, C2>(
+    sponge: &PoseidonSponge<C1::ScalarField>,
+    pp_hash: C1::ScalarField,
+    cf_U: &CycleFoldCommittedInstance<C2>,
+    cf_u: &CycleFoldCommittedInstance<C2>,
+    cf_cmT: C2,
+) -> Result<C2::ScalarField, Error>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+{
+    let mut transcript = sponge.clone();
+    let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_native(
+        &mut transcript,
+        pp_hash,
+        cf_U.clone(),
+        cf_u.clone(),
+        cf_cmT,
+    );
+    C2::ScalarField::from_bigint(BigInteger::from_bits_le(&cf_r_bits)).ok_or(Error::OutOfBounds)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    type N = Nova<
+        Projective,
+        Projective2,
+        CubicFCircuit<Fr>,
+        Pedersen<Projective>,
+        Pedersen<Projective2>,
+    >;
+    type Coordinator =
+        FoldCoordinator<Projective, Projective2, Pedersen<Projective>, Pedersen<Projective2>>;
+
+    /// splits 6 steps across two workers and checks that the coordinator's proof matches the
+    /// result of folding the 6 steps on a single machine
+    #[test]
+    fn test_two_workers() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+
+        // single machine
+        let mut nova = N::init(&params, F_circuit, z_0.clone())?;
+        for _ in 0..6 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+
+        // workers, where the initial state of the second chunk is the final state of the first
+        let chunk_1 = N::prove_chunk(&mut rng, &params, F_circuit, z_0.clone(), vec![(); 3])?;
+        let chunk_2 = N::prove_chunk(
+            &mut rng,
+            &params,
+            F_circuit,
+            chunk_1.z_i.clone(),
+            vec![(); 3],
+        )?;
+
+        // chunks are sent serialized to the coordinator
+        let mut bytes = vec![];
+        chunk_2.serialize_compressed(&mut bytes)?;
+        let chunk_2_received = ChunkProof::deserialize_compressed(bytes.as_slice())?;
+        assert_eq!(chunk_2, chunk_2_received);
+
+        // absorbing the chunks in the wrong order fails
+        let mut coordinator = Coordinator::new(&params)?;
+        coordinator.absorb_chunk(chunk_2.clone())?;
+        assert!(matches!(
+            coordinator.absorb_chunk(chunk_1.clone()),
+            Err(Error::ChunkBoundaryMismatch)
+        ));
+
+        let mut coordinator = Coordinator::new(&params)?;
+        coordinator.absorb_chunk(chunk_1)?;
+        coordinator.absorb_chunk(chunk_2_received)?;
+        let proof = coordinator.proof()?;
+        Coordinator::verify(&params.1, &proof)?;
+
+        assert_eq!(proof.z_0()?, z_0);
+        assert_eq!(proof.z_i()?, nova.state());
+        assert_eq!(proof.num_steps(), Fr::from(6_u32));
+
+        // tampering with the boundary states is detected
+        let mut bad_proof = proof.clone();
+        bad_proof.chunks[1].z_end = vec![Fr::from(42_u32)];
+        assert!(Coordinator::verify(&params.1, &bad_proof).is_err());
+        Ok(())
+    }
+
+    /// checks that a chunk whose folding fails leaves the coordinator unchanged, so that the
+    /// correct chunk can still be absorbed afterwards
+    #[test]
+    fn test_absorb_chunk_error() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let chunk_1 = N::prove_chunk(&mut rng, &params, F_circuit, z_0.clone(), vec![(); 2])?;
+        let chunk_2 = N::prove_chunk(
+            &mut rng,
+            &params,
+            F_circuit,
+            chunk_1.z_i.clone(),
+            vec![(); 2],
+        )?;
+
+        let mut coordinator = Coordinator::new(&params)?;
+        coordinator.absorb_chunk(chunk_1)?;
+        let before = coordinator.proof()?;
+
+        // the fold of the main instances fails
+        let mut bad_chunk = chunk_2.clone();
+        bad_chunk.W_i.W.pop();
+        assert!(coordinator.absorb_chunk(bad_chunk).is_err());
+        assert_eq!(coordinator.proof()?, before);
+
+        // the fold of the main instances succeeds, but the one of the CycleFold instances fails
+        let mut bad_chunk = chunk_2.clone();
+        bad_chunk.cf_W_i.W.pop();
+        assert!(coordinator.absorb_chunk(bad_chunk).is_err());
+        assert_eq!(coordinator.proof()?, before);
+
+        coordinator.absorb_chunk(chunk_2)?;
+        Coordinator::verify(&params.1, &coordinator.proof()?)?;
+        Ok(())
+    }
+}
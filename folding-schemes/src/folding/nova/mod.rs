@@ -4,38 +4,56 @@
 /// The structure of the Nova code is the following:
 /// - NIFS implementation for Nova (nifs.rs), Mova (mova.rs), Ova (ova.rs)
 /// - IVC and the Decider (offchain Decider & onchain Decider) implementations for Nova
+/// - IVC implementation using the Ova NIFS (ova/)
+/// - distributed proving of chunks of steps, folded by a coordinator (distributed.rs)
 use ark_crypto_primitives::sponge::{
     poseidon::{PoseidonConfig, PoseidonSponge},
     Absorb, CryptographicSponge,
 };
+use ark_ec::pairing::Pairing;
 use ark_ff::{BigInteger, PrimeField};
+use ark_poly_commit::kzg10::VerifierKey;
 use ark_r1cs_std::R1CSVar;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
-use ark_std::{cmp::max, fmt::Debug, marker::PhantomData, rand::RngCore, One, UniformRand, Zero};
+use ark_std::{cmp::max, fmt::Debug, rand::RngCore, One, UniformRand, Zero};
 
 use crate::folding::{circuits::CF1, traits::Dummy};
 use crate::frontend::FCircuit;
-use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
+use crate::transcript::{
+    poseidon::{
+        deserialize_poseidon_config, poseidon_config_serialized_size, serialize_poseidon_config,
+    },
+    Transcript,
+};
+#[cfg(feature = "memory-tracking")]
+use crate::utils::memory::{MemoryTracker, ProveStepStats};
 use crate::utils::vec::is_zero_vec;
 use crate::FoldingScheme;
 use crate::{
     arith::r1cs::{extract_r1cs, extract_w_x, R1CS},
-    constants::NOVA_N_BITS_RO,
-    utils::pp_hash,
+    utils::{field_to_u64, pp_hash},
 };
 use crate::{
     arith::Arith,
     folding::circuits::cyclefold::{
-        fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldConfig,
-        CycleFoldWitness,
+        fold_cyclefold_circuit_with_scratch, CycleFoldCircuit, CycleFoldCommittedInstance,
+        CycleFoldWitness, GenericCycleFoldConfig,
+    },
+};
+use crate::{
+    arith::ArithRelation,
+    commitment::{
+        kzg::{ProverKey as KZGProverKey, KZG},
+        CommitmentScheme,
     },
 };
-use crate::{arith::ArithRelation, commitment::CommitmentScheme};
 use crate::{Curve, Error};
 use decider_eth_circuit::WitnessVar;
 
 pub mod circuits;
+pub mod distributed;
+pub mod ova;
 pub mod traits;
 pub mod zk;
 
@@ -43,7 +61,11 @@ pub mod zk;
 pub mod nifs;
 
 use circuits::AugmentedFCircuit;
-use nifs::{nova::NIFS, nova_circuits::CommittedInstanceVar, NIFSTrait};
+use nifs::{
+    nova::{ProverScratch, NIFS},
+    nova_circuits::CommittedInstanceVar,
+    NIFSTrait,
+};
 
 // offchain decider
 pub mod decider;
@@ -54,29 +76,28 @@ pub mod decider_eth_circuit;
 
 use super::traits::{CommittedInstanceOps, Inputize, WitnessOps};
 
-/// Configuration for Nova's CycleFold circuit
-pub struct NovaCycleFoldConfig<C: Curve> {
-    _c: PhantomData<C>,
-}
-
-impl<C: Curve> CycleFoldConfig for NovaCycleFoldConfig<C> {
-    const RANDOMNESS_BIT_LENGTH: usize = NOVA_N_BITS_RO;
-    // Number of points to be folded in the CycleFold circuit, in Nova's case, this is a fixed
-    // amount:
-    // 2 points to be folded.
-    const N_INPUT_POINTS: usize = 2;
-    type C = C;
-}
+/// Configuration for Nova's CycleFold circuit. Each CycleFold instance checks 2 random linear
+/// combinations of 2 points: the fold of `cmW` and the fold of `cmE`.
+pub type NovaCycleFoldConfig<C> = GenericCycleFoldConfig<C, 2, 2>;
 
 /// CycleFold circuit for computing random linear combinations of group elements
 /// in Nova instances.
 pub type NovaCycleFoldCircuit<C> = CycleFoldCircuit<NovaCycleFoldConfig<C>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct CommittedInstance<C: Curve> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     pub cmE: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub u: C::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     pub cmW: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::fields"))]
     pub x: Vec<C::ScalarField>,
 }
 
@@ -137,27 +158,37 @@ impl<C: Curve> Inputize<CF1<C>> for CommittedInstance<C> {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Witness<C: Curve> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::fields"))]
     pub E: Vec<C::ScalarField>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub rE: C::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::fields"))]
     pub W: Vec<C::ScalarField>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub rW: C::ScalarField,
 }
 
 impl<C: Curve> Witness<C> {
+    /// Returns the witness of an incoming instance, blinding the commitment to `W` if `H` is set.
+    /// The error term of an incoming instance is zero and its commitment is the identity (which
+    /// is what the circuits and `check_incoming` expect), so `rE` is always zero. The running
+    /// instances get a non-zero `rE` from the blinded commitments to the cross terms when folding.
     pub fn new<const H: bool>(w: Vec<C::ScalarField>, e_len: usize, mut rng: impl RngCore) -> Self {
-        let (rW, rE) = if H {
-            (
-                C::ScalarField::rand(&mut rng),
-                C::ScalarField::rand(&mut rng),
-            )
+        let rW = if H {
+            C::ScalarField::rand(&mut rng)
         } else {
-            (C::ScalarField::zero(), C::ScalarField::zero())
+            C::ScalarField::zero()
         };
 
         Self {
             E: vec![C::ScalarField::zero(); e_len],
-            rE,
+            rE: C::ScalarField::zero(),
             W: w,
             rW,
         }
@@ -210,6 +241,10 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
+    /// Poseidon configuration of the transcripts and of the hashes of the instances in the
+    /// augmented circuit, e.g. a wider one from
+    /// [`crate::transcript::poseidon::poseidon_config_with_width`] for long states. It is bound
+    /// into the public parameters hash.
     pub poseidon_config: PoseidonConfig<C1::ScalarField>,
     pub F: FC,
     // cs params if not provided, will be generated at the preprocess method
@@ -239,6 +274,36 @@ where
     }
 }
 
+impl<'a, E, C2, FC, CS2> PreprocessorParam<E::G1, C2, FC, KZG<'a, E>, CS2>
+where
+    E: Pairing<G1: Curve>,
+    C2: Curve,
+    FC: FCircuit<E::ScalarField>,
+    CS2: CommitmentScheme<C2>,
+{
+    /// Uses the given KZG SRS (eg. loaded from a powers of tau ceremony through
+    /// [`crate::commitment::ptau::kzg_srs_from_ptau`]) for `CS1`, so that `preprocess` does not
+    /// generate it and only does the circuit-specific work. `preprocess` checks that the SRS is
+    /// big enough for the circuit.
+    pub fn with_kzg_srs(mut self, (pk, vk): (KZGProverKey<'a, E::G1>, VerifierKey<E>)) -> Self {
+        self.cs_pp = Some(pk);
+        self.cs_vp = Some(vk);
+        self
+    }
+
+    /// Same as [`Self::with_kzg_srs`], but first checks the structural consistency of the SRS
+    /// (see [`KZG::verify_srs`]), for SRSs that come from untrusted sources.
+    pub fn with_verified_kzg_srs(
+        self,
+        srs: (KZGProverKey<'a, E::G1>, VerifierKey<E>),
+        security_bits: usize,
+        rng: impl RngCore,
+    ) -> Result<Self, Error> {
+        KZG::<'a, E>::verify_srs(&srs, security_bits, rng)?;
+        Ok(self.with_kzg_srs(srs))
+    }
+}
+
 /// Proving parameters for Nova-based IVC
 #[derive(Debug, Clone)]
 pub struct ProverParams<C1, C2, CS1, CS2, const H: bool = false>
@@ -254,6 +319,23 @@ where
     pub cs_pp: CS1::ProverParams,
     /// Proving parameters of the underlying commitment scheme over C2
     pub cf_cs_pp: CS2::ProverParams,
+    /// Hash of the public parameters, computed at `preprocess` from the `VerifierParams` (see
+    /// [`VerifierParams::pp_hash`])
+    pub pp_hash: C1::ScalarField,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> ProverParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// returns the hash of the public parameters of Nova, which matches the one returned by
+    /// [`VerifierParams::pp_hash`] for the `VerifierParams` generated together with `self`.
+    pub fn pp_hash(&self) -> C1::ScalarField {
+        self.pp_hash
+    }
 }
 
 impl<C1, C2, CS1, CS2, const H: bool> Valid for ProverParams<C1, C2, CS1, CS2, H>
@@ -273,6 +355,7 @@ where
         self.poseidon_config.capacity.check()?;
         self.cs_pp.check()?;
         self.cf_cs_pp.check()?;
+        self.pp_hash.check()?;
         Ok(())
     }
 }
@@ -289,11 +372,16 @@ where
         compress: ark_serialize::Compress,
     ) -> Result<(), ark_serialize::SerializationError> {
         self.cs_pp.serialize_with_mode(&mut writer, compress)?;
-        self.cf_cs_pp.serialize_with_mode(&mut writer, compress)
+        self.cf_cs_pp.serialize_with_mode(&mut writer, compress)?;
+        self.pp_hash.serialize_with_mode(&mut writer, compress)?;
+        serialize_poseidon_config(&self.poseidon_config, &mut writer, compress)
     }
 
     fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
-        self.cs_pp.serialized_size(compress) + self.cf_cs_pp.serialized_size(compress)
+        self.cs_pp.serialized_size(compress)
+            + self.cf_cs_pp.serialized_size(compress)
+            + self.pp_hash.serialized_size(compress)
+            + poseidon_config_serialized_size(&self.poseidon_config, compress)
     }
 }
 impl<C1, C2, CS1, CS2, const H: bool> CanonicalDeserialize for ProverParams<C1, C2, CS1, CS2, H>
@@ -310,10 +398,13 @@ where
     ) -> Result<Self, ark_serialize::SerializationError> {
         let cs_pp = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
         let cf_cs_pp = CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let pp_hash = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+        let poseidon_config = deserialize_poseidon_config(&mut reader, compress, validate)?;
         Ok(ProverParams {
-            poseidon_config: poseidon_canonical_config::<C1::ScalarField>(),
+            poseidon_config,
             cs_pp,
             cf_cs_pp,
+            pp_hash,
         })
     }
 }
@@ -365,11 +456,14 @@ where
         compress: ark_serialize::Compress,
     ) -> Result<(), ark_serialize::SerializationError> {
         self.cs_vp.serialize_with_mode(&mut writer, compress)?;
-        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)
+        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)?;
+        serialize_poseidon_config(&self.poseidon_config, &mut writer, compress)
     }
 
     fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
-        self.cs_vp.serialized_size(compress) + self.cf_cs_vp.serialized_size(compress)
+        self.cs_vp.serialized_size(compress)
+            + self.cf_cs_vp.serialized_size(compress)
+            + poseidon_config_serialized_size(&self.poseidon_config, compress)
     }
 }
 
@@ -380,7 +474,9 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
 {
-    /// returns the hash of the public parameters of Nova
+    /// returns the hash of the public parameters of Nova, which is absorbed in the hash of the
+    /// committed instances and thus binds the IVC proofs to the parameters they were generated
+    /// with. See [`crate::utils::pp_hash`] for the specification of what is hashed.
     pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
         pp_hash::<C1, C2, CS1, CS2, H>(
             &self.r1cs,
@@ -392,17 +488,29 @@ where
     }
 }
 
+/// With the `serde` feature, the proof has a JSON representation, see [`crate::utils::json`]
 #[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct IVCProof<C1, C2>
 where
     C1: Curve,
     C2: Curve,
 {
+    // hash of the public parameters that the proof is bound to
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
+    pub pp_hash: C1::ScalarField,
     // current step of the IVC
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub i: C1::ScalarField,
     // initial state
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::fields"))]
     pub z_0: Vec<C1::ScalarField>,
     // current state
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::fields"))]
     pub z_i: Vec<C1::ScalarField>,
     // running instance
     pub W_i: Witness<C1>,
@@ -415,6 +523,14 @@ where
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
 }
 
+impl<C1: Curve, C2: Curve> IVCProof<C1, C2> {
+    /// returns the number of steps `i` of the proof, or `Error::OutOfBounds` if it does not fit
+    /// in a `u64`
+    pub fn num_steps(&self) -> Result<u64, Error> {
+        field_to_u64(self.i)
+    }
+}
+
 /// Implements Nova+CycleFold's IVC, described in [Nova](https://eprint.iacr.org/2021/370.pdf) and
 /// [CycleFold](https://eprint.iacr.org/2023/1192.pdf), following the FoldingScheme trait
 /// The `H` const generic specifies whether the homorphic commitment scheme is blinding
@@ -454,6 +570,15 @@ where
     /// CycleFold running instance
     pub cf_W_i: CycleFoldWitness<C2>,
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
+
+    /// sizes of the main allocations of the prover, see [`Nova::prove_step_stats`]
+    #[cfg(feature = "memory-tracking")]
+    pub memory: MemoryTracker,
+
+    /// buffers for the temporaries of the provers of the Nova and CycleFold instances, reused
+    /// across the steps
+    scratch: ProverScratch<C1::ScalarField>,
+    cf_scratch: ProverScratch<C2::ScalarField>,
 }
 
 impl<C1, C2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC>
@@ -491,7 +616,9 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::VerifierParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let poseidon_config = deserialize_poseidon_config(&mut reader, compress, validate)?;
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
@@ -516,9 +643,6 @@ where
         let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
         let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
 
-        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
-        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
-
         Ok(Self::VerifierParam {
             poseidon_config,
             r1cs,
@@ -535,35 +659,32 @@ where
         let (r1cs, cf_r1cs) =
             get_r1cs::<C1, C2, FC>(&prep_param.poseidon_config, prep_param.F.clone())?;
 
+        // `CS1` is for committing to Nova's witness vector `w` and error term `e`, where the
+        // length of `e` is the number of constraints, so we set `len` to the maximum of `e` and
+        // `w`'s lengths. Same for `CS2` and CycleFold's `w` and `e`.
+        let cs_len = max(r1cs.n_constraints(), r1cs.n_witnesses());
+        let cf_cs_len = max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses());
+
         // if cs params exist, use them, if not, generate new ones
         let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
             (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
-            _ => CS1::setup(
-                &mut rng,
-                // `CS1` is for committing to Nova's witness vector `w` and
-                // error term `e`, where the length of `e` is the number of
-                // constraints, so we set `len` to the maximum of `e` and `w`'s
-                // lengths.
-                max(r1cs.n_constraints(), r1cs.n_witnesses()),
-            )?,
+            _ => CS1::setup(&mut rng, cs_len)?,
         };
         let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
             (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
-            _ => CS2::setup(
-                &mut rng,
-                // `CS2` is for committing to CycleFold's witness vector `w` and
-                // error term `e`, where the length of `e` is the number of
-                // constraints, so we set `len` to the maximum of `e` and `w`'s
-                // lengths.
-                max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
-            )?,
+            _ => CS2::setup(&mut rng, cf_cs_len)?,
         };
+        // the provided params (eg. an SRS from a ceremony) must be big enough for the circuits
+        if CS1::max_len(&cs_pp) < cs_len {
+            return Err(Error::CommitmentParamsLen(CS1::max_len(&cs_pp), cs_len));
+        }
+        if CS2::max_len(&cf_cs_pp) < cf_cs_len {
+            return Err(Error::CommitmentParamsLen(
+                CS2::max_len(&cf_cs_pp),
+                cf_cs_len,
+            ));
+        }
 
-        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
-            poseidon_config: prep_param.poseidon_config.clone(),
-            cs_pp: cs_pp.clone(),
-            cf_cs_pp: cf_cs_pp.clone(),
-        };
         let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
             poseidon_config: prep_param.poseidon_config.clone(),
             r1cs,
@@ -571,6 +692,12 @@ where
             cs_vp,
             cf_cs_vp,
         };
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp: cs_pp.clone(),
+            cf_cs_pp: cf_cs_pp.clone(),
+            pp_hash: verifier_params.pp_hash()?,
+        };
 
         Ok((prover_params, verifier_params))
     }
@@ -605,6 +732,9 @@ where
 
         // compute the public params hash
         let pp_hash = vp.pp_hash()?;
+        if pp.pp_hash != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
 
         // setup the dummy instances
         let (W_dummy, U_dummy) = r1cs.dummy_witness_instance();
@@ -631,24 +761,269 @@ where
             // cyclefold running instance
             cf_W_i: cf_W_dummy,
             cf_U_i: cf_U_dummy,
+            #[cfg(feature = "memory-tracking")]
+            memory: MemoryTracker::new(),
+            scratch: ProverScratch::default(),
+            cf_scratch: ProverScratch::default(),
         })
     }
 
     /// Implements IVC.P of Nova+CycleFold
     fn prove_step(
         &mut self,
-        mut rng: impl RngCore,
+        rng: impl RngCore,
         external_inputs: FC::ExternalInputs,
         // Nova does not support multi-instances folding (by design)
         _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+    ) -> Result<(), Error> {
+        self.prove_step_with(
+            rng,
+            external_inputs,
+            _other_instances,
+            Self::precompute_and_fold,
+        )
+    }
+
+    fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        Self::IVCProof {
+            pp_hash: self.pp_hash,
+            i: self.i,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    fn from_ivc_proof(
+        ivc_proof: IVCProof<C1, C2>,
+        fcircuit_params: FC::Params,
+        params: (Self::ProverParam, Self::VerifierParam),
+    ) -> Result<Self, Error> {
+        let IVCProof {
+            pp_hash,
+            i,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+        let (pp, vp) = params;
+        if pp_hash != vp.pp_hash()? {
+            return Err(Error::PPHashMismatch);
+        }
+
+        let f_circuit = FC::new(fcircuit_params)?;
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
+        cs2.set_mode(SynthesisMode::Setup);
+        let augmented_F_circuit =
+            AugmentedFCircuit::<C1, C2, FC>::empty(&pp.poseidon_config, f_circuit.clone());
+        let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
+
+        augmented_F_circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let r1cs = extract_r1cs::<C1::ScalarField>(&cs)?;
+
+        cf_circuit.generate_constraints(cs2.clone())?;
+        cs2.finalize();
+        let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.poseidon_config,
+            cs_pp: pp.cs_pp,
+            cf_cs_pp: pp.cf_cs_pp,
+            F: f_circuit,
+            pp_hash,
+            i,
+            z_0,
+            z_i,
+            w_i,
+            u_i,
+            W_i,
+            U_i,
+            cf_W_i,
+            cf_U_i,
+            #[cfg(feature = "memory-tracking")]
+            memory: MemoryTracker::new(),
+            scratch: ProverScratch::default(),
+            cf_scratch: ProverScratch::default(),
+        })
+    }
+
+    /// Implements IVC.V of Nov.clone()a+CycleFold. Notice that this method does not include the
+    /// commitments verification, which is done in the Decider.
+    fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
+        let Self::IVCProof {
+            pp_hash: proof_pp_hash,
+            i: num_steps,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+
+        // check that the proof has been generated for the given public parameters
+        let pp_hash = vp.pp_hash()?;
+        if proof_pp_hash != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+
+        if num_steps == C1::ScalarField::zero() {
+            // at i=0 nothing has been folded yet, so the state must be the initial one and the
+            // instances must be the dummy ones set at `init`
+            let (_, U_dummy): (Witness<C1>, CommittedInstance<C1>) =
+                vp.r1cs.dummy_witness_instance();
+            let (_, cf_U_dummy): (CycleFoldWitness<C2>, CycleFoldCommittedInstance<C2>) =
+                vp.cf_r1cs.dummy_witness_instance();
+            if z_0 != z_i || U_i != U_dummy || u_i != U_dummy || cf_U_i != cf_U_dummy {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+
+        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check that u_i's output points to the running instance
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, &z_0, &z_i);
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability, which is equivalent to checking if `u_i`
+        // is an incoming instance and if `w_i` and `u_i` satisfy RelaxedR1CS
+        u_i.check_incoming()?;
+        vp.r1cs.check_relation(&w_i, &u_i)?;
+        // check RelaxedR1CS satisfiability
+        vp.r1cs.check_relation(&W_i, &U_i)?;
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> Nova<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    /// returns the number of steps folded so far, or `Error::OutOfBounds` if it does not fit in
+    /// a `u64`
+    pub fn num_steps(&self) -> Result<u64, Error> {
+        field_to_u64(self.i)
+    }
+
+    /// returns the scratch buffers of the Nova and CycleFold provers, which hold the temporaries
+    /// of the folds (such as the cross terms) and are reused across the steps
+    pub fn scratch(
+        &self,
+    ) -> (
+        &ProverScratch<C1::ScalarField>,
+        &ProverScratch<C2::ScalarField>,
+    ) {
+        (&self.scratch, &self.cf_scratch)
+    }
+
+    /// drops the scratch buffers of the Nova and CycleFold provers, which frees their memory at
+    /// the cost of reallocating them at the next step
+    pub fn release_scratch(&mut self) {
+        self.scratch = ProverScratch::default();
+        self.cf_scratch = ProverScratch::default();
+    }
+
+    /// returns the memory statistics of the last `prove_step`: the tracked allocations (the
+    /// witnesses, the cross terms and the commitment keys) at the end of the step, and the peak
+    /// of their total size during the step
+    #[cfg(feature = "memory-tracking")]
+    pub fn prove_step_stats(&self) -> ProveStepStats {
+        ProveStepStats::new(&self.memory)
+    }
+
+    /// registers the current sizes of the Nova and CycleFold witnesses, of the scratch buffers
+    /// (the cross terms `T` and `cf_T` being registered apart) and of the commitment keys
+    #[cfg(feature = "memory-tracking")]
+    fn track_memory(&mut self) {
+        let memory = &mut self.memory;
+        memory.set_slice("W_i.W", &self.W_i.W);
+        memory.set_slice("W_i.E", &self.W_i.E);
+        memory.set_slice("w_i.W", &self.w_i.W);
+        memory.set_slice("w_i.E", &self.w_i.E);
+        memory.set_slice("cf_W_i.W", &self.cf_W_i.W);
+        memory.set_slice("cf_W_i.E", &self.cf_W_i.E);
+        memory.set("scratch", self.scratch.size_bytes());
+        memory.set("T", self.scratch.cross_terms_size_bytes());
+        memory.set("cf_scratch", self.cf_scratch.size_bytes());
+        memory.set("cf_T", self.cf_scratch.cross_terms_size_bytes());
+        memory.set_serializable("cs_pp", &self.cs_pp);
+        memory.set_serializable("cf_cs_pp", &self.cf_cs_pp);
+    }
+
+    /// proves a step as in [`FoldingScheme::prove_step`], where `precompute_and_fold` computes
+    /// the witness hint of the step and folds the Nova instances
+    #[allow(clippy::type_complexity)]
+    fn prove_step_with(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+        _other_instances: Option<()>,
+        precompute_and_fold: impl FnOnce(
+            &mut Self,
+            usize,
+            FC::ExternalInputs,
+            &mut PoseidonSponge<C1::ScalarField>,
+        ) -> Result<
+            (
+                FC::ExternalInputs,
+                (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>),
+            ),
+            Error,
+        >,
     ) -> Result<(), Error> {
         // ensure that commitments are blinding if user has specified so.
         if H && self.i >= C1::ScalarField::one() {
+            // the incoming instances have no error term, so only their cmW is blinded
             let blinding_commitments = if self.i == C1::ScalarField::one() {
                 // blinding values of the running instances are zero at the first iteration
-                vec![self.w_i.rW, self.w_i.rE]
+                vec![self.w_i.rW]
             } else {
-                vec![self.w_i.rW, self.w_i.rE, self.W_i.rW, self.W_i.rE]
+                vec![self.w_i.rW, self.W_i.rW, self.W_i.rE]
             };
             if blinding_commitments.contains(&C1::ScalarField::zero()) {
                 return Err(Error::IncorrectBlinding(
@@ -698,18 +1073,26 @@ where
             i_usize = usize::from_le_bytes(i_bytes);
         }
 
-        // fold Nova instances
-        let (W_i1, U_i1, cmT, r_bits): (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>) =
-            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
-                &self.cs_pp,
-                &self.r1cs,
-                &mut transcript,
-                self.pp_hash,
-                &self.W_i,
-                &self.U_i,
-                &self.w_i,
-                &self.u_i,
-            )?;
+        #[cfg(feature = "memory-tracking")]
+        {
+            self.track_memory();
+            self.memory.reset_peak();
+        }
+
+        // fold Nova instances, and complete the external inputs with the witness hint of the step
+        let (external_inputs, (W_i1, U_i1, cmT, r_bits)) =
+            precompute_and_fold(&mut *self, i_usize, external_inputs, &mut transcript)?;
+
+        // the cross terms `T`, computed by the fold before the folded witness, stay allocated in
+        // the scratch buffers, which are reused by the next steps and thus never released, and the
+        // folded witness coexists with the running one until the end of the step
+        #[cfg(feature = "memory-tracking")]
+        {
+            self.memory.set("scratch", self.scratch.size_bytes());
+            self.memory.set("T", self.scratch.cross_terms_size_bytes());
+            self.memory.set_slice("W_i1.W", &W_i1.W);
+            self.memory.set_slice("W_i1.E", &W_i1.E);
+        }
 
         if self.i == C1::ScalarField::zero() {
             // base case
@@ -727,11 +1110,9 @@ where
                 U_i1_cmW: Some(U_i1.cmW),
                 cmT: Some(cmT),
                 F: self.F.clone(),
-                cf1_u_i_cmW: None,
-                cf2_u_i_cmW: None,
+                cf_u_i_cmW: None,
                 cf_U_i: None,
-                cf1_cmT: None,
-                cf2_cmT: None,
+                cf_cmT: None,
             };
 
             #[cfg(test)]
@@ -746,31 +1127,24 @@ where
             }
         } else {
             // CycleFold part:
-            let cfW_circuit = NovaCycleFoldCircuit::<C1> {
-                r_bits: Some(r_bits.clone()),
-                points: Some(vec![self.U_i.clone().cmW, self.u_i.clone().cmW]),
-            };
-            let cfE_circuit = NovaCycleFoldCircuit::<C1> {
+            // a single CycleFold instance checks both the fold of cmW and the fold of cmE
+            let cf_circuit = NovaCycleFoldCircuit::<C1> {
                 r_bits: Some(r_bits.clone()),
-                points: Some(vec![self.U_i.clone().cmE, cmT]),
+                points: Some(vec![self.U_i.cmW, self.u_i.cmW, self.U_i.cmE, cmT]),
             };
 
-            // fold self.cf_U_i + cfW_U -> folded running with cfW
-            let (cfW_u_i, cfW_W_i1, cfW_U_i1, cfW_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript,
-                self.cf_W_i.clone(), // CycleFold running instance witness
-                self.cf_U_i.clone(), // CycleFold running instance
-                cfW_circuit,
-                &mut rng,
-            )?;
-            // fold [the output from folding self.cf_U_i + cfW_U] + cfE_U = folded_running_with_cfW + cfE
-            let (cfE_u_i, cf_W_i1, cf_U_i1, cf_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript,
-                cfW_W_i1,
-                cfW_U_i1.clone(),
-                cfE_circuit,
-                &mut rng,
-            )?;
+            // fold self.cf_U_i + cf_u_i -> cf_U_i1
+            let (cf_u_i, cf_W_i1, cf_U_i1, cf_cmT) =
+                self.fold_cyclefold_circuit(&mut transcript, cf_circuit, &mut rng)?;
+
+            #[cfg(feature = "memory-tracking")]
+            {
+                self.memory.set("cf_scratch", self.cf_scratch.size_bytes());
+                self.memory
+                    .set("cf_T", self.cf_scratch.cross_terms_size_bytes());
+                self.memory.set_slice("cf_W_i1.W", &cf_W_i1.W);
+                self.memory.set_slice("cf_W_i1.E", &cf_W_i1.E);
+            }
 
             augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
                 poseidon_config: self.poseidon_config.clone(),
@@ -787,11 +1161,9 @@ where
                 cmT: Some(cmT),
                 F: self.F.clone(),
                 // cyclefold values
-                cf1_u_i_cmW: Some(cfW_u_i.cmW),
-                cf2_u_i_cmW: Some(cfE_u_i.cmW),
+                cf_u_i_cmW: Some(cf_u_i.cmW),
                 cf_U_i: Some(self.cf_U_i.clone()),
-                cf1_cmT: Some(cfW_cmT),
-                cf2_cmT: Some(cf_cmT),
+                cf_cmT: Some(cf_cmT),
             };
 
             self.cf_W_i = cf_W_i1;
@@ -801,11 +1173,14 @@ where
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
 
         let z_i1 = augmented_F_circuit
-            .compute_next_state(cs.clone())?
+            .compute_next_state(cs.clone())
+            .map_err(|e| self.F.take_step_error().unwrap_or(e.into()))?
             .value()?;
 
-        #[cfg(test)]
-        assert!(cs.is_satisfied()?);
+        // in debug builds, report which constraint is not satisfied, instead of producing a
+        // folded instance that will not pass the verification
+        #[cfg(any(test, debug_assertions))]
+        crate::arith::r1cs::check_cs_satisfied(&self.r1cs, &cs, i_usize)?;
 
         let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
         let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
@@ -823,163 +1198,73 @@ where
         self.W_i = W_i1;
         self.U_i = U_i1;
 
+        // the folded witnesses replaced the running ones
+        #[cfg(feature = "memory-tracking")]
+        {
+            for label in ["W_i1.W", "W_i1.E", "cf_W_i1.W", "cf_W_i1.E"] {
+                self.memory.release(label);
+            }
+            self.track_memory();
+        }
+
+        // in tests, report which constraints the new instances do not satisfy, which points to
+        // the step where the folding went wrong
         #[cfg(test)]
         {
             self.u_i.check_incoming()?;
-            self.r1cs.check_relation(&self.w_i, &self.u_i)?;
-            self.r1cs.check_relation(&self.W_i, &self.U_i)?;
+            self.r1cs
+                .check_relaxed_relation(&self.w_i.W, &self.u_i.x, self.u_i.u, &self.w_i.E)?;
+            self.r1cs
+                .check_relaxed_relation(&self.W_i.W, &self.U_i.x, self.U_i.u, &self.W_i.E)?;
+            self.cf_r1cs.check_relaxed_relation(
+                &self.cf_W_i.W,
+                &self.cf_U_i.x,
+                self.cf_U_i.u,
+                &self.cf_W_i.E,
+            )?;
         }
 
         Ok(())
     }
 
-    fn state(&self) -> Vec<C1::ScalarField> {
-        self.z_i.clone()
-    }
-
-    fn ivc_proof(&self) -> Self::IVCProof {
-        Self::IVCProof {
-            i: self.i,
-            z_0: self.z_0.clone(),
-            z_i: self.z_i.clone(),
-            W_i: self.W_i.clone(),
-            U_i: self.U_i.clone(),
-            w_i: self.w_i.clone(),
-            u_i: self.u_i.clone(),
-            cf_W_i: self.cf_W_i.clone(),
-            cf_U_i: self.cf_U_i.clone(),
-        }
-    }
-
-    fn from_ivc_proof(
-        ivc_proof: IVCProof<C1, C2>,
-        fcircuit_params: FC::Params,
-        params: (Self::ProverParam, Self::VerifierParam),
-    ) -> Result<Self, Error> {
-        let IVCProof {
-            i,
-            z_0,
-            z_i,
-            W_i,
-            U_i,
-            w_i,
-            u_i,
-            cf_W_i,
-            cf_U_i,
-        } = ivc_proof;
-        let (pp, vp) = params;
-
-        let f_circuit = FC::new(fcircuit_params)?;
-        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
-        cs.set_mode(SynthesisMode::Setup);
-        let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
-        cs2.set_mode(SynthesisMode::Setup);
-        let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&pp.poseidon_config, f_circuit.clone());
-        let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
-
-        augmented_F_circuit.generate_constraints(cs.clone())?;
-        cs.finalize();
-        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let r1cs = extract_r1cs::<C1::ScalarField>(&cs)?;
-
-        cf_circuit.generate_constraints(cs2.clone())?;
-        cs2.finalize();
-        let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
-        let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
-
-        Ok(Self {
-            r1cs,
-            cf_r1cs,
-            poseidon_config: pp.poseidon_config,
-            cs_pp: pp.cs_pp,
-            cf_cs_pp: pp.cf_cs_pp,
-            F: f_circuit,
-            pp_hash: vp.pp_hash()?,
-            i,
-            z_0,
-            z_i,
-            w_i,
-            u_i,
-            W_i,
-            U_i,
-            cf_W_i,
-            cf_U_i,
-        })
-    }
-
-    /// Implements IVC.V of Nov.clone()a+CycleFold. Notice that this method does not include the
-    /// commitments verification, which is done in the Decider.
-    fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
-        let Self::IVCProof {
-            i: num_steps,
-            z_0,
-            z_i,
-            W_i,
-            U_i,
-            w_i,
-            u_i,
-            cf_W_i,
-            cf_U_i,
-        } = ivc_proof;
-
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
-
-        if num_steps == C1::ScalarField::zero() {
-            if z_0 != z_i {
-                return Err(Error::IVCVerificationFail);
-            }
-            return Ok(());
-        }
-
-        if u_i.x.len() != 2 || U_i.x.len() != 2 {
-            return Err(Error::IVCVerificationFail);
-        }
-
-        let pp_hash = vp.pp_hash()?;
-
-        // check that u_i's output points to the running instance
-        // u_i.X[0] == H(i, z_0, z_i, U_i)
-        let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, &z_0, &z_i);
-        if expected_u_i_x != u_i.x[0] {
-            return Err(Error::IVCVerificationFail);
-        }
-        // u_i.X[1] == H(cf_U_i)
-        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
-        if expected_cf_u_i_x != u_i.x[1] {
-            return Err(Error::IVCVerificationFail);
-        }
-
-        // check R1CS satisfiability, which is equivalent to checking if `u_i`
-        // is an incoming instance and if `w_i` and `u_i` satisfy RelaxedR1CS
-        u_i.check_incoming()?;
-        vp.r1cs.check_relation(&w_i, &u_i)?;
-        // check RelaxedR1CS satisfiability
-        vp.r1cs.check_relation(&W_i, &U_i)?;
-
-        // check CycleFold RelaxedR1CS satisfiability
-        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
-
-        Ok(())
+    /// computes the witness hint of the step `i_usize` with [`FCircuit::precompute_witness`] and
+    /// folds the Nova instances, returning the external inputs completed with the hint.
+    #[allow(clippy::type_complexity)]
+    fn precompute_and_fold(
+        &mut self,
+        i_usize: usize,
+        external_inputs: FC::ExternalInputs,
+        transcript: &mut PoseidonSponge<C1::ScalarField>,
+    ) -> Result<
+        (
+            FC::ExternalInputs,
+            (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>),
+        ),
+        Error,
+    > {
+        let hint = self
+            .F
+            .precompute_witness(i_usize, &self.z_i, &external_inputs)?;
+        let external_inputs = self.F.with_witness_hint(external_inputs, hint);
+        let folded = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove_with_scratch(
+            &self.cs_pp,
+            &self.r1cs,
+            transcript,
+            self.pp_hash,
+            &self.W_i,
+            &self.U_i,
+            &self.w_i,
+            &self.u_i,
+            &mut self.scratch,
+        )?;
+        Ok((external_inputs, folded))
     }
-}
 
-impl<C1, C2, FC, CS1, CS2, const H: bool> Nova<C1, C2, FC, CS1, CS2, H>
-where
-    C1: Curve,
-    C2: Curve,
-    FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1, H>,
-    CS2: CommitmentScheme<C2, H>,
-    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
-{
-    // folds the given cyclefold circuit and its instances
+    // folds the given cyclefold circuit and its instances into the CycleFold running instance
     #[allow(clippy::type_complexity)]
     fn fold_cyclefold_circuit<T: Transcript<C1::ScalarField>>(
-        &self,
+        &mut self,
         transcript: &mut T,
-        cf_W_i: CycleFoldWitness<C2>, // witness of the running instance
-        cf_U_i: CycleFoldCommittedInstance<C2>, // running instance
         cf_circuit: NovaCycleFoldCircuit<C1>,
         rng: &mut impl RngCore,
     ) -> Result<
@@ -991,19 +1276,90 @@ where
         ),
         Error,
     > {
-        fold_cyclefold_circuit::<NovaCycleFoldConfig<C1>, C2, CS2, H>(
+        fold_cyclefold_circuit_with_scratch::<NovaCycleFoldConfig<C1>, C2, CS2, H>(
             transcript,
-            self.cf_r1cs.clone(),
-            self.cf_cs_pp.clone(),
+            &self.cf_r1cs,
+            &self.cf_cs_pp,
             self.pp_hash,
-            cf_W_i,
-            cf_U_i,
+            &self.cf_W_i, // CycleFold running instance witness
+            &self.cf_U_i, // CycleFold running instance
             cf_circuit,
             rng,
+            &mut self.cf_scratch,
         )
     }
 }
 
+#[cfg(feature = "pipelining")]
+impl<C1, C2, FC, CS1, CS2, const H: bool> Nova<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField> + Sync,
+    FC::ExternalInputs: Sync,
+    FC::WitnessHint: Send,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    /// proves a step as [`FoldingScheme::prove_step`], but computing the witness hint of the step
+    /// with [`FCircuit::precompute_witness`] in another thread, while the prover folds the Nova
+    /// instances (ie. computes the cross term `T` and its commitment).
+    pub fn prove_step_pipelined(
+        &mut self,
+        rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+        // Nova does not support multi-instances folding (by design)
+        other_instances: Option<()>,
+    ) -> Result<(), Error> {
+        self.prove_step_with(
+            rng,
+            external_inputs,
+            other_instances,
+            Self::precompute_and_fold_pipelined,
+        )
+    }
+
+    // same as `precompute_and_fold`, but computing the witness hint in another thread
+    #[allow(clippy::type_complexity)]
+    fn precompute_and_fold_pipelined(
+        &mut self,
+        i_usize: usize,
+        external_inputs: FC::ExternalInputs,
+        transcript: &mut PoseidonSponge<C1::ScalarField>,
+    ) -> Result<
+        (
+            FC::ExternalInputs,
+            (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>),
+        ),
+        Error,
+    > {
+        let (F, z_i, inputs) = (&self.F, &self.z_i, &external_inputs);
+        let (hint, folded) = std::thread::scope(|s| {
+            let precompute = s.spawn(move || F.precompute_witness(i_usize, z_i, inputs));
+            let folded = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove_with_scratch(
+                &self.cs_pp,
+                &self.r1cs,
+                transcript,
+                self.pp_hash,
+                &self.W_i,
+                &self.U_i,
+                &self.w_i,
+                &self.u_i,
+                &mut self.scratch,
+            );
+            let hint = precompute.join().unwrap_or_else(|_| {
+                Err(Error::Other(
+                    "The precomputation of the witness of the step panicked".to_string(),
+                ))
+            });
+            (hint, folded)
+        });
+        let external_inputs = self.F.with_witness_hint(external_inputs, hint?);
+        Ok((external_inputs, folded?))
+    }
+}
+
 /// helper method to get the r1cs from the ConstraintSynthesizer
 pub fn get_r1cs_from_cs<F: PrimeField>(
     circuit: impl ConstraintSynthesizer<F>,
@@ -1041,11 +1397,21 @@ pub mod tests {
     use crate::commitment::kzg::KZG;
     use ark_bn254::{Bn254, Fr, G1Projective as Projective};
     use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::{
+        alloc::AllocVar,
+        eq::EqGadget,
+        fields::{fp::FpVar, FieldVar},
+    };
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_std::marker::PhantomData;
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, ThreadId};
+    use std::time::Duration;
 
     use super::*;
     use crate::commitment::pedersen::Pedersen;
-    use crate::frontend::utils::CubicFCircuit;
-    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::frontend::utils::{cubic_step_native, CubicFCircuit, DummyCircuit};
+    use crate::transcript::poseidon::{poseidon_canonical_config, poseidon_config_with_width};
 
     /// This test tests the Nova+CycleFold IVC, and by consequence it is also testing the
     /// AugmentedFCircuit
@@ -1078,6 +1444,598 @@ pub mod tests {
         Ok(())
     }
 
+    /// runs the same IVC twice in zero-knowledge mode, checking that the commitments of both runs
+    /// differ (for the main and the CycleFold instances) while both IVC proofs verify
+    #[test]
+    fn test_ivc_hiding_commitments() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova1 = N::init(&params, F_circuit, z_0.clone())?;
+        let mut nova2 = N::init(&params, F_circuit, z_0)?;
+        for _ in 0..3 {
+            nova1.prove_step(&mut rng, (), None)?;
+            nova2.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(nova1.z_i, nova2.z_i);
+
+        assert_ne!(nova1.u_i.cmW, nova2.u_i.cmW);
+        assert_ne!(nova1.U_i.cmW, nova2.U_i.cmW);
+        assert_ne!(nova1.U_i.cmE, nova2.U_i.cmE);
+        assert_ne!(nova1.cf_U_i.cmW, nova2.cf_U_i.cmW);
+        assert_ne!(nova1.cf_U_i.cmE, nova2.cf_U_i.cmE);
+
+        N::verify(params.1.clone(), nova1.ivc_proof())?;
+        N::verify(params.1, nova2.ivc_proof())?;
+        Ok(())
+    }
+
+    /// checks that the public parameters hash is the same in the prover and verifier params, that
+    /// it changes when an element of the commitment scheme's setup is modified, and that the
+    /// verification of an IVCProof against the modified params fails with the specific error.
+    #[test]
+    fn test_pp_hash_mismatch() -> Result<(), Error> {
+        use crate::arith::ArithSerializer;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param)?;
+        assert_eq!(pp.pp_hash(), vp.pp_hash()?);
+
+        let mut nova = N::init(&(pp.clone(), vp.clone()), F_circuit, vec![Fr::from(3_u32)])?;
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        let ivc_proof = nova.ivc_proof();
+        assert_eq!(ivc_proof.pp_hash, vp.pp_hash()?);
+        N::verify(vp.clone(), ivc_proof.clone())?;
+
+        // mutate a single element of the SRS
+        let mut vp_mutated = vp.clone();
+        vp_mutated.cs_vp.generators[0] = vp_mutated.cs_vp.generators[1];
+        assert_ne!(vp.pp_hash()?, vp_mutated.pp_hash()?);
+
+        assert!(matches!(
+            N::verify(vp_mutated.clone(), ivc_proof.clone()),
+            Err(Error::PPHashMismatch)
+        ));
+        assert!(matches!(
+            N::from_ivc_proof(ivc_proof.clone(), (), (pp, vp_mutated)),
+            Err(Error::PPHashMismatch)
+        ));
+
+        // nor do the ones of a circuit of the same shape but with other constraints
+        let mut vp_other_r1cs = vp.clone();
+        vp_other_r1cs.r1cs.A.entries[0].0 += Fr::one();
+        assert_eq!(
+            vp_other_r1cs.r1cs.params_to_le_bytes(),
+            vp.r1cs.params_to_le_bytes()
+        );
+        assert_ne!(vp.pp_hash()?, vp_other_r1cs.pp_hash()?);
+        assert!(matches!(
+            N::verify(vp_other_r1cs, ivc_proof.clone()),
+            Err(Error::PPHashMismatch)
+        ));
+        // the sizes are encoded as `u64`s, whatever the platform
+        assert_eq!(vp.r1cs.params_to_le_bytes().len(), 3 * 8);
+
+        // the verifier params with another Poseidon configuration do not match either
+        let mut vp_other_poseidon = vp.clone();
+        vp_other_poseidon.poseidon_config = poseidon_config_with_width::<Fr, 9>();
+        assert!(matches!(
+            N::verify(vp_other_poseidon, ivc_proof),
+            Err(Error::PPHashMismatch)
+        ));
+        Ok(())
+    }
+
+    /// runs the IVC with Poseidon configurations of other widths than the canonical one (including
+    /// the serialization of the params, which carries the configuration), and compares the sizes
+    /// of the augmented circuits for a state of 28 elements
+    #[test]
+    fn test_ivc_poseidon_widths() -> Result<(), Error> {
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config_with_width::<Fr, 3>(),
+            F_circuit,
+            3,
+        )?;
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config_with_width::<Fr, 9>(),
+            F_circuit,
+            3,
+        )?;
+
+        let dummy_circuit = <DummyCircuit as FCircuit<Fr>>::new(28)?;
+        let mut n_constraints = vec![];
+        for poseidon_config in [
+            poseidon_config_with_width::<Fr, 3>(),
+            poseidon_config_with_width::<Fr, 5>(),
+            poseidon_config_with_width::<Fr, 9>(),
+        ] {
+            let (r1cs, _) =
+                get_r1cs::<Projective, Projective2, _>(&poseidon_config, dummy_circuit.clone())?;
+            n_constraints.push(r1cs.n_constraints());
+        }
+        println!(
+            "AugmentedFCircuit with state_len=28 and Poseidon t = 3, 5, 9: {:?} constraints",
+            n_constraints
+        );
+        assert!(n_constraints[0] > n_constraints[1] && n_constraints[1] > n_constraints[2]);
+        Ok(())
+    }
+
+    /// reports the constraints of the augmented circuit saved by absorbing the nonnative values
+    /// by packing their limbs instead of decomposing them into bits
+    #[test]
+    fn test_augmented_circuit_nonnative_absorption() -> Result<(), Error> {
+        use crate::folding::circuits::nonnative::uint::{NonNativeUintVar, ABSORB_BY_BITS};
+        use ark_bn254::Fq;
+        use ark_r1cs_std::convert::ToBitsGadget;
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let n_constraints = |by_bits: bool| -> Result<usize, Error> {
+            ABSORB_BY_BITS.with(|b| b.set(by_bits));
+            let r1cs = get_r1cs::<Projective, Projective2, _>(&poseidon_config, F_circuit);
+            ABSORB_BY_BITS.with(|b| b.set(false));
+            Ok(r1cs?.0.n_constraints())
+        };
+        let by_limbs = n_constraints(false)?;
+        let by_bits = n_constraints(true)?;
+
+        // decomposing a coordinate of a commitment of the main curve into bits
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = NonNativeUintVar::<Fr>::new_witness(cs.clone(), || Ok(Fq::from(3_u32)))?;
+        let n = cs.num_constraints();
+        x.to_bits_le()?;
+        let per_coordinate = cs.num_constraints() - n;
+
+        println!(
+            "AugmentedFCircuit: {} constraints when absorbing the nonnative values by their \
+             limbs, {} by their bits ({} per coordinate of a commitment)",
+            by_limbs, by_bits, per_coordinate
+        );
+        // the savings include at least the decomposition of the coordinates of the witness
+        // commitments U_i.cmE, U_i.cmW (hash of U_i), u_i.cmW, cmT (NIFS transcript), and
+        // U_{i+1}.cmE, U_{i+1}.cmW (hash of U_{i+1}), on top of the ones of the nonnative values
+        // of the CycleFold instances
+        assert!(per_coordinate > 0);
+        assert!(by_bits - by_limbs >= 12 * per_coordinate);
+        Ok(())
+    }
+
+    /// pins down the semantics of the step counter `i` of the IVCProof at its boundaries
+    #[test]
+    fn test_ivc_step_boundaries() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, F_circuit, vec![Fr::from(3_u32)])?;
+
+        // i = 0: the proof is valid as long as z_i == z_0 and the instances are the dummy ones
+        let proof_0 = nova.ivc_proof();
+        assert_eq!(proof_0.num_steps()?, 0);
+        N::verify(params.1.clone(), proof_0.clone())?;
+        let mut bad_proof_0 = proof_0.clone();
+        bad_proof_0.z_i = vec![Fr::from(4_u32)];
+        assert!(N::verify(params.1.clone(), bad_proof_0).is_err());
+
+        // i = 1
+        nova.prove_step(&mut rng, (), None)?;
+        assert_eq!(nova.num_steps()?, 1);
+        let proof_1 = nova.ivc_proof();
+        N::verify(params.1.clone(), proof_1.clone())?;
+
+        nova.prove_step(&mut rng, (), None)?;
+        let proof_2 = nova.ivc_proof();
+
+        // i = 0 carrying a non-trivial running instance is rejected
+        let mut bad_proof_0 = proof_0;
+        bad_proof_0.U_i = proof_2.U_i.clone();
+        bad_proof_0.W_i = proof_2.W_i.clone();
+        assert!(matches!(
+            N::verify(params.1.clone(), bad_proof_0),
+            Err(Error::IVCVerificationFail)
+        ));
+
+        // a fabricated large i that still fits in a u64 does not verify
+        let mut large_proof = proof_2.clone();
+        large_proof.i = Fr::from(u64::MAX);
+        assert_eq!(large_proof.num_steps()?, u64::MAX);
+        assert!(matches!(
+            N::verify(params.1.clone(), large_proof),
+            Err(Error::IVCVerificationFail)
+        ));
+
+        // an i beyond u64 can not be converted
+        let mut huge_proof = proof_2;
+        huge_proof.i = Fr::from(u64::MAX) + Fr::one();
+        assert!(matches!(huge_proof.num_steps(), Err(Error::OutOfBounds)));
+        huge_proof.i = -Fr::one();
+        assert!(matches!(huge_proof.num_steps(), Err(Error::OutOfBounds)));
+        assert!(N::verify(params.1, huge_proof).is_err());
+        Ok(())
+    }
+
+    /// FCircuit computing z_{i+1} = z_i + 1, whose constraints are unsatisfied at the step
+    /// `broken_step`
+    #[derive(Clone, Copy, Debug)]
+    struct BrokenFCircuit<F: PrimeField> {
+        broken_step: usize,
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for BrokenFCircuit<F> {
+        type Params = usize;
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+        type WitnessHint = ();
+
+        fn new(broken_step: Self::Params) -> Result<Self, Error> {
+            Ok(Self {
+                broken_step,
+                _f: PhantomData,
+            })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            cs: ConstraintSystemRef<F>,
+            i: usize,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            let b = FpVar::new_witness(cs, || Ok(F::from((i == self.broken_step) as u64)))?;
+            b.enforce_equal(&FpVar::zero())?;
+            Ok(vec![&z_i[0] + FpVar::one()])
+        }
+    }
+
+    #[test]
+    fn test_unsatisfied_constraint_report() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = BrokenFCircuit::<Fr>::new(2)?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            BrokenFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, F_circuit, vec![Fr::zero()])?;
+        // steps 0 and 1 are fine
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        match nova.prove_step(&mut rng, (), None) {
+            Err(Error::UnsatisfiedConstraint { step, index, name }) => {
+                assert_eq!(step, 2);
+                assert!(index < nova.r1cs.n_constraints());
+                // without a `ConstraintLayer` tracing subscriber, arkworks names the constraint
+                // by its index, which must match the one that we found
+                assert_eq!(name, index.to_string());
+            }
+            r => panic!("expected Error::UnsatisfiedConstraint, got {:?}", r),
+        }
+        Ok(())
+    }
+
+    /// FCircuit computing z_{i+1} = z_i^3 + z_i + 5, where z_i^3 is an "expensive" witness hint,
+    /// precomputed natively while sleeping as if it were fetched from an external service
+    #[derive(Clone, Debug)]
+    struct HintFCircuit<F: PrimeField> {
+        // steps and threads of the calls to `precompute_witness`
+        calls: Arc<Mutex<Vec<(usize, ThreadId)>>>,
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for HintFCircuit<F> {
+        type Params = ();
+        type ExternalInputs = [F; 1];
+        type ExternalInputsVar = [FpVar<F>; 1];
+        type WitnessHint = F;
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self {
+                calls: Arc::new(Mutex::new(vec![])),
+                _f: PhantomData,
+            })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn precompute_witness(
+            &self,
+            i: usize,
+            z_i: &[F],
+            _external_inputs: &Self::ExternalInputs,
+        ) -> Result<Self::WitnessHint, Error> {
+            thread::sleep(Duration::from_millis(100));
+            self.calls.lock().unwrap().push((i, thread::current().id()));
+            Ok(z_i[0] * z_i[0] * z_i[0])
+        }
+        fn with_witness_hint(
+            &self,
+            _external_inputs: Self::ExternalInputs,
+            hint: Self::WitnessHint,
+        ) -> Self::ExternalInputs {
+            [hint]
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            _i: usize,
+            z_i: Vec<FpVar<F>>,
+            external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            let [z_i_cubed] = external_inputs;
+            z_i_cubed.enforce_equal(&(&z_i[0] * &z_i[0] * &z_i[0]))?;
+            Ok(vec![z_i_cubed + &z_i[0] + FpVar::Constant(F::from(5u32))])
+        }
+    }
+
+    #[test]
+    fn test_precompute_witness() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = HintFCircuit::<Fr>::new(())?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            HintFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&params, F_circuit.clone(), z_0.clone())?;
+        // the hints are not given by the caller, but precomputed at each step
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, [Fr::zero()], None)?;
+        }
+        N::verify(params.1.clone(), nova.ivc_proof())?;
+
+        let mut z = z_0.clone();
+        for _ in 0..3 {
+            z = cubic_step_native(z);
+        }
+        assert_eq!(nova.state(), z);
+
+        // the hints are computed before folding the instances, in the thread of the prover
+        let main_thread = thread::current().id();
+        let calls = F_circuit.calls.lock().unwrap().clone();
+        assert_eq!(calls.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(calls.iter().all(|(_, thread)| *thread == main_thread));
+
+        // with pipelining, the hints are computed in another thread, overlapping with the folding
+        // of the instances, and the folded instances are the same as without it
+        #[cfg(feature = "pipelining")]
+        {
+            let F_circuit = HintFCircuit::<Fr>::new(())?;
+            let mut pipelined = N::init(&params, F_circuit.clone(), z_0)?;
+            for _ in 0..3 {
+                pipelined.prove_step_pipelined(&mut rng, [Fr::zero()], None)?;
+            }
+            assert_eq!(pipelined.ivc_proof(), nova.ivc_proof());
+            N::verify(params.1, pipelined.ivc_proof())?;
+
+            let calls = F_circuit.calls.lock().unwrap().clone();
+            assert_eq!(calls.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [0, 1, 2]);
+            assert!(calls.iter().all(|(_, thread)| *thread != main_thread));
+        }
+        Ok(())
+    }
+
+    /// folds a large circuit with KZG, checking that the tracked allocations match the sizes of
+    /// the witnesses and commitment keys, and that the peak accounts for the folded witness
+    #[cfg(feature = "memory-tracking")]
+    #[test]
+    fn test_prove_step_memory_tracking() -> Result<(), Error> {
+        use crate::frontend::utils::CustomFCircuit;
+        use ark_bn254::Fq;
+        use std::mem::size_of;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CustomFCircuit::<Fr>::new(1 << 14)?;
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            CustomFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, F_circuit, vec![Fr::from(3_u32)])?;
+        // the second step also folds the CycleFold instances
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        let stats = nova.prove_step_stats();
+
+        let (n_constraints, n_witnesses) = (nova.r1cs.n_constraints(), nova.r1cs.n_witnesses());
+        assert!(n_constraints >= 1 << 14);
+        let nova_witness = (n_constraints + n_witnesses) * size_of::<Fr>();
+        let cf_witness =
+            (nova.cf_r1cs.n_constraints() + nova.cf_r1cs.n_witnesses()) * size_of::<Fq>();
+        let keys = nova.cs_pp.uncompressed_size() + nova.cf_cs_pp.uncompressed_size();
+        let scratch = nova.scratch.size_bytes() + nova.cf_scratch.size_bytes();
+        // the cross terms, which stay allocated in the scratch buffers across the steps
+        let T = nova.memory.get("T").unwrap();
+        let cf_T = nova.memory.get("cf_T").unwrap();
+        assert!(T >= n_constraints * size_of::<Fr>());
+        assert!(cf_T >= nova.cf_r1cs.n_constraints() * size_of::<Fq>());
+        assert_eq!(T, nova.scratch.cross_terms_size_bytes());
+        assert_eq!(cf_T, nova.cf_scratch.cross_terms_size_bytes());
+        // the running and the incoming Nova witnesses, and the running CycleFold witness
+        assert_eq!(
+            stats.tracked_bytes,
+            2 * nova_witness + cf_witness + scratch + T + cf_T + keys
+        );
+        assert_eq!(
+            nova.memory.get("W_i.E"),
+            Some(n_constraints * size_of::<Fr>())
+        );
+        // during the step, the folded witness coexists with the running one and the cross terms
+        assert!(stats.peak_tracked_bytes >= stats.tracked_bytes + nova_witness);
+        assert!(stats.peak_tracked_bytes < 2 * stats.tracked_bytes);
+        if let Some(peak_rss) = stats.peak_rss_bytes {
+            assert!(peak_rss >= stats.peak_tracked_bytes);
+        }
+
+        N::verify(params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
+    /// round-trips a real IVC proof, and its public data, through their JSON representations
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_ivc_proof_json() -> Result<(), Error> {
+        use crate::folding::traits::CommittedInstanceOps;
+        use crate::utils::json::{
+            fields_from_json, fields_to_json, points_from_json, points_to_json, JsonSerde,
+        };
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let (_, nova) = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config,
+            F_circuit,
+            3,
+        )?;
+
+        let ivc_proof = nova.ivc_proof();
+        let json = ivc_proof.to_json()?;
+        assert_eq!(
+            IVCProof::<Projective, Projective2>::from_json(&json)?,
+            ivc_proof
+        );
+
+        let z_i = fields_to_json(&nova.z_i)?;
+        assert_eq!(fields_from_json::<Fr>(&z_i)?, nova.z_i);
+        let commitments = nova.U_i.get_commitments();
+        let json = points_to_json(&commitments)?;
+        assert_eq!(points_from_json::<Projective>(&json)?, commitments);
+        assert_eq!(
+            CommittedInstance::<Projective>::from_json(&nova.u_i.to_json()?)?,
+            nova.u_i
+        );
+        Ok(())
+    }
+
+    /// checks the JSON representation of an IVC proof against the fixture, which freezes the
+    /// format
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_ivc_proof_json_fixture() -> Result<(), Error> {
+        use crate::utils::json::JsonSerde;
+        use ark_bn254::Fq;
+        use ark_ec::PrimeGroup;
+
+        let fixture = include_str!("ivc_proof_fixture.json");
+        let g = Projective::generator();
+        let expected: IVCProof<Projective, Projective2> = IVCProof {
+            pp_hash: Fr::from(0x1234u64),
+            i: Fr::from(3u64),
+            z_0: vec![Fr::from(3u64)],
+            z_i: vec![-Fr::one()],
+            W_i: Witness {
+                E: vec![Fr::from(1u64), Fr::from(2u64)],
+                rE: Fr::zero(),
+                W: vec![Fr::from(5u64), -Fr::one()],
+                rW: Fr::zero(),
+            },
+            U_i: CommittedInstance {
+                cmE: g,
+                u: Fr::from(2u64),
+                cmW: -g,
+                x: vec![Fr::from(7u64), Fr::from(8u64)],
+            },
+            w_i: Witness {
+                E: vec![Fr::zero(); 2],
+                rE: Fr::zero(),
+                W: vec![Fr::from(6u64), Fr::from(1u64)],
+                rW: Fr::zero(),
+            },
+            u_i: CommittedInstance {
+                cmE: Projective::zero(),
+                u: Fr::one(),
+                cmW: g,
+                x: vec![Fr::from(9u64), Fr::from(10u64)],
+            },
+            cf_W_i: CycleFoldWitness {
+                E: vec![Fq::from(1u64)],
+                rE: Fq::zero(),
+                W: vec![Fq::from(2u64), -Fq::one()],
+                rW: Fq::zero(),
+            },
+            cf_U_i: CycleFoldCommittedInstance {
+                cmE: Projective2::zero(),
+                u: Fq::one(),
+                cmW: Projective2::zero(),
+                x: vec![-Fq::one(), Fq::from(4u64)],
+            },
+        };
+
+        assert_eq!(
+            IVCProof::<Projective, Projective2>::from_json(fixture)?,
+            expected
+        );
+        let json: serde_json::Value = serde_json::from_str(&expected.to_json()?)
+            .map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+        let fixture: serde_json::Value =
+            serde_json::from_str(fixture).map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+        assert_eq!(json, fixture);
+        Ok(())
+    }
+
     // test_ivc allowing to choose the CommitmentSchemes
     #[allow(clippy::type_complexity)]
     pub(crate) fn test_ivc_opt<
@@ -18,7 +18,7 @@ use crate::folding::traits::Dummy;
 use crate::transcript::Transcript;
 use crate::utils::{
     mle::dense_vec_to_dense_mle,
-    vec::{is_zero_vec, vec_add, vec_scalar_mul},
+    vec::{add_scaled, axpy_in_place, is_zero_vec},
 };
 use crate::{Curve, Error};
 
@@ -174,16 +174,9 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         aux: &Vec<C::ScalarField>, // T in Mova's notation
     ) -> Result<Witness<C>, Error> {
         let a2 = a * a;
-        let E: Vec<C::ScalarField> = vec_add(
-            &vec_add(&W_i.E, &vec_scalar_mul(aux, &a))?,
-            &vec_scalar_mul(&w_i.E, &a2),
-        )?;
-        let W: Vec<C::ScalarField> = W_i
-            .W
-            .iter()
-            .zip(&w_i.W)
-            .map(|(i1, i2)| *i1 + (a * i2))
-            .collect();
+        let mut E = add_scaled(&W_i.E, &a, aux)?;
+        axpy_in_place(&mut E, &a2, &w_i.E)?;
+        let W = add_scaled(&W_i.W, &a, &w_i.W)?;
 
         let rW = W_i.rW + a * w_i.rW;
         Ok(Witness::<C> { E, W, rW })
@@ -232,8 +225,8 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         transcript.absorb(&mleE2_prime);
 
         // compute the cross terms
-        let z1: Vec<C::ScalarField> = [vec![U_i.u], U_i.x.to_vec(), W_i.W.to_vec()].concat();
-        let z2: Vec<C::ScalarField> = [vec![u_i.u], u_i.x.to_vec(), w_i.W.to_vec()].concat();
+        let z1: Vec<C::ScalarField> = [&[U_i.u][..], &U_i.x, &W_i.W].concat();
+        let z2: Vec<C::ScalarField> = [&[u_i.u][..], &u_i.x, &w_i.W].concat();
         let T = NovaNIFS::<C, CS, T, H>::compute_T(r1cs, U_i.u, u_i.u, &z1, &z2, &W_i.E, &w_i.E)?;
 
         let n_vars: usize = log2(W_i.E.len()) as usize;
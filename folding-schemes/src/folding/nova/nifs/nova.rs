@@ -1,11 +1,15 @@
 /// This module contains the implementation the NIFSTrait for the
 /// [Nova](https://eprint.iacr.org/2021/370.pdf) NIFS (Non-Interactive Folding Scheme).
-use ark_crypto_primitives::sponge::{constraints::AbsorbGadget, Absorb, CryptographicSponge};
+use ark_crypto_primitives::sponge::{
+    constraints::AbsorbGadget, poseidon::PoseidonSponge, Absorb, CryptographicSponge,
+};
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar};
 use ark_relations::r1cs::SynthesisError;
 use ark_std::rand::RngCore;
-use ark_std::Zero;
+use ark_std::{cfg_iter_mut, Zero};
+use rayon::prelude::*;
+use std::iter::once;
 use std::marker::PhantomData;
 
 use super::NIFSTrait;
@@ -18,8 +22,11 @@ use crate::folding::circuits::{
     CF1,
 };
 use crate::folding::nova::{CommittedInstance, Witness};
-use crate::transcript::{Transcript, TranscriptVar};
-use crate::utils::vec::{hadamard, mat_vec_mul, vec_add, vec_scalar_mul, vec_sub};
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript, TranscriptVar};
+use crate::utils::vec::{
+    add_scaled, axpy_in_place, dense_vec_to_sparse_into, hadamard, mat_vec_mul, mat_vec_mul_into,
+    vec_add, vec_scalar_mul, vec_sub,
+};
 use crate::{Curve, Error};
 
 /// ChallengeGadget computes the RO challenge used for the Nova instances NIFS, it contains a
@@ -69,6 +76,40 @@ impl<C: Curve, CI: Absorb> ChallengeGadget<C, CI> {
     }
 }
 
+/// Buffers for the temporaries of the Nova prover (the vectors used to compute the cross terms,
+/// and the cross terms themselves). They are kept across the folding steps, so that at each step
+/// they are cleared and refilled instead of reallocated.
+#[derive(Clone, Debug, Default)]
+pub struct ProverScratch<F: PrimeField> {
+    z: Vec<F>,
+    Az: Vec<F>,
+    Bz: Vec<F>,
+    Cz: Vec<F>,
+    /// cross terms computed by the last call to [`NIFS::compute_T_into`]
+    pub(crate) T: Vec<F>,
+    T_sparse: Vec<(usize, F)>,
+}
+
+impl<F: PrimeField> ProverScratch<F> {
+    /// size in bytes of the memory allocated by the buffers, the cross terms excluded (see
+    /// [`ProverScratch::cross_terms_size_bytes`])
+    pub fn size_bytes(&self) -> usize {
+        (self.z.capacity() + self.Az.capacity() + self.Bz.capacity() + self.Cz.capacity())
+            * std::mem::size_of::<F>()
+            + self.T_sparse.capacity() * std::mem::size_of::<(usize, F)>()
+    }
+
+    /// cross terms computed by the last fold
+    pub fn cross_terms(&self) -> &[F] {
+        &self.T
+    }
+
+    /// size in bytes of the memory allocated by the buffer of the cross terms
+    pub fn cross_terms_size_bytes(&self) -> usize {
+        self.T.capacity() * std::mem::size_of::<F>()
+    }
+}
+
 /// Implements the Non-Interactive Folding Scheme described in section 4 of
 /// [Nova](https://eprint.iacr.org/2021/370.pdf).
 /// `H` specifies whether the NIFS will use a blinding factor
@@ -112,19 +153,11 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         aux: &Self::ProverAux, // T in Nova's notation
     ) -> Result<Self::Witness, Error> {
         let r2 = r * r;
-        let E: Vec<C::ScalarField> = vec_add(
-            &vec_add(&W_i.E, &vec_scalar_mul(aux, &r))?, // aux is Nova's T
-            &vec_scalar_mul(&w_i.E, &r2),
-        )?;
-        // use r_T=0 since we don't need hiding property for cm(T)
-        let rT = C::ScalarField::zero();
+        let mut E = add_scaled(&W_i.E, &r, aux)?; // aux is Nova's T
+        axpy_in_place(&mut E, &r2, &w_i.E)?;
+        let rT = Self::cross_terms_blinding(W_i, w_i);
         let rE = W_i.rE + r * rT + r2 * w_i.rE;
-        let W: Vec<C::ScalarField> = W_i
-            .W
-            .iter()
-            .zip(&w_i.W)
-            .map(|(a, b)| *a + (r * b))
-            .collect();
+        let W = add_scaled(&W_i.W, &r, &w_i.W)?;
 
         let rW = W_i.rW + r * w_i.rW;
         Ok(Self::Witness { E, rE, W, rW })
@@ -148,29 +181,17 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         ),
         Error,
     > {
-        // compute the cross terms
-        let z1: Vec<C::ScalarField> = [vec![U_i.u], U_i.x.to_vec(), W_i.W.to_vec()].concat();
-        let z2: Vec<C::ScalarField> = [vec![u_i.u], u_i.x.to_vec(), w_i.W.to_vec()].concat();
-        let T = Self::compute_T(r1cs, U_i.u, u_i.u, &z1, &z2, &W_i.E, &w_i.E)?;
-
-        // use r_T=0 since we don't need hiding property for cm(T)
-        let cmT = CS::commit(cs_prover_params, &T, &C::ScalarField::zero())?;
-
-        let r_bits = ChallengeGadget::<C, Self::CommittedInstance>::get_challenge_native(
+        Self::prove_with_scratch(
+            cs_prover_params,
+            r1cs,
             transcript,
             pp_hash,
+            W_i,
             U_i,
+            w_i,
             u_i,
-            Some(&cmT),
-        );
-        let r_Fr = C::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
-            .ok_or(Error::OutOfBounds)?;
-
-        let w = Self::fold_witness(r_Fr, W_i, w_i, &T)?;
-
-        let ci = Self::fold_committed_instances(r_Fr, U_i, u_i, &cmT);
-
-        Ok((w, ci, cmT, r_bits))
+            &mut ProverScratch::default(),
+        )
     }
 
     fn verify(
@@ -197,6 +218,46 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
 impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const H: bool>
     NIFS<C, CS, T, H>
 {
+    /// same as [`NIFSTrait::prove`], but reusing the buffers of `scratch` for the temporaries
+    #[allow(clippy::type_complexity)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_with_scratch(
+        cs_prover_params: &CS::ProverParams,
+        r1cs: &R1CS<C::ScalarField>,
+        transcript: &mut T,
+        pp_hash: C::ScalarField,
+        W_i: &Witness<C>,
+        U_i: &CommittedInstance<C>,
+        w_i: &Witness<C>,
+        u_i: &CommittedInstance<C>,
+        scratch: &mut ProverScratch<C::ScalarField>,
+    ) -> Result<(Witness<C>, CommittedInstance<C>, C, Vec<bool>), Error> {
+        // compute the cross terms
+        Self::compute_T_into(r1cs, W_i, U_i, w_i, u_i, scratch)?;
+
+        let rT = Self::cross_terms_blinding(W_i, w_i);
+        // the cross terms are usually sparse (eg. they are all zero when folding into the dummy
+        // running instance), so commit only to their non-zero entries
+        dense_vec_to_sparse_into(&scratch.T, &mut scratch.T_sparse);
+        let cmT = CS::commit_sparse(cs_prover_params, &scratch.T_sparse, scratch.T.len(), &rT)?;
+
+        let r_bits = ChallengeGadget::<C, CommittedInstance<C>>::get_challenge_native(
+            transcript,
+            pp_hash,
+            U_i,
+            u_i,
+            Some(&cmT),
+        );
+        let r_Fr = C::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+
+        let w = Self::fold_witness(r_Fr, W_i, w_i, &scratch.T)?;
+
+        let ci = Self::fold_committed_instances(r_Fr, U_i, u_i, &cmT);
+
+        Ok((w, ci, cmT, r_bits))
+    }
+
     /// compute_T: compute cross-terms T. We use the approach described in
     /// [Mova](https://eprint.iacr.org/2024/1220.pdf)'s section 5.2.
     pub fn compute_T(
@@ -221,6 +282,76 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         vec_sub(&vec_sub(&lhs, E1)?, E2)
     }
 
+    /// same as [`Self::compute_T`], but for the folding of `(W1, ci1)` with `(W2, ci2)`, computing
+    /// the cross terms into `scratch.T` and reusing the buffers of `scratch` for the intermediate
+    /// vectors
+    pub fn compute_T_into(
+        r1cs: &R1CS<C::ScalarField>,
+        W1: &Witness<C>,
+        ci1: &CommittedInstance<C>,
+        W2: &Witness<C>,
+        ci2: &CommittedInstance<C>,
+        scratch: &mut ProverScratch<C::ScalarField>,
+    ) -> Result<(), Error> {
+        let (z1_len, z2_len) = (1 + ci1.x.len() + W1.W.len(), 1 + ci2.x.len() + W2.W.len());
+        if z1_len != z2_len {
+            return Err(Error::NotSameLength(
+                "z1.len()".to_string(),
+                z1_len,
+                "z2.len()".to_string(),
+                z2_len,
+            ));
+        }
+        let z1 = once(&ci1.u).chain(&ci1.x).chain(&W1.W);
+        let z2 = once(&ci2.u).chain(&ci2.x).chain(&W2.W);
+        scratch.z.clear();
+        scratch.z.extend(z1.zip(z2).map(|(a, b)| *a + b));
+
+        mat_vec_mul_into(&r1cs.A, &scratch.z, &mut scratch.Az)?;
+        mat_vec_mul_into(&r1cs.B, &scratch.z, &mut scratch.Bz)?;
+        mat_vec_mul_into(&r1cs.C, &scratch.z, &mut scratch.Cz)?;
+        for E in [&W1.E, &W2.E] {
+            if E.len() != r1cs.n_constraints() {
+                return Err(Error::NotSameLength(
+                    "E.len()".to_string(),
+                    E.len(),
+                    "n_constraints".to_string(),
+                    r1cs.n_constraints(),
+                ));
+            }
+        }
+
+        let u = ci1.u + ci2.u;
+        let ProverScratch { Az, Bz, Cz, T, .. } = scratch;
+        T.clear();
+        T.resize(Az.len(), C::ScalarField::zero());
+        cfg_iter_mut!(T)
+            .enumerate()
+            .for_each(|(i, t)| *t = Az[i] * Bz[i] - Cz[i] * u - W1.E[i] - W2.E[i]);
+        Ok(())
+    }
+
+    /// Returns the blinding factor of the commitment to the cross terms `T` of the folding of
+    /// `W_i` and `w_i`, which is zero when the commitments are not hiding.
+    /// Since `fold_witness` does not get any randomness, the blinding factor is derived from the
+    /// (random and secret) blinding factors of both witnesses, so that the same value is obtained
+    /// when committing to `T` and when folding the witnesses. Note that the order of the witnesses
+    /// matters, it has to be the same as in `fold_witness`.
+    pub fn cross_terms_blinding(W_i: &Witness<C>, w_i: &Witness<C>) -> C::ScalarField {
+        if !H {
+            return C::ScalarField::zero();
+        }
+        let mut sponge =
+            PoseidonSponge::<C::ScalarField>::new(&poseidon_canonical_config::<C::ScalarField>());
+        sponge.absorb(&W_i.rW);
+        sponge.absorb(&W_i.rE);
+        sponge.absorb(&w_i.rW);
+        sponge.absorb(&w_i.rE);
+        sponge.get_challenge()
+    }
+
+    /// computes the cross terms `T` and their commitment `cmT` for folding the CycleFold running
+    /// instance `(w1, ci1)` with `(w2, ci2)`, which must be later folded in the same order.
     pub fn compute_cyclefold_cmT(
         cs_prover_params: &CS::ProverParams,
         r1cs: &R1CS<C::ScalarField>, // R1CS over C2.Fr=C1.Fq (here C=C2)
@@ -229,14 +360,38 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         w2: &CycleFoldWitness<C>,
         ci2: &CycleFoldCommittedInstance<C>,
     ) -> Result<(Vec<C::ScalarField>, C), Error> {
-        let z1: Vec<C::ScalarField> = [vec![ci1.u], ci1.x.to_vec(), w1.W.to_vec()].concat();
-        let z2: Vec<C::ScalarField> = [vec![ci2.u], ci2.x.to_vec(), w2.W.to_vec()].concat();
+        let mut scratch = ProverScratch::default();
+        let cmT = Self::compute_cyclefold_cmT_into(
+            cs_prover_params,
+            r1cs,
+            w1,
+            ci1,
+            w2,
+            ci2,
+            &mut scratch,
+        )?;
+        Ok((scratch.T, cmT))
+    }
 
+    /// same as [`Self::compute_cyclefold_cmT`], but computing the cross terms into `scratch.T`
+    pub fn compute_cyclefold_cmT_into(
+        cs_prover_params: &CS::ProverParams,
+        r1cs: &R1CS<C::ScalarField>, // R1CS over C2.Fr=C1.Fq (here C=C2)
+        w1: &CycleFoldWitness<C>,
+        ci1: &CycleFoldCommittedInstance<C>,
+        w2: &CycleFoldWitness<C>,
+        ci2: &CycleFoldCommittedInstance<C>,
+        scratch: &mut ProverScratch<C::ScalarField>,
+    ) -> Result<C, Error> {
         // compute cross terms
-        let T = Self::compute_T(r1cs, ci1.u, ci2.u, &z1, &z2, &w1.E, &w2.E)?;
-        // use r_T=0 since we don't need hiding property for cm(T)
-        let cmT = CS::commit(cs_prover_params, &T, &C::ScalarField::zero())?;
-        Ok((T, cmT))
+        Self::compute_T_into(r1cs, w1, ci1, w2, ci2, scratch)?;
+        dense_vec_to_sparse_into(&scratch.T, &mut scratch.T_sparse);
+        CS::commit_sparse(
+            cs_prover_params,
+            &scratch.T_sparse,
+            scratch.T.len(),
+            &Self::cross_terms_blinding(w1, w2),
+        )
     }
 
     /// folds two committed instances with the given r and cmT. This method is used by
@@ -261,6 +416,8 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         CommittedInstance { cmE, u, cmW, x }
     }
 
+    /// `rT` is the blinding factor of `cmT`, as returned by `cross_terms_blinding`.
+    #[allow(clippy::too_many_arguments)]
     pub fn prove_commitments(
         tr: &mut impl Transcript<C::ScalarField>,
         cs_prover_params: &CS::ProverParams,
@@ -268,10 +425,26 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         ci: &CommittedInstance<C>,
         T: Vec<C::ScalarField>,
         cmT: &C,
+        rT: &C::ScalarField,
+        mut rng: Option<&mut dyn RngCore>,
     ) -> Result<[CS::Proof; 3], Error> {
-        let cmE_proof = CS::prove(cs_prover_params, tr, &ci.cmE, &w.E, &w.rE, None)?;
-        let cmW_proof = CS::prove(cs_prover_params, tr, &ci.cmW, &w.W, &w.rW, None)?;
-        let cmT_proof = CS::prove(cs_prover_params, tr, cmT, &T, &C::ScalarField::zero(), None)?; // cm(T) is committed with rT=0
+        let cmE_proof = CS::prove(
+            cs_prover_params,
+            tr,
+            &ci.cmE,
+            &w.E,
+            &w.rE,
+            rng.as_mut().map(|rng| &mut **rng as &mut dyn RngCore),
+        )?;
+        let cmW_proof = CS::prove(
+            cs_prover_params,
+            tr,
+            &ci.cmW,
+            &w.W,
+            &w.rW,
+            rng.as_mut().map(|rng| &mut **rng as &mut dyn RngCore),
+        )?;
+        let cmT_proof = CS::prove(cs_prover_params, tr, cmT, &T, rT, rng)?;
         Ok([cmE_proof, cmW_proof, cmT_proof])
     }
 }
@@ -282,7 +455,12 @@ pub mod tests {
     use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
     use ark_pallas::{Fr, Projective};
 
-    use crate::arith::{r1cs::tests::get_test_r1cs, ArithRelation};
+    use ark_std::{cmp::max, UniformRand};
+
+    use crate::arith::{
+        r1cs::tests::{get_test_r1cs, get_test_z},
+        ArithRelation,
+    };
     use crate::commitment::pedersen::Pedersen;
     use crate::folding::nova::nifs::tests::test_nifs_opt;
 
@@ -295,4 +473,59 @@ pub mod tests {
         r1cs.check_relation(&W, &U)?;
         Ok(())
     }
+
+    /// folds reusing the same scratch buffers at each iteration, checking that the results match
+    /// the ones of `prove` (which uses fresh buffers), and that the buffers are not reallocated
+    /// after the first iteration
+    #[test]
+    fn test_prove_with_scratch() -> Result<(), Error> {
+        type N = NIFS<Projective, Pedersen<Projective>, PoseidonSponge<Fr>>;
+        let r1cs: R1CS<Fr> = get_test_r1cs();
+        let mut rng = ark_std::test_rng();
+        let (pedersen_params, _) =
+            Pedersen::<Projective>::setup(&mut rng, max(r1cs.n_constraints(), r1cs.n_witnesses()))?;
+        let mut transcript = PoseidonSponge::<Fr>::new(&poseidon_canonical_config::<Fr>());
+        let pp_hash = Fr::rand(&mut rng);
+
+        let (w, x) = r1cs.split_z(&get_test_z(3));
+        let mut W_i = N::new_witness(w, r1cs.n_constraints(), &mut rng);
+        let mut U_i = N::new_instance(&mut rng, &pedersen_params, &W_i, x, vec![])?;
+
+        let mut scratch = ProverScratch::default();
+        let mut T_ptr = None;
+        for i in 0..5 {
+            let (w, x) = r1cs.split_z(&get_test_z(i + 4));
+            let w_i = N::new_witness(w, r1cs.n_constraints(), &mut rng);
+            let u_i = N::new_instance(&mut rng, &pedersen_params, &w_i, x, vec![])?;
+
+            let expected = N::prove(
+                &pedersen_params,
+                &r1cs,
+                &mut transcript.clone(),
+                pp_hash,
+                &W_i,
+                &U_i,
+                &w_i,
+                &u_i,
+            )?;
+            let folded = N::prove_with_scratch(
+                &pedersen_params,
+                &r1cs,
+                &mut transcript,
+                pp_hash,
+                &W_i,
+                &U_i,
+                &w_i,
+                &u_i,
+                &mut scratch,
+            )?;
+            assert_eq!(folded, expected);
+            assert_eq!(*T_ptr.get_or_insert(scratch.T.as_ptr()), scratch.T.as_ptr());
+
+            W_i = folded.0;
+            U_i = folded.1;
+        }
+        r1cs.check_relation(&W_i, &U_i)?;
+        Ok(())
+    }
 }
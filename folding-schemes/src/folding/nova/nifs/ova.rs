@@ -16,7 +16,7 @@ use crate::commitment::CommitmentScheme;
 use crate::folding::traits::{CommittedInstanceOps, Inputize};
 use crate::folding::{circuits::CF1, traits::Dummy};
 use crate::transcript::Transcript;
-use crate::utils::vec::{hadamard, mat_vec_mul, vec_scalar_mul, vec_sub};
+use crate::utils::vec::{add_scaled, hadamard, mat_vec_mul, vec_scalar_mul, vec_sub};
 use crate::{Curve, Error};
 
 /// A CommittedInstance in [Ova](https://hackmd.io/V4838nnlRKal9ZiTHiGYzw) is represented by `W` or
@@ -31,6 +31,22 @@ pub struct CommittedInstance<C: Curve> {
     pub cmWE: C,
 }
 
+impl<C: Curve> Dummy<usize> for CommittedInstance<C> {
+    fn dummy(io_len: usize) -> Self {
+        Self {
+            u: CF1::<C>::zero(),
+            x: vec![CF1::<C>::zero(); io_len],
+            cmWE: C::zero(),
+        }
+    }
+}
+
+impl<C: Curve> Dummy<&R1CS<CF1<C>>> for CommittedInstance<C> {
+    fn dummy(r1cs: &R1CS<CF1<C>>) -> Self {
+        Self::dummy(r1cs.n_public_inputs())
+    }
+}
+
 impl<C: Curve> Absorb for CommittedInstance<C> {
     fn to_sponge_bytes(&self, dest: &mut Vec<u8>) {
         C::ScalarField::batch_to_sponge_bytes(&self.to_sponge_field_elements_as_vec(), dest);
@@ -155,12 +171,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         w_i: &Self::Witness,
         _aux: &Self::ProverAux,
     ) -> Result<Self::Witness, Error> {
-        let w: Vec<C::ScalarField> = W_i
-            .w
-            .iter()
-            .zip(&w_i.w)
-            .map(|(a, b)| *a + (r * b))
-            .collect();
+        let w = add_scaled(&W_i.w, &r, &w_i.w)?;
 
         let rW = W_i.rW + r * w_i.rW;
         Ok(Self::Witness { w, rW })
@@ -236,12 +247,9 @@ pub fn compute_E<C: Curve>(
     z: &[C::ScalarField],
     u: C::ScalarField,
 ) -> Result<Vec<C::ScalarField>, Error> {
-    let (A, B, C) = (r1cs.A.clone(), r1cs.B.clone(), r1cs.C.clone());
-
-    // this is parallelizable (for the future)
-    let Az = mat_vec_mul(&A, z)?;
-    let Bz = mat_vec_mul(&B, z)?;
-    let Cz = mat_vec_mul(&C, z)?;
+    let Az = mat_vec_mul(&r1cs.A, z)?;
+    let Bz = mat_vec_mul(&r1cs.B, z)?;
+    let Cz = mat_vec_mul(&r1cs.C, z)?;
 
     let Az_Bz = hadamard(&Az, &Bz)?;
     let uCz = vec_scalar_mul(&Cz, &u);
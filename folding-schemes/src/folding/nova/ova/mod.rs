@@ -0,0 +1,816 @@
+/// Implements the Nova+CycleFold IVC using the [Ova](https://hackmd.io/V4838nnlRKal9ZiTHiGYzw)
+/// NIFS instead of the original Nova NIFS.
+///
+/// In Ova the witness `w` and the error term `E` (or the cross term `T` for the incoming instances)
+/// are committed together in a single commitment `cmWE`. The cross term `T` of the next fold is
+/// known as soon as the incoming witness is computed, so it is committed together with it, which
+/// removes the separate commitment to `T` that Nova computes at each step, and halves the number
+/// of scalar multiplications (and thus of CycleFold instances) that the AugmentedFCircuit has to
+/// delegate to the CycleFold circuit.
+///
+/// The IVC follows the same `FoldingScheme` API as [`crate::folding::nova::Nova`], and reuses its
+/// `PreprocessorParam` and `ProverParams`. Its verifier parameters, [`OvaVerifierParams`], extend
+/// Nova's `VerifierParams` with the parameters needed to recompute the commitment of the running
+/// instance.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::ConstraintSystem;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use ark_std::{cmp::max, rand::RngCore, One, Zero};
+
+use super::{
+    get_r1cs_from_cs,
+    nifs::{
+        nova::NIFS as NovaNIFS,
+        ova::{compute_E, CommittedInstance, Witness, NIFS},
+        NIFSTrait,
+    },
+    PreprocessorParam, ProverParams, VerifierParams,
+};
+use crate::arith::{
+    r1cs::{extract_w_x, R1CS},
+    Arith, ArithRelation,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::circuits::cyclefold::{
+    fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldWitness,
+    GenericCycleFoldConfig,
+};
+use crate::folding::traits::{CommittedInstanceOps, Dummy};
+use crate::frontend::FCircuit;
+use crate::transcript::poseidon::deserialize_poseidon_config;
+use crate::{Curve, Error, FoldingScheme};
+
+pub mod circuits;
+
+use circuits::AugmentedFCircuit;
+
+/// Configuration for Ova's CycleFold circuit. Since `cmW` and `cmE` are committed together in
+/// `cmWE`, each CycleFold instance only checks a single random linear combination of 2 points.
+pub type OvaCycleFoldConfig<C> = GenericCycleFoldConfig<C, 2>;
+
+/// CycleFold circuit for computing random linear combinations of group elements in Ova instances.
+pub type OvaCycleFoldCircuit<C> = CycleFoldCircuit<OvaCycleFoldConfig<C>>;
+
+/// Verifier parameters of Ova, which are Nova's [`VerifierParams`] together with the prover
+/// parameters of `CS1`. Unlike Nova's error term `E`, which is part of the witness, Ova's error
+/// term is derived from the witness and only bound by the commitment `cmWE`, so checking the
+/// relation of the running instance requires recomputing this commitment.
+#[derive(Debug, Clone)]
+pub struct OvaVerifierParams<C1, C2, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub vp: VerifierParams<C1, C2, CS1, CS2, H>,
+    /// CommitmentScheme::ProverParams over C1, used to recompute `U_i.cmWE`
+    pub cs_pp: CS1::ProverParams,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> Valid for OvaVerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.vp.check()?;
+        self.cs_pp.check()
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for OvaVerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn serialize_with_mode<W: std::io::prelude::Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), ark_serialize::SerializationError> {
+        self.vp.serialize_with_mode(&mut writer, compress)?;
+        self.cs_pp.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.vp.serialized_size(compress) + self.cs_pp.serialized_size(compress)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> OvaVerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// returns the hash of the public parameters, see [`VerifierParams::pp_hash`]
+    pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
+        self.vp.pp_hash()
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1, C2>
+where
+    C1: Curve,
+    C2: Curve,
+{
+    // hash of the public parameters that the proof is bound to
+    pub pp_hash: C1::ScalarField,
+    // current step of the IVC
+    pub i: C1::ScalarField,
+    // initial state
+    pub z_0: Vec<C1::ScalarField>,
+    // current state
+    pub z_i: Vec<C1::ScalarField>,
+    // running instance
+    pub W_i: Witness<C1>,
+    pub U_i: CommittedInstance<C1>,
+    // incoming instance
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    // CycleFold instances
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+/// Implements Nova+CycleFold's IVC using the Ova NIFS, following the FoldingScheme trait.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding.
+///
+/// Notice that the commitment `CS1` is used to commit to the concatenation of the witness and
+/// the error (or cross) term, so its setup needs to support vectors of length
+/// `n_witnesses + n_constraints` of the AugmentedFCircuit's R1CS.
+#[derive(Clone, Debug)]
+pub struct Ova<C1, C2, FC, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// R1CS of the Augmented Function circuit
+    pub r1cs: R1CS<C1::ScalarField>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuit, the circuit that is being folded
+    pub F: FC,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+    pub i: C1::ScalarField,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// Ova instances
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    pub W_i: Witness<C1>,
+    pub U_i: CommittedInstance<C1>,
+
+    /// CycleFold running instance
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC> for Ova<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
+    type VerifierParam = OvaVerifierParams<C1, C2, CS1, CS2, H>;
+    type RunningInstance = (CommittedInstance<C1>, Witness<C1>);
+    type IncomingInstance = (CommittedInstance<C1>, Witness<C1>);
+    type MultiCommittedInstanceWithWitness = ();
+    type CFInstance = (CycleFoldCommittedInstance<C2>, CycleFoldWitness<C2>);
+    type IVCProof = IVCProof<C1, C2>;
+
+    fn pp_deserialize_with_mode<R: std::io::prelude::Read>(
+        reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        _fc_params: FC::Params, // FCircuit params
+    ) -> Result<Self::ProverParam, Error> {
+        Ok(Self::ProverParam::deserialize_with_mode(
+            reader, compress, validate,
+        )?)
+    }
+    fn vp_deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        fc_params: FC::Params,
+    ) -> Result<Self::VerifierParam, Error> {
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let poseidon_config = deserialize_poseidon_config(&mut reader, compress, validate)?;
+        let cs_pp = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        // generate the r1cs & cf_r1cs needed for the VerifierParams, as done in Nova
+        let f_circuit = FC::new(fc_params)?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&poseidon_config, f_circuit)?;
+
+        Ok(Self::VerifierParam {
+            vp: VerifierParams {
+                poseidon_config,
+                r1cs,
+                cf_r1cs,
+                cs_vp,
+                cf_cs_vp,
+            },
+            cs_pp,
+        })
+    }
+
+    fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &Self::PreprocessorParam,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC>(&prep_param.poseidon_config, prep_param.F.clone())?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            _ => CS1::setup(
+                &mut rng,
+                // `CS1` is for committing to the concatenation of Ova's witness vector `w` and
+                // error term `e` (or cross term `t`), whose length is the number of constraints.
+                r1cs.n_witnesses() + r1cs.n_constraints(),
+            )?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(
+                &mut rng,
+                // `CS2` is for committing to CycleFold's witness vector `w` and error term `e`,
+                // which are committed separately as in Nova.
+                max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
+            )?,
+        };
+
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp: cs_pp.clone(),
+            cf_cs_pp,
+            pp_hash: verifier_params.pp_hash()?,
+        };
+
+        Ok((
+            prover_params,
+            OvaVerifierParams {
+                vp: verifier_params,
+                cs_pp,
+            },
+        ))
+    }
+
+    /// Initializes the Ova+CycleFold's IVC for the given parameters and initial state `z_0`.
+    fn init(
+        params: &(Self::ProverParam, Self::VerifierParam),
+        F: FC,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&pp.poseidon_config, F.clone())?;
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+        if pp.pp_hash != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+
+        // setup the dummy instances
+        let W_dummy = Witness::<C1>::dummy(&r1cs);
+        let U_dummy = CommittedInstance::<C1>::dummy(&r1cs);
+        let (cf_W_dummy, cf_U_dummy) = cf_r1cs.dummy_witness_instance();
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.poseidon_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash,
+            i: C1::ScalarField::zero(),
+            z_0: z_0.clone(),
+            z_i: z_0,
+            w_i: W_dummy.clone(),
+            u_i: U_dummy.clone(),
+            W_i: W_dummy,
+            U_i: U_dummy,
+            // cyclefold running instance
+            cf_W_i: cf_W_dummy,
+            cf_U_i: cf_U_dummy,
+        })
+    }
+
+    /// Implements IVC.P of Ova+CycleFold
+    fn prove_step(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+        // Ova does not support multi-instances folding
+        _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+    ) -> Result<(), Error> {
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let augmented_F_circuit: AugmentedFCircuit<C1, C2, FC>;
+
+        if _other_instances.is_some() {
+            return Err(Error::NoMultiInstances);
+        }
+
+        if self.z_i.len() != self.F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                self.F.state_len(),
+            ));
+        }
+
+        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
+            return Err(Error::MaxStep);
+        }
+
+        let i_usize;
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let mut i_bytes: [u8; 8] = [0; 8];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        #[cfg(target_pointer_width = "32")]
+        {
+            let mut i_bytes: [u8; 4] = [0; 4];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..4]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        let hint = self
+            .F
+            .precompute_witness(i_usize, &self.z_i, &external_inputs)?;
+        let external_inputs = self.F.with_witness_hint(external_inputs, hint);
+
+        let (W_i1, U_i1) = if self.i == C1::ScalarField::zero() {
+            // base case: there is no incoming instance yet, the running instance U_1 is the dummy
+            // instance U_{\bot}, which is the one used by the circuit to compute u_1.x
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                poseidon_config: self.poseidon_config.clone(),
+                pp_hash: Some(self.pp_hash),
+                i: Some(C1::ScalarField::zero()), // = i=0
+                i_usize: Some(0),
+                z_0: Some(self.z_0.clone()), // = z_i
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs.clone()),
+                u_i_cmWE: Some(self.u_i.cmWE), // = dummy
+                U_i: Some(self.U_i.clone()),   // = dummy
+                U_i1_cmWE: Some(self.U_i.cmWE),
+                F: self.F.clone(),
+                cf_u_i_cmW: None,
+                cf_U_i: None,
+                cf_cmT: None,
+            };
+
+            (self.W_i.clone(), self.U_i.clone())
+        } else {
+            // fold Ova instances
+            let (W_i1, U_i1, _, r_bits) =
+                NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                    &self.cs_pp,
+                    &self.r1cs,
+                    &mut transcript,
+                    self.pp_hash,
+                    &self.W_i,
+                    &self.U_i,
+                    &self.w_i,
+                    &self.u_i,
+                )?;
+
+            // CycleFold part: a single CycleFold instance is needed, since cmW and cmE are
+            // folded together
+            let cf_circuit = OvaCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits),
+                points: Some(vec![self.U_i.cmWE, self.u_i.cmWE]),
+            };
+            let (cf_u_i, cf_W_i1, cf_U_i1, cf_cmT) =
+                fold_cyclefold_circuit::<OvaCycleFoldConfig<C1>, C2, CS2, H>(
+                    &mut transcript,
+                    &self.cf_r1cs,
+                    &self.cf_cs_pp,
+                    self.pp_hash,
+                    &self.cf_W_i,
+                    &self.cf_U_i,
+                    cf_circuit,
+                    &mut rng,
+                )?;
+
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                poseidon_config: self.poseidon_config.clone(),
+                pp_hash: Some(self.pp_hash),
+                i: Some(self.i),
+                i_usize: Some(i_usize),
+                z_0: Some(self.z_0.clone()),
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs.clone()),
+                u_i_cmWE: Some(self.u_i.cmWE),
+                U_i: Some(self.U_i.clone()),
+                U_i1_cmWE: Some(U_i1.cmWE),
+                F: self.F.clone(),
+                // cyclefold values
+                cf_u_i_cmW: Some(cf_u_i.cmW),
+                cf_U_i: Some(self.cf_U_i.clone()),
+                cf_cmT: Some(cf_cmT),
+            };
+
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+
+            (W_i1, U_i1)
+        };
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let z_i1 = augmented_F_circuit
+            .compute_next_state(cs.clone())?
+            .value()?;
+
+        // in debug builds, report which constraint is not satisfied, instead of producing a
+        // folded instance that will not pass the verification
+        #[cfg(any(test, debug_assertions))]
+        crate::arith::r1cs::check_cs_satisfied(&self.r1cs, &cs, i_usize)?;
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x_i1.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i1.len(), 2));
+        }
+
+        // compute the cross term T between the running instance U_{i+1} and the new incoming
+        // instance u_{i+1}, which in Ova is committed together with the incoming witness
+        let T = self.compute_T(&W_i1, &U_i1, &w_i1, &x_i1)?;
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.z_i = z_i1;
+        self.w_i = Witness::<C1>::new::<H>(w_i1, &mut rng);
+        self.u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1, T)?;
+        self.W_i = W_i1;
+        self.U_i = U_i1;
+
+        #[cfg(test)]
+        {
+            self.u_i.check_incoming()?;
+            self.r1cs.check_relation(&self.w_i.w, &self.u_i.x)?;
+            // check that U_i.cmWE opens to the running witness and its error term
+            let E = compute_E::<C1>(
+                &self.r1cs,
+                &[&[self.U_i.u][..], &self.U_i.x, &self.W_i.w].concat(),
+                self.U_i.u,
+            )?;
+            let expected = self
+                .W_i
+                .commit::<CS1, H>(&self.cs_pp, self.U_i.x.clone(), E)?;
+            assert_eq!(expected.cmWE, self.U_i.cmWE);
+        }
+
+        Ok(())
+    }
+
+    fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        Self::IVCProof {
+            pp_hash: self.pp_hash,
+            i: self.i,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    fn from_ivc_proof(
+        ivc_proof: IVCProof<C1, C2>,
+        fcircuit_params: FC::Params,
+        params: (Self::ProverParam, Self::VerifierParam),
+    ) -> Result<Self, Error> {
+        let IVCProof {
+            pp_hash,
+            i,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+        let (pp, vp) = params;
+        if pp_hash != vp.pp_hash()? {
+            return Err(Error::PPHashMismatch);
+        }
+
+        let f_circuit = FC::new(fcircuit_params)?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&pp.poseidon_config, f_circuit.clone())?;
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.poseidon_config,
+            cs_pp: pp.cs_pp,
+            cf_cs_pp: pp.cf_cs_pp,
+            F: f_circuit,
+            pp_hash,
+            i,
+            z_0,
+            z_i,
+            w_i,
+            u_i,
+            W_i,
+            U_i,
+            cf_W_i,
+            cf_U_i,
+        })
+    }
+
+    /// Implements IVC.V of Ova+CycleFold. The relation of the running instance is checked by
+    /// recomputing `U_i.cmWE` from `W_i` and the error term derived from it. As in Nova, the
+    /// commitment `u_i.cmWE` of the incoming instance is not checked, since it commits to the
+    /// cross term of the next fold.
+    fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
+        let OvaVerifierParams { vp, cs_pp } = vp;
+        let Self::IVCProof {
+            pp_hash: proof_pp_hash,
+            i: num_steps,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+
+        // check that the proof has been generated for the given public parameters
+        let pp_hash = vp.pp_hash()?;
+        if proof_pp_hash != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+
+        if num_steps == C1::ScalarField::zero() {
+            if z_0 != z_i {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+
+        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+        if W_i.w.len() != vp.r1cs.n_witnesses() {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check that u_i's output points to the running instance
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, &z_0, &z_i);
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability of the incoming instance
+        u_i.check_incoming()?;
+        vp.r1cs.check_relation(&w_i.w, &u_i.x)?;
+
+        // check the relation of the running instance. Its error term is not part of the witness
+        // but derived from it (see `compute_E`), so the relation holds if `U_i.cmWE` opens to
+        // the witness concatenated with the derived error term.
+        let E = compute_E::<C1>(&vp.r1cs, &[&[U_i.u][..], &U_i.x, &W_i.w].concat(), U_i.u)?;
+        if W_i.commit::<CS1, H>(&cs_pp, U_i.x.clone(), E)?.cmWE != U_i.cmWE {
+            return Err(Error::NotSatisfied);
+        }
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> Ova<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    // computes the cross term between the running instance (W, U) and the incoming witness `w`
+    // with public inputs `x`. Since the incoming instance is not relaxed (u=1 and E=0), this is
+    // the same cross term T as in Nova.
+    fn compute_T(
+        &self,
+        W: &Witness<C1>,
+        U: &CommittedInstance<C1>,
+        w: &[C1::ScalarField],
+        x: &[C1::ScalarField],
+    ) -> Result<Vec<C1::ScalarField>, Error> {
+        let z1: Vec<C1::ScalarField> = [&[U.u][..], &U.x, &W.w].concat();
+        let z2: Vec<C1::ScalarField> = [&[C1::ScalarField::one()][..], x, w].concat();
+        let E1 = compute_E::<C1>(&self.r1cs, &z1, U.u)?;
+        let E2 = vec![C1::ScalarField::zero(); self.r1cs.n_constraints()];
+        NovaNIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::compute_T(
+            &self.r1cs,
+            U.u,
+            C1::ScalarField::one(),
+            &z1,
+            &z2,
+            &E1,
+            &E2,
+        )
+    }
+}
+
+/// helper method to get the R1CS for both the Ova AugmentedFCircuit and the CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, C2, FC>(
+    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    F_circuit: FC,
+) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+{
+    let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(poseidon_config, F_circuit);
+    let cf_circuit = OvaCycleFoldCircuit::<C1>::empty();
+    let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::Nova;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    /// tests the Ova+CycleFold IVC, and by consequence the Ova AugmentedFCircuit
+    #[test]
+    fn test_ivc() -> Result<(), Error> {
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(3)?;
+        test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(3)?;
+        Ok(())
+    }
+
+    fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
+        num_steps: usize,
+    ) -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        type O<CS1, CS2, const H: bool> =
+            Ova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>;
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = O::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut ova = O::<CS1, CS2, H>::init(&params, F_circuit, z_0)?;
+        for _ in 0..num_steps {
+            ova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(Fr::from(num_steps as u32), ova.i);
+
+        // serialize and deserialize the VerifierParams & IVCProof
+        let mut vp_serialized = vec![];
+        params.1.serialize_compressed(&mut vp_serialized)?;
+        let vp = O::<CS1, CS2, H>::vp_deserialize_with_mode(
+            &mut vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            (),
+        )?;
+        let mut ivc_proof_serialized = vec![];
+        ova.ivc_proof()
+            .serialize_compressed(&mut ivc_proof_serialized)?;
+        let ivc_proof: IVCProof<Projective, Projective2> =
+            IVCProof::deserialize_compressed(ivc_proof_serialized.as_slice())?;
+
+        O::<CS1, CS2, H>::verify(vp.clone(), ivc_proof.clone())?;
+
+        // a running witness that does not open U_i.cmWE is rejected, even though U_i itself is
+        // still the one hashed in u_i.x
+        let mut bogus_proof = ivc_proof;
+        bogus_proof.W_i.w[0] += Fr::one();
+        assert!(matches!(
+            O::<CS1, CS2, H>::verify(vp, bogus_proof),
+            Err(Error::NotSatisfied)
+        ));
+        Ok(())
+    }
+
+    /// checks that Ova reaches the same state as Nova, with a smaller AugmentedFCircuit
+    #[test]
+    fn test_ova_vs_nova() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+        >;
+        type O = Ova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+        >;
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let ova_params = O::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0.clone())?;
+        let mut ova = O::init(&ova_params, F_circuit, z_0)?;
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, (), None)?;
+            ova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(nova.state(), ova.state());
+        assert!(ova.r1cs.n_constraints() < nova.r1cs.n_constraints());
+        Ok(())
+    }
+}
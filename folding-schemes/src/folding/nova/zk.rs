@@ -45,6 +45,7 @@ use crate::{
     commitment::CommitmentScheme,
     folding::traits::CommittedInstanceOps,
     frontend::FCircuit,
+    transcript::Transcript,
     Curve, Error,
 };
 
@@ -69,13 +70,31 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
         CS2: CommitmentScheme<C2, true>,
     >(
         nova: &Nova<C1, C2, FC, CS1, CS2, true>,
-        mut rng: impl RngCore,
+        rng: impl RngCore,
     ) -> Result<RandomizedIVCProof<C1, C2>, Error> {
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config);
+        let transcript = PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config);
+        Self::new_with_transcript(nova, transcript, rng)
+    }
 
+    /// Same as [`RandomizedIVCProof::new`], but with the given Fiat-Shamir transcript, which has to
+    /// be the one given to [`RandomizedIVCProof::verify_with_transcript`]. As this proof is only
+    /// verified natively, any [`Transcript`] can be used, e.g. a [`KeccakTranscript`] when it is
+    /// verified on the EVM.
+    ///
+    /// [`KeccakTranscript`]: crate::transcript::keccak::KeccakTranscript
+    pub fn new_with_transcript<
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        T: Transcript<C1::ScalarField>,
+    >(
+        nova: &Nova<C1, C2, FC, CS1, CS2, true>,
+        mut transcript: T,
+        mut rng: impl RngCore,
+    ) -> Result<RandomizedIVCProof<C1, C2>, Error> {
         // I. Compute proof for 'regular' instances
         // 1. Fold the instance-witness pairs (U_i, W_i) with (u_i, w_i)
-        let (W_f, U_f, cmT, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::prove(
+        let (W_f, U_f, cmT, _) = NIFS::<C1, CS1, T, true>::prove(
             &nova.cs_pp,
             &nova.r1cs,
             &mut transcript,
@@ -92,17 +111,16 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
             .sample_witness_instance::<CS1>(&nova.cs_pp, &mut rng)?;
 
         // 3. Fold the instance-witness pair (U_f, W_f) with (U_r, W_r)
-        let (W_i_prime, _, cmT_i_prime, _) =
-            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::prove(
-                &nova.cs_pp,
-                &nova.r1cs,
-                &mut transcript,
-                nova.pp_hash,
-                &W_f,
-                &U_f,
-                &W_r,
-                &U_r,
-            )?;
+        let (W_i_prime, _, cmT_i_prime, _) = NIFS::<C1, CS1, T, true>::prove(
+            &nova.cs_pp,
+            &nova.r1cs,
+            &mut transcript,
+            nova.pp_hash,
+            &W_f,
+            &U_f,
+            &W_r,
+            &U_r,
+        )?;
 
         Ok(RandomizedIVCProof {
             U_i: nova.U_i.clone(),
@@ -129,6 +147,43 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
         z_i: Vec<C1::ScalarField>,
         proof: &RandomizedIVCProof<C1, C2>,
     ) -> Result<(), Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        let transcript = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        Self::verify_with_transcript::<CS1, CS2, _>(
+            r1cs,
+            cf_r1cs,
+            pp_hash,
+            poseidon_config,
+            transcript,
+            i,
+            z_0,
+            z_i,
+            proof,
+        )
+    }
+
+    /// Same as [`RandomizedIVCProof::verify`], but with the given Fiat-Shamir transcript, which
+    /// has to be the one given to [`RandomizedIVCProof::new_with_transcript`]. The hashes of the
+    /// instances are still computed with Poseidon, as they are public inputs of the augmented
+    /// circuit.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_with_transcript<
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        T: Transcript<C1::ScalarField>,
+    >(
+        r1cs: &R1CS<C1::ScalarField>,
+        cf_r1cs: &R1CS<C2::ScalarField>,
+        pp_hash: C1::ScalarField,
+        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+        mut transcript: T,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        proof: &RandomizedIVCProof<C1, C2>,
+    ) -> Result<(), Error>
     where
         C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     {
@@ -164,9 +219,8 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
             return Err(Error::zkIVCVerificationFail);
         }
 
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
         // 3. Obtain the U_f folded instance
-        let (U_f, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::verify(
+        let (U_f, _) = NIFS::<C1, CS1, T, true>::verify(
             &mut transcript,
             pp_hash,
             &proof.u_i,
@@ -175,7 +229,7 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
         )?;
 
         // 4. Obtain the U^{\prime}_i folded instance
-        let (U_i_prime, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::verify(
+        let (U_i_prime, _) = NIFS::<C1, CS1, T, true>::verify(
             &mut transcript,
             pp_hash,
             &U_f,
@@ -199,7 +253,7 @@ pub mod tests {
     use crate::commitment::pedersen::Pedersen;
     use crate::folding::nova::tests::test_ivc_opt;
     use crate::frontend::utils::CubicFCircuit;
-    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::transcript::{keccak::KeccakTranscript, poseidon::poseidon_canonical_config};
     use ark_bn254::{Fr, G1Projective as Projective};
     use ark_grumpkin::Projective as Projective2;
     use rand::rngs::OsRng;
@@ -232,6 +286,57 @@ pub mod tests {
         Ok(())
     }
 
+    // Tests zk proof generation and verification for a valid nova IVC proof, with the keccak
+    // transcript selected for the zk layer
+    #[test]
+    fn test_zk_nova_ivc_keccak_transcript() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let (_, nova) = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >(poseidon_config.clone(), F_circuit, 3)?;
+
+        let proof = RandomizedIVCProof::new_with_transcript(
+            &nova,
+            KeccakTranscript::<Fr>::new(&()),
+            &mut rng,
+        )?;
+        let verify = RandomizedIVCProof::verify_with_transcript::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            _,
+        >(
+            &nova.r1cs,
+            &nova.cf_r1cs,
+            nova.pp_hash,
+            &nova.poseidon_config,
+            KeccakTranscript::<Fr>::new(&()),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &proof,
+        );
+        assert!(verify.is_ok());
+
+        // the proof does not verify with the default poseidon transcript
+        let verify =
+            RandomizedIVCProof::verify::<Pedersen<Projective, true>, Pedersen<Projective2, true>>(
+                &nova.r1cs,
+                &nova.cf_r1cs,
+                nova.pp_hash,
+                &nova.poseidon_config,
+                nova.i,
+                nova.z_0,
+                nova.z_i,
+                &proof,
+            );
+        assert!(verify.is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_zk_nova_when_i_is_zero() -> Result<(), Error> {
         let mut rng = OsRng;
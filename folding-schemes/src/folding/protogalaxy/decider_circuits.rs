@@ -0,0 +1,206 @@
+/// This file implements the ProtoGalaxy's offchain decider circuits. For ethereum use cases, use
+/// the DeciderEthCircuit.
+/// More details can be found at the documentation page:
+/// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-offchain.html
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::fields::fp::FpVar;
+use core::marker::PhantomData;
+
+use super::{
+    constants::{INCOMING, RUNNING},
+    decider_eth_circuit::DeciderProtoGalaxyGadget,
+    folding::Folding,
+    CommittedInstance, ProtoGalaxy, Witness,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{circuits::CF1, traits::WitnessOps};
+use crate::{
+    arith::r1cs::{circuits::R1CSMatricesVar, R1CS},
+    folding::circuits::decider::{
+        off_chain::{GenericOffchainDeciderCircuit1, GenericOffchainDeciderCircuit2},
+        EvalGadget, KZGChallengesGadget,
+    },
+};
+use crate::{frontend::FCircuit, transcript::poseidon::poseidon_custom_config};
+use crate::{Curve, Error};
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve2's BaseField (=Curve1's ScalarField).
+pub type DeciderCircuit1<C1, C2> = GenericOffchainDeciderCircuit1<
+    C1,
+    C2,
+    CommittedInstance<C1, RUNNING>,
+    CommittedInstance<C1, INCOMING>,
+    Witness<CF1<C1>>,
+    R1CS<CF1<C1>>,
+    R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+    DeciderProtoGalaxyGadget,
+>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1>,
+        CS2: CommitmentScheme<C2>,
+    > TryFrom<ProtoGalaxy<C1, C2, FC, CS1, CS2>> for DeciderCircuit1<C1, C2>
+{
+    type Error = Error;
+
+    fn try_from(protogalaxy: ProtoGalaxy<C1, C2, FC, CS1, CS2>) -> Result<Self, Error> {
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&protogalaxy.poseidon_config);
+
+        // compute the U_{i+1}, W_{i+1}
+        let (U_i1, W_i1, proof, aux) = Folding::prove(
+            &mut transcript,
+            &protogalaxy.r1cs,
+            &protogalaxy.U_i,
+            &protogalaxy.W_i,
+            &[protogalaxy.u_i.clone()],
+            &[protogalaxy.w_i.clone()],
+        )?;
+
+        // compute the KZG challenges used as inputs in the circuit
+        let kzg_challenges = KZGChallengesGadget::get_challenges_native(&mut transcript, &U_i1);
+
+        // get KZG evals
+        let kzg_evaluations = W_i1
+            .get_openings()
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            _avar: PhantomData,
+            arith: protogalaxy.r1cs,
+            poseidon_config: protogalaxy.poseidon_config,
+            pp_hash: protogalaxy.pp_hash,
+            i: protogalaxy.i,
+            z_0: protogalaxy.z_0,
+            z_i: protogalaxy.z_i,
+            U_i: protogalaxy.U_i,
+            W_i: protogalaxy.W_i,
+            u_i: protogalaxy.u_i,
+            w_i: protogalaxy.w_i,
+            U_i1,
+            W_i1,
+            proof,
+            randomness: aux.L_X_evals,
+            cf_U_i: protogalaxy.cf_U_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve1's BaseField (=Curve2's ScalarField).
+pub type DeciderCircuit2<C2> = GenericOffchainDeciderCircuit2<C2>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1>,
+        CS2: CommitmentScheme<C2>,
+    > TryFrom<ProtoGalaxy<C1, C2, FC, CS1, CS2>> for DeciderCircuit2<C2>
+{
+    type Error = Error;
+
+    fn try_from(protogalaxy: ProtoGalaxy<C1, C2, FC, CS1, CS2>) -> Result<Self, Error> {
+        // Create a poseidon config on `C2`'s scalar field for `circuit2`, with
+        // the same parameters (`full_rounds` etc.) as `circuit1` to ensure the
+        // security level is the same.
+        // Note: `ark` and `mds` will be different because they depend on the
+        // field, but they will not affect the security level.
+        let poseidon_config = poseidon_custom_config(
+            protogalaxy.poseidon_config.full_rounds,
+            protogalaxy.poseidon_config.partial_rounds,
+            protogalaxy.poseidon_config.alpha,
+            protogalaxy.poseidon_config.rate,
+            protogalaxy.poseidon_config.capacity,
+        );
+        let mut transcript = PoseidonSponge::<C2::ScalarField>::new(&poseidon_config);
+        let pp_hash_Fq = C2::ScalarField::from_le_bytes_mod_order(
+            &protogalaxy.pp_hash.into_bigint().to_bytes_le(),
+        );
+        transcript.absorb(&pp_hash_Fq);
+
+        // compute the KZG challenges used as inputs in the circuit
+        let kzg_challenges =
+            KZGChallengesGadget::get_challenges_native(&mut transcript, &protogalaxy.cf_U_i);
+
+        // get KZG evals
+        let kzg_evaluations = protogalaxy
+            .cf_W_i
+            .get_openings()
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            cf_arith: protogalaxy.cf_r1cs,
+            poseidon_config,
+            pp_hash: pp_hash_Fq,
+            cf_U_i: protogalaxy.cf_U_i,
+            cf_W_i: protogalaxy.cf_W_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{Fq, Fr, Projective};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_vesta::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_decider_circuits() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type PG = ProtoGalaxy<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+        >;
+        let pg_params = PG::preprocess(&mut rng, &(poseidon_config, F_circuit))?;
+
+        // generate a ProtoGalaxy instance and do a step of it
+        let mut protogalaxy = PG::init(&pg_params, F_circuit, z_0.clone())?;
+        protogalaxy.prove_step(&mut rng, (), None)?;
+        // verify the IVC
+        let ivc_proof = protogalaxy.ivc_proof();
+        PG::verify(pg_params.1, ivc_proof)?;
+
+        // load the DeciderCircuit 1 & 2 from the ProtoGalaxy instance
+        let decider_circuit1 =
+            DeciderCircuit1::<Projective, Projective2>::try_from(protogalaxy.clone())?;
+        let decider_circuit2 = DeciderCircuit2::<Projective2>::try_from(protogalaxy)?;
+
+        // generate the constraints of both circuits and check that are satisfied by the inputs
+        let cs1 = ConstraintSystem::<Fr>::new_ref();
+        decider_circuit1.generate_constraints(cs1.clone())?;
+        assert!(cs1.is_satisfied()?);
+        let cs2 = ConstraintSystem::<Fq>::new_ref();
+        decider_circuit2.generate_constraints(cs2.clone())?;
+        assert!(cs2.is_satisfied()?);
+        Ok(())
+    }
+}
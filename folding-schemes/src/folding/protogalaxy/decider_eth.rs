@@ -8,7 +8,7 @@ use ark_std::{
     log2,
     marker::PhantomData,
     rand::{CryptoRng, RngCore},
-    One, Zero,
+    One,
 };
 
 pub use super::decider_eth_circuit::DeciderEthCircuit;
@@ -161,15 +161,12 @@ where
         let kzg_challenges = circuit.kzg_challenges.clone();
 
         // generate KZG proofs
-        let kzg_proofs = circuit
-            .W_i1
-            .get_openings()
-            .iter()
-            .zip(&kzg_challenges)
-            .map(|((v, _), &c)| {
-                CS1::prove_with_challenge(&cs_pk, c, v, &C1::ScalarField::zero(), None)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let kzg_proofs = CS1::batch_prove_with_challenges(
+            &cs_pk,
+            &kzg_challenges,
+            &circuit.W_i1.get_openings(),
+            Some(&mut rng),
+        )?;
 
         let snark_proof =
             S::prove(&snark_pk, circuit, &mut rng).map_err(|e| Error::Other(e.to_string()))?;
@@ -239,15 +236,14 @@ where
             return Err(Error::SNARKVerificationFail);
         }
 
-        // 7.3. Verify the KZG proofs
-        for ((cm, &c), pi) in U_final_commitments
-            .iter()
-            .zip(&proof.kzg_challenges)
-            .zip(&proof.kzg_proofs)
-        {
-            // we're at the Ethereum EVM case, so the CS1 is KZG commitments
-            CS1::verify_with_challenge(&cs_vp, c, cm, pi)?;
-        }
+        // 7.3. Verify the KZG proofs. We're at the Ethereum EVM case, so the CS1 is KZG
+        // commitments, whose proofs are batched into a single pairing check
+        CS1::batch_verify_with_challenges(
+            &cs_vp,
+            &proof.kzg_challenges,
+            &U_final_commitments,
+            &proof.kzg_proofs,
+        )?;
 
         Ok(true)
     }
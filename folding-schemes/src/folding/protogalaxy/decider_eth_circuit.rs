@@ -124,6 +124,7 @@ impl<
             i: protogalaxy.i,
             z_0: protogalaxy.z_0,
             z_i: protogalaxy.z_i,
+            hash_state: false,
             U_i: protogalaxy.U_i,
             W_i: protogalaxy.W_i,
             u_i: protogalaxy.u_i,
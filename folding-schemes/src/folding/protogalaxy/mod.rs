@@ -43,6 +43,8 @@ use crate::{
 
 pub mod circuits;
 pub mod constants;
+pub mod decider;
+pub mod decider_circuits;
 pub mod decider_eth;
 pub mod decider_eth_circuit;
 pub mod folding;
@@ -433,17 +435,25 @@ where
     CS1: CommitmentScheme<C1>,
     CS2: CommitmentScheme<C2>,
 {
+    /// Serializes the VerifierParams together with its `pp_hash`, which is checked against the
+    /// one recomputed at deserialization, binding the serialized params to the FCircuit.
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
         mut writer: W,
         compress: ark_serialize::Compress,
     ) -> Result<(), ark_serialize::SerializationError> {
+        let pp_hash = self
+            .pp_hash()
+            .map_err(|_| ark_serialize::SerializationError::InvalidData)?;
         self.cs_vp.serialize_with_mode(&mut writer, compress)?;
-        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)
+        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)?;
+        pp_hash.serialize_with_mode(&mut writer, compress)
     }
 
     fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
-        self.cs_vp.serialized_size(compress) + self.cf_cs_vp.serialized_size(compress)
+        self.cs_vp.serialized_size(compress)
+            + self.cf_cs_vp.serialized_size(compress)
+            + C1::ScalarField::zero().serialized_size(compress)
     }
 }
 
@@ -470,6 +480,8 @@ where
 
 #[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct IVCProof<C1: Curve, C2: Curve> {
+    /// hash of the public parameters that the proof is bound to
+    pub pp_hash: C1::ScalarField,
     pub i: C1::ScalarField,
     pub z_0: Vec<C1::ScalarField>,
     pub z_i: Vec<C1::ScalarField>,
@@ -661,6 +673,10 @@ where
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let pp_hash = C1::ScalarField::deserialize_with_mode(&mut reader, compress, validate)?;
+
         let f_circuit = FC::new(fc_params)?;
         let k = 1;
         let d = R1CS::<CF1<C1>>::empty().degree();
@@ -685,16 +701,18 @@ where
         let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
         let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
 
-        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
-        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
-
-        Ok(Self::VerifierParam {
+        let vp = Self::VerifierParam {
             poseidon_config,
             r1cs,
             cf_r1cs,
             cs_vp,
             cf_cs_vp,
-        })
+        };
+        // the serialized pp_hash binds the commitment schemes' params to the circuits
+        if vp.pp_hash()? != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+        Ok(vp)
     }
 
     fn preprocess(
@@ -832,6 +850,10 @@ where
 
         let i_bn: BigUint = self.i.into();
         let i_usize: usize = i_bn.try_into().map_err(|_| Error::MaxStep)?;
+        let hint = self
+            .F
+            .precompute_witness(i_usize, &self.z_i, &external_inputs)?;
+        let external_inputs = self.F.with_witness_hint(external_inputs, hint);
 
         if self.i.is_zero() {
             augmented_F_circuit = AugmentedFCircuit::empty(
@@ -885,16 +907,16 @@ where
             // fold self.cf_U_i + cf1_U -> folded running with cf1
             let (cf1_u_i, cf1_W_i1, cf1_U_i1, cf1_cmT) = self.fold_cyclefold_circuit(
                 &mut transcript_prover,
-                self.cf_W_i.clone(), // CycleFold running instance witness
-                self.cf_U_i.clone(), // CycleFold running instance
+                &self.cf_W_i, // CycleFold running instance witness
+                &self.cf_U_i, // CycleFold running instance
                 cf1_circuit,
                 &mut rng,
             )?;
             // fold [the output from folding self.cf_U_i + cf1_U] + cf2_U = folded_running_with_cf1 + cf2
             let (cf2_u_i, cf_W_i1, cf_U_i1, cf2_cmT) = self.fold_cyclefold_circuit(
                 &mut transcript_prover,
-                cf1_W_i1,
-                cf1_U_i1.clone(),
+                &cf1_W_i1,
+                &cf1_U_i1,
                 cf2_circuit,
                 &mut rng,
             )?;
@@ -945,7 +967,8 @@ where
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
 
         let z_i1 = augmented_F_circuit
-            .compute_next_state(cs.clone())?
+            .compute_next_state(cs.clone())
+            .map_err(|e| self.F.take_step_error().unwrap_or(e.into()))?
             .value()?;
 
         #[cfg(test)]
@@ -981,6 +1004,7 @@ where
 
     fn ivc_proof(&self) -> Self::IVCProof {
         Self::IVCProof {
+            pp_hash: self.pp_hash,
             i: self.i,
             z_0: self.z_0.clone(),
             z_i: self.z_i.clone(),
@@ -999,6 +1023,7 @@ where
         params: (Self::ProverParam, Self::VerifierParam),
     ) -> Result<Self, Error> {
         let IVCProof {
+            pp_hash,
             i,
             z_0,
             z_i,
@@ -1010,6 +1035,9 @@ where
             cf_U_i,
         } = ivc_proof;
         let (pp, vp) = params;
+        if pp_hash != vp.pp_hash()? {
+            return Err(Error::PPHashMismatch);
+        }
 
         let f_circuit = FC::new(fcircuit_params)?;
 
@@ -1020,7 +1048,7 @@ where
             cs_params: pp.cs_params,
             cf_cs_params: pp.cf_cs_params,
             F: f_circuit,
-            pp_hash: vp.pp_hash()?,
+            pp_hash,
             i,
             z_0,
             z_i,
@@ -1036,6 +1064,7 @@ where
     /// Implements IVC.V of ProtoGalaxy+CycleFold
     fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
         let Self::IVCProof {
+            pp_hash,
             i: num_steps,
             z_0,
             z_i,
@@ -1053,7 +1082,9 @@ where
             return Err(Error::IVCVerificationFail);
         }
 
-        let pp_hash = vp.pp_hash()?;
+        if pp_hash != vp.pp_hash()? {
+            return Err(Error::PPHashMismatch);
+        }
 
         // check that u_i's output points to the running instance
         // u_i.X[0] == H(i, z_0, z_i, U_i)
@@ -1094,8 +1125,8 @@ where
     fn fold_cyclefold_circuit(
         &self,
         transcript: &mut PoseidonSponge<C1::ScalarField>,
-        cf_W_i: CycleFoldWitness<C2>, // witness of the running instance
-        cf_U_i: CycleFoldCommittedInstance<C2>, // running instance
+        cf_W_i: &CycleFoldWitness<C2>, // witness of the running instance
+        cf_U_i: &CycleFoldCommittedInstance<C2>, // running instance
         cf_circuit: ProtoGalaxyCycleFoldCircuit<C1>,
         rng: &mut impl RngCore,
     ) -> Result<
@@ -1109,8 +1140,8 @@ where
     > {
         fold_cyclefold_circuit::<ProtoGalaxyCycleFoldConfig<C1>, C2, CS2, false>(
             transcript,
-            self.cf_r1cs.clone(),
-            self.cf_cs_params.clone(),
+            &self.cf_r1cs,
+            &self.cf_cs_params,
             self.pp_hash,
             cf_W_i,
             cf_U_i,
@@ -1131,9 +1162,10 @@ mod tests {
 
     use crate::{
         commitment::{kzg::KZG, pedersen::Pedersen},
-        frontend::utils::CubicFCircuit,
+        frontend::utils::{CubicFCircuit, CustomFCircuit},
         transcript::poseidon::poseidon_canonical_config,
     };
+    use ark_serialize::{Compress, Validate};
 
     /// This test tests the ProtoGalaxy+CycleFold IVC, and by consequence it is
     /// also testing the AugmentedFCircuit
@@ -1177,6 +1209,75 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_serialization_resume() -> Result<(), Error> {
+        type PG = ProtoGalaxy<
+            Projective,
+            Projective2,
+            CustomFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+        >;
+        let n_constraints = 10;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CustomFCircuit::<Fr>::new(n_constraints)?;
+
+        let params = PG::preprocess(&mut test_rng(), &(poseidon_config, F_circuit))?;
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut protogalaxy = PG::init(&params, F_circuit, z_0.clone())?;
+        for _ in 0..5 {
+            protogalaxy.prove_step(&mut test_rng(), (), None)?;
+        }
+
+        let mut pp_bytes = vec![];
+        params.0.serialize_compressed(&mut pp_bytes)?;
+        let mut vp_bytes = vec![];
+        params.1.serialize_compressed(&mut vp_bytes)?;
+        let mut proof_bytes = vec![];
+        protogalaxy
+            .ivc_proof()
+            .serialize_compressed(&mut proof_bytes)?;
+
+        // resume the folding from the deserialized params and IVCProof
+        let pp =
+            PG::pp_deserialize_with_mode(&*pp_bytes, Compress::Yes, Validate::Yes, n_constraints)?;
+        let vp =
+            PG::vp_deserialize_with_mode(&*vp_bytes, Compress::Yes, Validate::Yes, n_constraints)?;
+        let ivc_proof = IVCProof::<Projective, Projective2>::deserialize_compressed(&*proof_bytes)?;
+        assert_eq!(ivc_proof, protogalaxy.ivc_proof());
+        let mut resumed = PG::from_ivc_proof(ivc_proof.clone(), n_constraints, (pp, vp.clone()))?;
+        for _ in 0..5 {
+            protogalaxy.prove_step(&mut test_rng(), (), None)?;
+            resumed.prove_step(&mut test_rng(), (), None)?;
+        }
+        assert_eq!(Fr::from(10_u32), resumed.i);
+        assert_eq!(protogalaxy.state(), resumed.state());
+        PG::verify(vp.clone(), resumed.ivc_proof())?;
+
+        // the verifier params are bound to the FCircuit they were generated for
+        assert!(matches!(
+            PG::vp_deserialize_with_mode(
+                &*vp_bytes,
+                Compress::Yes,
+                Validate::Yes,
+                2 * n_constraints,
+            ),
+            Err(Error::PPHashMismatch)
+        ));
+        // and the IVCProof to the params
+        let mut wrong_proof = ivc_proof;
+        wrong_proof.pp_hash += Fr::one();
+        assert!(matches!(
+            PG::verify(vp.clone(), wrong_proof.clone()),
+            Err(Error::PPHashMismatch)
+        ));
+        assert!(matches!(
+            PG::from_ivc_proof(wrong_proof, n_constraints, (params.0, vp)),
+            Err(Error::PPHashMismatch)
+        ));
+        Ok(())
+    }
+
     #[ignore]
     #[test]
     fn test_t_bounds() -> Result<(), Error> {
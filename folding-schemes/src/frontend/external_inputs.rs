@@ -0,0 +1,283 @@
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::marker::PhantomData;
+
+use super::multistep::{MultiStepInputs, MultiStepInputsVar};
+use super::FCircuit;
+use crate::Error;
+
+/// Converts the native external inputs of an FCircuit into field elements, so that they can be
+/// absorbed by the [`ExternalInputsCommitted`] running hash.
+pub trait ExternalInputsToFields<F: PrimeField> {
+    fn to_field_elements(&self) -> Vec<F>;
+}
+
+/// In-circuit counterpart of [`ExternalInputsToFields`], which must return the same elements (in
+/// the same order) as the native implementation.
+pub trait ExternalInputsVarToFields<F: PrimeField> {
+    fn to_field_element_vars(&self) -> Result<Vec<FpVar<F>>, SynthesisError>;
+}
+
+impl<F: PrimeField> ExternalInputsToFields<F> for () {
+    fn to_field_elements(&self) -> Vec<F> {
+        vec![]
+    }
+}
+impl<F: PrimeField> ExternalInputsVarToFields<F> for () {
+    fn to_field_element_vars(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(vec![])
+    }
+}
+
+impl<F: PrimeField, const N: usize> ExternalInputsToFields<F> for [F; N] {
+    fn to_field_elements(&self) -> Vec<F> {
+        self.to_vec()
+    }
+}
+impl<F: PrimeField, const N: usize> ExternalInputsVarToFields<F> for [FpVar<F>; N] {
+    fn to_field_element_vars(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(self.to_vec())
+    }
+}
+
+impl<F: PrimeField> ExternalInputsToFields<F> for Vec<F> {
+    fn to_field_elements(&self) -> Vec<F> {
+        self.clone()
+    }
+}
+impl<F: PrimeField> ExternalInputsVarToFields<F> for Vec<FpVar<F>> {
+    fn to_field_element_vars(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(self.clone())
+    }
+}
+
+impl<F: PrimeField, I: ExternalInputsToFields<F>, const K: usize> ExternalInputsToFields<F>
+    for MultiStepInputs<I, K>
+{
+    fn to_field_elements(&self) -> Vec<F> {
+        self.0.iter().flat_map(|i| i.to_field_elements()).collect()
+    }
+}
+impl<F: PrimeField, V: ExternalInputsVarToFields<F>, const K: usize> ExternalInputsVarToFields<F>
+    for MultiStepInputsVar<V, K>
+{
+    fn to_field_element_vars(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(self
+            .0
+            .iter()
+            .map(|v| v.to_field_element_vars())
+            .collect::<Result<Vec<_>, _>>()?
+            .concat())
+    }
+}
+
+/// ExternalInputsCommitted wraps an FCircuit `FC` so that the proof binds the sequence of
+/// external inputs used at each step.
+///
+/// The state of the wrapped circuit is the state of `FC` with an extra element appended at the
+/// end, which contains a running Poseidon hash of the external inputs:
+/// `h_{i+1} = H(h_i, external_inputs_i)`, where `h_0` is the last element of `z_0` (usually `0`).
+/// Since the state is part of the statement verified by the folding scheme, the last element of
+/// the `z_i` of the IVCProof is the digest of all the external inputs used so far, which the
+/// verifier can compare against the expected one computed with
+/// [`ExternalInputsCommitted::digest`].
+///
+/// The hash uses the Poseidon parameters given in the `Params` of the circuit, together with the
+/// `Params` of `FC`, both natively in [`ExternalInputsCommitted::digest`] and in-circuit. When `FC`
+/// precomputes a witness hint, the hashed external inputs are the ones completed with the hint.
+#[derive(Clone, Debug)]
+pub struct ExternalInputsCommitted<F: PrimeField, FC: FCircuit<F>> {
+    pub FC: FC,
+    poseidon_config: PoseidonConfig<F>,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField, FC: FCircuit<F>> ExternalInputsCommitted<F, FC>
+where
+    FC::ExternalInputs: ExternalInputsToFields<F>,
+{
+    /// computes natively the running hash of the given sequence of external inputs, starting
+    /// from `h_0`, with the same Poseidon parameters used by the circuit.
+    pub fn digest(&self, h_0: F, external_inputs: &[FC::ExternalInputs]) -> F {
+        external_inputs.iter().fold(h_0, |h, inputs| {
+            let mut sponge = PoseidonSponge::<F>::new(&self.poseidon_config);
+            sponge.absorb(&[vec![h], inputs.to_field_elements()].concat());
+            sponge.squeeze_field_elements(1)[0]
+        })
+    }
+
+    /// returns the running hash of the external inputs contained in the state `z_i`.
+    pub fn digest_from_state(z_i: &[F]) -> Result<F, Error> {
+        z_i.last().copied().ok_or(Error::Empty)
+    }
+}
+
+impl<F: PrimeField, FC: FCircuit<F>> FCircuit<F> for ExternalInputsCommitted<F, FC>
+where
+    FC::ExternalInputs: ExternalInputsToFields<F>,
+    FC::ExternalInputsVar: ExternalInputsVarToFields<F>,
+{
+    type Params = (PoseidonConfig<F>, FC::Params);
+    type ExternalInputs = FC::ExternalInputs;
+    type ExternalInputsVar = FC::ExternalInputsVar;
+    type WitnessHint = FC::WitnessHint;
+
+    fn new((poseidon_config, params): Self::Params) -> Result<Self, Error> {
+        Ok(Self {
+            FC: FC::new(params)?,
+            poseidon_config,
+            _f: PhantomData,
+        })
+    }
+    fn state_len(&self) -> usize {
+        self.FC.state_len() + 1
+    }
+    fn precompute_witness(
+        &self,
+        i: usize,
+        z_i: &[F],
+        external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        // the state of `FC` is `z_i` without the running hash
+        let z_i = z_i.split_last().ok_or(Error::Empty)?.1;
+        self.FC.precompute_witness(i, z_i, external_inputs)
+    }
+    fn with_witness_hint(
+        &self,
+        external_inputs: Self::ExternalInputs,
+        hint: Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        self.FC.with_witness_hint(external_inputs, hint)
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        i: usize,
+        mut z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        // `unwrap` is safe since the state always contains at least the running hash
+        let h_i = z_i.pop().unwrap();
+
+        let mut sponge = PoseidonSpongeVar::<F>::new(cs.clone(), &self.poseidon_config);
+        sponge.absorb(&[vec![h_i], external_inputs.to_field_element_vars()?].concat())?;
+        // `unwrap` is safe because the sponge is guaranteed to return a single element
+        let h_i1 = sponge.squeeze_field_elements(1)?.pop().unwrap();
+
+        let mut z_i1 = self
+            .FC
+            .generate_step_constraints(cs, i, z_i, external_inputs)?;
+        z_i1.push(h_i1);
+        Ok(z_i1)
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.FC.take_step_error()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_std::{UniformRand, Zero};
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::transcript::poseidon::{poseidon_canonical_config, poseidon_config_with_width};
+    use crate::FoldingScheme;
+
+    /// test circuit that computes z_{i+1} = z_i + external_inputs[0] * external_inputs[1]
+    #[derive(Clone, Copy, Debug)]
+    struct MulAddFCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for MulAddFCircuit<F> {
+        type Params = ();
+        type ExternalInputs = [F; 2];
+        type ExternalInputsVar = [FpVar<F>; 2];
+        type WitnessHint = ();
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            _i: usize,
+            z_i: Vec<FpVar<F>>,
+            external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            Ok(vec![&z_i[0] + &external_inputs[0] * &external_inputs[1]])
+        }
+    }
+
+    type FC = ExternalInputsCommitted<Fr, MulAddFCircuit<Fr>>;
+
+    /// folds some steps of the wrapped circuit hashing with `poseidon_config`, and returns the
+    /// circuit and the external inputs used
+    fn check_external_inputs_committed(
+        poseidon_config: PoseidonConfig<Fr>,
+    ) -> Result<(FC, Vec<[Fr; 2]>), Error> {
+        let mut rng = ark_std::test_rng();
+
+        type N = Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>>;
+
+        let F_circuit = FC::new((poseidon_config, ()))?;
+        assert_eq!(F_circuit.state_len(), 2);
+        // the transcript of Nova is independent of the config used by the wrapper
+        let prep_param =
+            PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+
+        // z_0 = [inner state, h_0]
+        let z_0 = vec![Fr::zero(), Fr::zero()];
+        let mut nova = N::init(&params, F_circuit.clone(), z_0)?;
+
+        let inputs: Vec<[Fr; 2]> = (0..3)
+            .map(|_| [Fr::rand(&mut rng), Fr::rand(&mut rng)])
+            .collect();
+        for step_inputs in inputs.iter() {
+            nova.prove_step(&mut rng, *step_inputs, None)?;
+        }
+        let ivc_proof = nova.ivc_proof();
+        N::verify(params.1, ivc_proof.clone())?;
+
+        // the inner state is not affected by the wrapper
+        let expected_z: Fr = inputs.iter().map(|i| i[0] * i[1]).sum();
+        assert_eq!(ivc_proof.z_i[0], expected_z);
+
+        // the last element of the state is the digest of the used external inputs
+        let digest = FC::digest_from_state(&ivc_proof.z_i)?;
+        assert_eq!(digest, F_circuit.digest(Fr::zero(), &inputs));
+
+        // a different sequence of external inputs (with the same inner output) does not match
+        let mut swapped = inputs.clone();
+        swapped.swap(0, 1);
+        assert_ne!(digest, F_circuit.digest(Fr::zero(), &swapped));
+        Ok((F_circuit, inputs))
+    }
+
+    #[test]
+    fn test_external_inputs_committed() -> Result<(), Error> {
+        let (canonical, inputs) =
+            check_external_inputs_committed(poseidon_canonical_config::<Fr>())?;
+        // the circuit and the native digest use the given config instead of the canonical one
+        let (narrow, _) = check_external_inputs_committed(poseidon_config_with_width::<Fr, 3>())?;
+        assert_ne!(
+            canonical.digest(Fr::zero(), &inputs),
+            narrow.digest(Fr::zero(), &inputs)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+//! Merkle trees whose inner nodes are the Poseidon hashes of their two children, with the
+//! inclusion proofs of their leaves as R1CS constraints, and the [`MerkleInclusionFCircuit`]
+//! folding the verification of one inclusion proof per step.
+
+use ark_crypto_primitives::sponge::{poseidon::PoseidonConfig, Absorb};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    select::CondSelectGadget,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::borrow::Borrow;
+
+use super::FCircuit;
+use crate::transcript::poseidon::{poseidon_hash, poseidon_hash_gadget};
+use crate::Error;
+
+/// Inclusion proof of a leaf in a Merkle tree of depth `D`: the leaf, the siblings of the nodes
+/// of its path from the leaves to the root, and the bits of its index, least significant first,
+/// where a set bit means that the node of the path is a right child.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof<F: PrimeField, const D: usize> {
+    pub leaf: F,
+    pub siblings: [F; D],
+    pub path: [bool; D],
+}
+
+impl<F: PrimeField, const D: usize> Default for MerkleProof<F, D> {
+    fn default() -> Self {
+        Self {
+            leaf: F::zero(),
+            siblings: [F::zero(); D],
+            path: [false; D],
+        }
+    }
+}
+
+impl<F: PrimeField + Absorb, const D: usize> MerkleProof<F, D> {
+    /// Computes the root of the tree from the proof, natively.
+    pub fn root(&self, poseidon_config: &PoseidonConfig<F>) -> Result<F, Error> {
+        self.siblings
+            .iter()
+            .zip(self.path)
+            .try_fold(self.leaf, |node, (&sibling, is_right)| {
+                let children = if is_right {
+                    [sibling, node]
+                } else {
+                    [node, sibling]
+                };
+                poseidon_hash(poseidon_config, &children)
+            })
+    }
+}
+
+/// In-circuit counterpart of [`MerkleProof`].
+#[derive(Clone, Debug)]
+pub struct MerkleProofVar<F: PrimeField, const D: usize> {
+    pub leaf: FpVar<F>,
+    pub siblings: [FpVar<F>; D],
+    pub path: [Boolean<F>; D],
+}
+
+impl<F: PrimeField, const D: usize> AllocVar<MerkleProof<F, D>, F> for MerkleProofVar<F, D> {
+    fn new_variable<T: Borrow<MerkleProof<F, D>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let val = val.borrow();
+            let leaf = FpVar::new_variable(cs.clone(), || Ok(val.leaf), mode)?;
+            let siblings =
+                Vec::<FpVar<F>>::new_variable(cs.clone(), || Ok(val.siblings.to_vec()), mode)?;
+            let path = Vec::<Boolean<F>>::new_variable(cs.clone(), || Ok(val.path.to_vec()), mode)?;
+            Ok(Self {
+                leaf,
+                siblings: core::array::from_fn(|j| siblings[j].clone()),
+                path: core::array::from_fn(|j| path[j].clone()),
+            })
+        })
+    }
+}
+
+/// Native Merkle tree of depth `depth`, whose leaves are field elements and whose inner nodes are
+/// the Poseidon hashes of their two children, used to build the inputs of the
+/// [`MerkleInclusionFCircuit`].
+#[derive(Clone, Debug)]
+pub struct MerkleTree<F: PrimeField> {
+    /// the layers of the tree, from the leaves to the root
+    layers: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField + Absorb> MerkleTree<F> {
+    /// Builds the tree of the given leaves, padded with zeros to the `2^depth` leaves of the tree.
+    pub fn new(
+        poseidon_config: &PoseidonConfig<F>,
+        depth: usize,
+        mut leaves: Vec<F>,
+    ) -> Result<Self, Error> {
+        if leaves.len() > 1 << depth {
+            return Err(Error::NotExpectedLength(leaves.len(), 1 << depth));
+        }
+        leaves.resize(1 << depth, F::zero());
+        let mut layers = vec![leaves];
+        for _ in 0..depth {
+            // `unwrap` is safe since `layers` contains at least the leaves
+            let layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|children| poseidon_hash(poseidon_config, children))
+                .collect::<Result<Vec<_>, _>>()?;
+            layers.push(layer);
+        }
+        Ok(Self { layers })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    pub fn root(&self) -> F {
+        self.layers[self.depth()][0]
+    }
+
+    /// Returns the inclusion proof of the leaf at `index`, where `D` must be the depth of the tree.
+    pub fn proof<const D: usize>(&self, index: usize) -> Result<MerkleProof<F, D>, Error> {
+        if D != self.depth() {
+            return Err(Error::NotExpectedLength(D, self.depth()));
+        }
+        if index >= self.layers[0].len() {
+            return Err(Error::OutOfBounds);
+        }
+        Ok(MerkleProof {
+            leaf: self.layers[0][index],
+            siblings: core::array::from_fn(|j| self.layers[j][(index >> j) ^ 1]),
+            path: core::array::from_fn(|j| (index >> j) & 1 == 1),
+        })
+    }
+}
+
+/// MerkleInclusionFCircuit folds the verification of the inclusion proofs of the leaves of a
+/// Merkle tree of depth `D` (see [`MerkleTree`]), one leaf per step, against a fixed root.
+/// State: [root, counter], where the counter is the index of the leaf of the step
+/// External inputs: the [`MerkleProof`] of the leaf
+///
+/// The step recomputes the root from the leaf and its siblings, enforces that it is the root of
+/// the state, and that the path is the one of the leaf at index `counter`, so that folding the
+/// steps `0..n` proves the inclusion of the first `n` leaves of the tree, each at its position.
+#[derive(Clone, Debug)]
+pub struct MerkleInclusionFCircuit<F: PrimeField, const D: usize> {
+    poseidon_config: PoseidonConfig<F>,
+}
+
+impl<F: PrimeField, const D: usize> MerkleInclusionFCircuit<F, D> {
+    /// Initial state of the circuit for the tree of the given root.
+    pub fn initial_state(&self, root: F) -> Vec<F> {
+        vec![root, F::zero()]
+    }
+}
+
+impl<F: PrimeField + Absorb, const D: usize> FCircuit<F> for MerkleInclusionFCircuit<F, D> {
+    /// the Poseidon configuration of the hashes of the tree
+    type Params = PoseidonConfig<F>;
+    type ExternalInputs = MerkleProof<F, D>;
+    type ExternalInputsVar = MerkleProofVar<F, D>;
+    type WitnessHint = ();
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        // the index of the leaves must fit in a field element
+        if D >= F::MODULUS_BIT_SIZE as usize {
+            return Err(Error::OutOfBounds);
+        }
+        Ok(Self {
+            poseidon_config: params,
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        2
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut node = external_inputs.leaf;
+        for (sibling, is_right) in external_inputs.siblings.iter().zip(&external_inputs.path) {
+            let left = FpVar::conditionally_select(is_right, sibling, &node)?;
+            let right = FpVar::conditionally_select(is_right, &node, sibling)?;
+            node = poseidon_hash_gadget(cs.clone(), &self.poseidon_config, &[left, right])?;
+        }
+        node.enforce_equal(&z_i[0])?;
+        Boolean::le_bits_to_fp(&external_inputs.path)?.enforce_equal(&z_i[1])?;
+
+        Ok(vec![z_i[0].clone(), &z_i[1] + FpVar::one()])
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_ff::UniformRand;
+    use ark_grumpkin::Projective as Projective2;
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::frontend::utils::{simulate_steps, SimulatedSteps};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_merkle_tree() -> Result<(), Error> {
+        let config = poseidon_canonical_config::<Fr>();
+        let leaves: Vec<Fr> = (0..5u32).map(Fr::from).collect();
+        let tree = MerkleTree::new(&config, 3, leaves.clone())?;
+        assert_eq!(tree.depth(), 3);
+
+        // the root of a tree of depth 1 is the hash of its two leaves
+        let small_tree = MerkleTree::new(&config, 1, leaves[..2].to_vec())?;
+        assert_eq!(small_tree.root(), poseidon_hash(&config, &leaves[..2])?);
+
+        for index in 0..8 {
+            let proof = tree.proof::<3>(index)?;
+            assert_eq!(proof.leaf, leaves.get(index).copied().unwrap_or_default());
+            assert_eq!(proof.root(&config)?, tree.root());
+        }
+        assert!(matches!(tree.proof::<3>(8), Err(Error::OutOfBounds)));
+        assert!(tree.proof::<2>(0).is_err());
+        assert!(MerkleTree::new(&config, 2, leaves).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merkle_inclusion_f_circuit() -> Result<(), Error> {
+        const D: usize = 4;
+        let mut rng = ark_std::test_rng();
+        let config = poseidon_canonical_config::<Fr>();
+        let leaves: Vec<Fr> = (0..1 << D).map(|_| Fr::rand(&mut rng)).collect();
+        let tree = MerkleTree::new(&config, D, leaves)?;
+        let proofs = (0..1 << D)
+            .map(|index| tree.proof::<D>(index))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let circuit = MerkleInclusionFCircuit::<Fr, D>::new(config)?;
+        let z_0 = circuit.initial_state(tree.root());
+        let states = simulate_steps(&circuit, z_0.clone(), proofs.clone())?.into_states()?;
+        assert_eq!(states[1 << D], vec![tree.root(), Fr::from(1u32 << D)]);
+
+        // a wrong sibling, a wrong leaf, and the proof of another leaf than the one of the
+        // counter are rejected
+        let mut wrong_sibling = proofs[0].clone();
+        wrong_sibling.siblings[2] += Fr::from(1u32);
+        assert!(simulate_steps(&circuit, z_0.clone(), [wrong_sibling])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        let mut wrong_leaf = proofs[0].clone();
+        wrong_leaf.leaf = Fr::rand(&mut rng);
+        assert!(simulate_steps(&circuit, z_0.clone(), [wrong_leaf])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        assert!(simulate_steps(&circuit, z_0, [proofs[1].clone()])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+
+        // and so is a valid proof against another root
+        let other_z_0 = circuit.initial_state(Fr::rand(&mut rng));
+        assert!(simulate_steps(&circuit, other_z_0, [proofs[0].clone()])
+            .and_then(SimulatedSteps::into_states)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merkle_inclusion_folding() -> Result<(), Error> {
+        const D: usize = 3;
+        type FC = MerkleInclusionFCircuit<Fr, D>;
+        type N =
+            Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+        let mut rng = ark_std::test_rng();
+        let config = poseidon_canonical_config::<Fr>();
+        let leaves: Vec<Fr> = (0..6).map(|_| Fr::rand(&mut rng)).collect();
+        let tree = MerkleTree::new(&config, D, leaves)?;
+
+        let circuit = FC::new(config.clone())?;
+        let prep_param = PreprocessorParam::new(config, circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = circuit.initial_state(tree.root());
+        let mut nova = N::init(&params, circuit, z_0)?;
+        for index in 0..6 {
+            nova.prove_step(&mut rng, tree.proof::<D>(index)?, None)?;
+        }
+        N::verify(params.1, nova.ivc_proof())?;
+        assert_eq!(nova.state(), vec![tree.root(), Fr::from(6u32)]);
+        Ok(())
+    }
+}
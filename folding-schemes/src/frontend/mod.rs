@@ -4,8 +4,16 @@ use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 use ark_std::fmt::Debug;
 
+pub mod external_inputs;
+pub mod merkle;
+pub mod multistep;
+pub mod sequential;
+pub mod step_constants;
+pub mod synthesizer;
 pub mod utils;
 
+pub use folding_schemes_macros::fcircuit;
+
 /// FCircuit defines the trait of the circuit of the F function, which is the one being folded (ie.
 /// inside the agmented F' function).
 /// The parameter z_i denotes the current state, and z_{i+1} denotes the next state after applying
@@ -18,6 +26,10 @@ pub trait FCircuit<F: PrimeField>: Clone + Debug {
     type Params: Debug;
     type ExternalInputs: Clone + Default + Debug;
     type ExternalInputsVar: Clone + Debug + AllocVar<Self::ExternalInputs, F>;
+    /// parts of the witness of a step computed natively by [`FCircuit::precompute_witness`], `()`
+    /// for the FCircuits that do not need them. The combinators wrapping several steps clone the
+    /// hint of a step to compute the state passed to the next one.
+    type WitnessHint: Clone + Default;
 
     /// returns a new FCircuit instance
     fn new(params: Self::Params) -> Result<Self, Error>;
@@ -36,15 +48,63 @@ pub trait FCircuit<F: PrimeField>: Clone + Debug {
         z_i: Vec<FpVar<F>>,
         external_inputs: Self::ExternalInputsVar, // inputs that are not part of the state
     ) -> Result<Vec<FpVar<F>>, SynthesisError>;
+
+    /// computes natively, before the synthesis of the step `i`, the parts of its witness that are
+    /// expensive or awkward to compute inside the closures of `generate_step_constraints` (eg. by
+    /// calling an external service). The folding schemes call it at each step, and hand the hint
+    /// to `generate_step_constraints` through [`FCircuit::with_witness_hint`]. Notice that, as
+    /// any other witness, the precomputed values must be constrained by the circuit.
+    ///
+    /// With the `pipelining` feature, `Nova::prove_step_pipelined` runs it in another thread
+    /// while folding the previous step.
+    fn precompute_witness(
+        &self,
+        _i: usize,
+        _z_i: &[F],
+        _external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        Ok(Self::WitnessHint::default())
+    }
+
+    /// returns the external inputs of a step completed with its witness hint, which
+    /// `generate_step_constraints` then receives allocated as witnesses, so the `ExternalInputs`
+    /// of the circuit have to hold the precomputed values (their `Default` holding placeholders
+    /// for them). The FCircuits with a `WitnessHint` other than `()` have to implement it, since
+    /// by default the hint is dropped.
+    fn with_witness_hint(
+        &self,
+        external_inputs: Self::ExternalInputs,
+        _hint: Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        external_inputs
+    }
+
+    /// returns (and clears) the error that made the last call to `generate_step_constraints`
+    /// fail, for the FCircuits that can describe it more precisely than with a
+    /// [`SynthesisError`] (eg. the message of a failed assertion of a frontend's circuit). The
+    /// folding schemes return it instead of the [`SynthesisError`] when proving a step.
+    fn take_step_error(&self) -> Option<Error> {
+        None
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use ark_bn254::Fr;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::{eq::EqGadget, fields::FieldVar};
     use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::marker::PhantomData;
 
-    use utils::{custom_step_native, CubicFCircuit, CustomFCircuit, WrapperCircuit};
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+    use utils::{
+        cubic_step_native, custom_step_native, simulate_steps, CubicFCircuit, CustomFCircuit,
+        WrapperCircuit,
+    };
 
     #[test]
     fn test_testfcircuit() -> Result<(), Error> {
@@ -76,4 +136,98 @@ pub mod tests {
         assert_eq!(cs.num_constraints(), n_constraints);
         Ok(())
     }
+
+    #[test]
+    fn test_simulate_steps() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let simulated = simulate_steps(&F_circuit, z_0.clone(), vec![(); 4])?;
+        assert!(simulated.all_satisfied());
+        let states = simulated.into_states()?;
+        assert_eq!(states.len(), 5);
+        for (z_i, z_i1) in states.iter().zip(&states[1..]) {
+            assert_eq!(z_i1, &cubic_step_native(z_i.clone()));
+        }
+
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit);
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, F_circuit, z_0)?;
+        for z_i1 in &states[1..] {
+            nova.prove_step(&mut rng, (), None)?;
+            assert_eq!(&nova.state(), z_i1);
+        }
+        Ok(())
+    }
+
+    /// CubicFCircuit whose constraints are not satisfied when `z_i = 35`
+    #[derive(Clone, Copy, Debug)]
+    struct Not35FCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for Not35FCircuit<F> {
+        type Params = ();
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+        type WitnessHint = ();
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            cs: ConstraintSystemRef<F>,
+            i: usize,
+            z_i: Vec<FpVar<F>>,
+            external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            z_i[0].enforce_not_equal(&FpVar::constant(F::from(35_u32)))?;
+            CubicFCircuit::<F>::new(())
+                .map_err(|_| SynthesisError::Unsatisfiable)?
+                .generate_step_constraints(cs, i, z_i, external_inputs)
+        }
+    }
+
+    #[test]
+    fn test_simulate_steps_unsatisfied() -> Result<(), Error> {
+        let F_circuit = Not35FCircuit::<Fr>::new(())?;
+        // z_1 = 3^3 + 3 + 5 = 35, so only the step 1 is not satisfied, and the simulation goes on
+        // with the state it computed
+        let simulated = simulate_steps(&F_circuit, vec![Fr::from(3_u32)], vec![(); 3])?;
+        assert!(!simulated.all_satisfied());
+        assert!(matches!(
+            simulated.satisfied[..],
+            [
+                Ok(()),
+                Err(Error::UnsatisfiedConstraint { step: 1, .. }),
+                Ok(())
+            ]
+        ));
+        let z_1 = cubic_step_native(vec![Fr::from(3_u32)]);
+        assert_eq!(
+            simulated.states[..3],
+            [vec![Fr::from(3_u32)], z_1.clone(), cubic_step_native(z_1)]
+        );
+        assert!(matches!(
+            simulated.into_states(),
+            Err(Error::UnsatisfiedConstraint { step: 1, .. })
+        ));
+        assert!(matches!(
+            simulate_steps(&F_circuit, vec![Fr::from(3_u32); 2], vec![(); 3]),
+            Err(Error::NotSameLength(_, 2, _, 1))
+        ));
+        Ok(())
+    }
 }
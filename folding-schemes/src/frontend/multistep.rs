@@ -0,0 +1,305 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    fields::fp::FpVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, marker::PhantomData, mem::size_of};
+
+use super::{utils::synthesize_step, FCircuit};
+use crate::Error;
+
+/// External inputs of a [`MultiStepFCircuit`], containing the external inputs of each one of the
+/// `K` steps of the inner circuit that are computed in a single fold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiStepInputs<I, const K: usize>(pub Vec<I>);
+
+impl<I: Clone + Default, const K: usize> Default for MultiStepInputs<I, K> {
+    fn default() -> Self {
+        Self(vec![I::default(); K])
+    }
+}
+
+impl<I, const K: usize> From<[I; K]> for MultiStepInputs<I, K> {
+    fn from(inputs: [I; K]) -> Self {
+        Self(inputs.into())
+    }
+}
+
+/// In-circuit representation of [`MultiStepInputs`].
+#[derive(Clone, Debug)]
+pub struct MultiStepInputsVar<V, const K: usize>(pub Vec<V>);
+
+impl<F: PrimeField, I, V: AllocVar<I, F>, const K: usize> AllocVar<MultiStepInputs<I, K>, F>
+    for MultiStepInputsVar<V, K>
+{
+    fn new_variable<T: Borrow<MultiStepInputs<I, K>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let inputs = &val.borrow().0;
+            if inputs.len() != K {
+                return Err(SynthesisError::Unsatisfiable);
+            }
+            let v = Vec::<V>::new_variable(cs, || Ok(&inputs[..]), mode)?;
+            Ok(Self(v))
+        })
+    }
+}
+
+/// MultiStepFCircuit wraps an FCircuit `FC` so that each call to its `generate_step_constraints`
+/// computes `K` consecutive steps of `FC`, chaining the state through the `K` copies of the
+/// circuit and consuming one external input of `FC` per step.
+///
+/// This allows to amortize the overhead of the folding verifier (and CycleFold) of the
+/// AugmentedFCircuit over `K` steps of a small step circuit, since the folding scheme only folds
+/// once every `K` steps. Notice that the step counter `i` of the folding scheme (and of its
+/// IVCProof) counts the number of folds, so after `i` folds the state `z_i` is the result of
+/// applying `i * K` steps of `FC` to `z_0` (see [`MultiStepFCircuit::num_inner_steps`]). The inner
+/// circuit receives `i * K + j` as its step index for the `j`-th copy.
+///
+/// The witness hint of a fold is the vector of the hints of the `K` inner steps. Since each inner
+/// step precomputes its hint from the state computed by the previous one, the inner steps but the
+/// last one are synthesized natively when precomputing the hints, unless the hint of `FC` holds no
+/// data (eg. `()`).
+#[derive(Clone, Debug)]
+pub struct MultiStepFCircuit<F: PrimeField, FC: FCircuit<F>, const K: usize> {
+    pub FC: FC,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField, FC: FCircuit<F>, const K: usize> MultiStepFCircuit<F, FC, K> {
+    /// number of steps of the inner circuit that are computed at each fold
+    pub const STEPS_PER_FOLD: usize = K;
+
+    /// returns the number of steps of the inner circuit that have been applied after `num_folds`
+    /// folds of the folding scheme.
+    pub fn num_inner_steps(num_folds: usize) -> usize {
+        num_folds * K
+    }
+}
+
+impl<F: PrimeField, FC: FCircuit<F>, const K: usize> FCircuit<F> for MultiStepFCircuit<F, FC, K> {
+    type Params = FC::Params;
+    type ExternalInputs = MultiStepInputs<FC::ExternalInputs, K>;
+    type ExternalInputsVar = MultiStepInputsVar<FC::ExternalInputsVar, K>;
+    type WitnessHint = Vec<FC::WitnessHint>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        if K == 0 {
+            return Err(Error::NotExpectedLength(K, 1));
+        }
+        Ok(Self {
+            FC: FC::new(params)?,
+            _f: PhantomData,
+        })
+    }
+    fn state_len(&self) -> usize {
+        self.FC.state_len()
+    }
+    fn precompute_witness(
+        &self,
+        i: usize,
+        z_i: &[F],
+        external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        // a hint without data does not depend on the intermediate states, so they are not computed
+        if size_of::<FC::WitnessHint>() == 0 {
+            return Ok(Vec::new());
+        }
+        let mut z = z_i.to_vec();
+        let mut hints = Vec::with_capacity(K);
+        for (j, inputs) in external_inputs.0.iter().enumerate() {
+            let hint = self.FC.precompute_witness(i * K + j, &z, inputs)?;
+            if j + 1 < external_inputs.0.len() {
+                let inputs = self.FC.with_witness_hint(inputs.clone(), hint.clone());
+                z = synthesize_step(&self.FC, i * K + j, z, inputs)?;
+            }
+            hints.push(hint);
+        }
+        Ok(hints)
+    }
+    fn with_witness_hint(
+        &self,
+        external_inputs: Self::ExternalInputs,
+        hint: Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        // the inner steps without a hint (all of them for the default, empty, hint) keep their
+        // external inputs
+        let mut hints = hint.into_iter();
+        MultiStepInputs(
+            external_inputs
+                .0
+                .into_iter()
+                .map(|inputs| match hints.next() {
+                    Some(hint) => self.FC.with_witness_hint(inputs, hint),
+                    None => inputs,
+                })
+                .collect(),
+        )
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        if external_inputs.0.len() != K {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        external_inputs
+            .0
+            .into_iter()
+            .enumerate()
+            .try_fold(z_i, |z, (j, inputs)| {
+                self.FC
+                    .generate_step_constraints(cs.clone(), i * K + j, z, inputs)
+            })
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.FC.take_step_error()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::frontend::utils::{cubic_step_native, CubicFCircuit, CubicHintFCircuit};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    /// test circuit that computes z_{i+1} = z_i + external_input + i, so that both the chaining of
+    /// the external inputs and of the step index can be checked
+    #[derive(Clone, Copy, Debug)]
+    struct AddFCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for AddFCircuit<F> {
+        type Params = ();
+        type ExternalInputs = [F; 1];
+        type ExternalInputsVar = [FpVar<F>; 1];
+        type WitnessHint = ();
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            i: usize,
+            z_i: Vec<FpVar<F>>,
+            external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            Ok(vec![
+                &z_i[0] + &external_inputs[0] + FpVar::Constant(F::from(i as u64)),
+            ])
+        }
+    }
+
+    #[test]
+    fn test_multistep_external_inputs() -> Result<(), Error> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let circuit = MultiStepFCircuit::<Fr, AddFCircuit<Fr>, 4>::new(())?;
+
+        let inputs: MultiStepInputs<[Fr; 1], 4> =
+            [[Fr::from(1)], [Fr::from(2)], [Fr::from(3)], [Fr::from(4)]].into();
+        let inputsVar = MultiStepInputsVar::new_witness(cs.clone(), || Ok(inputs))?;
+        let z_i = vec![FpVar::new_witness(cs.clone(), || Ok(Fr::from(10)))?];
+
+        // second fold (i=1), so the inner steps are 4, 5, 6, 7
+        let z_i1 = circuit.generate_step_constraints(cs.clone(), 1, z_i, inputsVar)?;
+        assert_eq!(
+            z_i1[0].value()?,
+            Fr::from(10 + (1 + 2 + 3 + 4) + (4 + 5 + 6 + 7))
+        );
+        assert!(cs.is_satisfied()?);
+
+        // wrong amount of external inputs
+        let wrong_inputs = MultiStepInputs::<[Fr; 1], 4>(vec![[Fr::from(1)]; 3]);
+        assert!(
+            MultiStepInputsVar::<[FpVar<Fr>; 1], 4>::new_witness(cs.clone(), || Ok(wrong_inputs))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    /// checks that folding once a MultiStepFCircuit with K=4 results in the same state as folding
+    /// 4 times the inner circuit
+    #[test]
+    fn test_multistep_ivc() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type N<FC> = Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>>;
+        type FC1 = CubicFCircuit<Fr>;
+        type FC4 = MultiStepFCircuit<Fr, CubicFCircuit<Fr>, 4>;
+
+        let F1 = FC1::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), F1);
+        let params = N::<FC1>::preprocess(&mut rng, &prep_param)?;
+        let mut nova1 = N::<FC1>::init(&params, F1, z_0.clone())?;
+        for _ in 0..4 {
+            nova1.prove_step(&mut rng, (), None)?;
+        }
+
+        let F4 = FC4::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_config, F4.clone());
+        let params = N::<FC4>::preprocess(&mut rng, &prep_param)?;
+        let mut nova4 = N::<FC4>::init(&params, F4, z_0)?;
+        nova4.prove_step(&mut rng, MultiStepInputs::default(), None)?;
+
+        assert_eq!(nova1.state(), nova4.state());
+        assert_eq!(FC4::num_inner_steps(1), 4);
+        N::<FC4>::verify(params.1, nova4.ivc_proof())?;
+        Ok(())
+    }
+
+    /// checks that the witness hints of the inner steps are precomputed from their own states and
+    /// handed to them, by folding a circuit that takes `z_i^3` from its hint
+    #[test]
+    fn test_multistep_witness_hint() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        type FC = MultiStepFCircuit<Fr, CubicHintFCircuit<Fr>, 3>;
+        type N = Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>>;
+
+        let f_circuit = FC::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+        let hints = f_circuit.precompute_witness(0, &z_0, &MultiStepInputs::default())?;
+        let z_1 = cubic_step_native(z_0.clone());
+        let z_2 = cubic_step_native(z_1.clone());
+        assert_eq!(hints, [z_0[0], z_1[0], z_2[0]].map(|z| z * z * z).to_vec());
+
+        let prep_param =
+            PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+        // the hints are not given by the caller, but precomputed at each fold
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, MultiStepInputs::default(), None)?;
+        }
+        N::verify(params.1, nova.ivc_proof())?;
+
+        let mut z = z_0;
+        for _ in 0..2 * 3 {
+            z = cubic_step_native(z);
+        }
+        assert_eq!(nova.state(), z);
+        Ok(())
+    }
+}
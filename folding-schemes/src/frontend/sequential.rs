@@ -0,0 +1,245 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    fields::fp::FpVar,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, marker::PhantomData, mem::size_of};
+
+use super::{utils::synthesize_step, FCircuit};
+use crate::Error;
+
+/// In-circuit representation of the external inputs `(A::ExternalInputs, B::ExternalInputs)` of
+/// a [`SequentialFCircuit`].
+#[derive(Clone, Debug)]
+pub struct SequentialInputsVar<VA, VB>(pub VA, pub VB);
+
+impl<F: PrimeField, IA, IB, VA: AllocVar<IA, F>, VB: AllocVar<IB, F>> AllocVar<(IA, IB), F>
+    for SequentialInputsVar<VA, VB>
+{
+    fn new_variable<T: Borrow<(IA, IB)>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into().cs();
+            let (a, b) = val.borrow();
+            Ok(Self(
+                VA::new_variable(cs.clone(), || Ok(a), mode)?,
+                VB::new_variable(cs, || Ok(b), mode)?,
+            ))
+        })
+    }
+}
+
+/// SequentialFCircuit composes two FCircuits `A` and `B` into a single step, which applies the
+/// step of `A` to the state `z_i` and then the step of `B` to its output, so that both circuits
+/// are folded at once without merging their gadgets by hand. Both circuits receive the same step
+/// index, and the external inputs of the composed step are the pair of the external inputs of `A`
+/// and `B`.
+///
+/// The state of `A` is passed as is to `B`, so both circuits must have the same state length,
+/// which is checked by [`FCircuit::new`].
+///
+/// The witness hint of the composed step is the pair of the hints of `A` and `B`. Since `B`
+/// precomputes its hint from the state computed by `A`, the step of `A` is synthesized natively
+/// when precomputing the hints, unless the hint of `B` holds no data (eg. `()`).
+#[derive(Clone, Debug)]
+pub struct SequentialFCircuit<F: PrimeField, A: FCircuit<F>, B: FCircuit<F>> {
+    pub A: A,
+    pub B: B,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField, A: FCircuit<F>, B: FCircuit<F>> FCircuit<F> for SequentialFCircuit<F, A, B> {
+    type Params = (A::Params, B::Params);
+    type ExternalInputs = (A::ExternalInputs, B::ExternalInputs);
+    type ExternalInputsVar = SequentialInputsVar<A::ExternalInputsVar, B::ExternalInputsVar>;
+    type WitnessHint = (A::WitnessHint, B::WitnessHint);
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        let (a_params, b_params) = params;
+        let (A, B) = (A::new(a_params)?, B::new(b_params)?);
+        if A.state_len() != B.state_len() {
+            return Err(Error::NotSameLength(
+                "A.state_len()".to_string(),
+                A.state_len(),
+                "B.state_len()".to_string(),
+                B.state_len(),
+            ));
+        }
+        Ok(Self {
+            A,
+            B,
+            _f: PhantomData,
+        })
+    }
+    fn state_len(&self) -> usize {
+        self.A.state_len()
+    }
+    fn precompute_witness(
+        &self,
+        i: usize,
+        z_i: &[F],
+        external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        let (a_inputs, b_inputs) = external_inputs;
+        let a_hint = self.A.precompute_witness(i, z_i, a_inputs)?;
+        // a hint without data does not depend on the state, so the one of `A` is not computed
+        if size_of::<B::WitnessHint>() == 0 {
+            return Ok((a_hint, B::WitnessHint::default()));
+        }
+        let a_inputs = self.A.with_witness_hint(a_inputs.clone(), a_hint.clone());
+        let z_a = synthesize_step(&self.A, i, z_i.to_vec(), a_inputs)?;
+        let b_hint = self.B.precompute_witness(i, &z_a, b_inputs)?;
+        Ok((a_hint, b_hint))
+    }
+    fn with_witness_hint(
+        &self,
+        (a_inputs, b_inputs): Self::ExternalInputs,
+        (a_hint, b_hint): Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        (
+            self.A.with_witness_hint(a_inputs, a_hint),
+            self.B.with_witness_hint(b_inputs, b_hint),
+        )
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let SequentialInputsVar(a_inputs, b_inputs) = external_inputs;
+        let z_a = self
+            .A
+            .generate_step_constraints(cs.clone(), i, z_i, a_inputs)?;
+        if z_a.len() != self.B.state_len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        self.B.generate_step_constraints(cs, i, z_a, b_inputs)
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.A
+            .take_step_error()
+            .or_else(|| self.B.take_step_error())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::frontend::utils::{
+        cubic_step_native, multi_inputs_step_native, CubicFCircuit, CubicHintFCircuit,
+        MultiInputsFCircuit,
+    };
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    type N<FC> =
+        Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+    /// folds `n_steps` steps of `f_circuit` from `z_0`, returning the final state
+    fn fold<FC: FCircuit<Fr>>(
+        f_circuit: FC,
+        z_0: Vec<Fr>,
+        n_steps: usize,
+    ) -> Result<Vec<Fr>, Error> {
+        let mut rng = ark_std::test_rng();
+        let prep_param =
+            PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+        let params = N::<FC>::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::<FC>::init(&params, f_circuit, z_0)?;
+        for _ in 0..n_steps {
+            nova.prove_step(&mut rng, FC::ExternalInputs::default(), None)?;
+        }
+        N::<FC>::verify(params.1, nova.ivc_proof())?;
+        Ok(nova.state())
+    }
+
+    #[test]
+    fn test_sequential_cubic() -> Result<(), Error> {
+        type FC = SequentialFCircuit<Fr, CubicFCircuit<Fr>, CubicFCircuit<Fr>>;
+        let f_circuit = FC::new(((), ()))?;
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(3_u32)]))?;
+        let external_inputs =
+            SequentialInputsVar::<(), ()>::new_witness(cs.clone(), || Ok(((), ())))?;
+        let z_i1 = f_circuit.generate_step_constraints(cs.clone(), 0, z_i, external_inputs)?;
+        assert_eq!(
+            z_i1.value()?,
+            cubic_step_native(cubic_step_native(vec![Fr::from(3_u32)]))
+        );
+        assert!(cs.is_satisfied()?);
+
+        let mut z = vec![Fr::from(3_u32)];
+        for _ in 0..3 {
+            z = cubic_step_native(cubic_step_native(z));
+        }
+        assert_eq!(fold(f_circuit, vec![Fr::from(3_u32)], 3)?, z);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequential_multi_inputs() -> Result<(), Error> {
+        // three steps of MultiInputsFCircuit per fold, to check that the compositions nest
+        type FC = SequentialFCircuit<
+            Fr,
+            MultiInputsFCircuit<Fr>,
+            SequentialFCircuit<Fr, MultiInputsFCircuit<Fr>, MultiInputsFCircuit<Fr>>,
+        >;
+        let f_circuit = FC::new(((), ((), ())))?;
+        assert_eq!(f_circuit.state_len(), 5);
+
+        let z_0: Vec<Fr> = (1..=5_u32).map(Fr::from).collect();
+        let mut z = z_0.clone();
+        for _ in 0..2 * 3 {
+            z = multi_inputs_step_native(z);
+        }
+        assert_eq!(fold(f_circuit, z_0, 2)?, z);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequential_witness_hint() -> Result<(), Error> {
+        // the hint of `B` is precomputed from the state computed by `A`, which also uses a hint
+        type FC = SequentialFCircuit<Fr, CubicHintFCircuit<Fr>, CubicHintFCircuit<Fr>>;
+        let f_circuit = FC::new(((), ()))?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let (a_hint, b_hint) = f_circuit.precompute_witness(0, &z_0, &Default::default())?;
+        let z_a = cubic_step_native(z_0.clone());
+        assert_eq!(a_hint, z_0[0] * z_0[0] * z_0[0]);
+        assert_eq!(b_hint, z_a[0] * z_a[0] * z_a[0]);
+
+        let mut z = z_0.clone();
+        for _ in 0..3 {
+            z = cubic_step_native(cubic_step_native(z));
+        }
+        assert_eq!(fold(f_circuit, z_0, 3)?, z);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequential_state_len_mismatch() {
+        assert!(matches!(
+            SequentialFCircuit::<Fr, CubicFCircuit<Fr>, MultiInputsFCircuit<Fr>>::new(((), ())),
+            Err(Error::NotSameLength(_, 1, _, 5))
+        ));
+        assert!(matches!(
+            SequentialFCircuit::<Fr, MultiInputsFCircuit<Fr>, CubicFCircuit<Fr>>::new(((), ())),
+            Err(Error::NotSameLength(_, 5, _, 1))
+        ));
+    }
+}
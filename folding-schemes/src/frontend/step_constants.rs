@@ -0,0 +1,410 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::{fmt::Debug, Zero};
+
+use super::FCircuit;
+use crate::Error;
+
+/// StepConstantsCircuit defines the trait of a step circuit that depends on public constants
+/// varying with the step index (eg. round keys, or the positions of a Merkle path), given by a
+/// schedule that is known when building the circuit, as opposed to the `ExternalInputs`, which are
+/// chosen by the prover. It is folded through the [`StepConstantsFCircuit`] wrapper.
+///
+/// Notice that the constants of a step can not be allocated as constants of the step circuit
+/// computed from the step index `i` of [`FCircuit::generate_step_constraints`]: the constants end
+/// up as coefficients of the R1CS, so the circuit of each step would have a different R1CS than
+/// the one the folding scheme was preprocessed with, and folding it would fail. Instead, the whole
+/// schedule is embedded in every step, where the constants of the current step are selected by a
+/// step counter kept in the state.
+pub trait StepConstantsCircuit<F: PrimeField>: Clone + Debug {
+    type Params: Debug;
+    type ExternalInputs: Clone + Default + Debug;
+    type ExternalInputsVar: Clone + Debug + AllocVar<Self::ExternalInputs, F>;
+    /// as [`FCircuit::WitnessHint`].
+    type WitnessHint: Clone + Default;
+
+    /// returns a new StepConstantsCircuit instance
+    fn new(params: Self::Params) -> Result<Self, Error>;
+
+    /// returns the number of elements in the state of the circuit, without the step counter
+    /// added by [`StepConstantsFCircuit`].
+    fn state_len(&self) -> usize;
+
+    /// returns the number of steps of the schedule, which is the number of steps that can be
+    /// folded.
+    fn num_steps(&self) -> usize;
+
+    /// returns the constants of the step `i`, which must have the same length for all the steps of
+    /// the schedule.
+    fn step_constants(&self, i: usize) -> Vec<F>;
+
+    /// generates the constraints for the step of F for the given z_i and constants of the step.
+    /// Unlike [`FCircuit::generate_step_constraints`], it is not given the step index, since the
+    /// step circuit must be the same at every step.
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+        step_constants: Vec<FpVar<F>>,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError>;
+
+    /// as [`FCircuit::precompute_witness`], where `z_i` is the state of the circuit, without the
+    /// step counter added by [`StepConstantsFCircuit`].
+    fn precompute_witness(
+        &self,
+        _i: usize,
+        _z_i: &[F],
+        _external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        Ok(Self::WitnessHint::default())
+    }
+
+    /// as [`FCircuit::with_witness_hint`].
+    fn with_witness_hint(
+        &self,
+        external_inputs: Self::ExternalInputs,
+        _hint: Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        external_inputs
+    }
+
+    /// returns (and clears) the error that made the last call to `generate_step_constraints`
+    /// fail, which [`StepConstantsFCircuit`] forwards as its [`FCircuit::take_step_error`].
+    fn take_step_error(&self) -> Option<Error> {
+        None
+    }
+}
+
+/// StepConstantsFCircuit wraps a [`StepConstantsCircuit`] `SC` into an FCircuit, providing it the
+/// constants of each step of its schedule.
+///
+/// The state of the wrapper is the state of `SC` followed by a step counter, which is incremented
+/// at each step, so that `z_0` has to end with a zero (see
+/// [`StepConstantsFCircuit::initial_state`]) and the verifier of the IVC proof checks the counter
+/// along with the rest of `z_0`. At each step, the constants of the step given by the counter are
+/// selected from the schedule, which is embedded as constants of the circuit: the R1CS is the same
+/// at every step, and the prover can not choose other constants than the scheduled ones. Folding
+/// more steps than the ones of the schedule is not possible.
+///
+/// The selection costs a few constraints per step of the schedule, so long schedules are better
+/// committed to and opened from the external inputs instead.
+#[derive(Clone, Debug)]
+pub struct StepConstantsFCircuit<F: PrimeField, SC: StepConstantsCircuit<F>> {
+    pub SC: SC,
+    schedule: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField, SC: StepConstantsCircuit<F>> StepConstantsFCircuit<F, SC> {
+    /// returns the initial state of the wrapper for the initial state `z_0` of the inner circuit.
+    pub fn initial_state(&self, mut z_0: Vec<F>) -> Vec<F> {
+        z_0.push(F::zero());
+        z_0
+    }
+}
+
+impl<F: PrimeField, SC: StepConstantsCircuit<F>> FCircuit<F> for StepConstantsFCircuit<F, SC> {
+    type Params = SC::Params;
+    type ExternalInputs = SC::ExternalInputs;
+    type ExternalInputsVar = SC::ExternalInputsVar;
+    type WitnessHint = SC::WitnessHint;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        let SC = SC::new(params)?;
+        if SC.num_steps() == 0 {
+            return Err(Error::NotExpectedLength(0, 1));
+        }
+        let schedule: Vec<Vec<F>> = (0..SC.num_steps()).map(|i| SC.step_constants(i)).collect();
+        if let Some(constants) = schedule.iter().find(|c| c.len() != schedule[0].len()) {
+            return Err(Error::NotSameLength(
+                "step_constants(i)".to_string(),
+                constants.len(),
+                "step_constants(0)".to_string(),
+                schedule[0].len(),
+            ));
+        }
+        Ok(Self { SC, schedule })
+    }
+    fn state_len(&self) -> usize {
+        self.SC.state_len() + 1
+    }
+    fn precompute_witness(
+        &self,
+        i: usize,
+        z_i: &[F],
+        external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        // the state of `SC` is `z_i` without the step counter
+        let z_i = z_i.split_last().ok_or(Error::Empty)?.1;
+        self.SC.precompute_witness(i, z_i, external_inputs)
+    }
+    fn with_witness_hint(
+        &self,
+        external_inputs: Self::ExternalInputs,
+        hint: Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        self.SC.with_witness_hint(external_inputs, hint)
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        mut z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let counter = z_i.pop().ok_or(SynthesisError::Unsatisfiable)?;
+
+        // selectors of the step of the schedule given by the counter, exactly one of which is set
+        // if the counter is within the schedule
+        let selectors = (0..self.schedule.len())
+            .map(|j| {
+                counter
+                    .is_eq(&FpVar::Constant(F::from(j as u64)))
+                    .map(FpVar::from)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        selectors
+            .iter()
+            .fold(FpVar::zero(), |acc, s| acc + s)
+            .enforce_equal(&FpVar::one())?;
+
+        // since the schedule is constant, the selection is a linear combination of the selectors
+        let step_constants = (0..self.schedule[0].len())
+            .map(|k| {
+                selectors
+                    .iter()
+                    .zip(&self.schedule)
+                    .filter(|(_, constants)| !constants[k].is_zero())
+                    .fold(FpVar::zero(), |acc, (s, constants)| acc + s * constants[k])
+            })
+            .collect();
+
+        let mut z_i1 =
+            self.SC
+                .generate_step_constraints(cs, z_i, external_inputs, step_constants)?;
+        if z_i1.len() != self.SC.state_len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        z_i1.push(counter + FpVar::one());
+        Ok(z_i1)
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.SC.take_step_error()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::marker::PhantomData;
+
+    use crate::arith::r1cs::{extract_r1cs, R1CS};
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::{Nova, PreprocessorParam};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    /// constants (c_0, c_1) = (i + 2, i^2 + 1) of the step i of the test circuits
+    fn affine_constants<F: PrimeField>(i: usize) -> Vec<F> {
+        vec![F::from(i as u64 + 2), F::from((i * i) as u64 + 1)]
+    }
+
+    /// test circuit computing z_{i+1} = z_i * c_0 + c_1 with the constants of the step i
+    #[derive(Clone, Copy, Debug)]
+    struct AffineCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> StepConstantsCircuit<F> for AffineCircuit<F> {
+        type Params = ();
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+        type WitnessHint = ();
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn num_steps(&self) -> usize {
+            4
+        }
+        fn step_constants(&self, i: usize) -> Vec<F> {
+            affine_constants(i)
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Self::ExternalInputsVar,
+            step_constants: Vec<FpVar<F>>,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            Ok(vec![&z_i[0] * &step_constants[0] + &step_constants[1]])
+        }
+    }
+
+    /// same as `AffineCircuit`, but allocating the constants of the step `i` as constants of the
+    /// circuit
+    #[derive(Clone, Copy, Debug)]
+    struct BakedAffineFCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for BakedAffineFCircuit<F> {
+        type Params = ();
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+        type WitnessHint = ();
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            cs: ConstraintSystemRef<F>,
+            i: usize,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            let c = Vec::<FpVar<F>>::new_constant(cs, affine_constants::<F>(i))?;
+            Ok(vec![&z_i[0] * &c[0] + &c[1]])
+        }
+    }
+
+    /// returns the R1CS of the step `i` of `f_circuit`, whose next state is constrained as in the
+    /// AugmentedFCircuit, where it is hashed into the public inputs
+    fn step_r1cs<FC: FCircuit<Fr, ExternalInputsVar = ()>>(
+        f_circuit: &FC,
+        i: usize,
+        z_i: Vec<Fr>,
+    ) -> Result<R1CS<Fr>, Error> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z_i))?;
+        let z_i1 = f_circuit.generate_step_constraints(cs.clone(), i, z_i, ())?;
+        Vec::<FpVar<Fr>>::new_input(cs.clone(), || z_i1.value())?.enforce_equal(&z_i1)?;
+        cs.finalize();
+        assert!(cs.is_satisfied()?);
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        extract_r1cs(&cs)
+    }
+
+    #[test]
+    fn test_step_constants_r1cs() -> Result<(), Error> {
+        // allocating the constants of each step as constants of the circuit changes the R1CS
+        let baked = BakedAffineFCircuit::<Fr>::new(())?;
+        assert_ne!(
+            step_r1cs(&baked, 0, vec![Fr::from(3)])?,
+            step_r1cs(&baked, 1, vec![Fr::from(3)])?
+        );
+
+        // while selecting them from the schedule keeps it the same at every step
+        let f_circuit = StepConstantsFCircuit::<Fr, AffineCircuit<Fr>>::new(())?;
+        let r1cs = step_r1cs(&f_circuit, 0, vec![Fr::from(3), Fr::from(0)])?;
+        for i in 1..4 {
+            assert_eq!(
+                step_r1cs(&f_circuit, i, vec![Fr::from(3), Fr::from(i as u64)])?,
+                r1cs
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_constants_selection() -> Result<(), Error> {
+        let f_circuit = StepConstantsFCircuit::<Fr, AffineCircuit<Fr>>::new(())?;
+        assert_eq!(f_circuit.state_len(), 2);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(3), Fr::from(2)]))?;
+        // the constants are selected by the counter, regardless of the step index
+        let z_i1 = f_circuit.generate_step_constraints(cs.clone(), 0, z_i, ())?;
+        assert_eq!(z_i1.value()?, vec![Fr::from(3 * 4 + 5), Fr::from(3)]);
+        assert!(cs.is_satisfied()?);
+
+        // the counter can not go past the schedule
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(3), Fr::from(4)]))?;
+        f_circuit.generate_step_constraints(cs.clone(), 4, z_i, ())?;
+        assert!(!cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_constants_nova() -> Result<(), Error> {
+        type FC = StepConstantsFCircuit<Fr, AffineCircuit<Fr>>;
+        type N =
+            Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+
+        let mut rng = ark_std::test_rng();
+        let f_circuit = FC::new(())?;
+        let prep_param =
+            PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = f_circuit.initial_state(vec![Fr::from(3)]);
+        let mut nova = N::init(&params, f_circuit, z_0)?;
+
+        let mut z = Fr::from(3);
+        for i in 0..4 {
+            nova.prove_step(&mut rng, (), None)?;
+            let c = affine_constants::<Fr>(i);
+            z = z * c[0] + c[1];
+        }
+        assert_eq!(nova.state(), vec![z, Fr::from(4)]);
+        N::verify(params.1, nova.ivc_proof())?;
+
+        // the schedule is over
+        assert!(nova.prove_step(&mut rng, (), None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_constants_schedule() {
+        /// circuit whose constants have a different length at each step
+        #[derive(Clone, Copy, Debug)]
+        struct RaggedCircuit;
+        impl StepConstantsCircuit<Fr> for RaggedCircuit {
+            type Params = ();
+            type ExternalInputs = ();
+            type ExternalInputsVar = ();
+            type WitnessHint = ();
+
+            fn new(_params: Self::Params) -> Result<Self, Error> {
+                Ok(Self)
+            }
+            fn state_len(&self) -> usize {
+                1
+            }
+            fn num_steps(&self) -> usize {
+                2
+            }
+            fn step_constants(&self, i: usize) -> Vec<Fr> {
+                vec![Fr::from(1); i + 1]
+            }
+            fn generate_step_constraints(
+                &self,
+                _cs: ConstraintSystemRef<Fr>,
+                z_i: Vec<FpVar<Fr>>,
+                _external_inputs: Self::ExternalInputsVar,
+                _step_constants: Vec<FpVar<Fr>>,
+            ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+                Ok(z_i)
+            }
+        }
+        assert!(matches!(
+            StepConstantsFCircuit::<Fr, RaggedCircuit>::new(()),
+            Err(Error::NotSameLength(_, 2, _, 1))
+        ));
+    }
+}
@@ -0,0 +1,533 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::{
+    ns,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+};
+use ark_std::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use super::FCircuit;
+use crate::Error;
+
+/// Closure building the [`ConstraintSynthesizer`] of a step from the state `z_i`, the external
+/// inputs and the step index, and returning it together with the next state `z_{i+1}`.
+pub type SynthesizerBuilderFn<F, CS, EIV> = dyn Fn(
+        ConstraintSystemRef<F>,
+        &[FpVar<F>],
+        &EIV,
+        usize,
+    ) -> Result<(CS, Vec<FpVar<F>>), SynthesisError>
+    + Send
+    + Sync;
+
+/// Closure computing natively, before the synthesis of a step, the external inputs of the step
+/// completed with the parts of its witness that are expensive to compute in the builder, from the
+/// step index, the state `z_i` and the external inputs given to the folding scheme (see
+/// [`FCircuit::precompute_witness`]).
+pub type SynthesizerPrecomputeFn<F, EI> =
+    dyn Fn(usize, &[F], &EI) -> Result<EI, Error> + Send + Sync;
+
+/// Shareable [`SynthesizerBuilderFn`], used as the parameters of a [`SynthesizerFCircuit`].
+pub struct SynthesizerBuilder<F, CS, EIV>(pub Arc<SynthesizerBuilderFn<F, CS, EIV>>);
+
+impl<F, CS, EIV> SynthesizerBuilder<F, CS, EIV> {
+    pub fn new(
+        build: impl Fn(
+                ConstraintSystemRef<F>,
+                &[FpVar<F>],
+                &EIV,
+                usize,
+            ) -> Result<(CS, Vec<FpVar<F>>), SynthesisError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self(Arc::new(build))
+    }
+}
+
+impl<F, CS, EIV> Clone for SynthesizerBuilder<F, CS, EIV> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<F, CS, EIV> Debug for SynthesizerBuilder<F, CS, EIV> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SynthesizerBuilder")
+    }
+}
+
+/// SynthesizerFCircuit adapts an arkworks [`ConstraintSynthesizer`] `CS` into an FCircuit, so that
+/// existing gadgets can be folded without being rewritten.
+///
+/// At each step, the [`SynthesizerBuilder`] receives the variables of the state `z_i` and of the
+/// external inputs (of type `EIV`, allocated from `EI`) and the step index, and returns the
+/// synthesizer of the step together with the variables of the next state `z_{i+1}`, which it
+/// usually allocates as witnesses for the synthesizer to constrain. The synthesizer is then
+/// synthesized in its own namespace of the constraint system of the AugmentedFCircuit.
+///
+/// Some care is needed when porting a synthesizer written as a standalone circuit:
+/// - it must not allocate public inputs (with `new_input`): the public inputs of the
+///   AugmentedFCircuit are fixed by the folding scheme, so the state has to be passed through
+///   `z_i` and `z_{i+1}` instead. A step allocating public inputs fails, and
+///   [`FCircuit::take_step_error`] returns an [`Error::Other`] describing it.
+/// - it must constrain the variables given by the builder rather than allocating again the
+///   values of the state, since new witnesses would not be bound to `z_i` and `z_{i+1}`.
+/// - it must not assume that the values of the variables are available, since the folding
+///   schemes also synthesize the step in setup mode. Witnesses should be allocated with closures
+///   propagating the [`SynthesisError::AssignmentMissing`] returned by `value()`.
+/// - it is built and synthesized again each time the folding scheme synthesizes the step, which
+///   can happen more than once per step.
+///
+/// The values that are expensive to compute inside the builder can be precomputed natively with
+/// [`SynthesizerFCircuit::with_precompute`], whose completed external inputs are the witness hint
+/// of the circuit.
+pub struct SynthesizerFCircuit<F: PrimeField, CS, EI = (), EIV = ()> {
+    state_len: usize,
+    builder: SynthesizerBuilder<F, CS, EIV>,
+    // error of the last failed step, shared by the clones of the circuit since the folding
+    // schemes synthesize the steps with their own clone
+    step_error: Arc<Mutex<Option<Error>>>,
+    precompute: Option<Arc<SynthesizerPrecomputeFn<F, EI>>>,
+    _ei: PhantomData<EI>,
+}
+
+impl<F: PrimeField, CS, EI, EIV> SynthesizerFCircuit<F, CS, EI, EIV> {
+    /// sets the closure precomputing natively the external inputs of each step, which the builder
+    /// then receives allocated as witnesses.
+    pub fn with_precompute(
+        mut self,
+        precompute: impl Fn(usize, &[F], &EI) -> Result<EI, Error> + Send + Sync + 'static,
+    ) -> Self {
+        self.precompute = Some(Arc::new(precompute));
+        self
+    }
+
+    fn record(&self, err: Error) -> SynthesisError {
+        if let Ok(mut step_error) = self.step_error.lock() {
+            *step_error = Some(err);
+        }
+        SynthesisError::Unsatisfiable
+    }
+}
+
+impl<F: PrimeField, CS, EI, EIV> Clone for SynthesizerFCircuit<F, CS, EI, EIV> {
+    fn clone(&self) -> Self {
+        Self {
+            state_len: self.state_len,
+            builder: self.builder.clone(),
+            step_error: self.step_error.clone(),
+            precompute: self.precompute.clone(),
+            _ei: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField, CS, EI, EIV> Debug for SynthesizerFCircuit<F, CS, EI, EIV> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SynthesizerFCircuit")
+            .field("state_len", &self.state_len)
+            .field("builder", &self.builder)
+            .field("precompute", &self.precompute.is_some())
+            .finish()
+    }
+}
+
+impl<F, CS, EI, EIV> FCircuit<F> for SynthesizerFCircuit<F, CS, EI, EIV>
+where
+    F: PrimeField,
+    CS: ConstraintSynthesizer<F>,
+    EI: Clone + Default + Debug,
+    EIV: Clone + Debug + AllocVar<EI, F>,
+{
+    /// (state_len, builder)
+    type Params = (usize, SynthesizerBuilder<F, CS, EIV>);
+    type ExternalInputs = EI;
+    type ExternalInputsVar = EIV;
+    // the external inputs computed by the precompute closure, if any
+    type WitnessHint = Option<EI>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        let (state_len, builder) = params;
+        Ok(Self {
+            state_len,
+            builder,
+            step_error: Arc::new(Mutex::new(None)),
+            precompute: None,
+            _ei: PhantomData,
+        })
+    }
+    fn state_len(&self) -> usize {
+        self.state_len
+    }
+    fn precompute_witness(
+        &self,
+        i: usize,
+        z_i: &[F],
+        external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        self.precompute
+            .as_ref()
+            .map(|precompute| precompute(i, z_i, external_inputs))
+            .transpose()
+    }
+    fn with_witness_hint(
+        &self,
+        external_inputs: Self::ExternalInputs,
+        hint: Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        hint.unwrap_or(external_inputs)
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let num_instance_variables = cs.num_instance_variables();
+
+        let (synthesizer, z_i1) =
+            (self.builder.0)(ns!(cs, "builder").cs(), &z_i, &external_inputs, i)?;
+        synthesizer.generate_constraints(ns!(cs, "synthesizer").cs())?;
+
+        if cs.num_instance_variables() != num_instance_variables {
+            return Err(self.record(Error::Other(format!(
+                "the ConstraintSynthesizer of step {} allocated {} public inputs, while the state \
+                 must be passed through z_i and z_{{i+1}}",
+                i,
+                cs.num_instance_variables() - num_instance_variables
+            ))));
+        }
+        if z_i1.len() != self.state_len {
+            return Err(self.record(Error::NotSameLength(
+                "z_{i+1}".to_string(),
+                z_i1.len(),
+                "state_len".to_string(),
+                self.state_len,
+            )));
+        }
+        Ok(z_i1)
+    }
+
+    fn take_step_error(&self) -> Option<Error> {
+        self.step_error.lock().ok()?.take()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_crypto_primitives::{
+        crh::{
+            poseidon::{
+                constraints::{CRHGadget, CRHParametersVar},
+                CRH,
+            },
+            CRHScheme, CRHSchemeGadget,
+        },
+        sponge::{poseidon::PoseidonConfig, Absorb},
+    };
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::{eq::EqGadget, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::{
+        hypernova::HyperNova,
+        nova::{Nova, PreprocessorParam},
+        protogalaxy::ProtoGalaxy,
+    };
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    /// Arkworks circuit checking that `image` is the Poseidon hash of `preimage`.
+    struct PoseidonPreimage<F: PrimeField + Absorb> {
+        config: PoseidonConfig<F>,
+        preimage: Vec<FpVar<F>>,
+        image: FpVar<F>,
+    }
+
+    impl<F: PrimeField + Absorb> ConstraintSynthesizer<F> for PoseidonPreimage<F> {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            let crh_params = CRHParametersVar::<F>::new_constant(cs, self.config)?;
+            let h = CRHGadget::<F>::evaluate(&crh_params, &self.preimage)?;
+            h.enforce_equal(&self.image)
+        }
+    }
+
+    type PoseidonFCircuit = SynthesizerFCircuit<Fr, PoseidonPreimage<Fr>, [Fr; 1], [FpVar<Fr>; 1]>;
+
+    /// The next state is the hash of the state and the external input, allocated as a witness
+    /// that the synthesizer checks to be the image of both.
+    fn poseidon_builder(
+        config: PoseidonConfig<Fr>,
+    ) -> SynthesizerBuilder<Fr, PoseidonPreimage<Fr>, [FpVar<Fr>; 1]> {
+        SynthesizerBuilder::new(move |cs, z_i, external_inputs, _i| {
+            let preimage = vec![z_i[0].clone(), external_inputs[0].clone()];
+            let image = FpVar::new_witness(cs, || {
+                CRH::<Fr>::evaluate(&config, preimage.value()?)
+                    .map_err(|_| SynthesisError::Unsatisfiable)
+            })?;
+            let synthesizer = PoseidonPreimage {
+                config: config.clone(),
+                preimage,
+                image: image.clone(),
+            };
+            Ok((synthesizer, vec![image]))
+        })
+    }
+
+    #[test]
+    fn test_synthesizer_fcircuit() -> Result<(), Error> {
+        let config = poseidon_canonical_config::<Fr>();
+        let f_circuit = PoseidonFCircuit::new((1, poseidon_builder(config.clone())))?;
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(1_u32)]))?;
+        let external_inputs = <[FpVar<Fr>; 1]>::new_witness(cs.clone(), || Ok([Fr::from(2_u32)]))?;
+        let z_i1 = f_circuit.generate_step_constraints(cs.clone(), 0, z_i, external_inputs)?;
+        assert_eq!(
+            z_i1.value()?,
+            vec![CRH::<Fr>::evaluate(&config, [Fr::from(1_u32), Fr::from(2_u32)]).unwrap()]
+        );
+        assert!(cs.is_satisfied()?);
+        assert_eq!(cs.num_instance_variables(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_synthesizer_fcircuit_nova() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            PoseidonFCircuit,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let config = poseidon_canonical_config::<Fr>();
+        let f_circuit = PoseidonFCircuit::new((1, poseidon_builder(config.clone())))?;
+
+        let prep_param = PreprocessorParam::new(config.clone(), f_circuit.clone());
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut nova = N::init(&params, f_circuit, z_0.clone())?;
+
+        let mut z = z_0;
+        for w in 1..=3_u32 {
+            nova.prove_step(&mut rng, [Fr::from(w)], None)?;
+            z = vec![CRH::<Fr>::evaluate(&config, [z[0], Fr::from(w)]).unwrap()];
+        }
+        assert_eq!(nova.state(), z);
+        N::verify(params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
+    /// Arkworks circuit passing its state through a public input when `public` is set, as
+    /// standalone circuits do, and through a witness otherwise.
+    struct PublicStep<F: PrimeField> {
+        z_i1: FpVar<F>,
+        public: bool,
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for PublicStep<F> {
+        fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            let z_i1 = if self.public {
+                FpVar::new_input(cs, || self.z_i1.value())?
+            } else {
+                FpVar::new_witness(cs, || self.z_i1.value())?
+            };
+            z_i1.enforce_equal(&self.z_i1)
+        }
+    }
+
+    #[test]
+    fn test_synthesizer_fcircuit_rejects_public_inputs() -> Result<(), Error> {
+        let builder = SynthesizerBuilder::new(|_cs, z_i: &[FpVar<Fr>], _: &(), _i| {
+            Ok((
+                PublicStep {
+                    z_i1: &z_i[0] + &z_i[0],
+                    public: true,
+                },
+                vec![&z_i[0] + &z_i[0]],
+            ))
+        });
+        let f_circuit = SynthesizerFCircuit::<Fr, PublicStep<Fr>>::new((1, builder))?;
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(1_u32)]))?;
+        assert!(f_circuit.generate_step_constraints(cs, 0, z_i, ()).is_err());
+        // the error is taken from a clone, as the folding schemes do
+        assert!(matches!(
+            f_circuit.clone().take_step_error(),
+            Some(Error::Other(_))
+        ));
+        assert!(f_circuit.take_step_error().is_none());
+        Ok(())
+    }
+
+    /// Arkworks circuit checking that `z_cubed` is the cube of `z`.
+    struct CubeStep<F: PrimeField> {
+        z: FpVar<F>,
+        z_cubed: FpVar<F>,
+    }
+
+    impl<F: PrimeField> ConstraintSynthesizer<F> for CubeStep<F> {
+        fn generate_constraints(self, _cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+            self.z_cubed.enforce_equal(&(&self.z * &self.z * &self.z))
+        }
+    }
+
+    #[test]
+    fn test_synthesizer_fcircuit_precompute() -> Result<(), Error> {
+        use crate::frontend::utils::{cubic_step_native, simulate_steps};
+
+        // z_{i+1} = z_i^3 + z_i + 5, taking z_i^3 from the external inputs
+        let builder = SynthesizerBuilder::new(|_cs, z_i: &[FpVar<Fr>], ei: &[FpVar<Fr>; 1], _i| {
+            let [z_cubed] = ei.clone();
+            Ok((
+                CubeStep {
+                    z: z_i[0].clone(),
+                    z_cubed: z_cubed.clone(),
+                },
+                vec![z_cubed + &z_i[0] + FpVar::Constant(Fr::from(5_u32))],
+            ))
+        });
+        let f_circuit =
+            SynthesizerFCircuit::<Fr, CubeStep<Fr>, [Fr; 1], [FpVar<Fr>; 1]>::new((1, builder))?;
+
+        // without precomputing z_i^3, the constraints of the steps are not satisfied
+        let z_0 = vec![Fr::from(3_u32)];
+        let steps = simulate_steps(&f_circuit, z_0.clone(), [[Fr::from(0_u32)]; 2])?;
+        assert!(!steps.all_satisfied());
+
+        let f_circuit = f_circuit.with_precompute(|_i, z_i, _ei| Ok([z_i[0] * z_i[0] * z_i[0]]));
+        let steps = simulate_steps(&f_circuit, z_0.clone(), [[Fr::from(0_u32)]; 2])?;
+        let z_1 = cubic_step_native(z_0.clone());
+        assert_eq!(
+            steps.into_states()?,
+            [z_0, z_1.clone(), cubic_step_native(z_1)]
+        );
+        Ok(())
+    }
+
+    // the error of a failing step is forwarded by the combinators wrapping the circuit
+    #[test]
+    fn test_synthesizer_fcircuit_step_error_through_wrappers() -> Result<(), Error> {
+        use crate::frontend::{
+            external_inputs::ExternalInputsCommitted,
+            multistep::{MultiStepFCircuit, MultiStepInputs, MultiStepInputsVar},
+        };
+        type FC = SynthesizerFCircuit<Fr, PublicStep<Fr>>;
+
+        let builder = SynthesizerBuilder::new(|_cs, z_i: &[FpVar<Fr>], _: &(), _i| {
+            Ok((
+                PublicStep {
+                    z_i1: &z_i[0] + &z_i[0],
+                    public: true,
+                },
+                vec![&z_i[0] + &z_i[0]],
+            ))
+        });
+
+        let f_circuit = MultiStepFCircuit::<Fr, FC, 2>::new((1, builder.clone()))?;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(vec![Fr::from(1_u32)]))?;
+        let inputs = MultiStepInputs::<(), 2>::default();
+        let external_inputs = MultiStepInputsVar::new_witness(cs.clone(), || Ok(inputs))?;
+        assert!(f_circuit
+            .generate_step_constraints(cs, 0, z_i, external_inputs)
+            .is_err());
+        assert!(matches!(
+            f_circuit.clone().take_step_error(),
+            Some(Error::Other(_))
+        ));
+
+        let f_circuit = ExternalInputsCommitted::<Fr, FC>::new((
+            poseidon_canonical_config::<Fr>(),
+            (1, builder),
+        ))?;
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let z_i = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || {
+            Ok(vec![Fr::from(1_u32), Fr::from(0_u32)])
+        })?;
+        assert!(f_circuit.generate_step_constraints(cs, 0, z_i, ()).is_err());
+        assert!(matches!(
+            f_circuit.clone().take_step_error(),
+            Some(Error::Other(_))
+        ));
+        Ok(())
+    }
+
+    // the error of a failing step is returned by the `prove_step` of every folding scheme,
+    // instead of the generic `SynthesisError` of the AugmentedFCircuit
+    #[test]
+    fn test_synthesizer_fcircuit_step_error_is_surfaced() -> Result<(), Error> {
+        type FC = SynthesizerFCircuit<Fr, PublicStep<Fr>>;
+        // the step allocates a public input from its second step on, so that the preprocessing
+        // and the first step succeed
+        let builder = SynthesizerBuilder::new(|_cs, z_i: &[FpVar<Fr>], _: &(), i| {
+            Ok((
+                PublicStep {
+                    z_i1: &z_i[0] + &z_i[0],
+                    public: i > 0,
+                },
+                vec![&z_i[0] + &z_i[0]],
+            ))
+        });
+        let f_circuit = FC::new((1, builder))?;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), f_circuit.clone());
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type N =
+            Nova<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>, false>;
+        let params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&params, f_circuit.clone(), z_0.clone())?;
+        nova.prove_step(&mut rng, (), None)?;
+        assert!(matches!(
+            nova.prove_step(&mut rng, (), None),
+            Err(Error::Other(_))
+        ));
+
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            FC,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            1,
+            1,
+            false,
+        >;
+        let params = HN::preprocess(&mut rng, &prep_param)?;
+        let mut hypernova = HN::init(&params, f_circuit.clone(), z_0.clone())?;
+        hypernova.prove_step(&mut rng, (), None)?;
+        assert!(matches!(
+            hypernova.prove_step(&mut rng, (), None),
+            Err(Error::Other(_))
+        ));
+
+        type PG =
+            ProtoGalaxy<Projective, Projective2, FC, Pedersen<Projective>, Pedersen<Projective2>>;
+        let params = PG::preprocess(&mut rng, &(poseidon_config, f_circuit.clone()))?;
+        let mut protogalaxy = PG::init(&params, f_circuit, z_0)?;
+        protogalaxy.prove_step(&mut rng, (), None)?;
+        assert!(matches!(
+            protogalaxy.prove_step(&mut rng, (), None),
+            Err(Error::Other(_))
+        ));
+        Ok(())
+    }
+}
@@ -2,12 +2,16 @@ use ark_ff::PrimeField;
 use ark_r1cs_std::{
     alloc::AllocVar,
     fields::{fp::FpVar, FieldVar},
+    R1CSVar,
+};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError, SynthesisMode,
 };
-use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use ark_std::marker::PhantomData;
 use ark_std::{fmt::Debug, Zero};
 
 use super::FCircuit;
+use crate::arith::r1cs::{check_cs_satisfied, extract_r1cs};
 use crate::Error;
 
 /// DummyCircuit is a circuit that has dummy state whose length is specified in the `state_len`
@@ -20,6 +24,7 @@ impl<F: PrimeField> FCircuit<F> for DummyCircuit {
     type Params = usize;
     type ExternalInputs = ();
     type ExternalInputsVar = ();
+    type WitnessHint = ();
 
     fn new(state_len: Self::Params) -> Result<Self, Error> {
         Ok(Self { state_len })
@@ -53,6 +58,7 @@ impl<F: PrimeField> FCircuit<F> for CubicFCircuit<F> {
     type Params = ();
     type ExternalInputs = ();
     type ExternalInputsVar = ();
+    type WitnessHint = ();
 
     fn new(_params: Self::Params) -> Result<Self, Error> {
         Ok(Self { _f: PhantomData })
@@ -81,6 +87,110 @@ pub fn cubic_step_native<F: PrimeField>(z_i: Vec<F>) -> Vec<F> {
     vec![z * z * z + z + F::from(5)]
 }
 
+/// CubicHintFCircuit computes the same step as `CubicFCircuit`, but taking `z_i^3` from its
+/// external inputs, where [`FCircuit::precompute_witness`] places it, and constraining it.
+#[cfg(test)]
+#[derive(Clone, Copy, Debug)]
+pub struct CubicHintFCircuit<F: PrimeField> {
+    _f: PhantomData<F>,
+}
+
+#[cfg(test)]
+impl<F: PrimeField> FCircuit<F> for CubicHintFCircuit<F> {
+    type Params = ();
+    type ExternalInputs = [F; 1];
+    type ExternalInputsVar = [FpVar<F>; 1];
+    type WitnessHint = F;
+
+    fn new(_params: Self::Params) -> Result<Self, Error> {
+        Ok(Self { _f: PhantomData })
+    }
+    fn state_len(&self) -> usize {
+        1
+    }
+    fn precompute_witness(
+        &self,
+        _i: usize,
+        z_i: &[F],
+        _external_inputs: &Self::ExternalInputs,
+    ) -> Result<Self::WitnessHint, Error> {
+        Ok(z_i[0] * z_i[0] * z_i[0])
+    }
+    fn with_witness_hint(
+        &self,
+        _external_inputs: Self::ExternalInputs,
+        hint: Self::WitnessHint,
+    ) -> Self::ExternalInputs {
+        [hint]
+    }
+    fn generate_step_constraints(
+        &self,
+        _cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        use ark_r1cs_std::eq::EqGadget;
+        let [z_i_cubed] = external_inputs;
+        z_i_cubed.enforce_equal(&(&z_i[0] * &z_i[0] * &z_i[0]))?;
+        Ok(vec![z_i_cubed + &z_i[0] + FpVar::Constant(F::from(5u32))])
+    }
+}
+
+/// MultiInputsFCircuit is a circuit with a state of 5 elements, on each of which it does a
+/// different operation at each step.
+#[cfg(test)]
+#[derive(Clone, Copy, Debug)]
+pub struct MultiInputsFCircuit<F: PrimeField> {
+    _f: PhantomData<F>,
+}
+
+#[cfg(test)]
+impl<F: PrimeField> FCircuit<F> for MultiInputsFCircuit<F> {
+    type Params = ();
+    type ExternalInputs = ();
+    type ExternalInputsVar = ();
+    type WitnessHint = ();
+
+    fn new(_params: Self::Params) -> Result<Self, Error> {
+        Ok(Self { _f: PhantomData })
+    }
+    fn state_len(&self) -> usize {
+        5
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        _external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let four = FpVar::<F>::new_constant(cs.clone(), F::from(4u32))?;
+        let forty = FpVar::<F>::new_constant(cs.clone(), F::from(40u32))?;
+        let onehundred = FpVar::<F>::new_constant(cs.clone(), F::from(100u32))?;
+
+        Ok(vec![
+            &z_i[0] + &four,
+            &z_i[1] + &forty,
+            &z_i[2] * &four,
+            &z_i[3] * &forty,
+            &z_i[4] + &onehundred,
+        ])
+    }
+}
+
+/// Native implementation of `MultiInputsFCircuit`
+#[cfg(test)]
+pub fn multi_inputs_step_native<F: PrimeField>(z_i: Vec<F>) -> Vec<F> {
+    vec![
+        z_i[0] + F::from(4),
+        z_i[1] + F::from(40),
+        z_i[2] * F::from(4),
+        z_i[3] * F::from(40),
+        z_i[4] + F::from(100),
+    ]
+}
+
 /// CustomFCircuit is a circuit that has the number of constraints specified in the
 /// `n_constraints` parameter. Note that the generated circuit will have very sparse matrices.
 #[derive(Clone, Copy, Debug)]
@@ -93,6 +203,7 @@ impl<F: PrimeField> FCircuit<F> for CustomFCircuit<F> {
     type Params = usize;
     type ExternalInputs = ();
     type ExternalInputsVar = ();
+    type WitnessHint = ();
 
     fn new(params: Self::Params) -> Result<Self, Error> {
         Ok(Self {
@@ -157,3 +268,107 @@ impl<F: PrimeField, FC: FCircuit<F>> ConstraintSynthesizer<F> for WrapperCircuit
         Ok(())
     }
 }
+
+/// Outcome of [`simulate_steps`]: the states reached by the steps, and whether the constraints of
+/// each step are satisfied.
+#[derive(Debug)]
+pub struct SimulatedSteps<F: PrimeField> {
+    /// the states `z_0, z_1, ..., z_n`
+    pub states: Vec<Vec<F>>,
+    /// for each step, `Ok` if its constraints are satisfied, or [`Error::UnsatisfiedConstraint`]
+    /// with the first constraint that it violates
+    pub satisfied: Vec<Result<(), Error>>,
+}
+
+impl<F: PrimeField> SimulatedSteps<F> {
+    /// returns whether the constraints of all the steps are satisfied
+    pub fn all_satisfied(&self) -> bool {
+        self.satisfied.iter().all(Result::is_ok)
+    }
+
+    /// returns the states `z_0, z_1, ..., z_n` if the constraints of all the steps are
+    /// satisfied, or the error of the first step whose constraints are not
+    pub fn into_states(self) -> Result<Vec<Vec<F>>, Error> {
+        self.satisfied.into_iter().collect::<Result<(), _>>()?;
+        Ok(self.states)
+    }
+}
+
+/// Runs the steps of `fc` from the state `z_0` over the given external inputs (one step per
+/// external input) without folding them, to check that they lead to the expected states before
+/// proving them. Each step is synthesized in a fresh `ConstraintSystem`, after precomputing its
+/// witness hint with [`FCircuit::precompute_witness`], as the folding schemes do.
+///
+/// The steps whose constraints are not satisfied do not stop the simulation, which continues from
+/// the state they computed, so that [`SimulatedSteps::satisfied`] reports every violating step.
+/// An error is only returned when a step cannot be synthesized.
+pub fn simulate_steps<F: PrimeField, FC: FCircuit<F>>(
+    fc: &FC,
+    z_0: Vec<F>,
+    inputs: impl IntoIterator<Item = FC::ExternalInputs>,
+) -> Result<SimulatedSteps<F>, Error> {
+    if z_0.len() != fc.state_len() {
+        return Err(Error::NotSameLength(
+            "z_0.len()".to_string(),
+            z_0.len(),
+            "state_len".to_string(),
+            fc.state_len(),
+        ));
+    }
+    let mut states = vec![z_0];
+    let mut satisfied = vec![];
+    for (i, external_inputs) in inputs.into_iter().enumerate() {
+        // `unwrap` is safe since `states` contains at least `z_0`
+        let z_i = states.last().unwrap().clone();
+        let hint = fc.precompute_witness(i, &z_i, &external_inputs)?;
+        let external_inputs = fc.with_witness_hint(external_inputs, hint);
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        let z_i_var = Vec::<FpVar<F>>::new_witness(cs.clone(), || Ok(z_i))?;
+        let external_inputs_var =
+            FC::ExternalInputsVar::new_witness(cs.clone(), || Ok(external_inputs))?;
+        let z_i1 = fc
+            .generate_step_constraints(cs.clone(), i, z_i_var, external_inputs_var)
+            .map_err(|e| fc.take_step_error().unwrap_or(e.into()))?;
+        if z_i1.len() != fc.state_len() {
+            return Err(Error::NotSameLength(
+                "z_{i+1}.len()".to_string(),
+                z_i1.len(),
+                "state_len".to_string(),
+                fc.state_len(),
+            ));
+        }
+
+        satisfied.push(if cs.is_satisfied()? {
+            Ok(())
+        } else {
+            cs.finalize();
+            let r1cs = extract_r1cs(&cs.borrow().ok_or(Error::NoInnerConstraintSystem)?)?;
+            check_cs_satisfied(&r1cs, &cs, i)
+        });
+        states.push(z_i1.value()?);
+    }
+    Ok(SimulatedSteps { states, satisfied })
+}
+
+/// Computes natively the state `z_{i+1}` of the step `i` of `fc` from `z_i` and the external
+/// inputs of the step (already completed with its witness hint), by synthesizing the step in a
+/// fresh `ConstraintSystem` without its matrices. Used by the combinators whose inner steps need
+/// the states computed by the previous ones to precompute their witness hints.
+pub(crate) fn synthesize_step<F: PrimeField, FC: FCircuit<F>>(
+    fc: &FC,
+    i: usize,
+    z_i: Vec<F>,
+    external_inputs: FC::ExternalInputs,
+) -> Result<Vec<F>, Error> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_mode(SynthesisMode::Prove {
+        construct_matrices: false,
+    });
+    let z_i = Vec::<FpVar<F>>::new_witness(cs.clone(), || Ok(z_i))?;
+    let external_inputs = FC::ExternalInputsVar::new_witness(cs.clone(), || Ok(external_inputs))?;
+    let z_i1 = fc
+        .generate_step_constraints(cs, i, z_i, external_inputs)
+        .map_err(|e| fc.take_step_error().unwrap_or(e.into()))?;
+    Ok(z_i1.value()?)
+}
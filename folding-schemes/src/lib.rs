@@ -23,6 +23,13 @@ use crate::folding::traits::{Inputize, InputizeNonNative};
 use crate::frontend::FCircuit;
 use crate::transcript::AbsorbNonNative;
 
+#[cfg(feature = "bls12-381")]
+compile_error!(
+    "the folding schemes can not be instantiated over BLS12-381: CycleFold needs a curve defined \
+     over its scalar field whose group has the order of its base field, and there is none. See the \
+     documentation of `folding_schemes::Curve`."
+);
+
 pub mod arith;
 pub mod commitment;
 pub mod constants;
@@ -44,6 +51,10 @@ pub enum Error {
     ArithError(#[from] utils::espresso::virtual_polynomial::ArithErrors),
     #[error(transparent)]
     ProtoGalaxy(folding::protogalaxy::ProtoGalaxyError),
+    /// Error of the detailed satisfiability checks, an [`arith::ArithError`] over the field of the
+    /// constraint system
+    #[error(transparent)]
+    Arith(Box<dyn std::error::Error + Send + Sync>),
     #[error("std::io::Error")]
     IOError(#[from] std::io::Error),
 
@@ -56,10 +67,24 @@ pub enum Error {
     SNARKVerificationFail,
     #[error("IVC verification failed")]
     IVCVerificationFail,
+    #[error("The public parameters hash does not match the one the proof is bound to")]
+    PPHashMismatch,
+    #[error("Unsupported serialization version {0}, expected {1}")]
+    UnsupportedSerializationVersion(u8, u8),
+    #[error("The deserialized {0} does not match the one computed from the FCircuit")]
+    DeserializedParamsMismatch(String),
+    #[error("The initial state of the chunk does not match the final state of the previous one")]
+    ChunkBoundaryMismatch,
     #[error("zkIVC verification failed")]
     zkIVCVerificationFail,
     #[error("Committed instance is expected to be an incoming (fresh) instance")]
     NotIncomingCommittedInstance,
+    #[error("Constraint {index} ({name}) is not satisfied at step {step}")]
+    UnsatisfiedConstraint {
+        step: usize,
+        index: usize,
+        name: String,
+    },
     #[error("R1CS instance is expected to not be relaxed")]
     R1CSUnrelaxedFail,
     #[error("Could not find the inner ConstraintSystem")]
@@ -94,12 +119,20 @@ pub enum Error {
     // Commitment errors
     #[error("Pedersen parameters length is not sufficient (generators.len={0} < vector.len={1} unsatisfied)")]
     PedersenParamsLen(usize, usize),
+    #[error("Commitment scheme parameters length is not sufficient (max_len={0} < vector.len={1} unsatisfied)")]
+    CommitmentParamsLen(usize, usize),
     #[error("Blinding factor not 0 for Commitment without hiding")]
     BlindingNotZero,
     #[error("Blinding factors incorrect, blinding is set to {0} but blinding values are {1}")]
     IncorrectBlinding(bool, String),
     #[error("Commitment verification failed")]
     CommitmentVerificationFail,
+    #[error("Commitment scheme parameters are not sufficient (they support vectors of length up to {0}, but {1} is needed)")]
+    CommitmentParamsLen(usize, usize),
+    #[error("Invalid powers of tau file: {0}")]
+    InvalidPtau(String),
+    #[error("Invalid SRS: {0}")]
+    InvalidSRS(String),
 
     // Polynomial IOP errors, from https://github.com/EspressoSystems/hyperplonk/blob/main/subroutines/src/poly_iop/errors.rs
     #[error("Invalid Polynomial IOP Prover: {0}")]
@@ -126,10 +159,24 @@ pub enum Error {
     MaxStep,
     #[error("Witness calculation error: {0}")]
     WitnessCalculationError(String),
+    /// Error of a frontend, such as the ones loading circuits written in Circom or Noir
+    #[error(transparent)]
+    Frontend(Box<dyn std::error::Error + Send + Sync>),
     #[error("Failed to convert {0} into {1}: {2}")]
     ConversionError(String, String, String),
     #[error("Failed to serde: {0}")]
     JSONSerdeError(String),
+    #[error("The {origin} has states of length {found}, but the decider expects states of length {expected}")]
+    DeciderStateLenMismatch {
+        origin: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error(
+        "The {mode} verification mode can not be used with a decider contract whose states are {}",
+        if *.hashed_state { "hashed" } else { "not hashed" }
+    )]
+    DeciderHashedStateMismatch { mode: String, hashed_state: bool },
     #[error("Multi instances folding not supported in this scheme")]
     NoMultiInstances,
     #[error("Missing 'other' instances, since this is a multi-instances folding scheme. Expected number of instances, mu:{0}, nu:{1}")]
@@ -301,6 +348,15 @@ impl<P: FpConfig<N>, const N: usize> Field for Fp<P, N> {
 
 /// `Curve` trait is a wrapper around `CurveGroup` that also includes the
 /// necessary bounds for the curve to be used conveniently in folding schemes.
+///
+/// The folding schemes are instantiated over a cycle of curves `(C1, C2)`, where
+/// `C1::BaseField == C2::ScalarField` and `C1::ScalarField == C2::BaseField`, e.g., BN254 and
+/// Grumpkin. This rules out BLS12-381: the partner of its G1 would be a curve over its 255-bit
+/// scalar field with a group of 381-bit prime order, which does not exist (Jubjub is defined over
+/// the right field, but its group order is a 252-bit prime). Such instantiations are rejected at
+/// compile time by the bounds of the folding schemes, and the `bls12-381` feature is a compile
+/// error. The components that do not need the cycle, such as `KZG<Bls12_381>`, the Poseidon
+/// transcript over its scalar field and the non-native gadgets over its G1, support it.
 pub trait Curve:
     CurveGroup<ScalarField: Field, BaseField: Field>
     + AbsorbNonNative
@@ -0,0 +1,188 @@
+//! A native-only [`Transcript`] based on Keccak-256, for the Fiat-Shamir transforms whose
+//! challenges are recomputed outside of any circuit, and in particular by EVM verifiers, for which
+//! Keccak-256 is much cheaper than Poseidon.
+//!
+//! It has no in-circuit counterpart, so it can not be used where the challenges are recomputed
+//! in-circuit, see the documentation of the [`crate::transcript`] module.
+//!
+//! The encoding is the following:
+//! - Everything is first converted to elements of the field `F` of the transcript, as for the
+//!   Poseidon transcript: through `Absorb` for the native values, through `AbsorbNonNative` for
+//!   the non-native ones, and as the affine coordinates `(x, y)` for the points absorbed with
+//!   `absorb_point`, where the point at infinity is `(0, 0)`.
+//! - Each field element is then encoded as the big-endian bytes of its canonical representative,
+//!   on `8 * N` bytes where `N` is the number of 64-bit limbs of `F` (i.e. as an `uint256` for the
+//!   scalar fields of BN254 and BLS12-381).
+//! - The transcript keeps a 32-byte digest `d`, initially zero, and the bytes absorbed since the
+//!   last squeeze `m`. A squeeze first sets `d = keccak256(d || m)` and empties `m`, then outputs
+//!   the stream `keccak256(d || 0) || keccak256(d || 1) || ...`, where the counters are encoded as
+//!   4-byte big-endian integers.
+//! - A challenge is obtained by reducing 64 bytes of the output stream, read as a big-endian
+//!   integer, modulo the order of `F`, which makes its bias negligible. A challenge of `nbits` bits
+//!   is made of the bits of the first `ceil(nbits / 8)` bytes of the output stream, little-endian
+//!   within each byte.
+//!
+//! As the digest chains all the squeezes, the challenges do not need to be absorbed back into the
+//! transcript.
+
+use ark_crypto_primitives::sponge::{Absorb, CryptographicSponge};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::marker::PhantomData;
+use sha3::{Digest, Keccak256};
+
+use super::{AbsorbNonNative, Transcript};
+
+/// A Fiat-Shamir transcript over the field `F` based on Keccak-256, see the module documentation
+/// for its encoding.
+#[derive(Clone, Debug)]
+pub struct KeccakTranscript<F: PrimeField> {
+    digest: [u8; 32],
+    pending: Vec<u8>,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakTranscript<F> {
+    fn absorb_field_elements(&mut self, elements: &[F]) {
+        for e in elements {
+            self.pending.extend(e.into_bigint().to_bytes_be());
+        }
+    }
+}
+
+impl<F: PrimeField> CryptographicSponge for KeccakTranscript<F> {
+    type Config = ();
+
+    fn new(_params: &Self::Config) -> Self {
+        Self {
+            digest: [0; 32],
+            pending: vec![],
+            _f: PhantomData,
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        self.absorb_field_elements(&input.to_sponge_field_elements_as_vec::<F>());
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.digest);
+        hasher.update(&self.pending);
+        self.digest = hasher.finalize().into();
+        self.pending.clear();
+
+        let mut output = Vec::with_capacity(num_bytes + 32);
+        let mut counter = 0u32;
+        while output.len() < num_bytes {
+            let mut hasher = Keccak256::new();
+            hasher.update(self.digest);
+            hasher.update(counter.to_be_bytes());
+            output.extend(hasher.finalize());
+            counter += 1;
+        }
+        output.truncate(num_bytes);
+        output
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let mut bits: Vec<bool> = self
+            .squeeze_bytes(num_bits.div_ceil(8))
+            .into_iter()
+            .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+            .collect();
+        bits.truncate(num_bits);
+        bits
+    }
+
+    fn squeeze_field_elements<F2: PrimeField>(&mut self, num_elements: usize) -> Vec<F2> {
+        self.squeeze_bytes(64 * num_elements)
+            .chunks(64)
+            .map(F2::from_be_bytes_mod_order)
+            .collect()
+    }
+}
+
+impl<F: PrimeField> Transcript<F> for KeccakTranscript<F> {
+    fn absorb_point<C: CurveGroup<BaseField = F>>(&mut self, p: &C) {
+        let (x, y) = p.into_affine().xy().unwrap_or_default();
+        self.absorb_field_elements(&[x, y]);
+    }
+    fn absorb_nonnative<V: AbsorbNonNative>(&mut self, v: &V) {
+        self.absorb_field_elements(&v.to_native_sponge_field_elements_as_vec::<F>());
+    }
+    fn get_challenge(&mut self) -> F {
+        self.squeeze_field_elements(1)[0]
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool> {
+        self.squeeze_bits(nbits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Vec<F> {
+        self.squeeze_field_elements(n)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Fr, G1Projective as G1};
+    use ark_grumpkin::Projective as GrumpkinProjective;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+
+    #[test]
+    fn test_keccak_transcript_encoding() {
+        // absorbing 42 and squeezing a challenge hashes the 32-byte big-endian word of 42
+        let mut tr = KeccakTranscript::<Fr>::new(&());
+        tr.absorb(&Fr::from(42_u32));
+        let c = tr.get_challenge();
+
+        let mut word = [0u8; 32];
+        word[31] = 42;
+        let digest = Keccak256::new()
+            .chain_update([0u8; 32])
+            .chain_update(word)
+            .finalize();
+        let stream: Vec<u8> = [0u32, 1]
+            .into_iter()
+            .flat_map(|counter| {
+                Keccak256::new()
+                    .chain_update(digest)
+                    .chain_update(counter.to_be_bytes())
+                    .finalize()
+            })
+            .collect();
+        assert_eq!(c, Fr::from_be_bytes_mod_order(&stream));
+    }
+
+    #[test]
+    fn test_keccak_transcript() {
+        let mut rng = test_rng();
+        let p = G1::rand(&mut rng);
+        let q = GrumpkinProjective::rand(&mut rng);
+
+        let mut tr1 = KeccakTranscript::<Fr>::new(&());
+        let mut tr2 = KeccakTranscript::<Fr>::new(&());
+        for tr in [&mut tr1, &mut tr2] {
+            tr.absorb(&Fr::from(42_u32));
+            tr.absorb_nonnative(&p);
+            tr.absorb_point(&q);
+        }
+        // the same transcripts give the same challenges
+        let c = tr1.get_challenges(2);
+        assert_eq!(c, tr2.get_challenges(2));
+        assert_ne!(c[0], c[1]);
+        // and the following challenges depend on the previous ones
+        let c2 = tr1.get_challenge();
+        assert_ne!(c2, c[0]);
+        assert_eq!(c2, tr2.get_challenge());
+        assert_eq!(tr1.get_challenge_nbits(128), tr2.get_challenge_nbits(128));
+
+        // absorbing another value gives another challenge
+        let mut tr3 = KeccakTranscript::<Fr>::new(&());
+        tr3.absorb(&Fr::from(43_u32));
+        tr3.absorb_nonnative(&p);
+        tr3.absorb_point(&q);
+        assert_ne!(tr3.get_challenges(2), c);
+    }
+}
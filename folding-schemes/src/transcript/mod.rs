@@ -1,9 +1,29 @@
+//! Fiat-Shamir transcripts, used natively through [`Transcript`] and in-circuit through
+//! [`TranscriptVar`].
+//!
+//! Two implementations of [`Transcript`] are provided:
+//! - `PoseidonSponge` (see [`poseidon`]), which has an in-circuit counterpart, `PoseidonSpongeVar`.
+//! - [`keccak::KeccakTranscript`], which is cheaper to recompute natively, in particular on the
+//!   EVM, but has no in-circuit counterpart.
+//!
+//! The following components recompute their challenges or hashes in-circuit, so they still
+//! require the circuit-friendly Poseidon transcript:
+//! - The NIFS challenges of the folding schemes (Nova, HyperNova, ProtoGalaxy, Ova), which are
+//!   recomputed by their augmented circuits, as well as the CycleFold challenges.
+//! - The hashes of the running instances (`u_i.x`) of the IVC, which are public inputs of the
+//!   augmented circuits.
+//! - The challenges of the deciders, which are computed in the decider circuits (and then exposed
+//!   as their public inputs, so that the onchain verifiers do not recompute them).
+//!
+//! The transcripts that are only used natively can be selected, such as the one of Nova's zk layer
+//! (see `RandomizedIVCProof::new_with_transcript` in `folding::nova::zk`).
 use ark_crypto_primitives::sponge::{constraints::CryptographicSpongeVar, CryptographicSponge};
 use ark_ec::CurveGroup;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar};
 use ark_relations::r1cs::SynthesisError;
 
+pub mod keccak;
 pub mod poseidon;
 
 /// An interface for objects that can be absorbed by a `Transcript`.
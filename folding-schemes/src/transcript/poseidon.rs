@@ -1,16 +1,30 @@
-use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{
-        constraints::PoseidonSpongeVar, find_poseidon_ark_and_mds, PoseidonConfig, PoseidonSponge,
+use ark_crypto_primitives::{
+    crh::{
+        poseidon::{
+            constraints::{CRHGadget, CRHParametersVar},
+            CRH,
+        },
+        CRHScheme, CRHSchemeGadget,
+    },
+    sponge::{
+        constraints::CryptographicSpongeVar,
+        poseidon::{
+            constraints::PoseidonSpongeVar, find_poseidon_ark_and_mds, PoseidonConfig,
+            PoseidonSponge,
+        },
+        Absorb, CryptographicSponge,
     },
-    Absorb, CryptographicSponge,
 };
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, PrimeField};
-use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar};
-use ark_relations::r1cs::SynthesisError;
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, fields::fp::FpVar, groups::CurveVar};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Validate, Write,
+};
 
 use super::{AbsorbNonNative, AbsorbNonNativeGadget, Transcript, TranscriptVar};
+use crate::Error;
 
 impl<F: PrimeField + Absorb> Transcript<F> for PoseidonSponge<F> {
     // Compatible with the in-circuit `TranscriptVar::absorb_point`
@@ -100,7 +114,8 @@ pub fn poseidon_custom_config<F: PrimeField>(
     PoseidonConfig::new(full_rounds, partial_rounds, alpha, mds, ark, rate, capacity)
 }
 
-/// This Poseidon configuration generator agrees with Circom's Poseidon(4) in the case of BN254's scalar field
+/// This Poseidon configuration generator agrees with Circom's Poseidon(4) in the case of BN254's scalar field.
+/// Prefer [`poseidon_config_for`], which errors for the fields whose parameters have not been checked.
 pub fn poseidon_canonical_config<F: PrimeField>() -> PoseidonConfig<F> {
     // 120 bit security target as in
     // https://eprint.iacr.org/2019/458.pdf
@@ -114,20 +129,192 @@ pub fn poseidon_canonical_config<F: PrimeField>() -> PoseidonConfig<F> {
     poseidon_custom_config(full_rounds, partial_rounds, alpha, rate, 1)
 }
 
+/// Returns the number of partial rounds of the configurations of [`poseidon_config_with_width`]
+/// for the state width `t`, and fails (at compile time when evaluated in a const context) for the
+/// unsupported widths.
+const fn poseidon_partial_rounds(t: usize) -> usize {
+    match t {
+        3 => 57,
+        5 => 60,
+        9 => 63,
+        _ => panic!("unsupported Poseidon state width, the supported ones are 3, 5 and 9"),
+    }
+}
+
+/// Returns the Poseidon configuration of state width `T` (rate `T - 1` and capacity 1), with the
+/// `x^5` S-box and the round numbers of table 2 of the
+/// [Poseidon paper](https://eprint.iacr.org/2019/458.pdf) for fields of 254 and 255 bits (which
+/// include its security margins): 8 full rounds, and 57, 60 and 63 partial rounds for `T` equal
+/// to 3, 5 and 9 respectively. The other widths do not compile.
+///
+/// `T = 5` gives the configuration of [`poseidon_canonical_config`]. A wider state absorbs more
+/// elements per permutation, which reduces the constraints of hashing long inputs, e.g. the
+/// hashes of the instances of the augmented circuits for long IVC states.
+pub fn poseidon_config_with_width<F: PrimeField, const T: usize>() -> PoseidonConfig<F> {
+    let partial_rounds = const { poseidon_partial_rounds(T) };
+    poseidon_custom_config(8, partial_rounds, 5, T - 1, 1)
+}
+
+/// Serializes the parameters of the Poseidon `config`, in the order in which they are hashed by
+/// [`crate::utils::pp_hash`]: `full_rounds`, `partial_rounds`, `alpha`, `ark`, `mds`, `rate` and
+/// `capacity`.
+pub fn serialize_poseidon_config<F: PrimeField, W: Write>(
+    config: &PoseidonConfig<F>,
+    mut writer: W,
+    compress: Compress,
+) -> Result<(), SerializationError> {
+    config
+        .full_rounds
+        .serialize_with_mode(&mut writer, compress)?;
+    config
+        .partial_rounds
+        .serialize_with_mode(&mut writer, compress)?;
+    config.alpha.serialize_with_mode(&mut writer, compress)?;
+    config.ark.serialize_with_mode(&mut writer, compress)?;
+    config.mds.serialize_with_mode(&mut writer, compress)?;
+    config.rate.serialize_with_mode(&mut writer, compress)?;
+    config.capacity.serialize_with_mode(&mut writer, compress)
+}
+
+/// Returns the size of the serialization of `config` by [`serialize_poseidon_config`].
+pub fn poseidon_config_serialized_size<F: PrimeField>(
+    config: &PoseidonConfig<F>,
+    compress: Compress,
+) -> usize {
+    config.full_rounds.serialized_size(compress)
+        + config.partial_rounds.serialized_size(compress)
+        + config.alpha.serialized_size(compress)
+        + config.ark.serialized_size(compress)
+        + config.mds.serialized_size(compress)
+        + config.rate.serialized_size(compress)
+        + config.capacity.serialized_size(compress)
+}
+
+/// Deserializes a Poseidon configuration serialized by [`serialize_poseidon_config`].
+pub fn deserialize_poseidon_config<F: PrimeField, R: Read>(
+    mut reader: R,
+    compress: Compress,
+    validate: Validate,
+) -> Result<PoseidonConfig<F>, SerializationError> {
+    let full_rounds = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+    let partial_rounds = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+    let alpha = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+    let ark = Vec::<Vec<F>>::deserialize_with_mode(&mut reader, compress, validate)?;
+    let mds = Vec::<Vec<F>>::deserialize_with_mode(&mut reader, compress, validate)?;
+    let rate = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+    let capacity = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+    if ark.len() != full_rounds + partial_rounds
+        || mds.len() != rate + capacity
+        || ark
+            .iter()
+            .chain(&mds)
+            .any(|row| row.len() != rate + capacity)
+    {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(PoseidonConfig::new(
+        full_rounds,
+        partial_rounds,
+        alpha,
+        mds,
+        ark,
+        rate,
+        capacity,
+    ))
+}
+
+/// The fields for which [`poseidon_config_for`] provides a Poseidon configuration, by name and
+/// big-endian hexadecimal modulus.
+pub const POSEIDON_SUPPORTED_FIELDS: [(&str, &str); 5] = [
+    (
+        "BN254 scalar field",
+        "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+    ),
+    (
+        "BN254 base field (Grumpkin scalar field)",
+        "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47",
+    ),
+    (
+        "Pallas scalar field (Vesta base field)",
+        "40000000000000000000000000000000224698fc0994a8dd8c46eb2100000001",
+    ),
+    (
+        "Vesta scalar field (Pallas base field)",
+        "40000000000000000000000000000000224698fc094cf91b992d30ed00000001",
+    ),
+    (
+        "BLS12-381 scalar field",
+        "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+    ),
+];
+
+/// Returns the Poseidon configuration of [`poseidon_canonical_config`] for the fields whose
+/// parameters have been checked, listed in [`POSEIDON_SUPPORTED_FIELDS`], and an error for the
+/// other ones.
+///
+/// For all of them, the state width is `t = 5` (rate 4, capacity 1), the S-box is `x^5`, which is a
+/// permutation of these fields, and there are 8 full and 60 partial rounds, the round numbers of
+/// table 2 of the [Poseidon paper](https://eprint.iacr.org/2019/458.pdf) for `t = 5` and fields of
+/// 254 and 255 bits. The round constants and the MDS matrix are generated with the Grain LFSR of
+/// the reference implementation, and can be reproduced independently of arkworks with
+/// `scripts/poseidon_params.py`, which also prints the known-answer values of the tests.
+pub fn poseidon_config_for<F: PrimeField>() -> Result<PoseidonConfig<F>, Error> {
+    let modulus: String = F::MODULUS
+        .to_bytes_be()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if !POSEIDON_SUPPORTED_FIELDS
+        .iter()
+        .any(|(_, supported)| *supported == modulus)
+    {
+        return Err(Error::NotSupported(format!(
+            "Poseidon configuration for the field of modulus 0x{}",
+            modulus
+        )));
+    }
+    Ok(poseidon_canonical_config())
+}
+
+/// Hashes `inputs` with the Poseidon CRH of `config`, e.g. of [`poseidon_canonical_config`]. This is
+/// the native counterpart of [`poseidon_hash_gadget`], with which verifiers recompute the digests
+/// computed in-circuit.
+///
+/// Notice that the inputs are absorbed without their length, so that inputs differing only by
+/// trailing zeros (eg. `[x]` and `[x, 0]`) have the same hash. Callers hashing inputs of variable
+/// length have to include the length in the inputs, eg. as their first element.
+pub fn poseidon_hash<F: PrimeField + Absorb>(
+    config: &PoseidonConfig<F>,
+    inputs: &[F],
+) -> Result<F, Error> {
+    CRH::<F>::evaluate(config, inputs).map_err(|_| Error::EvaluationFail)
+}
+
+/// Hashes `inputs` in-circuit with the Poseidon CRH of `config`, whose output is the one of
+/// [`poseidon_hash`] on the values of `inputs`. As the latter, it does not separate inputs of
+/// different lengths.
+pub fn poseidon_hash_gadget<F: PrimeField + Absorb>(
+    cs: ConstraintSystemRef<F>,
+    config: &PoseidonConfig<F>,
+    inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let params = CRHParametersVar::<F>::new_constant(cs, config)?;
+    CRHGadget::<F>::evaluate(&params, inputs)
+}
+
 #[cfg(test)]
 pub mod tests {
     use ark_bn254::{constraints::GVar, g1::Config, Fq, Fr, G1Projective as G1};
     use ark_ec::PrimeGroup;
     use ark_ff::UniformRand;
     use ark_r1cs_std::{
-        alloc::AllocVar, groups::curves::short_weierstrass::ProjectiveVar, R1CSVar,
+        fields::FieldVar, groups::curves::short_weierstrass::ProjectiveVar, R1CSVar,
     };
     use ark_relations::r1cs::ConstraintSystem;
     use ark_std::test_rng;
 
     use super::*;
     use crate::folding::circuits::nonnative::affine::NonNativeAffineVar;
-    use crate::Error;
 
     // Test with value taken from https://github.com/iden3/circomlibjs/blob/43cc582b100fc3459cf78d903a6f538e5d7f38ee/test/poseidon.js#L32
     #[test]
@@ -164,6 +351,100 @@ pub mod tests {
         Ok(())
     }
 
+    /// Checks the Poseidon hashes of `[1, 2, 3, 4]` and `[1, 2, 3, 4, 5]` with the configuration of
+    /// [`poseidon_config_for`] against the values given by `scripts/poseidon_params.py`.
+    fn check_poseidon_known_answers<F: PrimeField + Absorb>(
+        hash_4: &str,
+        hash_5: &str,
+    ) -> Result<(), Error> {
+        let config = poseidon_config_for::<F>()?;
+        let inputs: Vec<F> = (1..=5_u64).map(F::from).collect();
+        for (inputs, expected) in [(&inputs[..4], hash_4), (&inputs[..], hash_5)] {
+            let expected = F::from_str(expected).map_err(|_| {
+                Error::ConversionError("str".to_string(), "F".to_string(), expected.to_string())
+            })?;
+            assert_eq!(poseidon_hash(&config, inputs)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon_config_for_known_answers() -> Result<(), Error> {
+        check_poseidon_known_answers::<Fr>(
+            "7817711165059374331357136443537800893307845083525445872661165200086166013245",
+            "2006158340318488760025443826385193554248487533762005435528482214650964641219",
+        )?;
+        check_poseidon_known_answers::<Fq>(
+            "12966747000467892474428216991452664010806050265020116619471743338097592006837",
+            "5560524468812587680311701651769518889373608622650446344438884885157424103370",
+        )?;
+        check_poseidon_known_answers::<ark_pallas::Fr>(
+            "13165277847464490598479962973119952866051031494292164086128871304700352428663",
+            "26517016495227133822772434962145314221041667412850897944991033484734950182281",
+        )?;
+        check_poseidon_known_answers::<ark_vesta::Fr>(
+            "15910357140900815403146120202838418147950138322348554477919783506456815019990",
+            "25544706892909348657492964282529899487676165119137799247807027016412013012585",
+        )?;
+        check_poseidon_known_answers::<ark_bls12_381::Fr>(
+            "46100521179547351030890356806707858465640857221128207179269773056079077808119",
+            "27783108741496982328403161095734076945072442982761142563621311396588599705451",
+        )?;
+
+        // the fields whose parameters have not been checked are not supported
+        assert!(matches!(
+            poseidon_config_for::<ark_mnt4_298::Fr>(),
+            Err(Error::NotSupported(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon_config_with_width() -> Result<(), Error> {
+        // the width 5 gives the canonical configuration
+        let canonical = poseidon_canonical_config::<Fr>();
+        let config = poseidon_config_with_width::<Fr, 5>();
+        assert_eq!(config.ark, canonical.ark);
+        assert_eq!(config.mds, canonical.mds);
+
+        let inputs: Vec<Fr> = (0..28_u64).map(Fr::from).collect();
+        let mut num_constraints = vec![];
+        for config in [
+            poseidon_config_with_width::<Fr, 3>(),
+            poseidon_config_with_width::<Fr, 5>(),
+            poseidon_config_with_width::<Fr, 9>(),
+        ] {
+            assert_eq!(config.rate + config.capacity, config.mds.len());
+            assert_eq!(config.ark.len(), config.full_rounds + config.partial_rounds);
+
+            // the serialization round-trips
+            let mut bytes = vec![];
+            serialize_poseidon_config(&config, &mut bytes, Compress::Yes)?;
+            assert_eq!(
+                bytes.len(),
+                poseidon_config_serialized_size(&config, Compress::Yes)
+            );
+            let deserialized =
+                deserialize_poseidon_config::<Fr, _>(&bytes[..], Compress::Yes, Validate::Yes)?;
+            assert_eq!(deserialized.ark, config.ark);
+            assert_eq!(deserialized.mds, config.mds);
+
+            // and the hash gadget matches the native hash
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let inputs_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone()))?;
+            let h_var = poseidon_hash_gadget(cs.clone(), &config, &inputs_var)?;
+            assert_eq!(h_var.value()?, poseidon_hash(&config, &inputs)?);
+            num_constraints.push(cs.num_constraints());
+        }
+        println!(
+            "hash of 28 elements with t = 3, 5, 9: {:?} constraints",
+            num_constraints
+        );
+        // a wider state hashes long inputs with fewer constraints
+        assert!(num_constraints[0] > num_constraints[1] && num_constraints[1] > num_constraints[2]);
+        Ok(())
+    }
+
     #[test]
     fn test_transcript_and_transcriptvar_absorb_native_point() -> Result<(), Error> {
         // use 'native' transcript
@@ -234,6 +515,35 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_transcript_and_transcriptvar_bls12_381() -> Result<(), Error> {
+        use ark_bls12_381::{Fr as BlsFr, G1Projective as BlsG1};
+
+        // use 'native' transcript over BLS12-381's scalar field
+        let config = poseidon_canonical_config::<BlsFr>();
+        let mut tr = PoseidonSponge::<BlsFr>::new(&config);
+        let rng = &mut test_rng();
+
+        let p = BlsG1::rand(rng);
+        tr.absorb(&BlsFr::from(42_u32));
+        tr.absorb_nonnative(&p);
+        let c = tr.get_challenge();
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<BlsFr>::new_ref();
+        let mut tr_var = PoseidonSpongeVar::<BlsFr>::new(cs.clone(), &config);
+        let v = FpVar::<BlsFr>::new_witness(cs.clone(), || Ok(BlsFr::from(42_u32)))?;
+        let p_var = NonNativeAffineVar::<BlsG1>::new_witness(cs.clone(), || Ok(p))?;
+        tr_var.absorb(&v)?;
+        tr_var.absorb_nonnative(&p_var)?;
+        let c_var = tr_var.get_challenge()?;
+
+        // assert that native & gadget transcripts return the same challenge
+        assert_eq!(c, c_var.value()?);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
     #[test]
     fn test_transcript_and_transcriptvar_nbits() -> Result<(), Error> {
         let nbits = crate::constants::NOVA_N_BITS_RO;
@@ -276,4 +586,40 @@ pub mod tests {
         assert_eq!(cP_native_bits.into_affine(), cPVar.value()?.into_affine());
         Ok(())
     }
+
+    #[test]
+    fn test_poseidon_hash_gadget() -> Result<(), Error> {
+        let config = poseidon_canonical_config::<Fr>();
+        let mut rng = test_rng();
+        for len in [1, 2, 3, 4, 5, 8, 17] {
+            let inputs: Vec<Fr> = (0..len).map(|_| Fr::rand(&mut rng)).collect();
+            let h = poseidon_hash(&config, &inputs)?;
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let inputs_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(inputs.clone()))?;
+            let h_var = poseidon_hash_gadget(cs.clone(), &config, &inputs_var)?;
+            assert_eq!(h_var.value()?, h);
+            assert!(cs.is_satisfied()?);
+
+            // the constant inputs give the same digest
+            let constants: Vec<FpVar<Fr>> = inputs.iter().map(|&x| FpVar::constant(x)).collect();
+            assert_eq!(
+                poseidon_hash_gadget(cs.clone(), &config, &constants)?.value()?,
+                h
+            );
+        }
+
+        // the length of the inputs is not absorbed, so that trailing zeros give the same digest,
+        // unless the length is hashed along with the inputs
+        let (x, zero) = (Fr::rand(&mut rng), Fr::from(0u32));
+        assert_eq!(
+            poseidon_hash(&config, &[x])?,
+            poseidon_hash(&config, &[x, zero])?
+        );
+        assert_ne!(
+            poseidon_hash(&config, &[Fr::from(1u32), x])?,
+            poseidon_hash(&config, &[Fr::from(2u32), x, zero])?
+        );
+        Ok(())
+    }
 }
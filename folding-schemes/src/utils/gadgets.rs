@@ -67,7 +67,7 @@ impl<F: PrimeField> VectorGadget<FpVar<F>> for [FpVar<F>] {
 pub struct SparseMatrixVar<FV> {
     pub n_rows: usize,
     pub n_cols: usize,
-    // same format as the native SparseMatrix (which follows ark_relations::r1cs::Matrix format
+    // the rows of the native SparseMatrix, in the ark_relations::r1cs::Matrix format
     pub coeffs: Vec<Vec<(FV, usize)>>,
 }
 
@@ -83,7 +83,7 @@ impl<F: PrimeField, CF: PrimeField, FV: AllocVar<F, CF>> AllocVar<SparseMatrix<F
             let cs = cs.into();
 
             let mut coeffs: Vec<Vec<(FV, usize)>> = Vec::new();
-            for row in val.borrow().coeffs.iter() {
+            for row in val.borrow().rows() {
                 let mut rowVar: Vec<(FV, usize)> = Vec::new();
                 for &(value, col_i) in row.iter() {
                     let coeffVar = FV::new_variable(cs.clone(), || Ok(value), mode)?;
@@ -0,0 +1,370 @@
+//! JSON representations of the proofs and of their public data (committed instances, states), for
+//! clients that can not use arkworks' `CanonicalSerialize` encodings, eg. web verifiers or REST
+//! APIs. Only available with the `serde` feature.
+//!
+//! All the values are encoded as JSON strings of 0x-prefixed lowercase hex:
+//! - field elements are encoded as the big-endian bytes of their canonical representative, padded
+//!   to `ceil(MODULUS_BIT_SIZE / 8)` bytes. When parsing, the length must match and the value must
+//!   be smaller than the modulus.
+//! - curve points, and other arkworks values such as the SNARK proofs or the commitment scheme
+//!   proofs, are encoded with their compressed `CanonicalSerialize` encoding (for the short
+//!   Weierstrass curves, the little-endian `x` coordinate with the sign of `y` and the point at
+//!   infinity flagged in the most significant bits). When parsing, the encoding must be complete
+//!   and valid, which includes checking that the points are in the prime order subgroup.
+//! - vectors and arrays are JSON arrays of the encodings of their elements.
+//!
+//! Structs are JSON objects with the names of their Rust fields as keys. See
+//! `folding-schemes/src/folding/nova/ivc_proof_fixture.json` for an example of a Nova `IVCProof`.
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use num_bigint::BigUint;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Write;
+
+use crate::Error;
+
+/// `to_json` and `from_json` helpers for the types that implement serde's traits (which, with the
+/// `serde` feature, includes the proofs and the committed instances).
+pub trait JsonSerde: Serialize + DeserializeOwned {
+    fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(|e| Error::JSONSerdeError(e.to_string()))
+    }
+
+    fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| Error::JSONSerdeError(e.to_string()))
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> JsonSerde for T {}
+
+/// returns the JSON array of the encodings of the given field elements, eg. the states `z_i`
+pub fn fields_to_json<F: PrimeField>(v: &[F]) -> Result<String, Error> {
+    let mut json = Vec::new();
+    fields::serialize(v, &mut serde_json::Serializer::new(&mut json))
+        .map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+    String::from_utf8(json).map_err(|e| Error::JSONSerdeError(e.to_string()))
+}
+
+/// parses a JSON array of field elements, see [`fields_to_json`]
+pub fn fields_from_json<F: PrimeField>(json: &str) -> Result<Vec<F>, Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let v = fields::deserialize(&mut deserializer)
+        .and_then(|v| deserializer.end().map(|_| v))
+        .map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+    Ok(v)
+}
+
+/// returns the JSON array of the encodings of the given points, eg. the outputs of
+/// [`crate::folding::traits::CommittedInstanceOps::get_commitments`]
+pub fn points_to_json<C: CanonicalSerialize + CanonicalDeserialize>(
+    v: &[C],
+) -> Result<String, Error> {
+    let mut json = Vec::new();
+    canonicals::serialize(v, &mut serde_json::Serializer::new(&mut json))
+        .map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+    String::from_utf8(json).map_err(|e| Error::JSONSerdeError(e.to_string()))
+}
+
+/// parses a JSON array of points, see [`points_to_json`]
+pub fn points_from_json<C: CanonicalSerialize + CanonicalDeserialize>(
+    json: &str,
+) -> Result<Vec<C>, Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let v = canonicals::deserialize(&mut deserializer)
+        .and_then(|v| deserializer.end().map(|_| v))
+        .map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+    Ok(v)
+}
+
+/// The vectors and arrays, which are encoded as JSON arrays. When parsing an array, the length of
+/// the JSON array has to match.
+pub trait Seq: Sized {
+    type Item;
+    fn from_vec(v: Vec<Self::Item>) -> Option<Self>;
+}
+
+impl<T> Seq for Vec<T> {
+    type Item = T;
+    fn from_vec(v: Vec<T>) -> Option<Self> {
+        Some(v)
+    }
+}
+
+impl<T, const N: usize> Seq for [T; N] {
+    type Item = T;
+    fn from_vec(v: Vec<T>) -> Option<Self> {
+        v.try_into().ok()
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + 2 * bytes.len());
+    s.push_str("0x");
+    for b in bytes {
+        // writing into a String does not fail
+        let _ = write!(s, "{:02x}", b);
+    }
+    s
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>, String> {
+    let digits = s
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("{} is not 0x-prefixed", s))?;
+    if digits.len() % 2 != 0 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("{} is not a hex encoding of bytes", s));
+    }
+    // the digits are ASCII, so they can be sliced at any position
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// returns the hex encoding of the field element
+pub fn field_to_hex<F: PrimeField>(f: &F) -> String {
+    let n_bytes = (F::MODULUS_BIT_SIZE as usize).div_ceil(8);
+    let bytes = f.into_bigint().to_bytes_be();
+    to_hex(&bytes[bytes.len() - n_bytes..])
+}
+
+/// parses the hex encoding of a field element, which has to be canonical
+pub fn field_from_hex<F: PrimeField>(s: &str) -> Result<F, String> {
+    let bytes = from_hex(s)?;
+    let n_bytes = (F::MODULUS_BIT_SIZE as usize).div_ceil(8);
+    if bytes.len() != n_bytes {
+        return Err(format!(
+            "{} has {} bytes, expected {}",
+            s,
+            bytes.len(),
+            n_bytes
+        ));
+    }
+    if BigUint::from_bytes_be(&bytes) >= BigUint::from_bytes_be(&F::MODULUS.to_bytes_be()) {
+        return Err(format!("{} is not smaller than the modulus", s));
+    }
+    Ok(F::from_be_bytes_mod_order(&bytes))
+}
+
+/// returns the hex encoding of the compressed serialization of `v`
+pub fn canonical_to_hex<T: CanonicalSerialize>(v: &T) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    v.serialize_compressed(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    Ok(to_hex(&bytes))
+}
+
+/// parses the hex encoding of the compressed serialization of a value, validating it (eg. the
+/// subgroup checks of the points) and checking that there are no trailing bytes
+pub fn canonical_from_hex<T: CanonicalDeserialize>(s: &str) -> Result<T, String> {
+    let bytes = from_hex(s)?;
+    let mut reader = &bytes[..];
+    let v = T::deserialize_with_mode(&mut reader, Compress::Yes, Validate::Yes)
+        .map_err(|e| format!("{} is not a valid encoding: {}", s, e))?;
+    if !reader.is_empty() {
+        return Err(format!("{} has {} trailing bytes", s, reader.len()));
+    }
+    Ok(v)
+}
+
+/// serde `with` module for the field elements
+pub mod field {
+    use super::*;
+    use serde::de::Error;
+
+    pub fn serialize<F: PrimeField, S: Serializer>(f: &F, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&field_to_hex(f))
+    }
+
+    pub fn deserialize<'de, F: PrimeField, D: Deserializer<'de>>(d: D) -> Result<F, D::Error> {
+        field_from_hex(&String::deserialize(d)?).map_err(D::Error::custom)
+    }
+}
+
+/// serde `with` module for the vectors and arrays of field elements
+pub mod fields {
+    use super::*;
+    use serde::de::Error;
+
+    pub fn serialize<F: PrimeField, S: Serializer>(v: &[F], s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(v.iter().map(field_to_hex))
+    }
+
+    pub fn deserialize<'de, F, V, D>(d: D) -> Result<V, D::Error>
+    where
+        F: PrimeField,
+        V: Seq<Item = F>,
+        D: Deserializer<'de>,
+    {
+        let v = Vec::<String>::deserialize(d)?
+            .iter()
+            .map(|s| field_from_hex(s))
+            .collect::<Result<Vec<F>, _>>()
+            .map_err(D::Error::custom)?;
+        let len = v.len();
+        V::from_vec(v)
+            .ok_or_else(|| D::Error::invalid_length(len, &"an array of the expected length"))
+    }
+}
+
+/// serde `with` module for the points and other arkworks values
+pub mod canonical {
+    use super::*;
+    use serde::{de::Error as _, ser::Error as _};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(v: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&canonical_to_hex(v).map_err(S::Error::custom)?)
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+        d: D,
+    ) -> Result<T, D::Error> {
+        canonical_from_hex(&String::deserialize(d)?).map_err(D::Error::custom)
+    }
+}
+
+/// serde `with` module for the vectors and arrays of points and other arkworks values
+pub mod canonicals {
+    use super::*;
+    use serde::{de::Error as _, ser::Error as _};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+        v: &[T],
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        let v = v
+            .iter()
+            .map(canonical_to_hex)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(S::Error::custom)?;
+        s.collect_seq(v)
+    }
+
+    pub fn deserialize<'de, T, V, D>(d: D) -> Result<V, D::Error>
+    where
+        T: CanonicalDeserialize,
+        V: Seq<Item = T>,
+        D: Deserializer<'de>,
+    {
+        let v = Vec::<String>::deserialize(d)?
+            .iter()
+            .map(|s| canonical_from_hex(s))
+            .collect::<Result<Vec<T>, _>>()
+            .map_err(D::Error::custom)?;
+        let len = v.len();
+        V::from_vec(v)
+            .ok_or_else(|| D::Error::invalid_length(len, &"an array of the expected length"))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective};
+    use ark_ec::{AffineRepr, PrimeGroup};
+    use ark_std::{One, UniformRand, Zero};
+
+    // BN254's scalar field modulus
+    const R: &str = "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+
+    #[test]
+    fn test_fields_json() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let v: Vec<Fr> = (0..5).map(|_| Fr::rand(&mut rng)).collect();
+        assert_eq!(fields_from_json::<Fr>(&fields_to_json(&v)?)?, v);
+
+        assert_eq!(field_to_hex(&Fr::one()), format!("0x{:0>64}", "1"));
+        assert_eq!(field_to_hex(&-Fr::one()), format!("{}0", &R[..R.len() - 1]));
+        assert_eq!(
+            field_from_hex::<Fr>(&field_to_hex(&-Fr::one())),
+            Ok(-Fr::one())
+        );
+
+        // not 0x-prefixed
+        assert!(field_from_hex::<Fr>(&R[2..]).is_err());
+        // wrong lengths and not hex
+        assert!(field_from_hex::<Fr>("0x01").is_err());
+        assert!(field_from_hex::<Fr>(&R[..R.len() - 1]).is_err());
+        assert!(field_from_hex::<Fr>(&format!("{}00", R)).is_err());
+        assert!(field_from_hex::<Fr>(&format!("0x{}", "zz".repeat(32))).is_err());
+        // not canonical
+        assert!(field_from_hex::<Fr>(R).is_err());
+        assert!(field_from_hex::<Fr>(&format!("0x{}", "ff".repeat(32))).is_err());
+
+        assert!(matches!(
+            fields_from_json::<Fr>(&format!("[\"{}\"]", R)),
+            Err(Error::JSONSerdeError(_))
+        ));
+        assert!(matches!(
+            fields_from_json::<Fr>("[\"0x01\"] trailing"),
+            Err(Error::JSONSerdeError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrays_json() {
+        let json = format!("[\"{}\"]", field_to_hex(&Fr::one()));
+        let v: Result<[Fr; 1], _> =
+            fields::deserialize(&mut serde_json::Deserializer::from_str(&json));
+        assert_eq!(v.ok(), Some([Fr::one()]));
+        // the length of the JSON array has to match the one of the Rust array
+        let v: Result<[Fr; 2], _> =
+            fields::deserialize(&mut serde_json::Deserializer::from_str(&json));
+        assert!(v.is_err());
+    }
+
+    #[test]
+    fn test_points_json() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let g = G1Projective::generator();
+        let v = vec![G1Projective::rand(&mut rng), g, -g, G1Projective::zero()];
+        assert_eq!(points_from_json::<G1Projective>(&points_to_json(&v)?)?, v);
+
+        // the compressed encodings of the generator (1, 2), of its negation and of the point at
+        // infinity
+        let g_hex = format!("0x01{}", "00".repeat(31));
+        assert_eq!(canonical_to_hex(&g), Ok(g_hex.clone()));
+        assert_eq!(
+            canonical_to_hex(&-g),
+            Ok(format!("0x01{}80", "00".repeat(30)))
+        );
+        assert_eq!(
+            canonical_to_hex(&G1Projective::zero()),
+            Ok(format!("0x{}40", "00".repeat(31)))
+        );
+        assert_eq!(canonical_from_hex::<G1Projective>(&g_hex), Ok(g));
+
+        // trailing bytes
+        assert!(canonical_from_hex::<G1Projective>(&format!("{}00", g_hex)).is_err());
+        // x not smaller than the modulus of the base field
+        assert!(canonical_from_hex::<G1Projective>(&format!("0x{}3f", "ff".repeat(31))).is_err());
+        // both the negative and the infinity flags set
+        assert!(canonical_from_hex::<G1Projective>(&format!("0x{}c0", "00".repeat(31))).is_err());
+        // truncated
+        assert!(canonical_from_hex::<G1Projective>(&g_hex[..g_hex.len() - 2]).is_err());
+        Ok(())
+    }
+
+    /// points that are on the curve but not in the prime order subgroup are rejected
+    #[test]
+    fn test_points_json_subgroup_check() {
+        use ark_bls12_381::{Fq, G1Affine};
+
+        let p = (1u64..)
+            .filter_map(|x| G1Affine::get_point_from_x_unchecked(Fq::from(x), false))
+            .find(|p| !p.is_in_correct_subgroup_assuming_on_curve())
+            .unwrap();
+        assert!(p.is_on_curve());
+        let hex = canonical_to_hex(&p).unwrap();
+        assert!(canonical_from_hex::<G1Affine>(&hex).is_err());
+
+        // while the points of the subgroup are accepted
+        let q = p.mul_by_cofactor();
+        assert!(q.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(
+            canonical_from_hex::<G1Affine>(&canonical_to_hex(&q).unwrap()),
+            Ok(q)
+        );
+    }
+}
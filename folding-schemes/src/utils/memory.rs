@@ -0,0 +1,144 @@
+//! Accounting of the memory used by the main allocations of the provers (witness vectors, cross
+//! terms, commitment keys), which allows to find out which of them dominate the memory usage of a
+//! fold. It is only available with the `memory-tracking` feature.
+use ark_serialize::CanonicalSerialize;
+use std::collections::BTreeMap;
+
+/// Keeps track of the sizes (in bytes) of a set of labeled allocations, and of the peak of their
+/// total size. The sizes are the ones of the payload of the allocations (eg. `len *
+/// size_of::<F>()` for a vector of field elements), so they are a lower bound of the memory that
+/// is actually used.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryTracker {
+    allocations: BTreeMap<&'static str, usize>,
+    current: usize,
+    peak: usize,
+}
+
+impl MemoryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// sets the size of the allocation `label`, replacing its previous size if it was already
+    /// registered
+    pub fn set(&mut self, label: &'static str, bytes: usize) {
+        let old = self.allocations.insert(label, bytes).unwrap_or(0);
+        self.current = self.current - old + bytes;
+        self.peak = self.peak.max(self.current);
+    }
+
+    /// sets the size of the allocation `label` to the size of the elements of `v`
+    pub fn set_slice<T>(&mut self, label: &'static str, v: &[T]) {
+        self.set(label, std::mem::size_of_val(v));
+    }
+
+    /// sets the size of the allocation `label` to the uncompressed serialized size of `v`, which
+    /// is used for the commitment keys
+    pub fn set_serializable(&mut self, label: &'static str, v: &impl CanonicalSerialize) {
+        self.set(label, v.uncompressed_size());
+    }
+
+    /// removes the allocation `label`, eg. once a temporary buffer has been dropped
+    pub fn release(&mut self, label: &'static str) {
+        if let Some(bytes) = self.allocations.remove(label) {
+            self.current -= bytes;
+        }
+    }
+
+    /// resets the peak to the current total size, so that the next peak only accounts for the
+    /// allocations done from now on
+    pub fn reset_peak(&mut self) {
+        self.peak = self.current;
+    }
+
+    /// total size of the allocations currently registered
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// peak of the total size of the registered allocations since the last `reset_peak`
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// size of the allocation `label`, if it is registered
+    pub fn get(&self, label: &str) -> Option<usize> {
+        self.allocations.get(label).copied()
+    }
+
+    /// registered allocations, sorted by label
+    pub fn allocations(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.allocations
+            .iter()
+            .map(|(label, bytes)| (*label, *bytes))
+    }
+}
+
+/// Memory statistics of the last call to `prove_step`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProveStepStats {
+    /// total size of the tracked allocations at the end of the step
+    pub tracked_bytes: usize,
+    /// peak of the total size of the tracked allocations during the step
+    pub peak_tracked_bytes: usize,
+    /// tracked allocations at the end of the step, sorted by label
+    pub allocations: Vec<(&'static str, usize)>,
+    /// peak resident set size of the process so far, see [`peak_rss_bytes`]
+    pub peak_rss_bytes: Option<usize>,
+}
+
+impl ProveStepStats {
+    pub fn new(tracker: &MemoryTracker) -> Self {
+        Self {
+            tracked_bytes: tracker.current(),
+            peak_tracked_bytes: tracker.peak(),
+            allocations: tracker.allocations().collect(),
+            peak_rss_bytes: peak_rss_bytes(),
+        }
+    }
+}
+
+/// Returns the peak resident set size of the process (`VmHWM`), which accounts for all the
+/// allocations and not only the tracked ones. Only available on Linux, `None` elsewhere.
+pub fn peak_rss_bytes() -> Option<usize> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+        // the line has the form `VmHWM:    1234 kB`
+        let kb: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb * 1024)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_pallas::Fr;
+
+    #[test]
+    fn test_memory_tracker() {
+        let mut tracker = MemoryTracker::new();
+        let v = vec![Fr::from(1u64); 100];
+        tracker.set_slice("W", &v);
+        tracker.set("T", 1000);
+        assert_eq!(tracker.get("W"), Some(100 * std::mem::size_of::<Fr>()));
+        assert_eq!(tracker.current(), 100 * std::mem::size_of::<Fr>() + 1000);
+
+        // releasing and replacing allocations keeps the peak
+        let peak = tracker.current();
+        tracker.release("T");
+        tracker.set("W", 10);
+        assert_eq!(tracker.current(), 10);
+        assert_eq!(tracker.peak(), peak);
+        assert_eq!(tracker.allocations().collect::<Vec<_>>(), vec![("W", 10)]);
+
+        tracker.reset_peak();
+        assert_eq!(tracker.peak(), 10);
+    }
+}
@@ -8,8 +8,7 @@ use super::vec::SparseMatrix;
 /// Pad matrix so that its columns and rows are powers of two
 pub fn pad_matrix<F: PrimeField>(m: &SparseMatrix<F>) -> SparseMatrix<F> {
     let mut r = m.clone();
-    r.n_rows = m.n_rows.next_power_of_two();
-    r.n_cols = m.n_cols.next_power_of_two();
+    r.pad(m.n_rows.next_power_of_two(), m.n_cols.next_power_of_two());
     r
 }
 
@@ -23,7 +22,7 @@ pub fn matrix_to_dense_mle<F: PrimeField>(matrix: SparseMatrix<F>) -> DenseMulti
 
     // build dense vector representing the sparse padded matrix
     let mut v: Vec<F> = vec![F::zero(); padded_matrix.n_rows * padded_matrix.n_cols];
-    for (row_i, row) in padded_matrix.coeffs.iter().enumerate() {
+    for (row_i, row) in padded_matrix.rows().enumerate() {
         for &(value, col_i) in row.iter() {
             v[(padded_matrix.n_cols * row_i) + col_i] = value;
         }
@@ -59,7 +58,7 @@ pub fn matrix_to_mle<F: PrimeField>(m: SparseMatrix<F>) -> SparseMultilinearExte
 
     // build the sparse vec representing the sparse matrix
     let mut v: Vec<(usize, F)> = Vec::new();
-    for (i, row) in m.coeffs.iter().enumerate() {
+    for (i, row) in m.rows().enumerate() {
         for (val, j) in row.iter() {
             v.push((i * n_cols + j, *val));
         }
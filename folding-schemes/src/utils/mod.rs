@@ -13,7 +13,11 @@ use crate::{Curve, Error};
 
 pub mod gadgets;
 pub mod hypercube;
+#[cfg(feature = "serde")]
+pub mod json;
 pub mod lagrange_poly;
+#[cfg(feature = "memory-tracking")]
+pub mod memory;
 pub mod mle;
 pub mod vec;
 
@@ -33,6 +37,18 @@ pub fn powers_of<F: PrimeField>(x: F, n: usize) -> Vec<F> {
     c
 }
 
+/// Converts the given field element into a `u64`, returning `Error::OutOfBounds` if its canonical
+/// representation does not fit in 64 bits. Used to interpret step counters such as the `i` of the
+/// IVC proofs, which are field elements.
+pub fn field_to_u64<F: PrimeField>(x: F) -> Result<u64, Error> {
+    let bigint = x.into_bigint();
+    let limbs = bigint.as_ref();
+    if limbs[1..].iter().any(|limb| *limb != 0) {
+        return Err(Error::OutOfBounds);
+    }
+    Ok(limbs[0])
+}
+
 /// returns the coordinates of a commitment point. This is compatible with the arkworks
 /// GC.to_constraint_field()[..2]
 pub fn get_cm_coordinates<C: Curve>(cm: &C) -> Vec<C::BaseField> {
@@ -40,7 +56,26 @@ pub fn get_cm_coordinates<C: Curve>(cm: &C) -> Vec<C::BaseField> {
     vec![cm_x, cm_y]
 }
 
-/// returns the hash of the given public parameters of the Folding Scheme
+/// returns the hash of the given public parameters of the Folding Scheme.
+///
+/// The hash is computed with SHA3-256 over the concatenation of the following byte strings, in
+/// this order:
+/// 1. `C1::ScalarField::MODULUS_BIT_SIZE`, as a 4-byte little-endian `u32`
+/// 2. `C2::ScalarField::MODULUS_BIT_SIZE`, as a 4-byte little-endian `u32`
+/// 3. `arith.params_to_le_bytes()`, the sizes of the main (augmented) constraint system (for R1CS:
+///    `l`, `n_rows`, `n_cols`, each one as a little-endian `u64`)
+/// 4. `arith.matrices_digest()`, the 32-byte SHA3-256 digest of its matrices (for R1CS: `A`, `B`
+///    and `C`, see [`vec::SparseMatrix::update_hasher`] for their encoding)
+/// 5. `cf_arith.params_to_le_bytes()`, the same as 3. for the CycleFold constraint system
+/// 6. `cf_arith.matrices_digest()`, the same as 4. for the CycleFold constraint system
+/// 7. `cs_vp`, the verifier params of the commitment scheme over `C1`, serialized uncompressed
+/// 8. `cf_cs_vp`, the verifier params of the commitment scheme over `C2`, serialized uncompressed
+/// 9. the Poseidon config, as the uncompressed serialization of `full_rounds`, `partial_rounds`,
+///    `alpha`, `ark`, `mds`, `rate` and `capacity`
+///
+/// The resulting 32-byte digest is interpreted as a little-endian integer and reduced modulo the
+/// order of `C1::ScalarField`. All the integers have a fixed width, so the hash does not depend on
+/// the platform.
 pub fn pp_hash<C1, C2, CS1, CS2, const H: bool>(
     arith: &impl ArithSerializer,
     cf_arith: &impl ArithSerializer,
@@ -59,10 +94,12 @@ where
     // Fr & Fq modulus bit size
     hasher.update(C1::ScalarField::MODULUS_BIT_SIZE.to_le_bytes());
     hasher.update(C2::ScalarField::MODULUS_BIT_SIZE.to_le_bytes());
-    // AugmentedFCircuit Arith params
+    // AugmentedFCircuit Arith params and matrices
     hasher.update(arith.params_to_le_bytes());
-    // CycleFold Circuit Arith params
+    hasher.update(arith.matrices_digest());
+    // CycleFold Circuit Arith params and matrices
     hasher.update(cf_arith.params_to_le_bytes());
+    hasher.update(cf_arith.matrices_digest());
     // cs_vp & cf_cs_vp (commitments setup)
     let mut cs_vp_bytes = Vec::new();
     cs_vp.serialize_uncompressed(&mut cs_vp_bytes)?;
@@ -1,22 +1,34 @@
-use ark_ff::PrimeField;
+use ark_ff::{BigInteger, PrimeField};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, GeneralEvaluationDomain,
 };
 pub use ark_relations::r1cs::Matrix as R1CSMatrix;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::cfg_iter;
 use ark_std::rand::Rng;
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use ark_std::{cfg_chunks, cfg_chunks_mut, cfg_into_iter, cfg_iter, cfg_iter_mut};
+use rayon::{
+    iter::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+        IntoParallelRefMutIterator, ParallelIterator,
+    },
+    slice::{ParallelSlice, ParallelSliceMut},
+};
+use sha3::Digest;
 
 use crate::{folding::traits::Dummy, Error};
 
+/// A sparse matrix in the compressed sparse row (CSR) format: the non-zero coefficients of all the
+/// rows are stored contiguously, in row order, and `row_offsets` delimits the rows.
 #[derive(Clone, Debug, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct SparseMatrix<F: PrimeField> {
     pub n_rows: usize,
     pub n_cols: usize,
-    /// coeffs = R1CSMatrix = Vec<Vec<(F, usize)>>, which contains each row and the F is the value
-    /// of the coefficient and the usize indicates the column position
-    pub coeffs: R1CSMatrix<F>,
+    /// the non-zero coefficients of the matrix, row after row, where the F is the value of the
+    /// coefficient and the usize indicates the column position
+    pub entries: Vec<(F, usize)>,
+    /// `row_offsets[i]..row_offsets[i + 1]` is the range of `entries` that holds the i-th row, so
+    /// it contains `n_rows + 1` elements, the first one being 0
+    pub row_offsets: Vec<usize>,
 }
 
 impl<F: PrimeField> Dummy<(usize, usize)> for SparseMatrix<F> {
@@ -24,8 +36,9 @@ impl<F: PrimeField> Dummy<(usize, usize)> for SparseMatrix<F> {
         Self {
             n_rows,
             n_cols,
-            // unnecessary to allocate each row as the matrix is sparse
-            coeffs: vec![vec![]; n_rows],
+            // unnecessary to allocate any entry as the matrix is sparse
+            entries: vec![],
+            row_offsets: vec![0; n_rows + 1],
         }
     }
 }
@@ -35,6 +48,74 @@ impl<F: PrimeField> SparseMatrix<F> {
         Self::dummy((0, 0))
     }
 
+    /// Builds the matrix from its rows in the `ark_relations::r1cs::Matrix` format, i.e., where
+    /// each row is a vector of `(value, column position)` pairs.
+    pub fn from_rows(n_cols: usize, rows: R1CSMatrix<F>) -> Self {
+        let mut row_offsets = Vec::with_capacity(rows.len() + 1);
+        row_offsets.push(0);
+        for row in rows.iter() {
+            row_offsets.push(row_offsets[row_offsets.len() - 1] + row.len());
+        }
+        Self {
+            n_rows: rows.len(),
+            n_cols,
+            entries: rows.into_iter().flatten().collect(),
+            row_offsets,
+        }
+    }
+
+    /// Returns the `(value, column position)` pairs of the non-zero coefficients of the i-th row.
+    pub fn row(&self, i: usize) -> &[(F, usize)] {
+        &self.entries[self.row_offsets[i]..self.row_offsets[i + 1]]
+    }
+
+    /// Returns an iterator over the rows of the matrix, see [`SparseMatrix::row`].
+    pub fn rows(&self) -> impl Iterator<Item = &[(F, usize)]> {
+        self.row_offsets
+            .windows(2)
+            .map(|w| &self.entries[w[0]..w[1]])
+    }
+
+    /// Returns the rows of the matrix in the `ark_relations::r1cs::Matrix` format, i.e., the
+    /// former `coeffs` field of `SparseMatrix`, which was replaced by `entries` and `row_offsets`.
+    /// This copies the entries: prefer [`SparseMatrix::rows`] to just iterate over them.
+    pub fn coeffs(&self) -> R1CSMatrix<F> {
+        self.rows().map(<[_]>::to_vec).collect()
+    }
+
+    /// Builds the matrix from its rows, see [`SparseMatrix::from_rows`]. Migrates the code that
+    /// built a `SparseMatrix` from its former `coeffs` field.
+    pub fn from_coeffs(n_rows: usize, n_cols: usize, coeffs: R1CSMatrix<F>) -> Self {
+        let mut matrix = Self::from_rows(n_cols, coeffs);
+        matrix.pad(n_rows, n_cols);
+        matrix
+    }
+
+    /// Feeds the matrix to `hasher`: `n_rows` and `n_cols`, and then each row as its number of
+    /// non-zero coefficients followed by their column positions and values, where the integers
+    /// are little-endian `u64`s and the values are their canonical little-endian representations.
+    pub fn update_hasher(&self, hasher: &mut impl Digest) {
+        hasher.update((self.n_rows as u64).to_le_bytes());
+        hasher.update((self.n_cols as u64).to_le_bytes());
+        for row in self.rows() {
+            hasher.update((row.len() as u64).to_le_bytes());
+            for (value, col_i) in row {
+                hasher.update((*col_i as u64).to_le_bytes());
+                hasher.update(value.into_bigint().to_bytes_le());
+            }
+        }
+    }
+
+    /// Pads the matrix with zeros up to `n_rows` rows and `n_cols` columns, leaving unchanged the
+    /// dimensions that are already larger.
+    pub fn pad(&mut self, n_rows: usize, n_cols: usize) {
+        let n_entries = self.entries.len();
+        self.row_offsets
+            .resize(n_rows.max(self.n_rows) + 1, n_entries);
+        self.n_rows = n_rows.max(self.n_rows);
+        self.n_cols = n_cols.max(self.n_cols);
+    }
+
     pub fn rand<R: Rng>(rng: &mut R, n_rows: usize, n_cols: usize) -> Self {
         const ZERO_VAL_PROBABILITY: f64 = 0.8f64;
 
@@ -54,7 +135,7 @@ impl<F: PrimeField> SparseMatrix<F> {
     }
     pub fn to_dense(&self) -> Vec<Vec<F>> {
         let mut r: Vec<Vec<F>> = vec![vec![F::zero(); self.n_cols]; self.n_rows];
-        for (row_i, row) in self.coeffs.iter().enumerate() {
+        for (row_i, row) in self.rows().enumerate() {
             for &(value, col_i) in row.iter() {
                 r[row_i][col_i] = value;
             }
@@ -64,21 +145,19 @@ impl<F: PrimeField> SparseMatrix<F> {
 }
 
 pub fn dense_matrix_to_sparse<F: PrimeField>(m: Vec<Vec<F>>) -> SparseMatrix<F> {
-    let mut r = SparseMatrix::<F> {
-        n_rows: m.len(),
-        n_cols: m[0].len(),
-        coeffs: Vec::new(),
-    };
-    for m_row in m.iter() {
-        let mut row: Vec<(F, usize)> = Vec::new();
-        for (col_i, value) in m_row.iter().enumerate() {
-            if !value.is_zero() {
-                row.push((*value, col_i));
-            }
-        }
-        r.coeffs.push(row);
-    }
-    r
+    let n_cols = m[0].len();
+    let rows = m
+        .iter()
+        .map(|m_row| {
+            m_row
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(col_i, value)| (*value, col_i))
+                .collect()
+        })
+        .collect();
+    SparseMatrix::from_rows(n_cols, rows)
 }
 
 pub fn vec_add<F: PrimeField>(a: &[F], b: &[F]) -> Result<Vec<F>, Error> {
@@ -113,6 +192,37 @@ pub fn is_zero_vec<F: PrimeField>(vec: &[F]) -> bool {
     cfg_iter!(vec).all(|a| a.is_zero())
 }
 
+/// returns the non-zero entries of the given vector as `(index, value)` pairs
+pub fn dense_vec_to_sparse<F: PrimeField>(vec: &[F]) -> Vec<(usize, F)> {
+    cfg_iter!(vec)
+        .enumerate()
+        .filter(|(_, a)| !a.is_zero())
+        .map(|(i, a)| (i, *a))
+        .collect()
+}
+
+/// same as [`dense_vec_to_sparse`], but writing the non-zero entries into `out`, whose allocation
+/// is reused
+pub fn dense_vec_to_sparse_into<F: PrimeField>(vec: &[F], out: &mut Vec<(usize, F)>) {
+    out.clear();
+    out.extend(
+        vec.iter()
+            .enumerate()
+            .filter(|(_, a)| !a.is_zero())
+            .map(|(i, a)| (i, *a)),
+    );
+}
+
+/// returns the vector of length `len` with the given `(index, value)` entries, where the entries
+/// with the same index are added
+pub fn sparse_vec_to_dense<F: PrimeField>(vec: &[(usize, F)], len: usize) -> Result<Vec<F>, Error> {
+    let mut dense = vec![F::zero(); len];
+    for (i, a) in vec {
+        *dense.get_mut(*i).ok_or(Error::OutOfBounds)? += a;
+    }
+    Ok(dense)
+}
+
 pub fn mat_vec_mul_dense<F: PrimeField>(M: &[Vec<F>], z: &[F]) -> Result<Vec<F>, Error> {
     if M.is_empty() {
         return Err(Error::Empty);
@@ -140,11 +250,43 @@ pub fn mat_vec_mul<F: PrimeField>(M: &SparseMatrix<F>, z: &[F]) -> Result<Vec<F>
             z.len(),
         ));
     }
-    Ok(cfg_iter!(M.coeffs)
-        .map(|row| row.iter().map(|(value, col_i)| *value * z[*col_i]).sum())
+    // the rows are independent, and each one is a contiguous slice of `M.entries`
+    Ok(cfg_into_iter!(0..M.n_rows)
+        .map(|i| {
+            M.row(i)
+                .iter()
+                .map(|(value, col_i)| *value * z[*col_i])
+                .sum()
+        })
         .collect())
 }
 
+/// same as [`mat_vec_mul`], but writing the result into `out`, whose allocation is reused
+pub fn mat_vec_mul_into<F: PrimeField>(
+    M: &SparseMatrix<F>,
+    z: &[F],
+    out: &mut Vec<F>,
+) -> Result<(), Error> {
+    if M.n_cols != z.len() {
+        return Err(Error::NotSameLength(
+            "M.n_cols".to_string(),
+            M.n_cols,
+            "z.len()".to_string(),
+            z.len(),
+        ));
+    }
+    out.clear();
+    out.resize(M.n_rows, F::zero());
+    cfg_iter_mut!(out).enumerate().for_each(|(i, o)| {
+        *o = M
+            .row(i)
+            .iter()
+            .map(|(value, col_i)| *value * z[*col_i])
+            .sum()
+    });
+    Ok(())
+}
+
 pub fn mat_from_str_mat<F: PrimeField>(str_mat: Vec<Vec<&str>>) -> Result<Vec<Vec<F>>, Error> {
     str_mat
         .into_iter()
@@ -158,6 +300,41 @@ pub fn mat_from_str_mat<F: PrimeField>(str_mat: Vec<Vec<&str>>) -> Result<Vec<Ve
         .collect()
 }
 
+/// The number of elements processed at once by the batch operations [`axpy_in_place`],
+/// [`add_scaled`] and [`hadamard`]. The vectors are split in chunks of this size, which are
+/// processed in parallel, so that each rayon task does enough work to amortize its scheduling
+/// (and shorter vectors are processed by a single task), while the loop over each chunk is simple
+/// enough for the compiler to vectorize the limb arithmetic.
+pub const BATCH_CHUNK_SIZE: usize = 1 << 10;
+
+/// Sets `y = y + a * x` (the BLAS `axpy`) in place, i.e., without allocating the result.
+pub fn axpy_in_place<F: PrimeField>(y: &mut [F], a: &F, x: &[F]) -> Result<(), Error> {
+    if y.len() != x.len() {
+        return Err(Error::NotSameLength(
+            "y.len()".to_string(),
+            y.len(),
+            "x.len()".to_string(),
+            x.len(),
+        ));
+    }
+    cfg_chunks_mut!(y, BATCH_CHUNK_SIZE)
+        .zip(cfg_chunks!(x, BATCH_CHUNK_SIZE))
+        .for_each(|(y, x)| {
+            for (y_i, x_i) in y.iter_mut().zip(x) {
+                *y_i += *a * x_i;
+            }
+        });
+    Ok(())
+}
+
+/// Returns `a + c * b`, which is the pattern of the folding of witnesses and instances (e.g.,
+/// `W' = W_1 + r * W_2`).
+pub fn add_scaled<F: PrimeField>(a: &[F], c: &F, b: &[F]) -> Result<Vec<F>, Error> {
+    let mut result = a.to_vec();
+    axpy_in_place(&mut result, c, b)?;
+    Ok(result)
+}
+
 pub fn hadamard<F: PrimeField>(a: &[F], b: &[F]) -> Result<Vec<F>, Error> {
     if a.len() != b.len() {
         return Err(Error::NotSameLength(
@@ -167,7 +344,15 @@ pub fn hadamard<F: PrimeField>(a: &[F], b: &[F]) -> Result<Vec<F>, Error> {
             b.len(),
         ));
     }
-    Ok(cfg_iter!(a).zip(b).map(|(a, b)| *a * b).collect())
+    let mut result = a.to_vec();
+    cfg_chunks_mut!(result, BATCH_CHUNK_SIZE)
+        .zip(cfg_chunks!(b, BATCH_CHUNK_SIZE))
+        .for_each(|(a, b)| {
+            for (a_i, b_i) in a.iter_mut().zip(b) {
+                *a_i *= b_i;
+            }
+        });
+    Ok(result)
 }
 
 /// returns the interpolated polynomial of degree=v.len().next_power_of_two(), which passes through all
@@ -181,6 +366,7 @@ pub fn poly_from_vec<F: PrimeField>(v: Vec<F>) -> Result<DensePolynomial<F>, Err
 pub mod tests {
     use super::*;
     use ark_pallas::Fr;
+    use ark_std::{One, UniformRand};
 
     pub fn to_F_matrix<F: PrimeField>(M: Vec<Vec<usize>>) -> SparseMatrix<F> {
         dense_matrix_to_sparse(to_F_dense_matrix(M))
@@ -204,6 +390,12 @@ pub mod tests {
         ]);
         let A_sparse = dense_matrix_to_sparse(A.clone());
         assert_eq!(A_sparse.to_dense(), A);
+
+        // the rows in the former `coeffs` format
+        let coeffs = A_sparse.coeffs();
+        assert_eq!(coeffs.len(), 4);
+        assert_eq!(coeffs[2], vec![(Fr::one(), 1), (Fr::one(), 4)]);
+        assert_eq!(SparseMatrix::from_coeffs(4, 6, coeffs), A_sparse);
     }
 
     // test mat_vec_mul & mat_vec_mul_sparse
@@ -231,6 +423,14 @@ pub mod tests {
             to_F_vec(vec![418, 1158, 979])
         );
         assert_eq!(mat_vec_mul(&A, &v)?, to_F_vec(vec![418, 1158, 979]));
+
+        // the previous content of the output buffer is discarded
+        let mut out = to_F_vec(vec![1, 2, 3, 4, 5]);
+        mat_vec_mul_into(&A, &v, &mut out)?;
+        assert_eq!(out, to_F_vec(vec![418, 1158, 979]));
+        let mut sparse = vec![(7, Fr::one())];
+        dense_vec_to_sparse_into(&to_F_vec(vec![0, 3, 0, 5]), &mut sparse);
+        assert_eq!(sparse, vec![(1, Fr::from(3u64)), (3, Fr::from(5u64))]);
         Ok(())
     }
 
@@ -242,6 +442,40 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_batch_ops() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        // lengths around the chunk size, so that the last chunk is partial or empty
+        for len in [
+            0,
+            1,
+            BATCH_CHUNK_SIZE - 1,
+            BATCH_CHUNK_SIZE,
+            3 * BATCH_CHUNK_SIZE + 7,
+            rng.gen_range(0..8 * BATCH_CHUNK_SIZE),
+        ] {
+            let a: Vec<Fr> = (0..len).map(|_| Fr::rand(&mut rng)).collect();
+            let b: Vec<Fr> = (0..len).map(|_| Fr::rand(&mut rng)).collect();
+            let c = Fr::rand(&mut rng);
+
+            // the naive implementations
+            let a_plus_cb: Vec<Fr> = a.iter().zip(&b).map(|(a, b)| *a + c * b).collect();
+            let a_times_b: Vec<Fr> = a.iter().zip(&b).map(|(a, b)| *a * b).collect();
+
+            assert_eq!(add_scaled(&a, &c, &b)?, a_plus_cb);
+            let mut y = a.clone();
+            axpy_in_place(&mut y, &c, &b)?;
+            assert_eq!(y, a_plus_cb);
+            assert_eq!(hadamard(&a, &b)?, a_times_b);
+        }
+
+        let a = vec![Fr::one(); BATCH_CHUNK_SIZE + 1];
+        assert!(add_scaled(&a, &Fr::one(), &a[1..]).is_err());
+        assert!(axpy_in_place(&mut a.clone(), &Fr::one(), &a[1..]).is_err());
+        assert!(hadamard(&a, &a[1..]).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_vec_add() -> Result<(), Error> {
         let a: Vec<Fr> = to_F_vec::<Fr>(vec![1, 2, 3, 4, 5, 6]);
@@ -0,0 +1,193 @@
+//! Allocations saved by reusing the scratch buffers of the Nova prover across the folding steps.
+//! The allocator of this test binary counts the allocations, so it is kept out of the unit tests
+//! of the crate.
+
+use ark_bn254::{Fr, G1Projective as Projective};
+use ark_ff::PrimeField;
+use ark_grumpkin::Projective as Projective2;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use folding_schemes::{
+    commitment::pedersen::Pedersen,
+    folding::nova::{Nova, PreprocessorParam},
+    frontend::FCircuit,
+    transcript::poseidon::poseidon_canonical_config,
+    Error, FoldingScheme,
+};
+
+/// Allocator counting the allocations done by each thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // `try_with` fails (and the allocation is not counted) while the thread is exiting
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// number of allocations done so far by the current thread
+fn allocations() -> usize {
+    ALLOCATIONS.with(|n| n.get())
+}
+
+/// The circuit of the steps: `z_{i+1} = z_i^3 + z_i + 5`.
+#[derive(Clone, Copy, Debug)]
+struct CubicFCircuit<F: PrimeField> {
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> FCircuit<F> for CubicFCircuit<F> {
+    type Params = ();
+    type ExternalInputs = ();
+    type ExternalInputsVar = ();
+    type WitnessHint = ();
+
+    fn new(_params: Self::Params) -> Result<Self, Error> {
+        Ok(Self { _f: PhantomData })
+    }
+    fn state_len(&self) -> usize {
+        1
+    }
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        _i: usize,
+        z_i: Vec<FpVar<F>>,
+        _external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let five = FpVar::<F>::new_constant(cs.clone(), F::from(5u32))?;
+        let z_i = z_i[0].clone();
+
+        Ok(vec![&z_i * &z_i * &z_i + &z_i + &five])
+    }
+}
+
+fn cubic_step_native(z_i: Vec<Fr>) -> Vec<Fr> {
+    let z = z_i[0];
+    vec![z * z * z + z + Fr::from(5_u32)]
+}
+
+type N = Nova<
+    Projective,
+    Projective2,
+    CubicFCircuit<Fr>,
+    Pedersen<Projective>,
+    Pedersen<Projective2>,
+    false,
+>;
+
+fn init() -> Result<
+    (
+        N,
+        <N as FoldingScheme<Projective, Projective2, CubicFCircuit<Fr>>>::VerifierParam,
+    ),
+    Error,
+> {
+    let mut rng = ark_std::test_rng();
+    let f_circuit = CubicFCircuit::<Fr>::new(())?;
+    let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit);
+    let params = N::preprocess(&mut rng, &prep_param)?;
+    let nova = N::init(&params, f_circuit, vec![Fr::from(3_u32)])?;
+    Ok((nova, params.1))
+}
+
+/// counts the allocations done by the prover in the calling thread (the parallel computations
+/// allocate in rayon's threads, which are not counted), checking that reusing the scratch buffers
+/// saves their reallocation at each step, while producing the same instances
+#[test]
+fn test_prover_scratch_allocations() -> Result<(), Error> {
+    let mut rng = ark_std::test_rng();
+    let (mut nova, _) = init()?;
+    // the first step does not fold the CycleFold instances, so the CycleFold buffers are only
+    // allocated at the second one
+    for _ in 0..2 {
+        nova.prove_step(&mut rng, (), None)?;
+    }
+
+    let n_steps = 3;
+    let mut fresh = nova.clone();
+    let before = allocations();
+    for _ in 0..n_steps {
+        nova.prove_step(&mut rng, (), None)?;
+    }
+    let reused_allocations = allocations() - before;
+
+    let before = allocations();
+    for _ in 0..n_steps {
+        // start each step with empty buffers, as if they were not reused
+        fresh.release_scratch();
+        fresh.prove_step(&mut rng, (), None)?;
+    }
+    let fresh_allocations = allocations() - before;
+
+    // each step allocates (at least) the z, Az, Bz, Cz and T buffers of the Nova and CycleFold
+    // provers when they are not reused
+    assert!(reused_allocations + 5 * n_steps <= fresh_allocations);
+    assert_eq!(nova.W_i, fresh.W_i);
+    assert_eq!(nova.U_i, fresh.U_i);
+    assert_eq!(nova.cf_W_i, fresh.cf_W_i);
+    assert_eq!(nova.cf_U_i, fresh.cf_U_i);
+    Ok(())
+}
+
+/// folds `n_steps` steps of the cubic circuit, checking that the buffers of the cross terms are
+/// not reallocated after they are first filled
+fn check_scratch_reuse(n_steps: usize) -> Result<(), Error> {
+    let mut rng = ark_std::test_rng();
+    let (mut nova, vp) = init()?;
+    let z_0 = nova.state();
+
+    let mut buffers = None;
+    for i in 0..n_steps {
+        nova.prove_step(&mut rng, (), None)?;
+        // the CycleFold instances are folded from the second step on
+        if i > 0 {
+            let (scratch, cf_scratch) = nova.scratch();
+            let ptrs = (
+                scratch.cross_terms().as_ptr(),
+                cf_scratch.cross_terms().as_ptr(),
+            );
+            assert_eq!(*buffers.get_or_insert(ptrs), ptrs);
+        }
+    }
+
+    let mut z = z_0;
+    for _ in 0..n_steps {
+        z = cubic_step_native(z);
+    }
+    assert_eq!(nova.state(), z);
+    N::verify(vp, nova.ivc_proof())?;
+    Ok(())
+}
+
+#[test]
+fn test_scratch_reuse() -> Result<(), Error> {
+    check_scratch_reuse(20)
+}
+
+/// as `test_scratch_reuse`, over 1000 steps. Ignored by default since it takes several minutes in
+/// debug builds.
+#[ignore]
+#[test]
+fn test_ivc_long_run() -> Result<(), Error> {
+    check_scratch_reuse(1000)
+}
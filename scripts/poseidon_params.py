@@ -0,0 +1,180 @@
+#!/usr/bin/env python3
+"""
+Reproduces, independently of arkworks, the Poseidon configurations returned by
+`poseidon_config_for` (folding-schemes/src/transcript/poseidon.rs), and prints the known-answer
+values that its tests pin.
+
+The round constants and the MDS matrix are generated with the Grain LFSR of the Poseidon reference
+implementation (https://extgit.iaik.tugraz.at/krypto/hadeshash), in the variant of arkworks'
+`find_poseidon_ark_and_mds` (round constants by rejection sampling, Cauchy MDS matrix
+`1 / (x_i + y_j)` from the elements sampled next, without skipping any matrix).
+
+Usage: python3 scripts/poseidon_params.py
+"""
+
+from math import gcd
+
+# The same parameters for all the supported fields: state width t = RATE + CAPACITY, x^ALPHA S-box
+# and the round numbers of the Poseidon paper (https://eprint.iacr.org/2019/458.pdf, table 2) for
+# t = 5 and fields of 254 and 255 bits.
+FULL_ROUNDS = 8
+PARTIAL_ROUNDS = 60
+ALPHA = 5
+RATE = 4
+CAPACITY = 1
+
+FIELDS = [
+    (
+        "BN254 scalar field",
+        0x30644E72E131A029B85045B68181585D2833E84879B9709143E1F593F0000001,
+    ),
+    (
+        "BN254 base field (Grumpkin scalar field)",
+        0x30644E72E131A029B85045B68181585D97816A916871CA8D3C208C16D87CFD47,
+    ),
+    (
+        "Pallas scalar field (Vesta base field)",
+        0x40000000000000000000000000000000224698FC0994A8DD8C46EB2100000001,
+    ),
+    (
+        "Vesta scalar field (Pallas base field)",
+        0x40000000000000000000000000000000224698FC094CF91B992D30ED00000001,
+    ),
+    (
+        "BLS12-381 scalar field",
+        0x73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001,
+    ),
+]
+
+CIRCOM_POSEIDON_1_2_3_4 = (
+    18821383157269793795438455681495246036402687001665670618754263018637548127333
+)
+
+
+class GrainLFSR:
+    def __init__(self, prime_bits, t, full_rounds, partial_rounds):
+        self.state = [0] * 80
+        self.head = 0
+        # b0, b1: prime field; b2..b5: x^alpha S-box
+        self.state[1] = 1
+        for start, end, value in [
+            (6, 17, prime_bits),
+            (18, 29, t),
+            (30, 39, full_rounds),
+            (40, 49, partial_rounds),
+        ]:
+            for i in range(end, start - 1, -1):
+                self.state[i] = value & 1
+                value >>= 1
+        for i in range(50, 80):
+            self.state[i] = 1
+        for _ in range(160):
+            self.update()
+
+    def update(self):
+        h = self.head
+        new_bit = (
+            self.state[(h + 62) % 80]
+            ^ self.state[(h + 51) % 80]
+            ^ self.state[(h + 38) % 80]
+            ^ self.state[(h + 23) % 80]
+            ^ self.state[(h + 13) % 80]
+            ^ self.state[h]
+        )
+        self.state[h] = new_bit
+        self.head = (h + 1) % 80
+        return new_bit
+
+    def bits(self, n):
+        res = []
+        for _ in range(n):
+            while self.update() == 0:
+                self.update()
+            res.append(self.update())
+        return res
+
+    def integer(self, n):
+        # the bits are output most significant first
+        return int("".join(map(str, self.bits(n))), 2)
+
+    def field_elements_rejection_sampling(self, p, n):
+        res = []
+        for _ in range(n):
+            while True:
+                x = self.integer(p.bit_length())
+                if x < p:
+                    res.append(x)
+                    break
+        return res
+
+    def field_elements_mod_p(self, p, n):
+        return [self.integer(p.bit_length()) % p for _ in range(n)]
+
+
+def ark_and_mds(p):
+    t = RATE + 1
+    lfsr = GrainLFSR(p.bit_length(), t, FULL_ROUNDS, PARTIAL_ROUNDS)
+    ark = [
+        lfsr.field_elements_rejection_sampling(p, t)
+        for _ in range(FULL_ROUNDS + PARTIAL_ROUNDS)
+    ]
+    xs = lfsr.field_elements_mod_p(p, t)
+    ys = lfsr.field_elements_mod_p(p, t)
+    assert len(set(xs)) == t and len(set(ys)) == t
+    assert all((x + y) % p != 0 for x in xs for y in ys)
+    mds = [[pow(x + y, -1, p) for y in ys] for x in xs]
+    return ark, mds
+
+
+def permute(p, ark, mds, state):
+    t = len(state)
+    half = FULL_ROUNDS // 2
+    for r in range(FULL_ROUNDS + PARTIAL_ROUNDS):
+        state = [(s + c) % p for s, c in zip(state, ark[r])]
+        if r < half or r >= half + PARTIAL_ROUNDS:
+            state = [pow(s, ALPHA, p) for s in state]
+        else:
+            state[0] = pow(state[0], ALPHA, p)
+        state = [sum(mds[i][j] * state[j] for j in range(t)) % p for i in range(t)]
+    return state
+
+
+def absorb_and_permute(p, ark, mds, inputs):
+    """Absorbs the inputs in the rate part of the state as the Poseidon sponge of arkworks, and
+    returns the state after the permutation of the first squeeze, whose first element of the rate
+    is the Poseidon CRH of the inputs."""
+    state = [0] * (RATE + CAPACITY)
+    for i in range(0, max(len(inputs), 1), RATE):
+        chunk = inputs[i : i + RATE]
+        if i > 0:
+            state = permute(p, ark, mds, state)
+        for j, x in enumerate(chunk):
+            state[CAPACITY + j] = (state[CAPACITY + j] + x) % p
+    return permute(p, ark, mds, state)
+
+
+def main():
+    for name, p in FIELDS:
+        assert gcd(ALPHA, p - 1) == 1, "x^alpha is not a permutation of " + name
+        ark, mds = ark_and_mds(p)
+        state = absorb_and_permute(p, ark, mds, [1, 2, 3, 4])
+        h4 = state[CAPACITY]
+        h5 = absorb_and_permute(p, ark, mds, [1, 2, 3, 4, 5])[CAPACITY]
+        if name == FIELDS[0][0]:
+            # the same as circomlib's Poseidon(4), see `check_against_circom_poseidon`
+            assert state[0] == CIRCOM_POSEIDON_1_2_3_4
+        print(name)
+        print("  modulus:", hex(p), "(%d bits)" % p.bit_length())
+        print(
+            "  t = %d, R_F = %d, R_P = %d, alpha = %d"
+            % (RATE + CAPACITY, FULL_ROUNDS, PARTIAL_ROUNDS, ALPHA)
+        )
+        print("  ark[0][0]:", ark[0][0])
+        print("  mds[0][0]:", mds[0][0])
+        print("  state[0] after absorbing [1, 2, 3, 4]:", state[0])
+        print("  hash([1, 2, 3, 4]):", h4)
+        print("  hash([1, 2, 3, 4, 5]):", h5)
+
+
+if __name__ == "__main__":
+    main()
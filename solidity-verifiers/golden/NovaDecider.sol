@@ -0,0 +1,879 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity >=0.7.0 <0.9.0;
+
+/*
+    Sonobe's Nova + CycleFold decider verifier.
+    Joint effort by 0xPARC & PSE.
+
+    More details at https://github.com/privacy-scaling-explorations/sonobe
+    Usage and design documentation at https://privacy-scaling-explorations.github.io/sonobe-docs/
+
+    Uses the https://github.com/iden3/snarkjs/blob/master/templates/verifier_groth16.sol.ejs
+    Groth16 verifier implementation and a KZG10 Solidity template adapted from
+    https://github.com/weijiekoh/libkzg.
+    Additionally we implement the NovaDecider contract, which combines the
+    Groth16 and KZG10 verifiers to verify the zkSNARK proofs coming from
+    Nova+CycleFold folding.
+*/
+
+
+/* =============================== */
+/* KZG10 verifier methods */
+/**
+ * @author  Privacy and Scaling Explorations team - pse.dev
+ * @dev     Contains utility functions for ops in BN254; in G_1 mostly.
+ * @notice  Forked from https://github.com/weijiekoh/libkzg.
+ * Among others, a few of the changes we did on this fork were:
+ * - Templating the pragma version
+ * - Removing type wrappers and use uints instead
+ * - Performing changes on arg types
+ * - Update some of the `require` statements 
+ * - Use the bn254 scalar field instead of checking for overflow on the babyjub prime
+ * - In batch checking, we compute auxiliary polynomials and their commitments at the same time.
+ */
+contract KZG10Verifier {
+
+    // prime of field F_p over which y^2 = x^3 + 3 is defined
+    uint256 public constant BN254_PRIME_FIELD =
+        21888242871839275222246405745257275088696311157297823662689037894645226208583;
+    uint256 public constant BN254_SCALAR_FIELD =
+        21888242871839275222246405745257275088548364400416034343698204186575808495617;
+
+    /**
+     * @notice  Performs scalar multiplication in G_1.
+     * @param   p  G_1 point to multiply
+     * @param   s  Scalar to multiply by
+     * @return  r  G_1 point p multiplied by scalar s
+     */
+    function mulScalar(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input;
+        input[0] = p[0];
+        input[1] = p[1];
+        input[2] = s;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 7, input, 0x60, r, 0x40)
+            switch success
+            case 0 { invalid() }
+        }
+        require(success, "bn254: scalar mul failed");
+    }
+
+    /**
+     * @notice  Negates a point in G_1.
+     * @param   p  G_1 point to negate
+     * @return  uint256[2]  G_1 point -p
+     */
+    function negate(uint256[2] memory p) internal pure returns (uint256[2] memory) {
+        if (p[0] == 0 && p[1] == 0) {
+            return p;
+        }
+        return [p[0], BN254_PRIME_FIELD - (p[1] % BN254_PRIME_FIELD)];
+    }
+
+    /**
+     * @notice  Adds two points in G_1.
+     * @param   p1  G_1 point 1
+     * @param   p2  G_1 point 2
+     * @return  r  G_1 point p1 + p2
+     */
+    function add(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {
+        bool success;
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        assembly {
+            success := staticcall(sub(gas(), 2000), 6, input, 0x80, r, 0x40)
+            switch success
+            case 0 { invalid() }
+        }
+
+        require(success, "bn254: point add failed");
+    }
+
+    /**
+     * @notice  Computes the pairing check e(p1, p2) * e(p3, p4) == 1
+     * @dev     Note that G_2 points a*i + b are encoded as two elements of F_p, (a, b)
+     * @param   a_1  G_1 point 1
+     * @param   a_2  G_2 point 1
+     * @param   b_1  G_1 point 2
+     * @param   b_2  G_2 point 2
+     * @return  result  true if pairing check is successful
+     */
+    function pairing(uint256[2] memory a_1, uint256[2][2] memory a_2, uint256[2] memory b_1, uint256[2][2] memory b_2)
+        internal
+        view
+        returns (bool result)
+    {
+        uint256[12] memory input = [
+            a_1[0],
+            a_1[1],
+            a_2[0][1], // imaginary part first
+            a_2[0][0],
+            a_2[1][1], // imaginary part first
+            a_2[1][0],
+            b_1[0],
+            b_1[1],
+            b_2[0][1], // imaginary part first
+            b_2[0][0],
+            b_2[1][1], // imaginary part first
+            b_2[1][0]
+        ];
+
+        uint256[1] memory out;
+        bool success;
+
+        assembly {
+            success := staticcall(sub(gas(), 2000), 8, input, 0x180, out, 0x20)
+            switch success
+            case 0 { invalid() }
+        }
+
+        require(success, "bn254: pairing failed");
+
+        return out[0] == 1;
+    }
+
+    uint256[2] G_1 = [
+            1,
+            2
+    ];
+    uint256[2][2] G_2 = [
+        [
+            10857046999023057135944570762232829481370756359578518086990519993285655852781,
+            11559732032986387107991004021392285783925812861821192530917403151452391805634
+        ],
+        [
+            8495653923123431417604973247489272438418190587263600148770280649306958101930,
+            4082367875863433681332203403145435568316851327593401208105741076214120093531
+        ]
+    ];
+    uint256[2][2] VK = [
+        [
+            10857046999023057135944570762232829481370756359578518086990519993285655852781,
+            11559732032986387107991004021392285783925812861821192530917403151452391805634
+        ],
+        [
+            13392588948715843804641432497768002650278120570034223513918757245338268106653,
+            17805874995975841540914202342111839520379459829704422454583296818431106115052
+        ]
+    ];
+
+    
+
+    /**
+     * @notice  Verifies a single point evaluation proof. Function name follows `ark-poly`.
+     * @dev     To avoid ops in G_2, we slightly tweak how the verification is done.
+     * @param   c  G_1 point commitment to polynomial.
+     * @param   pi G_1 point proof.
+     * @param   x  Value to prove evaluation of polynomial at.
+     * @param   y  Evaluation poly(x).
+     * @return  result Indicates if KZG proof is correct.
+     */
+    function check(uint256[2] calldata c, uint256[2] calldata pi, uint256 x, uint256 y)
+        public
+        view
+        returns (bool result)
+    {
+        //
+        // we want to:
+        //      1. avoid gas intensive ops in G2
+        //      2. format the pairing check in line with what the evm opcode expects.
+        //
+        // we can do this by tweaking the KZG check to be:
+        //
+        //          e(pi, vk - x * g2) = e(c - y * g1, g2) [initial check]
+        //          e(pi, vk - x * g2) * e(c - y * g1, g2)^{-1} = 1
+        //          e(pi, vk - x * g2) * e(-c + y * g1, g2) = 1 [bilinearity of pairing for all subsequent steps]
+        //          e(pi, vk) * e(pi, -x * g2) * e(-c + y * g1, g2) = 1
+        //          e(pi, vk) * e(-x * pi, g2) * e(-c + y * g1, g2) = 1
+        //          e(pi, vk) * e(x * -pi - c + y * g1, g2) = 1 [done]
+        //                        |_   rhs_pairing  _|
+        //
+        uint256[2] memory rhs_pairing =
+            add(mulScalar(negate(pi), x), add(negate(c), mulScalar(G_1, y)));
+        return pairing(pi, VK, rhs_pairing, G_2);
+    }
+
+    /**
+     * @notice  Verifies several single point evaluation proofs, each one for a different polynomial, with a single pairing check.
+     * @dev     The pairing checks of each proof (see `check`) are combined through a random linear combination, with the
+     *          powers of a challenge derived from all the inputs.
+     * @param   c  G_1 point commitments to the polynomials.
+     * @param   pi  G_1 point proofs.
+     * @param   x  Values to prove evaluation of each polynomial at.
+     * @param   y  Evaluations poly_j(x_j).
+     * @return  result Indicates if all the KZG proofs are correct.
+     */
+    function multiCheck(uint256[2][] memory c, uint256[2][] memory pi, uint256[] memory x, uint256[] memory y)
+        public
+        view
+        returns (bool result)
+    {
+        require(
+            c.length == pi.length && c.length == x.length && c.length == y.length,
+            "KZG: the number of commitments, proofs and evaluations differ"
+        );
+        //
+        // each proof j satisfies (see `check`):
+        //
+        //          e(pi_j, vk) * e(x_j * -pi_j - c_j + y_j * g1, g2) = 1
+        //
+        // so with r_j = r^j, by bilinearity all the checks hold at once (except with negligible probability) if:
+        //
+        //          e(sum_j r_j * pi_j, vk) * e(sum_j r_j * (x_j * -pi_j - c_j) + (sum_j r_j * y_j) * g1, g2) = 1 [done]
+        //             |_   lhs_pairing  _|      |_                      rhs_pairing                        _|
+        //
+        uint256 r = uint256(keccak256(abi.encode(c, pi, x, y))) % BN254_SCALAR_FIELD;
+        uint256 r_j = 1;
+        uint256 y_acc = 0;
+        uint256[2] memory lhs_pairing;
+        uint256[2] memory rhs_pairing;
+        for (uint256 j = 0; j < c.length; j++) {
+            lhs_pairing = add(lhs_pairing, mulScalar(pi[j], r_j));
+            rhs_pairing = add(
+                rhs_pairing,
+                add(mulScalar(negate(pi[j]), mulmod(x[j], r_j, BN254_SCALAR_FIELD)), mulScalar(negate(c[j]), r_j))
+            );
+            y_acc = addmod(y_acc, mulmod(y[j], r_j, BN254_SCALAR_FIELD), BN254_SCALAR_FIELD);
+            r_j = mulmod(r_j, r, BN254_SCALAR_FIELD);
+        }
+        rhs_pairing = add(rhs_pairing, mulScalar(G_1, y_acc));
+        return pairing(lhs_pairing, VK, rhs_pairing, G_2);
+    }
+
+    function evalPolyAt(uint256[] memory _coefficients, uint256 _index) public pure returns (uint256) {
+        uint256 m = BN254_SCALAR_FIELD;
+        uint256 result = 0;
+        uint256 powerOfX = 1;
+
+        for (uint256 i = 0; i < _coefficients.length; i++) {
+            uint256 coeff = _coefficients[i];
+            assembly {
+                result := addmod(result, mulmod(powerOfX, coeff, m), m)
+                powerOfX := mulmod(powerOfX, _index, m)
+            }
+        }
+        return result;
+    }
+
+    
+}
+
+/* =============================== */
+/* Groth16 verifier methods */
+/*
+    Copyright 2021 0KIMS association.
+
+    * `solidity-verifiers` added comment
+        This file is a template built out of [snarkJS](https://github.com/iden3/snarkjs) groth16 verifier.
+        See the original ejs template [here](https://github.com/iden3/snarkjs/blob/master/templates/verifier_groth16.sol.ejs)
+    *
+
+    snarkJS is a free software: you can redistribute it and/or modify it
+    under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    snarkJS is distributed in the hope that it will be useful, but WITHOUT
+    ANY WARRANTY; without even the implied warranty of MERCHANTABILITY
+    or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public
+    License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with snarkJS. If not, see <https://www.gnu.org/licenses/>.
+*/
+
+contract Groth16Verifier {
+    // Scalar field size
+    uint256 constant r    = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    // Base field size
+    uint256 constant q   = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    // Verification Key data
+    uint256 constant alphax  = 1;
+    uint256 constant alphay  = 2;
+    uint256 constant betax1  = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant betax2  = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant betay1  = 4082367875863433681332203403145435568316851327593401208105741076214120093531;
+    uint256 constant betay2  = 8495653923123431417604973247489272438418190587263600148770280649306958101930;
+    uint256 constant gammax1 = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant gammax2 = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant gammay1 = 4082367875863433681332203403145435568316851327593401208105741076214120093531;
+    uint256 constant gammay2 = 8495653923123431417604973247489272438418190587263600148770280649306958101930;
+    uint256 constant deltax1 = 11559732032986387107991004021392285783925812861821192530917403151452391805634;
+    uint256 constant deltax2 = 10857046999023057135944570762232829481370756359578518086990519993285655852781;
+    uint256 constant deltay1 = 17805874995975841540914202342111839520379459829704422454583296818431106115052;
+    uint256 constant deltay2 = 13392588948715843804641432497768002650278120570034223513918757245338268106653;
+
+    
+    uint256 constant IC0x = 1;
+    uint256 constant IC0y = 2;
+    
+    uint256 constant IC1x = 1368015179489954701390400359078579693043519447331113978918064868415326638035;
+    uint256 constant IC1y = 9918110051302171585080402603319702774565515993150576347155970296011118125764;
+    
+    uint256 constant IC2x = 3353031288059533942658390886683067124040920775575537747144343083137631628272;
+    uint256 constant IC2y = 19321533766552368860946552437480515441416830039777911637913418824951667761761;
+    
+    uint256 constant IC3x = 3010198690406615200373504922352659861758983907867017329644089018310584441462;
+    uint256 constant IC3y = 4027184618003122424972590350825261965929648733675738730716654005365300998076;
+    
+    uint256 constant IC4x = 10744596414106452074759370245733544594153395043370666422502510773307029471145;
+    uint256 constant IC4y = 848677436511517736191562425154572367705380862894644942948681172815252343932;
+    
+    uint256 constant IC5x = 4503322228978077916651710446042370109107355802721800704639343137502100212473;
+    uint256 constant IC5y = 6132642251294427119375180147349983541569387941788025780665104001559216576968;
+    
+    uint256 constant IC6x = 10415861484417082502655338383609494480414113902179649885744799961447382638712;
+    uint256 constant IC6y = 10196215078179488638353184030336251401353352596818396260819493263908881608606;
+    
+    uint256 constant IC7x = 3932705576657793550893430333273221375907985235130430286685735064194643946083;
+    uint256 constant IC7y = 18813763293032256545937756946359266117037834559191913266454084342712532869153;
+    
+    uint256 constant IC8x = 1624070059937464756887933993293429854168590106605707304006200119738501412969;
+    uint256 constant IC8y = 3269329550605213075043232856820720631601935657990457502777101397807070461336;
+    
+    uint256 constant IC9x = 4444740815889402603535294170722302758225367627362056425101568584910268024244;
+    uint256 constant IC9y = 10537263096529483164618820017164668921386457028564663708352735080900270541420;
+    
+    uint256 constant IC10x = 19033251874843656108471242320417533909414939332036131356573128480367742634479;
+    uint256 constant IC10y = 20792135454608030201903199625673964159744755218442260092768620403349374102584;
+    
+    uint256 constant IC11x = 17108685722251241369314020928988529881027530433467445791267465866135602972753;
+    uint256 constant IC11y = 20666112440056908034039013737427066139426903072479162670940363761207457724060;
+    
+    uint256 constant IC12x = 2672242651313367459976336264061690128665099451055893690004467838496751824703;
+    uint256 constant IC12y = 18247534626997477790812670345925575171672701304065784723769023620148097699216;
+    
+    uint256 constant IC13x = 9836339169314901400584090930519505895878753154116006108033708428907043344230;
+    uint256 constant IC13y = 2085718088180884207082818799076507077917184375787335400014805976331012093279;
+    
+    uint256 constant IC14x = 20620327752371756597889511849668302065574790742892641857779427155670977738300;
+    uint256 constant IC14y = 13476221886639441297190182883126933680754442408693165714792516739857175455715;
+    
+    uint256 constant IC15x = 10835225521862395592687560951453385602895512958032257955899877380493200080708;
+    uint256 constant IC15y = 2623520004791921319615054428233368525468155544765295675952919303096698181037;
+    
+    uint256 constant IC16x = 12852522211178622728088728121177131998585782282560100422041774753646305409836;
+    uint256 constant IC16y = 15918672909255108529698304535345707578139606904951176064731093256171019744261;
+    
+    uint256 constant IC17x = 20687098839691105097230132006705975099432129393118730698937199498853576759031;
+    uint256 constant IC17y = 2784555085364475896955849729890301289648525422842103121195101376751786827234;
+    
+    uint256 constant IC18x = 9642222084729607517877300695132775567109325334448449884825136965142866412173;
+    uint256 constant IC18y = 4237181956005900153121967166075358295245559468450620141848474158744070559022;
+    
+    uint256 constant IC19x = 18947110137775984544896515092961257947872750783784269176923414004072777296602;
+    uint256 constant IC19y = 12292085037693291586083644966434670280746730626861846747147579999202931064992;
+    
+    uint256 constant IC20x = 13940766438396802022003403700150119103921439873158775302201999840306601026555;
+    uint256 constant IC20y = 20366854387609749451649589446643328667334616581983267447585608088473228416457;
+    
+    uint256 constant IC21x = 15727213640762128376977790067421582934261473041285176203873887513123693207669;
+    uint256 constant IC21y = 19144605879150273414601776380457513460094228635793066771119021730299648624873;
+    
+    uint256 constant IC22x = 13640322012419910779160519747081036978280854528525356142388876682012724302321;
+    uint256 constant IC22y = 18538714940515721848968265449014632110570653454278528879450713650630487487382;
+    
+    uint256 constant IC23x = 20453939078259811958859768391452073654460321168773748684493785442363495374770;
+    uint256 constant IC23y = 9582859829925552874957318860636821932456214701004608986274201852321144884827;
+    
+    uint256 constant IC24x = 20765039372871530718554589730410158162413780974122112544611863764810626751360;
+    uint256 constant IC24y = 2444183914824638066910831265243126275246160293098948571390980460351548298384;
+    
+    uint256 constant IC25x = 8681338680946461717535019514322286392232941027202505457936697409126487752597;
+    uint256 constant IC25y = 17863388846210655694771784462046081596475441676851022940554701727704151239446;
+    
+    uint256 constant IC26x = 7386018680896664845814314739404608274906642913094681192334051733974875574294;
+    uint256 constant IC26y = 5852141382496003785706624430625245910156483599429242455944689364421678894028;
+    
+    uint256 constant IC27x = 13093913218499068528079927169315581029488038715846819897949203493926040477433;
+    uint256 constant IC27y = 18866812021242893984958271807367250411442129524282083647490667697096642392711;
+    
+    uint256 constant IC28x = 9961482077405933653703920413004101065199760487639777914203301284159532567165;
+    uint256 constant IC28y = 5862436715964027487145075334372980905100234227901145792980374837265196864691;
+    
+    uint256 constant IC29x = 1527465159374431915328497116935179161014331322368960485951268517950184093102;
+    uint256 constant IC29y = 17274044707157828649723710289902216429715848248207037129568326237800068062774;
+    
+    uint256 constant IC30x = 9366015879375004571250438303432407971238053874512316318402267084951246439740;
+    uint256 constant IC30y = 18456548560916331602912926306132216314029103442570467520030714287463663922742;
+    
+    uint256 constant IC31x = 4873079524557847867653965550062716553062346862158697560012111398864356025363;
+    uint256 constant IC31y = 11422470166079944859104614283946245081791188387376113119760245565153108742933;
+    
+    uint256 constant IC32x = 12643418736033227053786352010911706350519409749146221098915102879679320422546;
+    uint256 constant IC32y = 20244910942408978007550006931066140611657597349862739175933913066040413145521;
+    
+    uint256 constant IC33x = 5841054468380737358126901601208759440531393618333695939860021859990434602332;
+    uint256 constant IC33y = 14496198492936799798866613472708085382638873795843716312315177042738122955744;
+    
+    uint256 constant IC34x = 19603121658858655875247255127227546065511167701958109023745805570144594432590;
+    uint256 constant IC34y = 18396643206309242224060210403331962159520263222429416365150105776739848612253;
+    
+    uint256 constant IC35x = 12109125168208163605857260910923713983580584394895257282529162242928595286368;
+    uint256 constant IC35y = 15968495061591974818369503561669603416490648399745553243439919872679665689139;
+    
+    uint256 constant IC36x = 16711416843522114883433203178480474873102412521895441414645328675899158417507;
+    uint256 constant IC36y = 8853086173564136849925865683598036960975837022999254197357454379453354604209;
+    
+    uint256 constant IC37x = 7516377092939190245003354266325638140107382505065164044898800274245368101059;
+    uint256 constant IC37y = 8066393891172060466851929391494091885437146651549336987667651070831429295203;
+    
+    uint256 constant IC38x = 12231734685659393914320260566447712574192421431051443092223777906741357260966;
+    uint256 constant IC38y = 17727947864982905001040320444642043959882380122793290910937606141681385178793;
+    
+    
+    // Memory data
+    uint16 constant pVk = 0;
+    uint16 constant pPairing = 128;
+
+    uint16 constant pLastMem = 896;
+
+    function verifyProof(uint[2] calldata _pA, uint[2][2] calldata _pB, uint[2] calldata _pC, uint[38] calldata _pubSignals) public view returns (bool) {
+        assembly {
+            function checkField(v) {
+                if iszero(lt(v, r)) {
+                    mstore(0, 0)
+                    return(0, 0x20)
+                }
+            }
+            
+            // G1 function to multiply a G1 value(x,y) to value in an address
+            function g1_mulAccC(pR, x, y, s) {
+                let success
+                let mIn := mload(0x40)
+                mstore(mIn, x)
+                mstore(add(mIn, 32), y)
+                mstore(add(mIn, 64), s)
+
+                success := staticcall(sub(gas(), 2000), 7, mIn, 96, mIn, 64)
+
+                if iszero(success) {
+                    mstore(0, 0)
+                    return(0, 0x20)
+                }
+
+                mstore(add(mIn, 64), mload(pR))
+                mstore(add(mIn, 96), mload(add(pR, 32)))
+
+                success := staticcall(sub(gas(), 2000), 6, mIn, 128, pR, 64)
+
+                if iszero(success) {
+                    mstore(0, 0)
+                    return(0, 0x20)
+                }
+            }
+
+            function checkPairing(pA, pB, pC, pubSignals, pMem) -> isOk {
+                let _pPairing := add(pMem, pPairing)
+                let _pVk := add(pMem, pVk)
+
+                mstore(_pVk, IC0x)
+                mstore(add(_pVk, 32), IC0y)
+
+                // Compute the linear combination vk_x
+                
+                
+                g1_mulAccC(_pVk, IC1x, IC1y, calldataload(add(pubSignals, 0)))
+                g1_mulAccC(_pVk, IC2x, IC2y, calldataload(add(pubSignals, 32)))
+                g1_mulAccC(_pVk, IC3x, IC3y, calldataload(add(pubSignals, 64)))
+                g1_mulAccC(_pVk, IC4x, IC4y, calldataload(add(pubSignals, 96)))
+                g1_mulAccC(_pVk, IC5x, IC5y, calldataload(add(pubSignals, 128)))
+                g1_mulAccC(_pVk, IC6x, IC6y, calldataload(add(pubSignals, 160)))
+                g1_mulAccC(_pVk, IC7x, IC7y, calldataload(add(pubSignals, 192)))
+                g1_mulAccC(_pVk, IC8x, IC8y, calldataload(add(pubSignals, 224)))
+                g1_mulAccC(_pVk, IC9x, IC9y, calldataload(add(pubSignals, 256)))
+                g1_mulAccC(_pVk, IC10x, IC10y, calldataload(add(pubSignals, 288)))
+                g1_mulAccC(_pVk, IC11x, IC11y, calldataload(add(pubSignals, 320)))
+                g1_mulAccC(_pVk, IC12x, IC12y, calldataload(add(pubSignals, 352)))
+                g1_mulAccC(_pVk, IC13x, IC13y, calldataload(add(pubSignals, 384)))
+                g1_mulAccC(_pVk, IC14x, IC14y, calldataload(add(pubSignals, 416)))
+                g1_mulAccC(_pVk, IC15x, IC15y, calldataload(add(pubSignals, 448)))
+                g1_mulAccC(_pVk, IC16x, IC16y, calldataload(add(pubSignals, 480)))
+                g1_mulAccC(_pVk, IC17x, IC17y, calldataload(add(pubSignals, 512)))
+                g1_mulAccC(_pVk, IC18x, IC18y, calldataload(add(pubSignals, 544)))
+                g1_mulAccC(_pVk, IC19x, IC19y, calldataload(add(pubSignals, 576)))
+                g1_mulAccC(_pVk, IC20x, IC20y, calldataload(add(pubSignals, 608)))
+                g1_mulAccC(_pVk, IC21x, IC21y, calldataload(add(pubSignals, 640)))
+                g1_mulAccC(_pVk, IC22x, IC22y, calldataload(add(pubSignals, 672)))
+                g1_mulAccC(_pVk, IC23x, IC23y, calldataload(add(pubSignals, 704)))
+                g1_mulAccC(_pVk, IC24x, IC24y, calldataload(add(pubSignals, 736)))
+                g1_mulAccC(_pVk, IC25x, IC25y, calldataload(add(pubSignals, 768)))
+                g1_mulAccC(_pVk, IC26x, IC26y, calldataload(add(pubSignals, 800)))
+                g1_mulAccC(_pVk, IC27x, IC27y, calldataload(add(pubSignals, 832)))
+                g1_mulAccC(_pVk, IC28x, IC28y, calldataload(add(pubSignals, 864)))
+                g1_mulAccC(_pVk, IC29x, IC29y, calldataload(add(pubSignals, 896)))
+                g1_mulAccC(_pVk, IC30x, IC30y, calldataload(add(pubSignals, 928)))
+                g1_mulAccC(_pVk, IC31x, IC31y, calldataload(add(pubSignals, 960)))
+                g1_mulAccC(_pVk, IC32x, IC32y, calldataload(add(pubSignals, 992)))
+                g1_mulAccC(_pVk, IC33x, IC33y, calldataload(add(pubSignals, 1024)))
+                g1_mulAccC(_pVk, IC34x, IC34y, calldataload(add(pubSignals, 1056)))
+                g1_mulAccC(_pVk, IC35x, IC35y, calldataload(add(pubSignals, 1088)))
+                g1_mulAccC(_pVk, IC36x, IC36y, calldataload(add(pubSignals, 1120)))
+                g1_mulAccC(_pVk, IC37x, IC37y, calldataload(add(pubSignals, 1152)))
+                g1_mulAccC(_pVk, IC38x, IC38y, calldataload(add(pubSignals, 1184)))
+
+                // -A
+                mstore(_pPairing, calldataload(pA))
+                mstore(add(_pPairing, 32), mod(sub(q, calldataload(add(pA, 32))), q))
+
+                // B
+                mstore(add(_pPairing, 64), calldataload(pB))
+                mstore(add(_pPairing, 96), calldataload(add(pB, 32)))
+                mstore(add(_pPairing, 128), calldataload(add(pB, 64)))
+                mstore(add(_pPairing, 160), calldataload(add(pB, 96)))
+
+                // alpha1
+                mstore(add(_pPairing, 192), alphax)
+                mstore(add(_pPairing, 224), alphay)
+
+                // beta2
+                mstore(add(_pPairing, 256), betax1)
+                mstore(add(_pPairing, 288), betax2)
+                mstore(add(_pPairing, 320), betay1)
+                mstore(add(_pPairing, 352), betay2)
+
+                // vk_x
+                mstore(add(_pPairing, 384), mload(add(pMem, pVk)))
+                mstore(add(_pPairing, 416), mload(add(pMem, add(pVk, 32))))
+
+
+                // gamma2
+                mstore(add(_pPairing, 448), gammax1)
+                mstore(add(_pPairing, 480), gammax2)
+                mstore(add(_pPairing, 512), gammay1)
+                mstore(add(_pPairing, 544), gammay2)
+
+                // C
+                mstore(add(_pPairing, 576), calldataload(pC))
+                mstore(add(_pPairing, 608), calldataload(add(pC, 32)))
+
+                // delta2
+                mstore(add(_pPairing, 640), deltax1)
+                mstore(add(_pPairing, 672), deltax2)
+                mstore(add(_pPairing, 704), deltay1)
+                mstore(add(_pPairing, 736), deltay2)
+
+
+                let success := staticcall(sub(gas(), 2000), 8, _pPairing, 768, _pPairing, 0x20)
+
+                isOk := and(success, mload(_pPairing))
+            }
+
+            let pMem := mload(0x40)
+            mstore(0x40, add(pMem, pLastMem))
+
+            // Validate that all evaluations ∈ F
+            
+            checkField(calldataload(add(_pubSignals, 0)))
+            
+            checkField(calldataload(add(_pubSignals, 32)))
+            
+            checkField(calldataload(add(_pubSignals, 64)))
+            
+            checkField(calldataload(add(_pubSignals, 96)))
+            
+            checkField(calldataload(add(_pubSignals, 128)))
+            
+            checkField(calldataload(add(_pubSignals, 160)))
+            
+            checkField(calldataload(add(_pubSignals, 192)))
+            
+            checkField(calldataload(add(_pubSignals, 224)))
+            
+            checkField(calldataload(add(_pubSignals, 256)))
+            
+            checkField(calldataload(add(_pubSignals, 288)))
+            
+            checkField(calldataload(add(_pubSignals, 320)))
+            
+            checkField(calldataload(add(_pubSignals, 352)))
+            
+            checkField(calldataload(add(_pubSignals, 384)))
+            
+            checkField(calldataload(add(_pubSignals, 416)))
+            
+            checkField(calldataload(add(_pubSignals, 448)))
+            
+            checkField(calldataload(add(_pubSignals, 480)))
+            
+            checkField(calldataload(add(_pubSignals, 512)))
+            
+            checkField(calldataload(add(_pubSignals, 544)))
+            
+            checkField(calldataload(add(_pubSignals, 576)))
+            
+            checkField(calldataload(add(_pubSignals, 608)))
+            
+            checkField(calldataload(add(_pubSignals, 640)))
+            
+            checkField(calldataload(add(_pubSignals, 672)))
+            
+            checkField(calldataload(add(_pubSignals, 704)))
+            
+            checkField(calldataload(add(_pubSignals, 736)))
+            
+            checkField(calldataload(add(_pubSignals, 768)))
+            
+            checkField(calldataload(add(_pubSignals, 800)))
+            
+            checkField(calldataload(add(_pubSignals, 832)))
+            
+            checkField(calldataload(add(_pubSignals, 864)))
+            
+            checkField(calldataload(add(_pubSignals, 896)))
+            
+            checkField(calldataload(add(_pubSignals, 928)))
+            
+            checkField(calldataload(add(_pubSignals, 960)))
+            
+            checkField(calldataload(add(_pubSignals, 992)))
+            
+            checkField(calldataload(add(_pubSignals, 1024)))
+            
+            checkField(calldataload(add(_pubSignals, 1056)))
+            
+            checkField(calldataload(add(_pubSignals, 1088)))
+            
+            checkField(calldataload(add(_pubSignals, 1120)))
+            
+            checkField(calldataload(add(_pubSignals, 1152)))
+            
+            checkField(calldataload(add(_pubSignals, 1184)))
+            
+            checkField(calldataload(add(_pubSignals, 1216)))
+            
+
+            // Validate all evaluations
+            let isValid := checkPairing(_pA, _pB, _pC, _pubSignals, pMem)
+
+            mstore(0, isValid)
+            
+            return(0, 0x20)
+        }
+    }
+}
+
+
+/* =============================== */
+/* Nova+CycleFold Decider verifier */
+/**
+ * @notice  Computes the decomposition of a `uint256` into num_limbs limbs of bits_per_limb bits each.
+ * @dev     Compatible with sonobe::folding-schemes::folding::circuits::nonnative::nonnative_field_to_field_elements.
+ */
+library LimbsDecomposition {
+    function decompose(uint256 x) internal pure returns (uint256[5] memory) {
+        uint256[5] memory limbs;
+        for (uint8 i = 0; i < 5; i++) {
+            limbs[i] = (x >> (55 * i)) & ((1 << 55) - 1);
+        }
+        return limbs;
+    }
+}
+
+/**
+ * @author PSE & 0xPARC
+ * @title  Interface for the NovaDecider contract hiding proof details.
+ * @dev    This interface enables calling the verifyNovaProof function without exposing the proof details.
+ */
+interface OpaqueDecider {
+    /**
+     * @notice  Verifies a Nova+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
+     * @dev     This function should simply reorganize arguments and pass them to the proper verification function.
+     */
+    function verifyOpaqueNovaProofWithInputs(
+        uint256 steps, // number of folded steps (i)
+        uint256[1] calldata initial_state, // initial IVC state (z0)
+        uint256[1] calldata final_state, // IVC state after i steps (zi)
+        uint256[25] calldata proof // the rest of the decider inputs
+    ) external view returns (bool);
+
+    /**
+     * @notice  Verifies a Nova+CycleFold proof given all the proof inputs collected in a single array.
+     * @dev     This function should simply reorganize arguments and pass them to the proper verification function.
+     */
+    function verifyOpaqueNovaProof(uint256[28] calldata proof) external view returns (bool);
+}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   NovaDecider contract, for verifying Nova IVC SNARK proofs.
+ * @dev     This is an askama template which, when templated, features a Groth16 and KZG10 verifiers from which this contract inherits.
+ */
+contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
+    /**
+     * @notice  Computes the linear combination of a and b with r as the coefficient.
+     * @dev     All ops are done mod the BN254 scalar field prime
+     */
+    function rlc(uint256 a, uint256 r, uint256 b) internal pure returns (uint256 result) {
+        assembly {
+            result := addmod(a, mulmod(r, b, BN254_SCALAR_FIELD), BN254_SCALAR_FIELD)
+        }
+    }
+
+    /**
+     * @notice  Verifies a nova cyclefold proof consisting of two KZG proofs and of a groth16 proof.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`. Both KZG proofs are verified
+     *          together with a single pairing check.
+     */
+    function verifyNovaProof(
+        // inputs are grouped to prevent errors due stack too deep
+        uint256[3] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
+        uint256[4] calldata U_i_cmW_U_i_cmE, // [U_i_cmW[2], U_i_cmE[2]]
+        uint256[2] calldata u_i_cmW, // [u_i_cmW[2]]
+        uint256[3] calldata cmT_r, // [cmT[2], r]
+        uint256[2] calldata pA, // groth16 
+        uint256[2][2] calldata pB, // groth16
+        uint256[2] calldata pC, // groth16
+        uint256[4] calldata challenge_W_challenge_E_kzg_evals, // [challenge_W, challenge_E, eval_W, eval_E]
+        uint256[2][2] calldata kzg_proof // [proof_W, proof_E]
+    ) public view returns (bool) {
+
+        require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        // from gamma_abc_len, we subtract 1. 
+        uint256[38] memory public_inputs; 
+
+        public_inputs[0] = 42;
+        public_inputs[1] = i_z0_zi[0];
+
+        for (uint i = 0; i < 2; i++) {
+            public_inputs[2 + i] = i_z0_zi[1 + i];
+        }
+
+        // commitments opened by the KZG proofs, [cmW, cmE]
+        uint256[2][] memory kzg_cms = new uint256[2][](2);
+
+        {
+            // U_i.cmW + r * u_i.cmW
+            uint256[2] memory mulScalarPoint = super.mulScalar([u_i_cmW[0], u_i_cmW[1]], cmT_r[2]);
+            uint256[2] memory cmW = super.add([U_i_cmW_U_i_cmE[0], U_i_cmW_U_i_cmE[1]], mulScalarPoint);
+
+            {
+                uint256[5] memory cmW_x_limbs = LimbsDecomposition.decompose(cmW[0]);
+                uint256[5] memory cmW_y_limbs = LimbsDecomposition.decompose(cmW[1]);
+        
+                for (uint8 k = 0; k < 5; k++) {
+                    public_inputs[4 + k] = cmW_x_limbs[k];
+                    public_inputs[9 + k] = cmW_y_limbs[k];
+                }
+            }
+
+            kzg_cms[0] = cmW;
+        }
+
+        {
+            // U_i.cmE + r * cmT
+            uint256[2] memory mulScalarPoint = super.mulScalar([cmT_r[0], cmT_r[1]], cmT_r[2]);
+            uint256[2] memory cmE = super.add([U_i_cmW_U_i_cmE[2], U_i_cmW_U_i_cmE[3]], mulScalarPoint);
+
+            {
+                uint256[5] memory cmE_x_limbs = LimbsDecomposition.decompose(cmE[0]);
+                uint256[5] memory cmE_y_limbs = LimbsDecomposition.decompose(cmE[1]);
+            
+                for (uint8 k = 0; k < 5; k++) {
+                    public_inputs[14 + k] = cmE_x_limbs[k];
+                    public_inputs[19 + k] = cmE_y_limbs[k];
+                }
+            }
+
+            kzg_cms[1] = cmE;
+        }
+
+        {
+            uint256[2][] memory kzg_pis = new uint256[2][](2);
+            uint256[] memory kzg_challenges = new uint256[](2);
+            uint256[] memory kzg_evals = new uint256[](2);
+            for (uint8 k = 0; k < 2; k++) {
+                kzg_pis[k] = kzg_proof[k];
+                kzg_challenges[k] = challenge_W_challenge_E_kzg_evals[k];
+                kzg_evals[k] = challenge_W_challenge_E_kzg_evals[2 + k];
+            }
+
+            require(super.multiCheck(kzg_cms, kzg_pis, kzg_challenges, kzg_evals), "KZG: verifying proofs for challenges W and E failed");
+        }
+
+        {
+            // add challenges
+            public_inputs[24] = challenge_W_challenge_E_kzg_evals[0];
+            public_inputs[25] = challenge_W_challenge_E_kzg_evals[1];
+            public_inputs[26] = challenge_W_challenge_E_kzg_evals[2];
+            public_inputs[27] = challenge_W_challenge_E_kzg_evals[3];
+
+            uint256[5] memory cmT_x_limbs;
+            uint256[5] memory cmT_y_limbs;
+        
+            cmT_x_limbs = LimbsDecomposition.decompose(cmT_r[0]);
+            cmT_y_limbs = LimbsDecomposition.decompose(cmT_r[1]);
+        
+            for (uint8 k = 0; k < 5; k++) {
+                public_inputs[24 + 4 + k] = cmT_x_limbs[k]; 
+                public_inputs[29 + 4 + k] = cmT_y_limbs[k];
+            }
+
+            bool success_g16 = this.verifyProof(pA, pB, pC, public_inputs);
+            require(success_g16 == true, "Groth16: verifying proof failed");
+        }
+
+        return(true);
+    }
+
+    /**
+     * @notice  Verifies a Nova+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
+     * @dev     Simply reorganization of arguments and call to the `verifyNovaProof` function.
+     */
+    function verifyOpaqueNovaProofWithInputs(
+        uint256 steps,
+        uint256[1] calldata initial_state,
+        uint256[1] calldata final_state,
+        uint256[25] calldata proof
+    ) public override view returns (bool) {
+        uint256[1 + 2 * 1] memory i_z0_zi;
+        i_z0_zi[0] = steps;
+        for (uint256 i = 0; i < 1; i++) {
+            i_z0_zi[i + 1] = initial_state[i];
+            i_z0_zi[i + 1 + 1] = final_state[i];
+        }
+
+        uint256[4] memory U_i_cmW_U_i_cmE = [proof[0], proof[1], proof[2], proof[3]];
+        uint256[2] memory u_i_cmW = [proof[4], proof[5]];
+        uint256[3] memory cmT_r = [proof[6], proof[7], proof[8]];
+        uint256[2] memory pA = [proof[9], proof[10]];
+        uint256[2][2] memory pB = [[proof[11], proof[12]], [proof[13], proof[14]]];
+        uint256[2] memory pC = [proof[15], proof[16]];
+        uint256[4] memory challenge_W_challenge_E_kzg_evals = [proof[17], proof[18], proof[19], proof[20]];
+        uint256[2][2] memory kzg_proof = [[proof[21], proof[22]], [proof[23], proof[24]]];
+
+        return this.verifyNovaProof(
+            i_z0_zi,
+            U_i_cmW_U_i_cmE,
+            u_i_cmW,
+            cmT_r,
+            pA,
+            pB,
+            pC,
+            challenge_W_challenge_E_kzg_evals,
+            kzg_proof
+        );
+    }
+
+    /**
+     * @notice  Verifies a Nova+CycleFold proof given all proof inputs concatenated.
+     * @dev     Simply reorganization of arguments and call to the `verifyNovaProof` function.
+     */
+    function verifyOpaqueNovaProof(uint256[28] calldata proof) public override view returns (bool) {
+        uint256[1] memory z0;
+        uint256[1] memory zi;
+        for (uint256 i = 0; i < 1; i++) {
+            z0[i] = proof[i + 1];
+            zi[i] = proof[i + 1 + 1];
+        }
+
+        uint256[25] memory extracted_proof;
+        for (uint256 i = 0; i < 25; i++) {
+            extracted_proof[i] = proof[3 + i];
+        }
+
+        return this.verifyOpaqueNovaProofWithInputs(proof[0], z0, zi, extracted_proof);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+//! Typed arguments of the NovaDecider contract, ABI-encoded through alloy, so that offchain
+//! services can submit proofs using the ABI of the contract (see
+//! [`crate::evm::compile_solidity_abi`]) instead of the hand-rolled calldata.
+#![allow(non_snake_case)]
+
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use ark_bn254::{Bn254, Fr, G1Projective};
+use ark_groth16::Groth16;
+use folding_schemes::commitment::kzg::KZG;
+use folding_schemes::folding::nova::decider_eth::Proof;
+use folding_schemes::folding::nova::CommittedInstance;
+use folding_schemes::Error;
+use revm::primitives::{keccak256, U256};
+
+use crate::utils::eth::ToEth;
+
+/// Arguments of the verification function of the NovaDecider contract, in the order of the
+/// function's parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NovaDeciderCalldata {
+    /// `[i, z_0, z_i]`
+    pub i_z0_zi: Vec<U256>,
+    /// `[U_i.cmW, U_i.cmE]`
+    pub U_i_cmW_U_i_cmE: [U256; 4],
+    /// `u_i.cmW`
+    pub u_i_cmW: [U256; 2],
+    /// `[cmT, r]`
+    pub cmT_r: [U256; 3],
+    /// Groth16 proof's `A`
+    pub pA: [U256; 2],
+    /// Groth16 proof's `B`
+    pub pB: [[U256; 2]; 2],
+    /// Groth16 proof's `C`
+    pub pC: [U256; 2],
+    /// `[challenge_W, challenge_E, eval_W, eval_E]`
+    pub challenge_W_challenge_E_kzg_evals: [U256; 4],
+    /// `[proof_W, proof_E]`
+    pub kzg_proof: [[U256; 2]; 2],
+}
+
+impl NovaDeciderCalldata {
+    pub fn new(
+        i: Fr,
+        z_0: Vec<Fr>,
+        z_i: Vec<Fr>,
+        running_instance: &CommittedInstance<G1Projective>,
+        incoming_instance: &CommittedInstance<G1Projective>,
+        proof: &Proof<G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+    ) -> Self {
+        let snark_proof = proof.snark_proof();
+        let kzg_proofs = proof.kzg_proofs();
+        let kzg_challenges = proof.kzg_challenges();
+        let pB = words::<4>(&snark_proof.b.to_eth());
+        let kzg_proof = words::<4>(&[kzg_proofs[0].proof, kzg_proofs[1].proof].to_eth());
+        Self {
+            i_z0_zi: [i.to_eth(), z_0.to_eth(), z_i.to_eth()]
+                .concat()
+                .chunks(32)
+                .map(U256::from_be_slice)
+                .collect(),
+            U_i_cmW_U_i_cmE: words(&[running_instance.cmW, running_instance.cmE].to_eth()),
+            u_i_cmW: words(&incoming_instance.cmW.to_eth()),
+            cmT_r: words(&[proof.cmT().to_eth(), proof.r().to_eth()].concat()),
+            pA: words(&snark_proof.a.to_eth()),
+            pB: [[pB[0], pB[1]], [pB[2], pB[3]]],
+            pC: words(&snark_proof.c.to_eth()),
+            challenge_W_challenge_E_kzg_evals: words(
+                &[
+                    kzg_challenges[0],
+                    kzg_challenges[1],
+                    kzg_proofs[0].eval,
+                    kzg_proofs[1].eval,
+                ]
+                .to_eth(),
+            ),
+            kzg_proof: [[kzg_proof[0], kzg_proof[1]], [kzg_proof[2], kzg_proof[3]]],
+        }
+    }
+
+    /// Returns the arguments as dynamically typed Solidity values.
+    pub fn to_values(&self) -> Vec<DynSolValue> {
+        vec![
+            fixed_array(&self.i_z0_zi),
+            fixed_array(&self.U_i_cmW_U_i_cmE),
+            fixed_array(&self.u_i_cmW),
+            fixed_array(&self.cmT_r),
+            fixed_array(&self.pA),
+            DynSolValue::FixedArray(self.pB.iter().map(|p| fixed_array(p)).collect()),
+            fixed_array(&self.pC),
+            fixed_array(&self.challenge_W_challenge_E_kzg_evals),
+            DynSolValue::FixedArray(self.kzg_proof.iter().map(|p| fixed_array(p)).collect()),
+        ]
+    }
+
+    /// ABI-encodes a call to the `verifyNovaProof` function.
+    pub fn abi_encode(&self) -> Vec<u8> {
+        self.abi_encode_for("verifyNovaProof")
+    }
+
+    /// ABI-encodes a call to the verification function named `function_name`, as set in
+    /// [`crate::SolidityTemplateOptions::function_name`].
+    pub fn abi_encode_for(&self, function_name: &str) -> Vec<u8> {
+        let values = self.to_values();
+        let signature = format!(
+            "{function_name}({})",
+            values
+                .iter()
+                .map(|value| value.sol_type_name().unwrap())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        [
+            keccak256(signature)[..4].to_vec(),
+            DynSolValue::Tuple(values).abi_encode_params(),
+        ]
+        .concat()
+    }
+
+    /// ABI-encodes a call to `function`, taken from the ABI of the contract. Fails if the
+    /// arguments do not match the parameters of `function`.
+    pub fn abi_encode_with(&self, function: &Function) -> Result<Vec<u8>, Error> {
+        function
+            .abi_encode_input(&self.to_values())
+            .map_err(|e| Error::Other(e.to_string()))
+    }
+}
+
+fn words<const N: usize>(bytes: &[u8]) -> [U256; N] {
+    let words: Vec<U256> = bytes.chunks(32).map(U256::from_be_slice).collect();
+    words.try_into().unwrap()
+}
+
+fn fixed_array(values: &[U256]) -> DynSolValue {
+    DynSolValue::FixedArray(values.iter().map(|v| DynSolValue::Uint(*v, 256)).collect())
+}
@@ -0,0 +1,250 @@
+//! Packing of the states of a Nova proof into an [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844)
+//! blob, for the `verifyNovaProofWithBlob` function of a decider contract rendered with the
+//! [`crate::SolidityTemplateOptions::blob`] option, which receives the hashes of the states in
+//! calldata while the states themselves are posted as a blob.
+//!
+//! The blob holds `[i, H(z_0), H(z_i), z_0, z_i]`, where `H` is the hash of the states exposed by
+//! the decider circuit ([`StateHashGadget`]), one element per 32 bytes, big-endian, padded with
+//! zeros to [`FIELD_ELEMENTS_PER_BLOB`] elements. The BN254 scalar field is smaller than the
+//! BLS12-381 one, so every BN254 element is a canonical element of the blob.
+//!
+//! The contract checks the openings of the first [`NOVA_BLOB_OPENINGS`] elements of the blob,
+//! which binds the number of steps and the hashes of the states that the proof is verified with to
+//! the blob commitment, so that the states in the blob are the ones whose hashes are proven, as
+//! anyone holding the blob can check. The KZG commitment of the blob and the proofs of these
+//! openings are computed with the KZG ceremony setup, eg. through
+//! [c-kzg](https://github.com/ethereum/c-kzg-4844), and passed as a [`BlobOpening`].
+
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use folding_schemes::{folding::circuits::decider::StateHashGadget, Error};
+use num_bigint::BigUint;
+
+/// Number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// Number of bytes of each field element of a blob.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// Number of bytes of a blob.
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+/// Version byte of the versioned hashes of KZG commitments.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+/// Modulus of the BLS12-381 scalar field, over which the polynomials of the blobs are defined.
+const BLS_MODULUS: &[u8] =
+    b"52435875175126190479447740508185965837690552500527637822603658699938581184513";
+/// Generator of the multiplicative group of the BLS12-381 scalar field, from which EIP-4844 derives
+/// the roots of unity.
+const PRIMITIVE_ROOT_OF_UNITY: u32 = 7;
+/// Number of elements of a Nova blob whose openings are checked by the contract: the number of
+/// steps and the hashes of the initial and final states.
+pub const NOVA_BLOB_OPENINGS: usize = 3;
+
+/// KZG commitment of a blob and proofs of its evaluations at the points of its first
+/// [`NOVA_BLOB_OPENINGS`] elements (see [`evaluation_point`]), as passed to the point evaluation
+/// precompile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlobOpening {
+    pub commitment: [u8; 48],
+    pub proofs: [[u8; 48]; NOVA_BLOB_OPENINGS],
+}
+
+impl BlobOpening {
+    /// Returns the versioned hash of the commitment, which identifies the blob in a transaction.
+    pub fn versioned_hash(&self) -> [u8; 32] {
+        kzg_to_versioned_hash(&self.commitment)
+    }
+
+    /// Returns the commitment followed by the proofs, as taken by the contract.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.commitment[..], &self.proofs.concat()].concat()
+    }
+}
+
+/// Returns the point at which the polynomial of a blob evaluates to its `index`-th element, as a
+/// big-endian word. The blobs are in evaluation form over the [`FIELD_ELEMENTS_PER_BLOB`]-th roots
+/// of unity in bit-reversed order, so this is `ω^bitrev(index)` for the primitive root `ω`.
+pub fn evaluation_point(index: usize) -> Result<[u8; 32], Error> {
+    if index >= FIELD_ELEMENTS_PER_BLOB {
+        return Err(Error::OutOfBounds);
+    }
+    let modulus = BigUint::parse_bytes(BLS_MODULUS, 10).unwrap();
+    let root = BigUint::from(PRIMITIVE_ROOT_OF_UNITY)
+        .modpow(&((&modulus - 1_u32) / FIELD_ELEMENTS_PER_BLOB), &modulus);
+    let log_n = FIELD_ELEMENTS_PER_BLOB.trailing_zeros();
+    let exponent = index.reverse_bits() >> (usize::BITS - log_n);
+    let point = root
+        .modpow(&BigUint::from(exponent), &modulus)
+        .to_bytes_be();
+
+    let mut word = [0u8; 32];
+    word[32 - point.len()..].copy_from_slice(&point);
+    Ok(word)
+}
+
+/// Computes the versioned hash of a KZG commitment, its sha256 digest with the first byte replaced
+/// by [`VERSIONED_HASH_VERSION_KZG`].
+pub fn kzg_to_versioned_hash(commitment: &[u8; 48]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.input(commitment);
+    let mut hash = [0u8; 32];
+    hasher.result(&mut hash);
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Packs `elements` into a blob, padding it with zeros.
+pub fn to_blob(elements: &[Fr]) -> Result<Vec<u8>, Error> {
+    if elements.len() > FIELD_ELEMENTS_PER_BLOB {
+        return Err(Error::OutOfBounds);
+    }
+    let mut blob = Vec::with_capacity(BYTES_PER_BLOB);
+    for element in elements {
+        blob.extend(element.into_bigint().to_bytes_be());
+    }
+    blob.resize(BYTES_PER_BLOB, 0);
+    Ok(blob)
+}
+
+/// Unpacks the [`FIELD_ELEMENTS_PER_BLOB`] elements of `blob`, failing if it is not a blob or if
+/// one of its elements is not a canonical BN254 scalar.
+pub fn from_blob(blob: &[u8]) -> Result<Vec<Fr>, Error> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(Error::NotExpectedLength(blob.len(), BYTES_PER_BLOB));
+    }
+    blob.chunks(BYTES_PER_FIELD_ELEMENT)
+        .map(|bytes| {
+            let element = Fr::from_be_bytes_mod_order(bytes);
+            if element.into_bigint().to_bytes_be() != bytes {
+                return Err(Error::ConversionError(
+                    "blob element".to_string(),
+                    "Fr".to_string(),
+                    "not a canonical BN254 scalar".to_string(),
+                ));
+            }
+            Ok(element)
+        })
+        .collect()
+}
+
+/// Packs the number of steps `i`, the hashes of the states `z_0` and `z_i` of a Nova proof and the
+/// states themselves into a blob.
+pub fn nova_blob(i: Fr, z_0: &[Fr], z_i: &[Fr]) -> Result<Vec<u8>, Error> {
+    let hashes = [
+        StateHashGadget::hash_native(z_0)?,
+        StateHashGadget::hash_native(z_i)?,
+    ];
+    to_blob(&[&[i], &hashes[..], z_0, z_i].concat())
+}
+
+/// Unpacks the number of steps and the states of length `state_len` from a blob built by
+/// [`nova_blob`], failing if the hashes in the blob are not the ones of the states.
+pub fn nova_states_from_blob(
+    blob: &[u8],
+    state_len: usize,
+) -> Result<(Fr, Vec<Fr>, Vec<Fr>), Error> {
+    if NOVA_BLOB_OPENINGS + 2 * state_len > FIELD_ELEMENTS_PER_BLOB {
+        return Err(Error::OutOfBounds);
+    }
+    let elements = from_blob(blob)?;
+    let z_0 = elements[NOVA_BLOB_OPENINGS..NOVA_BLOB_OPENINGS + state_len].to_vec();
+    let z_i = elements[NOVA_BLOB_OPENINGS + state_len..NOVA_BLOB_OPENINGS + 2 * state_len].to_vec();
+    if elements[1] != StateHashGadget::hash_native(&z_0)?
+        || elements[2] != StateHashGadget::hash_native(&z_i)?
+    {
+        return Err(Error::NotEqual);
+    }
+    Ok((elements[0], z_0, z_i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn nova_blob_roundtrip() {
+        let mut rng = ark_std::test_rng();
+        let state_len = 1000;
+        let i = Fr::from(7_u32);
+        let z_0: Vec<Fr> = (0..state_len).map(|_| Fr::rand(&mut rng)).collect();
+        let z_i: Vec<Fr> = (0..state_len).map(|_| Fr::rand(&mut rng)).collect();
+
+        let blob = nova_blob(i, &z_0, &z_i).unwrap();
+        assert_eq!(blob.len(), BYTES_PER_BLOB);
+        // the elements opened by the contract are the number of steps and the hashes of the states
+        assert_eq!(blob[..31], [0u8; 31]);
+        assert_eq!(blob[31], 7);
+        assert_eq!(
+            blob[32..64],
+            StateHashGadget::hash_native(&z_0)
+                .unwrap()
+                .into_bigint()
+                .to_bytes_be()
+        );
+        assert_eq!(
+            nova_states_from_blob(&blob, state_len).unwrap(),
+            (i, z_0.clone(), z_i)
+        );
+
+        // a blob whose state does not match its hash is rejected
+        let mut tampered = blob.clone();
+        tampered[32 * (NOVA_BLOB_OPENINGS + 1) - 1] ^= 1;
+        assert!(matches!(
+            nova_states_from_blob(&tampered, state_len),
+            Err(Error::NotEqual)
+        ));
+
+        let too_long = vec![Fr::from(1_u32); FIELD_ELEMENTS_PER_BLOB + 1];
+        assert!(matches!(to_blob(&too_long), Err(Error::OutOfBounds)));
+        assert!(matches!(
+            from_blob(&blob[1..]),
+            Err(Error::NotExpectedLength(_, BYTES_PER_BLOB))
+        ));
+        let mut non_canonical = blob.clone();
+        non_canonical[32..64].copy_from_slice(&[0xff; 32]);
+        assert!(matches!(
+            from_blob(&non_canonical),
+            Err(Error::ConversionError(..))
+        ));
+    }
+
+    #[test]
+    fn evaluation_points_are_roots_of_unity() {
+        let hex = |point: [u8; 32]| revm::primitives::hex::encode(point);
+        // 1, -1 and the primitive 4th root of unity, as the indices 0, 1 and 2 are bit-reversed
+        // into the exponents 0, 2048 and 1024
+        assert_eq!(
+            hex(evaluation_point(0).unwrap()),
+            "0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            hex(evaluation_point(1).unwrap()),
+            "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000"
+        );
+        assert_eq!(
+            hex(evaluation_point(2).unwrap()),
+            "00000000000000008d51ccce760304d0ec030002760300000001000000000000"
+        );
+        assert!(matches!(
+            evaluation_point(FIELD_ELEMENTS_PER_BLOB),
+            Err(Error::OutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn versioned_hash_is_computed() {
+        // the versioned hash of the commitment of the zero blob, the point at infinity
+        let mut commitment = [0u8; 48];
+        commitment[0] = 0xc0;
+        let hash = kzg_to_versioned_hash(&commitment);
+        assert_eq!(hash[0], VERSIONED_HASH_VERSION_KZG);
+        assert_eq!(
+            hash[1..],
+            revm::primitives::hex::decode(
+                "0657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"
+            )
+            .unwrap()
+        );
+    }
+}
@@ -1,9 +1,15 @@
+use crate::blob::{BlobOpening, NOVA_BLOB_OPENINGS};
 use crate::utils::eth::ToEth;
+use crate::{NovaCycleFoldVerifierKey, SolidityTemplateOptions};
 use ark_bn254::Bn254;
 use ark_groth16::Groth16;
 use crypto::digest::Digest;
 use crypto::sha3::Sha3;
 use folding_schemes::commitment::kzg::KZG;
+use folding_schemes::folding::circuits::decider::StateHashGadget;
+use folding_schemes::folding::hypernova::{
+    cccs::CCCS, decider_eth::Proof as HyperNovaProof, lcccs::LCCCS,
+};
 use folding_schemes::folding::nova::decider_eth::Proof;
 use folding_schemes::folding::nova::CommittedInstance;
 use folding_schemes::Error;
@@ -19,6 +25,26 @@ pub enum NovaVerificationMode {
     Opaque,
     /// Use the `verifyOpaqueNovaProofWithInputs` function.
     OpaqueWithInputs,
+    /// Use the `verifyNovaProof` function of a contract whose decider circuit exposes the hashes
+    /// of the states, passing the hashes of `z_0` and `z_i` instead of the states.
+    Hashed,
+    /// Use the `verifyNovaProofWithState` function of a contract whose decider circuit exposes the
+    /// hashes of the states, which receives `z_0` and `z_i` and hashes them onchain.
+    HashedWithState,
+    /// Use the `processNovaProof` function, which calls the `_onVerified` hook after verifying
+    /// the proof.
+    Process,
+}
+
+/// Number of words of the arguments of the NovaDecider verification function besides the
+/// `2 * z_len` of the states: `i`, `U_i.cmW`, `U_i.cmE`, `u_i.cmW`, `cmT`, `r`, the Groth16 proof,
+/// the KZG challenges and evaluations, and the KZG proofs.
+pub(crate) const NOVA_PROOF_WORDS: usize = 26;
+
+/// Length in bytes of the arguments of the NovaDecider verification function, ie. of its calldata
+/// without the selector, for states of length `z_len`.
+pub(crate) fn nova_proof_len(z_len: usize) -> usize {
+    32 * (NOVA_PROOF_WORDS + 2 * z_len)
 }
 
 /// Formats call data from a vec of bytes to a hashmap
@@ -43,7 +69,48 @@ pub fn prepare_calldata_for_nova_cyclefold_verifier(
     incoming_instance: &CommittedInstance<ark_bn254::G1Projective>,
     proof: &Proof<ark_bn254::G1Projective, KZG<Bn254>, Groth16<Bn254>>,
 ) -> Result<Vec<u8>, Error> {
-    let selector = get_function_selector(verification_mode, z_0.len());
+    prepare_calldata_for_nova_cyclefold_verifier_with_options(
+        verification_mode,
+        &SolidityTemplateOptions::default(),
+        i,
+        z_0,
+        z_i,
+        running_instance,
+        incoming_instance,
+        proof,
+    )
+}
+
+/// Prepares solidity calldata for calling a decider contract rendered with the given `options`,
+/// whose verification function is `options.function_name`.
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_calldata_for_nova_cyclefold_verifier_with_options(
+    verification_mode: NovaVerificationMode,
+    options: &SolidityTemplateOptions,
+    i: ark_bn254::Fr,
+    z_0: Vec<ark_bn254::Fr>,
+    z_i: Vec<ark_bn254::Fr>,
+    running_instance: &CommittedInstance<ark_bn254::G1Projective>,
+    incoming_instance: &CommittedInstance<ark_bn254::G1Projective>,
+    proof: &Proof<ark_bn254::G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    if z_0.len() != z_i.len() {
+        return Err(Error::NotSameLength(
+            "z_0".to_string(),
+            z_0.len(),
+            "z_i".to_string(),
+            z_i.len(),
+        ));
+    }
+    let selector = get_function_selector(verification_mode, &options.function_name, z_0.len());
+
+    let (z_0, z_i) = match verification_mode {
+        NovaVerificationMode::Hashed => (
+            vec![StateHashGadget::hash_native(&z_0)?],
+            vec![StateHashGadget::hash_native(&z_i)?],
+        ),
+        _ => (z_0, z_i),
+    };
 
     Ok([
         selector.to_eth(),
@@ -65,24 +132,223 @@ pub fn prepare_calldata_for_nova_cyclefold_verifier(
     .concat())
 }
 
+/// Checks that `calldata`, as returned by [`prepare_calldata_for_nova_cyclefold_verifier_with_options`]
+/// in the given `verification_mode`, is laid out as the decider contract rendered from `vk` with
+/// `options` expects, so that a mismatch is reported before deploying the contract instead of as a
+/// rejected proof.
+pub fn check_calldata_for_nova_cyclefold_verifier(
+    vk: &NovaCycleFoldVerifierKey,
+    options: &SolidityTemplateOptions,
+    verification_mode: NovaVerificationMode,
+    calldata: &[u8],
+) -> Result<(), Error> {
+    vk.check_state_len()?;
+
+    let hashed_mode = matches!(
+        verification_mode,
+        NovaVerificationMode::Hashed | NovaVerificationMode::HashedWithState
+    );
+    if hashed_mode != vk.hashed_state() {
+        return Err(Error::DeciderHashedStateMismatch {
+            mode: format!("{:?}", verification_mode),
+            hashed_state: vk.hashed_state(),
+        });
+    }
+
+    // every mode passes the selector, `i`, the states and the 25 words of the proof, the states
+    // taking a single word each in the `Hashed` mode
+    let z_len = match verification_mode {
+        NovaVerificationMode::Hashed => 1,
+        _ => vk.state_len(),
+    };
+    let expected_len = 4 + nova_proof_len(z_len);
+    if calldata.len() != expected_len {
+        let words = calldata.len().saturating_sub(4) / 32;
+        if calldata.len() != 4 + 32 * words
+            || words < NOVA_PROOF_WORDS
+            || (words - NOVA_PROOF_WORDS) % 2 != 0
+        {
+            return Err(Error::NotExpectedLength(calldata.len(), expected_len));
+        }
+        return Err(Error::DeciderStateLenMismatch {
+            origin: "calldata".to_string(),
+            expected: z_len,
+            found: (words - NOVA_PROOF_WORDS) / 2,
+        });
+    }
+
+    let selector = get_function_selector(verification_mode, &options.function_name, vk.state_len());
+    if calldata[..4] != selector {
+        return Err(Error::Other(format!(
+            "the selector of the calldata is not the one of the {:?} verification function `{}`",
+            verification_mode, options.function_name
+        )));
+    }
+    Ok(())
+}
+
+/// Prepares solidity calldata for calling the `verifyBatch` function of a decider contract rendered
+/// with the `batch` option, from the calldata of each proof as returned by
+/// [`prepare_calldata_for_nova_cyclefold_verifier`] in the `Explicit` (or `Hashed`) mode.
+pub fn prepare_batch_calldata(proofs_calldata: &[Vec<u8>]) -> Vec<u8> {
+    // the contract decodes each proof as the arguments of its verification function, so the
+    // selectors are dropped
+    let proofs: Vec<&[u8]> = proofs_calldata.iter().map(|c| &c[4..]).collect();
+
+    // head of `bytes[]`: its length and the offsets of its elements, relative to the first offset
+    let mut head = [abi_word(0x20), abi_word(proofs.len())].concat();
+    let mut tail = vec![];
+    for proof in &proofs {
+        head.extend(abi_word(32 * proofs.len() + tail.len()));
+        tail.extend(abi_word(proof.len()));
+        tail.extend_from_slice(proof);
+        tail.resize(tail.len().next_multiple_of(32), 0);
+    }
+
+    [keccak_selector("verifyBatch(bytes[])").to_vec(), head, tail].concat()
+}
+
+/// Prepares solidity calldata for calling the `verify` function of a router contract (see
+/// [`crate::get_router_template`]) with a proof of the circuit `circuit_id`, from the calldata of
+/// the proof as returned by [`prepare_calldata_for_nova_cyclefold_verifier`] in the `Explicit` (or
+/// `Hashed`) mode.
+pub fn prepare_calldata_for_router(circuit_id: usize, proof_calldata: &[u8]) -> Vec<u8> {
+    // the router reads the arguments of the verification function, so the selector is dropped
+    let proof = &proof_calldata[4..];
+    [
+        keccak_selector("verify(uint256,bytes)").to_vec(),
+        abi_word(circuit_id).to_vec(),
+        abi_word(0x40).to_vec(), // offset of proof
+        abi_word(proof.len()).to_vec(),
+        proof.to_vec(),
+    ]
+    .concat()
+}
+
+/// Prepares solidity calldata for calling the `verifyNovaProofWithBlob` function of a decider
+/// contract rendered with the `blob` option, whose decider circuit exposes the hashes of the
+/// states. The calldata holds the hashes of `z_0` and `z_i` and the rest of the proof, while `i`,
+/// `z_0` and `z_i` are posted in the `blob_index`-th blob of the transaction, built by
+/// [`crate::blob::nova_blob`], whose commitment and openings of `i` and of the hashes are given by
+/// `blob_opening`.
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_calldata_for_nova_cyclefold_blob_verifier(
+    blob_index: usize,
+    blob_opening: &BlobOpening,
+    i: ark_bn254::Fr,
+    z_0: Vec<ark_bn254::Fr>,
+    z_i: Vec<ark_bn254::Fr>,
+    running_instance: &CommittedInstance<ark_bn254::G1Projective>,
+    incoming_instance: &CommittedInstance<ark_bn254::G1Projective>,
+    proof: &Proof<ark_bn254::G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    // steps, state hashes and proof, laid out as in the `Hashed` mode
+    let hashed = prepare_calldata_for_nova_cyclefold_verifier(
+        NovaVerificationMode::Hashed,
+        i,
+        z_0,
+        z_i,
+        running_instance,
+        incoming_instance,
+        proof,
+    )?;
+    // offset of `blob_opening`, after the versioned hash, the blob index, the steps, the state
+    // hashes, the proof and the offset itself
+    let offset = 32 * (1 + 1 + 1 + 2 + 25 + 1);
+
+    Ok([
+        keccak_selector(
+            "verifyNovaProofWithBlob(bytes32,uint256,uint256,uint256[2],uint256[25],bytes)",
+        )
+        .to_vec(),
+        blob_opening.versioned_hash().to_vec(),
+        abi_word(blob_index).to_vec(),
+        hashed[4..].to_vec(),
+        abi_word(offset).to_vec(),
+        abi_word(48 * (1 + NOVA_BLOB_OPENINGS)).to_vec(), // length of blob_opening
+        blob_opening.to_bytes(),
+    ]
+    .concat())
+}
+
+/// Prepares solidity calldata for calling the HyperNovaDecider contract
+pub fn prepare_calldata_for_hypernova_verifier(
+    i: ark_bn254::Fr,
+    z_0: Vec<ark_bn254::Fr>,
+    z_i: Vec<ark_bn254::Fr>,
+    running_instance: &LCCCS<ark_bn254::G1Projective>,
+    incoming_instance: &CCCS<ark_bn254::G1Projective>,
+    proof: &HyperNovaProof<ark_bn254::G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    let selector = get_hypernova_function_selector(z_0.len());
+
+    Ok([
+        selector.to_eth(),
+        i.to_eth(),   // i
+        z_0.to_eth(), // z_0
+        z_i.to_eth(), // z_i
+        running_instance.C.to_eth(),
+        incoming_instance.C.to_eth(),
+        proof.rho().to_eth(),             // rho
+        proof.snark_proof().to_eth(),     // pA, pB, pC
+        proof.kzg_challenge().to_eth(),   // challenge
+        proof.kzg_proof().eval.to_eth(),  // eval
+        proof.kzg_proof().proof.to_eth(), // kzg_proof
+    ]
+    .concat())
+}
+
 /// Computes the function selector for the nova cyclefold verifier.
 /// It is computed on the fly since it depends on the IVC state length.
-fn get_function_selector(mode: NovaVerificationMode, state_len: usize) -> [u8; 4] {
+pub(crate) fn get_function_selector(
+    mode: NovaVerificationMode,
+    function_name: &str,
+    state_len: usize,
+) -> [u8; 4] {
     let fn_sig = match mode {
         NovaVerificationMode::Explicit =>
             format!(
-                "verifyNovaProof(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[4],uint256[2][2])",
+                "{function_name}(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[4],uint256[2][2])",
                 state_len * 2 + 1
             ),
         NovaVerificationMode::Opaque =>
-            format!("verifyOpaqueNovaProof(uint256[{}])", 26 + 2 * state_len),
+            format!("verifyOpaqueNovaProof(uint256[{}])", NOVA_PROOF_WORDS + 2 * state_len),
         NovaVerificationMode::OpaqueWithInputs =>
             format!("verifyOpaqueNovaProofWithInputs(uint256,uint256[{state_len}],uint256[{state_len}],uint256[25])"),
+        NovaVerificationMode::Hashed =>
+            format!("{function_name}(uint256[3],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[4],uint256[2][2])"),
+        NovaVerificationMode::Process =>
+            format!(
+                "processNovaProof(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[4],uint256[2][2])",
+                state_len * 2 + 1
+            ),
+        NovaVerificationMode::HashedWithState =>
+            format!("verifyNovaProofWithState(uint256,uint256[{state_len}],uint256[{state_len}],uint256[25])"),
     };
 
+    keccak_selector(&fn_sig)
+}
+
+/// Computes the function selector for the hypernova verifier, which also depends on the IVC state
+/// length.
+fn get_hypernova_function_selector(state_len: usize) -> [u8; 4] {
+    keccak_selector(&format!(
+        "verifyHyperNovaProof(uint256[{}],uint256[4],uint256,uint256[2],uint256[2][2],uint256[2],uint256[2],uint256[2])",
+        state_len * 2 + 1
+    ))
+}
+
+pub(crate) fn keccak_selector(fn_sig: &str) -> [u8; 4] {
     let mut hasher = Sha3::keccak256();
-    hasher.input_str(&fn_sig);
+    hasher.input_str(fn_sig);
     let hash = &mut [0u8; 32];
     hasher.result(hash);
     [hash[0], hash[1], hash[2], hash[3]]
 }
+
+/// Encodes `value` as an ABI `uint256` word.
+fn abi_word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
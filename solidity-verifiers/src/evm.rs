@@ -1,10 +1,14 @@
+use crate::solc::resolve_solc;
 pub use revm;
 use revm::{
-    primitives::{hex, Address, ExecutionResult, Output, TransactTo, TxEnv},
-    Evm as EVM, EvmBuilder, InMemoryDB,
+    primitives::{
+        address, hex, AccountInfo, Address, Bytecode, ExecutionResult, Log, Output, TransactTo,
+        TxEnv, U256,
+    },
+    DatabaseRef, Evm as EVM, EvmBuilder, InMemoryDB,
 };
 use std::{
-    fmt::Debug,
+    fmt::{self, Debug, Display},
     fs::{self, create_dir_all, File},
     io::{self, Write},
     path::PathBuf,
@@ -27,23 +31,72 @@ pub fn save_solidity(name: impl AsRef<str>, solidity: &str) {
         .unwrap();
 }
 
+/// Errors of the compilation of solidity code with `solc`.
+#[derive(Debug, thiserror::Error)]
+pub enum SolcError {
+    #[error("Command 'solc' not found: set SOLC_PATH, add solc to PATH, or enable the 'svm' feature to download it")]
+    NotFound,
+    #[error("SOLC_PATH does not point to a file: {0:?}")]
+    InvalidSolcPath(PathBuf),
+    #[error("Could not read the version of {0:?}")]
+    UnknownVersion(PathBuf),
+    #[error("solc {found} differs from the expected solc {expected}")]
+    VersionMismatch { expected: String, found: String },
+    #[error("Failed to download solc: {0}")]
+    Download(String),
+    #[error("IO error while running 'solc': {0}")]
+    Io(#[from] io::Error),
+    #[error("Compilation fails:\n{0}")]
+    Compilation(String),
+    #[error("Failed to decode hex binary: {0}")]
+    InvalidBinary(String),
+}
+
 /// Compile solidity with `--via-ir` flag, then return creation bytecode.
 ///
-/// # Panics
-/// Panics if executable `solc` can not be found, or compilation fails.
-pub fn compile_solidity(solidity: impl AsRef<[u8]>, contract_name: &str) -> Vec<u8> {
-    // Create a temporary file for solcjs compatibility
-    let temp_file = std::env::temp_dir().join("temp_contract.sol");
-    std::fs::write(&temp_file, solidity.as_ref()).unwrap();
-    
+/// Fails if executable `solc` can not be found, or compilation fails, in which case the error
+/// contains the `solc` stderr.
+pub fn compile_solidity(
+    solidity: impl AsRef<[u8]>,
+    contract_name: &str,
+) -> Result<Vec<u8>, SolcError> {
+    let binary_hex = solc_artifact(solidity, contract_name, "bin")?;
+
+    // Convert hex string to bytes
+    hex::decode(&binary_hex).map_err(|_| SolcError::InvalidBinary(binary_hex))
+}
+
+/// Compile solidity, then return the JSON ABI of the contract `contract_name`, from which typed
+/// bindings can be generated.
+///
+/// Fails if executable `solc` can not be found, or compilation fails.
+pub fn compile_solidity_abi(
+    solidity: impl AsRef<[u8]>,
+    contract_name: &str,
+) -> Result<String, SolcError> {
+    solc_artifact(solidity, contract_name, "abi")
+}
+
+/// Compile solidity, then return the output of the given `kind` (`bin` or `abi`) for the contract
+/// `contract_name`.
+fn solc_artifact(
+    solidity: impl AsRef<[u8]>,
+    contract_name: &str,
+    kind: &str,
+) -> Result<String, SolcError> {
+    // Create a temporary file for solcjs compatibility, named after the contract so that
+    // contracts with different names can be compiled concurrently
+    let temp_file = std::env::temp_dir().join(format!("temp_{contract_name}.sol"));
+    std::fs::write(&temp_file, solidity.as_ref())?;
+
     // Create temporary output directory
-    let temp_output_dir = std::env::temp_dir().join("solc_output");
+    let temp_output_dir = std::env::temp_dir().join(format!("solc_output_{contract_name}"));
     let _ = std::fs::create_dir_all(&temp_output_dir);
-    
-    let output = match Command::new("solc")
+
+    let output = match Command::new(resolve_solc()?)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .arg("--bin")
+        .arg(format!("--{kind}"))
         .arg("--optimize")
         .arg("-o")
         .arg(&temp_output_dir)
@@ -52,61 +105,59 @@ pub fn compile_solidity(solidity: impl AsRef<[u8]>, contract_name: &str) -> Vec<
     {
         Ok(output) => output,
         Err(err) if err.kind() == io::ErrorKind::NotFound => {
-            panic!("Command 'solc' not found");
+            return Err(SolcError::NotFound);
         }
         Err(err) => {
-            panic!("Failed to spawn process with command 'solc':\n{err}");
+            return Err(SolcError::Io(err));
         }
     };
-    
+
     if !output.status.success() {
         // Clean up temporary files
         let _ = std::fs::remove_file(&temp_file);
         let _ = std::fs::remove_dir_all(&temp_output_dir);
-        panic!(
-            "Compilation fails:\n{}",
-            str::from_utf8(&output.stderr).unwrap()
-        );
+        return Err(SolcError::Compilation(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
     }
-    
-    // Read the generated binary file
-    // solcjs generates files with pattern: {full_path_with_underscores}_{contractname}.bin
+
+    // Read the generated file
+    // solcjs generates files with pattern: {full_path_with_underscores}_{contractname}.{kind}
     let temp_file_str = temp_file.to_string_lossy();
-    let bin_file_name = format!("{}_{}.bin", 
-        temp_file_str.replace('.', "_").replace('/', "_").replace('\\', "_"), 
-        contract_name);
-    let bin_file_path = temp_output_dir.join(&bin_file_name);
-    
-    let binary_hex = match std::fs::read_to_string(&bin_file_path) {
-        Ok(content) => content.trim().to_string(),
-        Err(_) => {
-            // Debug: list all files in the output directory
-            if let Ok(entries) = std::fs::read_dir(&temp_output_dir) {
-                eprintln!("Files in output directory:");
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        eprintln!("  {}", entry.file_name().to_string_lossy());
-                    }
-                }
+    let artifact_file_name = format!(
+        "{}_{}.{}",
+        temp_file_str
+            .replace('.', "_")
+            .replace('/', "_")
+            .replace('\\', "_"),
+        contract_name,
+        kind
+    );
+    let mut artifact_file_path = temp_output_dir.join(&artifact_file_name);
+    if !artifact_file_path.is_file() {
+        // solc generates files with pattern: {contractname}.{kind}
+        artifact_file_path = temp_output_dir.join(format!("{contract_name}.{kind}"));
+    }
+
+    let artifact =
+        std::fs::read_to_string(&artifact_file_path).map(|content| content.trim().to_string());
+    if artifact.is_err() {
+        // Debug: list all files in the output directory
+        if let Ok(entries) = std::fs::read_dir(&temp_output_dir) {
+            eprintln!("Files in output directory:");
+            for entry in entries.flatten() {
+                eprintln!("  {}", entry.file_name().to_string_lossy());
             }
-            eprintln!("Expected file name: {}", bin_file_name);
-            eprintln!("Temp file path: {:?}", temp_file);
-            
-            // Clean up temporary files
-            let _ = std::fs::remove_file(&temp_file);
-            let _ = std::fs::remove_dir_all(&temp_output_dir);
-            panic!("Failed to read compiled binary file: {}", bin_file_name);
         }
-    };
-    
+        eprintln!("Expected file name: {}", artifact_file_name);
+        eprintln!("Temp file path: {:?}", temp_file);
+    }
+
     // Clean up temporary files
     let _ = std::fs::remove_file(&temp_file);
     let _ = std::fs::remove_dir_all(&temp_output_dir);
-    
-    // Convert hex string to bytes
-    hex::decode(&binary_hex).unwrap_or_else(|_| {
-        panic!("Failed to decode hex binary: {}", binary_hex);
-    })
+
+    Ok(artifact?)
 }
 
 /// Find binary from `stdout` with given `contract_name`.
@@ -123,6 +174,141 @@ fn find_binary(stdout: &str, contract_name: &str) -> Option<Vec<u8>> {
     Some(hex::decode(binary_section).unwrap())
 }
 
+/// Gas costs of verifying a proof with a decider contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasReport {
+    /// Gas used to deploy the contract.
+    pub deployment_gas: u64,
+    /// Gas used by the verification call, including the intrinsic and calldata costs.
+    pub verification_gas: u64,
+    /// Size of the calldata in bytes.
+    pub calldata_size: usize,
+    /// Whether the contract accepted the proof.
+    pub verified: bool,
+    /// Why the verification call failed, if it reverted or halted.
+    pub revert_reason: Option<RevertReason>,
+}
+
+impl Display for GasReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "deployment gas: {}", self.deployment_gas)?;
+        writeln!(f, "verification gas: {}", self.verification_gas)?;
+        writeln!(f, "calldata size: {} bytes", self.calldata_size)?;
+        write!(f, "verified: {}", self.verified)?;
+        if let Some(reason) = &self.revert_reason {
+            write!(f, "\nrevert reason: {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compiles the given `NovaDecider` solidity code, deploys it in a fresh [`Evm`], and calls it
+/// with `calldata`, reporting the gas used by both transactions.
+///
+/// # Panics
+/// Panics if the deployment fails.
+pub fn gas_report(
+    decider_solidity_code: impl AsRef<[u8]>,
+    calldata: Vec<u8>,
+) -> Result<GasReport, SolcError> {
+    let bytecode = compile_solidity(decider_solidity_code, "NovaDecider")?;
+    let mut evm = Evm::default();
+    let (address, deployment_gas) = evm.create_with_gas(bytecode);
+    let calldata_size = calldata.len();
+    let result = evm.execute(address, calldata);
+    Ok(GasReport {
+        deployment_gas,
+        verification_gas: result.gas_used,
+        calldata_size,
+        verified: result.success && result.output.last() == Some(&1),
+        revert_reason: result.revert_reason,
+    })
+}
+
+/// Why a transaction did not succeed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /// Reverted with `Error(string)`, as `require` and `revert` with a message do.
+    Error(String),
+    /// Reverted with `Panic(uint256)`, as failed assertions, overflows or out-of-bounds accesses do.
+    Panic(U256),
+    /// Reverted with other data, eg. a custom error, which is kept raw.
+    Custom(Vec<u8>),
+    /// The execution halted, eg. by running out of gas.
+    Halt(String),
+}
+
+impl RevertReason {
+    /// Selector of `Error(string)`.
+    const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+    /// Selector of `Panic(uint256)`.
+    const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+    /// Decodes the revert data returned by a transaction.
+    pub fn decode(output: &[u8]) -> Self {
+        let (selector, data) = output.split_at(output.len().min(4));
+        if selector == Self::PANIC_SELECTOR && data.len() == 32 {
+            return Self::Panic(U256::from_be_slice(data));
+        }
+        if selector == Self::ERROR_SELECTOR {
+            if let Some(message) = Self::decode_string(data) {
+                return Self::Error(message);
+            }
+        }
+        Self::Custom(output.to_vec())
+    }
+
+    /// Decodes an ABI-encoded `string`, made of its offset, its length and its padded bytes.
+    fn decode_string(data: &[u8]) -> Option<String> {
+        let word = |i: usize| -> Option<usize> {
+            let word = data.get(i..i + 32)?;
+            word[..24]
+                .iter()
+                .all(|b| *b == 0)
+                .then(|| u64::from_be_bytes(word[24..].try_into().unwrap()) as usize)
+        };
+        let offset = word(0)?;
+        let len = word(offset)?;
+        let bytes = data.get(offset + 32..offset + 32 + len)?;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(message) => write!(f, "Error({message:?})"),
+            Self::Panic(code) => write!(f, "Panic({code:#x})"),
+            Self::Custom(data) => write!(f, "0x{}", hex::encode(data)),
+            Self::Halt(reason) => write!(f, "halted: {reason}"),
+        }
+    }
+}
+
+/// Result of a call transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallResult {
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// Gas used by the transaction, including the intrinsic and calldata costs.
+    pub gas_used: u64,
+    /// The returned data, or the revert data if the transaction reverted.
+    pub output: Vec<u8>,
+    /// Logs emitted by the transaction.
+    pub logs: Vec<Log>,
+    /// Why the transaction failed, if it did.
+    pub revert_reason: Option<RevertReason>,
+}
+
+/// Address of the deterministic deployment proxy (<https://github.com/Arachnid/deterministic-deployment-proxy>),
+/// which deploys the contracts used by [`Evm::create2`].
+pub const CREATE2_FACTORY: Address = address!("4e59b44847b379578588920ca78fbf26c0b4956c");
+
+/// Runtime bytecode of the deterministic deployment proxy, which deploys the code in its calldata
+/// after the first 32 bytes with `CREATE2`, using those bytes as salt, and returns the address of
+/// the deployed contract.
+const CREATE2_FACTORY_CODE: &str = "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3";
+
 /// Evm runner.
 #[derive(Debug)]
 pub struct Evm<'a> {
@@ -144,62 +330,252 @@ impl<'a> Evm<'a> {
     /// # Panics
     /// Panics if execution reverts or halts unexpectedly.
     pub fn create(&mut self, bytecode: Vec<u8>) -> Address {
-        let (_, output) = self.transact_success_or_panic(TxEnv {
+        self.create_with_gas(bytecode).0
+    }
+
+    /// Apply create transaction with given `bytecode` as creation bytecode.
+    /// Returns created `address` and `gas_used`.
+    ///
+    /// # Panics
+    /// Panics if execution reverts or halts unexpectedly.
+    pub fn create_with_gas(&mut self, bytecode: Vec<u8>) -> (Address, u64) {
+        let result = self.transact(TxEnv {
             gas_limit: u64::MAX,
             transact_to: TransactTo::Create,
             data: bytecode.into(),
             ..Default::default()
         });
-        match output {
-            Output::Create(_, Some(address)) => address,
-            _ => unreachable!(),
+        match result {
+            ExecutionResult::Success {
+                gas_used,
+                output: Output::Create(_, Some(address)),
+                ..
+            } => (address, gas_used),
+            result => panic!("Contract creation fails unexpectedly: {result:?}"),
         }
     }
 
+    /// Deploys `bytecode` with `CREATE2` through [`CREATE2_FACTORY`], so that the address of the
+    /// contract only depends on `salt` and `bytecode`, and not on the previous transactions.
+    /// Returns the created `address`.
+    ///
+    /// # Panics
+    /// Panics if execution reverts or halts unexpectedly.
+    pub fn create2(&mut self, bytecode: Vec<u8>, salt: [u8; 32]) -> Address {
+        let factory_code = Bytecode::new_raw(hex::decode(CREATE2_FACTORY_CODE).unwrap().into());
+        self.evm
+            .db_mut()
+            .insert_account_info(CREATE2_FACTORY, AccountInfo::from_bytecode(factory_code));
+
+        let (_, output) = self.call(CREATE2_FACTORY, [salt.to_vec(), bytecode].concat());
+        assert_eq!(output.len(), 20, "Contract creation through CREATE2 fails");
+        Address::from_slice(&output)
+    }
+
     /// Apply call transaction to given `address` with `calldata`.
     /// Returns `gas_used` and `return_data`.
     ///
     /// # Panics
-    /// Panics if execution reverts or halts unexpectedly.
+    /// Panics if execution halts unexpectedly.
     pub fn call(&mut self, address: Address, calldata: Vec<u8>) -> (u64, Vec<u8>) {
-        let (gas_used, output) = self.transact_success_or_panic(TxEnv {
+        let result = self.execute(address, calldata);
+        if let Some(RevertReason::Halt(reason)) = result.revert_reason {
+            panic!(
+                "Transaction halts unexpectedly with gas_used {} and reason {reason}",
+                result.gas_used
+            );
+        }
+        if !result.logs.is_empty() {
+            println!("--- logs from {} ---", result.logs[0].address);
+            for (log_idx, log) in result.logs.iter().enumerate() {
+                println!("log#{log_idx}");
+                for (topic_idx, topic) in log.topics().iter().enumerate() {
+                    println!("  topic{topic_idx}: {topic:?}");
+                }
+            }
+            println!("--- end ---");
+        }
+        (result.gas_used, result.output)
+    }
+
+    /// Apply call transaction to given `address` with `calldata`, returning whether it succeeded,
+    /// the gas used, the returned data, the emitted logs and, on failure, the decoded revert
+    /// reason.
+    pub fn execute(&mut self, address: Address, calldata: Vec<u8>) -> CallResult {
+        self.execute_tx(TxEnv {
             gas_limit: u64::MAX,
             transact_to: TransactTo::Call(address),
             data: calldata.into(),
             ..Default::default()
-        });
-        match output {
-            Output::Call(output) => (gas_used, output.into()),
-            _ => unreachable!(),
-        }
+        })
     }
 
-    fn transact_success_or_panic(&mut self, tx: TxEnv) -> (u64, Output) {
-        *self.evm.tx_mut() = tx;
-        let result = self.evm.transact_commit().unwrap();
-        match result {
+    /// Same as [`Evm::execute`], in a transaction carrying blobs with the given versioned hashes,
+    /// which the contract reads with the `BLOBHASH` opcode. The blobs themselves are not available
+    /// to the EVM, and the caller is funded to pay for their gas.
+    pub fn execute_with_blobs(
+        &mut self,
+        address: Address,
+        calldata: Vec<u8>,
+        blob_hashes: &[[u8; 32]],
+    ) -> CallResult {
+        let caller = TxEnv::default().caller;
+        let mut info = self.evm.db().basic_ref(caller).unwrap().unwrap_or_default();
+        info.balance = U256::from(u64::MAX);
+        self.evm.db_mut().insert_account_info(caller, info);
+
+        self.execute_tx(TxEnv {
+            gas_limit: u64::MAX,
+            transact_to: TransactTo::Call(address),
+            data: calldata.into(),
+            blob_hashes: blob_hashes.iter().map(|hash| (*hash).into()).collect(),
+            max_fee_per_blob_gas: Some(U256::from(1)),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the code at `address` to the runtime code of the contract deployed at `source`, eg. to
+    /// mock a precompile which is not available in this EVM.
+    ///
+    /// # Panics
+    /// Panics if there is no account at `source`.
+    pub fn copy_code(&mut self, source: Address, address: Address) {
+        let db = self.evm.db();
+        let info = db.basic_ref(source).unwrap().expect("no account at source");
+        let code = match info.code {
+            Some(code) => code,
+            None => db.code_by_hash_ref(info.code_hash).unwrap(),
+        };
+        self.evm
+            .db_mut()
+            .insert_account_info(address, AccountInfo::from_bytecode(code));
+    }
+
+    fn execute_tx(&mut self, tx: TxEnv) -> CallResult {
+        match self.transact(tx) {
             ExecutionResult::Success {
                 gas_used,
                 output,
                 logs,
                 ..
-            } => {
-                if !logs.is_empty() {
-                    println!("--- logs from {} ---", logs[0].address);
-                    for (log_idx, log) in logs.iter().enumerate() {
-                        println!("log#{log_idx}");
-                        for (topic_idx, topic) in log.topics().iter().enumerate() {
-                            println!("  topic{topic_idx}: {topic:?}");
-                        }
-                    }
-                    println!("--- end ---");
-                }
-                (gas_used, output)
-            }
-            ExecutionResult::Revert { gas_used, output } => (gas_used, Output::Call(output)),
-            ExecutionResult::Halt { reason, gas_used } => panic!(
-                "Transaction halts unexpectedly with gas_used {gas_used} and reason {reason:?}"
-            ),
+            } => CallResult {
+                success: true,
+                gas_used,
+                output: output.into_data().into(),
+                logs,
+                revert_reason: None,
+            },
+            ExecutionResult::Revert { gas_used, output } => CallResult {
+                success: false,
+                gas_used,
+                revert_reason: Some(RevertReason::decode(&output)),
+                output: output.into(),
+                logs: vec![],
+            },
+            ExecutionResult::Halt { reason, gas_used } => CallResult {
+                success: false,
+                gas_used,
+                output: vec![],
+                logs: vec![],
+                revert_reason: Some(RevertReason::Halt(format!("{reason:?}"))),
+            },
         }
     }
+
+    /// Returns the value at storage `slot` of the contract at `address`.
+    pub fn storage(&self, address: Address, slot: U256) -> U256 {
+        self.evm.db().storage_ref(address, slot).unwrap()
+    }
+
+    fn transact(&mut self, tx: TxEnv) -> ExecutionResult {
+        *self.evm.tx_mut() = tx;
+        self.evm.transact_commit().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{keccak256, B256};
+
+    const REVERTER: &str = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract Reverter {
+    event Called(uint256 value);
+
+    function fail() public pure {
+        revert("Reverter: known message");
+    }
+
+    function outOfBounds() public pure returns (uint256) {
+        uint256[] memory a = new uint256[](1);
+        return a[2];
+    }
+
+    function emitCalled(uint256 value) public returns (bool) {
+        emit Called(value);
+        return true;
+    }
+}
+"#;
+
+    fn selector(signature: &str) -> Vec<u8> {
+        keccak256(signature)[..4].to_vec()
+    }
+
+    #[test]
+    fn evm_call_results() {
+        let mut evm = Evm::default();
+        let address = evm.create(compile_solidity(REVERTER, "Reverter").unwrap());
+
+        let result = evm.execute(address, selector("fail()"));
+        assert!(!result.success);
+        assert_eq!(
+            result.revert_reason,
+            Some(RevertReason::Error("Reverter: known message".to_string()))
+        );
+
+        // out-of-bounds array accesses panic with code 0x32
+        let result = evm.execute(address, selector("outOfBounds()"));
+        assert_eq!(
+            result.revert_reason,
+            Some(RevertReason::Panic(U256::from(0x32)))
+        );
+
+        let value = B256::with_last_byte(42);
+        let result = evm.execute(
+            address,
+            [selector("emitCalled(uint256)"), value.to_vec()].concat(),
+        );
+        assert!(result.success && result.revert_reason.is_none());
+        assert_eq!(result.output.last(), Some(&1));
+        assert_eq!(result.logs.len(), 1);
+        assert_eq!(result.logs[0].address, address);
+        assert_eq!(result.logs[0].topics()[0], keccak256("Called(uint256)"));
+        assert_eq!(result.logs[0].data.data.to_vec(), value.to_vec());
+    }
+
+    #[test]
+    fn evm_create2_is_deterministic() {
+        let bytecode = compile_solidity(REVERTER, "Reverter").unwrap();
+        let salt = [7u8; 32];
+
+        let mut evm = Evm::default();
+        // a previous deployment changes the address of `create`, but not the one of `create2`
+        evm.create(bytecode.clone());
+        let address = evm.create2(bytecode.clone(), salt);
+        assert_eq!(address, Evm::default().create2(bytecode.clone(), salt));
+        assert_eq!(address, CREATE2_FACTORY.create2_from_code(salt, bytecode));
+
+        let result = evm.execute(address, selector("fail()"));
+        assert!(matches!(result.revert_reason, Some(RevertReason::Error(_))));
+    }
+
+    #[test]
+    fn compile_solidity_reports_errors() {
+        let err = compile_solidity("contract Broken {", "Broken").unwrap_err();
+        assert!(matches!(err, SolcError::Compilation(stderr) if !stderr.is_empty()));
+    }
 }
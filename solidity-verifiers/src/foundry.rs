@@ -0,0 +1,91 @@
+//! Export of the NovaDecider contract as a [Foundry](https://book.getfoundry.sh) project, together
+//! with a test exercising it with a proof.
+
+use askama::Template;
+use revm::primitives::hex;
+use std::{fs, io, path::Path};
+
+use crate::{get_decider_template_for_cyclefold_decider, NovaCycleFoldVerifierKey};
+
+/// Minimal Foundry configuration of the exported project. The optimizer is enabled as in
+/// [`crate::evm::compile_solidity`], to keep the contract under the EVM's code size limit.
+const FOUNDRY_TOML: &str = r#"[profile.default]
+src = "src"
+test = "test"
+out = "out"
+libs = []
+optimizer = true
+"#;
+
+#[derive(Template)]
+#[template(path = "nova_decider_foundry_test.askama.sol", ext = "sol")]
+struct NovaDeciderFoundryTest {
+    /// Hex encoded calldata verifying the proof.
+    calldata: String,
+}
+
+/// Writes to `dir` a Foundry project with the NovaDecider contract for `decider_vk` at
+/// `src/NovaDecider.sol`, a test at `test/NovaDecider.t.sol` checking that the contract accepts
+/// `proof_calldata` (as returned by
+/// [`crate::calldata::prepare_calldata_for_nova_cyclefold_verifier`]) and rejects a tampered
+/// version of it, and a `foundry.toml`. Running `forge test` in `dir` then runs the tests.
+pub fn export_foundry_project(
+    dir: impl AsRef<Path>,
+    decider_vk: NovaCycleFoldVerifierKey,
+    proof_calldata: &[u8],
+) -> io::Result<()> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir.join("src"))?;
+    fs::create_dir_all(dir.join("test"))?;
+
+    fs::write(dir.join("foundry.toml"), FOUNDRY_TOML)?;
+    fs::write(
+        dir.join("src").join("NovaDecider.sol"),
+        get_decider_template_for_cyclefold_decider(decider_vk),
+    )?;
+    let test = NovaDeciderFoundryTest {
+        calldata: hex::encode(proof_calldata),
+    }
+    .render()
+    .map_err(io::Error::other)?;
+    fs::write(dir.join("test").join("NovaDecider.t.sol"), test)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_std::rand::RngCore;
+
+    use super::*;
+    use crate::verifiers::tests::{setup, DEFAULT_SETUP_LEN};
+    use folding_schemes::folding::nova::decider_eth::VerifierParam as DeciderVerifierParam;
+
+    #[test]
+    fn foundry_project_exports() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+        let decider_vp = DeciderVerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: kzg_vk,
+        };
+        let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp, 1));
+
+        let mut calldata = vec![0u8; 900];
+        ark_std::test_rng().fill_bytes(&mut calldata);
+
+        let dir = std::env::temp_dir().join("sonobe_foundry_project_exports");
+        export_foundry_project(&dir, nova_cyclefold_vk, &calldata).unwrap();
+
+        assert!(dir.join("foundry.toml").is_file());
+        let contract = fs::read_to_string(dir.join("src").join("NovaDecider.sol")).unwrap();
+        assert!(contract.contains("contract NovaDecider is"));
+
+        // the calldata embedded in the test round-trips
+        let test = fs::read_to_string(dir.join("test").join("NovaDecider.t.sol")).unwrap();
+        let start = test.find("hex\"").unwrap() + 4;
+        let end = start + test[start..].find('"').unwrap();
+        assert_eq!(hex::decode(&test[start..end]).unwrap(), calldata);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
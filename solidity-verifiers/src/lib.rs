@@ -1,10 +1,19 @@
+#[cfg(feature = "abi")]
+pub mod abi;
+pub mod blob;
 pub mod calldata;
 pub mod evm;
+pub mod foundry;
+pub mod solc;
 pub mod utils;
 pub mod verifiers;
 
+pub use foundry::export_foundry_project;
 pub use verifiers::*;
 pub use verifiers::{
-    get_decider_template_for_cyclefold_decider, Groth16VerifierKey, KZG10VerifierKey,
-    NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    get_decider_template_for_cyclefold_decider,
+    get_decider_template_for_cyclefold_decider_with_options,
+    get_decider_template_for_hypernova_decider, get_router_template, Groth16VerifierKey,
+    HyperNovaVerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    SolidityTemplateOptions,
 };
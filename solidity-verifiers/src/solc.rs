@@ -0,0 +1,203 @@
+//! Discovery of the `solc` binary used by [`crate::evm::compile_solidity`].
+//!
+//! The binary is looked up, in order, at the path given by the `SOLC_PATH` environment variable, in
+//! the directories of `PATH`, and, with the `svm` feature, in the cache of
+//! [svm-rs](https://github.com/alloy-rs/svm-rs), which downloads the [`SOLC_VERSION`] release if
+//! needed.
+
+use std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+use crate::evm::SolcError;
+use crate::SOLC_VERSION;
+
+/// Environment variable with the path of the `solc` binary to use.
+pub const SOLC_PATH_ENV: &str = "SOLC_PATH";
+
+/// Returns the path of the `solc` binary, resolving it on the first call. Warns if its version
+/// differs from [`SOLC_VERSION`], which the templates are tested with.
+pub fn resolve_solc() -> Result<PathBuf, SolcError> {
+    static SOLC: OnceLock<PathBuf> = OnceLock::new();
+    if let Some(path) = SOLC.get() {
+        return Ok(path.clone());
+    }
+
+    let path = locate_solc(env::var_os(SOLC_PATH_ENV), env::var_os("PATH"))?;
+    if let Err(err) = check_solc_version(&path, SOLC_VERSION) {
+        log::warn!("{err}");
+    }
+    Ok(SOLC.get_or_init(|| path).clone())
+}
+
+/// Locates `solc` from the given values of the `SOLC_PATH` and `PATH` environment variables,
+/// falling back to downloading it when the `svm` feature is enabled.
+fn locate_solc(solc_path: Option<OsString>, path: Option<OsString>) -> Result<PathBuf, SolcError> {
+    if let Some(solc_path) = solc_path {
+        let solc_path = PathBuf::from(solc_path);
+        if !solc_path.is_file() {
+            return Err(SolcError::InvalidSolcPath(solc_path));
+        }
+        return Ok(solc_path);
+    }
+    if let Some(solc) = path.and_then(|path| find_in_path("solc", &path)) {
+        return Ok(solc);
+    }
+
+    install_solc(SOLC_VERSION)
+}
+
+/// Returns the first file named `name` in the directories of `path`.
+fn find_in_path(name: &str, path: &OsString) -> Option<PathBuf> {
+    env::split_paths(path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Returns the version of the `solc` binary at `solc`, eg. `0.8.28`.
+pub fn solc_version(solc: &Path) -> Result<String, SolcError> {
+    let output = Command::new(solc).arg("--version").output()?;
+    parse_solc_version(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| SolcError::UnknownVersion(solc.to_path_buf()))
+}
+
+/// Checks that the version of the `solc` binary at `solc` is `expected`.
+pub fn check_solc_version(solc: &Path, expected: &str) -> Result<(), SolcError> {
+    let found = solc_version(solc)?;
+    if found != expected {
+        return Err(SolcError::VersionMismatch {
+            expected: expected.to_string(),
+            found,
+        });
+    }
+    Ok(())
+}
+
+/// Parses the version out of the output of `solc --version`, which is `Version: 0.8.28+commit...`
+/// for solc, and `0.8.28+commit...` for solcjs.
+fn parse_solc_version(output: &str) -> Option<String> {
+    output.split_whitespace().find_map(|token| {
+        let version = token.split('+').next()?;
+        let is_version = version.split('.').count() == 3
+            && version
+                .split('.')
+                .all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+        is_version.then(|| version.to_string())
+    })
+}
+
+/// Returns the `solc` binary of the given `version` from the svm-rs cache, downloading it if it is
+/// not there. svm-rs checks the checksum of the downloaded binary against the one published in the
+/// list of solc releases.
+#[cfg(feature = "svm")]
+fn install_solc(version: &str) -> Result<PathBuf, SolcError> {
+    let solc = svm::version_binary(version);
+    if solc.is_file() {
+        return Ok(solc);
+    }
+    let version =
+        semver::Version::parse(version).map_err(|e| SolcError::Download(e.to_string()))?;
+    svm::blocking_install(&version).map_err(|e| SolcError::Download(e.to_string()))
+}
+
+#[cfg(not(feature = "svm"))]
+fn install_solc(_version: &str) -> Result<PathBuf, SolcError> {
+    Err(SolcError::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Creates a directory named `name` in the temp dir, with an empty `solc` file if `with_solc`.
+    fn fake_dir(name: &str, with_solc: bool) -> PathBuf {
+        let dir = env::temp_dir().join(name);
+        fs::create_dir_all(&dir).unwrap();
+        if with_solc {
+            fs::write(dir.join("solc"), "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn solc_is_located() {
+        let without_solc = fake_dir("sonobe_solc_without", false);
+        let with_solc = fake_dir("sonobe_solc_with", true);
+        let path = env::join_paths([&without_solc, &with_solc]).unwrap();
+
+        // PATH is probed in order
+        assert_eq!(
+            locate_solc(None, Some(path.clone())).unwrap(),
+            with_solc.join("solc")
+        );
+        // SOLC_PATH takes precedence over PATH
+        let solc_path = without_solc.join("my-solc");
+        fs::write(&solc_path, "").unwrap();
+        assert_eq!(
+            locate_solc(Some(solc_path.clone().into()), Some(path.clone())).unwrap(),
+            solc_path
+        );
+        // an invalid SOLC_PATH is reported instead of being ignored
+        let missing = without_solc.join("missing");
+        assert!(matches!(
+            locate_solc(Some(missing.into()), Some(path)),
+            Err(SolcError::InvalidSolcPath(_))
+        ));
+        #[cfg(not(feature = "svm"))]
+        assert!(matches!(
+            locate_solc(None, Some(without_solc.clone().into())),
+            Err(SolcError::NotFound)
+        ));
+
+        fs::remove_dir_all(without_solc).unwrap();
+        fs::remove_dir_all(with_solc).unwrap();
+    }
+
+    #[test]
+    fn solc_version_is_parsed() {
+        let solc = "solc, the solidity compiler commandline interface\nVersion: 0.8.28+commit.7893614a.Linux.g++\n";
+        assert_eq!(parse_solc_version(solc), Some("0.8.28".to_string()));
+        assert_eq!(
+            parse_solc_version("0.8.26+commit.8a97fa7a.Emscripten.clang\n"),
+            Some("0.8.26".to_string())
+        );
+        assert_eq!(parse_solc_version("solc: command not found"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn solc_version_mismatch_is_detected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = fake_dir("sonobe_solc_version", false);
+        let solc = dir.join("solc");
+        fs::write(
+            &solc,
+            "#!/bin/sh\necho 'Version: 0.8.1+commit.df193b15.Linux.g++'\n",
+        )
+        .unwrap();
+        fs::set_permissions(&solc, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(check_solc_version(&solc, "0.8.1").is_ok());
+        assert!(matches!(
+            check_solc_version(&solc, "0.8.28"),
+            Err(SolcError::VersionMismatch { found, .. }) if found == "0.8.1"
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Downloads the pinned solc release, needs network access.
+    #[cfg(feature = "svm")]
+    #[ignore]
+    #[test]
+    fn solc_is_downloaded() {
+        let solc = install_solc(SOLC_VERSION).unwrap();
+        assert_eq!(solc_version(&solc).unwrap(), SOLC_VERSION);
+    }
+}
@@ -1,13 +1,31 @@
 /// Defines encodings of G1 and G2 elements for use in Solidity templates.
-use ark_bn254::{Fq, G1Affine, G2Affine};
+use ark_bn254::{Fq, Fr, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
 use std::fmt::{self, Display};
 
+/// Writes `x` as a decimal integer, which, unlike the `Display` implementation of the field
+/// elements, also writes `0` for zero.
+fn write_decimal<F: PrimeField>(f: &mut fmt::Formatter<'_>, x: F) -> fmt::Result {
+    let x: BigUint = x.into();
+    write!(f, "{x}")
+}
+
 #[derive(Debug, Default)]
 pub struct FqWrapper(pub Fq);
 
 impl Display for FqWrapper {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write_decimal(f, self.0)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FrWrapper(pub Fr);
+
+impl Display for FrWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_decimal(f, self.0)
     }
 }
 
@@ -16,7 +34,7 @@ pub struct G1Repr(pub [FqWrapper; 2]);
 
 impl Display for G1Repr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:#?}", self.0)
+        write!(f, "[{}, {}]", self.0[0], self.0[1])
     }
 }
 
@@ -30,7 +48,11 @@ pub struct G2Repr(pub [[FqWrapper; 2]; 2]);
 
 impl Display for G2Repr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:#?}", self.0)
+        write!(
+            f,
+            "[[{}, {}], [{}, {}]]",
+            self.0[0][0], self.0[0][1], self.0[1][0], self.0[1][1]
+        )
     }
 }
 
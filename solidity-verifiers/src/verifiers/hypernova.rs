@@ -0,0 +1,290 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective};
+use ark_ff::PrimeField;
+use ark_groth16::VerifyingKey as ArkG16VerifierKey;
+use ark_poly_commit::kzg10::VerifierKey as ArkKZG10VerifierKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use askama::Template;
+
+use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
+use folding_schemes::folding::nova::decider_eth::VerifierParam as DeciderVerifierParam;
+
+use super::g16::Groth16Verifier;
+use super::kzg::KZG10Verifier;
+use crate::utils::{encoding::FrWrapper, HeaderInclusion};
+use crate::{Groth16VerifierKey, KZG10VerifierKey, ProtocolVerifierKey, PRAGMA_GROTH16_VERIFIER};
+
+pub fn get_decider_template_for_hypernova_decider(hypernova_vk: HyperNovaVerifierKey) -> String {
+    HeaderInclusion::<HyperNovaDecider>::builder()
+        .template(hypernova_vk)
+        .build()
+        .render()
+        .unwrap()
+}
+
+#[derive(Template, Default)]
+#[template(path = "hypernova_decider.askama.sol", ext = "sol")]
+pub struct HyperNovaDecider {
+    pp_hash: FrWrapper, // public params hash
+    groth16_verifier: Groth16Verifier,
+    kzg10_verifier: KZG10Verifier,
+    // z_len denotes the FCircuit state (z_i) length
+    z_len: usize,
+    public_inputs_len: usize,
+    num_limbs: usize,
+    bits_per_limb: usize,
+}
+
+impl From<HyperNovaVerifierKey> for HyperNovaDecider {
+    fn from(value: HyperNovaVerifierKey) -> Self {
+        let groth16_verifier = Groth16Verifier::from(value.g16_vk);
+        let public_inputs_len = groth16_verifier.gamma_abc_len;
+        let bits_per_limb = NonNativeUintVar::<Fq>::bits_per_limb();
+        Self {
+            pp_hash: FrWrapper(value.pp_hash),
+            groth16_verifier,
+            kzg10_verifier: KZG10Verifier::from(value.kzg_vk),
+            z_len: value.z_len,
+            public_inputs_len,
+            // limbs of the coordinates of the commitments, which are elements of `Fq`
+            num_limbs: (Fq::MODULUS_BIT_SIZE as usize).div_ceil(bits_per_limb),
+            bits_per_limb,
+        }
+    }
+}
+
+#[derive(CanonicalDeserialize, CanonicalSerialize, PartialEq, Debug, Clone)]
+pub struct HyperNovaVerifierKey {
+    pp_hash: Fr,
+    g16_vk: Groth16VerifierKey,
+    kzg_vk: KZG10VerifierKey,
+    z_len: usize,
+}
+
+impl ProtocolVerifierKey for HyperNovaVerifierKey {
+    const PROTOCOL_NAME: &'static str = "HyperNova";
+
+    fn render_as_template(self, pragma: Option<String>) -> Vec<u8> {
+        HeaderInclusion::<HyperNovaDecider>::builder()
+            .pragma_version(pragma.unwrap_or(PRAGMA_GROTH16_VERIFIER.to_string()))
+            .template(self)
+            .build()
+            .render()
+            .unwrap()
+            .into_bytes()
+    }
+}
+
+impl From<(Fr, Groth16VerifierKey, KZG10VerifierKey, usize)> for HyperNovaVerifierKey {
+    fn from(value: (Fr, Groth16VerifierKey, KZG10VerifierKey, usize)) -> Self {
+        Self {
+            pp_hash: value.0,
+            g16_vk: value.1,
+            kzg_vk: value.2,
+            z_len: value.3,
+        }
+    }
+}
+
+// implements From assuming that the 'batchCheck' method from the KZG10 template will not be used
+// in the HyperNovaDecider verifier contract. Notice that HyperNova's onchain decider reuses Nova's
+// `VerifierParam`.
+impl
+    From<(
+        DeciderVerifierParam<G1Projective, ArkKZG10VerifierKey<Bn254>, ArkG16VerifierKey<Bn254>>,
+        usize,
+    )> for HyperNovaVerifierKey
+{
+    fn from(
+        value: (
+            DeciderVerifierParam<
+                G1Projective,
+                ArkKZG10VerifierKey<Bn254>,
+                ArkG16VerifierKey<Bn254>,
+            >,
+            usize,
+        ),
+    ) -> Self {
+        let decider_vp = value.0;
+        let g16_vk = Groth16VerifierKey::from(decider_vp.snark_vp);
+        // pass `Vec::new()` since batchCheck will not be used
+        let kzg_vk = KZG10VerifierKey::from((decider_vp.cs_vp, Vec::new()));
+        Self {
+            pp_hash: decider_vp.pp_hash,
+            g16_vk,
+            kzg_vk,
+            z_len: value.1,
+        }
+    }
+}
+
+impl HyperNovaVerifierKey {
+    pub fn new(
+        pp_hash: Fr,
+        vkey_g16: ArkG16VerifierKey<Bn254>,
+        vkey_kzg: ArkKZG10VerifierKey<Bn254>,
+        crs_points: Vec<G1Affine>,
+        z_len: usize,
+    ) -> Self {
+        Self {
+            pp_hash,
+            g16_vk: Groth16VerifierKey::from(vkey_g16),
+            kzg_vk: KZG10VerifierKey::from((vkey_kzg, crs_points)),
+            z_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as G1};
+    use ark_groth16::Groth16;
+    use ark_grumpkin::Projective as G2;
+    use askama::Template;
+    use std::time::Instant;
+
+    use super::{DeciderVerifierParam, HyperNovaDecider};
+    use crate::calldata::prepare_calldata_for_hypernova_verifier;
+    use crate::verifiers::tests::{setup, DEFAULT_SETUP_LEN};
+    use crate::{
+        evm::{compile_solidity, save_solidity, Evm},
+        utils::HeaderInclusion,
+        verifiers::hypernova::get_decider_template_for_hypernova_decider,
+        HyperNovaVerifierKey, ProtocolVerifierKey,
+    };
+    use folding_schemes::{
+        commitment::{kzg::KZG, pedersen::Pedersen},
+        folding::{
+            hypernova::{decider_eth::Decider as DeciderEth, HyperNova},
+            nova::PreprocessorParam,
+            traits::CommittedInstanceOps,
+        },
+        frontend::{utils::CubicFCircuit, FCircuit},
+        transcript::poseidon::poseidon_canonical_config,
+        Decider, FoldingScheme,
+    };
+
+    const MU: usize = 1;
+    const NU: usize = 1;
+
+    type HN<FC> = HyperNova<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, MU, NU, false>;
+    type DECIDER<FC> =
+        DeciderEth<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, Groth16<Bn254>, HN<FC>, MU, NU>;
+
+    #[test]
+    fn hypernova_vk_serde_roundtrip() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+
+        let decider_vp = DeciderVerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: kzg_vk,
+        };
+        let hypernova_vk = HyperNovaVerifierKey::from((decider_vp, 1));
+
+        let mut bytes = vec![];
+        hypernova_vk
+            .serialize_protocol_verifier_key(&mut bytes)
+            .unwrap();
+        let obtained_hypernova_vk =
+            HyperNovaVerifierKey::deserialize_protocol_verifier_key(bytes.as_slice()).unwrap();
+
+        assert_eq!(hypernova_vk, obtained_hypernova_vk)
+    }
+
+    #[test]
+    fn hypernova_decider_template_renders() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+        let decider_vp = DeciderVerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: kzg_vk,
+        };
+        let hypernova_vk = HyperNovaVerifierKey::from((decider_vp, 1));
+
+        let decider_solidity_code = HeaderInclusion::<HyperNovaDecider>::builder()
+            .template(hypernova_vk)
+            .build();
+
+        save_solidity(
+            "HyperNovaDecider.sol",
+            &decider_solidity_code.render().unwrap(),
+        );
+    }
+
+    /// Actions performed by this test:
+    /// - runs HyperNova for the CubicFCircuit
+    /// - generates a DeciderEth proof, and executes it through the EVM
+    /// - modifies i, z_0 and z_i in the calldata and checks that it does not pass the EVM check
+    #[test]
+    fn hypernova_solidity_verifier() {
+        type FC = CubicFCircuit<Fr>;
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let f_circuit = FC::new(()).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit);
+        let hypernova_params = HN::<FC>::preprocess(&mut rng, &prep_param).unwrap();
+        let (decider_pp, decider_vp) =
+            DECIDER::<FC>::preprocess(&mut rng, (hypernova_params.clone(), f_circuit.state_len()))
+                .unwrap();
+
+        let hypernova_vk = HyperNovaVerifierKey::from((decider_vp.clone(), f_circuit.state_len()));
+
+        let mut hypernova =
+            HN::<FC>::init(&hypernova_params, f_circuit, vec![Fr::from(3_u32)]).unwrap();
+        for _ in 0..3 {
+            hypernova
+                .prove_step(&mut rng, (), Some((vec![], vec![])))
+                .unwrap();
+        }
+
+        let start = Instant::now();
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, hypernova.clone()).unwrap();
+        println!("generated Decider proof: {:?}", start.elapsed());
+
+        let verified = DECIDER::<FC>::verify(
+            decider_vp,
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_i.clone(),
+            &hypernova.U_i.get_commitments(),
+            &hypernova.u_i.get_commitments(),
+            &proof,
+        )
+        .unwrap();
+        assert!(verified);
+
+        let decider_solidity_code = get_decider_template_for_hypernova_decider(hypernova_vk);
+        let hypernova_verifier_bytecode =
+            compile_solidity(decider_solidity_code, "HyperNovaDecider").unwrap();
+
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(hypernova_verifier_bytecode);
+
+        let calldata: Vec<u8> = prepare_calldata_for_hypernova_verifier(
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_i.clone(),
+            &hypernova.U_i,
+            &hypernova.u_i,
+            &proof,
+        )
+        .unwrap();
+
+        let (_, output) = evm.call(verifier_address, calldata.clone());
+        assert_eq!(*output.last().unwrap(), 1);
+
+        // change i (bytes 4 - 35), z_0 (bytes 36 - 67) and z_i (bytes 68 - 99) to make the EVM
+        // check fail
+        for byte in [35, 67, 99] {
+            let mut invalid_calldata = calldata.clone();
+            invalid_calldata[byte] += 1;
+            let (_, output) = evm.call(verifier_address, invalid_calldata);
+            assert_eq!(*output.last().unwrap(), 0);
+        }
+    }
+}
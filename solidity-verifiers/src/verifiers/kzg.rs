@@ -78,7 +78,7 @@ mod tests {
         utils::HeaderInclusion,
         ProtocolVerifierKey,
     };
-    use ark_bn254::{Bn254, Fr};
+    use ark_bn254::{Bn254, Fr, G1Projective as G1};
     use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
     use ark_ec::{AffineRepr, CurveGroup};
     use ark_ff::{BigInteger, PrimeField};
@@ -96,6 +96,7 @@ mod tests {
     use crate::verifiers::tests::{setup, DEFAULT_SETUP_LEN};
 
     const FUNCTION_SELECTOR_KZG10_CHECK: [u8; 4] = [0x9e, 0x78, 0xcc, 0xf7];
+    const FUNCTION_SELECTOR_KZG10_MULTI_CHECK: [u8; 4] = [0xdf, 0xc4, 0xf6, 0x75];
 
     #[test]
     fn kzg_vk_serde_roundtrip() {
@@ -121,7 +122,7 @@ mod tests {
             .render()
             .unwrap();
 
-        let kzg_verifier_bytecode = compile_solidity(res, "KZG10Verifier");
+        let kzg_verifier_bytecode = compile_solidity(res, "KZG10Verifier").unwrap();
         let mut evm = Evm::default();
         _ = evm.create(kzg_verifier_bytecode);
     }
@@ -147,7 +148,7 @@ mod tests {
             .render()
             .unwrap();
 
-        let kzg_verifier_bytecode = compile_solidity(template, "KZG10Verifier");
+        let kzg_verifier_bytecode = compile_solidity(template, "KZG10Verifier").unwrap();
         let mut evm = Evm::default();
         let verifier_address = evm.create(kzg_verifier_bytecode);
 
@@ -180,4 +181,87 @@ mod tests {
         let (_, output) = evm.call(verifier_address, calldata);
         assert_eq!(*output.last().unwrap(), 0);
     }
+
+    #[test]
+    fn kzg_verifier_multi_check_accepts_and_rejects_proofs() {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let n_proofs = 2;
+
+        let (_, kzg_pk, kzg_vk, _, _, _) = setup(DEFAULT_SETUP_LEN);
+        let kzg_vk = KZG10VerifierKey::from((kzg_vk.clone(), kzg_pk.powers_of_g[0..3].to_vec()));
+
+        let vs: Vec<Vec<Fr>> = (0..n_proofs)
+            .map(|_| {
+                std::iter::repeat_with(|| Fr::rand(&mut rng))
+                    .take(DEFAULT_SETUP_LEN)
+                    .collect()
+            })
+            .collect();
+        let cms = vs
+            .iter()
+            .map(|v| KZG::<Bn254>::commit(&kzg_pk, v, &Fr::zero()).unwrap())
+            .collect::<Vec<_>>();
+        let challenges: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(n_proofs)
+            .collect();
+        let openings: Vec<(&[Fr], Fr)> = vs.iter().map(|v| (&v[..], Fr::zero())).collect();
+        let proofs =
+            KZG::<Bn254>::batch_prove_with_challenges(&kzg_pk, &challenges, &openings, None)
+                .unwrap();
+
+        let template = HeaderInclusion::<KZG10Verifier>::builder()
+            .template(kzg_vk)
+            .build()
+            .render()
+            .unwrap();
+
+        let kzg_verifier_bytecode = compile_solidity(template, "KZG10Verifier").unwrap();
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(kzg_verifier_bytecode);
+
+        // abi encoding of `(uint256[2][] c, uint256[2][] pi, uint256[] x, uint256[] y)`
+        let word = |n: usize| {
+            let mut w = [0u8; 32];
+            w[24..].copy_from_slice(&(n as u64).to_be_bytes());
+            w.to_vec()
+        };
+        let points = |points: Vec<G1>| -> Vec<u8> {
+            let mut bytes = word(points.len());
+            for p in points {
+                let (x, y) = p.into_affine().xy().unwrap();
+                bytes.extend(x.into_bigint().to_bytes_be());
+                bytes.extend(y.into_bigint().to_bytes_be());
+            }
+            bytes
+        };
+        let scalars = |scalars: Vec<Fr>| -> Vec<u8> {
+            let mut bytes = word(scalars.len());
+            for s in scalars {
+                bytes.extend(s.into_bigint().to_bytes_be());
+            }
+            bytes
+        };
+        let tails = [
+            points(cms),
+            points(proofs.iter().map(|p| p.proof).collect()),
+            scalars(challenges),
+            scalars(proofs.iter().map(|p| p.eval).collect()),
+        ];
+        let mut offset = 32 * tails.len();
+        let mut calldata = FUNCTION_SELECTOR_KZG10_MULTI_CHECK.to_vec();
+        for tail in &tails {
+            calldata.extend(word(offset));
+            offset += tail.len();
+        }
+        calldata.extend(tails.concat());
+
+        let (_, output) = evm.call(verifier_address, calldata.clone());
+        assert_eq!(*output.last().unwrap(), 1);
+
+        // change the last evaluation to make the batch invalid
+        let last_calldata_element = calldata.last_mut().unwrap();
+        *last_calldata_element = last_calldata_element.wrapping_add(1);
+        let (_, output) = evm.call(verifier_address, calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+    }
 }
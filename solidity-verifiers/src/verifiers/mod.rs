@@ -3,20 +3,59 @@
 
 // Pragma statements for verifiers
 pub const PRAGMA_GROTH16_VERIFIER: &str = "pragma solidity >=0.7.0 <0.9.0;"; // from snarkjs, avoid changing
-pub const PRAGMA_KZG10_VERIFIER: &str = "pragma solidity >=0.8.1 <=0.8.4;";
+pub const PRAGMA_KZG10_VERIFIER: &str = "pragma solidity >=0.8.1 <0.9.0;";
+
+/// Version of solc the templates are tested with, which `crate::solc::resolve_solc` expects and
+/// downloads when the `svm` feature is enabled.
+pub const SOLC_VERSION: &str = "0.8.28";
 
 /// Default SDPX License identifier
 pub const GPL3_SDPX_IDENTIFIER: &str = "// SPDX-License-Identifier: GPL-3.0";
 pub const MIT_SDPX_IDENTIFIER: &str = "// SPDX-License-Identifier: MIT";
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use crypto::digest::Digest;
+use crypto::sha3::Sha3;
 
 pub mod g16;
+pub mod hypernova;
 pub mod kzg;
 pub mod nova_cyclefold;
+pub mod router;
 
 pub use g16::Groth16VerifierKey;
+pub use hypernova::{get_decider_template_for_hypernova_decider, HyperNovaVerifierKey};
 pub use kzg::KZG10VerifierKey;
-pub use nova_cyclefold::{get_decider_template_for_cyclefold_decider, NovaCycleFoldVerifierKey};
+pub use nova_cyclefold::{
+    get_decider_template_for_cyclefold_decider,
+    get_decider_template_for_cyclefold_decider_with_options, NovaCycleFoldVerifierKey,
+};
+pub use router::get_router_template;
+
+/// Sources of the templates, which determine the rendered contracts together with the verifier
+/// keys and the [`SolidityTemplateOptions`].
+const TEMPLATES: [&str; 7] = [
+    include_str!("../../templates/header_template.askama.sol"),
+    include_str!("../../templates/groth16_verifier.askama.sol"),
+    include_str!("../../templates/kzg10_verifier.askama.sol"),
+    include_str!("../../templates/nova_cyclefold_decider.askama.sol"),
+    include_str!("../../templates/hypernova_decider.askama.sol"),
+    include_str!("../../templates/nova_decider_foundry_test.askama.sol"),
+    include_str!("../../templates/nova_cyclefold_router.askama.sol"),
+];
+
+/// Returns the keccak256 hash of the version of this crate and of the templates' sources, which
+/// identifies the generator of a contract, so that deployments can be tied to the version of the
+/// generator that rendered them.
+pub fn template_hash() -> [u8; 32] {
+    let mut hasher = Sha3::keccak256();
+    hasher.input_str(env!("CARGO_PKG_VERSION"));
+    for template in TEMPLATES {
+        hasher.input_str(template);
+    }
+    let mut hash = [0u8; 32];
+    hasher.result(&mut hash);
+    hash
+}
 
 pub trait ProtocolVerifierKey: CanonicalDeserialize + CanonicalSerialize {
     const PROTOCOL_NAME: &'static str;
@@ -50,6 +89,55 @@ pub trait ProtocolVerifierKey: CanonicalDeserialize + CanonicalSerialize {
     fn render_as_template(self, pragma: Option<String>) -> Vec<u8>;
 }
 
+/// Options to customize the rendered decider contract, eg. to embed the verifiers of different
+/// circuits in the same project without their names colliding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolidityTemplateOptions {
+    /// Name of the decider contract.
+    pub contract_name: String,
+    /// The `pragma` statement.
+    pub pragma_version: String,
+    /// SPDX license identifier of the contract, eg. `GPL-3.0`.
+    pub license: String,
+    /// Name of the contract's function that verifies the proofs.
+    pub function_name: String,
+    /// Whether to include the `verifyBatch` function, which verifies several proofs in a single
+    /// call. Their Groth16 proofs are checked with a single random linear combination, and their
+    /// KZG openings with a single `multiCheck`, so each proof costs about one pairing instead of
+    /// the six of its standalone verification. Only when the combined check fails, the proofs are
+    /// verified one by one to find the invalid one.
+    pub batch: bool,
+    /// Whether to include the `processNovaProof` function, which verifies a proof and then passes
+    /// its states to the `_onVerified` hook, that contracts inheriting from the decider override to
+    /// consume them.
+    pub on_verified_hook: bool,
+    /// Whether to include the `verifyNovaProofWithBlob` function, which takes the states from an
+    /// EIP-4844 blob (see [`crate::blob`]). Requires a decider circuit exposing the hashes of the
+    /// states, otherwise the rendering fails, and solc 0.8.24 or later.
+    pub blob: bool,
+}
+
+impl Default for SolidityTemplateOptions {
+    fn default() -> Self {
+        Self {
+            contract_name: "NovaDecider".to_string(),
+            pragma_version: PRAGMA_GROTH16_VERIFIER.to_string(),
+            license: "GPL-3.0".to_string(),
+            function_name: "verifyNovaProof".to_string(),
+            batch: false,
+            on_verified_hook: false,
+            blob: false,
+        }
+    }
+}
+
+impl SolidityTemplateOptions {
+    /// Returns the SPDX-License-Identifier comment of the contract.
+    pub fn sdpx(&self) -> String {
+        format!("// SPDX-License-Identifier: {}", self.license)
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use ark_bn254::{Bn254, Fr, G1Projective as G1};
@@ -10,17 +10,47 @@ use askama::Template;
 
 use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
 use folding_schemes::folding::nova::decider_eth::VerifierParam as DeciderVerifierParam;
+use folding_schemes::Error;
 
 use super::g16::Groth16Verifier;
 use super::kzg::KZG10Verifier;
-use crate::utils::HeaderInclusion;
-use crate::{Groth16VerifierKey, KZG10VerifierKey, ProtocolVerifierKey, PRAGMA_GROTH16_VERIFIER};
+use crate::blob::{evaluation_point, NOVA_BLOB_OPENINGS};
+use crate::evm::revm::primitives::hex;
+use crate::utils::{encoding::FrWrapper, HeaderInclusion};
+use crate::{
+    Groth16VerifierKey, KZG10VerifierKey, ProtocolVerifierKey, SolidityTemplateOptions,
+    PRAGMA_GROTH16_VERIFIER,
+};
 
 pub fn get_decider_template_for_cyclefold_decider(
     nova_cyclefold_vk: NovaCycleFoldVerifierKey,
+) -> String {
+    render_decider(nova_cyclefold_vk, &SolidityTemplateOptions::default())
+}
+
+/// Renders the decider contract with the contract name, pragma, license and verification function
+/// name given in `options`. Fails if `options` enables the `blob` function for a verifier key whose
+/// decider circuit does not expose the hashes of the states.
+pub fn get_decider_template_for_cyclefold_decider_with_options(
+    nova_cyclefold_vk: NovaCycleFoldVerifierKey,
+    options: &SolidityTemplateOptions,
+) -> Result<String, Error> {
+    if options.blob && !nova_cyclefold_vk.hashed_state() {
+        return Err(Error::NotSupported(
+            "the blob option requires a verifier key with hashed states".to_string(),
+        ));
+    }
+    Ok(render_decider(nova_cyclefold_vk, options))
+}
+
+fn render_decider(
+    nova_cyclefold_vk: NovaCycleFoldVerifierKey,
+    options: &SolidityTemplateOptions,
 ) -> String {
     HeaderInclusion::<NovaCycleFoldDecider>::builder()
-        .template(nova_cyclefold_vk)
+        .sdpx(options.sdpx())
+        .pragma_version(&options.pragma_version)
+        .template((nova_cyclefold_vk, options))
         .build()
         .render()
         .unwrap()
@@ -29,29 +59,60 @@ pub fn get_decider_template_for_cyclefold_decider(
 #[derive(Template, Default)]
 #[template(path = "nova_cyclefold_decider.askama.sol", ext = "sol")]
 pub struct NovaCycleFoldDecider {
-    pp_hash: Fr, // public params hash
-    groth16_verifier: Groth16Verifier,
-    kzg10_verifier: KZG10Verifier,
-    // z_len denotes the FCircuit state (z_i) length
-    z_len: usize,
+    pub(crate) pp_hash: FrWrapper, // public params hash
+    pub(crate) groth16_verifier: Groth16Verifier,
+    pub(crate) kzg10_verifier: KZG10Verifier,
+    // z_len denotes the length of the states received by the contract, which is the FCircuit
+    // state (z_i) length, or 1 when the states are hashed
+    pub(crate) z_len: usize,
+    // state_len denotes the FCircuit state (z_i) length
+    state_len: usize,
+    // whether the decider circuit exposes the hashes of the states instead of the states
+    hashed_state: bool,
     public_inputs_len: usize,
-    num_limbs: usize,
-    bits_per_limb: usize,
+    pub(crate) num_limbs: usize,
+    pub(crate) bits_per_limb: usize,
+    contract_name: String,
+    function_name: String,
+    // whether to include the `verifyBatch` function
+    batch: bool,
+    // whether to include the `processNovaProof` function and its `_onVerified` hook
+    on_verified_hook: bool,
+    // whether to include the `verifyNovaProofWithBlob` function, when the states are hashed
+    blob: bool,
+    // points at which the blobs evaluate to their elements opened by `verifyNovaProofWithBlob`
+    blob_evaluation_points: Vec<String>,
 }
 
 impl From<NovaCycleFoldVerifierKey> for NovaCycleFoldDecider {
     fn from(value: NovaCycleFoldVerifierKey) -> Self {
+        Self::from((value, &SolidityTemplateOptions::default()))
+    }
+}
+
+impl From<(NovaCycleFoldVerifierKey, &SolidityTemplateOptions)> for NovaCycleFoldDecider {
+    fn from((value, options): (NovaCycleFoldVerifierKey, &SolidityTemplateOptions)) -> Self {
         let groth16_verifier = Groth16Verifier::from(value.g16_vk);
         let public_inputs_len = groth16_verifier.gamma_abc_len;
         let bits_per_limb = NonNativeUintVar::<Fq>::bits_per_limb();
         Self {
-            pp_hash: value.pp_hash,
+            pp_hash: FrWrapper(value.pp_hash),
             groth16_verifier,
             kzg10_verifier: KZG10Verifier::from(value.kzg_vk),
-            z_len: value.z_len,
+            z_len: if value.hashed_state { 1 } else { value.z_len },
+            state_len: value.z_len,
+            hashed_state: value.hashed_state,
             public_inputs_len,
             num_limbs: (250_f32 / (bits_per_limb as f32)).ceil() as usize,
             bits_per_limb,
+            contract_name: options.contract_name.clone(),
+            function_name: options.function_name.clone(),
+            batch: options.batch,
+            on_verified_hook: options.on_verified_hook,
+            blob: options.blob,
+            blob_evaluation_points: (0..NOVA_BLOB_OPENINGS)
+                .map(|k| format!("0x{}", hex::encode(evaluation_point(k).unwrap())))
+                .collect(),
         }
     }
 }
@@ -62,6 +123,7 @@ pub struct NovaCycleFoldVerifierKey {
     g16_vk: Groth16VerifierKey,
     kzg_vk: KZG10VerifierKey,
     z_len: usize,
+    hashed_state: bool,
 }
 
 impl ProtocolVerifierKey for NovaCycleFoldVerifierKey {
@@ -85,6 +147,7 @@ impl From<(Fr, Groth16VerifierKey, KZG10VerifierKey, usize)> for NovaCycleFoldVe
             g16_vk: value.1,
             kzg_vk: value.2,
             z_len: value.3,
+            hashed_state: false,
         }
     }
 }
@@ -116,6 +179,7 @@ impl
             g16_vk,
             kzg_vk,
             z_len: value.1,
+            hashed_state: false,
         }
     }
 }
@@ -133,13 +197,52 @@ impl NovaCycleFoldVerifierKey {
             g16_vk: Groth16VerifierKey::from(vkey_g16),
             kzg_vk: KZG10VerifierKey::from((vkey_kzg, crs_points)),
             z_len,
+            hashed_state: false,
+        }
+    }
+
+    /// Sets that the decider circuit exposes the hashes of the initial and final states instead of
+    /// the states themselves, as the onchain decider does when its `HASHED_STATE` parameter is
+    /// set.
+    pub fn with_hashed_state(mut self) -> Self {
+        self.hashed_state = true;
+        self
+    }
+
+    /// Returns the FCircuit state (z_i) length.
+    pub fn state_len(&self) -> usize {
+        self.z_len
+    }
+
+    /// Returns whether the decider circuit exposes the hashes of the states.
+    pub fn hashed_state(&self) -> bool {
+        self.hashed_state
+    }
+
+    /// Checks that the state length of the key is the one of the decider circuit, deduced from the
+    /// number of public inputs of its Groth16 key. With any other length, the rendered contract
+    /// compiles but rejects every proof.
+    pub fn check_state_len(&self) -> Result<(), Error> {
+        let decider = NovaCycleFoldDecider::from(self.clone());
+        // the public inputs are `[pp_hash, i, z_0, z_i]`, the limbs of `cmW`, `cmE` and `cmT`, and
+        // the KZG challenges and evaluations
+        let other_inputs = 2 + 6 * decider.num_limbs + 4;
+        let decider_z_len = (decider.public_inputs_len - 1).saturating_sub(other_inputs) / 2;
+        if decider.public_inputs_len - 1 != other_inputs + 2 * decider.z_len {
+            return Err(Error::DeciderStateLenMismatch {
+                origin: "verifier key".to_string(),
+                expected: decider_z_len,
+                found: decider.z_len,
+            });
         }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use ark_bn254::{Bn254, Fr, G1Projective as G1, G1Projective};
+    use ark_bn254::{Bn254, Fr, G1Affine, G1Projective as G1, G1Projective, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
     use ark_ff::PrimeField;
     use ark_groth16::Groth16;
     use ark_grumpkin::Projective as G2;
@@ -147,19 +250,44 @@ mod tests {
     use ark_r1cs_std::fields::fp::FpVar;
     use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
     use askama::Template;
+    use std::array;
     use std::marker::PhantomData;
     use std::time::Instant;
 
-    use super::{DeciderVerifierParam, NovaCycleFoldDecider};
-    use crate::calldata::NovaVerificationMode::{Explicit, Opaque, OpaqueWithInputs};
-    use crate::calldata::{prepare_calldata_for_nova_cyclefold_verifier, NovaVerificationMode};
+    use super::{
+        ArkG16VerifierKey, ArkKZG10VerifierKey, DeciderVerifierParam, NovaCycleFoldDecider,
+    };
+    use crate::blob::{
+        evaluation_point, kzg_to_versioned_hash, nova_blob, nova_states_from_blob, BlobOpening,
+        NOVA_BLOB_OPENINGS,
+    };
+    use crate::calldata::NovaVerificationMode::{
+        Explicit, Hashed, HashedWithState, Opaque, OpaqueWithInputs, Process,
+    };
+    use crate::calldata::{
+        check_calldata_for_nova_cyclefold_verifier, keccak_selector, prepare_batch_calldata,
+        prepare_calldata_for_nova_cyclefold_blob_verifier,
+        prepare_calldata_for_nova_cyclefold_verifier,
+        prepare_calldata_for_nova_cyclefold_verifier_with_options, prepare_calldata_for_router,
+        NovaVerificationMode,
+    };
     use crate::verifiers::tests::{setup, DEFAULT_SETUP_LEN};
     use crate::{
-        evm::{compile_solidity, save_solidity, Evm},
+        evm::revm::primitives::{Address, U256},
+        utils::eth::ToEth,
+    };
+    use crate::{
+        evm::{compile_solidity, gas_report, save_solidity, Evm, RevertReason},
+        export_foundry_project, get_router_template, template_hash,
         utils::HeaderInclusion,
-        verifiers::nova_cyclefold::get_decider_template_for_cyclefold_decider,
-        NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+        verifiers::nova_cyclefold::{
+            get_decider_template_for_cyclefold_decider,
+            get_decider_template_for_cyclefold_decider_with_options,
+        },
+        Groth16VerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+        SolidityTemplateOptions,
     };
+    use experimental_frontends::noir::NoirFCircuitDyn;
     use folding_schemes::folding::nova::decider_eth::Proof;
     use folding_schemes::{
         commitment::{kzg::KZG, pedersen::Pedersen},
@@ -176,8 +304,14 @@ mod tests {
     type DECIDER<FC> =
         DeciderEth<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, Groth16<Bn254>, NOVA<FC>>;
 
+    /// Upper bounds for the gas used by the NovaDecider contract.
+    const MAX_DEPLOYMENT_GAS: u64 = 10_000_000;
+    const MAX_VERIFICATION_GAS: u64 = 2_000_000;
+
     type FS_PP<FC> = <NOVA<FC> as FoldingScheme<G1, G2, FC>>::ProverParam;
     type FS_VP<FC> = <NOVA<FC> as FoldingScheme<G1, G2, FC>>::VerifierParam;
+    type HASHED_DECIDER<FC> =
+        DeciderEth<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, Groth16<Bn254>, NOVA<FC>, true>;
     type DECIDER_PP<FC> = <DECIDER<FC> as Decider<G1, G2, FC, NOVA<FC>>>::ProverParam;
     type DECIDER_VP<FC> = <DECIDER<FC> as Decider<G1, G2, FC, NOVA<FC>>>::VerifierParam;
 
@@ -190,6 +324,7 @@ mod tests {
         type Params = ();
         type ExternalInputs = ();
         type ExternalInputsVar = ();
+        type WitnessHint = ();
         fn new(_params: Self::Params) -> Result<Self, Error> {
             Ok(Self { _f: PhantomData })
         }
@@ -223,6 +358,7 @@ mod tests {
         type Params = ();
         type ExternalInputs = ();
         type ExternalInputsVar = ();
+        type WitnessHint = ();
 
         fn new(_params: Self::Params) -> Result<Self, Error> {
             Ok(Self { _f: PhantomData })
@@ -251,6 +387,39 @@ mod tests {
         }
     }
 
+    /// Test circuit with a state of 28 elements (as the ChaCha20 state), used to compare the costs
+    /// of verifying the states in the clear and through their hashes.
+    #[derive(Clone, Copy, Debug)]
+    pub struct LargeStateFCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for LargeStateFCircuit<F> {
+        type Params = ();
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+        type WitnessHint = ();
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            28
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            _i: usize,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            Ok(z_i
+                .into_iter()
+                .enumerate()
+                .map(|(j, z_j)| z_j + F::from(j as u64 + 1))
+                .collect())
+        }
+    }
+
     #[test]
     fn nova_cyclefold_vk_serde_roundtrip() {
         let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
@@ -400,11 +569,29 @@ mod tests {
         let decider_solidity_code = get_decider_template_for_cyclefold_decider(nova_cyclefold_vk);
 
         let nova_cyclefold_verifier_bytecode =
-            compile_solidity(decider_solidity_code, "NovaDecider");
+            compile_solidity(&decider_solidity_code, "NovaDecider").unwrap();
 
         for mode in [Explicit, Opaque, OpaqueWithInputs] {
             interact_with_contract(&nova_cyclefold_verifier_bytecode, &nova, &proof, mode);
         }
+
+        let calldata = prepare_calldata_for_nova_cyclefold_verifier(
+            Explicit,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        let report = gas_report(&decider_solidity_code, calldata.clone()).unwrap();
+        println!("{report}");
+        assert!(report.verified);
+        assert_eq!(report.calldata_size, calldata.len());
+        // generous ceilings, to catch regressions in the templates
+        assert!(report.deployment_gas > 0 && report.deployment_gas < MAX_DEPLOYMENT_GAS);
+        assert!(report.verification_gas > 0 && report.verification_gas < MAX_VERIFICATION_GAS);
     }
 
     /// Given an `FCircuit` type and initial IVC state `z_0`, this function tests the `NovaCycleFold`
@@ -430,4 +617,877 @@ mod tests {
     fn nova_cyclefold_solidity_verifier_multi_input() {
         nova_cyclefold_solidity_verifier_test::<MultiInputsFCircuit<Fr>>(vec![Fr::from(1_u32); 5]);
     }
+
+    /// Runs the Noir → Nova → DeciderEth → Solidity pipeline for the Noir circuit
+    /// `test_state_len_{state_len}`, whose state is an array of `state_len` elements, checking the
+    /// layout of the calldata before deploying the contract.
+    fn nova_cyclefold_solidity_verifier_noir_opt(state_len: usize) {
+        type FC = NoirFCircuitDyn<Fr>;
+        let name = format!("test_state_len_{state_len}");
+        let f_circuit = FC::new(
+            std::env::current_dir()
+                .unwrap()
+                .join(format!(
+                    "../experimental-frontends/src/noir/test_folder/{name}/target/{name}.json"
+                ))
+                .into(),
+        )
+        .unwrap();
+        assert_eq!(f_circuit.state_len(), state_len);
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let prep_param =
+            PreprocessorParam::<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, false>::new(
+                poseidon_config,
+                f_circuit.clone(),
+            );
+        let nova_params = NOVA::<FC>::preprocess(&mut rng, &prep_param).unwrap();
+        let (decider_pp, decider_vp) =
+            DECIDER::<FC>::preprocess(&mut rng, (nova_params.clone(), state_len)).unwrap();
+
+        let mut nova =
+            NOVA::<FC>::init(&nova_params, f_circuit, vec![Fr::from(1_u32); state_len]).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, vec![], None).unwrap();
+        }
+        // each element is squared and added its position at each step
+        let expected_z_i: Vec<Fr> = (1..=state_len as u64)
+            .map(|j| {
+                (Fr::from(1_u32) + Fr::from(j)) * (Fr::from(1_u32) + Fr::from(j)) + Fr::from(j)
+            })
+            .collect();
+        assert_eq!(nova.z_i, expected_z_i);
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, nova.clone()).unwrap();
+
+        let calldata = |z_0: Vec<Fr>, z_i: Vec<Fr>| {
+            prepare_calldata_for_nova_cyclefold_verifier(
+                Explicit, nova.i, z_0, z_i, &nova.U_i, &nova.u_i, &proof,
+            )
+            .unwrap()
+        };
+        let options = SolidityTemplateOptions::default();
+        let vk = NovaCycleFoldVerifierKey::from((decider_vp.clone(), state_len));
+        check_calldata_for_nova_cyclefold_verifier(
+            &vk,
+            &options,
+            Explicit,
+            &calldata(nova.z_0.clone(), nova.z_i.clone()),
+        )
+        .unwrap();
+
+        // the public inputs of the ACIR circuit also hold its returned values, so their number is
+        // twice the state length, which would render a contract rejecting every proof
+        let acir_public_inputs_len = 2 * state_len;
+        assert!(matches!(
+            NovaCycleFoldVerifierKey::from((decider_vp, acir_public_inputs_len)).check_state_len(),
+            Err(Error::DeciderStateLenMismatch { expected, found, .. })
+                if expected == state_len && found == acir_public_inputs_len
+        ));
+        // states of another length are reported before reaching the EVM
+        let longer = |z: &Vec<Fr>| [z.clone(), vec![Fr::from(0_u32)]].concat();
+        assert!(matches!(
+            check_calldata_for_nova_cyclefold_verifier(
+                &vk,
+                &options,
+                Explicit,
+                &calldata(longer(&nova.z_0), longer(&nova.z_i)),
+            ),
+            Err(Error::DeciderStateLenMismatch { expected, found, .. })
+                if expected == state_len && found == state_len + 1
+        ));
+        assert!(matches!(
+            check_calldata_for_nova_cyclefold_verifier(
+                &vk,
+                &options,
+                Hashed,
+                &calldata(nova.z_0.clone(), nova.z_i.clone()),
+            ),
+            Err(Error::DeciderHashedStateMismatch {
+                hashed_state: false,
+                ..
+            })
+        ));
+
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider(vk);
+        let nova_cyclefold_verifier_bytecode =
+            compile_solidity(&decider_solidity_code, "NovaDecider").unwrap();
+        for mode in [Explicit, Opaque, OpaqueWithInputs] {
+            interact_with_contract(&nova_cyclefold_verifier_bytecode, &nova, &proof, mode);
+        }
+    }
+
+    #[test]
+    fn nova_cyclefold_solidity_verifier_noir() {
+        for state_len in [1, 4, 28] {
+            nova_cyclefold_solidity_verifier_noir_opt(state_len);
+        }
+    }
+
+    /// Exports a Foundry project for a CubicFCircuit proof, and runs its tests with `forge`. It is
+    /// ignored by default since it requires Foundry to be installed.
+    #[test]
+    #[ignore]
+    fn nova_cyclefold_foundry_project() {
+        type FC = CubicFCircuit<Fr>;
+        let (fs_params, (decider_pp, decider_vp)) = init_params::<FC>();
+        let f_circuit = FC::new(()).unwrap();
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let mut nova = NOVA::<FC>::init(&fs_params, f_circuit, vec![Fr::from(3_u32)]).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, nova.clone()).unwrap();
+        let calldata = prepare_calldata_for_nova_cyclefold_verifier(
+            Explicit,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+
+        let dir = std::env::temp_dir().join("sonobe_nova_cyclefold_foundry_project");
+        export_foundry_project(
+            &dir,
+            NovaCycleFoldVerifierKey::from((decider_vp, f_circuit.state_len())),
+            &calldata,
+        )
+        .unwrap();
+
+        let output = std::process::Command::new("forge")
+            .arg("test")
+            .current_dir(&dir)
+            .output()
+            .expect("Command 'forge' not found");
+        assert!(
+            output.status.success(),
+            "forge test failed:\n{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Folds a few steps of `FC` from `z_0`, and returns its decider contract rendered with the
+    /// given `options`, together with the calldata to verify the resulting proof.
+    fn render_verifier_and_calldata<FC: FCircuit<Fr, Params = ()>>(
+        options: &SolidityTemplateOptions,
+        z_0: Vec<Fr>,
+    ) -> (String, Vec<u8>) {
+        let (fs_params, (decider_pp, decider_vp)) = init_params::<FC>();
+        let f_circuit = FC::new(()).unwrap();
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let mut nova = NOVA::<FC>::init(&fs_params, f_circuit.clone(), z_0).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, FC::ExternalInputs::default(), None)
+                .unwrap();
+        }
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, nova.clone()).unwrap();
+
+        let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp, f_circuit.state_len()));
+        let decider_solidity_code =
+            get_decider_template_for_cyclefold_decider_with_options(nova_cyclefold_vk, options)
+                .unwrap();
+        let calldata = prepare_calldata_for_nova_cyclefold_verifier_with_options(
+            Explicit,
+            options,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        (decider_solidity_code, calldata)
+    }
+
+    /// Renders the verifiers of two circuits with different contract and function names, deploys
+    /// both in the same EVM, and checks that each one only accepts the proof of its circuit.
+    #[test]
+    fn nova_cyclefold_solidity_verifier_with_options() {
+        let cubic_options = SolidityTemplateOptions {
+            contract_name: "CubicDecider".to_string(),
+            license: "MIT".to_string(),
+            function_name: "verifyCubicProof".to_string(),
+            ..Default::default()
+        };
+        let multi_inputs_options = SolidityTemplateOptions {
+            contract_name: "MultiInputsDecider".to_string(),
+            pragma_version: "pragma solidity ^0.8.0;".to_string(),
+            function_name: "verifyMultiInputsProof".to_string(),
+            ..Default::default()
+        };
+
+        let (cubic_code, cubic_calldata) = render_verifier_and_calldata::<CubicFCircuit<Fr>>(
+            &cubic_options,
+            vec![Fr::from(3_u32)],
+        );
+        let (multi_inputs_code, multi_inputs_calldata) =
+            render_verifier_and_calldata::<MultiInputsFCircuit<Fr>>(
+                &multi_inputs_options,
+                vec![Fr::from(1_u32); 5],
+            );
+        assert!(cubic_code.starts_with("// SPDX-License-Identifier: MIT"));
+        assert!(cubic_code.contains("contract CubicDecider is"));
+        assert!(multi_inputs_code.contains("pragma solidity ^0.8.0;"));
+        assert!(multi_inputs_code.contains("function verifyMultiInputsProof("));
+
+        let mut evm = Evm::default();
+        let cubic_address = evm.create(compile_solidity(&cubic_code, "CubicDecider").unwrap());
+        let multi_inputs_address =
+            evm.create(compile_solidity(&multi_inputs_code, "MultiInputsDecider").unwrap());
+
+        let (_, output) = evm.call(cubic_address, cubic_calldata.clone());
+        assert_eq!(output.last(), Some(&1));
+        let (_, output) = evm.call(multi_inputs_address, multi_inputs_calldata.clone());
+        assert_eq!(output.last(), Some(&1));
+
+        // each contract rejects the proof of the other circuit
+        let (_, output) = evm.call(cubic_address, multi_inputs_calldata);
+        assert_ne!(output.last(), Some(&1));
+        let (_, output) = evm.call(multi_inputs_address, cubic_calldata);
+        assert_ne!(output.last(), Some(&1));
+    }
+
+    /// Compares the costs of verifying the proofs of a circuit with a state of 28 elements when the
+    /// decider circuit exposes the states, and when it exposes their hashes.
+    #[test]
+    fn nova_cyclefold_solidity_verifier_hashed_state() {
+        type FC = LargeStateFCircuit<Fr>;
+        let f_circuit = FC::new(()).unwrap();
+        let state_len = f_circuit.state_len();
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let (fs_params, (decider_pp, decider_vp)) = init_params::<FC>();
+        let (hashed_decider_pp, hashed_decider_vp) =
+            HASHED_DECIDER::<FC>::preprocess(&mut rng, (fs_params.clone(), state_len)).unwrap();
+
+        let mut nova =
+            NOVA::<FC>::init(&fs_params, f_circuit, vec![Fr::from(1_u32); state_len]).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, nova.clone()).unwrap();
+        let hashed_proof =
+            HASHED_DECIDER::<FC>::prove(rng, hashed_decider_pp, nova.clone()).unwrap();
+        let verified = HASHED_DECIDER::<FC>::verify(
+            hashed_decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &hashed_proof,
+        )
+        .unwrap();
+        assert!(verified);
+
+        let calldata = |mode, proof| {
+            prepare_calldata_for_nova_cyclefold_verifier(
+                mode,
+                nova.i,
+                nova.z_0.clone(),
+                nova.z_i.clone(),
+                &nova.U_i,
+                &nova.u_i,
+                proof,
+            )
+            .unwrap()
+        };
+
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider(
+            NovaCycleFoldVerifierKey::from((decider_vp, state_len)),
+        );
+        let hashed_decider_solidity_code = get_decider_template_for_cyclefold_decider(
+            NovaCycleFoldVerifierKey::from((hashed_decider_vp, state_len)).with_hashed_state(),
+        );
+
+        let explicit = gas_report(&decider_solidity_code, calldata(Explicit, &proof)).unwrap();
+        let hashed = gas_report(
+            &hashed_decider_solidity_code,
+            calldata(Hashed, &hashed_proof),
+        )
+        .unwrap();
+        let with_state = gas_report(
+            &hashed_decider_solidity_code,
+            calldata(HashedWithState, &hashed_proof),
+        )
+        .unwrap();
+        println!("explicit states:\n{explicit}");
+        println!("hashed states:\n{hashed}");
+        println!("states hashed onchain:\n{with_state}");
+
+        assert!(explicit.verified && hashed.verified && with_state.verified);
+        // z_0 and z_i take a single word each
+        assert_eq!(
+            explicit.calldata_size - hashed.calldata_size,
+            2 * (state_len - 1) * 32
+        );
+        assert!(hashed.verification_gas < explicit.verification_gas);
+        assert!(with_state.verification_gas < explicit.verification_gas);
+
+        // change z_i, placed after the selector, i and z_0, to make the EVM check fail
+        let mut invalid_calldata = calldata(HashedWithState, &hashed_proof);
+        invalid_calldata[4 + 32 * (state_len + 2) - 1] ^= 1;
+        assert!(
+            !gas_report(&hashed_decider_solidity_code, invalid_calldata)
+                .unwrap()
+                .verified
+        );
+    }
+
+    /// Mock of the point evaluation precompile, which is not available in the EVM of the tests. It
+    /// checks the versioned hash against the commitment as the precompile does, and accepts an
+    /// opening at `z` to `y` iff the first 32 bytes of its proof are the keccak256 hash of
+    /// `versioned_hash || z || y || commitment`, which only the test can build.
+    const POINT_EVALUATION_MOCK: &str = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract PointEvaluationMock {
+    fallback(bytes calldata input) external returns (bytes memory) {
+        require(input.length == 192);
+        uint256 versioned_hash = (uint256(sha256(input[96:144])) & type(uint248).max) | (uint256(1) << 248);
+        require(bytes32(input[0:32]) == bytes32(versioned_hash));
+        require(bytes32(input[144:176]) == keccak256(input[0:144]));
+        return abi.encode(uint256(4096), uint256(0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001));
+    }
+}
+"#;
+
+    /// Returns the proof accepted by [`POINT_EVALUATION_MOCK`] for the opening of the blob with the
+    /// given `commitment` at the point of its `index`-th element to `y`.
+    fn mock_blob_proof(commitment: &[u8; 48], index: usize, y: &[u8]) -> [u8; 48] {
+        let versioned_hash = kzg_to_versioned_hash(commitment);
+        let point = evaluation_point(index).unwrap();
+        let hash = crate::evm::revm::primitives::keccak256(
+            [&versioned_hash[..], &point, y, commitment].concat(),
+        );
+        let mut proof = [0u8; 48];
+        proof[..32].copy_from_slice(hash.as_slice());
+        proof
+    }
+
+    /// Verifies a proof through the `verifyNovaProofWithBlob` function, with the point evaluation
+    /// precompile mocked by [`POINT_EVALUATION_MOCK`], checking the layout of its calldata and that
+    /// it rejects a versioned hash which is not a blob of the transaction and state hashes which are
+    /// not the ones opened from the blob.
+    #[test]
+    fn nova_cyclefold_blob_verifier() {
+        type FC = LargeStateFCircuit<Fr>;
+        let f_circuit = FC::new(()).unwrap();
+        let state_len = f_circuit.state_len();
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let (fs_params, _) = init_params::<FC>();
+        let (decider_pp, decider_vp) =
+            HASHED_DECIDER::<FC>::preprocess(&mut rng, (fs_params.clone(), state_len)).unwrap();
+        let mut nova =
+            NOVA::<FC>::init(&fs_params, f_circuit, vec![Fr::from(1_u32); state_len]).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+        let proof = HASHED_DECIDER::<FC>::prove(rng, decider_pp, nova.clone()).unwrap();
+
+        let blob = nova_blob(nova.i, &nova.z_0, &nova.z_i).unwrap();
+        assert_eq!(
+            nova_states_from_blob(&blob, state_len).unwrap(),
+            (nova.i, nova.z_0.clone(), nova.z_i.clone())
+        );
+
+        // the opened elements of the blob are the number of steps and the hashes of the states
+        let commitment = [1; 48];
+        let blob_opening = BlobOpening {
+            commitment,
+            proofs: array::from_fn(|k| {
+                mock_blob_proof(&commitment, k, &blob[32 * k..32 * (k + 1)])
+            }),
+        };
+        let calldata = prepare_calldata_for_nova_cyclefold_blob_verifier(
+            0,
+            &blob_opening,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        let hashed_calldata = prepare_calldata_for_nova_cyclefold_verifier(
+            Hashed,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        let word = |k: usize| &calldata[4 + 32 * k..4 + 32 * (k + 1)];
+        assert_eq!(word(0), blob_opening.versioned_hash());
+        assert_eq!(word(1), U256::from(0).to_be_bytes::<32>());
+        // steps, state hashes and proof
+        assert_eq!(calldata[4 + 64..4 + 32 * 30], hashed_calldata[4..]);
+        assert_eq!(
+            calldata[4 + 64..4 + 32 * 5],
+            blob[..32 * NOVA_BLOB_OPENINGS]
+        );
+        assert_eq!(word(30), U256::from(32 * 31).to_be_bytes::<32>());
+        assert_eq!(word(31), U256::from(192).to_be_bytes::<32>());
+        assert_eq!(calldata[4 + 32 * 32..], blob_opening.to_bytes());
+
+        // the blob function is only rendered for decider circuits exposing the hashes of the states
+        let options = SolidityTemplateOptions {
+            blob: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            get_decider_template_for_cyclefold_decider_with_options(
+                NovaCycleFoldVerifierKey::from((decider_vp.clone(), state_len)),
+                &options,
+            ),
+            Err(Error::NotSupported(_))
+        ));
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider_with_options(
+            NovaCycleFoldVerifierKey::from((decider_vp, state_len)).with_hashed_state(),
+            &options,
+        )
+        .unwrap();
+        let mut evm = Evm::default();
+        let verifier_address =
+            evm.create(compile_solidity(&decider_solidity_code, "NovaDecider").unwrap());
+        let mock_address =
+            evm.create(compile_solidity(POINT_EVALUATION_MOCK, "PointEvaluationMock").unwrap());
+        evm.copy_code(mock_address, Address::with_last_byte(0x0a));
+
+        let versioned_hash = blob_opening.versioned_hash();
+        let result = evm.execute_with_blobs(verifier_address, calldata.clone(), &[versioned_hash]);
+        assert!(result.success);
+        assert_eq!(result.output.last(), Some(&1));
+
+        // the blob is not in the transaction
+        let result = evm.execute(verifier_address, calldata.clone());
+        assert!(!result.success);
+        assert_eq!(
+            result.revert_reason,
+            Some(RevertReason::Error(
+                "Blob: versioned hash not in the transaction".to_string()
+            ))
+        );
+
+        // the hash of the final state is not the one in the blob
+        let mut tampered_calldata = calldata;
+        tampered_calldata[4 + 32 * 5 - 1] ^= 1;
+        let result = evm.execute_with_blobs(verifier_address, tampered_calldata, &[versioned_hash]);
+        assert!(!result.success);
+        assert_eq!(
+            result.revert_reason,
+            Some(RevertReason::Error(
+                "Blob: point evaluation failed".to_string()
+            ))
+        );
+    }
+
+    /// Verifies three proofs through the `verifyBatch` function, checking that the gas per proof is
+    /// lower than the one of verifying a proof alone, and that corrupting one of them makes the call
+    /// revert with its index.
+    #[test]
+    fn nova_cyclefold_solidity_verifier_batch() {
+        type FC = CubicFCircuit<Fr>;
+        let f_circuit = FC::new(()).unwrap();
+        let (fs_params, (decider_pp, decider_vp)) = init_params::<FC>();
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let mut nova = NOVA::<FC>::init(&fs_params, f_circuit, vec![Fr::from(3_u32)]).unwrap();
+        nova.prove_step(&mut rng, (), None).unwrap();
+        // proofs of the IVC after 2, 3 and 4 steps
+        let mut proofs_calldata = vec![];
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+            let proof = DECIDER::<FC>::prove(rng, decider_pp.clone(), nova.clone()).unwrap();
+            proofs_calldata.push(
+                prepare_calldata_for_nova_cyclefold_verifier(
+                    Explicit,
+                    nova.i,
+                    nova.z_0.clone(),
+                    nova.z_i.clone(),
+                    &nova.U_i,
+                    &nova.u_i,
+                    &proof,
+                )
+                .unwrap(),
+            );
+        }
+
+        let options = SolidityTemplateOptions {
+            batch: true,
+            ..Default::default()
+        };
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider_with_options(
+            NovaCycleFoldVerifierKey::from((decider_vp, f_circuit.state_len())),
+            &options,
+        )
+        .unwrap();
+        let mut evm = Evm::default();
+        let verifier_address =
+            evm.create(compile_solidity(&decider_solidity_code, "NovaDecider").unwrap());
+
+        let (standalone_gas, output) = evm.call(verifier_address, proofs_calldata[0].clone());
+        assert_eq!(output.last(), Some(&1));
+
+        let (single_gas, output) = evm.call(
+            verifier_address,
+            prepare_batch_calldata(&proofs_calldata[..1]),
+        );
+        assert_eq!(output.last(), Some(&1));
+        let (batch_gas, output) =
+            evm.call(verifier_address, prepare_batch_calldata(&proofs_calldata));
+        assert_eq!(output.last(), Some(&1));
+        // the cost of each proof after the first one, which also pays for the shared checks
+        let marginal_gas = (batch_gas - single_gas) / 2;
+        println!(
+            "standalone verification gas: {standalone_gas}, per proof in a batch: {marginal_gas}"
+        );
+        assert!(marginal_gas < standalone_gas);
+
+        // corrupt z_i of the second proof, placed after the selector, i and z_0
+        proofs_calldata[1][4 + 32 * 3 - 1] ^= 1;
+        let (_, output) = evm.call(verifier_address, prepare_batch_calldata(&proofs_calldata));
+        let mut expected_error = keccak_selector("InvalidProof(uint256)").to_vec();
+        expected_error.extend([0u8; 31]);
+        expected_error.push(1);
+        assert_eq!(output, expected_error);
+    }
+
+    /// Verifies proofs of three circuits through a single router contract embedding their verifier
+    /// keys: two circuits with the same state length but different verifier keys, and one with a
+    /// different state length. Checks that a proof submitted with the id of another circuit is
+    /// rejected, both by the length check and, for the same length, by the verifier key of the
+    /// other circuit, and that the router is not rendered without circuits or with repeated ids.
+    #[test]
+    fn nova_cyclefold_router() {
+        let mut rng = ark_std::rand::rngs::OsRng;
+
+        type FC1 = CubicFCircuit<Fr>;
+        type FC2 = MultiInputsFCircuit<Fr>;
+        let cubic_circuit = FC1::new(()).unwrap();
+        let multi_inputs_circuit = FC2::new(()).unwrap();
+
+        // proves 2 steps of each circuit, with its own parameters
+        let mut vks = vec![];
+        let mut calldatas = vec![];
+        for _ in 0..2 {
+            let (params, (decider_pp, decider_vp)) = init_params::<FC1>();
+            let mut nova =
+                NOVA::<FC1>::init(&params, cubic_circuit, vec![Fr::from(3_u32)]).unwrap();
+            for _ in 0..2 {
+                nova.prove_step(&mut rng, (), None).unwrap();
+            }
+            let proof = DECIDER::<FC1>::prove(rng, decider_pp, nova.clone()).unwrap();
+            vks.push(NovaCycleFoldVerifierKey::from((
+                decider_vp,
+                cubic_circuit.state_len(),
+            )));
+            calldatas.push(
+                prepare_calldata_for_nova_cyclefold_verifier(
+                    Explicit, nova.i, nova.z_0, nova.z_i, &nova.U_i, &nova.u_i, &proof,
+                )
+                .unwrap(),
+            );
+        }
+        {
+            let (params, (decider_pp, decider_vp)) = init_params::<FC2>();
+            let mut nova =
+                NOVA::<FC2>::init(&params, multi_inputs_circuit, vec![Fr::from(1_u32); 5]).unwrap();
+            for _ in 0..2 {
+                nova.prove_step(&mut rng, (), None).unwrap();
+            }
+            let proof = DECIDER::<FC2>::prove(rng, decider_pp, nova.clone()).unwrap();
+            vks.push(NovaCycleFoldVerifierKey::from((
+                decider_vp,
+                multi_inputs_circuit.state_len(),
+            )));
+            calldatas.push(
+                prepare_calldata_for_nova_cyclefold_verifier(
+                    Explicit, nova.i, nova.z_0, nova.z_i, &nova.U_i, &nova.u_i, &proof,
+                )
+                .unwrap(),
+            );
+        }
+
+        assert!(matches!(get_router_template(vec![]), Err(Error::Empty)));
+        assert!(matches!(
+            get_router_template(vec![(1, vks[0].clone()), (1, vks[1].clone())]),
+            Err(Error::Other(_))
+        ));
+
+        let router_solidity_code =
+            get_router_template((1..=3).zip(vks).collect::<Vec<(usize, _)>>()).unwrap();
+        save_solidity("NovaRouter.sol", &router_solidity_code);
+        let mut evm = Evm::default();
+        let router_address =
+            evm.create(compile_solidity(&router_solidity_code, "NovaRouter").unwrap());
+
+        for (circuit_id, calldata) in (1..=3).zip(&calldatas) {
+            let (_, output) = evm.call(
+                router_address,
+                prepare_calldata_for_router(circuit_id, calldata),
+            );
+            assert_eq!(output.last(), Some(&1));
+        }
+
+        // a proof of the same length is checked against the verifier key of the other circuit,
+        // which rejects it
+        assert_eq!(calldatas[0].len(), calldatas[1].len());
+        for (circuit_id, calldata) in [(2, &calldatas[0]), (1, &calldatas[1])] {
+            let result = evm.execute(
+                router_address,
+                prepare_calldata_for_router(circuit_id, calldata),
+            );
+            assert!(!result.success);
+            assert!(matches!(result.revert_reason, Some(RevertReason::Error(_))));
+        }
+
+        // a proof of another length, and an unknown circuit
+        let result = evm.execute(
+            router_address,
+            prepare_calldata_for_router(3, &calldatas[0]),
+        );
+        assert_eq!(
+            result.revert_reason,
+            Some(RevertReason::Error(
+                "Router: invalid proof length for the circuit".to_string()
+            ))
+        );
+        let result = evm.execute(
+            router_address,
+            prepare_calldata_for_router(4, &calldatas[0]),
+        );
+        assert_eq!(
+            result.revert_reason,
+            Some(RevertReason::Error("Router: unknown circuit".to_string()))
+        );
+
+        // a corrupted z_i, placed after i and z_0, with the right id
+        let mut invalid_calldata = calldatas[0].clone();
+        invalid_calldata[4 + 32 * 3 - 1] ^= 1;
+        let result = evm.execute(
+            router_address,
+            prepare_calldata_for_router(1, &invalid_calldata),
+        );
+        assert_eq!(
+            result.revert_reason,
+            Some(RevertReason::Error(
+                "Groth16: verifying proof failed".to_string()
+            ))
+        );
+    }
+
+    /// Checks that the calldata ABI-encoded through alloy, both from the function signature and
+    /// from the ABI of the compiled contract, matches the hand-rolled calldata byte-for-byte.
+    #[cfg(feature = "abi")]
+    #[test]
+    fn nova_cyclefold_abi_encoded_calldata() {
+        use crate::{abi::NovaDeciderCalldata, evm::compile_solidity_abi};
+        use alloy_json_abi::JsonAbi;
+
+        type FC = MultiInputsFCircuit<Fr>;
+        let f_circuit = FC::new(()).unwrap();
+        let (fs_params, (decider_pp, decider_vp)) = init_params::<FC>();
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let mut nova = NOVA::<FC>::init(&fs_params, f_circuit, vec![Fr::from(1_u32); 5]).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, nova.clone()).unwrap();
+
+        let calldata = prepare_calldata_for_nova_cyclefold_verifier(
+            Explicit,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        let abi_calldata = NovaDeciderCalldata::new(
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        );
+        assert_eq!(abi_calldata.abi_encode(), calldata);
+
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider(
+            NovaCycleFoldVerifierKey::from((decider_vp, f_circuit.state_len())),
+        );
+        let abi = JsonAbi::from_json_str(
+            &compile_solidity_abi(&decider_solidity_code, "NovaDecider").unwrap(),
+        )
+        .unwrap();
+        let function = &abi.function("verifyNovaProof").unwrap()[0];
+        assert_eq!(abi_calldata.abi_encode_with(function).unwrap(), calldata);
+    }
+
+    /// Contract inheriting from the NovaDecider, storing the last element of the final state of
+    /// the verified proofs.
+    const STORING_DECIDER: &str = r#"
+contract StoringDecider is NovaDecider {
+    uint256 public lastState;
+
+    function lastStateSlot() public pure returns (uint256 slot) {
+        assembly {
+            slot := lastState.slot
+        }
+    }
+
+    function _onVerified(uint256, uint256[28] memory, uint256[28] memory zi) internal override {
+        lastState = zi[27];
+    }
+}
+"#;
+
+    /// Checks that the `_onVerified` hook of a contract inheriting from the NovaDecider is only
+    /// called for valid proofs.
+    #[test]
+    fn nova_cyclefold_solidity_verifier_on_verified_hook() {
+        type FC = LargeStateFCircuit<Fr>;
+        let f_circuit = FC::new(()).unwrap();
+        let state_len = f_circuit.state_len();
+        let (fs_params, (decider_pp, decider_vp)) = init_params::<FC>();
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let mut nova =
+            NOVA::<FC>::init(&fs_params, f_circuit, vec![Fr::from(1_u32); state_len]).unwrap();
+        for _ in 0..2 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, nova.clone()).unwrap();
+
+        let options = SolidityTemplateOptions {
+            on_verified_hook: true,
+            ..Default::default()
+        };
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider_with_options(
+            NovaCycleFoldVerifierKey::from((decider_vp, state_len)),
+            &options,
+        )
+        .unwrap();
+        let storing_decider_code = format!("{decider_solidity_code}\n{STORING_DECIDER}");
+
+        let mut evm = Evm::default();
+        let address = evm.create(compile_solidity(storing_decider_code, "StoringDecider").unwrap());
+        let (_, slot) = evm.call(address, keccak_selector("lastStateSlot()").to_vec());
+        let slot = U256::from_be_slice(&slot);
+
+        let calldata = prepare_calldata_for_nova_cyclefold_verifier(
+            Process,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+
+        // change i to make the verification fail, which reverts without calling the hook
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[35] ^= 1;
+        let result = evm.execute(address, invalid_calldata);
+        assert!(!result.success);
+        assert_eq!(evm.storage(address, slot), U256::ZERO);
+
+        let result = evm.execute(address, calldata);
+        assert!(result.success);
+        assert_eq!(
+            evm.storage(address, slot),
+            U256::from_be_slice(&nova.z_i[27].to_eth())
+        );
+    }
+
+    /// Verifier key with fixed points, from which the golden NovaDecider contract is rendered.
+    fn fixture_vk() -> NovaCycleFoldVerifierKey {
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        let neg_g2 = (-g2.into_group()).into_affine();
+        // the decider circuit of a state of length 1 has 38 public inputs
+        let g16_vk = ArkG16VerifierKey::<Bn254> {
+            alpha_g1: g1,
+            beta_g2: g2,
+            gamma_g2: g2,
+            delta_g2: neg_g2,
+            gamma_abc_g1: (1..=39_u64)
+                .map(|k| (g1 * Fr::from(k)).into_affine())
+                .collect(),
+        };
+        let kzg_vk = ArkKZG10VerifierKey::<Bn254> {
+            g: g1,
+            gamma_g: g1,
+            h: g2,
+            beta_h: neg_g2,
+            prepared_h: g2.into(),
+            prepared_beta_h: neg_g2.into(),
+        };
+        NovaCycleFoldVerifierKey::from((
+            Fr::from(42_u32),
+            Groth16VerifierKey::from(g16_vk),
+            KZG10VerifierKey::from((kzg_vk, vec![])),
+            1,
+        ))
+    }
+
+    /// Checks that the NovaDecider contract rendered for a fixed verifier key is reproducible and
+    /// matches the checked-in `golden/NovaDecider.sol`. Run with `SONOBE_BLESS=1` to update the
+    /// golden file after changing the templates.
+    #[test]
+    fn nova_cyclefold_decider_template_is_reproducible() {
+        let golden = concat!(env!("CARGO_MANIFEST_DIR"), "/golden/NovaDecider.sol");
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider(fixture_vk());
+        assert_eq!(
+            decider_solidity_code,
+            get_decider_template_for_cyclefold_decider(fixture_vk())
+        );
+
+        if std::env::var_os("SONOBE_BLESS").is_some() {
+            std::fs::write(golden, &decider_solidity_code).unwrap();
+        }
+        assert_eq!(
+            decider_solidity_code,
+            std::fs::read_to_string(golden).unwrap(),
+            "the rendered contract differs from {golden}"
+        );
+    }
+
+    /// Pins the hash of the templates and the hash of the NovaDecider contract rendered from them for
+    /// a fixed verifier key, which have to be updated together with the templates or the version of
+    /// the crate.
+    #[test]
+    fn template_hash_is_stable() {
+        use crate::evm::revm::primitives::hex;
+        use crypto::{digest::Digest, sha3::Sha3};
+
+        assert_eq!(
+            hex::encode(template_hash()),
+            "1e263d2b68341cd24b09ea59338206046e433ffd07b071c65ce07273c899993d"
+        );
+
+        let mut hasher = Sha3::keccak256();
+        hasher.input_str(&get_decider_template_for_cyclefold_decider(fixture_vk()));
+        assert_eq!(
+            hasher.result_str(),
+            "6a4b38fe07bb5d590fd772dfaa9eb6d41712badb035376a4c40c99abb02ad42f"
+        );
+    }
 }
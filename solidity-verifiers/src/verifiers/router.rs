@@ -0,0 +1,85 @@
+use askama::Template;
+use folding_schemes::Error;
+
+use super::g16::Groth16Verifier;
+use super::kzg::KZG10Verifier;
+use super::nova_cyclefold::NovaCycleFoldDecider;
+use crate::calldata::nova_proof_len;
+use crate::utils::{encoding::FrWrapper, HeaderInclusion};
+use crate::NovaCycleFoldVerifierKey;
+
+/// Renders the NovaRouter contract, whose `verify(uint256 circuitId, bytes proof)` function
+/// verifies the proofs of each of the given circuits, identified by their ids, against the
+/// verifier key of the circuit, which the contract embeds. The proofs are laid out as the calldata
+/// of the NovaDecider contract of the circuit, as rendered by
+/// [`crate::get_decider_template_for_cyclefold_decider`] from its verifier key, see
+/// [`crate::calldata::prepare_calldata_for_router`].
+///
+/// Fails if no circuit is given, if two circuits share an id, or if the state length of a verifier
+/// key is not the one of its decider circuit.
+pub fn get_router_template(
+    circuits: Vec<(usize, NovaCycleFoldVerifierKey)>,
+) -> Result<String, Error> {
+    Ok(HeaderInclusion::<NovaCycleFoldRouter>::builder()
+        .template(NovaCycleFoldRouter::try_from(circuits)?)
+        .build()
+        .render()
+        .unwrap())
+}
+
+/// A circuit served by the router, with its verifier key.
+struct RoutedCircuit {
+    id: usize,
+    pp_hash: FrWrapper,
+    // length of the states received by the contract, as in the NovaDecider contract
+    z_len: usize,
+    // length of the proofs, in words
+    proof_words: usize,
+    groth16_verifier: Groth16Verifier,
+    kzg10_verifier: KZG10Verifier,
+}
+
+#[derive(Template, Default)]
+#[template(path = "nova_cyclefold_router.askama.sol", ext = "sol")]
+pub struct NovaCycleFoldRouter {
+    circuits: Vec<RoutedCircuit>,
+    num_limbs: usize,
+    bits_per_limb: usize,
+    contract_name: String,
+}
+
+impl TryFrom<Vec<(usize, NovaCycleFoldVerifierKey)>> for NovaCycleFoldRouter {
+    type Error = Error;
+
+    fn try_from(circuits: Vec<(usize, NovaCycleFoldVerifierKey)>) -> Result<Self, Error> {
+        if circuits.is_empty() {
+            return Err(Error::Empty);
+        }
+        let mut ids: Vec<usize> = circuits.iter().map(|(id, _)| *id).collect();
+        ids.sort_unstable();
+        if let Some(id) = ids.windows(2).find(|w| w[0] == w[1]).map(|w| w[0]) {
+            return Err(Error::Other(format!("the circuit id {id} is not unique")));
+        }
+
+        let mut router = Self {
+            contract_name: "NovaRouter".to_string(),
+            ..Default::default()
+        };
+        for (id, vk) in circuits {
+            vk.check_state_len()?;
+            let decider = NovaCycleFoldDecider::from(vk);
+            // the limbs decomposition of the commitments does not depend on the circuit
+            router.num_limbs = decider.num_limbs;
+            router.bits_per_limb = decider.bits_per_limb;
+            router.circuits.push(RoutedCircuit {
+                id,
+                pp_hash: decider.pp_hash,
+                z_len: decider.z_len,
+                proof_words: nova_proof_len(decider.z_len) / 32,
+                groth16_verifier: decider.groth16_verifier,
+                kzg10_verifier: decider.kzg10_verifier,
+            });
+        }
+        Ok(router)
+    }
+}
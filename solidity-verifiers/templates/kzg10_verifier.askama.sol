@@ -185,6 +185,53 @@ contract KZG10Verifier {
         return pairing(pi, VK, rhs_pairing, G_2);
     }
 
+    /**
+     * @notice  Verifies several single point evaluation proofs, each one for a different polynomial, with a single pairing check.
+     * @dev     The pairing checks of each proof (see `check`) are combined through a random linear combination, with the
+     *          powers of a challenge derived from all the inputs.
+     * @param   c  G_1 point commitments to the polynomials.
+     * @param   pi  G_1 point proofs.
+     * @param   x  Values to prove evaluation of each polynomial at.
+     * @param   y  Evaluations poly_j(x_j).
+     * @return  result Indicates if all the KZG proofs are correct.
+     */
+    function multiCheck(uint256[2][] memory c, uint256[2][] memory pi, uint256[] memory x, uint256[] memory y)
+        public
+        view
+        returns (bool result)
+    {
+        require(
+            c.length == pi.length && c.length == x.length && c.length == y.length,
+            "KZG: the number of commitments, proofs and evaluations differ"
+        );
+        //
+        // each proof j satisfies (see `check`):
+        //
+        //          e(pi_j, vk) * e(x_j * -pi_j - c_j + y_j * g1, g2) = 1
+        //
+        // so with r_j = r^j, by bilinearity all the checks hold at once (except with negligible probability) if:
+        //
+        //          e(sum_j r_j * pi_j, vk) * e(sum_j r_j * (x_j * -pi_j - c_j) + (sum_j r_j * y_j) * g1, g2) = 1 [done]
+        //             |_   lhs_pairing  _|      |_                      rhs_pairing                        _|
+        //
+        uint256 r = uint256(keccak256(abi.encode(c, pi, x, y))) % BN254_SCALAR_FIELD;
+        uint256 r_j = 1;
+        uint256 y_acc = 0;
+        uint256[2] memory lhs_pairing;
+        uint256[2] memory rhs_pairing;
+        for (uint256 j = 0; j < c.length; j++) {
+            lhs_pairing = add(lhs_pairing, mulScalar(pi[j], r_j));
+            rhs_pairing = add(
+                rhs_pairing,
+                add(mulScalar(negate(pi[j]), mulmod(x[j], r_j, BN254_SCALAR_FIELD)), mulScalar(negate(c[j]), r_j))
+            );
+            y_acc = addmod(y_acc, mulmod(y[j], r_j, BN254_SCALAR_FIELD), BN254_SCALAR_FIELD);
+            r_j = mulmod(r_j, r, BN254_SCALAR_FIELD);
+        }
+        rhs_pairing = add(rhs_pairing, mulScalar(G_1, y_acc));
+        return pairing(lhs_pairing, VK, rhs_pairing, G_2);
+    }
+
     function evalPolyAt(uint256[] memory _coefficients, uint256 _index) public pure returns (uint256) {
         uint256 m = BN254_SCALAR_FIELD;
         uint256 result = 0;
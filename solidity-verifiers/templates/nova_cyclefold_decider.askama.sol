@@ -8,7 +8,7 @@
     Uses the https://github.com/iden3/snarkjs/blob/master/templates/verifier_groth16.sol.ejs
     Groth16 verifier implementation and a KZG10 Solidity template adapted from
     https://github.com/weijiekoh/libkzg.
-    Additionally we implement the NovaDecider contract, which combines the
+    Additionally we implement the {{ contract_name }} contract, which combines the
     Groth16 and KZG10 verifiers to verify the zkSNARK proofs coming from
     Nova+CycleFold folding.
 */
@@ -41,8 +41,8 @@ library LimbsDecomposition {
 
 /**
  * @author PSE & 0xPARC
- * @title  Interface for the NovaDecider contract hiding proof details.
- * @dev    This interface enables calling the verifyNovaProof function without exposing the proof details.
+ * @title  Interface for the {{ contract_name }} contract hiding proof details.
+ * @dev    This interface enables calling the {{ function_name }} function without exposing the proof details.
  */
 interface OpaqueDecider {
     /**
@@ -65,10 +65,10 @@ interface OpaqueDecider {
 
 /**
  * @author  PSE & 0xPARC
- * @title   NovaDecider contract, for verifying Nova IVC SNARK proofs.
+ * @title   {{ contract_name }} contract, for verifying Nova IVC SNARK proofs.
  * @dev     This is an askama template which, when templated, features a Groth16 and KZG10 verifiers from which this contract inherits.
  */
-contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
+contract {{ contract_name }} is Groth16Verifier, KZG10Verifier, OpaqueDecider {
     /**
      * @notice  Computes the linear combination of a and b with r as the coefficient.
      * @dev     All ops are done mod the BN254 scalar field prime
@@ -81,9 +81,10 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
 
     /**
      * @notice  Verifies a nova cyclefold proof consisting of two KZG proofs and of a groth16 proof.
-     * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`. Both KZG proofs are verified
+     *          together with a single pairing check.
      */
-    function verifyNovaProof(
+    function {{ function_name }}(
         // inputs are grouped to prevent errors due stack too deep
         uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
         uint256[4] calldata U_i_cmW_U_i_cmE, // [U_i_cmW[2], U_i_cmE[2]]
@@ -108,6 +109,9 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
             public_inputs[2 + i] = i_z0_zi[1 + i];
         }
 
+        // commitments opened by the KZG proofs, [cmW, cmE]
+        uint256[2][] memory kzg_cms = new uint256[2][](2);
+
         {
             // U_i.cmW + r * u_i.cmW
             uint256[2] memory mulScalarPoint = super.mulScalar([u_i_cmW[0], u_i_cmW[1]], cmT_r[2]);
@@ -122,8 +126,8 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
                     public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = cmW_y_limbs[k];
                 }
             }
-        
-            require(this.check(cmW, kzg_proof[0], challenge_W_challenge_E_kzg_evals[0], challenge_W_challenge_E_kzg_evals[2]), "KZG: verifying proof for challenge W failed");
+
+            kzg_cms[0] = cmW;
         }
 
         {
@@ -141,7 +145,20 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
                 }
             }
 
-            require(this.check(cmE, kzg_proof[1], challenge_W_challenge_E_kzg_evals[1], challenge_W_challenge_E_kzg_evals[3]), "KZG: verifying proof for challenge E failed");
+            kzg_cms[1] = cmE;
+        }
+
+        {
+            uint256[2][] memory kzg_pis = new uint256[2][](2);
+            uint256[] memory kzg_challenges = new uint256[](2);
+            uint256[] memory kzg_evals = new uint256[](2);
+            for (uint8 k = 0; k < 2; k++) {
+                kzg_pis[k] = kzg_proof[k];
+                kzg_challenges[k] = challenge_W_challenge_E_kzg_evals[k];
+                kzg_evals[k] = challenge_W_challenge_E_kzg_evals[2 + k];
+            }
+
+            require(super.multiCheck(kzg_cms, kzg_pis, kzg_challenges, kzg_evals), "KZG: verifying proofs for challenges W and E failed");
         }
 
         {
@@ -171,7 +188,7 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
 
     /**
      * @notice  Verifies a Nova+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
-     * @dev     Simply reorganization of arguments and call to the `verifyNovaProof` function.
+     * @dev     Simply reorganization of arguments and call to the `{{ function_name }}` function.
      */
     function verifyOpaqueNovaProofWithInputs(
         uint256 steps,
@@ -195,7 +212,7 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
         uint256[4] memory challenge_W_challenge_E_kzg_evals = [proof[17], proof[18], proof[19], proof[20]];
         uint256[2][2] memory kzg_proof = [[proof[21], proof[22]], [proof[23], proof[24]]];
 
-        return this.verifyNovaProof(
+        return this.{{ function_name }}(
             i_z0_zi,
             U_i_cmW_U_i_cmE,
             u_i_cmW,
@@ -210,7 +227,7 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
 
     /**
      * @notice  Verifies a Nova+CycleFold proof given all proof inputs concatenated.
-     * @dev     Simply reorganization of arguments and call to the `verifyNovaProof` function.
+     * @dev     Simply reorganization of arguments and call to the `{{ function_name }}` function.
      */
     function verifyOpaqueNovaProof(uint256[{{ 26 + z_len * 2 }}] calldata proof) public override view returns (bool) {
         uint256[{{ z_len }}] memory z0;
@@ -227,4 +244,287 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
 
         return this.verifyOpaqueNovaProofWithInputs(proof[0], z0, zi, extracted_proof);
     }
-}
+{% if hashed_state %}
+    /**
+     * @notice  Computes the hash of an IVC state, which the decider circuit exposes as public input instead of the state.
+     * @dev     Compatible with sonobe::folding-schemes::folding::circuits::decider::StateHashGadget: the keccak256 digest of
+     *          the state elements, truncated to its first 31 bytes.
+     */
+    function hashState(uint256[{{ state_len }}] memory state) public pure returns (uint256) {
+        return uint256(keccak256(abi.encodePacked(state))) >> 8;
+    }
+
+    /**
+     * @notice  Verifies a Nova+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
+     * @dev     Hashes the states and calls the `verifyOpaqueNovaProofWithInputs` function with them.
+     */
+    function verifyNovaProofWithState(
+        uint256 steps,
+        uint256[{{ state_len }}] calldata initial_state,
+        uint256[{{ state_len }}] calldata final_state,
+        uint256[25] calldata proof
+    ) public view returns (bool) {
+        return this.verifyOpaqueNovaProofWithInputs(steps, [hashState(initial_state)], [hashState(final_state)], proof);
+    }
+{% endif %}{% if blob %}
+    /**
+     * @notice  Verifies a Nova+CycleFold proof whose number of steps and initial and final states are posted in the
+     *          EIP-4844 blob with the given versioned hash, which must be the `blob_index`-th blob of the transaction.
+     * @dev     The blob holds [i, hash(z0), hash(zi), z0, zi], see sonobe::solidity-verifiers::blob. The point evaluation
+     *          precompile checks that its first 3 elements are `steps` and `state_hashes`, which binds
+     *          them to the blob commitment, and the proof is verified with the hashes of the states, which anyone holding
+     *          the blob can check against its contents. Requires the Cancun EVM and solc 0.8.24 or later.
+     */
+    function verifyNovaProofWithBlob(
+        bytes32 versioned_hash,
+        uint256 blob_index,
+        uint256 steps,
+        uint256[2] calldata state_hashes, // [hash(z0), hash(zi)]
+        uint256[25] calldata proof,
+        bytes calldata blob_opening // [commitment, proof of steps, proof of hash(z0), proof of hash(zi)]
+    ) public view returns (bool) {
+        bytes32 tx_blob_hash;
+        assembly {
+            tx_blob_hash := blobhash(blob_index)
+        }
+        require(tx_blob_hash == versioned_hash, "Blob: versioned hash not in the transaction");
+        require(blob_opening.length == 192, "Blob: invalid opening length");
+
+        // points at which the blob evaluates to its elements of indices 0, 1 and 2
+        uint256[3] memory points = [{% for point in blob_evaluation_points %}{% if !loop.first %}, {% endif %}uint256({{ point }}){% endfor %}];
+        uint256[3] memory evaluations = [steps, state_hashes[0], state_hashes[1]];
+        for (uint256 k = 0; k < 3; k++) {
+            (bool success, ) = address(0x0a).staticcall(
+                abi.encodePacked(
+                    versioned_hash,
+                    points[k],
+                    evaluations[k],
+                    blob_opening[0:48],
+                    blob_opening[48 * (k + 1):48 * (k + 2)]
+                )
+            );
+            require(success, "Blob: point evaluation failed");
+        }
+
+        return this.verifyOpaqueNovaProofWithInputs(steps, [state_hashes[0]], [state_hashes[1]], proof);
+    }
+{% endif %}{% if on_verified_hook %}
+    /**
+     * @notice  Hook called by `processNovaProof` once a proof verifies, with its number of steps (i), initial state (z0) and
+     *          final state (zi), which inheriting contracts can override to consume the states, eg. decoding them into
+     *          application-level types.
+     * @dev     Does nothing by default.
+     */
+    function _onVerified(uint256, uint256[{{ z_len }}] memory, uint256[{{ z_len }}] memory) internal virtual {}
+
+    /**
+     * @notice  Verifies a Nova+CycleFold proof, taking the same arguments as `{{ function_name }}`, and then calls `_onVerified`
+     *          with its number of steps and states. Reverts if the proof does not verify.
+     */
+    function processNovaProof(
+        uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi,
+        uint256[4] calldata U_i_cmW_U_i_cmE,
+        uint256[2] calldata u_i_cmW,
+        uint256[3] calldata cmT_r,
+        uint256[2] calldata pA,
+        uint256[2][2] calldata pB,
+        uint256[2] calldata pC,
+        uint256[4] calldata challenge_W_challenge_E_kzg_evals,
+        uint256[2][2] calldata kzg_proof
+    ) public returns (bool) {
+        require(
+            this.{{ function_name }}(i_z0_zi, U_i_cmW_U_i_cmE, u_i_cmW, cmT_r, pA, pB, pC, challenge_W_challenge_E_kzg_evals, kzg_proof),
+            "Nova: verifying proof failed"
+        );
+
+        uint256[{{ z_len }}] memory z0;
+        uint256[{{ z_len }}] memory zi;
+        for (uint256 k = 0; k < {{ z_len }}; k++) {
+            z0[k] = i_z0_zi[1 + k];
+            zi[k] = i_z0_zi[1 + {{ z_len }} + k];
+        }
+        _onVerified(i_z0_zi[0], z0, zi);
+        return true;
+    }
+{% endif %}{% if batch %}
+    /**
+     * @notice  Inputs of the combined pairing checks of a batch of proofs.
+     * @dev     `groth16_pairs` holds the pair (-rho_k * A_k, B_k) of each proof k, and `vk_x`, `C` and `rho_sum` the sums of
+     *          rho_k * vk_x_k, rho_k * C_k and rho_k, which are paired with gamma, delta and (alpha, beta) respectively.
+     *          The KZG openings of all the proofs are collected to be checked together by `multiCheck`.
+     */
+    struct NovaBatch {
+        uint256[] groth16_pairs;
+        uint256[2] vk_x;
+        uint256[2] C;
+        uint256 rho_sum;
+        uint256[2][] kzg_cms;
+        uint256[2][] kzg_pis;
+        uint256[] kzg_challenges;
+        uint256[] kzg_evals;
+    }
+
+    /**
+     * @notice  Computes the linear combination of the Groth16 verifier key points with the given public inputs (vk_x).
+     */
+    function groth16VkX(uint256[{{ public_inputs_len - 1 }}] memory public_inputs) internal view returns (uint256[2] memory vk_x) {
+        vk_x = [IC0x, IC0y];
+        {%- for i in 1..public_inputs_len %}
+        vk_x = super.add(vk_x, super.mulScalar([IC{{ i }}x, IC{{ i }}y], public_inputs[{{ i - 1 }}]));
+        {%- endfor %}
+    }
+
+    /**
+     * @notice  Writes the limbs of `x` into `public_inputs`, starting at `offset`.
+     */
+    function writeLimbs(uint256[{{ public_inputs_len - 1 }}] memory public_inputs, uint256 offset, uint256 x) internal pure {
+        uint256[{{num_limbs}}] memory limbs = LimbsDecomposition.decompose(x);
+        for (uint8 j = 0; j < {{num_limbs}}; j++) {
+            public_inputs[offset + j] = limbs[j];
+        }
+    }
+
+    /**
+     * @notice  Adds the `k`-th proof of a batch to the combined checks, with `rho` as its coefficient in the Groth16 one.
+     * @dev     `proof` contains the arguments of the `{{ function_name }}` function, concatenated in the same order,
+     *          from which the same public inputs and KZG commitments are computed.
+     */
+    function accumulateNovaProof(NovaBatch memory batch, uint256 k, uint256[{{ 26 + z_len * 2 }}] memory proof, uint256 rho)
+        internal
+        view
+    {
+        require(proof[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        uint256[{{ public_inputs_len - 1 }}] memory public_inputs;
+        public_inputs[0] = {{pp_hash}};
+        for (uint256 i = 0; i < {{ 1 + z_len * 2 }}; i++) {
+            public_inputs[1 + i] = proof[i];
+        }
+
+        {
+            // U_i.cmW + r * u_i.cmW and U_i.cmE + r * cmT
+            uint256 r_fold = proof[{{ 9 + z_len * 2 }}];
+            uint256[2] memory cmW = super.add(
+                [proof[{{ 1 + z_len * 2 }}], proof[{{ 2 + z_len * 2 }}]],
+                super.mulScalar([proof[{{ 5 + z_len * 2 }}], proof[{{ 6 + z_len * 2 }}]], r_fold)
+            );
+            uint256[2] memory cmE = super.add(
+                [proof[{{ 3 + z_len * 2 }}], proof[{{ 4 + z_len * 2 }}]],
+                super.mulScalar([proof[{{ 7 + z_len * 2 }}], proof[{{ 8 + z_len * 2 }}]], r_fold)
+            );
+            writeLimbs(public_inputs, {{ z_len * 2 + 2 }}, cmW[0]);
+            writeLimbs(public_inputs, {{ z_len * 2 + 2 + num_limbs }}, cmW[1]);
+            writeLimbs(public_inputs, {{ z_len * 2 + 2 + num_limbs * 2 }}, cmE[0]);
+            writeLimbs(public_inputs, {{ z_len * 2 + 2 + num_limbs * 3 }}, cmE[1]);
+            batch.kzg_cms[2 * k] = cmW;
+            batch.kzg_cms[2 * k + 1] = cmE;
+        }
+
+        // challenges, evaluations and proofs of the KZG openings of W and E
+        for (uint256 j = 0; j < 2; j++) {
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 4 }} + j] = proof[{{ 18 + z_len * 2 }} + j];
+            public_inputs[{{ z_len * 2 + 4 + num_limbs * 4 }} + j] = proof[{{ 20 + z_len * 2 }} + j];
+            batch.kzg_challenges[2 * k + j] = proof[{{ 18 + z_len * 2 }} + j];
+            batch.kzg_evals[2 * k + j] = proof[{{ 20 + z_len * 2 }} + j];
+            batch.kzg_pis[2 * k + j] = [proof[{{ 22 + z_len * 2 }} + 2 * j], proof[{{ 23 + z_len * 2 }} + 2 * j]];
+        }
+        writeLimbs(public_inputs, {{ z_len * 2 + 6 + num_limbs * 4 }}, proof[{{ 7 + z_len * 2 }}]);
+        writeLimbs(public_inputs, {{ z_len * 2 + 6 + num_limbs * 5 }}, proof[{{ 8 + z_len * 2 }}]);
+
+        // as in the Groth16 verifier, the public inputs have to be elements of the scalar field
+        for (uint256 i = 0; i < {{ public_inputs_len - 1 }}; i++) {
+            require(public_inputs[i] < BN254_SCALAR_FIELD, "Groth16: public input not in the scalar field");
+        }
+
+        // (-rho * A, B)
+        uint256[2] memory A = super.negate(super.mulScalar([proof[{{ 10 + z_len * 2 }}], proof[{{ 11 + z_len * 2 }}]], rho));
+        batch.groth16_pairs[6 * k] = A[0];
+        batch.groth16_pairs[6 * k + 1] = A[1];
+        for (uint256 j = 0; j < 4; j++) {
+            batch.groth16_pairs[6 * k + 2 + j] = proof[{{ 12 + z_len * 2 }} + j];
+        }
+        batch.vk_x = super.add(batch.vk_x, super.mulScalar(groth16VkX(public_inputs), rho));
+        batch.C = super.add(batch.C, super.mulScalar([proof[{{ 16 + z_len * 2 }}], proof[{{ 17 + z_len * 2 }}]], rho));
+        batch.rho_sum = addmod(batch.rho_sum, rho, BN254_SCALAR_FIELD);
+    }
+
+    /**
+     * @notice  Verifies several Nova+CycleFold proofs together, with one pairing check for all their Groth16 proofs and one
+     *          for all their KZG proofs. Returns false (or reverts) if any of them does not verify, without telling which.
+     * @dev     Each proof k satisfies e(-A_k, B_k) * e(alpha, beta) * e(vk_x_k, gamma) * e(C_k, delta) = 1, so with random
+     *          rho_k derived from all the proofs, all the Groth16 proofs verify (except with negligible probability) if:
+     *
+     *          prod_k e(-rho_k * A_k, B_k) * e(sum_k rho_k * alpha, beta) * e(sum_k rho_k * vk_x_k, gamma) * e(sum_k rho_k * C_k, delta) = 1
+     *
+     *          which costs one pairing per proof plus three, instead of the six pairings of verifying each proof alone.
+     */
+    function verifyBatchAggregated(bytes[] calldata proofs) public view returns (bool) {
+        uint256 n = proofs.length;
+        require(n > 0, "Batch: no proofs");
+
+        NovaBatch memory batch;
+        batch.groth16_pairs = new uint256[](6 * (n + 3));
+        batch.kzg_cms = new uint256[2][](2 * n);
+        batch.kzg_pis = new uint256[2][](2 * n);
+        batch.kzg_challenges = new uint256[](2 * n);
+        batch.kzg_evals = new uint256[](2 * n);
+
+        bytes32 seed = keccak256(abi.encode(proofs));
+        for (uint256 k = 0; k < n; k++) {
+            require(proofs[k].length == {{ 32 * (26 + z_len * 2) }}, "Batch: invalid proof length");
+            uint256 rho = uint256(keccak256(abi.encode(seed, k))) % BN254_SCALAR_FIELD;
+            accumulateNovaProof(batch, k, abi.decode(proofs[k], (uint256[{{ 26 + z_len * 2 }}])), rho);
+        }
+
+        if (!super.multiCheck(batch.kzg_cms, batch.kzg_pis, batch.kzg_challenges, batch.kzg_evals)) {
+            return false;
+        }
+
+        uint256[2] memory alpha = super.mulScalar([alphax, alphay], batch.rho_sum);
+        uint256[18] memory vk_pairs = [
+            alpha[0], alpha[1], betax1, betax2, betay1, betay2,
+            batch.vk_x[0], batch.vk_x[1], gammax1, gammax2, gammay1, gammay2,
+            batch.C[0], batch.C[1], deltax1, deltax2, deltay1, deltay2
+        ];
+        uint256[] memory pairs = batch.groth16_pairs;
+        for (uint256 j = 0; j < 18; j++) {
+            pairs[6 * n + j] = vk_pairs[j];
+        }
+
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 8, add(pairs, 32), mul(mload(pairs), 32), out, 0x20)
+        }
+        return success && out[0] == 1;
+    }
+
+    /**
+     * @notice  Verifies several Nova+CycleFold proofs in a single call, reverting with `InvalidProof(index)` at the first
+     *          proof that does not verify.
+     * @dev     Each element of `proofs` is the ABI encoding of the arguments of the `{{ function_name }}` function, ie. its
+     *          calldata without the selector. The proofs are verified together by `verifyBatchAggregated`, and only when
+     *          that fails they are verified one by one to find the invalid one. The revert data is built by hand, since
+     *          custom errors are not available before 0.8.4.
+     */
+    function verifyBatch(bytes[] calldata proofs) public view returns (bool) {
+        (bool success, bytes memory result) = address(this).staticcall(
+            abi.encodeWithSelector(this.verifyBatchAggregated.selector, proofs)
+        );
+        if (success && result.length == 32 && abi.decode(result, (bool))) {
+            return true;
+        }
+        for (uint256 k = 0; k < proofs.length; k++) {
+            (success, result) = address(this).staticcall(
+                abi.encodePacked(this.{{ function_name }}.selector, proofs[k])
+            );
+            if (!success || result.length != 32 || !abi.decode(result, (bool))) {
+                bytes memory err = abi.encodeWithSignature("InvalidProof(uint256)", k);
+                assembly {
+                    revert(add(err, 32), mload(err))
+                }
+            }
+        }
+        return true;
+    }
+{% endif %}}
@@ -0,0 +1,274 @@
+/*
+    Sonobe's Nova + CycleFold decider router.
+    Joint effort by 0xPARC & PSE.
+
+    More details at https://github.com/privacy-scaling-explorations/sonobe
+    Usage and design documentation at https://privacy-scaling-explorations.github.io/sonobe-docs/
+
+    The {{ contract_name }} contract verifies the zkSNARK proofs coming from Nova+CycleFold folding of several
+    circuits, each one against the Groth16 and KZG10 verifier keys of its circuit, which the contract embeds. The
+    verification follows the one of the NovaDecider contract of each circuit.
+*/
+
+/**
+ * @notice  Computes the decomposition of a `uint256` into num_limbs limbs of bits_per_limb bits each.
+ * @dev     Compatible with sonobe::folding-schemes::folding::circuits::nonnative::nonnative_field_to_field_elements.
+ */
+library LimbsDecomposition {
+    function decompose(uint256 x) internal pure returns (uint256[{{num_limbs}}] memory) {
+        uint256[{{num_limbs}}] memory limbs;
+        for (uint8 i = 0; i < {{num_limbs}}; i++) {
+            limbs[i] = (x >> ({{bits_per_limb}} * i)) & ((1 << {{bits_per_limb}}) - 1);
+        }
+        return limbs;
+    }
+}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   {{ contract_name }} contract, for verifying the Nova IVC SNARK proofs of several circuits.
+ * @dev     This is an askama template which, when templated, features the ids and the verifier keys of the circuits.
+ */
+contract {{ contract_name }} {
+    // prime of field F_p over which y^2 = x^3 + 3 is defined
+    uint256 constant BN254_PRIME_FIELD =
+        21888242871839275222246405745257275088696311157297823662689037894645226208583;
+    uint256 constant BN254_SCALAR_FIELD =
+        21888242871839275222246405745257275088548364400416034343698204186575808495617;
+
+    /**
+     * @notice  Verifier key of a circuit: the hash of its public parameters, the Groth16 verifier key of its decider
+     *          circuit and the KZG10 verifier key of its commitments.
+     * @dev     G_2 points are encoded as expected by the pairing precompile, with the imaginary parts first.
+     */
+    struct CircuitVk {
+        uint256 ppHash;
+        uint256 zLen;
+        uint256[2] alpha;
+        uint256[4] beta;
+        uint256[4] gamma;
+        uint256[4] delta;
+        uint256[2][] ic;
+        uint256[2] kzgG1;
+        uint256[4] kzgG2;
+        uint256[4] kzgVk;
+    }
+{% for circuit in circuits %}
+    /**
+     * @notice  Returns the verifier key of the circuit {{ circuit.id }}.
+     */
+    function circuitVk{{ circuit.id }}() internal pure returns (CircuitVk memory vk) {
+        vk.ppHash = {{ circuit.pp_hash }};
+        vk.zLen = {{ circuit.z_len }};
+        vk.alpha = [uint256({{ circuit.groth16_verifier.vkey_alpha_g1.0[0] }}), {{ circuit.groth16_verifier.vkey_alpha_g1.0[1] }}];
+        vk.beta = [uint256({{ circuit.groth16_verifier.vkey_beta_g2.0[0][1] }}), {{ circuit.groth16_verifier.vkey_beta_g2.0[0][0] }}, {{ circuit.groth16_verifier.vkey_beta_g2.0[1][1] }}, {{ circuit.groth16_verifier.vkey_beta_g2.0[1][0] }}];
+        vk.gamma = [uint256({{ circuit.groth16_verifier.vkey_gamma_g2.0[0][1] }}), {{ circuit.groth16_verifier.vkey_gamma_g2.0[0][0] }}, {{ circuit.groth16_verifier.vkey_gamma_g2.0[1][1] }}, {{ circuit.groth16_verifier.vkey_gamma_g2.0[1][0] }}];
+        vk.delta = [uint256({{ circuit.groth16_verifier.vkey_delta_g2.0[0][1] }}), {{ circuit.groth16_verifier.vkey_delta_g2.0[0][0] }}, {{ circuit.groth16_verifier.vkey_delta_g2.0[1][1] }}, {{ circuit.groth16_verifier.vkey_delta_g2.0[1][0] }}];
+        vk.ic = new uint256[2][]({{ circuit.groth16_verifier.gamma_abc_len }});
+        {%- for (i, point) in circuit.groth16_verifier.gamma_abc_g1.iter().enumerate() %}
+        vk.ic[{{ i }}] = [uint256({{ point.0[0] }}), {{ point.0[1] }}];
+        {%- endfor %}
+        vk.kzgG1 = [uint256({{ circuit.kzg10_verifier.g1.0[0] }}), {{ circuit.kzg10_verifier.g1.0[1] }}];
+        vk.kzgG2 = [uint256({{ circuit.kzg10_verifier.g2.0[0][1] }}), {{ circuit.kzg10_verifier.g2.0[0][0] }}, {{ circuit.kzg10_verifier.g2.0[1][1] }}, {{ circuit.kzg10_verifier.g2.0[1][0] }}];
+        vk.kzgVk = [uint256({{ circuit.kzg10_verifier.vk.0[0][1] }}), {{ circuit.kzg10_verifier.vk.0[0][0] }}, {{ circuit.kzg10_verifier.vk.0[1][1] }}, {{ circuit.kzg10_verifier.vk.0[1][0] }}];
+    }
+{% endfor %}
+    /**
+     * @notice  Performs scalar multiplication in G_1.
+     */
+    function mulScalar(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 7, input, 0x60, r, 0x40)
+        }
+        require(success, "bn254: scalar mul failed");
+    }
+
+    /**
+     * @notice  Negates a point in G_1.
+     */
+    function negate(uint256[2] memory p) internal pure returns (uint256[2] memory) {
+        if (p[0] == 0 && p[1] == 0) {
+            return p;
+        }
+        return [p[0], BN254_PRIME_FIELD - (p[1] % BN254_PRIME_FIELD)];
+    }
+
+    /**
+     * @notice  Adds two points in G_1.
+     */
+    function add(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 6, input, 0x80, r, 0x40)
+        }
+        require(success, "bn254: point add failed");
+    }
+
+    /**
+     * @notice  Checks that the product of the pairings of the pairs of G_1 and G_2 points in `input` is 1.
+     */
+    function pairing(uint256[] memory input) internal view returns (bool) {
+        uint256[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), 8, add(input, 32), mul(mload(input), 32), out, 0x20)
+        }
+        require(success, "bn254: pairing failed");
+        return out[0] == 1;
+    }
+
+    /**
+     * @notice  Writes the limbs of `x` into `public_inputs`, starting at `offset`.
+     */
+    function writeLimbs(uint256[] memory public_inputs, uint256 offset, uint256 x) internal pure {
+        uint256[{{num_limbs}}] memory limbs = LimbsDecomposition.decompose(x);
+        for (uint8 k = 0; k < {{num_limbs}}; k++) {
+            public_inputs[offset + k] = limbs[k];
+        }
+    }
+
+    /**
+     * @notice  Verifies a Nova+CycleFold proof of the circuit `circuitId`, reverting with the reason of the failed check
+     *          if it does not verify.
+     * @dev     `proof` is the ABI encoding of the arguments of the `verifyNovaProof` function of the NovaDecider contract
+     *          of the circuit, ie. its calldata without the selector.
+     */
+    function verify(uint256 circuitId, bytes calldata proof) public view returns (bool) {
+        {%- for circuit in circuits %}
+        if (circuitId == {{ circuit.id }}) {
+            require(proof.length == {{ circuit.proof_words * 32 }}, "Router: invalid proof length for the circuit");
+            uint256[{{ circuit.proof_words }}] memory words = abi.decode(proof, (uint256[{{ circuit.proof_words }}]));
+            uint256[] memory p = new uint256[]({{ circuit.proof_words }});
+            for (uint256 j = 0; j < {{ circuit.proof_words }}; j++) {
+                p[j] = words[j];
+            }
+            return verifyNovaProof(circuitVk{{ circuit.id }}(), p);
+        }
+        {%- endfor %}
+        revert("Router: unknown circuit");
+    }
+
+    /**
+     * @notice  Verifies a Nova+CycleFold proof against the verifier key `vk`, as the `verifyNovaProof` function of the
+     *          NovaDecider contract of the circuit does.
+     * @dev     `p` holds the arguments of the `verifyNovaProof` function, concatenated in the same order: after
+     *          [i, z0, zi], at the offset `b`, come U_i.cmW, U_i.cmE, u_i.cmW, cmT, r, the Groth16 proof (A, B, C), the
+     *          KZG challenges and evaluations [challenge_W, challenge_E, eval_W, eval_E] and the KZG proofs [proof_W, proof_E].
+     */
+    function verifyNovaProof(CircuitVk memory vk, uint256[] memory p) internal view returns (bool) {
+        require(p[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        uint256 b = 1 + 2 * vk.zLen;
+        uint256[] memory public_inputs = new uint256[](vk.ic.length - 1);
+        public_inputs[0] = vk.ppHash;
+        for (uint256 j = 0; j < b; j++) {
+            public_inputs[1 + j] = p[j];
+        }
+
+        // U_i.cmW + r * u_i.cmW and U_i.cmE + r * cmT
+        uint256[2] memory cmW = add([p[b], p[b + 1]], mulScalar([p[b + 4], p[b + 5]], p[b + 8]));
+        uint256[2] memory cmE = add([p[b + 2], p[b + 3]], mulScalar([p[b + 6], p[b + 7]], p[b + 8]));
+        writeLimbs(public_inputs, b + 1, cmW[0]);
+        writeLimbs(public_inputs, b + 1 + {{ num_limbs }}, cmW[1]);
+        writeLimbs(public_inputs, b + 1 + {{ num_limbs * 2 }}, cmE[0]);
+        writeLimbs(public_inputs, b + 1 + {{ num_limbs * 3 }}, cmE[1]);
+
+        require(checkKzg(vk, p, b, cmW, cmE), "KZG: verifying proofs for challenges W and E failed");
+
+        for (uint256 j = 0; j < 4; j++) {
+            public_inputs[b + 1 + {{ num_limbs * 4 }} + j] = p[b + 17 + j];
+        }
+        writeLimbs(public_inputs, b + 5 + {{ num_limbs * 4 }}, p[b + 6]);
+        writeLimbs(public_inputs, b + 5 + {{ num_limbs * 5 }}, p[b + 7]);
+
+        require(checkGroth16(vk, p, b, public_inputs), "Groth16: verifying proof failed");
+
+        return true;
+    }
+
+    /**
+     * @notice  Computes x * -pi - c + y * g1, the G_1 point paired with g2 in the check of a KZG proof `pi` of the
+     *          evaluation `y` at `x` of the polynomial committed in `c`, see `check` in the KZG10 verifier.
+     */
+    function kzgRhs(CircuitVk memory vk, uint256[2] memory c, uint256[2] memory pi, uint256 x, uint256 y)
+        internal
+        view
+        returns (uint256[2] memory)
+    {
+        return add(mulScalar(negate(pi), x), add(negate(c), mulScalar(vk.kzgG1, y)));
+    }
+
+    /**
+     * @notice  Verifies the KZG proofs of the openings of cmW and cmE with a single pairing check.
+     * @dev     As in `multiCheck` of the KZG10 verifier, the checks e(pi_j, vk) * e(rhs_j, g2) = 1 of both proofs are
+     *          combined with a challenge rho derived from all the inputs:
+     *
+     *          e(pi_W + rho * pi_E, vk) * e(rhs_W + rho * rhs_E, g2) = 1
+     */
+    function checkKzg(CircuitVk memory vk, uint256[] memory p, uint256 b, uint256[2] memory cmW, uint256[2] memory cmE)
+        internal
+        view
+        returns (bool)
+    {
+        uint256[2] memory piW = [p[b + 21], p[b + 22]];
+        uint256[2] memory piE = [p[b + 23], p[b + 24]];
+        uint256 rho = uint256(
+            keccak256(abi.encode(cmW, cmE, piW, piE, p[b + 17], p[b + 18], p[b + 19], p[b + 20]))
+        ) % BN254_SCALAR_FIELD;
+
+        uint256[2] memory lhs = add(piW, mulScalar(piE, rho));
+        uint256[2] memory rhs = add(
+            kzgRhs(vk, cmW, piW, p[b + 17], p[b + 19]),
+            mulScalar(kzgRhs(vk, cmE, piE, p[b + 18], p[b + 20]), rho)
+        );
+
+        uint256[] memory input = new uint256[](12);
+        input[0] = lhs[0];
+        input[1] = lhs[1];
+        input[6] = rhs[0];
+        input[7] = rhs[1];
+        for (uint256 j = 0; j < 4; j++) {
+            input[2 + j] = vk.kzgVk[j];
+            input[8 + j] = vk.kzgG2[j];
+        }
+        return pairing(input);
+    }
+
+    /**
+     * @notice  Verifies the Groth16 proof of the decider circuit with the given public inputs:
+     *          e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) = 1
+     */
+    function checkGroth16(CircuitVk memory vk, uint256[] memory p, uint256 b, uint256[] memory public_inputs)
+        internal
+        view
+        returns (bool)
+    {
+        uint256[2] memory vk_x = vk.ic[0];
+        for (uint256 j = 0; j < public_inputs.length; j++) {
+            require(public_inputs[j] < BN254_SCALAR_FIELD, "Groth16: public input not in the scalar field");
+            vk_x = add(vk_x, mulScalar(vk.ic[j + 1], public_inputs[j]));
+        }
+
+        uint256[2] memory A = negate([p[b + 9], p[b + 10]]);
+        uint256[] memory input = new uint256[](24);
+        input[0] = A[0];
+        input[1] = A[1];
+        input[6] = vk.alpha[0];
+        input[7] = vk.alpha[1];
+        input[12] = vk_x[0];
+        input[13] = vk_x[1];
+        input[18] = p[b + 15];
+        input[19] = p[b + 16];
+        for (uint256 j = 0; j < 4; j++) {
+            // B is given in the order expected by the pairing precompile
+            input[2 + j] = p[b + 11 + j];
+            input[8 + j] = vk.beta[j];
+            input[14 + j] = vk.gamma[j];
+            input[20 + j] = vk.delta[j];
+        }
+        return pairing(input);
+    }
+}
@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.0;
+
+import {NovaDecider} from "../src/NovaDecider.sol";
+
+/**
+ * @notice  Checks that the NovaDecider contract accepts the exported proof, and rejects it once tampered.
+ * @dev     This is an askama template, rendered by sonobe's `solidity_verifiers::export_foundry_project`.
+ */
+contract NovaDeciderTest {
+    NovaDecider decider;
+
+    // calldata of the call to the NovaDecider contract verifying the proof, including the function selector
+    bytes constant PROOF_CALLDATA = hex"{{ calldata }}";
+
+    function setUp() public {
+        decider = new NovaDecider();
+    }
+
+    function testVerifyProof() public view {
+        (bool success, bytes memory result) = address(decider).staticcall(PROOF_CALLDATA);
+        require(success, "NovaDecider: call failed");
+        require(abi.decode(result, (bool)), "NovaDecider: proof rejected");
+    }
+
+    function testRejectTamperedProof() public view {
+        bytes memory tampered = PROOF_CALLDATA;
+        // change the number of folded steps (i), placed right after the function selector
+        tampered[35] = tampered[35] ^ bytes1(0x01);
+        (bool success, bytes memory result) = address(decider).staticcall(tampered);
+        require(!success || !abi.decode(result, (bool)), "NovaDecider: tampered proof accepted");
+    }
+}